@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use async_trait::async_trait;
 
 use crate::result::Result;
@@ -7,4 +9,25 @@ pub trait Cache<K, V>: Sync + Send {
     async fn get(&self, k: &K) -> Option<V>;
     async fn set(&self, k: K, v: V) -> Result<()>;
     async fn delete(&self, k: &K) -> Result<()>;
+
+    /// Removes the value and returns it in a single step, so when several callers take the same
+    /// value only one of them gets it.
+    async fn take(&self, k: &K) -> Option<V>;
+
+    /// Sets a value that expires after `ttl`. Expired values are not returned, and they are
+    /// removed when the cache is swept.
+    async fn set_with_ttl(&self, k: K, v: V, ttl: Duration) -> Result<()>;
+}
+
+/// CacheNamespace separates the values of different types stored in a shared cache, so keys
+/// only need to be unique for the same type.
+pub trait CacheNamespace {
+    const NAMESPACE: &'static str;
+}
+
+/// Sweep removes expired values from a cache.
+#[async_trait]
+pub trait Sweep: Sync + Send {
+    /// Returns how many values were removed.
+    async fn sweep(&self) -> Result<usize>;
 }
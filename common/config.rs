@@ -1,92 +1,382 @@
 mod business_rules;
+mod repository;
+mod secret;
 mod service;
 pub use business_rules::*;
+pub use repository::*;
+pub use secret::*;
 pub use service::*;
 
+use std::collections::HashMap;
 use std::env;
+use std::fs;
+use std::net::IpAddr;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
 
 use dotenv::dotenv;
+use serde::Deserialize;
 
-#[derive(Debug, Clone)]
+use crate::error::Error;
+use crate::result::Result;
+
+const DEFAULT_FILE: &str = "config.toml";
+
+/// Config is loaded once at startup and shared through the containers. Keys of the TOML file are
+/// the field names, and the environment variable of each field is its name in uppercase.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct Config {
     port: u16,
     env: String,
-
-    pagination_limit: usize,
+    trusted_proxies: String,
 
     aws_key: String,
-    aws_secret: String,
+    aws_secret: Secret,
     aws_s3_bucket: String,
     aws_region: String,
 
     postgres_host: String,
     postgres_port: u16,
     postgres_username: String,
-    postgres_password: String,
+    postgres_password: Secret,
     postgres_database: String,
+    postgres_ssl_mode: String,
+    postgres_pool_size: usize,
+    postgres_pool_timeout: u64,
+    postgres_connect_timeout: u64,
+
+    event_bus: String,
+    event_bus_consumer_group: String,
+
+    jwt_key_id: String,
+    jwt_secret: Secret,
+    jwt_previous_keys: Secret,
+    access_token_ttl: u64,
+    session_days: u32,
+    cursor_secret: Secret,
+
+    oauth_redirect_url: String,
+    google_client_id: String,
+    google_client_secret: Secret,
+    facebook_client_id: String,
+    facebook_client_secret: Secret,
+
+    retention_days: u32,
+    retention_interval: u64,
 
     smtp_server: String,
     smtp_email: String,
-    smtp_password: String,
+    smtp_password: Secret,
     smtp_port: u16,
 
     mp_public_key: String,
-    mp_access_token: String,
+    mp_access_token: Secret,
+}
+
+impl Default for Config {
+    /// Credentials have no default value, so they must be configured explicitly.
+    fn default() -> Self {
+        Config {
+            port: 3000,
+            env: "development".to_owned(),
+            trusted_proxies: String::new(),
+
+            aws_key: String::new(),
+            aws_secret: Secret::default(),
+            aws_s3_bucket: String::new(),
+            aws_region: String::new(),
+
+            postgres_host: "localhost".to_owned(),
+            postgres_port: 5432,
+            postgres_username: String::new(),
+            postgres_password: Secret::default(),
+            postgres_database: "omics".to_owned(),
+            postgres_ssl_mode: "disable".to_owned(),
+            postgres_pool_size: 16,
+            postgres_pool_timeout: 10,
+            postgres_connect_timeout: 5,
+
+            event_bus: "inmem".to_owned(),
+            event_bus_consumer_group: "omics".to_owned(),
+
+            jwt_key_id: "1".to_owned(),
+            jwt_secret: Secret::default(),
+            jwt_previous_keys: Secret::default(),
+            access_token_ttl: 900,
+            session_days: 30,
+            cursor_secret: Secret::default(),
+
+            oauth_redirect_url: "http://localhost:4000/auth".to_owned(),
+            google_client_id: String::new(),
+            google_client_secret: Secret::default(),
+            facebook_client_id: String::new(),
+            facebook_client_secret: Secret::default(),
+
+            retention_days: 30,
+            retention_interval: 3600,
+
+            smtp_server: "localhost".to_owned(),
+            smtp_email: String::new(),
+            smtp_password: Secret::default(),
+            smtp_port: 25,
+
+            mp_public_key: String::new(),
+            mp_access_token: Secret::default(),
+        }
+    }
+}
+
+fn read_file(path: &str) -> Result<String> {
+    fs::read_to_string(path).map_err(|err| {
+        Error::internal("config", "read_file")
+            .add_context("path", path)
+            .wrap_raw(err)
+    })
+}
+
+/// Collects the environment variables that can't be parsed, to report all of them at once.
+struct EnvOverrides<'a> {
+    vars: &'a HashMap<String, String>,
+    invalid: Vec<&'static str>,
+}
+
+impl<'a> EnvOverrides<'a> {
+    fn set<T: FromStr>(&mut self, key: &'static str, field: &mut T) {
+        if let Some(value) = self.vars.get(key) {
+            match value.parse() {
+                Ok(value) => *field = value,
+                Err(_) => self.invalid.push(key),
+            }
+        }
+    }
 }
 
 impl Config {
-    pub fn get() -> Self {
+    /// Loads the configuration from the defaults, the TOML file at `CONFIG_FILE` (`config.toml`
+    /// if it exists) and the environment variables, also read from `.env`. Each source overrides
+    /// the previous one.
+    pub fn load() -> Result<Self> {
         dotenv().ok();
+        let vars: HashMap<String, String> = env::vars().collect();
 
-        Config {
-            port: env::var("PORT")
-                .map(|port| {
-                    if let Ok(port) = port.parse() {
-                        port
-                    } else {
-                        3000
-                    }
-                })
-                .unwrap_or_else(|_| 3000),
-            env: env::var("ENV").unwrap_or_else(|_| "development".to_owned()),
-
-            pagination_limit: 100,
-
-            aws_key: env::var("AWS_ACCESS_KEY_ID").unwrap_or_else(|_| "".to_owned()),
-            aws_secret: env::var("AWS_SECRET_ACCESS_KEY").unwrap_or_else(|_| "".to_owned()),
-            aws_s3_bucket: env::var("AWS_S3_BUCKET").unwrap_or_else(|_| "".to_owned()),
-            aws_region: env::var("AWS_REGION").unwrap_or_else(|_| "".to_owned()),
-
-            postgres_host: env::var("POSTGRES_HOST").unwrap_or_else(|_| "localhost".to_owned()),
-            postgres_port: env::var("POSTGRES_PORT")
-                .map(|port| {
-                    if let Ok(port) = port.parse() {
-                        port
-                    } else {
-                        5432
-                    }
-                })
-                .unwrap_or_else(|_| 5432),
-            postgres_username: env::var("POSTGRES_USERNAME").unwrap_or_else(|_| "admin".to_owned()),
-            postgres_password: env::var("POSTGRES_PASSWORD").unwrap_or_else(|_| "admin".to_owned()),
-            postgres_database: env::var("POSTGRES_DATABASE").unwrap_or_else(|_| "omics".to_owned()),
-
-            smtp_server: env::var("SMTP_SERVER").unwrap_or_else(|_| "localhost".to_owned()),
-            smtp_email: env::var("SMTP_EMAIL").unwrap_or_else(|_| "user@omics.com".to_owned()),
-            smtp_password: env::var("SMTP_PASSWORD").unwrap_or_else(|_| "user123".to_owned()),
-            smtp_port: env::var("SMTP_PORT")
-                .map(|port| {
-                    if let Ok(port) = port.parse() {
-                        port
-                    } else {
-                        25
-                    }
-                })
-                .unwrap_or_else(|_| 25),
-
-            mp_public_key: env::var("MP_PUBLIC_KEY").unwrap_or_else(|_| "".to_owned()),
-            mp_access_token: env::var("MP_ACCESS_TOKEN").unwrap_or_else(|_| "".to_owned()),
+        let file = match vars.get("CONFIG_FILE") {
+            Some(path) => Some(read_file(path)?),
+            None if Path::new(DEFAULT_FILE).exists() => Some(read_file(DEFAULT_FILE)?),
+            None => None,
+        };
+
+        Self::from_sources(file.as_deref(), &vars)
+    }
+
+    /// Builds and validates the configuration from the content of a TOML file and a set of
+    /// environment variables.
+    pub fn from_sources(file: Option<&str>, vars: &HashMap<String, String>) -> Result<Self> {
+        let mut config: Config = match file {
+            Some(file) => toml::from_str(file)
+                .map_err(|err| Error::internal("config", "invalid_file").wrap_raw(err))?,
+            None => Config::default(),
+        };
+
+        config.override_with(vars)?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    fn override_with(&mut self, vars: &HashMap<String, String>) -> Result<()> {
+        let mut env = EnvOverrides {
+            vars,
+            invalid: Vec::new(),
+        };
+
+        env.set("PORT", &mut self.port);
+        env.set("ENV", &mut self.env);
+        env.set("TRUSTED_PROXIES", &mut self.trusted_proxies);
+
+        env.set("AWS_ACCESS_KEY_ID", &mut self.aws_key);
+        env.set("AWS_SECRET_ACCESS_KEY", &mut self.aws_secret);
+        env.set("AWS_S3_BUCKET", &mut self.aws_s3_bucket);
+        env.set("AWS_REGION", &mut self.aws_region);
+
+        env.set("POSTGRES_HOST", &mut self.postgres_host);
+        env.set("POSTGRES_PORT", &mut self.postgres_port);
+        env.set("POSTGRES_USERNAME", &mut self.postgres_username);
+        env.set("POSTGRES_PASSWORD", &mut self.postgres_password);
+        env.set("POSTGRES_DATABASE", &mut self.postgres_database);
+        env.set("POSTGRES_SSL_MODE", &mut self.postgres_ssl_mode);
+        env.set("POSTGRES_POOL_SIZE", &mut self.postgres_pool_size);
+        env.set("POSTGRES_POOL_TIMEOUT", &mut self.postgres_pool_timeout);
+        env.set(
+            "POSTGRES_CONNECT_TIMEOUT",
+            &mut self.postgres_connect_timeout,
+        );
+
+        env.set("EVENT_BUS", &mut self.event_bus);
+        env.set(
+            "EVENT_BUS_CONSUMER_GROUP",
+            &mut self.event_bus_consumer_group,
+        );
+
+        env.set("JWT_KEY_ID", &mut self.jwt_key_id);
+        env.set("JWT_SECRET", &mut self.jwt_secret);
+        env.set("JWT_PREVIOUS_KEYS", &mut self.jwt_previous_keys);
+        env.set("ACCESS_TOKEN_TTL", &mut self.access_token_ttl);
+        env.set("SESSION_DAYS", &mut self.session_days);
+        env.set("CURSOR_SECRET", &mut self.cursor_secret);
+
+        env.set("OAUTH_REDIRECT_URL", &mut self.oauth_redirect_url);
+        env.set("GOOGLE_CLIENT_ID", &mut self.google_client_id);
+        env.set("GOOGLE_CLIENT_SECRET", &mut self.google_client_secret);
+        env.set("FACEBOOK_CLIENT_ID", &mut self.facebook_client_id);
+        env.set("FACEBOOK_CLIENT_SECRET", &mut self.facebook_client_secret);
+
+        env.set("RETENTION_DAYS", &mut self.retention_days);
+        env.set("RETENTION_INTERVAL", &mut self.retention_interval);
+
+        env.set("SMTP_SERVER", &mut self.smtp_server);
+        env.set("SMTP_EMAIL", &mut self.smtp_email);
+        env.set("SMTP_PASSWORD", &mut self.smtp_password);
+        env.set("SMTP_PORT", &mut self.smtp_port);
+
+        env.set("MP_PUBLIC_KEY", &mut self.mp_public_key);
+        env.set("MP_ACCESS_TOKEN", &mut self.mp_access_token);
+
+        if env.invalid.is_empty() {
+            return Ok(());
+        }
+
+        let mut err = Error::internal("config", "invalid_env");
+        for key in env.invalid.into_iter() {
+            err = err.add_context(key, "invalid value");
+        }
+
+        Err(err)
+    }
+
+    /// Checks every value and returns an error with the reason of each invalid one as context.
+    pub fn validate(&self) -> Result<()> {
+        let mut err = Error::internal("config", "invalid");
+
+        if self.port == 0 {
+            err = err.add_context("port", "must be greater than 0");
+        }
+        if self
+            .trusted_proxies_list()
+            .any(|proxy| proxy.parse::<IpAddr>().is_err())
+        {
+            err = err.add_context(
+                "trusted_proxies",
+                "must be IP addresses separated by commas",
+            );
+        }
+
+        if self.postgres_host.is_empty() {
+            err = err.add_context("postgres_host", "required");
+        }
+        if self.postgres_port == 0 {
+            err = err.add_context("postgres_port", "must be greater than 0");
+        }
+        if self.postgres_username.is_empty() {
+            err = err.add_context("postgres_username", "required");
+        }
+        if self.postgres_password.is_empty() {
+            err = err.add_context("postgres_password", "required");
+        }
+        if self.postgres_database.is_empty() {
+            err = err.add_context("postgres_database", "required");
+        }
+        if !["disable", "prefer", "require"].contains(&self.postgres_ssl_mode.as_str()) {
+            err = err.add_context("postgres_ssl_mode", "must be disable, prefer or require");
+        }
+        if self.postgres_pool_size == 0 {
+            err = err.add_context("postgres_pool_size", "must be greater than 0");
+        }
+        if self.postgres_pool_timeout == 0 {
+            err = err.add_context("postgres_pool_timeout", "must be greater than 0");
+        }
+        if self.postgres_connect_timeout == 0 {
+            err = err.add_context("postgres_connect_timeout", "must be greater than 0");
+        }
+
+        if !["inmem", "postgres"].contains(&self.event_bus.as_str()) {
+            err = err.add_context("event_bus", "must be inmem or postgres");
+        }
+        // Every instance has to accept the cursors signed by the others.
+        if self.event_bus == "postgres" && self.cursor_secret.is_empty() {
+            err = err.add_context("cursor_secret", "required with the postgres event bus");
+        }
+        if self.event_bus_consumer_group.is_empty() {
+            err = err.add_context("event_bus_consumer_group", "required");
+        }
+
+        if self.jwt_key_id.is_empty() {
+            err = err.add_context("jwt_key_id", "required");
+        }
+        if self.jwt_secret.is_empty() {
+            err = err.add_context("jwt_secret", "required");
+        }
+        if self.jwt_previous_keys().iter().any(|(key_id, secret)| {
+            key_id.is_empty() || secret.is_empty() || *key_id == self.jwt_key_id
+        }) {
+            err = err.add_context(
+                "jwt_previous_keys",
+                "must be key_id:secret pairs separated by commas, with other key IDs",
+            );
+        }
+        if self.access_token_ttl == 0 {
+            err = err.add_context("access_token_ttl", "must be greater than 0");
+        }
+        if self.session_days == 0 {
+            err = err.add_context("session_days", "must be greater than 0");
+        }
+
+        if self.oauth_redirect_url.is_empty() {
+            err = err.add_context("oauth_redirect_url", "required");
+        }
+        if !self.google_client_id.is_empty() && self.google_client_secret.is_empty() {
+            err = err.add_context("google_client_secret", "required with google_client_id");
+        }
+        if !self.facebook_client_id.is_empty() && self.facebook_client_secret.is_empty() {
+            err = err.add_context("facebook_client_secret", "required with facebook_client_id");
+        }
+
+        if self.retention_days == 0 {
+            err = err.add_context("retention_days", "must be greater than 0");
+        }
+        if self.retention_interval == 0 {
+            err = err.add_context("retention_interval", "must be greater than 0");
+        }
+
+        if self.smtp_port == 0 {
+            err = err.add_context("smtp_port", "must be greater than 0");
+        }
+
+        // External services are only used in production.
+        if self.env == "production" {
+            let required = [
+                ("cursor_secret", self.cursor_secret.is_empty()),
+                ("aws_s3_bucket", self.aws_s3_bucket.is_empty()),
+                ("smtp_server", self.smtp_server.is_empty()),
+                ("smtp_email", self.smtp_email.is_empty()),
+                ("smtp_password", self.smtp_password.is_empty()),
+                ("mp_public_key", self.mp_public_key.is_empty()),
+                ("mp_access_token", self.mp_access_token.is_empty()),
+            ];
+
+            for (field, missing) in required.iter() {
+                if *missing {
+                    err = err.add_context(*field, "required in production");
+                }
+            }
+        }
+
+        if err.has_context() {
+            return Err(err);
         }
+
+        Ok(())
     }
 
     pub fn port(&self) -> u16 {
@@ -101,8 +391,19 @@ impl Config {
         &self.env
     }
 
-    pub fn pagination_limit(&self) -> usize {
-        self.pagination_limit
+    /// Proxies the API is deployed behind. Forwarding headers are only read from requests they
+    /// send, since any client can send them.
+    pub fn trusted_proxies(&self) -> Vec<IpAddr> {
+        self.trusted_proxies_list()
+            .filter_map(|proxy| proxy.parse().ok())
+            .collect()
+    }
+
+    fn trusted_proxies_list(&self) -> impl Iterator<Item = &str> {
+        self.trusted_proxies
+            .split(',')
+            .map(str::trim)
+            .filter(|proxy| !proxy.is_empty())
     }
 
     pub fn aws_key(&self) -> &str {
@@ -110,7 +411,7 @@ impl Config {
     }
 
     pub fn aws_secret(&self) -> &str {
-        &self.aws_secret
+        self.aws_secret.expose()
     }
 
     pub fn aws_s3_bucket(&self) -> &str {
@@ -134,13 +435,142 @@ impl Config {
     }
 
     pub fn postgres_password(&self) -> &str {
-        &self.postgres_password
+        self.postgres_password.expose()
     }
 
     pub fn postgres_database(&self) -> &str {
         &self.postgres_database
     }
 
+    /// TLS mode of Postgres connections: `disable`, `prefer` or `require`.
+    pub fn postgres_ssl_mode(&self) -> &str {
+        &self.postgres_ssl_mode
+    }
+
+    /// Maximum number of connections opened by the pool.
+    pub fn postgres_pool_size(&self) -> usize {
+        self.postgres_pool_size
+    }
+
+    /// Time to wait for a connection of the pool when all of them are in use.
+    pub fn postgres_pool_timeout(&self) -> Duration {
+        Duration::from_secs(self.postgres_pool_timeout)
+    }
+
+    pub fn postgres_connect_timeout(&self) -> Duration {
+        Duration::from_secs(self.postgres_connect_timeout)
+    }
+
+    /// Connection parameters for `tokio_postgres::connect`.
+    pub fn postgres_params(&self) -> String {
+        format!(
+            "host={} port={} user={} password={} dbname={} sslmode={}",
+            self.postgres_host,
+            self.postgres_port,
+            self.postgres_username,
+            self.postgres_password.expose(),
+            self.postgres_database,
+            self.postgres_ssl_mode,
+        )
+    }
+
+    /// Event bus implementation: `inmem` for a single process, or `postgres` to share the events
+    /// among several instances.
+    pub fn event_bus(&self) -> &str {
+        &self.event_bus
+    }
+
+    /// Instances in the same consumer group process each event once.
+    pub fn event_bus_consumer_group(&self) -> &str {
+        &self.event_bus_consumer_group
+    }
+
+    /// ID of the key used to sign the authentication tokens, sent in their `kid` header.
+    pub fn jwt_key_id(&self) -> &str {
+        &self.jwt_key_id
+    }
+
+    /// Key used to sign the authentication tokens.
+    pub fn jwt_secret(&self) -> &str {
+        self.jwt_secret.expose()
+    }
+
+    /// Keys used before the last rotations, as `(key_id, secret)`. Tokens signed with them are
+    /// still accepted until they expire.
+    pub fn jwt_previous_keys(&self) -> Vec<(&str, &str)> {
+        self.jwt_previous_keys
+            .expose()
+            .split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(|key| {
+                let mut parts = key.splitn(2, ':');
+                (
+                    parts.next().unwrap_or("").trim(),
+                    parts.next().unwrap_or("").trim(),
+                )
+            })
+            .collect()
+    }
+
+    /// Time an access token is valid. Then a new one is obtained with the refresh token.
+    pub fn access_token_ttl(&self) -> Duration {
+        Duration::from_secs(self.access_token_ttl)
+    }
+
+    /// Time a session is kept without refreshing its tokens.
+    pub fn session_ttl(&self) -> Duration {
+        Duration::from_secs(u64::from(self.session_days) * 24 * 60 * 60)
+    }
+
+    /// Key used to sign the pagination cursors. If it's empty, a random key is used, which is
+    /// not shared among instances, so it's required with the `postgres` event bus.
+    pub fn cursor_secret(&self) -> &str {
+        self.cursor_secret.expose()
+    }
+
+    /// URL of the page providers redirect back to after users log in with them, followed by the
+    /// name of the provider.
+    pub fn oauth_redirect_url(&self, provider: &str) -> String {
+        format!(
+            "{}/{}",
+            self.oauth_redirect_url.trim_end_matches('/'),
+            provider
+        )
+    }
+
+    /// Client ID and secret of the application in Google, if logging in with Google is enabled.
+    pub fn google_client(&self) -> Option<(&str, &str)> {
+        if self.google_client_id.is_empty() {
+            return None;
+        }
+
+        Some((&self.google_client_id, self.google_client_secret.expose()))
+    }
+
+    /// Client ID and secret of the application in Facebook, if logging in with Facebook is
+    /// enabled.
+    pub fn facebook_client(&self) -> Option<(&str, &str)> {
+        if self.facebook_client_id.is_empty() {
+            return None;
+        }
+
+        Some((
+            &self.facebook_client_id,
+            self.facebook_client_secret.expose(),
+        ))
+    }
+
+    /// Time deleted records can be restored before they are purged.
+    pub fn retention_period(&self) -> chrono::Duration {
+        chrono::Duration::days(i64::from(self.retention_days))
+    }
+
+    /// Time between the purges of deleted records.
+    pub fn retention_interval(&self) -> Duration {
+        Duration::from_secs(self.retention_interval)
+    }
+
     pub fn smtp_server(&self) -> &str {
         &self.smtp_server
     }
@@ -150,7 +580,7 @@ impl Config {
     }
 
     pub fn smtp_password(&self) -> &str {
-        &self.smtp_password
+        self.smtp_password.expose()
     }
 
     pub fn smtp_port(&self) -> u16 {
@@ -162,6 +592,181 @@ impl Config {
     }
 
     pub fn mp_access_token(&self) -> &str {
-        &self.mp_access_token
+        self.mp_access_token.expose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(vars: &[(&str, &str)]) -> HashMap<String, String> {
+        vars.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn required_vars() -> HashMap<String, String> {
+        vars(&[
+            ("POSTGRES_USERNAME", "omics"),
+            ("POSTGRES_PASSWORD", "p4ssw0rd"),
+            ("JWT_SECRET", "jwt-s3cr3t"),
+        ])
+    }
+
+    #[test]
+    fn layers() {
+        let config = Config::from_sources(None, &required_vars()).unwrap();
+        assert_eq!(config.port(), 3000);
+        assert_eq!(config.postgres_host(), "localhost");
+        assert_eq!(config.postgres_username(), "omics");
+        assert_eq!(config.retention_period(), chrono::Duration::days(30));
+
+        let file = r#"
+            port = 8080
+            postgres_host = "db"
+            postgres_pool_size = 4
+        "#;
+        let mut env = required_vars();
+        env.insert("PORT".to_owned(), "9090".to_owned());
+
+        let config = Config::from_sources(Some(file), &env).unwrap();
+        assert_eq!(config.port(), 9090);
+        assert_eq!(config.postgres_host(), "db");
+        assert_eq!(config.postgres_pool_size(), 4);
+        assert_eq!(config.postgres_port(), 5432);
+    }
+
+    #[test]
+    fn invalid_sources() {
+        let err = Config::from_sources(Some("unknown = 1"), &required_vars())
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "invalid_file");
+
+        let mut env = required_vars();
+        env.insert("PORT".to_owned(), "http".to_owned());
+        env.insert("POSTGRES_POOL_SIZE".to_owned(), "-1".to_owned());
+        let err = Config::from_sources(None, &env).err().unwrap();
+        assert_eq!(err.code(), "invalid_env");
+        assert_eq!(err.context().len(), 2);
+        assert!(err.context().contains_key("PORT"));
+        assert!(err.context().contains_key("POSTGRES_POOL_SIZE"));
+    }
+
+    #[test]
+    fn validation() {
+        let err = Config::from_sources(None, &HashMap::new()).err().unwrap();
+        assert_eq!(err.code(), "invalid");
+        assert!(err.context().contains_key("postgres_username"));
+        assert!(err.context().contains_key("postgres_password"));
+        assert!(err.context().contains_key("jwt_secret"));
+
+        let mut env = required_vars();
+        env.insert("ENV".to_owned(), "production".to_owned());
+        env.insert("EVENT_BUS".to_owned(), "kafka".to_owned());
+        let err = Config::from_sources(None, &env).err().unwrap();
+        assert_eq!(
+            err.context().get("event_bus"),
+            Some(&"must be inmem or postgres".to_owned())
+        );
+        assert_eq!(
+            err.context().get("mp_access_token"),
+            Some(&"required in production".to_owned())
+        );
+
+        let mut env = required_vars();
+        env.insert("EVENT_BUS".to_owned(), "postgres".to_owned());
+        let err = Config::from_sources(None, &env).err().unwrap();
+        assert_eq!(
+            err.context().get("cursor_secret"),
+            Some(&"required with the postgres event bus".to_owned())
+        );
+
+        env.insert("CURSOR_SECRET".to_owned(), "s3cr3t".to_owned());
+        assert!(Config::from_sources(None, &env).is_ok());
+    }
+
+    #[test]
+    fn jwt_keys() {
+        let mut env = required_vars();
+        env.insert("JWT_KEY_ID".to_owned(), "2024-02".to_owned());
+        env.insert(
+            "JWT_PREVIOUS_KEYS".to_owned(),
+            "2024-01:s3cr3t-1, 2023-12:s3cr3t:0".to_owned(),
+        );
+        let config = Config::from_sources(None, &env).unwrap();
+        assert_eq!(config.jwt_key_id(), "2024-02");
+        assert_eq!(
+            config.jwt_previous_keys(),
+            vec![("2024-01", "s3cr3t-1"), ("2023-12", "s3cr3t:0")]
+        );
+        assert_eq!(config.access_token_ttl(), Duration::from_secs(900));
+
+        for previous_keys in ["2024-01", "2024-02:s3cr3t", ":s3cr3t"].iter() {
+            env.insert("JWT_PREVIOUS_KEYS".to_owned(), previous_keys.to_string());
+            let err = Config::from_sources(None, &env).err().unwrap();
+            assert!(err.context().contains_key("jwt_previous_keys"));
+        }
+    }
+
+    #[test]
+    fn trusted_proxies() {
+        let config = Config::from_sources(None, &required_vars()).unwrap();
+        assert!(config.trusted_proxies().is_empty());
+
+        let mut env = required_vars();
+        env.insert("TRUSTED_PROXIES".to_owned(), "10.0.0.1, ::1".to_owned());
+        let config = Config::from_sources(None, &env).unwrap();
+        assert_eq!(
+            config.trusted_proxies(),
+            vec![
+                "10.0.0.1".parse::<IpAddr>().unwrap(),
+                "::1".parse::<IpAddr>().unwrap()
+            ]
+        );
+
+        env.insert("TRUSTED_PROXIES".to_owned(), "10.0.0.0/8".to_owned());
+        let err = Config::from_sources(None, &env).err().unwrap();
+        assert!(err.context().contains_key("trusted_proxies"));
+    }
+
+    #[test]
+    fn oauth_clients() {
+        let config = Config::from_sources(None, &required_vars()).unwrap();
+        assert!(config.google_client().is_none());
+        assert_eq!(
+            config.oauth_redirect_url("google"),
+            "http://localhost:4000/auth/google"
+        );
+
+        let mut env = required_vars();
+        env.insert("GOOGLE_CLIENT_ID".to_owned(), "omics-app".to_owned());
+        let err = Config::from_sources(None, &env).err().unwrap();
+        assert!(err.context().contains_key("google_client_secret"));
+
+        env.insert("GOOGLE_CLIENT_SECRET".to_owned(), "g00gl3".to_owned());
+        env.insert(
+            "OAUTH_REDIRECT_URL".to_owned(),
+            "https://omics.com/login/".to_owned(),
+        );
+        let config = Config::from_sources(None, &env).unwrap();
+        assert_eq!(config.google_client(), Some(("omics-app", "g00gl3")));
+        assert!(config.facebook_client().is_none());
+        assert_eq!(
+            config.oauth_redirect_url("google"),
+            "https://omics.com/login/google"
+        );
+    }
+
+    #[test]
+    fn redacted_secrets() {
+        let config = Config::from_sources(None, &required_vars()).unwrap();
+        assert_eq!(config.jwt_secret(), "jwt-s3cr3t");
+
+        let debug = format!("{:?}", config);
+        assert!(!debug.contains("p4ssw0rd"));
+        assert!(!debug.contains("jwt-s3cr3t"));
+        assert!(debug.contains("[REDACTED]"));
     }
 }
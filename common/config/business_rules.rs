@@ -1,4 +1,9 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::result::Result;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BusinessRules {
@@ -12,3 +17,205 @@ pub struct BusinessRules {
     pub subscription_percentage_retention: f64,
     pub minimum_charge_amount: f64,
 }
+
+impl BusinessRules {
+    /// Checks that every field is in its valid range. The error has the range of each invalid
+    /// field as context.
+    pub fn validate(&self) -> Result<()> {
+        let ranges = [
+            (
+                "minimum_donation_amount",
+                self.minimum_donation_amount,
+                0.0,
+                1_000_000.0,
+            ),
+            (
+                "donation_percentage_retention",
+                self.donation_percentage_retention,
+                0.0,
+                1.0,
+            ),
+            (
+                "days_to_generate_summaries",
+                self.days_to_generate_summaries as f64,
+                1.0,
+                365.0,
+            ),
+            (
+                "minimum_views_percentage_to_require_contract",
+                self.minimum_views_percentage_to_require_contract,
+                0.0,
+                1.0,
+            ),
+            (
+                "subscription_percentage_retention",
+                self.subscription_percentage_retention,
+                0.0,
+                1.0,
+            ),
+            (
+                "minimum_charge_amount",
+                self.minimum_charge_amount,
+                0.0,
+                1_000_000.0,
+            ),
+        ];
+
+        let mut err = Error::new("business_rules", "out_of_range");
+        for (field, value, min, max) in ranges.iter() {
+            // Written this way so NaN is out of range too.
+            if !(value >= min && value <= max) {
+                err = err.add_context(field.to_string(), format!("{}..{}", min, max));
+            }
+        }
+
+        if err.has_context() {
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    /// Fields whose value in `other` is different.
+    pub fn diff(&self, other: &BusinessRules) -> Result<Vec<BusinessRulesChange>> {
+        let from = serde_json::to_value(self)?;
+        let to = serde_json::to_value(other)?;
+
+        let mut changes = Vec::new();
+        if let (Value::Object(from), Value::Object(to)) = (from, to) {
+            for (field, from) in from.into_iter() {
+                let to = to.get(&field).cloned().unwrap_or(Value::Null);
+                if from != to {
+                    changes.push(BusinessRulesChange { field, from, to });
+                }
+            }
+        }
+
+        Ok(changes)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BusinessRulesChange {
+    pub field: String,
+    pub from: Value,
+    pub to: Value,
+}
+
+/// BusinessRulesVersion is an entry of the history of the business rules. A version is in force
+/// from `effective_from` until the next version becomes effective, so changes can be scheduled
+/// and payouts can be computed again with the rules of their period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusinessRulesVersion {
+    version: u32,
+    rules: BusinessRules,
+    author_id: Option<String>,
+    created_at: DateTime<Utc>,
+    effective_from: DateTime<Utc>,
+}
+
+impl BusinessRulesVersion {
+    pub fn build(
+        version: u32,
+        rules: BusinessRules,
+        author_id: Option<String>,
+        created_at: DateTime<Utc>,
+        effective_from: DateTime<Utc>,
+    ) -> Self {
+        BusinessRulesVersion {
+            version,
+            rules,
+            author_id,
+            created_at,
+            effective_from,
+        }
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn rules(&self) -> &BusinessRules {
+        &self.rules
+    }
+
+    pub fn author_id(&self) -> Option<&str> {
+        self.author_id.as_deref()
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn effective_from(&self) -> &DateTime<Utc> {
+        &self.effective_from
+    }
+
+    pub fn into_rules(self) -> BusinessRules {
+        self.rules
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn business_rules() -> BusinessRules {
+        BusinessRules {
+            minimum_donation_amount: 50.0,
+            donation_percentage_retention: 0.3,
+            days_to_generate_summaries: 10,
+            minimum_views_percentage_to_require_contract: 0.01,
+            subscription_percentage_retention: 0.7,
+            minimum_charge_amount: 200.0,
+        }
+    }
+
+    #[test]
+    fn validate() {
+        assert!(business_rules().validate().is_ok());
+
+        let mut rules = business_rules();
+        rules.donation_percentage_retention = 1.5;
+        rules.days_to_generate_summaries = 0;
+        rules.minimum_charge_amount = f64::NAN;
+
+        let err = rules.validate().err().unwrap();
+        assert_eq!(err.code(), "out_of_range");
+        assert_eq!(err.context().len(), 3);
+        assert_eq!(
+            err.context().get("donation_percentage_retention"),
+            Some(&"0..1".to_owned())
+        );
+        assert!(err.context().contains_key("days_to_generate_summaries"));
+        assert!(err.context().contains_key("minimum_charge_amount"));
+    }
+
+    #[test]
+    fn diff() {
+        let from = business_rules();
+        assert!(from.diff(&from).unwrap().is_empty());
+
+        let mut to = business_rules();
+        to.subscription_percentage_retention = 0.6;
+        to.days_to_generate_summaries = 15;
+
+        let mut changes = from.diff(&to).unwrap();
+        changes.sort_by(|a, b| a.field.cmp(&b.field));
+        assert_eq!(
+            changes,
+            vec![
+                BusinessRulesChange {
+                    field: "days_to_generate_summaries".to_owned(),
+                    from: Value::from(10),
+                    to: Value::from(15),
+                },
+                BusinessRulesChange {
+                    field: "subscription_percentage_retention".to_owned(),
+                    from: Value::from(0.7),
+                    to: Value::from(0.6),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::config::{BusinessRules, BusinessRulesVersion};
+use crate::result::Result;
+
+#[async_trait]
+pub trait BusinessRulesRepository: Sync + Send {
+    /// Every version, from the oldest to the newest.
+    async fn find_all(&self) -> Result<Vec<BusinessRulesVersion>>;
+    async fn find_by_version(&self, version: u32) -> Result<BusinessRulesVersion>;
+
+    /// The latest version whose `effective_from` is not after `date`.
+    async fn find_effective_at(&self, date: &DateTime<Utc>) -> Result<BusinessRulesVersion>;
+
+    /// Stores the rules as a new version. The version number is assigned by the repository.
+    async fn append(
+        &self,
+        rules: BusinessRules,
+        author_id: Option<String>,
+        effective_from: DateTime<Utc>,
+    ) -> Result<BusinessRulesVersion>;
+}
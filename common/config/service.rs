@@ -1,31 +1,157 @@
 use std::sync::Arc;
 
-use crate::cache::Cache;
+use chrono::{DateTime, Utc};
 
-use crate::config::BusinessRules;
+use crate::config::{
+    BusinessRules, BusinessRulesChange, BusinessRulesRepository, BusinessRulesVersion,
+};
 use crate::error::Error;
 use crate::result::Result;
 
 pub struct ConfigService {
-    cache: Arc<dyn Cache<String, BusinessRules>>,
+    business_rules_repo: Arc<dyn BusinessRulesRepository>,
 }
 
 impl ConfigService {
-    pub fn new(cache: Arc<dyn Cache<String, BusinessRules>>) -> Self {
-        ConfigService { cache }
+    pub fn new(business_rules_repo: Arc<dyn BusinessRulesRepository>) -> Self {
+        ConfigService {
+            business_rules_repo,
+        }
     }
 
+    /// Rules in force right now.
     pub async fn get_business_rules(&self) -> Result<BusinessRules> {
-        if let Some(business_rules) = self.cache.get(&"business_rules".to_owned()).await {
-            return Ok(business_rules);
+        self.get_business_rules_at(&Utc::now()).await
+    }
+
+    /// Rules that were (or will be) in force at `date`.
+    pub async fn get_business_rules_at(&self, date: &DateTime<Utc>) -> Result<BusinessRules> {
+        let version = self.business_rules_repo.find_effective_at(date).await?;
+        Ok(version.into_rules())
+    }
+
+    /// Stores the rules as a new version. Without `effective_from` they take effect
+    /// immediately; otherwise the change is scheduled for that date.
+    pub async fn save_business_rules(
+        &self,
+        business_rules: BusinessRules,
+        author_id: Option<String>,
+        effective_from: Option<DateTime<Utc>>,
+    ) -> Result<BusinessRulesVersion> {
+        business_rules.validate()?;
+
+        let now = Utc::now();
+        let effective_from = match effective_from {
+            Some(effective_from) if effective_from < now => {
+                return Err(Error::new("business_rules", "effective_from_in_past"));
+            }
+            Some(effective_from) => effective_from,
+            None => now,
+        };
+
+        self.business_rules_repo
+            .append(business_rules, author_id, effective_from)
+            .await
+    }
+
+    pub async fn history(&self) -> Result<Vec<BusinessRulesVersion>> {
+        self.business_rules_repo.find_all().await
+    }
+
+    /// Changes from version `from` to version `to`, or to the rules in force if `to` is not
+    /// given.
+    pub async fn diff(&self, from: u32, to: Option<u32>) -> Result<Vec<BusinessRulesChange>> {
+        let from = self.business_rules_repo.find_by_version(from).await?;
+        let to = match to {
+            Some(to) => self.business_rules_repo.find_by_version(to).await?,
+            None => {
+                self.business_rules_repo
+                    .find_effective_at(&Utc::now())
+                    .await?
+            }
+        };
+
+        from.rules().diff(to.rules())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Duration;
+
+    use crate::infrastructure::config::InMemBusinessRulesRepository;
+
+    fn business_rules() -> BusinessRules {
+        BusinessRules {
+            minimum_donation_amount: 50.0,
+            donation_percentage_retention: 0.3,
+            days_to_generate_summaries: 10,
+            minimum_views_percentage_to_require_contract: 0.01,
+            subscription_percentage_retention: 0.7,
+            minimum_charge_amount: 200.0,
         }
+    }
+
+    #[tokio::test]
+    async fn scheduled_rules() {
+        let serv = ConfigService::new(Arc::new(InMemBusinessRulesRepository::new()));
+        assert!(serv.get_business_rules().await.is_err());
+
+        let current = serv
+            .save_business_rules(business_rules(), Some("admin".to_owned()), None)
+            .await
+            .unwrap();
+        assert_eq!(current.version(), 1);
+        assert_eq!(current.author_id(), Some("admin"));
+
+        let mut rules = business_rules();
+        rules.subscription_percentage_retention = 0.6;
+        let next_month = Utc::now() + Duration::days(30);
+        let scheduled = serv
+            .save_business_rules(rules, None, Some(next_month))
+            .await
+            .unwrap();
+        assert_eq!(scheduled.version(), 2);
 
-        Err(Error::not_found("business_rules"))
+        let rules = serv.get_business_rules().await.unwrap();
+        assert_eq!(rules.subscription_percentage_retention, 0.7);
+
+        let rules = serv
+            .get_business_rules_at(&(next_month + Duration::days(1)))
+            .await
+            .unwrap();
+        assert_eq!(rules.subscription_percentage_retention, 0.6);
+
+        assert_eq!(serv.history().await.unwrap().len(), 2);
+
+        let changes = serv.diff(1, Some(2)).await.unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].field, "subscription_percentage_retention");
+        assert!(serv.diff(1, None).await.unwrap().is_empty());
     }
 
-    pub async fn save_business_rules(&self, business_rules: BusinessRules) -> Result<()> {
-        self.cache
-            .set("business_rules".to_owned(), business_rules)
+    #[tokio::test]
+    async fn invalid_rules() {
+        let serv = ConfigService::new(Arc::new(InMemBusinessRulesRepository::new()));
+
+        let mut rules = business_rules();
+        rules.donation_percentage_retention = 2.0;
+        let err = serv
+            .save_business_rules(rules, None, None)
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "out_of_range");
+
+        let err = serv
+            .save_business_rules(business_rules(), None, Some(Utc::now() - Duration::days(1)))
             .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "effective_from_in_past");
+
+        assert!(serv.history().await.unwrap().is_empty());
     }
 }
@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 
-use crate::event::EventSubscriber;
+use crate::event::{EventHandler, EventSubscriber};
 use crate::result::Result;
 
 #[async_trait]
@@ -16,6 +16,11 @@ pub trait Container: Sync + Send {
         Ok(())
     }
 
+    /// Handlers that can rebuild the state of this context from stored events, by name.
+    fn replay_handlers(&self) -> Vec<(&'static str, Box<dyn EventHandler>)> {
+        Vec::new()
+    }
+
     async fn populate(&self) -> Result<()> {
         Ok(())
     }
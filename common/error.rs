@@ -1,3 +1,8 @@
+mod catalog;
+mod language;
+pub use catalog::*;
+pub use language::*;
+
 use std::cmp;
 use std::collections::HashMap;
 use std::error;
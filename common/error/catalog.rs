@@ -0,0 +1,1453 @@
+use crate::error::Error;
+
+/// Path matching any entity or field, used by codes such as the ones of `Error::not_found` or
+/// the persistence errors of every repository.
+pub const ANY_PATH: &str = "*";
+
+/// ErrorDefinition registers an error code: the HTTP status returned to clients and the message
+/// shown to users in every supported language.
+#[derive(Debug)]
+pub struct ErrorDefinition {
+    path: &'static str,
+    code: &'static str,
+    status: u32,
+    es: &'static str,
+    en: &'static str,
+}
+
+impl ErrorDefinition {
+    const fn new(
+        path: &'static str,
+        code: &'static str,
+        status: u32,
+        es: &'static str,
+        en: &'static str,
+    ) -> Self {
+        ErrorDefinition {
+            path,
+            code,
+            status,
+            es,
+            en,
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        self.path
+    }
+
+    pub fn code(&self) -> &str {
+        self.code
+    }
+
+    pub fn status(&self) -> u32 {
+        self.status
+    }
+
+    pub fn message(&self, language: Language) -> &str {
+        match language {
+            Language::Es => self.es,
+            Language::En => self.en,
+        }
+    }
+}
+
+/// Every error code returned by the application. New `(path, code)` pairs created with
+/// `Error::new` have to be added here, or the catalog test fails.
+const CATALOG: &[ErrorDefinition] = &[
+    // Codes shared by every path.
+    ErrorDefinition::new("*", "not_found", 404, "No encontrado", "Not found"),
+    ErrorDefinition::new("*", "bad_format", 400, "Formato inválido", "Invalid format"),
+    ErrorDefinition::new(
+        "*",
+        "not_owner",
+        401,
+        "No es el propietario",
+        "You are not the owner",
+    ),
+    ErrorDefinition::new(
+        "*",
+        "create",
+        500,
+        "No se pudo crear",
+        "Could not be created",
+    ),
+    ErrorDefinition::new(
+        "*",
+        "update",
+        500,
+        "No se pudo actualizar",
+        "Could not be updated",
+    ),
+    ErrorDefinition::new(
+        "*",
+        "delete",
+        500,
+        "No se pudo eliminar",
+        "Could not be deleted",
+    ),
+    ErrorDefinition::new(
+        "*",
+        "total",
+        500,
+        "No se pudo contar",
+        "Could not be counted",
+    ),
+    ErrorDefinition::new(
+        "*",
+        "matching_criteria",
+        500,
+        "No se pudo buscar",
+        "Could not be searched",
+    ),
+    ErrorDefinition::new(
+        "*",
+        "purge",
+        500,
+        "No se pudo depurar",
+        "Could not be purged",
+    ),
+    ErrorDefinition::new("*", "deserialize", 500, "Datos inválidos", "Invalid data"),
+    ErrorDefinition::new(
+        "error",
+        "internal_server",
+        500,
+        "Ocurrió un error inesperado",
+        "An unexpected error occurred",
+    ),
+    ErrorDefinition::new(
+        "amount",
+        "invalid_range",
+        400,
+        "El monto está fuera del rango permitido",
+        "The amount is out of range",
+    ),
+    ErrorDefinition::new(
+        "attempts",
+        "record",
+        500,
+        "No se pudo registrar el intento",
+        "The attempt could not be recorded",
+    ),
+    ErrorDefinition::new(
+        "author",
+        "cannot_follow_itself",
+        400,
+        "No podés seguirte a vos mismo",
+        "You cannot follow yourself",
+    ),
+    ErrorDefinition::new(
+        "author",
+        "cannot_unfollow_itself",
+        400,
+        "No podés dejar de seguirte a vos mismo",
+        "You cannot unfollow yourself",
+    ),
+    ErrorDefinition::new(
+        "author",
+        "does_not_have_followers",
+        400,
+        "El autor no tiene seguidores",
+        "The author does not have followers",
+    ),
+    ErrorDefinition::new(
+        "authorization",
+        "exchange",
+        502,
+        "No se pudo completar el ingreso con el proveedor",
+        "The login with the provider could not be completed",
+    ),
+    ErrorDefinition::new(
+        "authorization",
+        "invalid_grant",
+        400,
+        "El proveedor rechazó el código de autorización",
+        "The provider rejected the authorization code",
+    ),
+    ErrorDefinition::new(
+        "authorization",
+        "invalid_header",
+        401,
+        "El encabezado de autorización es inválido",
+        "The authorization header is invalid",
+    ),
+    ErrorDefinition::new(
+        "authorization",
+        "invalid_id_token",
+        401,
+        "El proveedor devolvió una identidad inválida",
+        "The provider returned an invalid identity",
+    ),
+    ErrorDefinition::new(
+        "authorization",
+        "invalid_state",
+        400,
+        "La autorización es inválida o expiró, volvé a intentarlo",
+        "The authorization is invalid or expired, try again",
+    ),
+    ErrorDefinition::new(
+        "authorization",
+        "unauthorized",
+        401,
+        "No estás autorizado",
+        "You are not authorized",
+    ),
+    ErrorDefinition::new(
+        "backup",
+        "execute_command",
+        500,
+        "No se pudo generar el backup",
+        "The backup could not be generated",
+    ),
+    ErrorDefinition::new(
+        "backup",
+        "read_file",
+        500,
+        "No se pudo leer el backup",
+        "The backup could not be read",
+    ),
+    ErrorDefinition::new(
+        "backup",
+        "read_files",
+        500,
+        "No se pudieron listar los backups",
+        "The backups could not be listed",
+    ),
+    ErrorDefinition::new(
+        "biography",
+        "empty",
+        400,
+        "La biografía está vacía",
+        "The biography is empty",
+    ),
+    ErrorDefinition::new(
+        "birthdate",
+        "too_old",
+        400,
+        "La fecha de nacimiento es demasiado antigua",
+        "The birthdate is too old",
+    ),
+    ErrorDefinition::new(
+        "birthdate",
+        "too_young",
+        400,
+        "Sos demasiado joven para registrarte",
+        "You are too young to sign up",
+    ),
+    ErrorDefinition::new(
+        "business_rules",
+        "effective_from_in_past",
+        400,
+        "La fecha de entrada en vigencia no puede ser pasada",
+        "The effective date cannot be in the past",
+    ),
+    ErrorDefinition::new(
+        "business_rules",
+        "out_of_range",
+        400,
+        "Hay valores fuera de rango",
+        "Some values are out of range",
+    ),
+    ErrorDefinition::new(
+        "category",
+        "already_exists",
+        409,
+        "La categoría ya existe",
+        "The category already exists",
+    ),
+    ErrorDefinition::new(
+        "chunk",
+        "read",
+        500,
+        "No se pudo leer el archivo",
+        "The file could not be read",
+    ),
+    ErrorDefinition::new(
+        "cli",
+        "invalid_argument",
+        400,
+        "Argumento inválido",
+        "Invalid argument",
+    ),
+    ErrorDefinition::new("cli", "invalid_date", 400, "Fecha inválida", "Invalid date"),
+    ErrorDefinition::new(
+        "cli",
+        "missing_value",
+        400,
+        "Falta el valor del argumento",
+        "Missing argument value",
+    ),
+    ErrorDefinition::new(
+        "cli",
+        "unknown_subcommand",
+        400,
+        "Comando desconocido",
+        "Unknown command",
+    ),
+    ErrorDefinition::new(
+        "collection",
+        "not_deleted",
+        400,
+        "La colección no está eliminada",
+        "The collection is not deleted",
+    ),
+    ErrorDefinition::new(
+        "collection",
+        "publication_exists",
+        409,
+        "La publicación ya está en la colección",
+        "The publication is already in the collection",
+    ),
+    ErrorDefinition::new(
+        "comment",
+        "too_short",
+        400,
+        "El comentario es demasiado corto",
+        "The comment is too short",
+    ),
+    ErrorDefinition::new(
+        "contract",
+        "already_exists",
+        409,
+        "La publicación ya tiene un contrato",
+        "The publication already has a contract",
+    ),
+    ErrorDefinition::new(
+        "contract",
+        "minimum_charge",
+        400,
+        "El monto a cobrar es menor al mínimo",
+        "The amount to charge is below the minimum",
+    ),
+    ErrorDefinition::new(
+        "contract",
+        "missing_payment_email",
+        400,
+        "Falta el email de pago",
+        "The payment email is missing",
+    ),
+    ErrorDefinition::new(
+        "contract",
+        "not_active",
+        400,
+        "El contrato no está activo",
+        "The contract is not active",
+    ),
+    ErrorDefinition::new(
+        "contract",
+        "not_approved",
+        400,
+        "El contrato no está aprobado",
+        "The contract is not approved",
+    ),
+    ErrorDefinition::new(
+        "contract",
+        "not_requested",
+        400,
+        "El contrato no fue solicitado",
+        "The contract was not requested",
+    ),
+    ErrorDefinition::new(
+        "contract",
+        "publication_already_has_contract",
+        409,
+        "La publicación ya tiene un contrato",
+        "The publication already has a contract",
+    ),
+    ErrorDefinition::new(
+        "contract",
+        "publication_is_not_published",
+        400,
+        "La publicación no está publicada",
+        "The publication is not published",
+    ),
+    ErrorDefinition::new(
+        "contract_status",
+        "invalid",
+        400,
+        "Estado de contrato inválido",
+        "Invalid contract status",
+    ),
+    ErrorDefinition::new(
+        "credentials",
+        "invalid",
+        401,
+        "Usuario o contraseña incorrectos",
+        "Invalid username or password",
+    ),
+    ErrorDefinition::new(
+        "credentials",
+        "throttled",
+        429,
+        "Demasiados intentos fallidos, esperá unos segundos",
+        "Too many failed attempts, wait a few seconds",
+    ),
+    ErrorDefinition::new(
+        "cursor",
+        "invalid",
+        400,
+        "El cursor de la página es inválido",
+        "The page cursor is invalid",
+    ),
+    ErrorDefinition::new(
+        "data",
+        "read",
+        500,
+        "No se pudo leer el archivo",
+        "The file could not be read",
+    ),
+    ErrorDefinition::new(
+        "donation",
+        "already_charged",
+        400,
+        "La donación ya fue cobrada",
+        "The donation was already charged",
+    ),
+    ErrorDefinition::new(
+        "donation",
+        "already_paid",
+        400,
+        "La donación ya fue pagada",
+        "The donation was already paid",
+    ),
+    ErrorDefinition::new(
+        "donation",
+        "minimum_amount",
+        400,
+        "El monto de la donación es menor al mínimo",
+        "The donation amount is below the minimum",
+    ),
+    ErrorDefinition::new(
+        "donation",
+        "missing_payment_email",
+        400,
+        "Falta el email de pago",
+        "The payment email is missing",
+    ),
+    ErrorDefinition::new(
+        "donation",
+        "not_paid",
+        400,
+        "La donación no fue pagada",
+        "The donation was not paid",
+    ),
+    ErrorDefinition::new(
+        "donation_status",
+        "invalid",
+        400,
+        "Estado de donación inválido",
+        "Invalid donation status",
+    ),
+    ErrorDefinition::new(
+        "donation_status",
+        "not_paid",
+        400,
+        "La donación no fue pagada",
+        "The donation was not paid",
+    ),
+    ErrorDefinition::new(
+        "donation_status",
+        "not_waiting_payment",
+        400,
+        "La donación no está esperando el pago",
+        "The donation is not waiting for payment",
+    ),
+    ErrorDefinition::new(
+        "email",
+        "invalid",
+        400,
+        "El email es inválido",
+        "The email is invalid",
+    ),
+    ErrorDefinition::new(
+        "email",
+        "invalid_regex",
+        500,
+        "No se pudo validar el email",
+        "The email could not be validated",
+    ),
+    ErrorDefinition::new(
+        "email",
+        "taken",
+        409,
+        "El email ya está en uso",
+        "The email is already in use",
+    ),
+    ErrorDefinition::new(
+        "email",
+        "too_long",
+        400,
+        "El email es demasiado largo",
+        "The email is too long",
+    ),
+    ErrorDefinition::new(
+        "email",
+        "too_short",
+        400,
+        "El email es demasiado corto",
+        "The email is too short",
+    ),
+    ErrorDefinition::new(
+        "email",
+        "unchanged",
+        400,
+        "El email es igual al actual",
+        "The email is the same as the current one",
+    ),
+    ErrorDefinition::new(
+        "email_change",
+        "expired",
+        400,
+        "El código para cambiar el email venció, pedí uno nuevo",
+        "The code to change the email expired, request a new one",
+    ),
+    ErrorDefinition::new(
+        "email_change",
+        "invalid_code",
+        400,
+        "El código para cambiar el email es incorrecto",
+        "The code to change the email is incorrect",
+    ),
+    ErrorDefinition::new(
+        "email_change",
+        "not_requested",
+        400,
+        "No se pidió cambiar el email",
+        "No email change was requested",
+    ),
+    ErrorDefinition::new(
+        "external_identity",
+        "already_linked",
+        409,
+        "La cuenta del proveedor ya está vinculada",
+        "The provider account is already linked",
+    ),
+    ErrorDefinition::new(
+        "external_identity",
+        "not_linked",
+        409,
+        "Ya existe un usuario con ese email, ingresá y vinculá la cuenta del proveedor",
+        "A user with that email already exists, log in and link the provider account",
+    ),
+    ErrorDefinition::new(
+        "external_identity",
+        "required",
+        400,
+        "No podés desvincular la única forma de ingresar a tu cuenta",
+        "You can't unlink the only way to log in to your account",
+    ),
+    ErrorDefinition::new(
+        "external_user",
+        "email_required",
+        400,
+        "El proveedor no compartió tu email",
+        "The provider did not share your email",
+    ),
+    ErrorDefinition::new(
+        "external_user",
+        "invalid_subject",
+        502,
+        "El proveedor no identificó al usuario",
+        "The provider did not identify the user",
+    ),
+    ErrorDefinition::new(
+        "favorite",
+        "already_exists",
+        409,
+        "Ya está en tus favoritos",
+        "It is already in your favorites",
+    ),
+    ErrorDefinition::new(
+        "favorite",
+        "does_not_exist",
+        400,
+        "No está en tus favoritos",
+        "It is not in your favorites",
+    ),
+    ErrorDefinition::new(
+        "fields",
+        "unknown",
+        400,
+        "El campo solicitado no existe",
+        "The requested field does not exist",
+    ),
+    ErrorDefinition::new(
+        "file",
+        "invalid",
+        400,
+        "El archivo es inválido",
+        "The file is invalid",
+    ),
+    ErrorDefinition::new(
+        "file",
+        "invalud",
+        400,
+        "El archivo es inválido",
+        "The file is invalid",
+    ),
+    ErrorDefinition::new(
+        "file",
+        "open",
+        500,
+        "No se pudo abrir el archivo",
+        "The file could not be opened",
+    ),
+    ErrorDefinition::new(
+        "file",
+        "write",
+        500,
+        "No se pudo guardar el archivo",
+        "The file could not be saved",
+    ),
+    ErrorDefinition::new(
+        "file",
+        "wrong_extension",
+        400,
+        "La extensión del archivo no es válida",
+        "The file extension is not valid",
+    ),
+    ErrorDefinition::new(
+        "follow",
+        "already_exists",
+        409,
+        "Ya seguís a este autor",
+        "You already follow this author",
+    ),
+    ErrorDefinition::new(
+        "follow",
+        "does_not_exist",
+        400,
+        "No seguís a este autor",
+        "You do not follow this author",
+    ),
+    ErrorDefinition::new(
+        "fullname",
+        "invalid",
+        400,
+        "El nombre es inválido",
+        "The name is invalid",
+    ),
+    ErrorDefinition::new(
+        "gender",
+        "invalid",
+        400,
+        "El género es inválido",
+        "The gender is invalid",
+    ),
+    ErrorDefinition::new("id", "empty", 400, "El ID está vacío", "The ID is empty"),
+    ErrorDefinition::new(
+        "id",
+        "too_short",
+        400,
+        "El ID es demasiado corto",
+        "The ID is too short",
+    ),
+    ErrorDefinition::new(
+        "identity",
+        "invalid",
+        400,
+        "Los datos de identidad son inválidos",
+        "The identity is invalid",
+    ),
+    ErrorDefinition::new(
+        "image",
+        "wrong_extension",
+        400,
+        "La extensión de la imagen no es válida",
+        "The image extension is not valid",
+    ),
+    ErrorDefinition::new(
+        "include",
+        "unknown",
+        400,
+        "La relación solicitada no existe",
+        "The requested relation does not exist",
+    ),
+    ErrorDefinition::new("json", "error", 400, "JSON inválido", "Invalid JSON"),
+    ErrorDefinition::new(
+        "like",
+        "aleady_exists",
+        409,
+        "Ya te gusta esta publicación",
+        "You already like this publication",
+    ),
+    ErrorDefinition::new(
+        "like",
+        "already_exists",
+        409,
+        "Ya te gusta esta publicación",
+        "You already like this publication",
+    ),
+    ErrorDefinition::new(
+        "like",
+        "already_liked",
+        409,
+        "Ya te gusta esta publicación",
+        "You already like this publication",
+    ),
+    ErrorDefinition::new(
+        "like",
+        "not_liked",
+        400,
+        "No te gusta esta publicación",
+        "You do not like this publication",
+    ),
+    ErrorDefinition::new(
+        "login_challenge",
+        "invalid",
+        401,
+        "El inicio de sesión expiró, ingresá de nuevo",
+        "The login expired, log in again",
+    ),
+    ErrorDefinition::new(
+        "mercado_pago_service",
+        "get_external_reference_from_payment",
+        502,
+        "No se pudo obtener el pago de Mercado Pago",
+        "The payment could not be obtained from Mercado Pago",
+    ),
+    ErrorDefinition::new(
+        "mercado_pago_service",
+        "get_payment_link",
+        502,
+        "No se pudo generar el link de pago",
+        "The payment link could not be generated",
+    ),
+    ErrorDefinition::new(
+        "name",
+        "too_short",
+        400,
+        "El nombre es demasiado corto",
+        "The name is too short",
+    ),
+    ErrorDefinition::new(
+        "outbox",
+        "claim",
+        500,
+        "No se pudieron reservar los eventos pendientes",
+        "The pending events could not be claimed",
+    ),
+    ErrorDefinition::new(
+        "outbox",
+        "enqueue",
+        500,
+        "No se pudieron guardar los eventos",
+        "The events could not be saved",
+    ),
+    ErrorDefinition::new(
+        "outbox",
+        "mark_as_published",
+        500,
+        "No se pudieron publicar los eventos",
+        "The events could not be published",
+    ),
+    ErrorDefinition::new(
+        "outbox",
+        "pending",
+        500,
+        "No se pudieron obtener los eventos pendientes",
+        "The pending events could not be obtained",
+    ),
+    ErrorDefinition::new(
+        "password",
+        "hash",
+        500,
+        "No se pudo procesar la contraseña",
+        "The password could not be processed",
+    ),
+    ErrorDefinition::new(
+        "password",
+        "invalid",
+        400,
+        "La contraseña es incorrecta",
+        "The password is incorrect",
+    ),
+    ErrorDefinition::new(
+        "password",
+        "not_hashed",
+        500,
+        "La contraseña no fue procesada",
+        "The password was not processed",
+    ),
+    ErrorDefinition::new(
+        "password",
+        "not_required",
+        400,
+        "La contraseña no es requerida",
+        "The password is not required",
+    ),
+    ErrorDefinition::new(
+        "password",
+        "required",
+        400,
+        "La contraseña es requerida",
+        "The password is required",
+    ),
+    ErrorDefinition::new(
+        "password",
+        "too_short",
+        400,
+        "La contraseña es demasiado corta",
+        "The password is too short",
+    ),
+    ErrorDefinition::new(
+        "password",
+        "unavailable",
+        400,
+        "La cuenta ingresa con un proveedor y no tiene contraseña",
+        "The account logs in with a provider and has no password",
+    ),
+    ErrorDefinition::new(
+        "password_reset",
+        "expired",
+        400,
+        "El enlace para cambiar la contraseña venció, pedí uno nuevo",
+        "The link to reset the password expired, request a new one",
+    ),
+    ErrorDefinition::new(
+        "password_reset",
+        "invalid",
+        400,
+        "El enlace para cambiar la contraseña es inválido o ya fue usado",
+        "The link to reset the password is invalid or was already used",
+    ),
+    ErrorDefinition::new(
+        "passwords",
+        "are_the_same",
+        400,
+        "La nueva contraseña es igual a la anterior",
+        "The new password is the same as the old one",
+    ),
+    ErrorDefinition::new(
+        "plan",
+        "already_exists",
+        409,
+        "El plan ya existe",
+        "The plan already exists",
+    ),
+    ErrorDefinition::new(
+        "plan",
+        "existing_subscriptions",
+        400,
+        "El plan tiene suscripciones",
+        "The plan has subscriptions",
+    ),
+    ErrorDefinition::new(
+        "price",
+        "invalid_range",
+        400,
+        "El precio está fuera del rango permitido",
+        "The price is out of range",
+    ),
+    ErrorDefinition::new(
+        "provider",
+        "not_configured",
+        404,
+        "No se puede ingresar con ese proveedor",
+        "Logging in with that provider is not available",
+    ),
+    ErrorDefinition::new(
+        "publication",
+        "already_draft",
+        400,
+        "La publicación ya es un borrador",
+        "The publication is already a draft",
+    ),
+    ErrorDefinition::new(
+        "publication",
+        "already_has_a_contract",
+        409,
+        "La publicación ya tiene un contrato",
+        "The publication already has a contract",
+    ),
+    ErrorDefinition::new(
+        "publication",
+        "does_not_have_a_contract",
+        400,
+        "La publicación no tiene un contrato",
+        "The publication does not have a contract",
+    ),
+    ErrorDefinition::new(
+        "publication",
+        "does_not_have_pages",
+        400,
+        "La publicación no tiene páginas",
+        "The publication does not have pages",
+    ),
+    ErrorDefinition::new(
+        "publication",
+        "empty_page",
+        400,
+        "La publicación tiene páginas vacías",
+        "The publication has empty pages",
+    ),
+    ErrorDefinition::new(
+        "publication",
+        "maximum_tags_exceeded",
+        400,
+        "La publicación supera el máximo de etiquetas",
+        "The publication exceeds the maximum number of tags",
+    ),
+    ErrorDefinition::new(
+        "publication",
+        "not_a_draft",
+        400,
+        "La publicación no es un borrador",
+        "The publication is not a draft",
+    ),
+    ErrorDefinition::new(
+        "publication",
+        "not_deleted",
+        400,
+        "La publicación no está eliminada",
+        "The publication is not deleted",
+    ),
+    ErrorDefinition::new(
+        "publication",
+        "not_published",
+        400,
+        "La publicación no está publicada",
+        "The publication is not published",
+    ),
+    ErrorDefinition::new(
+        "publication",
+        "not_waiting_approval",
+        400,
+        "La publicación no está esperando aprobación",
+        "The publication is not waiting for approval",
+    ),
+    ErrorDefinition::new(
+        "publication_status",
+        "invalid",
+        400,
+        "Estado de publicación inválido",
+        "Invalid publication status",
+    ),
+    ErrorDefinition::new(
+        "reader",
+        "not_subscribed",
+        403,
+        "Necesitás una suscripción para leer esta publicación",
+        "You need a subscription to read this publication",
+    ),
+    ErrorDefinition::new(
+        "replay",
+        "missing_handlers",
+        400,
+        "Faltan los handlers a reprocesar",
+        "The handlers to replay are missing",
+    ),
+    ErrorDefinition::new(
+        "replay",
+        "unknown_handler",
+        400,
+        "Handler desconocido",
+        "Unknown handler",
+    ),
+    ErrorDefinition::new(
+        "request",
+        "rate_limited",
+        429,
+        "Demasiadas solicitudes, probá más tarde",
+        "Too many requests, try again later",
+    ),
+    ErrorDefinition::new(
+        "response",
+        "deserialize",
+        502,
+        "Respuesta inválida del servicio de pagos",
+        "Invalid response from the payment service",
+    ),
+    ErrorDefinition::new(
+        "refresh_token",
+        "invalid",
+        401,
+        "La sesión expiró, volvé a iniciar sesión",
+        "The session expired, log in again",
+    ),
+    ErrorDefinition::new(
+        "refresh_token",
+        "reused",
+        401,
+        "La sesión se cerró por seguridad, volvé a iniciar sesión",
+        "The session was closed for security reasons, log in again",
+    ),
+    ErrorDefinition::new(
+        "review",
+        "already_exists",
+        409,
+        "Ya reseñaste esta publicación",
+        "You already reviewed this publication",
+    ),
+    ErrorDefinition::new(
+        "review",
+        "existing",
+        409,
+        "Ya reseñaste esta publicación",
+        "You already reviewed this publication",
+    ),
+    ErrorDefinition::new(
+        "review",
+        "not_reviewed",
+        400,
+        "No reseñaste esta publicación",
+        "You did not review this publication",
+    ),
+    ErrorDefinition::new(
+        "role",
+        "already_exists",
+        409,
+        "El rol ya existe",
+        "The role already exists",
+    ),
+    ErrorDefinition::new(
+        "role",
+        "existing_users_assigned_to_role",
+        400,
+        "Hay usuarios con este rol",
+        "There are users with this role",
+    ),
+    ErrorDefinition::new(
+        "role",
+        "is_default",
+        400,
+        "El rol por defecto no se puede eliminar",
+        "The default role cannot be deleted",
+    ),
+    ErrorDefinition::new(
+        "role",
+        "not_deleted",
+        400,
+        "El rol no está eliminado",
+        "The role is not deleted",
+    ),
+    ErrorDefinition::new(
+        "s3",
+        "put_request",
+        502,
+        "No se pudo subir el archivo",
+        "The file could not be uploaded",
+    ),
+    ErrorDefinition::new(
+        "stars",
+        "invalid_range",
+        400,
+        "La calificación está fuera del rango permitido",
+        "The rating is out of range",
+    ),
+    ErrorDefinition::new(
+        "statistics",
+        "publication_has_low_views",
+        400,
+        "La publicación tiene pocas vistas",
+        "The publication has few views",
+    ),
+    ErrorDefinition::new(
+        "statistics",
+        "stars_are_not_positive",
+        400,
+        "La calificación debe ser positiva",
+        "The rating must be positive",
+    ),
+    ErrorDefinition::new(
+        "subscription",
+        "already_exists",
+        409,
+        "Ya tenés una suscripción",
+        "You already have a subscription",
+    ),
+    ErrorDefinition::new(
+        "subscription",
+        "already_paid",
+        400,
+        "La suscripción ya fue pagada",
+        "The subscription was already paid",
+    ),
+    ErrorDefinition::new(
+        "subscription",
+        "not_active",
+        400,
+        "La suscripción no está activa",
+        "The subscription is not active",
+    ),
+    ErrorDefinition::new(
+        "subscription",
+        "not_waiting_payment",
+        400,
+        "La suscripción no está esperando el pago",
+        "The subscription is not waiting for payment",
+    ),
+    ErrorDefinition::new(
+        "subscription_status",
+        "invalid",
+        400,
+        "Estado de suscripción inválido",
+        "Invalid subscription status",
+    ),
+    ErrorDefinition::new(
+        "subscription_total",
+        "zero",
+        400,
+        "No hay ingresos por suscripciones",
+        "There is no subscription income",
+    ),
+    ErrorDefinition::new(
+        "summary",
+        "already_paid",
+        400,
+        "El resumen ya fue pagado",
+        "The summary was already paid",
+    ),
+    ErrorDefinition::new(
+        "summary",
+        "date_should_be_the_last",
+        400,
+        "El resumen debe ser el último",
+        "The summary must be the last one",
+    ),
+    ErrorDefinition::new(
+        "summary",
+        "invalid_amount",
+        400,
+        "Monto del resumen inválido",
+        "Invalid summary amount",
+    ),
+    ErrorDefinition::new(
+        "summary",
+        "invalid_date_range",
+        400,
+        "Rango de fechas del resumen inválido",
+        "Invalid summary date range",
+    ),
+    ErrorDefinition::new(
+        "summary",
+        "invalid_total",
+        400,
+        "Total del resumen inválido",
+        "Invalid summary total",
+    ),
+    ErrorDefinition::new(
+        "synopsis",
+        "too_short",
+        400,
+        "La sinopsis es demasiado corta",
+        "The synopsis is too short",
+    ),
+    ErrorDefinition::new(
+        "tag",
+        "empty_name",
+        400,
+        "La etiqueta está vacía",
+        "The tag is empty",
+    ),
+    ErrorDefinition::new(
+        "token",
+        "decode",
+        401,
+        "El token es inválido",
+        "The token is invalid",
+    ),
+    ErrorDefinition::new(
+        "token",
+        "encode",
+        500,
+        "No se pudo generar el token",
+        "The token could not be generated",
+    ),
+    ErrorDefinition::new(
+        "two_factor",
+        "already_enabled",
+        409,
+        "La autenticación en dos pasos ya está activada",
+        "Two-factor authentication is already enabled",
+    ),
+    ErrorDefinition::new(
+        "two_factor",
+        "invalid_code",
+        401,
+        "El código es incorrecto",
+        "The code is incorrect",
+    ),
+    ErrorDefinition::new(
+        "two_factor",
+        "not_enabled",
+        400,
+        "La autenticación en dos pasos no está activada",
+        "Two-factor authentication is not enabled",
+    ),
+    ErrorDefinition::new(
+        "two_factor",
+        "not_enrolled",
+        400,
+        "Primero tenés que agregar la cuenta a tu aplicación de autenticación",
+        "You have to add the account to your authenticator app first",
+    ),
+    ErrorDefinition::new(
+        "two_factor",
+        "required",
+        403,
+        "Tu rol requiere autenticación en dos pasos",
+        "Your role requires two-factor authentication",
+    ),
+    ErrorDefinition::new(
+        "upcaster",
+        "already_registered",
+        500,
+        "El upcaster ya está registrado",
+        "The upcaster is already registered",
+    ),
+    ErrorDefinition::new(
+        "upcaster",
+        "upcast",
+        500,
+        "No se pudo migrar el evento",
+        "The event could not be migrated",
+    ),
+    ErrorDefinition::new(
+        "user",
+        "already_subscribed",
+        409,
+        "Ya tenés una suscripción",
+        "You already have a subscription",
+    ),
+    ErrorDefinition::new(
+        "user",
+        "already_validated",
+        400,
+        "El usuario ya fue validado",
+        "The user was already validated",
+    ),
+    ErrorDefinition::new(
+        "user",
+        "anonymized",
+        400,
+        "El usuario fue anonimizado y no se puede restaurar",
+        "The user was anonymized and cannot be restored",
+    ),
+    ErrorDefinition::new(
+        "user",
+        "invalid_code",
+        400,
+        "El código de validación es incorrecto",
+        "The validation code is incorrect",
+    ),
+    ErrorDefinition::new(
+        "user",
+        "locked_out",
+        423,
+        "Tu cuenta está bloqueada temporalmente por intentos fallidos",
+        "Your account is temporarily locked after failed login attempts",
+    ),
+    ErrorDefinition::new(
+        "user",
+        "not_active",
+        403,
+        "El usuario no está activo",
+        "The user is not active",
+    ),
+    ErrorDefinition::new(
+        "user",
+        "not_deleted",
+        400,
+        "El usuario no está eliminado",
+        "The user is not deleted",
+    ),
+    ErrorDefinition::new(
+        "user",
+        "not_locked_out",
+        400,
+        "El usuario no está bloqueado",
+        "The user is not locked out",
+    ),
+    ErrorDefinition::new(
+        "user",
+        "not_validated",
+        403,
+        "El usuario no fue validado",
+        "The user was not validated",
+    ),
+    ErrorDefinition::new(
+        "username",
+        "cooldown",
+        400,
+        "Todavía no podés volver a cambiar el nombre de usuario",
+        "The username can't be changed again yet",
+    ),
+    ErrorDefinition::new(
+        "username",
+        "invalid_characters",
+        400,
+        "El nombre de usuario tiene caracteres inválidos",
+        "The username has invalid characters",
+    ),
+    ErrorDefinition::new(
+        "username",
+        "invalid_regex",
+        500,
+        "No se pudo validar el nombre de usuario",
+        "The username could not be validated",
+    ),
+    ErrorDefinition::new(
+        "username",
+        "not_available",
+        409,
+        "No se pudo generar un nombre de usuario disponible",
+        "An available username could not be generated",
+    ),
+    ErrorDefinition::new(
+        "username",
+        "taken",
+        409,
+        "El nombre de usuario ya está en uso",
+        "The username is already in use",
+    ),
+    ErrorDefinition::new(
+        "username",
+        "too_long",
+        400,
+        "El nombre de usuario es demasiado largo",
+        "The username is too long",
+    ),
+    ErrorDefinition::new(
+        "username",
+        "too_short",
+        400,
+        "El nombre de usuario es demasiado corto",
+        "The username is too short",
+    ),
+    ErrorDefinition::new(
+        "username",
+        "unchanged",
+        400,
+        "El nombre de usuario es igual al actual",
+        "The username is the same as the current one",
+    ),
+];
+
+/// Returns the definition of the given code, looking for the path first and then for a code
+/// shared by every path.
+pub fn find_definition(path: &str, code: &str) -> Option<&'static ErrorDefinition> {
+    CATALOG
+        .iter()
+        .find(|def| def.path == path && def.code == code)
+        .or_else(|| {
+            CATALOG
+                .iter()
+                .find(|def| def.path == ANY_PATH && def.code == code)
+        })
+}
+
+impl Error {
+    pub fn definition(&self) -> Option<&'static ErrorDefinition> {
+        find_definition(self.path(), self.code())
+    }
+
+    /// Status set explicitly, or the one registered in the catalog.
+    pub fn public_status(&self) -> Option<u32> {
+        self.status()
+            .or_else(|| self.definition().map(|def| def.status()))
+    }
+
+    /// Message of the catalog in the given language, or the one set explicitly if the code is
+    /// not registered.
+    pub fn localized_message(&self, language: Language) -> Option<String> {
+        self.definition()
+            .map(|def| def.message(language).to_owned())
+            .or_else(|| self.message().cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashSet;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use regex::Regex;
+
+    fn source_files(dir: &Path, files: &mut Vec<PathBuf>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let name = path.file_name().unwrap().to_string_lossy();
+
+            if path.is_dir() {
+                if !["target", "web", "scripts", "node_modules"].contains(&name.as_ref())
+                    && !name.starts_with('.')
+                {
+                    source_files(&path, files);
+                }
+            } else if name.ends_with(".rs") {
+                files.push(path);
+            }
+        }
+    }
+
+    #[test]
+    fn every_error_is_registered() {
+        let workspace = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+        let mut files = Vec::new();
+        source_files(workspace, &mut files);
+        assert!(!files.is_empty());
+
+        let literal = Regex::new(r#"Error::new\(\s*"([^"]*)"\s*,\s*"([^"]*)""#).unwrap();
+        let mut missing = HashSet::new();
+
+        for file in files.iter() {
+            let content = fs::read_to_string(file).unwrap();
+            // Errors created by tests don't reach users.
+            let content = content.split("#[cfg(test)]").next().unwrap();
+
+            for captures in literal.captures_iter(content) {
+                if find_definition(&captures[1], &captures[2]).is_none() {
+                    missing.insert(format!(
+                        "{}.{} ({})",
+                        &captures[1],
+                        &captures[2],
+                        file.display()
+                    ));
+                }
+            }
+        }
+
+        assert!(missing.is_empty(), "Missing errors: {:#?}", missing);
+    }
+
+    #[test]
+    fn no_duplicated_definitions() {
+        let mut keys = HashSet::new();
+        for def in CATALOG.iter() {
+            assert!(
+                keys.insert((def.path, def.code)),
+                "{}.{}",
+                def.path,
+                def.code
+            );
+            assert!(!def.es.is_empty() && !def.en.is_empty());
+        }
+    }
+
+    #[test]
+    fn status_and_message() {
+        let err = Error::new("publication", "not_published");
+        assert_eq!(err.public_status(), Some(400));
+        assert_eq!(
+            err.localized_message(Language::En).unwrap(),
+            "The publication is not published"
+        );
+        assert_eq!(
+            err.localized_message(Language::Es).unwrap(),
+            "La publicación no está publicada"
+        );
+
+        let err = Error::not_found("publication");
+        assert_eq!(err.public_status(), Some(404));
+        assert_eq!(err.localized_message(Language::En).unwrap(), "Not found");
+
+        let err = Error::new("unknown", "unknown").set_message("message");
+        assert!(err.definition().is_none());
+        assert_eq!(err.public_status(), None);
+        assert_eq!(err.localized_message(Language::En).unwrap(), "message");
+    }
+}
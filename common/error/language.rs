@@ -0,0 +1,107 @@
+use std::future::Future;
+
+tokio::task_local! {
+    static CURRENT: Language;
+}
+
+/// Language of the messages shown to users.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Language {
+    Es,
+    En,
+}
+
+impl Language {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::Es => "es",
+            Language::En => "en",
+        }
+    }
+
+    /// Supported language of a tag like `es` or `en-US`.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.trim().split('-').next().unwrap_or("");
+        match primary.to_lowercase().as_str() {
+            "es" => Some(Language::Es),
+            "en" => Some(Language::En),
+            _ => None,
+        }
+    }
+
+    /// Supported language with the highest weight in an `Accept-Language` header, or the default
+    /// one if none is supported.
+    pub fn from_accept_language(header: &str) -> Self {
+        let mut selected: Option<(Language, f32)> = None;
+
+        for range in header.split(',') {
+            let mut parts = range.split(';');
+            let language = match parts.next().and_then(Language::from_tag) {
+                Some(language) => language,
+                None => continue,
+            };
+
+            let weight = parts
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .filter_map(|q| q.trim().parse::<f32>().ok())
+                .next()
+                .unwrap_or(1.0);
+
+            if weight > 0.0 && selected.map_or(true, |(_, max)| weight > max) {
+                selected = Some((language, weight));
+            }
+        }
+
+        selected.map(|(language, _)| language).unwrap_or_default()
+    }
+
+    /// Language of the current scope, or the default one outside of a scope.
+    pub fn current() -> Self {
+        CURRENT.try_with(|language| *language).unwrap_or_default()
+    }
+
+    /// Runs `f` with this language as the current one.
+    pub async fn scope<F: Future>(self, f: F) -> F::Output {
+        CURRENT.scope(self, f).await
+    }
+}
+
+impl Default for Language {
+    fn default() -> Self {
+        Language::Es
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accept_language() {
+        assert_eq!(Language::from_accept_language("en"), Language::En);
+        assert_eq!(
+            Language::from_accept_language("en-US,en;q=0.9"),
+            Language::En
+        );
+        assert_eq!(
+            Language::from_accept_language("fr-FR, en;q=0.5, es-AR;q=0.8"),
+            Language::Es
+        );
+        assert_eq!(
+            Language::from_accept_language("es;q=0, en;q=0.1"),
+            Language::En
+        );
+        assert_eq!(Language::from_accept_language("fr, de"), Language::Es);
+        assert_eq!(Language::from_accept_language(""), Language::Es);
+        assert_eq!(Language::from_accept_language("*"), Language::Es);
+    }
+
+    #[tokio::test]
+    async fn current_language() {
+        assert_eq!(Language::current(), Language::Es);
+        assert_eq!(
+            Language::En.scope(async { Language::current() }).await,
+            Language::En
+        );
+    }
+}
@@ -1,11 +1,23 @@
+mod dead_letter;
 mod handler;
+mod metadata;
+mod outbox;
 mod publisher;
+mod replayer;
 mod repository;
+mod retry_policy;
 mod subscriber;
+mod upcaster;
+pub use dead_letter::*;
 pub use handler::*;
+pub use metadata::*;
+pub use outbox::*;
 pub use publisher::*;
+pub use replayer::*;
 pub use repository::*;
+pub use retry_policy::*;
 pub use subscriber::*;
+pub use upcaster::*;
 
 use chrono::{DateTime, Utc};
 use serde_json::Value;
@@ -16,13 +28,18 @@ use crate::result::Result;
 
 pub type EventId = StringId;
 
+/// Version of the payload schema of events created without an explicit version.
+pub const INITIAL_EVENT_VERSION: u32 = 1;
+
 #[derive(Debug, Clone)]
 pub struct Event {
     id: EventId,
     topic: String,
     code: String,
+    version: u32,
     timestamp: DateTime<Utc>,
     payload: Value,
+    metadata: EventMetadata,
 }
 
 impl Event {
@@ -31,8 +48,10 @@ impl Event {
             id: EventId::new(Uuid::new_v4().to_string()).unwrap(),
             topic: topic.into(),
             code: code.into(),
+            version: INITIAL_EVENT_VERSION,
             timestamp: Utc::now(),
             payload,
+            metadata: EventMetadata::current(),
         }
     }
 
@@ -42,16 +61,33 @@ impl Event {
         code: String,
         timestamp: DateTime<Utc>,
         payload: Value,
+        version: u32,
     ) -> Self {
         Event {
             id,
             topic,
             code,
+            version,
             timestamp,
             payload,
+            metadata: EventMetadata::default(),
         }
     }
 
+    /// Sets the schema version of the payload. It has to be increased, together with an
+    /// `Upcaster` for the previous version, every time the payload of a topic changes.
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Sets the correlation, causation and actor IDs. Events created with `new` take them from
+    /// the current `EventMetadata` scope.
+    pub fn with_metadata(mut self, metadata: EventMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
     pub fn id(&self) -> &EventId {
         &self.id
     }
@@ -64,6 +100,10 @@ impl Event {
         &self.code
     }
 
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
     pub fn timestamp(&self) -> &DateTime<Utc> {
         &self.timestamp
     }
@@ -71,6 +111,25 @@ impl Event {
     pub fn payload(&self) -> Value {
         self.payload.clone()
     }
+
+    pub fn metadata(&self) -> &EventMetadata {
+        &self.metadata
+    }
+
+    /// ID shared by every event caused, directly or not, by the same request.
+    pub fn correlation_id(&self) -> Option<&str> {
+        self.metadata.correlation_id()
+    }
+
+    /// ID of the request or event that caused this event.
+    pub fn causation_id(&self) -> Option<&str> {
+        self.metadata.causation_id()
+    }
+
+    /// ID of the user that performed the action.
+    pub fn actor_id(&self) -> Option<&str> {
+        self.metadata.actor_id()
+    }
 }
 
 pub trait ToEvent {
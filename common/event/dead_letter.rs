@@ -0,0 +1,130 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::error::Error;
+use crate::event::Event;
+use crate::model::StringId;
+use crate::result::Result;
+
+pub type DeadLetterId = StringId;
+
+/// DeadLetter keeps an event that a handler could not process after exhausting its retries,
+/// together with the handler topic and the chain of errors, so it can be replayed later.
+#[derive(Debug, Clone)]
+pub struct DeadLetter {
+    id: DeadLetterId,
+    event: Event,
+    handler_topic: String,
+    errors: Vec<String>,
+    attempts: u32,
+    failed_at: DateTime<Utc>,
+}
+
+impl DeadLetter {
+    pub fn new<S: Into<String>>(
+        event: Event,
+        handler_topic: S,
+        err: &Error,
+        attempts: u32,
+    ) -> Result<Self> {
+        let mut errors = Vec::new();
+        let mut cause = Some(err);
+        while let Some(err) = cause {
+            errors.push(match err.message() {
+                Some(message) => format!("{}.{}: {}", err.path(), err.code(), message),
+                None => format!("{}.{}", err.path(), err.code()),
+            });
+            cause = err.cause();
+        }
+
+        Ok(DeadLetter {
+            id: DeadLetterId::new(Uuid::new_v4().to_string())?,
+            event,
+            handler_topic: handler_topic.into(),
+            errors,
+            attempts,
+            failed_at: Utc::now(),
+        })
+    }
+
+    pub fn build(
+        id: DeadLetterId,
+        event: Event,
+        handler_topic: String,
+        errors: Vec<String>,
+        attempts: u32,
+        failed_at: DateTime<Utc>,
+    ) -> Self {
+        DeadLetter {
+            id,
+            event,
+            handler_topic,
+            errors,
+            attempts,
+            failed_at,
+        }
+    }
+
+    pub fn id(&self) -> &DeadLetterId {
+        &self.id
+    }
+
+    pub fn event(&self) -> &Event {
+        &self.event
+    }
+
+    pub fn handler_topic(&self) -> &str {
+        &self.handler_topic
+    }
+
+    pub fn errors(&self) -> &[String] {
+        &self.errors
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn failed_at(&self) -> &DateTime<Utc> {
+        &self.failed_at
+    }
+}
+
+#[async_trait]
+pub trait DeadLetterRepository: Sync + Send {
+    async fn find_all(&self) -> Result<Vec<DeadLetter>>;
+    async fn find_by_id(&self, id: &DeadLetterId) -> Result<DeadLetter>;
+
+    async fn save(&self, dead_letter: &DeadLetter) -> Result<()>;
+
+    async fn delete(&self, id: &DeadLetterId) -> Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    fn error_chain() {
+        let err = Error::internal("event_publisher", "handler_error").wrap(
+            Error::new("notification", "create")
+                .wrap(Error::new("user", "not_found").set_message("User not found")),
+        );
+        let dead_letter =
+            DeadLetter::new(Event::new("user", "registered", json!({})), ".*", &err, 4).unwrap();
+
+        assert_eq!(dead_letter.handler_topic(), ".*");
+        assert_eq!(dead_letter.attempts(), 4);
+        assert_eq!(
+            dead_letter.errors(),
+            &[
+                "event_publisher.handler_error".to_owned(),
+                "notification.create".to_owned(),
+                "user.not_found: User not found".to_owned(),
+            ]
+        );
+    }
+}
@@ -1,11 +1,16 @@
 use async_trait::async_trait;
 
-use crate::event::Event;
+use crate::event::{Event, RetryPolicy};
 use crate::result::Result;
 
 #[async_trait]
 pub trait EventHandler: Sync + Send {
     fn topic(&self) -> &str;
 
+    /// Retry policy for this handler. The event bus policy is used when it's not defined.
+    fn retry_policy(&self) -> Option<RetryPolicy> {
+        None
+    }
+
     async fn handle(&mut self, event: &Event) -> Result<bool>;
 }
@@ -0,0 +1,140 @@
+use std::cell::RefCell;
+use std::future::Future;
+
+use crate::event::Event;
+
+tokio::task_local! {
+    static CURRENT: RefCell<EventMetadata>;
+}
+
+/// EventMetadata links an event with the request or event that caused it and with the user that
+/// performed the action. Events created inside a `scope` take the metadata of the scope, so every
+/// event published while handling a request (or another event) shares its correlation ID.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventMetadata {
+    correlation_id: Option<String>,
+    causation_id: Option<String>,
+    actor_id: Option<String>,
+}
+
+impl EventMetadata {
+    pub fn new(
+        correlation_id: Option<String>,
+        causation_id: Option<String>,
+        actor_id: Option<String>,
+    ) -> Self {
+        EventMetadata {
+            correlation_id,
+            causation_id,
+            actor_id,
+        }
+    }
+
+    /// Metadata of a request: the request ID is both the correlation and the causation.
+    pub fn from_request<S: Into<String>>(request_id: S) -> Self {
+        let request_id = request_id.into();
+        EventMetadata {
+            correlation_id: Some(request_id.clone()),
+            causation_id: Some(request_id),
+            actor_id: None,
+        }
+    }
+
+    /// Metadata of the events published while handling `event`. They keep the correlation ID and
+    /// the actor of `event`, and `event` becomes their cause. Events without correlation ID start
+    /// a new chain.
+    pub fn caused_by(event: &Event) -> Self {
+        EventMetadata {
+            correlation_id: Some(
+                event
+                    .correlation_id()
+                    .unwrap_or_else(|| event.id().value())
+                    .to_owned(),
+            ),
+            causation_id: Some(event.id().value().to_owned()),
+            actor_id: event.actor_id().map(ToOwned::to_owned),
+        }
+    }
+
+    /// Metadata of the current scope, or empty metadata outside of a scope.
+    pub fn current() -> Self {
+        CURRENT
+            .try_with(|metadata| metadata.borrow().clone())
+            .unwrap_or_default()
+    }
+
+    /// Runs `f` with this metadata as the current one.
+    pub async fn scope<F: Future>(self, f: F) -> F::Output {
+        CURRENT.scope(RefCell::new(self), f).await
+    }
+
+    /// Sets the actor of the current scope, once the user performing the request is known. It
+    /// has no effect outside of a scope.
+    pub fn set_current_actor<S: Into<String>>(actor_id: S) {
+        let actor_id = actor_id.into();
+        if CURRENT
+            .try_with(|metadata| metadata.borrow_mut().actor_id = Some(actor_id))
+            .is_err()
+        {}
+    }
+
+    pub fn correlation_id(&self) -> Option<&str> {
+        self.correlation_id.as_deref()
+    }
+
+    pub fn causation_id(&self) -> Option<&str> {
+        self.causation_id.as_deref()
+    }
+
+    pub fn actor_id(&self) -> Option<&str> {
+        self.actor_id.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn empty_outside_of_scope() {
+        assert_eq!(EventMetadata::current(), EventMetadata::default());
+
+        EventMetadata::set_current_actor("user-1");
+        let event = Event::new("topic", "code", json!({}));
+        assert!(event.correlation_id().is_none());
+        assert!(event.causation_id().is_none());
+        assert!(event.actor_id().is_none());
+    }
+
+    #[tokio::test]
+    async fn events_take_metadata_from_scope() {
+        let event = EventMetadata::from_request("request-1")
+            .scope(async {
+                EventMetadata::set_current_actor("user-1");
+                Event::new("topic", "code", json!({}))
+            })
+            .await;
+
+        assert_eq!(event.correlation_id(), Some("request-1"));
+        assert_eq!(event.causation_id(), Some("request-1"));
+        assert_eq!(event.actor_id(), Some("user-1"));
+    }
+
+    #[tokio::test]
+    async fn caused_by_event() {
+        let cause = Event::new("topic", "cause", json!({}));
+        let effect = EventMetadata::caused_by(&cause)
+            .scope(async { Event::new("topic", "effect", json!({})) })
+            .await;
+        assert_eq!(effect.correlation_id(), Some(cause.id().value()));
+        assert_eq!(effect.causation_id(), Some(cause.id().value()));
+
+        let next = EventMetadata::caused_by(&effect)
+            .scope(async { Event::new("topic", "next", json!({})) })
+            .await;
+        assert_eq!(next.correlation_id(), Some(cause.id().value()));
+        assert_eq!(next.causation_id(), Some(effect.id().value()));
+    }
+}
@@ -0,0 +1,22 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::event::{Event, EventId};
+use crate::result::Result;
+
+/// Outbox stores events that were recorded by aggregates until they are relayed to the event
+/// bus. Events are marked as published once every subscribed handler has received them.
+#[async_trait]
+pub trait Outbox: Sync + Send {
+    async fn enqueue(&self, events: &[Event]) -> Result<()>;
+
+    async fn pending(&self, limit: usize) -> Result<Vec<Event>>;
+
+    /// Claims up to `limit` pending events for `lease`. Events claimed by a relay are skipped by
+    /// the others until the lease expires, so a relay that stops before marking them as
+    /// published doesn't keep them from being published.
+    async fn claim(&self, limit: usize, lease: Duration) -> Result<Vec<Event>>;
+
+    async fn mark_as_published(&self, ids: &[EventId]) -> Result<()>;
+}
@@ -9,6 +9,10 @@ pub struct PublicationResult {
     pub published_events: u32,
     pub ok_handlers: u32,
     pub err_handlers: u32,
+    /// Events retried by at least one handler.
+    pub retried_events: u32,
+    /// Dead letters stored, one for each handler that failed after all retries.
+    pub dead_lettered_events: u32,
 }
 
 impl PublicationResult {
@@ -24,6 +28,14 @@ impl PublicationResult {
         self.err_handlers
     }
 
+    pub fn retried_events(&self) -> u32 {
+        self.retried_events
+    }
+
+    pub fn dead_lettered_events(&self) -> u32 {
+        self.dead_lettered_events
+    }
+
     pub fn activated_handlers(&self) -> u32 {
         self.ok_handlers + self.err_handlers
     }
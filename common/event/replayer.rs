@@ -0,0 +1,292 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::event::{EventHandler, EventMetadata, EventOrder, EventRepository};
+use crate::result::Result;
+
+tokio::task_local! {
+    static REPLAYING: bool;
+}
+
+/// Whether the current scope is handling a replayed event. Events recorded by aggregates while
+/// replaying were already published the first time the event was handled, so they must not be
+/// published again.
+pub fn is_replaying() -> bool {
+    REPLAYING.try_with(|replaying| *replaying).unwrap_or(false)
+}
+
+/// ReplayOptions filters the stored events to replay. In dry-run mode handlers are not executed,
+/// the report only counts which handlers would have been activated.
+#[derive(Debug, Default, Clone)]
+pub struct ReplayOptions {
+    pub topic: Option<String>,
+    pub code: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ReplayReport {
+    pub dry_run: bool,
+    pub events: u32,
+    pub activated_handlers: u32,
+    pub ok_handlers: u32,
+    pub err_handlers: u32,
+    pub errors: Vec<String>,
+}
+
+/// EventReplayer reads stored events in batches and passes them, in order, through a set of
+/// handlers to rebuild the state they maintain.
+pub struct EventReplayer {
+    event_repo: Arc<dyn EventRepository>,
+    batch_size: usize,
+    progress_interval: u32,
+}
+
+impl EventReplayer {
+    pub fn new(event_repo: Arc<dyn EventRepository>) -> Self {
+        EventReplayer {
+            event_repo,
+            batch_size: 500,
+            progress_interval: 100,
+        }
+    }
+
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Number of events between progress reports.
+    pub fn progress_interval(mut self, progress_interval: u32) -> Self {
+        self.progress_interval = progress_interval;
+        self
+    }
+
+    /// Replays the events matching `options`. `progress` is called with the partial report
+    /// every `progress_interval` events and once at the end. Handler errors don't stop the
+    /// replay, they are collected in the report.
+    pub async fn replay<P>(
+        &self,
+        handlers: &mut [Box<dyn EventHandler>],
+        options: &ReplayOptions,
+        mut progress: P,
+    ) -> Result<ReplayReport>
+    where
+        P: FnMut(&ReplayReport) + Send,
+    {
+        let mut regexes = Vec::new();
+        for handler in handlers.iter() {
+            regexes.push(Regex::new(handler.topic()).map_err(|err| {
+                Error::internal("event_replayer", "invalid_topic_regex").wrap_raw(err)
+            })?);
+        }
+
+        let mut report = ReplayReport {
+            dry_run: options.dry_run,
+            ..ReplayReport::default()
+        };
+        let mut after_id = None;
+
+        loop {
+            let page = self
+                .event_repo
+                .search(
+                    after_id.as_ref(),
+                    options.topic.as_ref(),
+                    options.code.as_ref(),
+                    None,
+                    options.from.as_ref(),
+                    options.to.as_ref(),
+                    Some(self.batch_size),
+                    EventOrder::Asc,
+                )
+                .await?;
+
+            for event in page.events().iter() {
+                for (handler, regex) in handlers.iter_mut().zip(regexes.iter()) {
+                    if !regex.is_match(event.topic()) {
+                        continue;
+                    }
+
+                    report.activated_handlers += 1;
+
+                    if options.dry_run {
+                        continue;
+                    }
+
+                    // Events published by the handler are caused by the replayed event.
+                    match REPLAYING
+                        .scope(
+                            true,
+                            EventMetadata::caused_by(event).scope(handler.handle(event)),
+                        )
+                        .await
+                    {
+                        Ok(_) => report.ok_handlers += 1,
+                        Err(err) => {
+                            report.err_handlers += 1;
+                            report.errors.push(format!(
+                                "{} ({}.{}) -> {}: {}",
+                                event.id(),
+                                event.topic(),
+                                event.code(),
+                                handler.topic(),
+                                err
+                            ));
+                        }
+                    }
+                }
+
+                report.events += 1;
+
+                if self.progress_interval > 0 && report.events % self.progress_interval == 0 {
+                    progress(&report);
+                }
+            }
+
+            match page.next_cursor() {
+                Some(next_cursor) => after_id = Some(next_cursor.clone()),
+                None => break,
+            }
+        }
+
+        progress(&report);
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_trait::async_trait;
+    use serde_json::json;
+
+    use crate::event::Event;
+    use crate::infrastructure::event::InMemEventRepository;
+    use crate::mocks::Counter;
+
+    struct CounterHandler {
+        topic: String,
+        counter: Arc<Counter>,
+    }
+
+    #[async_trait]
+    impl EventHandler for CounterHandler {
+        fn topic(&self) -> &str {
+            &self.topic
+        }
+
+        async fn handle(&mut self, event: &Event) -> Result<bool> {
+            if event.code() == "failed" {
+                return Err(Error::new("counter_handler", "failed"));
+            }
+
+            self.counter.inc(event.code());
+            if is_replaying() {
+                self.counter.inc("replaying");
+            }
+
+            Ok(true)
+        }
+    }
+
+    async fn setup() -> (EventReplayer, Vec<Box<dyn EventHandler>>, Arc<Counter>) {
+        let event_repo = Arc::new(InMemEventRepository::new());
+        for (topic, code) in [
+            ("publication", "published"),
+            ("publication", "liked"),
+            ("publication", "failed"),
+            ("user", "registered"),
+        ]
+        .iter()
+        {
+            event_repo
+                .save(&Event::new(*topic, *code, json!({})))
+                .await
+                .unwrap();
+        }
+
+        let counter = Arc::new(Counter::new());
+        let handlers: Vec<Box<dyn EventHandler>> = vec![
+            Box::new(CounterHandler {
+                topic: "publication".to_owned(),
+                counter: counter.clone(),
+            }),
+            Box::new(CounterHandler {
+                topic: ".*".to_owned(),
+                counter: counter.clone(),
+            }),
+        ];
+
+        (
+            EventReplayer::new(event_repo)
+                .batch_size(3)
+                .progress_interval(2),
+            handlers,
+            counter,
+        )
+    }
+
+    #[tokio::test]
+    async fn replay() {
+        let (replayer, mut handlers, counter) = setup().await;
+
+        let mut progress_reports = 0;
+        let report = replayer
+            .replay(&mut handlers, &ReplayOptions::default(), |_| {
+                progress_reports += 1
+            })
+            .await
+            .unwrap();
+
+        assert!(!report.dry_run);
+        assert_eq!(report.events, 4);
+        assert_eq!(report.activated_handlers, 7);
+        assert_eq!(report.ok_handlers, 5);
+        assert_eq!(report.err_handlers, 2);
+        assert_eq!(report.errors.len(), 2);
+        assert_eq!(progress_reports, 3);
+
+        assert_eq!(counter.count("published"), 2);
+        assert_eq!(counter.count("liked"), 2);
+        assert_eq!(counter.count("registered"), 1);
+
+        // Handlers know they are handling replayed events.
+        assert_eq!(counter.count("replaying"), 5);
+        assert!(!is_replaying());
+    }
+
+    #[tokio::test]
+    async fn dry_run() {
+        let (replayer, mut handlers, counter) = setup().await;
+
+        let report = replayer
+            .replay(
+                &mut handlers,
+                &ReplayOptions {
+                    dry_run: true,
+                    ..ReplayOptions::default()
+                },
+                |_| {},
+            )
+            .await
+            .unwrap();
+
+        assert!(report.dry_run);
+        assert_eq!(report.events, 4);
+        assert_eq!(report.activated_handlers, 7);
+        assert_eq!(report.ok_handlers, 0);
+        assert_eq!(report.err_handlers, 0);
+
+        assert_eq!(counter.count("published"), 0);
+        assert_eq!(counter.count("registered"), 0);
+    }
+}
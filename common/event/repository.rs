@@ -4,16 +4,100 @@ use chrono::{DateTime, Utc};
 use crate::event::{Event, EventId};
 use crate::result::Result;
 
+/// Events are sorted by timestamp, using the id as tie-breaker.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventOrder {
+    Asc,
+    Desc,
+}
+
+impl Default for EventOrder {
+    fn default() -> Self {
+        EventOrder::Asc
+    }
+}
+
+/// EventPage is a page of events returned by a keyset search. `next_cursor` is the id to use as
+/// `after_id` to get the next page, and it's `None` when there are no more events.
+#[derive(Debug, Clone)]
+pub struct EventPage {
+    events: Vec<Event>,
+    next_cursor: Option<EventId>,
+}
+
+impl EventPage {
+    pub fn new(mut events: Vec<Event>, limit: Option<usize>) -> Self {
+        // Repositories fetch one event more than the limit to know if there is a next page.
+        let next_cursor = match limit {
+            Some(limit) if events.len() > limit => {
+                events.truncate(limit);
+                events.last().map(|event| event.id().clone())
+            }
+            _ => None,
+        };
+
+        EventPage {
+            events,
+            next_cursor,
+        }
+    }
+
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    pub fn next_cursor(&self) -> Option<&EventId> {
+        self.next_cursor.as_ref()
+    }
+
+    pub fn into_events(self) -> Vec<Event> {
+        self.events
+    }
+}
+
 #[async_trait]
 pub trait EventRepository: Sync + Send {
+    /// Returns the events after `after_id` in the given order. If `after_id` does not exist the
+    /// page is empty. Filtering by `correlation_id` returns the causal chain: the events with
+    /// that correlation ID and the event that started it, if any.
+    #[allow(clippy::too_many_arguments)]
     async fn search(
         &self,
         after_id: Option<&EventId>,
         topic: Option<&String>,
         code: Option<&String>,
+        correlation_id: Option<&String>,
         from: Option<&DateTime<Utc>>,
         to: Option<&DateTime<Utc>>,
-    ) -> Result<Vec<Event>>;
+        limit: Option<usize>,
+        order: EventOrder,
+    ) -> Result<EventPage>;
 
     async fn save(&self, event: &Event) -> Result<()>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    fn next_cursor() {
+        let events: Vec<Event> = (0..3)
+            .map(|_| Event::new("topic", "code", json!({})))
+            .collect();
+
+        let page = EventPage::new(events.clone(), Some(2));
+        assert_eq!(page.events().len(), 2);
+        assert_eq!(page.next_cursor(), Some(events[1].id()));
+
+        let page = EventPage::new(events.clone(), Some(3));
+        assert_eq!(page.events().len(), 3);
+        assert!(page.next_cursor().is_none());
+
+        let page = EventPage::new(events, None);
+        assert_eq!(page.events().len(), 3);
+        assert!(page.next_cursor().is_none());
+    }
+}
@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+/// RetryPolicy defines how many times a failed handler is retried and how long the event bus
+/// waits between attempts. Delays grow exponentially up to `max_delay`.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    initial_delay: Duration,
+    multiplier: u32,
+    max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(
+        max_retries: u32,
+        initial_delay: Duration,
+        multiplier: u32,
+        max_delay: Duration,
+    ) -> Self {
+        RetryPolicy {
+            max_retries,
+            initial_delay,
+            multiplier,
+            max_delay,
+        }
+    }
+
+    pub fn none() -> Self {
+        Self::new(0, Duration::from_millis(0), 1, Duration::from_millis(0))
+    }
+
+    pub fn exponential(max_retries: u32, initial_delay: Duration) -> Self {
+        Self::new(max_retries, initial_delay, 2, Duration::from_secs(30))
+    }
+
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    pub fn initial_delay(&self) -> &Duration {
+        &self.initial_delay
+    }
+
+    pub fn multiplier(&self) -> u32 {
+        self.multiplier
+    }
+
+    pub fn max_delay(&self) -> &Duration {
+        &self.max_delay
+    }
+
+    /// Delay to wait before the given retry, starting from 1.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let factor = self.multiplier.saturating_pow(retry.saturating_sub(1));
+        match self.initial_delay.checked_mul(factor) {
+            Some(delay) if delay < self.max_delay => delay,
+            _ => self.max_delay,
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exponential_delays() {
+        let policy = RetryPolicy::new(
+            5,
+            Duration::from_millis(100),
+            2,
+            Duration::from_millis(1000),
+        );
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(3), Duration::from_millis(400));
+        assert_eq!(policy.delay(4), Duration::from_millis(800));
+        assert_eq!(policy.delay(5), Duration::from_millis(1000));
+        assert_eq!(policy.delay(100), Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn none() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.max_retries(), 0);
+        assert_eq!(policy.delay(1), Duration::from_millis(0));
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::event::{Event, INITIAL_EVENT_VERSION};
+use crate::result::Result;
+
+/// Upcaster migrates the payload of the events of a topic from `version` to `version + 1`.
+pub trait Upcaster: Sync + Send {
+    fn topic(&self) -> &str;
+
+    fn version(&self) -> u32;
+
+    fn upcast(&self, code: &str, payload: Value) -> Result<Value>;
+}
+
+/// UpcasterRegistry keeps the upcasters of every topic and migrates stored events to the
+/// current version of their schema when they are read.
+#[derive(Default)]
+pub struct UpcasterRegistry {
+    upcasters: HashMap<(String, u32), Box<dyn Upcaster>>,
+}
+
+impl UpcasterRegistry {
+    pub fn new() -> Self {
+        UpcasterRegistry {
+            upcasters: HashMap::new(),
+        }
+    }
+
+    pub fn register(&mut self, upcaster: Box<dyn Upcaster>) -> Result<()> {
+        let key = (upcaster.topic().to_owned(), upcaster.version());
+        if self.upcasters.contains_key(&key) {
+            return Err(Error::new("upcaster", "already_registered")
+                .add_context("topic", key.0.as_str())
+                .add_context("version", key.1.to_string().as_str()));
+        }
+
+        self.upcasters.insert(key, upcaster);
+
+        Ok(())
+    }
+
+    /// Current schema version of the topic: the one after the last registered upcaster.
+    pub fn current_version(&self, topic: &str) -> u32 {
+        self.upcasters
+            .keys()
+            .filter(|(t, _)| t == topic)
+            .map(|(_, version)| version + 1)
+            .max()
+            .unwrap_or(INITIAL_EVENT_VERSION)
+    }
+
+    pub fn upcast(&self, mut event: Event) -> Result<Event> {
+        while let Some(upcaster) = self.upcasters.get(&(event.topic.clone(), event.version)) {
+            let payload = std::mem::take(&mut event.payload);
+
+            event.payload = upcaster.upcast(&event.code, payload).map_err(|err| {
+                Error::new("upcaster", "upcast")
+                    .add_context("topic", event.topic.as_str())
+                    .add_context("version", event.version.to_string().as_str())
+                    .wrap(err)
+            })?;
+            event.version += 1;
+        }
+
+        Ok(event)
+    }
+
+    pub fn upcast_all(&self, events: Vec<Event>) -> Result<Vec<Event>> {
+        events.into_iter().map(|event| self.upcast(event)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    // v1: { "name": "..." }, v2: { "fullname": "..." }, v3: { "fullname": "...", "age": 0 }
+    struct RenameName;
+
+    impl Upcaster for RenameName {
+        fn topic(&self) -> &str {
+            "person"
+        }
+
+        fn version(&self) -> u32 {
+            1
+        }
+
+        fn upcast(&self, _code: &str, payload: Value) -> Result<Value> {
+            Ok(json!({ "fullname": payload["name"] }))
+        }
+    }
+
+    struct AddAge;
+
+    impl Upcaster for AddAge {
+        fn topic(&self) -> &str {
+            "person"
+        }
+
+        fn version(&self) -> u32 {
+            2
+        }
+
+        fn upcast(&self, _code: &str, mut payload: Value) -> Result<Value> {
+            payload["age"] = json!(0);
+            Ok(payload)
+        }
+    }
+
+    fn registry() -> UpcasterRegistry {
+        let mut registry = UpcasterRegistry::new();
+        registry.register(Box::new(RenameName)).unwrap();
+        registry.register(Box::new(AddAge)).unwrap();
+        registry
+    }
+
+    #[test]
+    fn current_version() {
+        let registry = registry();
+        assert_eq!(registry.current_version("person"), 3);
+        assert_eq!(registry.current_version("other"), INITIAL_EVENT_VERSION);
+    }
+
+    #[test]
+    fn duplicated() {
+        let mut registry = registry();
+        assert!(registry.register(Box::new(AddAge)).is_err());
+    }
+
+    #[test]
+    fn upcast() {
+        let registry = registry();
+
+        let event = registry
+            .upcast(Event::new("person", "created", json!({ "name": "Alan" })))
+            .unwrap();
+        assert_eq!(event.version(), 3);
+        assert_eq!(event.payload(), json!({ "fullname": "Alan", "age": 0 }));
+
+        let event = registry
+            .upcast(Event::new("person", "created", json!({ "fullname": "Alan" })).with_version(2))
+            .unwrap();
+        assert_eq!(event.version(), 3);
+        assert_eq!(event.payload(), json!({ "fullname": "Alan", "age": 0 }));
+
+        let event = registry
+            .upcast(Event::new("other", "created", json!({ "name": "Alan" })))
+            .unwrap();
+        assert_eq!(event.version(), 1);
+        assert_eq!(event.payload(), json!({ "name": "Alan" }));
+    }
+}
@@ -1,33 +1,162 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::hash::Hash;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use tokio::sync::Mutex;
 
-use crate::cache::Cache;
-
+use crate::cache::{Cache, Sweep};
+use crate::infrastructure::transaction::InMemTransaction;
 use crate::result::Result;
 
-#[derive(Default)]
+struct Entry<V> {
+    value: V,
+    expires_at: Option<Instant>,
+    last_used: u64,
+}
+
+impl<V> Entry<V> {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at
+            .map_or(false, |expires_at| expires_at <= now)
+    }
+}
+
+/// Entries, and their keys ordered from the least to the most recently used.
+struct Data<K, V> {
+    entries: HashMap<K, Entry<V>>,
+    recently_used: BTreeMap<u64, K>,
+    tick: u64,
+}
+
+impl<K: Hash + Eq + Clone, V> Data<K, V> {
+    fn new() -> Self {
+        Data {
+            entries: HashMap::new(),
+            recently_used: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn get(&mut self, k: &K, now: Instant) -> Option<&V> {
+        if self.entries.get(k)?.is_expired(now) {
+            self.remove(k);
+            return None;
+        }
+
+        let tick = self.next_tick();
+        let entry = self.entries.get_mut(k)?;
+        self.recently_used.remove(&entry.last_used);
+        self.recently_used.insert(tick, k.clone());
+        entry.last_used = tick;
+
+        Some(&entry.value)
+    }
+
+    fn insert(&mut self, k: K, value: V, expires_at: Option<Instant>) -> Option<Entry<V>> {
+        let tick = self.next_tick();
+        self.recently_used.insert(tick, k.clone());
+
+        let previous = self.entries.insert(
+            k,
+            Entry {
+                value,
+                expires_at,
+                last_used: tick,
+            },
+        );
+        if let Some(previous) = &previous {
+            self.recently_used.remove(&previous.last_used);
+        }
+
+        previous
+    }
+
+    fn remove(&mut self, k: &K) -> Option<Entry<V>> {
+        let entry = self.entries.remove(k)?;
+        self.recently_used.remove(&entry.last_used);
+        Some(entry)
+    }
+
+    /// Removes the least recently used entries until there are at most `capacity`.
+    fn evict(&mut self, capacity: usize) {
+        while self.entries.len() > capacity {
+            let tick = match self.recently_used.keys().next() {
+                Some(tick) => *tick,
+                None => return,
+            };
+
+            if let Some(k) = self.recently_used.remove(&tick) {
+                self.entries.remove(&k);
+            }
+        }
+    }
+
+    fn sweep(&mut self, now: Instant) -> usize {
+        let expired: Vec<K> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.is_expired(now))
+            .map(|(k, _)| k.clone())
+            .collect();
+
+        for k in expired.iter() {
+            self.remove(k);
+        }
+
+        expired.len()
+    }
+}
+
+/// InMemCache keeps values in memory. It can be bounded with `capacity`, in which case the least
+/// recently used values are evicted first. Expired values are ignored, and removed when they are
+/// read or when the cache is swept.
+///
+/// It joins the current `InMemTransaction`: changes made inside of it are undone if it's rolled
+/// back.
 pub struct InMemCache<K, V> {
-    data: Mutex<HashMap<K, V>>,
+    data: Arc<Mutex<Data<K, V>>>,
+    capacity: Option<usize>,
 }
 
-impl<K, V: Clone> InMemCache<K, V> {
-    pub fn new() -> InMemCache<K, V> {
+impl<K: Hash + Eq + Clone, V> Default for InMemCache<K, V> {
+    fn default() -> Self {
         InMemCache {
-            data: Mutex::new(HashMap::new()),
+            data: Arc::new(Mutex::new(Data::new())),
+            capacity: None,
         }
     }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> InMemCache<K, V> {
+    pub fn new() -> InMemCache<K, V> {
+        InMemCache::default()
+    }
+
+    /// Maximum number of values. It's unbounded by default.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
 
     pub async fn find<P>(&self, predicate: P) -> Option<V>
     where
         P: FnMut(&(&K, &V)) -> bool,
     {
+        let now = Instant::now();
         self.data
             .lock()
             .await
+            .entries
             .iter()
+            .filter(|(_, entry)| !entry.is_expired(now))
+            .map(|(k, entry)| (k, &entry.value))
             .find(predicate)
             .map(|(_, v)| v.clone())
     }
@@ -36,10 +165,14 @@ impl<K, V: Clone> InMemCache<K, V> {
     where
         P: FnMut(&(&K, &V)) -> bool,
     {
+        let now = Instant::now();
         self.data
             .lock()
             .await
+            .entries
             .iter()
+            .filter(|(_, entry)| !entry.is_expired(now))
+            .map(|(k, entry)| (k, &entry.value))
             .filter(predicate)
             .map(|(_, v)| v.clone())
             .collect()
@@ -50,34 +183,101 @@ impl<K, V: Clone> InMemCache<K, V> {
     }
 
     pub async fn len(&self) -> usize {
-        self.data.lock().await.len()
+        let now = Instant::now();
+        self.data
+            .lock()
+            .await
+            .entries
+            .values()
+            .filter(|entry| !entry.is_expired(now))
+            .count()
     }
 
     pub async fn is_empty(&self) -> bool {
-        self.data.lock().await.is_empty()
+        self.len().await == 0
+    }
+}
+
+impl<K, V> InMemCache<K, V>
+where
+    K: Hash + Eq + Clone + Send + 'static,
+    V: Send + 'static,
+{
+    /// Restores the previous value of `k` if the current transaction is rolled back.
+    fn on_rollback(&self, k: K, previous: Option<Entry<V>>) {
+        let data = Arc::clone(&self.data);
+        InMemTransaction::on_rollback(Box::pin(async move {
+            let mut data = data.lock().await;
+            match previous {
+                Some(entry) => data.insert(k, entry.value, entry.expires_at),
+                None => data.remove(&k),
+            };
+        }));
+    }
+
+    async fn insert(&self, k: K, v: V, expires_at: Option<Instant>) {
+        let mut data = self.data.lock().await;
+        let previous = data.insert(k.clone(), v, expires_at);
+        if let Some(capacity) = self.capacity {
+            data.evict(capacity);
+        }
+        self.on_rollback(k, previous);
     }
 }
 
 #[async_trait]
-impl<K: Hash + Eq + Send + Sync, V: Clone + Send + Sync> Cache<K, V> for InMemCache<K, V> {
+impl<K, V> Cache<K, V> for InMemCache<K, V>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+{
     async fn get(&self, k: &K) -> Option<V> {
-        let cache = self.data.lock().await;
-        cache.get(k).cloned()
+        let mut data = self.data.lock().await;
+        data.get(k, Instant::now()).cloned()
     }
 
     async fn set(&self, k: K, v: V) -> Result<()> {
-        let mut cache = self.data.lock().await;
-        cache.insert(k, v);
+        self.insert(k, v, None).await;
         Ok(())
     }
 
     async fn delete(&self, k: &K) -> Result<()> {
-        let mut cache = self.data.lock().await;
-        cache.remove(k);
+        let mut data = self.data.lock().await;
+        if let Some(previous) = data.remove(k) {
+            self.on_rollback(k.clone(), Some(previous));
+        }
+        Ok(())
+    }
+
+    async fn take(&self, k: &K) -> Option<V> {
+        let mut data = self.data.lock().await;
+        let entry = data.remove(k)?;
+        if entry.is_expired(Instant::now()) {
+            return None;
+        }
+
+        let value = entry.value.clone();
+        self.on_rollback(k.clone(), Some(entry));
+        Some(value)
+    }
+
+    async fn set_with_ttl(&self, k: K, v: V, ttl: Duration) -> Result<()> {
+        self.insert(k, v, Some(Instant::now() + ttl)).await;
         Ok(())
     }
 }
 
+#[async_trait]
+impl<K, V> Sweep for InMemCache<K, V>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+    V: Send + Sync + 'static,
+{
+    async fn sweep(&self) -> Result<usize> {
+        Ok(self.data.lock().await.sweep(Instant::now()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,7 +285,7 @@ mod tests {
     #[tokio::test]
     async fn initialize() {
         let c: InMemCache<u8, u8> = InMemCache::new();
-        assert_eq!(c.data.lock().await.len(), 0);
+        assert_eq!(c.data.lock().await.entries.len(), 0);
     }
 
     #[tokio::test]
@@ -99,6 +299,22 @@ mod tests {
         assert!(c.get(&k).await.is_none());
     }
 
+    #[tokio::test]
+    async fn take() {
+        let c = InMemCache::new();
+        let k = "key".to_owned();
+        c.set(k.clone(), 123).await.unwrap();
+        assert_eq!(c.take(&k).await, Some(123));
+        assert!(c.take(&k).await.is_none());
+        assert!(c.get(&k).await.is_none());
+
+        c.set_with_ttl(k.clone(), 456, Duration::from_millis(10))
+            .await
+            .unwrap();
+        tokio::time::delay_for(Duration::from_millis(20)).await;
+        assert!(c.take(&k).await.is_none());
+    }
+
     #[tokio::test]
     async fn find() {
         let c = InMemCache::new();
@@ -133,4 +349,74 @@ mod tests {
         let res = c.filter(|&(_, v)| *v > 2).await;
         assert_eq!(res.len(), 3);
     }
+
+    #[tokio::test]
+    async fn expiration() {
+        let c = InMemCache::new();
+        c.set_with_ttl("short", 1u8, Duration::from_millis(50))
+            .await
+            .unwrap();
+        c.set_with_ttl("long", 2, Duration::from_secs(60))
+            .await
+            .unwrap();
+        c.set("forever", 3).await.unwrap();
+        assert_eq!(c.get(&"short").await, Some(1));
+        assert_eq!(c.len().await, 3);
+
+        tokio::time::delay_for(Duration::from_millis(100)).await;
+
+        assert!(c.get(&"short").await.is_none());
+        assert_eq!(c.get(&"long").await, Some(2));
+        assert_eq!(c.get(&"forever").await, Some(3));
+        assert_eq!(c.len().await, 2);
+
+        // Overwriting a value removes its expiration.
+        c.set_with_ttl("again", 4, Duration::from_millis(50))
+            .await
+            .unwrap();
+        c.set("again", 5).await.unwrap();
+        tokio::time::delay_for(Duration::from_millis(100)).await;
+        assert_eq!(c.get(&"again").await, Some(5));
+    }
+
+    #[tokio::test]
+    async fn sweep() {
+        let c = InMemCache::new();
+        c.set_with_ttl("one", 1u8, Duration::from_millis(50))
+            .await
+            .unwrap();
+        c.set_with_ttl("two", 2, Duration::from_millis(50))
+            .await
+            .unwrap();
+        c.set("three", 3).await.unwrap();
+
+        assert_eq!(c.sweep().await.unwrap(), 0);
+        tokio::time::delay_for(Duration::from_millis(100)).await;
+        assert_eq!(c.sweep().await.unwrap(), 2);
+        assert_eq!(c.data.lock().await.entries.len(), 1);
+        assert_eq!(c.data.lock().await.recently_used.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn evict_least_recently_used() {
+        let c = InMemCache::new().capacity(3);
+        c.set("one", 1u8).await.unwrap();
+        c.set("two", 2).await.unwrap();
+        c.set("three", 3).await.unwrap();
+
+        // "one" becomes the most recently used, so "two" is evicted.
+        assert_eq!(c.get(&"one").await, Some(1));
+        c.set("four", 4).await.unwrap();
+
+        assert_eq!(c.len().await, 3);
+        assert!(c.get(&"two").await.is_none());
+        assert_eq!(c.get(&"one").await, Some(1));
+        assert_eq!(c.get(&"three").await, Some(3));
+        assert_eq!(c.get(&"four").await, Some(4));
+
+        // Overwriting doesn't evict.
+        c.set("four", 40).await.unwrap();
+        assert_eq!(c.len().await, 3);
+        assert_eq!(c.data.lock().await.recently_used.len(), 3);
+    }
 }
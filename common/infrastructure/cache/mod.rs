@@ -1,4 +1,6 @@
 mod inmem;
 mod postgres;
+mod sweeper;
 pub use inmem::*;
 pub use postgres::*;
+pub use sweeper::*;
@@ -1,39 +1,69 @@
-use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
 use tokio_postgres::types::ToSql;
-use tokio_postgres::Client;
 
-use crate::cache::Cache;
+use crate::cache::{Cache, CacheNamespace, Sweep};
 use crate::error::Error;
+use crate::infrastructure::postgres::PostgresClient;
 use crate::result::Result;
 
+/// PostgresCache stores every type of value in the `cache` table, each of them in its own
+/// namespace (see `CacheNamespace`).
 pub struct PostgresCache {
-    client: Arc<Client>,
+    client: PostgresClient,
 }
 
 impl PostgresCache {
-    pub fn new(client: Arc<Client>) -> Self {
+    pub fn new(client: PostgresClient) -> Self {
         PostgresCache { client }
     }
+
+    /// Inserts or replaces the value in a single statement.
+    async fn upsert<K, V>(&self, k: K, v: V, expires_at: Option<DateTime<Utc>>) -> Result<()>
+    where
+        K: ToSql + Sync + Send,
+        V: Serialize + CacheNamespace,
+    {
+        let value = serde_json::to_value(v)?;
+
+        self.client
+            .execute(
+                "INSERT INTO cache(namespace, key, value, expires_at)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (namespace, key) DO UPDATE
+                SET
+                    value = EXCLUDED.value,
+                    expires_at = EXCLUDED.expires_at",
+                &[&V::NAMESPACE, &k, &value, &expires_at],
+            )
+            .await
+            .map_err(|err| Error::new("cache", "update").wrap_raw(err))?;
+
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl<K, V> Cache<K, V> for PostgresCache
 where
     K: ToSql + Sync + Send + 'static,
-    V: Serialize + DeserializeOwned + Sync + Send + 'static,
+    V: Serialize + DeserializeOwned + CacheNamespace + Sync + Send + 'static,
 {
     async fn get(&self, k: &K) -> Option<V> {
         let row = self
             .client
             .query_one(
                 "SELECT value FROM cache
-                WHERE key = $1",
-                &[&k],
+                WHERE
+                    namespace = $1
+                    AND key = $2
+                    AND (expires_at IS NULL OR expires_at > $3)",
+                &[&V::NAMESPACE, &k, &Utc::now()],
             )
             .await
             .ok();
@@ -50,53 +80,64 @@ where
     }
 
     async fn set(&self, k: K, v: V) -> Result<()> {
-        let create = self
+        self.upsert(k, v, None).await
+    }
+
+    async fn delete(&self, k: &K) -> Result<()> {
+        self.client
+            .execute(
+                "DELETE FROM cache
+                WHERE
+                    namespace = $1
+                    AND key = $2",
+                &[&V::NAMESPACE, &k],
+            )
+            .await
+            .map_err(|err| Error::new("cache", "delete").wrap_raw(err))?;
+
+        Ok(())
+    }
+
+    async fn take(&self, k: &K) -> Option<V> {
+        let row = self
             .client
             .query_one(
-                "SELECT value FROM cache
-                WHERE key = $1",
-                &[&k],
+                "DELETE FROM cache
+                WHERE
+                    namespace = $1
+                    AND key = $2
+                    AND (expires_at IS NULL OR expires_at > $3)
+                RETURNING value",
+                &[&V::NAMESPACE, &k, &Utc::now()],
             )
             .await
-            .is_err();
-
-        let value = serde_json::to_value(v)?;
+            .ok()?;
 
-        if create {
-            self.client
-                .execute(
-                    "INSERT INTO cache(key, value)
-                    VALUES ($1, $2)",
-                    &[&k, &value],
-                )
-                .await
-                .map_err(|err| Error::new("cache", "create").wrap_raw(err))?;
-        } else {
-            self.client
-                .execute(
-                    "UPDATE cache
-                    SET
-                        value = $2
-                    WHERE key = $1",
-                    &[&k, &value],
-                )
-                .await
-                .map_err(|err| Error::new("cache", "update").wrap_raw(err))?;
-        }
+        row.try_get("value")
+            .ok()
+            .and_then(|value| serde_json::from_value(value).ok())
+    }
 
-        Ok(())
+    async fn set_with_ttl(&self, k: K, v: V, ttl: Duration) -> Result<()> {
+        let ttl = chrono::Duration::from_std(ttl)
+            .map_err(|err| Error::internal("cache", "ttl").wrap_raw(err))?;
+        self.upsert(k, v, Some(Utc::now() + ttl)).await
     }
+}
 
-    async fn delete(&self, k: &K) -> Result<()> {
-        self.client
+#[async_trait]
+impl Sweep for PostgresCache {
+    async fn sweep(&self) -> Result<usize> {
+        let deleted = self
+            .client
             .execute(
                 "DELETE FROM cache
-                WHERE key = $1",
-                &[&k],
+                WHERE expires_at <= $1",
+                &[&Utc::now()],
             )
             .await
             .map_err(|err| Error::new("cache", "delete").wrap_raw(err))?;
 
-        Ok(())
+        Ok(deleted as usize)
     }
 }
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::cache::Sweep;
+use crate::result::Result;
+
+/// CacheSweeper periodically removes expired values from the registered caches, so values set
+/// with a TTL and never read again don't accumulate.
+#[derive(Default)]
+pub struct CacheSweeper {
+    caches: Vec<Arc<dyn Sweep>>,
+}
+
+impl CacheSweeper {
+    pub fn new() -> Self {
+        CacheSweeper { caches: Vec::new() }
+    }
+
+    pub fn cache(mut self, cache: Arc<dyn Sweep>) -> Self {
+        self.caches.push(cache);
+        self
+    }
+
+    /// Sweeps every cache once and returns how many values were removed.
+    pub async fn sweep(&self) -> Result<usize> {
+        let mut total = 0;
+        for cache in self.caches.iter() {
+            total += cache.sweep().await?;
+        }
+        Ok(total)
+    }
+
+    pub fn start(self: Arc<Self>, interval: Duration) {
+        tokio::spawn(async move {
+            loop {
+                tokio::time::delay_for(interval).await;
+
+                if let Err(err) = self.sweep().await {
+                    println!("{:?}", err);
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::cache::Cache;
+    use crate::infrastructure::cache::InMemCache;
+
+    #[tokio::test]
+    async fn sweep_every_cache() {
+        let numbers = Arc::new(InMemCache::new());
+        numbers
+            .set_with_ttl(1u8, 1u8, Duration::from_millis(10))
+            .await
+            .unwrap();
+        numbers.set(2, 2).await.unwrap();
+
+        let words = Arc::new(InMemCache::new());
+        words
+            .set_with_ttl("one", "uno", Duration::from_millis(10))
+            .await
+            .unwrap();
+
+        let sweeper = Arc::new(
+            CacheSweeper::new()
+                .cache(numbers.clone())
+                .cache(words.clone()),
+        );
+        sweeper.clone().start(Duration::from_millis(20));
+
+        tokio::time::delay_for(Duration::from_millis(100)).await;
+
+        assert_eq!(sweeper.sweep().await.unwrap(), 0);
+        assert_eq!(numbers.len().await, 1);
+        assert!(words.is_empty().await);
+    }
+}
@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio::sync::Mutex;
+
+use crate::config::{BusinessRules, BusinessRulesRepository, BusinessRulesVersion};
+use crate::error::Error;
+use crate::result::Result;
+
+pub struct InMemBusinessRulesRepository {
+    versions: Mutex<Vec<BusinessRulesVersion>>,
+}
+
+impl InMemBusinessRulesRepository {
+    pub fn new() -> Self {
+        InMemBusinessRulesRepository {
+            versions: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl Default for InMemBusinessRulesRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl BusinessRulesRepository for InMemBusinessRulesRepository {
+    async fn find_all(&self) -> Result<Vec<BusinessRulesVersion>> {
+        Ok(self.versions.lock().await.clone())
+    }
+
+    async fn find_by_version(&self, version: u32) -> Result<BusinessRulesVersion> {
+        self.versions
+            .lock()
+            .await
+            .iter()
+            .find(|v| v.version() == version)
+            .cloned()
+            .ok_or_else(|| Error::not_found("business_rules"))
+    }
+
+    async fn find_effective_at(&self, date: &DateTime<Utc>) -> Result<BusinessRulesVersion> {
+        self.versions
+            .lock()
+            .await
+            .iter()
+            .filter(|v| v.effective_from() <= date)
+            .max_by(|a, b| {
+                a.effective_from()
+                    .cmp(b.effective_from())
+                    .then(a.version().cmp(&b.version()))
+            })
+            .cloned()
+            .ok_or_else(|| Error::not_found("business_rules"))
+    }
+
+    async fn append(
+        &self,
+        rules: BusinessRules,
+        author_id: Option<String>,
+        effective_from: DateTime<Utc>,
+    ) -> Result<BusinessRulesVersion> {
+        let mut versions = self.versions.lock().await;
+
+        let version = BusinessRulesVersion::build(
+            versions.len() as u32 + 1,
+            rules,
+            author_id,
+            Utc::now(),
+            effective_from,
+        );
+        versions.push(version.clone());
+
+        Ok(version)
+    }
+}
@@ -0,0 +1,4 @@
+mod inmem;
+mod postgres;
+pub use inmem::*;
+pub use postgres::*;
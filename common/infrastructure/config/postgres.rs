@@ -0,0 +1,120 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use tokio_postgres::row::Row;
+
+use crate::config::{BusinessRules, BusinessRulesRepository, BusinessRulesVersion};
+use crate::error::Error;
+use crate::infrastructure::postgres::PostgresClient;
+use crate::result::Result;
+
+impl BusinessRulesVersion {
+    fn from_row(row: Row) -> Result<Self> {
+        let version: i32 = row.get("version");
+        let rules: Value = row.get("rules");
+        let author_id: Option<String> = row.get("author_id");
+        let created_at: DateTime<Utc> = row.get("created_at");
+        let effective_from: DateTime<Utc> = row.get("effective_from");
+
+        Ok(BusinessRulesVersion::build(
+            version as u32,
+            serde_json::from_value(rules)
+                .map_err(|err| Error::internal("business_rules", "deserialize").wrap_raw(err))?,
+            author_id,
+            created_at,
+            effective_from,
+        ))
+    }
+}
+
+pub struct PostgresBusinessRulesRepository {
+    client: PostgresClient,
+}
+
+impl PostgresBusinessRulesRepository {
+    pub fn new(client: PostgresClient) -> Self {
+        PostgresBusinessRulesRepository { client }
+    }
+}
+
+#[async_trait]
+impl BusinessRulesRepository for PostgresBusinessRulesRepository {
+    async fn find_all(&self) -> Result<Vec<BusinessRulesVersion>> {
+        let rows = self
+            .client
+            .query(
+                "SELECT * FROM business_rules_versions ORDER BY version ASC",
+                &[],
+            )
+            .await
+            .map_err(|err| Error::not_found("business_rules").wrap_raw(err))?;
+
+        let mut versions = Vec::new();
+        for row in rows.into_iter() {
+            versions.push(BusinessRulesVersion::from_row(row)?);
+        }
+
+        Ok(versions)
+    }
+
+    async fn find_by_version(&self, version: u32) -> Result<BusinessRulesVersion> {
+        let row = self
+            .client
+            .query_one(
+                "SELECT * FROM business_rules_versions WHERE version = $1",
+                &[&(version as i32)],
+            )
+            .await
+            .map_err(|err| Error::not_found("business_rules").wrap_raw(err))?;
+
+        BusinessRulesVersion::from_row(row)
+    }
+
+    async fn find_effective_at(&self, date: &DateTime<Utc>) -> Result<BusinessRulesVersion> {
+        let row = self
+            .client
+            .query_one(
+                "SELECT * FROM business_rules_versions
+                WHERE effective_from <= $1
+                ORDER BY effective_from DESC, version DESC
+                LIMIT 1",
+                &[date],
+            )
+            .await
+            .map_err(|err| Error::not_found("business_rules").wrap_raw(err))?;
+
+        BusinessRulesVersion::from_row(row)
+    }
+
+    async fn append(
+        &self,
+        rules: BusinessRules,
+        author_id: Option<String>,
+        effective_from: DateTime<Utc>,
+    ) -> Result<BusinessRulesVersion> {
+        let rules = serde_json::to_value(rules)
+            .map_err(|err| Error::internal("business_rules", "serialize").wrap_raw(err))?;
+
+        // The version is assigned by the same statement, and the primary key rejects a
+        // concurrent append with the same number.
+        let row = self
+            .client
+            .query_one(
+                "INSERT INTO business_rules_versions (
+                    version,
+                    rules,
+                    author_id,
+                    created_at,
+                    effective_from
+                )
+                SELECT COALESCE(MAX(version), 0) + 1, $1, $2, $3, $4
+                FROM business_rules_versions
+                RETURNING *",
+                &[&rules, &author_id, &Utc::now(), &effective_from],
+            )
+            .await
+            .map_err(|err| Error::new("business_rules", "create").wrap_raw(err))?;
+
+        BusinessRulesVersion::from_row(row)
+    }
+}
@@ -0,0 +1,51 @@
+use async_trait::async_trait;
+
+use crate::cache::Cache;
+use crate::error::Error;
+use crate::event::{DeadLetter, DeadLetterId, DeadLetterRepository};
+use crate::infrastructure::cache::InMemCache;
+use crate::result::Result;
+
+pub struct InMemDeadLetterRepository {
+    cache: InMemCache<DeadLetterId, DeadLetter>,
+}
+
+impl InMemDeadLetterRepository {
+    pub fn new() -> Self {
+        InMemDeadLetterRepository {
+            cache: InMemCache::new(),
+        }
+    }
+}
+
+impl Default for InMemDeadLetterRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl DeadLetterRepository for InMemDeadLetterRepository {
+    async fn find_all(&self) -> Result<Vec<DeadLetter>> {
+        let mut dead_letters = self.cache.all().await;
+        dead_letters.sort_by(|a, b| a.failed_at().cmp(b.failed_at()));
+        Ok(dead_letters)
+    }
+
+    async fn find_by_id(&self, id: &DeadLetterId) -> Result<DeadLetter> {
+        self.cache
+            .get(id)
+            .await
+            .ok_or_else(|| Error::not_found("dead_letter"))
+    }
+
+    async fn save(&self, dead_letter: &DeadLetter) -> Result<()> {
+        self.cache
+            .set(dead_letter.id().clone(), dead_letter.clone())
+            .await
+    }
+
+    async fn delete(&self, id: &DeadLetterId) -> Result<()> {
+        self.cache.delete(id).await
+    }
+}
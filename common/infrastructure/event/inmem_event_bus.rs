@@ -2,79 +2,302 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use regex::Regex;
+use tokio::sync::mpsc;
 use tokio::sync::oneshot::{self, Receiver};
-use tokio::sync::Mutex;
+use tokio::sync::{RwLock, Semaphore};
 
 use crate::error::Error;
-use crate::event::{Event, EventHandler, EventPublisher, EventSubscriber, PublicationResult};
+use crate::event::{
+    DeadLetter, DeadLetterRepository, Event, EventHandler, EventMetadata, EventPublisher,
+    EventSubscriber, PublicationResult, RetryPolicy,
+};
 use crate::result::Result;
 
+/// Event delivered to a handler queue. The worker reports the outcome through `result`.
+struct Delivery {
+    event: Event,
+    result: oneshot::Sender<HandlerOutcome>,
+}
+
 #[derive(Default)]
+struct HandlerOutcome {
+    ok: bool,
+    retried: bool,
+    dead_lettered: bool,
+}
+
+/// Subscription keeps the compiled topic regex and the queue of a handler. The handler itself is
+/// owned by its worker, which processes the queue in order.
+struct Subscription {
+    topic: String,
+    regex: Regex,
+    queue: mpsc::Sender<Delivery>,
+}
+
+impl Subscription {
+    /// Whether the handler receives events of the topic, the same way for publications and
+    /// replays.
+    fn matches(&self, topic: &str) -> bool {
+        self.regex.is_match(topic)
+    }
+}
+
+/// InMemEventBus dispatches events to handlers running concurrently. Each handler has its own
+/// bounded queue, so events are delivered to a handler in the same order they were published,
+/// and publishers wait when a queue is full. The number of handlers executing at the same time
+/// is limited by `max_concurrency`.
 pub struct InMemEventBus {
-    handlers: Arc<Mutex<Vec<Box<dyn EventHandler>>>>,
+    subscriptions: RwLock<Vec<Subscription>>,
+    semaphore: Arc<Semaphore>,
+    queue_capacity: usize,
+    retry_policy: RetryPolicy,
+    dead_letter_repo: Option<Arc<dyn DeadLetterRepository>>,
 }
 
 impl InMemEventBus {
     pub fn new() -> Self {
         InMemEventBus {
-            handlers: Arc::new(Mutex::new(Vec::new())),
+            subscriptions: RwLock::new(Vec::new()),
+            semaphore: Arc::new(Semaphore::new(16)),
+            queue_capacity: 1024,
+            retry_policy: RetryPolicy::default(),
+            dead_letter_repo: None,
         }
     }
-}
 
-#[async_trait]
-impl EventPublisher for InMemEventBus {
-    async fn publish(&self, event: Event) -> Result<Receiver<PublicationResult>> {
-        self.publish_all(vec![event]).await
+    /// Maximum number of handlers executing at the same time.
+    pub fn max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.semaphore = Arc::new(Semaphore::new(max_concurrency));
+        self
     }
 
-    async fn publish_all(&self, events: Vec<Event>) -> Result<Receiver<PublicationResult>> {
-        let handlers = Arc::clone(&self.handlers);
+    /// Maximum number of pending events per handler before publishers have to wait.
+    pub fn queue_capacity(mut self, queue_capacity: usize) -> Self {
+        self.queue_capacity = queue_capacity;
+        self
+    }
+
+    /// Retry policy used by handlers that don't define their own.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Events failing after all retries are stored in the given repository.
+    pub fn dead_letter_repo(mut self, dead_letter_repo: Arc<dyn DeadLetterRepository>) -> Self {
+        self.dead_letter_repo = Some(dead_letter_repo);
+        self
+    }
+
+    /// Delivers a dead-lettered event again, only to the handlers that failed: the ones
+    /// subscribed with the topic of the dead letter whose subscription matches the event.
+    pub async fn replay(&self, dead_letter: &DeadLetter) -> Result<Receiver<PublicationResult>> {
+        Ok(self
+            .dispatch(
+                vec![dead_letter.event().clone()],
+                Some(dead_letter.handler_topic()),
+            )
+            .await)
+    }
+
+    async fn dispatch(
+        &self,
+        events: Vec<Event>,
+        handler_topic: Option<&str>,
+    ) -> Receiver<PublicationResult> {
+        let published_events = events.len() as u32;
+        let mut outcomes = Vec::new();
+
+        for (index, event) in events.into_iter().enumerate() {
+            // Queues are cloned so the lock is not held while waiting for a full queue.
+            let queues: Vec<mpsc::Sender<Delivery>> = self
+                .subscriptions
+                .read()
+                .await
+                .iter()
+                .filter(|sub| handler_topic.map_or(true, |topic| sub.topic == topic))
+                .filter(|sub| sub.matches(event.topic()))
+                .map(|sub| sub.queue.clone())
+                .collect();
+
+            for mut queue in queues.into_iter() {
+                let (tx, rx) = oneshot::channel();
+                let delivery = Delivery {
+                    event: event.clone(),
+                    result: tx,
+                };
+
+                // Waits if the handler queue is full. If the worker is gone the delivery is
+                // dropped and the outcome is reported as an error.
+                if queue.send(delivery).await.is_err() {
+                    let err = Error::internal("event_publisher", "closed_queue");
+                    println!("{:?}", err);
+                }
+
+                outcomes.push((index, rx));
+            }
+        }
+
         let (tx, rx) = oneshot::channel();
-        let mut publication_result = PublicationResult::default();
 
         tokio::spawn(async move {
-            for event in events.into_iter() {
-                let mut handlers = handlers.lock().await;
-                for handler in handlers.iter_mut() {
-                    match Regex::new(handler.topic()) {
-                        Ok(re) => {
-                            if re.is_match(event.topic()) {
-                                // Execute handler
-                                if let Err(err) = handler.handle(&event).await {
-                                    let err = Error::internal("event_publisher", "handler_error")
-                                        .wrap(err);
-                                    println!("{:?}", err);
-
-                                    publication_result.err_handlers += 1;
-                                } else {
-                                    publication_result.ok_handlers += 1;
-                                }
-                            }
-                        }
-                        Err(err) => {
-                            let err = Error::internal("event_publisher", "invalid_topic_regex")
-                                .wrap_raw(err);
-                            println!("{:?}", err);
-                        }
-                    }
+            let mut publication_result = PublicationResult {
+                published_events,
+                ..PublicationResult::default()
+            };
+
+            // An event is counted as retried once, even if several handlers retried it.
+            let mut retried = vec![false; published_events as usize];
+
+            for (index, outcome) in outcomes.into_iter() {
+                let outcome = outcome.await.unwrap_or_default();
+
+                if outcome.ok {
+                    publication_result.ok_handlers += 1;
+                } else {
+                    publication_result.err_handlers += 1;
+                }
+
+                if outcome.retried && !retried[index] {
+                    retried[index] = true;
+                    publication_result.retried_events += 1;
                 }
 
-                publication_result.published_events += 1;
+                if outcome.dead_lettered {
+                    publication_result.dead_lettered_events += 1;
+                }
             }
 
             if tx.send(publication_result).is_err() {}
         });
 
-        Ok(rx)
+        rx
+    }
+}
+
+impl Default for InMemEventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct Worker {
+    handler: Box<dyn EventHandler>,
+    semaphore: Arc<Semaphore>,
+    retry_policy: RetryPolicy,
+    dead_letter_repo: Option<Arc<dyn DeadLetterRepository>>,
+}
+
+impl Worker {
+    async fn run(mut self, mut queue: mpsc::Receiver<Delivery>) {
+        while let Some(delivery) = queue.recv().await {
+            let outcome = self.handle(&delivery.event).await;
+            if delivery.result.send(outcome).is_err() {}
+        }
+    }
+
+    async fn handle(&mut self, event: &Event) -> HandlerOutcome {
+        let mut outcome = HandlerOutcome::default();
+        let mut attempts = 0;
+
+        let res = loop {
+            attempts += 1;
+
+            // The permit is only held while the handler is executing, not between retries. Events
+            // published by the handler are caused by the handled event.
+            let permit = self.semaphore.acquire().await;
+            let res = EventMetadata::caused_by(event)
+                .scope(self.handler.handle(event))
+                .await;
+            drop(permit);
+
+            match res {
+                Err(err) if attempts <= self.retry_policy.max_retries() => {
+                    println!("{:?}", err);
+                    outcome.retried = true;
+                    tokio::time::delay_for(self.retry_policy.delay(attempts)).await;
+                }
+                res => break res,
+            }
+        };
+
+        match res {
+            Ok(_) => outcome.ok = true,
+            Err(err) => {
+                let err = Error::internal("event_publisher", "handler_error").wrap(err);
+                println!("{:?}", err);
+
+                if let Some(dead_letter_repo) = &self.dead_letter_repo {
+                    match dead_letter(
+                        dead_letter_repo.as_ref(),
+                        event,
+                        self.handler.topic(),
+                        &err,
+                        attempts,
+                    )
+                    .await
+                    {
+                        Ok(_) => outcome.dead_lettered = true,
+                        Err(err) => println!("{:?}", err),
+                    }
+                }
+            }
+        }
+
+        outcome
+    }
+}
+
+async fn dead_letter(
+    dead_letter_repo: &dyn DeadLetterRepository,
+    event: &Event,
+    handler_topic: &str,
+    err: &Error,
+    attempts: u32,
+) -> Result<()> {
+    let dead_letter = DeadLetter::new(event.clone(), handler_topic, err, attempts)?;
+    dead_letter_repo
+        .save(&dead_letter)
+        .await
+        .map_err(|err| Error::internal("event_publisher", "dead_letter").wrap(err))
+}
+
+#[async_trait]
+impl EventPublisher for InMemEventBus {
+    async fn publish(&self, event: Event) -> Result<Receiver<PublicationResult>> {
+        self.publish_all(vec![event]).await
+    }
+
+    async fn publish_all(&self, events: Vec<Event>) -> Result<Receiver<PublicationResult>> {
+        Ok(self.dispatch(events, None).await)
     }
 }
 
 #[async_trait]
 impl EventSubscriber for InMemEventBus {
     async fn subscribe(&self, handler: Box<dyn EventHandler>) -> Result<bool> {
-        let mut handlers = self.handlers.lock().await;
-        handlers.push(handler);
+        let topic = handler.topic().to_owned();
+        let regex = Regex::new(&topic).map_err(|err| {
+            Error::internal("event_subscriber", "invalid_topic_regex").wrap_raw(err)
+        })?;
+
+        let (tx, rx) = mpsc::channel(self.queue_capacity);
+        let worker = Worker {
+            retry_policy: handler
+                .retry_policy()
+                .unwrap_or_else(|| self.retry_policy.clone()),
+            handler,
+            semaphore: Arc::clone(&self.semaphore),
+            dead_letter_repo: self.dead_letter_repo.clone(),
+        };
+        tokio::spawn(worker.run(rx));
+
+        self.subscriptions.write().await.push(Subscription {
+            topic,
+            regex,
+            queue: tx,
+        });
+
         Ok(true)
     }
 }
@@ -87,6 +310,9 @@ mod tests {
 
     use serde_json::json;
 
+    use std::time::Duration;
+
+    use crate::infrastructure::event::InMemDeadLetterRepository;
     use crate::mocks::Counter;
 
     fn create_event(topic: &str) -> Event {
@@ -154,10 +380,72 @@ mod tests {
         }
     }
 
+    struct FlakyHandler {
+        counter: Arc<Counter>,
+        failures: u32,
+    }
+
+    #[async_trait]
+    impl EventHandler for FlakyHandler {
+        fn topic(&self) -> &str {
+            "flaky"
+        }
+
+        async fn handle(&mut self, event: &Event) -> Result<bool> {
+            self.counter.inc(event.topic());
+            if self.failures > 0 {
+                self.failures -= 1;
+                return Err(Error::new("flaky_handler", "error"));
+            }
+            Ok(true)
+        }
+
+        fn retry_policy(&self) -> Option<RetryPolicy> {
+            Some(RetryPolicy::exponential(2, Duration::from_millis(1)))
+        }
+    }
+
+    struct SlowHandler {
+        received: Arc<std::sync::Mutex<Vec<String>>>,
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl EventHandler for SlowHandler {
+        fn topic(&self) -> &str {
+            "slow.*"
+        }
+
+        async fn handle(&mut self, event: &Event) -> Result<bool> {
+            tokio::time::delay_for(self.delay).await;
+            self.received.lock().unwrap().push(event.topic().to_owned());
+            Ok(true)
+        }
+    }
+
+    struct CausedEventsHandler {
+        caused: Arc<std::sync::Mutex<Vec<Event>>>,
+    }
+
+    #[async_trait]
+    impl EventHandler for CausedEventsHandler {
+        fn topic(&self) -> &str {
+            "cause"
+        }
+
+        async fn handle(&mut self, _: &Event) -> Result<bool> {
+            self.caused
+                .lock()
+                .unwrap()
+                .push(Event::new("effect", "effect", json!({})));
+            Ok(true)
+        }
+    }
+
     #[tokio::test]
     async fn create() {
         let eb = InMemEventBus::new();
-        assert_eq!(eb.handlers.lock().await.len(), 0);
+        assert_eq!(eb.subscriptions.read().await.len(), 0);
     }
 
     #[tokio::test]
@@ -349,16 +637,30 @@ mod tests {
         let h = BasicHandler::new(r"^topic[0-9]+$");
         eb.subscribe(Box::new(h.clone())).await.unwrap();
 
-        eb.publish(create_event("topic007")).await.unwrap();
+        eb.publish(create_event("topic007"))
+            .await
+            .unwrap()
+            .await
+            .unwrap();
 
         let sub_eb = Arc::clone(&eb);
         let j1 = tokio::spawn(async move {
-            sub_eb.publish(create_event("topic1")).await.unwrap();
+            sub_eb
+                .publish(create_event("topic1"))
+                .await
+                .unwrap()
+                .await
+                .unwrap();
         });
 
         let sub_eb = Arc::clone(&eb);
         let j2 = tokio::spawn(async move {
-            sub_eb.publish(create_event("topic2")).await.unwrap();
+            sub_eb
+                .publish(create_event("topic2"))
+                .await
+                .unwrap()
+                .await
+                .unwrap();
         });
 
         let (_, _) = tokio::join!(j1, j2);
@@ -412,4 +714,240 @@ mod tests {
         assert_eq!(res.ok_handlers(), 3);
         assert_eq!(res.err_handlers(), 1);
     }
+
+    #[tokio::test]
+    async fn retries() {
+        let counter = Arc::new(Counter::new());
+        let eb = InMemEventBus::new();
+        eb.subscribe(Box::new(FlakyHandler {
+            counter: counter.clone(),
+            failures: 2,
+        }))
+        .await
+        .unwrap();
+
+        let res = eb
+            .publish(create_event("flaky"))
+            .await
+            .unwrap()
+            .await
+            .unwrap();
+        assert_eq!(res.ok_handlers(), 1);
+        assert_eq!(res.err_handlers(), 0);
+        assert_eq!(res.retried_events(), 1);
+        assert_eq!(res.dead_lettered_events(), 0);
+        assert_eq!(counter.count("flaky"), 3);
+    }
+
+    #[tokio::test]
+    async fn dead_letters() {
+        let dead_letter_repo = Arc::new(InMemDeadLetterRepository::new());
+        let counter = Arc::new(Counter::new());
+        let eb = InMemEventBus::new()
+            .retry_policy(RetryPolicy::exponential(1, Duration::from_millis(1)))
+            .dead_letter_repo(dead_letter_repo.clone());
+        eb.subscribe(Box::new(ErrorHandler)).await.unwrap();
+        eb.subscribe(Box::new(FlakyHandler {
+            counter: counter.clone(),
+            failures: 3,
+        }))
+        .await
+        .unwrap();
+
+        let res = eb
+            .publish_all(vec![create_event("error"), create_event("flaky")])
+            .await
+            .unwrap()
+            .await
+            .unwrap();
+        assert_eq!(res.published_events(), 2);
+        assert_eq!(res.ok_handlers(), 0);
+        assert_eq!(res.err_handlers(), 2);
+        assert_eq!(res.retried_events(), 2);
+        assert_eq!(res.dead_lettered_events(), 2);
+        assert_eq!(counter.count("flaky"), 3);
+
+        let dead_letters = dead_letter_repo.find_all().await.unwrap();
+        assert_eq!(dead_letters.len(), 2);
+
+        let dead_letter = dead_letters
+            .into_iter()
+            .find(|dead_letter| dead_letter.handler_topic() == "flaky")
+            .unwrap();
+        assert_eq!(dead_letter.attempts(), 3);
+        assert_eq!(dead_letter.event().topic(), "flaky");
+        assert_eq!(dead_letter.errors()[0], "event_publisher.handler_error");
+
+        // Only the failing handler receives the replayed event.
+        let res = eb.replay(&dead_letter).await.unwrap().await.unwrap();
+        assert_eq!(res.ok_handlers(), 1);
+        assert_eq!(res.err_handlers(), 0);
+        assert_eq!(counter.count("flaky"), 4);
+    }
+
+    #[tokio::test]
+    async fn retried_events_are_counted_once() {
+        let counter = Arc::new(Counter::new());
+        let eb = InMemEventBus::new();
+        for _ in 0..2 {
+            eb.subscribe(Box::new(FlakyHandler {
+                counter: counter.clone(),
+                failures: 1,
+            }))
+            .await
+            .unwrap();
+        }
+
+        let res = eb
+            .publish(create_event("flaky"))
+            .await
+            .unwrap()
+            .await
+            .unwrap();
+        assert_eq!(res.ok_handlers(), 2);
+        assert_eq!(res.retried_events(), 1);
+        assert_eq!(counter.count("flaky"), 4);
+    }
+
+    #[tokio::test]
+    async fn replay_to_wildcard_subscription() {
+        let dead_letter_repo = Arc::new(InMemDeadLetterRepository::new());
+        let eb = InMemEventBus::new()
+            .retry_policy(RetryPolicy::exponential(0, Duration::from_millis(1)))
+            .dead_letter_repo(dead_letter_repo.clone());
+        eb.subscribe(Box::new(ErrorHandler)).await.unwrap();
+        let handler = BasicHandler::new("error.+");
+        eb.subscribe(Box::new(handler.clone())).await.unwrap();
+
+        eb.publish(create_event("error.created"))
+            .await
+            .unwrap()
+            .await
+            .unwrap();
+        assert_eq!(handler.counter().count("error.created"), 1);
+
+        let dead_letters = dead_letter_repo.find_all().await.unwrap();
+        assert_eq!(dead_letters.len(), 1);
+        assert_eq!(dead_letters[0].handler_topic(), "error.*");
+
+        // The handler subscribed with "error.*" receives it again, the other one doesn't.
+        let res = eb.replay(&dead_letters[0]).await.unwrap().await.unwrap();
+        assert_eq!(res.activated_handlers(), 1);
+        assert_eq!(res.err_handlers(), 1);
+        assert_eq!(handler.counter().count("error.created"), 1);
+    }
+
+    #[tokio::test]
+    async fn invalid_topic_regex() {
+        let eb = InMemEventBus::new();
+        assert!(eb
+            .subscribe(Box::new(BasicHandler::new("*")))
+            .await
+            .is_err());
+        assert_eq!(eb.subscriptions.read().await.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn slow_handlers_do_not_block_others() {
+        let eb = InMemEventBus::new();
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        eb.subscribe(Box::new(SlowHandler {
+            received: received.clone(),
+            delay: Duration::from_secs(60),
+        }))
+        .await
+        .unwrap();
+        let handler = BasicHandler::new("slow.*");
+        eb.subscribe(Box::new(handler.clone())).await.unwrap();
+
+        let slow_res = eb.publish(create_event("slow.created")).await.unwrap();
+
+        let res = tokio::time::timeout(
+            Duration::from_secs(1),
+            eb.publish(create_event("slow.updated")).await.unwrap(),
+        )
+        .await;
+
+        // The publication waits for the slow handler, but the fast one already handled both
+        // events.
+        assert!(res.is_err());
+        assert_eq!(handler.counter().count("slow.created"), 1);
+        assert_eq!(handler.counter().count("slow.updated"), 1);
+        assert!(received.lock().unwrap().is_empty());
+
+        drop(slow_res);
+    }
+
+    #[tokio::test]
+    async fn keep_order_per_handler() {
+        let eb = InMemEventBus::new().max_concurrency(2);
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        eb.subscribe(Box::new(SlowHandler {
+            received: received.clone(),
+            delay: Duration::from_millis(1),
+        }))
+        .await
+        .unwrap();
+
+        let topics: Vec<String> = (0..20).map(|i| format!("slow.{}", i)).collect();
+        let res = eb
+            .publish_all(topics.iter().map(|topic| create_event(topic)).collect())
+            .await
+            .unwrap()
+            .await
+            .unwrap();
+        assert_eq!(res.published_events(), 20);
+        assert_eq!(res.ok_handlers(), 20);
+        assert_eq!(*received.lock().unwrap(), topics);
+    }
+
+    #[tokio::test]
+    async fn backpressure() {
+        let eb = InMemEventBus::new().queue_capacity(1);
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        eb.subscribe(Box::new(SlowHandler {
+            received: received.clone(),
+            delay: Duration::from_secs(60),
+        }))
+        .await
+        .unwrap();
+
+        // The first event is taken by the worker and the second one fills the queue, so the
+        // third one can't be enqueued until the handler makes progress.
+        let res = tokio::time::timeout(
+            Duration::from_secs(1),
+            eb.publish_all(vec![
+                create_event("slow.1"),
+                create_event("slow.2"),
+                create_event("slow.3"),
+            ]),
+        )
+        .await;
+        assert!(res.is_err());
+    }
+
+    #[tokio::test]
+    async fn events_published_by_handlers_are_caused_by_the_handled_event() {
+        let eb = InMemEventBus::new();
+        let caused = Arc::new(std::sync::Mutex::new(Vec::new()));
+        eb.subscribe(Box::new(CausedEventsHandler {
+            caused: caused.clone(),
+        }))
+        .await
+        .unwrap();
+
+        let cause = EventMetadata::from_request("request-1")
+            .scope(async {
+                EventMetadata::set_current_actor("user-1");
+                create_event("cause")
+            })
+            .await;
+        eb.publish(cause.clone()).await.unwrap().await.unwrap();
+
+        let caused = caused.lock().unwrap();
+        assert_eq!(caused.len(), 1);
+        assert_eq!(caused[0].correlation_id(), Some("request-1"));
+        assert_eq!(caused[0].causation_id(), Some(cause.id().value()));
+        assert_eq!(caused[0].actor_id(), Some("user-1"));
+    }
 }
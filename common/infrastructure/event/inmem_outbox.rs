@@ -0,0 +1,161 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio::sync::Mutex;
+
+use crate::error::Error;
+use crate::event::{Event, EventId, Outbox};
+use crate::result::Result;
+
+struct OutboxEntry {
+    event: Event,
+    claimed_until: Option<DateTime<Utc>>,
+    published_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Default)]
+pub struct InMemOutbox {
+    entries: Mutex<Vec<OutboxEntry>>,
+}
+
+impl InMemOutbox {
+    pub fn new() -> Self {
+        InMemOutbox {
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub async fn published(&self) -> Vec<Event> {
+        self.entries
+            .lock()
+            .await
+            .iter()
+            .filter(|entry| entry.published_at.is_some())
+            .map(|entry| entry.event.clone())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Outbox for InMemOutbox {
+    async fn enqueue(&self, events: &[Event]) -> Result<()> {
+        let mut entries = self.entries.lock().await;
+        for event in events.iter() {
+            entries.push(OutboxEntry {
+                event: event.clone(),
+                claimed_until: None,
+                published_at: None,
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn pending(&self, limit: usize) -> Result<Vec<Event>> {
+        Ok(self
+            .entries
+            .lock()
+            .await
+            .iter()
+            .filter(|entry| entry.published_at.is_none())
+            .take(limit)
+            .map(|entry| entry.event.clone())
+            .collect())
+    }
+
+    async fn claim(&self, limit: usize, lease: Duration) -> Result<Vec<Event>> {
+        let now = Utc::now();
+        let claimed_until = now
+            + chrono::Duration::from_std(lease)
+                .map_err(|err| Error::internal("outbox", "claim").wrap_raw(err))?;
+
+        Ok(self
+            .entries
+            .lock()
+            .await
+            .iter_mut()
+            .filter(|entry| entry.published_at.is_none())
+            .filter(|entry| entry.claimed_until.map_or(true, |until| until < now))
+            .take(limit)
+            .map(|entry| {
+                entry.claimed_until = Some(claimed_until);
+                entry.event.clone()
+            })
+            .collect())
+    }
+
+    async fn mark_as_published(&self, ids: &[EventId]) -> Result<()> {
+        let now = Utc::now();
+        let mut entries = self.entries.lock().await;
+        for entry in entries.iter_mut() {
+            if ids.contains(entry.event.id()) {
+                entry.published_at = Some(now);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn enqueue_and_publish() {
+        let outbox = InMemOutbox::new();
+        let events = vec![
+            Event::new("topic", "one", json!({})),
+            Event::new("topic", "two", json!({})),
+            Event::new("topic", "three", json!({})),
+        ];
+        outbox.enqueue(&events).await.unwrap();
+
+        let pending = outbox.pending(2).await.unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].code(), "one");
+        assert_eq!(pending[1].code(), "two");
+
+        outbox
+            .mark_as_published(&[pending[0].id().clone()])
+            .await
+            .unwrap();
+
+        let pending = outbox.pending(10).await.unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].code(), "two");
+        assert_eq!(outbox.published().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn claimed_events_are_skipped() {
+        let outbox = InMemOutbox::new();
+        let events = vec![
+            Event::new("topic", "one", json!({})),
+            Event::new("topic", "two", json!({})),
+            Event::new("topic", "three", json!({})),
+        ];
+        outbox.enqueue(&events).await.unwrap();
+
+        let lease = Duration::from_secs(60);
+        let claimed = outbox.claim(2, lease).await.unwrap();
+        assert_eq!(claimed.len(), 2);
+        assert_eq!(claimed[0].code(), "one");
+
+        // Another relay only gets the events that were not claimed.
+        let claimed = outbox.claim(10, lease).await.unwrap();
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed[0].code(), "three");
+        assert!(outbox.claim(10, lease).await.unwrap().is_empty());
+
+        // Events are claimed again when the lease expires.
+        outbox.entries.lock().await[0].claimed_until =
+            Some(Utc::now() - chrono::Duration::seconds(1));
+        let claimed = outbox.claim(10, lease).await.unwrap();
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed[0].code(), "one");
+    }
+}
@@ -1,22 +1,31 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 
 use crate::cache::Cache;
 
-use crate::event::{Event, EventId, EventRepository};
+use crate::event::{Event, EventId, EventOrder, EventPage, EventRepository, UpcasterRegistry};
 use crate::infrastructure::cache::InMemCache;
 use crate::result::Result;
 
 pub struct InMemEventRepository {
     cache: InMemCache<EventId, Event>,
+    upcasters: Arc<UpcasterRegistry>,
 }
 
 impl InMemEventRepository {
     pub fn new() -> Self {
         InMemEventRepository {
             cache: InMemCache::new(),
+            upcasters: Arc::new(UpcasterRegistry::new()),
         }
     }
+
+    pub fn upcasters(mut self, upcasters: Arc<UpcasterRegistry>) -> Self {
+        self.upcasters = upcasters;
+        self
+    }
 }
 
 impl Default for InMemEventRepository {
@@ -29,16 +38,216 @@ impl Default for InMemEventRepository {
 impl EventRepository for InMemEventRepository {
     async fn search(
         &self,
-        _after_id: Option<&EventId>,
-        _topic: Option<&String>,
-        _code: Option<&String>,
-        _from: Option<&DateTime<Utc>>,
-        _to: Option<&DateTime<Utc>>,
-    ) -> Result<Vec<Event>> {
-        Ok(self.cache.all().await)
+        after_id: Option<&EventId>,
+        topic: Option<&String>,
+        code: Option<&String>,
+        correlation_id: Option<&String>,
+        from: Option<&DateTime<Utc>>,
+        to: Option<&DateTime<Utc>>,
+        limit: Option<usize>,
+        order: EventOrder,
+    ) -> Result<EventPage> {
+        let mut events = self.cache.all().await;
+        events.sort_by(|a, b| {
+            a.timestamp()
+                .cmp(b.timestamp())
+                .then_with(|| a.id().value().cmp(b.id().value()))
+        });
+        if order == EventOrder::Desc {
+            events.reverse();
+        }
+
+        if let Some(after_id) = after_id {
+            // Same as Postgres: an unknown cursor doesn't match any event.
+            events = match events.iter().position(|event| event.id() == after_id) {
+                Some(index) => events.split_off(index + 1),
+                None => Vec::new(),
+            };
+        }
+
+        let events: Vec<Event> = events
+            .into_iter()
+            .filter(|event| topic.map_or(true, |topic| event.topic() == topic))
+            .filter(|event| code.map_or(true, |code| event.code() == code))
+            .filter(|event| {
+                correlation_id.map_or(true, |correlation_id| {
+                    event.correlation_id() == Some(correlation_id.as_str())
+                        || event.id().value() == correlation_id
+                })
+            })
+            .filter(|event| from.map_or(true, |from| event.timestamp() >= from))
+            .filter(|event| to.map_or(true, |to| event.timestamp() <= to))
+            .take(limit.map_or(usize::MAX, |limit| limit + 1))
+            .collect();
+
+        Ok(EventPage::new(self.upcasters.upcast_all(events)?, limit))
     }
 
     async fn save(&self, event: &Event) -> Result<()> {
         self.cache.set(event.id().clone(), event.clone()).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Duration;
+    use serde_json::json;
+
+    use crate::event::EventMetadata;
+
+    async fn repository() -> (InMemEventRepository, Vec<Event>) {
+        let repo = InMemEventRepository::new();
+        let now = Utc::now();
+
+        let mut events = Vec::new();
+        for i in 0..5 {
+            let event = Event::build(
+                EventId::new(format!("event-{}", i)).unwrap(),
+                if i % 2 == 0 { "even" } else { "odd" }.to_owned(),
+                "code".to_owned(),
+                now + Duration::seconds(i),
+                json!({}),
+                1,
+            );
+            repo.save(&event).await.unwrap();
+            events.push(event);
+        }
+
+        (repo, events)
+    }
+
+    fn ids(events: &[Event]) -> Vec<String> {
+        events.iter().map(|event| event.id().to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn paginate_ascending() {
+        let (repo, events) = repository().await;
+
+        let page = repo
+            .search(None, None, None, None, None, None, Some(2), EventOrder::Asc)
+            .await
+            .unwrap();
+        assert_eq!(ids(page.events()), ids(&events[0..2]));
+        assert_eq!(page.next_cursor(), Some(events[1].id()));
+
+        let page = repo
+            .search(
+                page.next_cursor(),
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(2),
+                EventOrder::Asc,
+            )
+            .await
+            .unwrap();
+        assert_eq!(ids(page.events()), ids(&events[2..4]));
+
+        let page = repo
+            .search(
+                page.next_cursor(),
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(2),
+                EventOrder::Asc,
+            )
+            .await
+            .unwrap();
+        assert_eq!(ids(page.events()), ids(&events[4..5]));
+        assert!(page.next_cursor().is_none());
+    }
+
+    #[tokio::test]
+    async fn paginate_descending_with_filters() {
+        let (repo, events) = repository().await;
+        let topic = "even".to_owned();
+
+        let page = repo
+            .search(
+                None,
+                Some(&topic),
+                None,
+                None,
+                None,
+                None,
+                Some(2),
+                EventOrder::Desc,
+            )
+            .await
+            .unwrap();
+        assert_eq!(ids(page.events()), vec!["event-4", "event-2"]);
+        assert_eq!(page.next_cursor(), Some(events[2].id()));
+
+        let page = repo
+            .search(
+                page.next_cursor(),
+                Some(&topic),
+                None,
+                None,
+                None,
+                None,
+                Some(2),
+                EventOrder::Desc,
+            )
+            .await
+            .unwrap();
+        assert_eq!(ids(page.events()), vec!["event-0"]);
+        assert!(page.next_cursor().is_none());
+    }
+
+    #[tokio::test]
+    async fn unknown_cursor() {
+        let (repo, _) = repository().await;
+
+        let page = repo
+            .search(
+                Some(&EventId::new("unknown").unwrap()),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                EventOrder::Asc,
+            )
+            .await
+            .unwrap();
+        assert!(page.events().is_empty());
+    }
+
+    #[tokio::test]
+    async fn search_by_correlation_id() {
+        let (repo, _) = repository().await;
+
+        let cause = Event::new("odd", "cause", json!({}));
+        repo.save(&cause).await.unwrap();
+        let effect = EventMetadata::caused_by(&cause)
+            .scope(async { Event::new("even", "effect", json!({})) })
+            .await;
+        repo.save(&effect).await.unwrap();
+
+        let correlation_id = cause.id().to_string();
+        let page = repo
+            .search(
+                None,
+                None,
+                None,
+                Some(&correlation_id),
+                None,
+                None,
+                None,
+                EventOrder::Asc,
+            )
+            .await
+            .unwrap();
+        assert_eq!(ids(page.events()), ids(&[cause, effect]));
+    }
+}
@@ -1,6 +1,18 @@
+mod inmem_dead_letter_repository;
 mod inmem_event_bus;
+mod inmem_outbox;
 mod inmem_repository;
+mod outbox_relay;
+mod postgres_dead_letter_repository;
+mod postgres_event_bus;
 mod postgres_event_repository;
+mod postgres_outbox;
+pub use inmem_dead_letter_repository::*;
 pub use inmem_event_bus::*;
+pub use inmem_outbox::*;
 pub use inmem_repository::*;
+pub use outbox_relay::*;
+pub use postgres_dead_letter_repository::*;
+pub use postgres_event_bus::*;
 pub use postgres_event_repository::*;
+pub use postgres_outbox::*;
@@ -0,0 +1,246 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::Error;
+use crate::event::{EventPublisher, Outbox, UpcasterRegistry};
+use crate::result::Result;
+
+/// OutboxRelay drains pending events from the outbox into the event bus. An event is marked as
+/// published only after the bus dispatched it to its handlers, so delivery is at-least-once.
+/// Events are claimed before relaying them, so several instances can run their relays.
+pub struct OutboxRelay {
+    outbox: Arc<dyn Outbox>,
+    event_pub: Arc<dyn EventPublisher>,
+    upcasters: Arc<UpcasterRegistry>,
+    batch_size: usize,
+    lease: Duration,
+}
+
+impl OutboxRelay {
+    pub fn new(outbox: Arc<dyn Outbox>, event_pub: Arc<dyn EventPublisher>) -> Self {
+        OutboxRelay {
+            outbox,
+            event_pub,
+            upcasters: Arc::new(UpcasterRegistry::new()),
+            batch_size: 100,
+            lease: Duration::from_secs(60),
+        }
+    }
+
+    /// Events written before a schema change are published in the current schema.
+    pub fn upcasters(mut self, upcasters: Arc<UpcasterRegistry>) -> Self {
+        self.upcasters = upcasters;
+        self
+    }
+
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Time a batch is claimed for. It must be longer than the time the bus takes to dispatch
+    /// it, or another relay could publish it again.
+    pub fn lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+
+    /// Relays one batch of pending events and returns how many were published.
+    pub async fn relay(&self) -> Result<usize> {
+        let events = self.outbox.claim(self.batch_size, self.lease).await?;
+        if events.is_empty() {
+            return Ok(0);
+        }
+
+        let events = self.upcasters.upcast_all(events)?;
+
+        let ids: Vec<_> = events.iter().map(|event| event.id().clone()).collect();
+
+        let rx = self.event_pub.publish_all(events).await?;
+        rx.await
+            .map_err(|err| Error::internal("outbox_relay", "publication_result").wrap_raw(err))?;
+
+        self.outbox.mark_as_published(&ids).await?;
+
+        Ok(ids.len())
+    }
+
+    /// Relays pending events until the outbox is empty.
+    pub async fn relay_all(&self) -> Result<usize> {
+        let mut total = 0;
+        loop {
+            let published = self.relay().await?;
+            if published == 0 {
+                return Ok(total);
+            }
+            total += published;
+        }
+    }
+
+    pub fn start(self: Arc<Self>, interval: Duration) {
+        tokio::spawn(async move {
+            loop {
+                if let Err(err) = self.relay_all().await {
+                    println!("{:?}", err);
+                }
+
+                tokio::time::delay_for(interval).await;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_trait::async_trait;
+    use serde_json::{json, Value};
+    use tokio::sync::Mutex;
+
+    use crate::event::{Event, EventHandler, EventSubscriber, Upcaster};
+    use crate::infrastructure::event::{InMemEventBus, InMemOutbox};
+    use crate::mocks::Counter;
+
+    struct CounterHandler {
+        counter: Arc<Counter>,
+    }
+
+    struct RecorderHandler {
+        events: Arc<Mutex<Vec<Event>>>,
+    }
+
+    #[async_trait]
+    impl EventHandler for RecorderHandler {
+        fn topic(&self) -> &str {
+            ".*"
+        }
+
+        async fn handle(&mut self, event: &Event) -> Result<bool> {
+            self.events.lock().await.push(event.clone());
+            Ok(true)
+        }
+    }
+
+    async fn recorded_bus() -> (Arc<InMemEventBus>, Arc<Mutex<Vec<Event>>>) {
+        let event_bus = Arc::new(InMemEventBus::new());
+        let events = Arc::new(Mutex::new(Vec::new()));
+        event_bus
+            .subscribe(Box::new(RecorderHandler {
+                events: events.clone(),
+            }))
+            .await
+            .unwrap();
+
+        (event_bus, events)
+    }
+
+    struct RenameName;
+
+    impl Upcaster for RenameName {
+        fn topic(&self) -> &str {
+            "entity"
+        }
+
+        fn version(&self) -> u32 {
+            1
+        }
+
+        fn upcast(&self, _code: &str, payload: Value) -> Result<Value> {
+            Ok(json!({ "fullname": payload["name"] }))
+        }
+    }
+
+    #[async_trait]
+    impl EventHandler for CounterHandler {
+        fn topic(&self) -> &str {
+            ".*"
+        }
+
+        async fn handle(&mut self, event: &Event) -> Result<bool> {
+            self.counter.inc(event.code());
+            Ok(true)
+        }
+    }
+
+    #[tokio::test]
+    async fn relay_pending_events() {
+        let outbox = Arc::new(InMemOutbox::new());
+        let event_bus = Arc::new(InMemEventBus::new());
+        let counter = Arc::new(Counter::new());
+        event_bus
+            .subscribe(Box::new(CounterHandler {
+                counter: counter.clone(),
+            }))
+            .await
+            .unwrap();
+
+        outbox
+            .enqueue(&[
+                Event::new("entity", "created", json!({})),
+                Event::new("entity", "updated", json!({})),
+                Event::new("entity", "deleted", json!({})),
+            ])
+            .await
+            .unwrap();
+
+        let relay = OutboxRelay::new(outbox.clone(), event_bus).batch_size(2);
+        assert_eq!(relay.relay().await.unwrap(), 2);
+        assert_eq!(relay.relay_all().await.unwrap(), 1);
+        assert_eq!(relay.relay_all().await.unwrap(), 0);
+
+        assert_eq!(counter.count("created"), 1);
+        assert_eq!(counter.count("updated"), 1);
+        assert_eq!(counter.count("deleted"), 1);
+        assert!(outbox.pending(10).await.unwrap().is_empty());
+        assert_eq!(outbox.published().await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn concurrent_relays() {
+        let outbox = Arc::new(InMemOutbox::new());
+        let (event_bus, events) = recorded_bus().await;
+        outbox
+            .enqueue(&[
+                Event::new("entity", "created", json!({})),
+                Event::new("entity", "updated", json!({})),
+                Event::new("entity", "deleted", json!({})),
+            ])
+            .await
+            .unwrap();
+
+        let relay1 = OutboxRelay::new(outbox.clone(), event_bus.clone()).batch_size(2);
+        let relay2 = OutboxRelay::new(outbox.clone(), event_bus).batch_size(2);
+
+        // The events claimed by a relay are not published by the other one, even before they
+        // are marked as published.
+        let claimed = outbox.claim(2, Duration::from_secs(60)).await.unwrap();
+        assert_eq!(claimed.len(), 2);
+        assert_eq!(relay1.relay_all().await.unwrap(), 1);
+        assert_eq!(relay2.relay_all().await.unwrap(), 0);
+
+        let events = events.lock().await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].code(), "deleted");
+    }
+
+    #[tokio::test]
+    async fn upcast_pending_events() {
+        let outbox = Arc::new(InMemOutbox::new());
+        let (event_bus, events) = recorded_bus().await;
+        let mut upcasters = UpcasterRegistry::new();
+        upcasters.register(Box::new(RenameName)).unwrap();
+
+        outbox
+            .enqueue(&[Event::new("entity", "created", json!({ "name": "Name" }))])
+            .await
+            .unwrap();
+
+        let relay = OutboxRelay::new(outbox, event_bus).upcasters(Arc::new(upcasters));
+        assert_eq!(relay.relay_all().await.unwrap(), 1);
+
+        let events = events.lock().await;
+        assert_eq!(events[0].version(), 2);
+        assert_eq!(events[0].payload(), json!({ "fullname": "Name" }));
+    }
+}
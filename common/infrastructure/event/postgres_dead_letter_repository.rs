@@ -0,0 +1,158 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use tokio_postgres::row::Row;
+use uuid::Uuid;
+
+use crate::error::Error;
+use crate::event::{DeadLetter, DeadLetterId, DeadLetterRepository, Event, EventId, EventMetadata};
+use crate::infrastructure::postgres::PostgresClient;
+use crate::result::Result;
+
+impl DeadLetter {
+    fn from_row(row: Row) -> Result<Self> {
+        let id: Uuid = row.get("id");
+        let event_id: Uuid = row.get("event_id");
+        let topic: String = row.get("topic");
+        let code: String = row.get("code");
+        let timestamp: DateTime<Utc> = row.get("timestamp");
+        let payload: Value = row.get("payload");
+        let version: i32 = row.get("version");
+        let correlation_id: Option<String> = row.get("correlation_id");
+        let causation_id: Option<String> = row.get("causation_id");
+        let actor_id: Option<String> = row.get("actor_id");
+        let handler_topic: String = row.get("handler_topic");
+        let errors: Value = row.get("errors");
+        let attempts: i32 = row.get("attempts");
+        let failed_at: DateTime<Utc> = row.get("failed_at");
+
+        Ok(DeadLetter::build(
+            DeadLetterId::new(id.to_string())?,
+            Event::build(
+                EventId::new(event_id.to_string())?,
+                topic,
+                code,
+                timestamp,
+                payload,
+                version as u32,
+            )
+            .with_metadata(EventMetadata::new(correlation_id, causation_id, actor_id)),
+            handler_topic,
+            serde_json::from_value(errors)
+                .map_err(|err| Error::internal("dead_letter", "deserialize").wrap_raw(err))?,
+            attempts as u32,
+            failed_at,
+        ))
+    }
+}
+
+pub struct PostgresDeadLetterRepository {
+    client: PostgresClient,
+}
+
+impl PostgresDeadLetterRepository {
+    pub fn new(client: PostgresClient) -> Self {
+        PostgresDeadLetterRepository { client }
+    }
+}
+
+#[async_trait]
+impl DeadLetterRepository for PostgresDeadLetterRepository {
+    async fn find_all(&self) -> Result<Vec<DeadLetter>> {
+        let rows = self
+            .client
+            .query("SELECT * FROM dead_letters ORDER BY failed_at ASC", &[])
+            .await
+            .map_err(|err| Error::not_found("dead_letter").wrap_raw(err))?;
+
+        let mut dead_letters = Vec::new();
+        for row in rows.into_iter() {
+            dead_letters.push(DeadLetter::from_row(row)?);
+        }
+
+        Ok(dead_letters)
+    }
+
+    async fn find_by_id(&self, id: &DeadLetterId) -> Result<DeadLetter> {
+        let row = self
+            .client
+            .query_one(
+                "SELECT * FROM dead_letters WHERE id = $1",
+                &[&id.to_uuid()?],
+            )
+            .await
+            .map_err(|err| Error::not_found("dead_letter").wrap_raw(err))?;
+
+        DeadLetter::from_row(row)
+    }
+
+    async fn save(&self, dead_letter: &DeadLetter) -> Result<()> {
+        let event = dead_letter.event();
+        let errors = serde_json::to_value(dead_letter.errors())
+            .map_err(|err| Error::internal("dead_letter", "serialize").wrap_raw(err))?;
+
+        self.client
+            .execute(
+                "INSERT INTO dead_letters (
+                    id,
+                    event_id,
+                    topic,
+                    code,
+                    timestamp,
+                    payload,
+                    version,
+                    correlation_id,
+                    causation_id,
+                    actor_id,
+                    handler_topic,
+                    errors,
+                    attempts,
+                    failed_at
+                ) VALUES (
+                    $1,
+                    $2,
+                    $3,
+                    $4,
+                    $5,
+                    $6,
+                    $7,
+                    $8,
+                    $9,
+                    $10,
+                    $11,
+                    $12,
+                    $13,
+                    $14
+                )",
+                &[
+                    &dead_letter.id().to_uuid()?,
+                    &event.id().to_uuid()?,
+                    &event.topic(),
+                    &event.code(),
+                    &event.timestamp(),
+                    &event.payload(),
+                    &(event.version() as i32),
+                    &event.correlation_id(),
+                    &event.causation_id(),
+                    &event.actor_id(),
+                    &dead_letter.handler_topic(),
+                    &errors,
+                    &(dead_letter.attempts() as i32),
+                    &dead_letter.failed_at(),
+                ],
+            )
+            .await
+            .map_err(|err| Error::new("dead_letter", "create").wrap_raw(err))?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: &DeadLetterId) -> Result<()> {
+        self.client
+            .execute("DELETE FROM dead_letters WHERE id = $1", &[&id.to_uuid()?])
+            .await
+            .map_err(|err| Error::new("dead_letter", "delete").wrap_raw(err))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,460 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::stream::{self, StreamExt};
+use tokio::sync::oneshot::{self, Receiver};
+use tokio::sync::{Mutex, Notify};
+use tokio_postgres::{AsyncMessage, Client};
+
+use crate::error::Error;
+use crate::event::{
+    DeadLetter, Event, EventHandler, EventPublisher, EventSubscriber, PublicationResult,
+    UpcasterRegistry,
+};
+use crate::infrastructure::event::{InMemEventBus, PostgresOutbox};
+use crate::infrastructure::postgres::PostgresClient;
+use crate::result::Result;
+
+/// Channel notified every time events are appended.
+const CHANNEL: &str = "events";
+
+/// Key of the advisory lock taken by publishers. Appending events one statement at a time makes
+/// sequences visible in order, so consumers never skip an event committed late.
+const PUBLISH_LOCK: i64 = 4_240_001;
+
+/// PostgresEventBus uses the events table as a durable queue shared by several instances.
+/// Published events are appended to the table and announced with `NOTIFY`. Each consumer group
+/// keeps the sequence of the last processed event, and the instances of a group take turns to
+/// process the next batch with their local handlers, so every event is processed once per group.
+pub struct PostgresEventBus {
+    client: PostgresClient,
+    consumer: Mutex<Client>,
+    notify: Arc<Notify>,
+    consumer_group: String,
+    local_bus: InMemEventBus,
+    upcasters: Arc<UpcasterRegistry>,
+    batch_size: usize,
+    poll_interval: Duration,
+}
+
+impl PostgresEventBus {
+    /// Events are published with `client`. A dedicated connection is opened to listen for new
+    /// events and consume them as part of `consumer_group`. A group created now starts after the
+    /// last stored event.
+    pub async fn connect(client: PostgresClient, consumer_group: &str) -> Result<Self> {
+        let notify = Arc::new(Notify::new());
+        let consumer = Self::listen(&client, &notify).await?;
+
+        consumer
+            .execute(
+                "INSERT INTO event_consumer_groups(name, last_sequence, updated_at)
+                SELECT $1, COALESCE(MAX(sequence), 0), $2 FROM events
+                ON CONFLICT (name) DO NOTHING",
+                &[&consumer_group, &Utc::now()],
+            )
+            .await
+            .map_err(|err| Error::internal("event_bus", "consumer_group").wrap_raw(err))?;
+
+        Ok(PostgresEventBus {
+            client,
+            consumer: Mutex::new(consumer),
+            notify,
+            consumer_group: consumer_group.to_owned(),
+            local_bus: InMemEventBus::new(),
+            upcasters: Arc::new(UpcasterRegistry::new()),
+            batch_size: 100,
+            poll_interval: Duration::from_secs(5),
+        })
+    }
+
+    /// Bus dispatching consumed events to the handlers of this instance. Concurrency, retries
+    /// and dead letters are configured on it.
+    pub fn local_bus(mut self, local_bus: InMemEventBus) -> Self {
+        self.local_bus = local_bus;
+        self
+    }
+
+    /// Stored events are migrated to the current version of their schema when they are consumed.
+    pub fn upcasters(mut self, upcasters: Arc<UpcasterRegistry>) -> Self {
+        self.upcasters = upcasters;
+        self
+    }
+
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Time to wait for a notification before checking for new events anyway.
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn consumer_group(&self) -> &str {
+        &self.consumer_group
+    }
+
+    /// Delivers a dead-lettered event again to the local handler that failed.
+    pub async fn replay(&self, dead_letter: &DeadLetter) -> Result<Receiver<PublicationResult>> {
+        self.local_bus.replay(dead_letter).await
+    }
+
+    /// Opens the connection used to consume events, and notifies `notify` every time events are
+    /// appended.
+    async fn listen(client: &PostgresClient, notify: &Arc<Notify>) -> Result<Client> {
+        let (consumer, mut connection) = client.pool().connector().connect().await?;
+
+        let listener = Arc::clone(notify);
+        tokio::spawn(async move {
+            let mut messages = stream::poll_fn(move |cx| connection.poll_message(cx));
+            while let Some(message) = messages.next().await {
+                match message {
+                    Ok(AsyncMessage::Notification(_)) => listener.notify(),
+                    Ok(_) => {}
+                    Err(err) => {
+                        eprintln!("error: {}", err);
+                        break;
+                    }
+                }
+            }
+        });
+
+        consumer
+            .batch_execute(&format!("LISTEN {}", CHANNEL))
+            .await
+            .map_err(|err| Error::internal("event_bus", "listen").wrap_raw(err))?;
+
+        Ok(consumer)
+    }
+
+    /// Processes the next batch of events of the consumer group and returns how many events
+    /// were processed. The group is locked until the batch is processed by the local handlers,
+    /// and it returns 0 when another instance is processing it.
+    pub async fn consume(&self) -> Result<usize> {
+        let mut consumer = self.consumer.lock().await;
+        if consumer.is_closed() {
+            *consumer = Self::listen(&self.client, &self.notify).await?;
+        }
+
+        let tx = consumer
+            .transaction()
+            .await
+            .map_err(|err| Error::internal("event_bus", "consume").wrap_raw(err))?;
+
+        let row = tx
+            .query(
+                "SELECT last_sequence FROM event_consumer_groups
+                WHERE name = $1
+                FOR UPDATE SKIP LOCKED",
+                &[&self.consumer_group],
+            )
+            .await
+            .map_err(|err| Error::internal("event_bus", "consume").wrap_raw(err))?
+            .into_iter()
+            .next();
+
+        let mut last_sequence: i64 = match row {
+            Some(row) => row.get("last_sequence"),
+            None => return Ok(0),
+        };
+
+        let rows = tx
+            .query(
+                &format!(
+                    "SELECT * FROM events
+                    WHERE sequence > $1
+                    ORDER BY sequence ASC
+                    LIMIT {}",
+                    self.batch_size,
+                ) as &str,
+                &[&last_sequence],
+            )
+            .await
+            .map_err(|err| Error::internal("event_bus", "consume").wrap_raw(err))?;
+
+        if rows.is_empty() {
+            return Ok(0);
+        }
+
+        let mut events = Vec::new();
+        for row in rows.into_iter() {
+            last_sequence = row.get("sequence");
+            events.push(self.upcasters.upcast(Event::from_row(row)?)?);
+        }
+        let processed = events.len();
+
+        // Failing handlers are retried and dead-lettered by the local bus, so the group always
+        // moves forward once every handler finished.
+        self.local_bus
+            .publish_all(events)
+            .await?
+            .await
+            .map_err(|err| Error::internal("event_bus", "publication_result").wrap_raw(err))?;
+
+        tx.execute(
+            "UPDATE event_consumer_groups
+            SET
+                last_sequence = $2,
+                updated_at = $3
+            WHERE
+                name = $1",
+            &[&self.consumer_group, &last_sequence, &Utc::now()],
+        )
+        .await
+        .map_err(|err| Error::internal("event_bus", "consume").wrap_raw(err))?;
+
+        tx.commit()
+            .await
+            .map_err(|err| Error::internal("event_bus", "consume").wrap_raw(err))?;
+
+        Ok(processed)
+    }
+
+    /// Consumes events as they are notified. It also checks every `poll_interval`, in case a
+    /// notification was missed or the group was busy in another instance.
+    pub fn start(self: Arc<Self>) {
+        tokio::spawn(async move {
+            loop {
+                match self.consume().await {
+                    Ok(0) => {
+                        if tokio::time::timeout(self.poll_interval, self.notify.notified())
+                            .await
+                            .is_err()
+                        {}
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        println!("{:?}", err);
+                        tokio::time::delay_for(self.poll_interval).await;
+                    }
+                }
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl EventPublisher for PostgresEventBus {
+    async fn publish(&self, event: Event) -> Result<Receiver<PublicationResult>> {
+        self.publish_all(vec![event]).await
+    }
+
+    /// Appends the events to the events table. Handlers are executed later by the consumer
+    /// groups, so the result only reports the published events.
+    async fn publish_all(&self, events: Vec<Event>) -> Result<Receiver<PublicationResult>> {
+        let published_events = events.len() as u32;
+
+        if !events.is_empty() {
+            self.client
+                .execute(
+                    &format!(
+                        "WITH publish_lock AS (
+                            SELECT pg_advisory_xact_lock($2)
+                        ), appended AS (
+                            INSERT INTO events(
+                                id,
+                                topic,
+                                code,
+                                timestamp,
+                                payload,
+                                version,
+                                correlation_id,
+                                causation_id,
+                                actor_id
+                            )
+                            SELECT
+                                e.id,
+                                e.topic,
+                                e.code,
+                                e.timestamp,
+                                e.payload,
+                                e.version,
+                                e.correlation_id,
+                                e.causation_id,
+                                e.actor_id
+                            FROM publish_lock, jsonb_to_recordset($1::jsonb) AS e(
+                                id UUID,
+                                topic VARCHAR,
+                                code VARCHAR,
+                                timestamp TIMESTAMPTZ,
+                                payload JSONB,
+                                version INTEGER,
+                                correlation_id VARCHAR,
+                                causation_id VARCHAR,
+                                actor_id VARCHAR
+                            )
+                            ON CONFLICT (id) DO NOTHING
+                            RETURNING sequence
+                        )
+                        SELECT pg_notify('{}', COALESCE(MAX(sequence), 0)::text)
+                        FROM appended",
+                        CHANNEL,
+                    ) as &str,
+                    &[&PostgresOutbox::event_records(&events)?, &PUBLISH_LOCK],
+                )
+                .await
+                .map_err(|err| Error::internal("event_bus", "publish").wrap_raw(err))?;
+        }
+
+        let (tx, rx) = oneshot::channel();
+        if tx
+            .send(PublicationResult {
+                published_events,
+                ..PublicationResult::default()
+            })
+            .is_err()
+        {}
+
+        Ok(rx)
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for PostgresEventBus {
+    async fn subscribe(&self, handler: Box<dyn EventHandler>) -> Result<bool> {
+        self.local_bus.subscribe(handler).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use crate::config::Config;
+    use crate::infrastructure::postgres::PostgresPool;
+    use crate::mocks::Counter;
+
+    // These tests need a throwaway Postgres with the migrations applied, configured through the
+    // same variables as the server. Run them with `cargo test -- --ignored`.
+
+    async fn client() -> PostgresClient {
+        PostgresClient::new(
+            PostgresPool::connect(&Config::load().unwrap())
+                .await
+                .unwrap(),
+        )
+    }
+
+    async fn bus(
+        consumer_group: &str,
+        counter: &Arc<Counter>,
+        topic: &str,
+    ) -> Arc<PostgresEventBus> {
+        let bus = PostgresEventBus::connect(client().await, consumer_group)
+            .await
+            .unwrap()
+            .poll_interval(Duration::from_millis(100));
+        bus.subscribe(Box::new(CounterHandler {
+            counter: Arc::clone(counter),
+            topic: topic.to_owned(),
+        }))
+        .await
+        .unwrap();
+
+        let bus = Arc::new(bus);
+        Arc::clone(&bus).start();
+        bus
+    }
+
+    async fn wait_for(counter: &Counter, name: &str, count: u32) {
+        for _ in 0..50 {
+            if counter.count(name) >= count {
+                break;
+            }
+            tokio::time::delay_for(Duration::from_millis(100)).await;
+        }
+    }
+
+    struct CounterHandler {
+        counter: Arc<Counter>,
+        topic: String,
+    }
+
+    #[async_trait]
+    impl EventHandler for CounterHandler {
+        fn topic(&self) -> &str {
+            &self.topic
+        }
+
+        async fn handle(&mut self, event: &Event) -> Result<bool> {
+            self.counter.inc(event.topic());
+            Ok(true)
+        }
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn process_each_event_once_per_consumer_group() {
+        let topic = format!("test-{}", Uuid::new_v4());
+        let group = format!("group-{}", Uuid::new_v4());
+        let other_group = format!("group-{}", Uuid::new_v4());
+
+        let counter = Arc::new(Counter::new());
+        let other_counter = Arc::new(Counter::new());
+
+        let first = bus(&group, &counter, &topic).await;
+        let _second = bus(&group, &counter, &topic).await;
+        let _other = bus(&other_group, &other_counter, &topic).await;
+
+        let events: Vec<Event> = (0..20)
+            .map(|i| Event::new(topic.as_str(), "created", json!({ "i": i })))
+            .collect();
+        let res = first.publish_all(events).await.unwrap().await.unwrap();
+        assert_eq!(res.published_events(), 20);
+
+        wait_for(&counter, &topic, 20).await;
+        wait_for(&other_counter, &topic, 20).await;
+
+        // Give the instances time to process duplicates, if any.
+        tokio::time::delay_for(Duration::from_millis(500)).await;
+        assert_eq!(counter.count(&topic), 20);
+        assert_eq!(other_counter.count(&topic), 20);
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn new_groups_start_after_last_event() {
+        let topic = format!("test-{}", Uuid::new_v4());
+
+        let counter = Arc::new(Counter::new());
+        let publisher = bus(&format!("group-{}", Uuid::new_v4()), &counter, &topic).await;
+        publisher
+            .publish(Event::new(topic.as_str(), "old", json!({})))
+            .await
+            .unwrap();
+
+        let late_counter = Arc::new(Counter::new());
+        let late = bus(&format!("group-{}", Uuid::new_v4()), &late_counter, &topic).await;
+        late.publish(Event::new(topic.as_str(), "new", json!({})))
+            .await
+            .unwrap();
+
+        wait_for(&counter, &topic, 2).await;
+        wait_for(&late_counter, &topic, 1).await;
+        tokio::time::delay_for(Duration::from_millis(500)).await;
+        assert_eq!(counter.count(&topic), 2);
+        assert_eq!(late_counter.count(&topic), 1);
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn publishing_the_same_event_twice_appends_it_once() {
+        let topic = format!("test-{}", Uuid::new_v4());
+        let counter = Arc::new(Counter::new());
+        let bus = bus(&format!("group-{}", Uuid::new_v4()), &counter, &topic).await;
+
+        let event = Event::new(topic.as_str(), "created", json!({}));
+        bus.publish(event.clone()).await.unwrap();
+        bus.publish(event).await.unwrap();
+
+        wait_for(&counter, &topic, 1).await;
+        tokio::time::delay_for(Duration::from_millis(500)).await;
+        assert_eq!(counter.count(&topic), 1);
+    }
+}
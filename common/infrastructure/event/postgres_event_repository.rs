@@ -4,21 +4,58 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde_json::Value;
 
-use tokio_postgres::Client;
+use tokio_postgres::row::Row;
 use uuid::Uuid;
 
 use crate::error::Error;
-use crate::event::{Event, EventId, EventRepository};
+use crate::event::{
+    Event, EventId, EventMetadata, EventOrder, EventPage, EventRepository, UpcasterRegistry,
+};
+use crate::infrastructure::postgres::PostgresClient;
 use crate::result::Result;
 use crate::sql::where_builder::WhereBuilder;
 
+impl Event {
+    pub(crate) fn from_row(row: Row) -> Result<Self> {
+        let id: Uuid = row.get("id");
+        let topic: String = row.get("topic");
+        let code: String = row.get("code");
+        let timestamp: DateTime<Utc> = row.get("timestamp");
+        let payload: Value = row.get("payload");
+        let version: i32 = row.get("version");
+        let correlation_id: Option<String> = row.get("correlation_id");
+        let causation_id: Option<String> = row.get("causation_id");
+        let actor_id: Option<String> = row.get("actor_id");
+
+        Ok(Event::build(
+            EventId::new(id.to_string())?,
+            topic,
+            code,
+            timestamp,
+            payload,
+            version as u32,
+        )
+        .with_metadata(EventMetadata::new(correlation_id, causation_id, actor_id)))
+    }
+}
+
 pub struct PostgresEventRepository {
-    client: Arc<Client>,
+    client: PostgresClient,
+    upcasters: Arc<UpcasterRegistry>,
 }
 
 impl PostgresEventRepository {
-    pub fn new(client: Arc<Client>) -> Self {
-        PostgresEventRepository { client }
+    pub fn new(client: PostgresClient) -> Self {
+        PostgresEventRepository {
+            client,
+            upcasters: Arc::new(UpcasterRegistry::new()),
+        }
+    }
+
+    /// Stored events are migrated to the current version of their schema when they are read.
+    pub fn upcasters(mut self, upcasters: Arc<UpcasterRegistry>) -> Self {
+        self.upcasters = upcasters;
+        self
     }
 }
 
@@ -29,26 +66,50 @@ impl EventRepository for PostgresEventRepository {
         after_id: Option<&EventId>,
         topic: Option<&String>,
         code: Option<&String>,
+        correlation_id: Option<&String>,
         from: Option<&DateTime<Utc>>,
         to: Option<&DateTime<Utc>>,
-    ) -> Result<Vec<Event>> {
-        let _after_id = after_id.map(|id| id.to_uuid()).transpose()?;
+        limit: Option<usize>,
+        order: EventOrder,
+    ) -> Result<EventPage> {
+        let after_id = after_id.map(|id| id.to_uuid()).transpose()?;
+
+        let (direction, comparison) = match order {
+            EventOrder::Asc => ("ASC", ">"),
+            EventOrder::Desc => ("DESC", "<"),
+        };
+        let after_id_statement = format!(
+            "(timestamp, id) {} (SELECT timestamp, id FROM events WHERE id = $$)",
+            comparison,
+        );
 
         let (sql, params) = WhereBuilder::new()
+            .add_param_opt(&after_id_statement, &after_id, after_id.is_some())
             .add_param_opt("topic = $$", &topic, topic.is_some())
             .add_param_opt("code = $$", &code, code.is_some())
+            .add_param_opt(
+                "(correlation_id = $$ OR id::text = $$)",
+                &correlation_id,
+                correlation_id.is_some(),
+            )
             .add_param_opt("timestamp >= $$", &from, from.is_some())
             .add_param_opt("timestamp <= $$", &to, to.is_some())
             .build();
 
+        // One more event is fetched to know if there is a next page.
+        let limit_sql = limit
+            .map(|limit| format!("LIMIT {}", limit + 1))
+            .unwrap_or_default();
+
         let rows = self
             .client
             .query(
                 &format!(
                     "SELECT * FROM events
                     {}
-                    ORDER BY timestamp ASC",
-                    sql,
+                    ORDER BY timestamp {}, id {}
+                    {}",
+                    sql, direction, direction, limit_sql,
                 ) as &str,
                 &params,
             )
@@ -58,22 +119,10 @@ impl EventRepository for PostgresEventRepository {
         let mut events = Vec::new();
 
         for row in rows.into_iter() {
-            let id: Uuid = row.get("id");
-            let topic: String = row.get("topic");
-            let code: String = row.get("code");
-            let timestamp: DateTime<Utc> = row.get("timestamp");
-            let payload: Value = row.get("payload");
-
-            events.push(Event::build(
-                EventId::new(id.to_string())?,
-                topic,
-                code,
-                timestamp,
-                payload,
-            ));
+            events.push(self.upcasters.upcast(Event::from_row(row)?)?);
         }
 
-        Ok(events)
+        Ok(EventPage::new(events, limit))
     }
 
     async fn save(&self, event: &Event) -> Result<()> {
@@ -84,13 +133,21 @@ impl EventRepository for PostgresEventRepository {
                     topic,
                     code,
                     timestamp,
-                    payload
+                    payload,
+                    version,
+                    correlation_id,
+                    causation_id,
+                    actor_id
                 ) VALUES (
                     $1,
                     $2,
                     $3,
                     $4,
-                    $5
+                    $5,
+                    $6,
+                    $7,
+                    $8,
+                    $9
                 )",
                 &[
                     &event.id().to_uuid()?,
@@ -98,6 +155,10 @@ impl EventRepository for PostgresEventRepository {
                     &event.code(),
                     &event.timestamp(),
                     &event.payload(),
+                    &(event.version() as i32),
+                    &event.correlation_id(),
+                    &event.causation_id(),
+                    &event.actor_id(),
                 ],
             )
             .await
@@ -0,0 +1,204 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use tokio_postgres::row::Row;
+use uuid::Uuid;
+
+use crate::error::Error;
+use crate::event::{is_replaying, Event, EventId, EventMetadata, Outbox};
+use crate::infrastructure::postgres::PostgresClient;
+use crate::result::Result;
+
+pub struct PostgresOutbox {
+    client: PostgresClient,
+}
+
+impl PostgresOutbox {
+    pub fn new(client: PostgresClient) -> Self {
+        PostgresOutbox { client }
+    }
+
+    /// Wraps a repository statement so the given events are inserted into the outbox by the same
+    /// statement, and therefore in the same transaction as the aggregate. Events must be passed
+    /// as the parameter at `events_param` using `PostgresOutbox::records`.
+    pub fn enlist(statement: &str, events_param: usize) -> String {
+        format!(
+            "WITH outbox_events AS (
+                INSERT INTO outbox(
+                    id,
+                    topic,
+                    code,
+                    timestamp,
+                    payload,
+                    version,
+                    correlation_id,
+                    causation_id,
+                    actor_id
+                )
+                SELECT
+                    id,
+                    topic,
+                    code,
+                    timestamp,
+                    payload,
+                    version,
+                    correlation_id,
+                    causation_id,
+                    actor_id
+                FROM jsonb_to_recordset(${}::jsonb) AS e(
+                    id UUID,
+                    topic VARCHAR,
+                    code VARCHAR,
+                    timestamp TIMESTAMPTZ,
+                    payload JSONB,
+                    version INTEGER,
+                    correlation_id VARCHAR,
+                    causation_id VARCHAR,
+                    actor_id VARCHAR
+                )
+            )
+            {}",
+            events_param, statement,
+        )
+    }
+
+    fn from_row(row: Row) -> Result<Event> {
+        let id: Uuid = row.get("id");
+        let topic: String = row.get("topic");
+        let code: String = row.get("code");
+        let timestamp: DateTime<Utc> = row.get("timestamp");
+        let payload: Value = row.get("payload");
+        let version: i32 = row.get("version");
+        let correlation_id: Option<String> = row.get("correlation_id");
+        let causation_id: Option<String> = row.get("causation_id");
+        let actor_id: Option<String> = row.get("actor_id");
+
+        Ok(Event::build(
+            EventId::new(id.to_string())?,
+            topic,
+            code,
+            timestamp,
+            payload,
+            version as u32,
+        )
+        .with_metadata(EventMetadata::new(correlation_id, causation_id, actor_id)))
+    }
+
+    /// Records of the events to enlist. Nothing is enlisted while replaying events: the events
+    /// recorded by handlers were already published when the events were first handled.
+    pub fn records(events: &[Event]) -> Result<Value> {
+        if is_replaying() {
+            return Ok(Value::Array(Vec::new()));
+        }
+
+        Self::event_records(events)
+    }
+
+    pub(crate) fn event_records(events: &[Event]) -> Result<Value> {
+        let mut records = Vec::new();
+        for event in events.iter() {
+            records.push(json!({
+                "id": event.id().to_uuid()?,
+                "topic": event.topic(),
+                "code": event.code(),
+                "timestamp": event.timestamp(),
+                "payload": event.payload(),
+                "version": event.version(),
+                "correlation_id": event.correlation_id(),
+                "causation_id": event.causation_id(),
+                "actor_id": event.actor_id(),
+            }));
+        }
+
+        Ok(Value::Array(records))
+    }
+}
+
+#[async_trait]
+impl Outbox for PostgresOutbox {
+    async fn enqueue(&self, events: &[Event]) -> Result<()> {
+        self.client
+            .execute(
+                &Self::enlist("SELECT 1", 1) as &str,
+                &[&Self::event_records(events)?],
+            )
+            .await
+            .map_err(|err| Error::new("outbox", "enqueue").wrap_raw(err))?;
+
+        Ok(())
+    }
+
+    async fn pending(&self, limit: usize) -> Result<Vec<Event>> {
+        let rows = self
+            .client
+            .query(
+                &format!(
+                    "SELECT * FROM outbox
+                    WHERE published_at IS NULL
+                    ORDER BY sequence ASC
+                    LIMIT {}",
+                    limit,
+                ) as &str,
+                &[],
+            )
+            .await
+            .map_err(|err| Error::new("outbox", "pending").wrap_raw(err))?;
+
+        rows.into_iter().map(Self::from_row).collect()
+    }
+
+    /// Rows are locked while they are claimed, and locked rows are skipped, so concurrent
+    /// relays never claim the same events.
+    async fn claim(&self, limit: usize, lease: Duration) -> Result<Vec<Event>> {
+        let mut rows = self
+            .client
+            .query(
+                &format!(
+                    "UPDATE outbox
+                    SET
+                        claimed_until = NOW() + make_interval(secs => $1)
+                    WHERE id IN (
+                        SELECT id FROM outbox
+                        WHERE
+                            published_at IS NULL
+                            AND (claimed_until IS NULL OR claimed_until < NOW())
+                        ORDER BY sequence ASC
+                        LIMIT {}
+                        FOR UPDATE SKIP LOCKED
+                    )
+                    RETURNING *",
+                    limit,
+                ) as &str,
+                &[&lease.as_secs_f64()],
+            )
+            .await
+            .map_err(|err| Error::new("outbox", "claim").wrap_raw(err))?;
+
+        rows.sort_by_key(|row| row.get::<_, i64>("sequence"));
+
+        rows.into_iter().map(Self::from_row).collect()
+    }
+
+    async fn mark_as_published(&self, ids: &[EventId]) -> Result<()> {
+        let mut uuids = Vec::new();
+        for id in ids.iter() {
+            uuids.push(id.to_uuid()?);
+        }
+
+        self.client
+            .execute(
+                "UPDATE outbox
+                SET
+                    published_at = $2
+                WHERE
+                    id = ANY($1)",
+                &[&uuids, &Utc::now()],
+            )
+            .await
+            .map_err(|err| Error::new("outbox", "mark_as_published").wrap_raw(err))?;
+
+        Ok(())
+    }
+}
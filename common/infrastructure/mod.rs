@@ -1,2 +1,5 @@
 pub mod cache;
+pub mod config;
 pub mod event;
+pub mod postgres;
+pub mod transaction;
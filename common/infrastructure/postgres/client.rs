@@ -0,0 +1,153 @@
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use tokio_postgres::row::Row;
+use tokio_postgres::types::ToSql;
+use tokio_postgres::ToStatement;
+
+use crate::error::Error;
+use crate::infrastructure::postgres::{PooledClient, PostgresPool};
+use crate::infrastructure::transaction::PostgresTransaction;
+use crate::transaction;
+
+/// ClientError is returned when a statement fails or when there is no connection available to
+/// execute it.
+#[derive(Debug)]
+pub enum ClientError {
+    Pool(Error),
+    Postgres(tokio_postgres::Error),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Pool(err) => write!(f, "{}", err),
+            ClientError::Postgres(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl error::Error for ClientError {}
+
+impl From<Error> for ClientError {
+    fn from(err: Error) -> Self {
+        ClientError::Pool(err)
+    }
+}
+
+impl From<tokio_postgres::Error> for ClientError {
+    fn from(err: tokio_postgres::Error) -> Self {
+        ClientError::Postgres(err)
+    }
+}
+
+/// PostgresClient is the client used by repositories. Statements are executed in the current
+/// `PostgresTransaction` when there is one, and with a connection of the pool otherwise. Opening
+/// the connection is retried once when it fails, but a statement that fails is never executed
+/// again: once it's sent, there is no way to know whether the database applied it.
+#[derive(Clone)]
+pub struct PostgresClient {
+    pool: PostgresPool,
+}
+
+impl PostgresClient {
+    pub fn new(pool: PostgresPool) -> Self {
+        PostgresClient { pool }
+    }
+
+    pub fn pool(&self) -> &PostgresPool {
+        &self.pool
+    }
+
+    async fn transaction_client(&self) -> Option<Arc<PooledClient>> {
+        if let Some(tx) = transaction::current() {
+            if let Some(tx) = tx.as_any().downcast_ref::<PostgresTransaction>() {
+                return tx.client().await;
+            }
+        }
+
+        None
+    }
+
+    /// Takes a connection of the pool, opening it again if the first attempt fails. The statement
+    /// hasn't been sent yet, so retrying can't execute it twice.
+    async fn pool_client(&self) -> Result<PooledClient, ClientError> {
+        match self.pool.get().await {
+            Err(err) if err.code() == "connect" || err.code() == "connect_timeout" => {
+                Ok(self.pool.get().await?)
+            }
+            res => Ok(res?),
+        }
+    }
+
+    pub async fn query<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Vec<Row>, ClientError>
+    where
+        T: ?Sized + ToStatement + Sync,
+    {
+        if let Some(client) = self.transaction_client().await {
+            return Ok(client.query(statement, params).await?);
+        }
+
+        Ok(self.pool_client().await?.query(statement, params).await?)
+    }
+
+    pub async fn query_one<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Row, ClientError>
+    where
+        T: ?Sized + ToStatement + Sync,
+    {
+        if let Some(client) = self.transaction_client().await {
+            return Ok(client.query_one(statement, params).await?);
+        }
+
+        Ok(self
+            .pool_client()
+            .await?
+            .query_one(statement, params)
+            .await?)
+    }
+
+    pub async fn execute<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<u64, ClientError>
+    where
+        T: ?Sized + ToStatement + Sync,
+    {
+        if let Some(client) = self.transaction_client().await {
+            return Ok(client.execute(statement, params).await?);
+        }
+
+        Ok(self.pool_client().await?.execute(statement, params).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use crate::infrastructure::postgres::PostgresConnector;
+
+    #[tokio::test]
+    async fn retry_connection_errors_once() {
+        let connector = PostgresConnector::new("host=127.0.0.1 port=1 user=omics sslmode=disable")
+            .unwrap()
+            .connect_timeout(Duration::from_millis(500));
+        let pool = PostgresPool::new(connector, 1, Duration::from_millis(100));
+        let client = PostgresClient::new(pool.clone());
+
+        assert!(client.execute("SELECT 1", &[]).await.is_err());
+        assert_eq!(pool.status().connection_errors, 2);
+    }
+}
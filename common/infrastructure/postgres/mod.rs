@@ -0,0 +1,4 @@
+mod client;
+mod pool;
+pub use client::*;
+pub use pool::*;
@@ -0,0 +1,308 @@
+use std::ops::Deref;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use native_tls::TlsConnector;
+use postgres_native_tls::{MakeTlsConnector, TlsStream};
+use serde::Serialize;
+use tokio::sync::Semaphore;
+use tokio::time;
+use tokio_postgres::{Client, Connection, Socket};
+
+use crate::config::Config;
+use crate::error::Error;
+use crate::result::Result;
+
+pub type PostgresConnection = Connection<Socket, TlsStream<Socket>>;
+
+/// PostgresConnector opens connections with the given parameters. TLS is negotiated according to
+/// the `sslmode` parameter (`disable`, `prefer` or `require`).
+pub struct PostgresConnector {
+    params: String,
+    tls: MakeTlsConnector,
+    connect_timeout: Duration,
+}
+
+impl PostgresConnector {
+    pub fn new<S: Into<String>>(params: S) -> Result<Self> {
+        let tls =
+            TlsConnector::new().map_err(|err| Error::internal("postgres", "tls").wrap_raw(err))?;
+
+        Ok(PostgresConnector {
+            params: params.into(),
+            tls: MakeTlsConnector::new(tls),
+            connect_timeout: Duration::from_secs(5),
+        })
+    }
+
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// Opens a connection. The caller must drive the returned `PostgresConnection`, usually by
+    /// spawning it.
+    pub async fn connect(&self) -> Result<(Client, PostgresConnection)> {
+        time::timeout(
+            self.connect_timeout,
+            tokio_postgres::connect(&self.params, self.tls.clone()),
+        )
+        .await
+        .map_err(|err| Error::internal("postgres", "connect_timeout").wrap_raw(err))?
+        .map_err(|err| Error::internal("postgres", "connect").wrap_raw(err))
+    }
+}
+
+/// PoolStatus reports the state of a pool and what happened since it was created.
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolStatus {
+    pub max_size: usize,
+    pub size: usize,
+    pub idle: usize,
+    pub in_use: usize,
+    pub opened: usize,
+    pub discarded: usize,
+    pub connection_errors: usize,
+    pub timeouts: usize,
+}
+
+struct Inner {
+    connector: PostgresConnector,
+    idle: Mutex<Vec<Client>>,
+    semaphore: Semaphore,
+    max_size: usize,
+    acquire_timeout: Duration,
+
+    size: AtomicUsize,
+    opened: AtomicUsize,
+    discarded: AtomicUsize,
+    connection_errors: AtomicUsize,
+    timeouts: AtomicUsize,
+}
+
+/// PostgresPool keeps up to `max_size` connections open. Connections are opened when they are
+/// needed, and closed connections are discarded when they are returned or taken from the pool,
+/// so the pool recovers by itself after the database restarts.
+#[derive(Clone)]
+pub struct PostgresPool {
+    inner: Arc<Inner>,
+}
+
+impl PostgresPool {
+    pub fn new(connector: PostgresConnector, max_size: usize, acquire_timeout: Duration) -> Self {
+        PostgresPool {
+            inner: Arc::new(Inner {
+                connector,
+                idle: Mutex::new(Vec::new()),
+                semaphore: Semaphore::new(max_size),
+                max_size,
+                acquire_timeout,
+                size: AtomicUsize::new(0),
+                opened: AtomicUsize::new(0),
+                discarded: AtomicUsize::new(0),
+                connection_errors: AtomicUsize::new(0),
+                timeouts: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Creates the pool configured in `config` and checks that the database is reachable.
+    pub async fn connect(config: &Config) -> Result<Self> {
+        let connector = PostgresConnector::new(config.postgres_params())?
+            .connect_timeout(config.postgres_connect_timeout());
+        let pool = PostgresPool::new(
+            connector,
+            config.postgres_pool_size(),
+            config.postgres_pool_timeout(),
+        );
+
+        pool.get().await.map_err(|err| {
+            Error::internal("postgres", "unavailable")
+                .set_message(format!(
+                    "cannot connect to postgres at {}:{}",
+                    config.postgres_host(),
+                    config.postgres_port()
+                ))
+                .wrap(err)
+        })?;
+
+        Ok(pool)
+    }
+
+    pub fn connector(&self) -> &PostgresConnector {
+        &self.inner.connector
+    }
+
+    /// Takes an idle connection, or opens a new one if there is none. It waits up to the acquire
+    /// timeout when every connection is in use.
+    pub async fn get(&self) -> Result<PooledClient> {
+        match time::timeout(self.inner.acquire_timeout, self.inner.semaphore.acquire()).await {
+            Ok(permit) => permit.forget(),
+            Err(err) => {
+                self.inner.timeouts.fetch_add(1, Ordering::Relaxed);
+                return Err(Error::internal("postgres", "pool_timeout").wrap_raw(err));
+            }
+        }
+
+        if let Some(client) = self.take_idle() {
+            return Ok(PooledClient {
+                client: Some(client),
+                pool: self.clone(),
+            });
+        }
+
+        match self.open().await {
+            Ok(client) => Ok(PooledClient {
+                client: Some(client),
+                pool: self.clone(),
+            }),
+            Err(err) => {
+                self.inner.semaphore.add_permits(1);
+                Err(err)
+            }
+        }
+    }
+
+    pub fn status(&self) -> PoolStatus {
+        let size = self.inner.size.load(Ordering::Relaxed);
+        let idle = self.inner.idle.lock().unwrap().len();
+
+        PoolStatus {
+            max_size: self.inner.max_size,
+            size,
+            idle,
+            in_use: size.saturating_sub(idle),
+            opened: self.inner.opened.load(Ordering::Relaxed),
+            discarded: self.inner.discarded.load(Ordering::Relaxed),
+            connection_errors: self.inner.connection_errors.load(Ordering::Relaxed),
+            timeouts: self.inner.timeouts.load(Ordering::Relaxed),
+        }
+    }
+
+    fn take_idle(&self) -> Option<Client> {
+        let mut idle = self.inner.idle.lock().unwrap();
+        while let Some(client) = idle.pop() {
+            if !client.is_closed() {
+                return Some(client);
+            }
+            self.discard();
+        }
+        None
+    }
+
+    async fn open(&self) -> Result<Client> {
+        let (client, connection) = match self.inner.connector.connect().await {
+            Ok(res) => res,
+            Err(err) => {
+                self.inner.connection_errors.fetch_add(1, Ordering::Relaxed);
+                return Err(err);
+            }
+        };
+
+        tokio::spawn(async move {
+            if let Err(err) = connection.await {
+                eprintln!("error: {}", err);
+            }
+        });
+
+        self.inner.size.fetch_add(1, Ordering::Relaxed);
+        self.inner.opened.fetch_add(1, Ordering::Relaxed);
+
+        Ok(client)
+    }
+
+    fn put_back(&self, client: Client) {
+        if client.is_closed() {
+            self.discard();
+        } else {
+            self.inner.idle.lock().unwrap().push(client);
+        }
+        self.inner.semaphore.add_permits(1);
+    }
+
+    fn discard(&self) {
+        self.inner.size.fetch_sub(1, Ordering::Relaxed);
+        self.inner.discarded.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// PooledClient is a connection taken from a pool. It's returned to the pool when dropped.
+pub struct PooledClient {
+    client: Option<Client>,
+    pool: PostgresPool,
+}
+
+impl Deref for PooledClient {
+    type Target = Client;
+
+    fn deref(&self) -> &Client {
+        self.client.as_ref().unwrap()
+    }
+}
+
+impl Drop for PooledClient {
+    fn drop(&mut self) {
+        if let Some(client) = self.client.take() {
+            self.pool.put_back(client);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unreachable_pool(max_size: usize) -> PostgresPool {
+        let connector = PostgresConnector::new("host=127.0.0.1 port=1 user=omics sslmode=disable")
+            .unwrap()
+            .connect_timeout(Duration::from_millis(500));
+        PostgresPool::new(connector, max_size, Duration::from_millis(100))
+    }
+
+    #[tokio::test]
+    async fn connection_error() {
+        let pool = unreachable_pool(1);
+
+        assert!(pool.get().await.is_err());
+        assert!(pool.get().await.is_err());
+
+        let status = pool.status();
+        assert_eq!(status.size, 0);
+        assert_eq!(status.connection_errors, 2);
+        assert_eq!(status.timeouts, 0);
+    }
+
+    #[tokio::test]
+    async fn acquire_timeout() {
+        let pool = unreachable_pool(0);
+
+        let err = pool.get().await.err().unwrap();
+        assert_eq!(err.code(), "pool_timeout");
+        assert_eq!(pool.status().timeouts, 1);
+        assert_eq!(pool.status().connection_errors, 0);
+    }
+
+    // Needs a Postgres configured through the same variables as the server. Run it with
+    // `cargo test -- --ignored`.
+    #[tokio::test]
+    #[ignore]
+    async fn reuse_connections() {
+        let pool = PostgresPool::connect(&Config::load().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(pool.status().opened, 1);
+        assert_eq!(pool.status().idle, 1);
+
+        {
+            let client = pool.get().await.unwrap();
+            client.execute("SELECT 1", &[]).await.unwrap();
+            assert_eq!(pool.status().in_use, 1);
+        }
+
+        let status = pool.status();
+        assert_eq!(status.opened, 1);
+        assert_eq!(status.idle, 1);
+        assert_eq!(status.in_use, 0);
+    }
+}
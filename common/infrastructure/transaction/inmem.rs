@@ -0,0 +1,139 @@
+use std::any::Any;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+
+use crate::result::Result;
+use crate::transaction::{self, Transaction, UnitOfWork};
+
+#[derive(Default)]
+pub struct InMemUnitOfWork;
+
+impl InMemUnitOfWork {
+    pub fn new() -> Self {
+        InMemUnitOfWork
+    }
+}
+
+impl UnitOfWork for InMemUnitOfWork {
+    fn transaction(&self) -> Arc<dyn Transaction> {
+        Arc::new(InMemTransaction::new())
+    }
+}
+
+/// InMemTransaction keeps the actions that undo the changes made inside of it. In-memory
+/// storages register them (see `InMemTransaction::on_rollback`) and they are executed in
+/// reverse order on rollback.
+#[derive(Default)]
+pub struct InMemTransaction {
+    undo_log: Mutex<Vec<BoxFuture<'static, ()>>>,
+}
+
+impl InMemTransaction {
+    pub fn new() -> Self {
+        InMemTransaction {
+            undo_log: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Registers `undo` in the current transaction, if it's an `InMemTransaction`. Otherwise the
+    /// change is final and `undo` is discarded.
+    pub fn on_rollback(undo: BoxFuture<'static, ()>) {
+        if let Some(tx) = transaction::current() {
+            if let Some(tx) = tx.as_any().downcast_ref::<InMemTransaction>() {
+                tx.undo_log.lock().unwrap().push(undo);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Transaction for InMemTransaction {
+    async fn begin(&self) -> Result<()> {
+        self.undo_log.lock().unwrap().clear();
+        Ok(())
+    }
+
+    async fn commit(&self) -> Result<()> {
+        self.undo_log.lock().unwrap().clear();
+        Ok(())
+    }
+
+    async fn rollback(&self) -> Result<()> {
+        let undo_log: Vec<_> = self.undo_log.lock().unwrap().drain(..).collect();
+        for undo in undo_log.into_iter().rev() {
+            undo.await;
+        }
+        Ok(())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + Sync) {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::cache::Cache;
+    use crate::error::Error;
+    use crate::infrastructure::cache::InMemCache;
+
+    #[tokio::test]
+    async fn commit() {
+        let uow = InMemUnitOfWork::new();
+        let cache = InMemCache::new();
+        cache.set("one", 1).await.unwrap();
+
+        let res = transaction::run(&uow, async {
+            cache.set("one", 10).await?;
+            cache.set("two", 2).await?;
+            Ok(())
+        })
+        .await;
+
+        assert!(res.is_ok());
+        assert_eq!(cache.get(&"one").await, Some(10));
+        assert_eq!(cache.get(&"two").await, Some(2));
+    }
+
+    #[tokio::test]
+    async fn rollback() {
+        let uow = InMemUnitOfWork::new();
+        let cache = InMemCache::new();
+        cache.set("one", 1).await.unwrap();
+        cache.set("three", 3).await.unwrap();
+
+        let res: Result<()> = transaction::run(&uow, async {
+            cache.set("one", 10).await?;
+            cache.set("two", 2).await?;
+            cache.delete(&"three").await?;
+            Err(Error::internal("transaction", "failed"))
+        })
+        .await;
+
+        assert!(res.is_err());
+        assert_eq!(cache.get(&"one").await, Some(1));
+        assert!(cache.get(&"two").await.is_none());
+        assert_eq!(cache.get(&"three").await, Some(3));
+    }
+
+    #[tokio::test]
+    async fn nested_runs_share_transaction() {
+        let uow = InMemUnitOfWork::new();
+        let cache = InMemCache::new();
+
+        let res: Result<()> = transaction::run(&uow, async {
+            transaction::run(&uow, async { cache.set("one", 1).await }).await?;
+            assert!(transaction::current().is_some());
+            Err(Error::internal("transaction", "failed"))
+        })
+        .await;
+
+        assert!(res.is_err());
+        assert!(transaction::current().is_none());
+        assert!(cache.get(&"one").await.is_none());
+    }
+}
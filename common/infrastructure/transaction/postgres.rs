@@ -0,0 +1,107 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+
+use crate::error::Error;
+use crate::infrastructure::postgres::{PooledClient, PostgresPool};
+use crate::result::Result;
+use crate::transaction::{Transaction, UnitOfWork};
+
+pub struct PostgresUnitOfWork {
+    pool: PostgresPool,
+}
+
+impl PostgresUnitOfWork {
+    /// Every transaction takes its own connection from `pool`, so statements of concurrent
+    /// requests are never mixed in the same transaction.
+    pub fn new(pool: PostgresPool) -> Self {
+        PostgresUnitOfWork { pool }
+    }
+}
+
+impl UnitOfWork for PostgresUnitOfWork {
+    fn transaction(&self) -> Arc<dyn Transaction> {
+        Arc::new(PostgresTransaction::new(self.pool.clone()))
+    }
+}
+
+/// PostgresTransaction holds a connection in which `BEGIN` was executed. Statements executed
+/// through a `PostgresClient` while it's the current transaction use that connection. If the
+/// transaction is dropped without finishing it, it's rolled back before the connection is
+/// reused.
+pub struct PostgresTransaction {
+    pool: PostgresPool,
+    client: Mutex<Option<Arc<PooledClient>>>,
+}
+
+impl PostgresTransaction {
+    pub fn new(pool: PostgresPool) -> Self {
+        PostgresTransaction {
+            pool,
+            client: Mutex::new(None),
+        }
+    }
+
+    /// Connection of the transaction, if it was begun and it's not finished yet.
+    pub async fn client(&self) -> Option<Arc<PooledClient>> {
+        self.client.lock().await.clone()
+    }
+
+    async fn finish(&self, statement: &str) -> Result<()> {
+        let client = self
+            .client
+            .lock()
+            .await
+            .take()
+            .ok_or_else(|| Error::internal("transaction", "not_begun"))?;
+
+        client
+            .batch_execute(statement)
+            .await
+            .map_err(|err| Error::internal("transaction", "finish").wrap_raw(err))
+    }
+}
+
+#[async_trait]
+impl Transaction for PostgresTransaction {
+    async fn begin(&self) -> Result<()> {
+        let mut current = self.client.lock().await;
+        if current.is_some() {
+            return Err(Error::internal("transaction", "already_begun"));
+        }
+
+        let client = self.pool.get().await?;
+        client
+            .batch_execute("BEGIN")
+            .await
+            .map_err(|err| Error::internal("transaction", "begin").wrap_raw(err))?;
+
+        *current = Some(Arc::new(client));
+
+        Ok(())
+    }
+
+    async fn commit(&self) -> Result<()> {
+        self.finish("COMMIT").await
+    }
+
+    async fn rollback(&self) -> Result<()> {
+        self.finish("ROLLBACK").await
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + Sync) {
+        self
+    }
+}
+
+impl Drop for PostgresTransaction {
+    fn drop(&mut self) {
+        if let Ok(mut client) = self.client.try_lock() {
+            if let Some(client) = client.take() {
+                tokio::spawn(async move { if client.batch_execute("ROLLBACK").await.is_err() {} });
+            }
+        }
+    }
+}
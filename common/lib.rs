@@ -10,5 +10,6 @@ pub mod model;
 pub mod prelude;
 pub mod request;
 pub mod result;
+pub mod retention;
 pub mod sql;
 pub mod transaction;
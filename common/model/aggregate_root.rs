@@ -55,6 +55,15 @@ impl<ID> AggregateRoot<ID> {
     pub fn delete(&mut self) {
         self.deleted_at = Some(Utc::now());
     }
+
+    pub fn restore(&mut self) {
+        self.deleted_at = None;
+        self.update();
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
 }
 
 impl<ID: PartialEq> PartialEq for AggregateRoot<ID> {
@@ -122,6 +131,13 @@ mod tests {
         e.base_mut().delete();
         assert!(e.base().deleted_at().is_some());
         assert!(e.base().deleted_at().unwrap() < &Utc::now());
+        assert!(e.base().is_deleted());
+
+        let updated_at = *e.base().updated_at().unwrap();
+        e.base_mut().restore();
+        assert!(e.base().deleted_at().is_none());
+        assert!(!e.base().is_deleted());
+        assert!(e.base().updated_at().unwrap() >= &updated_at);
     }
 
     #[test]
@@ -25,6 +25,10 @@ where
         }
         Ok(events)
     }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
 }
 
 impl<E> Clone for Events<E>
@@ -101,4 +105,13 @@ mod tests {
         assert_eq!(events[1].topic(), "agg_root.updated");
         assert_eq!(events[2].topic(), "agg_root.deleted");
     }
+
+    #[test]
+    fn clear() {
+        let mut ag = AggRoot::new();
+        ag.exec();
+
+        ag.events.clear();
+        assert!(ag.events().to_vec().unwrap().is_empty());
+    }
 }
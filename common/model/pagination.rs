@@ -1,9 +1,12 @@
+use crate::sql::Cursor;
+
 pub struct Pagination<T> {
     pub offset: usize,
     pub limit: usize,
-    pub total: usize,
-    pub matching_criteria: usize,
+    pub total: Option<usize>,
+    pub matching_criteria: Option<usize>,
     pub count: usize,
+    pub next_cursor: Option<Cursor>,
     pub items: Vec<T>,
 }
 
@@ -12,9 +15,23 @@ impl<T> Pagination<T> {
         Pagination {
             offset,
             limit,
-            total,
-            matching_criteria,
+            total: Some(total),
+            matching_criteria: Some(matching_criteria),
+            count: 0,
+            next_cursor: None,
+            items: Vec::new(),
+        }
+    }
+
+    /// Page whose records were not counted, like the pages after a cursor.
+    pub fn uncounted(offset: usize, limit: usize) -> Self {
+        Pagination {
+            offset,
+            limit,
+            total: None,
+            matching_criteria: None,
             count: 0,
+            next_cursor: None,
             items: Vec::new(),
         }
     }
@@ -27,11 +44,11 @@ impl<T> Pagination<T> {
         self.limit
     }
 
-    pub fn total(&self) -> usize {
+    pub fn total(&self) -> Option<usize> {
         self.total
     }
 
-    pub fn matching_criteria(&self) -> usize {
+    pub fn matching_criteria(&self) -> Option<usize> {
         self.matching_criteria
     }
 
@@ -39,6 +56,10 @@ impl<T> Pagination<T> {
         self.count
     }
 
+    pub fn next_cursor(&self) -> Option<&Cursor> {
+        self.next_cursor.as_ref()
+    }
+
     pub fn items(&self) -> &[T] {
         &self.items
     }
@@ -58,4 +79,9 @@ impl<T> Pagination<T> {
         self.count = self.items.len();
         self
     }
+
+    pub fn set_next_cursor(mut self, next_cursor: Option<Cursor>) -> Self {
+        self.next_cursor = next_cursor;
+        self
+    }
 }
@@ -1,6 +1,8 @@
 mod command;
+mod cursor;
 mod include;
 mod pagination;
 pub use command::*;
+pub use cursor::*;
 pub use include::*;
 pub use pagination::*;
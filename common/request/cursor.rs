@@ -0,0 +1,111 @@
+use hmac::{Hmac, Mac, NewMac};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::error::Error;
+use crate::result::Result;
+use crate::sql::Cursor;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// CursorSigner encodes cursors as opaque tokens: the cursor and its signature, so clients can't
+/// forge one to read records after arbitrary values. Every instance of the server has to use the
+/// same key.
+pub struct CursorSigner {
+    key: Vec<u8>,
+}
+
+impl CursorSigner {
+    /// Uses a random key when `key` is empty, so cursors are only valid while the process is
+    /// running.
+    pub fn new(key: &str) -> Self {
+        if key.is_empty() {
+            let mut key = Uuid::new_v4().as_bytes().to_vec();
+            key.extend_from_slice(Uuid::new_v4().as_bytes());
+            return CursorSigner { key };
+        }
+
+        CursorSigner {
+            key: key.as_bytes().to_vec(),
+        }
+    }
+
+    fn mac(&self) -> HmacSha256 {
+        HmacSha256::new_varkey(&self.key).expect("HMAC accepts keys of any size")
+    }
+
+    pub fn encode(&self, cursor: &Cursor) -> String {
+        let payload = serde_json::to_vec(cursor).expect("cursors are serializable");
+        let payload = base64::encode_config(&payload, base64::URL_SAFE_NO_PAD);
+
+        let mut mac = self.mac();
+        mac.update(payload.as_bytes());
+        let signature =
+            base64::encode_config(&mac.finalize().into_bytes(), base64::URL_SAFE_NO_PAD);
+
+        format!("{}.{}", payload, signature)
+    }
+
+    /// Decodes a token created by `encode`, checking its signature.
+    pub fn decode(&self, token: &str) -> Result<Cursor> {
+        let err = || Error::new("cursor", "invalid");
+
+        let mut parts = token.splitn(2, '.');
+        let payload = parts.next().ok_or_else(err)?;
+        let signature = parts.next().ok_or_else(err)?;
+        let signature =
+            base64::decode_config(signature, base64::URL_SAFE_NO_PAD).map_err(|_| err())?;
+
+        let mut mac = self.mac();
+        mac.update(payload.as_bytes());
+        mac.verify(&signature).map_err(|_| err())?;
+
+        let payload = base64::decode_config(payload, base64::URL_SAFE_NO_PAD).map_err(|_| err())?;
+        serde_json::from_slice(&payload).map_err(|raw| err().wrap_raw(raw))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::sql::Value;
+
+    #[test]
+    fn signed_cursor() {
+        let signer = CursorSigner::new("secret");
+        let cursor = Cursor::new(
+            "Views Desc,Id Asc",
+            vec![
+                Some(Value::Int(25)),
+                None,
+                Some(Value::Text("#01".to_owned())),
+            ],
+        );
+
+        let token = signer.encode(&cursor);
+        assert!(!token.contains("Views"));
+        assert_eq!(signer.decode(&token).unwrap(), cursor);
+
+        let forged = signer.encode(&Cursor::new("Views Desc,Id Asc", vec![Some(Value::Int(0))]));
+        let tampered = format!(
+            "{}.{}",
+            forged.split('.').next().unwrap(),
+            token.split('.').nth(1).unwrap(),
+        );
+
+        for token in ["", "invalid", "a.b", tampered.as_str()].iter() {
+            let err = signer.decode(token).err().unwrap();
+            assert_eq!(err.code(), "invalid");
+        }
+    }
+
+    #[test]
+    fn other_key() {
+        let cursor = Cursor::new("Id Asc", vec![Some(Value::Text("#01".to_owned()))]);
+        let token = CursorSigner::new("secret").encode(&cursor);
+
+        assert!(CursorSigner::new("other").decode(&token).is_err());
+        assert!(CursorSigner::new("").decode(&token).is_err());
+    }
+}
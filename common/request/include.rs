@@ -1,10 +1,15 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::result::Result;
 
 #[derive(Deserialize)]
 pub struct IncludeParams {
     pub include: Option<String>,
+    pub fields: Option<String>,
 }
 
 impl From<IncludeParams> for Option<String> {
@@ -13,47 +18,207 @@ impl From<IncludeParams> for Option<String> {
     }
 }
 
+/// Schema of a DTO: the fields it serializes and the relations that can be included, each one
+/// with the schema of its own DTO.
+pub struct Schema {
+    fields: &'static [&'static str],
+    relations: &'static [(&'static str, &'static Schema)],
+}
+
+impl Schema {
+    pub const fn new(
+        fields: &'static [&'static str],
+        relations: &'static [(&'static str, &'static Schema)],
+    ) -> Self {
+        Schema { fields, relations }
+    }
+
+    pub fn has_field(&self, field: &str) -> bool {
+        self.fields.contains(&field)
+    }
+
+    pub fn relation(&self, relation: &str) -> Option<&'static Schema> {
+        self.relations
+            .iter()
+            .find(|(name, _)| *name == relation)
+            .map(|(_, schema)| *schema)
+    }
+}
+
+/// Comma separated paths like `author.followers,category`, as a tree of lower-cased names.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Paths(BTreeMap<String, Paths>);
+
+impl Paths {
+    fn parse(paths: &str) -> Self {
+        let mut tree = Paths::default();
+
+        for path in paths.split(',') {
+            let mut node = &mut tree;
+            for name in path
+                .split('.')
+                .map(|name| name.trim().to_lowercase())
+                .filter(|name| !name.is_empty())
+            {
+                node = node.0.entry(name).or_default();
+            }
+        }
+
+        tree
+    }
+
+    fn merge(&mut self, paths: Paths) {
+        for (name, paths) in paths.0.into_iter() {
+            self.0.entry(name).or_default().merge(paths);
+        }
+    }
+
+    fn validate_relations(&self, schema: &Schema, prefix: &str) -> Result<()> {
+        for (name, paths) in self.0.iter() {
+            let path = join(prefix, name);
+
+            match schema.relation(name) {
+                Some(schema) => paths.validate_relations(schema, &path)?,
+                None => {
+                    return Err(Error::new("include", "unknown").add_context("field", &path));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_fields(&self, schema: &Schema, prefix: &str) -> Result<()> {
+        for (name, paths) in self.0.iter() {
+            let path = join(prefix, name);
+
+            // Only relations have nested fields.
+            match schema.relation(name) {
+                Some(schema) => paths.validate_fields(schema, &path)?,
+                None if schema.has_field(name) && paths.0.is_empty() => {}
+                None => {
+                    return Err(Error::new("fields", "unknown").add_context("field", &path));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes the fields of the object that are not selected. Relations without nested fields
+    /// are kept whole, and lists are selected item by item.
+    fn select(&self, value: &mut Value) {
+        match value {
+            Value::Object(object) => {
+                *object = std::mem::take(object)
+                    .into_iter()
+                    .filter(|(name, _)| self.0.contains_key(name))
+                    .collect();
+
+                for (name, value) in object.iter_mut() {
+                    if let Some(paths) = self.0.get(name).filter(|paths| !paths.0.is_empty()) {
+                        paths.select(value);
+                    }
+                }
+            }
+            Value::Array(values) => {
+                for value in values.iter_mut() {
+                    self.select(value);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn join(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{}.{}", prefix, name)
+    }
+}
+
+/// Include holds the relations requested with `include`, like `publication.author`, and the
+/// fields selected with `fields`, like `id,name,publication.author.username`. If no field is
+/// selected, the DTOs are returned whole.
+#[derive(Debug, Clone, Default)]
 pub struct Include {
-    fields: HashMap<String, bool>,
+    relations: Paths,
+    fields: Option<Paths>,
 }
 
 impl Include {
     pub fn new(include: &str) -> Self {
-        let mut fields = HashMap::new();
+        Include {
+            relations: Paths::parse(include),
+            fields: None,
+        }
+    }
 
-        let field_strs: Vec<String> = include
-            .split(',')
-            .map(|field| field.trim().to_lowercase())
-            .filter(|field| !field.is_empty())
-            .collect();
+    pub fn has(&self, field: &str) -> bool {
+        self.relations.0.get(field).is_some()
+    }
 
-        for field in field_strs.into_iter() {
-            fields.insert(field, true);
+    /// Include of the related DTO, with the relations nested in `field`.
+    pub fn nested(&self, field: &str) -> Include {
+        Include {
+            relations: self.relations.0.get(field).cloned().unwrap_or_default(),
+            fields: None,
         }
+    }
 
-        Include { fields }
+    pub fn add_field<S: Into<String>>(mut self, field: S) -> Self {
+        self.relations.merge(Paths::parse(&field.into()));
+        self
     }
 
-    pub fn has(&self, field: &str) -> bool {
-        self.fields.get(field).is_some()
+    pub fn set_fields(mut self, fields: &str) -> Self {
+        let fields = Paths::parse(fields);
+        self.fields = if fields.0.is_empty() {
+            None
+        } else {
+            Some(fields)
+        };
+        self
     }
 
-    pub fn add_field<S: Into<String>>(mut self, field: S) -> Self {
-        let field = field.into().trim().to_lowercase();
+    /// Checks that the included relations and the selected fields exist in the DTO.
+    pub fn validate(&self, schema: &Schema) -> Result<()> {
+        self.relations.validate_relations(schema, "")?;
 
-        if !field.is_empty() {
-            self.fields.insert(field, true);
+        if let Some(fields) = &self.fields {
+            fields.validate_fields(schema, "")?;
         }
 
-        self
+        Ok(())
     }
-}
 
-impl Default for Include {
-    fn default() -> Self {
-        Include {
-            fields: HashMap::new(),
+    /// Serializes the DTO with the selected fields only.
+    pub fn select<T: Serialize>(&self, dto: &T) -> Value {
+        let mut value = serde_json::to_value(dto).expect("DTOs are serializable");
+
+        if let Some(fields) = &self.fields {
+            fields.select(&mut value);
+        }
+
+        value
+    }
+
+    /// Serializes the response selecting the fields of the DTOs it has in `fields`, such as the
+    /// `items` of a page.
+    pub fn select_in<T: Serialize>(&self, response: &T, fields: &[&str]) -> Value {
+        let mut value = serde_json::to_value(response).expect("responses are serializable");
+
+        if let (Some(selected), Value::Object(object)) = (&self.fields, &mut value) {
+            for field in fields.iter() {
+                if let Some(value) = object.get_mut(*field) {
+                    selected.select(value);
+                }
+            }
         }
+
+        value
     }
 }
 
@@ -75,7 +240,12 @@ impl From<Option<String>> for Include {
 
 impl From<IncludeParams> for Include {
     fn from(q: IncludeParams) -> Self {
-        Self::from(q.include)
+        let include = Self::from(q.include);
+
+        match q.fields {
+            Some(fields) => include.set_fields(&fields),
+            None => include,
+        }
     }
 }
 
@@ -83,10 +253,16 @@ impl From<IncludeParams> for Include {
 mod tests {
     use super::*;
 
+    use serde_json::json;
+
+    const ROLE: Schema = Schema::new(&["id", "name"], &[]);
+    const USER: Schema = Schema::new(&["id", "username", "role_id", "role"], &[("role", &ROLE)]);
+    const SUBSCRIPTION: Schema = Schema::new(&["id", "user_id", "user"], &[("user", &USER)]);
+
     #[test]
     fn basic() {
         let include = Include::new("users,roles,name");
-        assert_eq!(include.fields.len(), 3);
+        assert_eq!(include.relations.0.len(), 3);
         assert!(include.has("users"));
         assert!(include.has("roles"));
         assert!(include.has("name"));
@@ -96,7 +272,7 @@ mod tests {
     #[test]
     fn none_include() {
         let include = Include::from(None);
-        assert_eq!(include.fields.len(), 0);
+        assert_eq!(include.relations.0.len(), 0);
         assert!(!include.has("users"));
         assert!(!include.has("roles"));
     }
@@ -104,7 +280,7 @@ mod tests {
     #[test]
     fn with_withespace() {
         let include = Include::from(Some(" ,  users, roles ,name ".to_owned()));
-        assert_eq!(include.fields.len(), 3);
+        assert_eq!(include.relations.0.len(), 3);
         assert!(include.has("users"));
         assert!(include.has("roles"));
         assert!(include.has("name"));
@@ -114,9 +290,105 @@ mod tests {
     #[test]
     fn to_lowercase() {
         let include = Include::new(" ,  useRS, rOLes ,nAMe ");
-        assert_eq!(include.fields.len(), 3);
+        assert_eq!(include.relations.0.len(), 3);
         assert!(include.has("users"));
         assert!(include.has("roles"));
         assert!(include.has("name"));
     }
+
+    #[test]
+    fn nested() {
+        let include = Include::new("user.role, plan").add_field("user . payments");
+        assert!(include.has("user"));
+        assert!(include.has("plan"));
+        assert!(!include.has("role"));
+
+        let user = include.nested("user");
+        assert!(user.has("role"));
+        assert!(user.has("payments"));
+        assert!(!user.nested("role").has("role"));
+        assert!(!include.nested("plan").has("role"));
+        assert!(!include.nested("non-existing").has("role"));
+    }
+
+    #[test]
+    fn validate() {
+        let valid = [
+            ("", ""),
+            ("user", ""),
+            ("user.role", "id,user.username,user.role.name"),
+            ("USER", "user_id, User"),
+        ];
+        for (include, fields) in valid.iter() {
+            let include = Include::new(include).set_fields(fields);
+            assert!(include.validate(&SUBSCRIPTION).is_ok());
+        }
+
+        let invalid = [
+            ("plan", "", "include", "plan"),
+            ("user.plan", "", "include", "user.plan"),
+            ("user_id", "", "include", "user_id"),
+            ("", "plan", "fields", "plan"),
+            (
+                "",
+                "user.role.permissions",
+                "fields",
+                "user.role.permissions",
+            ),
+            ("", "id.value", "fields", "id.value"),
+        ];
+        for (include, fields, path, field) in invalid.iter() {
+            let err = Include::new(include)
+                .set_fields(fields)
+                .validate(&SUBSCRIPTION)
+                .err()
+                .unwrap();
+            assert_eq!(err.path(), *path);
+            assert_eq!(err.code(), "unknown");
+            assert_eq!(err.context().get("field").map(String::as_str), Some(*field));
+        }
+    }
+
+    #[test]
+    fn select() {
+        let subscription = json!({
+            "id": "#subscription01",
+            "user_id": null,
+            "user": {
+                "id": "#user01",
+                "username": "user-1",
+                "role_id": null,
+                "role": { "id": "user", "name": "User" }
+            }
+        });
+
+        let include = Include::new("user.role");
+        assert_eq!(include.select(&subscription), subscription);
+
+        let include = include.set_fields("id,user.username,user.role");
+        assert_eq!(
+            include.select(&subscription),
+            json!({
+                "id": "#subscription01",
+                "user": {
+                    "username": "user-1",
+                    "role": { "id": "user", "name": "User" }
+                }
+            }),
+        );
+
+        let page = json!({
+            "count": 2,
+            "items": [subscription.clone(), { "id": "#subscription02", "user_id": "#user02" }]
+        });
+        assert_eq!(
+            Include::default()
+                .set_fields("id")
+                .select_in(&page, &["items"]),
+            json!({
+                "count": 2,
+                "items": [{ "id": "#subscription01" }, { "id": "#subscription02" }]
+            }),
+        );
+    }
 }
@@ -1,13 +1,21 @@
 use serde::{Deserialize, Serialize};
 
-use crate::config::Config;
 use crate::model::Pagination;
+use crate::request::CursorSigner;
+use crate::result::Result;
+use crate::sql::Cursor;
 
+/// Maximum number of items returned by a search.
+pub const PAGINATION_LIMIT: usize = 100;
+
+/// Pages can be read by offset, or after the `next_cursor` of the previous page, which is not
+/// affected by records created in the meantime and doesn't count the records again.
 #[derive(Deserialize)]
 pub struct PaginationParams {
     pub offset: Option<usize>,
     pub limit: Option<usize>,
     pub order_by: Option<String>,
+    pub cursor: Option<String>,
 }
 
 impl PaginationParams {
@@ -16,32 +24,37 @@ impl PaginationParams {
     }
 
     pub fn limit(&self) -> Option<usize> {
-        let config = Config::get();
-
         self.limit
             .map(|limit| {
-                if limit <= config.pagination_limit() {
+                if limit <= PAGINATION_LIMIT {
                     limit
                 } else {
-                    config.pagination_limit()
+                    PAGINATION_LIMIT
                 }
             })
-            .or_else(|| Some(config.pagination_limit()))
+            .or_else(|| Some(PAGINATION_LIMIT))
     }
 
     pub fn order_by(&self) -> Option<String> {
         self.order_by.clone()
     }
+
+    /// Cursor the page starts after, if its signature is valid.
+    pub fn cursor(&self, signer: &CursorSigner) -> Result<Option<Cursor>> {
+        self.cursor
+            .as_deref()
+            .map(|token| signer.decode(token))
+            .transpose()
+    }
 }
 
 impl Default for PaginationParams {
     fn default() -> Self {
-        let config = Config::get();
-
         PaginationParams {
             offset: Some(0),
-            limit: Some(config.pagination_limit()),
+            limit: Some(PAGINATION_LIMIT),
             order_by: None,
+            cursor: None,
         }
     }
 }
@@ -53,9 +66,13 @@ where
 {
     pub offset: usize,
     pub limit: usize,
-    pub total: usize,
-    pub matching_criteria: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matching_criteria: Option<usize>,
     pub count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
     pub items: Vec<T>,
 }
 
@@ -63,17 +80,31 @@ impl<T> PaginationResponse<T>
 where
     T: Serialize,
 {
-    pub fn new(offset: usize, limit: usize, total: usize, matching_criteria: usize) -> Self {
+    pub fn new(
+        offset: usize,
+        limit: usize,
+        total: Option<usize>,
+        matching_criteria: Option<usize>,
+    ) -> Self {
         PaginationResponse {
             offset,
             limit,
             total,
             matching_criteria,
             count: 0,
+            next_cursor: None,
             items: Vec::new(),
         }
     }
 
+    /// Creates the response of a page, with its next cursor signed by `signer`.
+    pub fn from_pagination<T1>(p: &Pagination<T1>, signer: &CursorSigner) -> Self {
+        let mut res =
+            PaginationResponse::new(p.offset(), p.limit(), p.total(), p.matching_criteria());
+        res.next_cursor = p.next_cursor().map(|cursor| signer.encode(cursor));
+        res
+    }
+
     pub fn add_items(&mut self, items: Vec<T>) {
         self.items.extend(items);
         self.count = self.items.len();
@@ -84,12 +115,3 @@ where
         self.count = self.items.len();
     }
 }
-
-impl<T1, T2> From<&Pagination<T1>> for PaginationResponse<T2>
-where
-    T2: Serialize,
-{
-    fn from(p: &Pagination<T1>) -> Self {
-        PaginationResponse::new(p.offset(), p.limit(), p.total(), p.matching_criteria())
-    }
-}
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::result::Result;
+
+/// Purge removes the records that were soft-deleted before a date, hard-deleting them or
+/// anonymizing the ones other records still depend on.
+#[async_trait]
+pub trait Purge: Sync + Send {
+    /// Returns how many records were purged.
+    async fn purge(&self, deleted_before: &DateTime<Utc>) -> Result<usize>;
+}
+
+/// Retention periodically purges the records of the registered repositories that have been
+/// deleted for longer than the retention period. Until then, they can be restored.
+pub struct Retention {
+    period: chrono::Duration,
+    repositories: Vec<Arc<dyn Purge>>,
+}
+
+impl Retention {
+    pub fn new(period: chrono::Duration) -> Self {
+        Retention {
+            period,
+            repositories: Vec::new(),
+        }
+    }
+
+    pub fn repository(mut self, repository: Arc<dyn Purge>) -> Self {
+        self.repositories.push(repository);
+        self
+    }
+
+    pub fn period(&self) -> chrono::Duration {
+        self.period
+    }
+
+    /// Purges every repository once and returns how many records were purged.
+    pub async fn purge(&self) -> Result<usize> {
+        let deleted_before = Utc::now() - self.period;
+
+        let mut total = 0;
+        for repository in self.repositories.iter() {
+            total += repository.purge(&deleted_before).await?;
+        }
+        Ok(total)
+    }
+
+    pub fn start(self: Arc<Self>, interval: Duration) {
+        tokio::spawn(async move {
+            loop {
+                if let Err(err) = self.purge().await {
+                    println!("{:?}", err);
+                }
+
+                tokio::time::delay_for(interval).await;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::sync::Mutex;
+
+    struct Deleted {
+        dates: Mutex<Vec<DateTime<Utc>>>,
+    }
+
+    #[async_trait]
+    impl Purge for Deleted {
+        async fn purge(&self, deleted_before: &DateTime<Utc>) -> Result<usize> {
+            let mut dates = self.dates.lock().await;
+            let len = dates.len();
+            dates.retain(|date| date >= deleted_before);
+            Ok(len - dates.len())
+        }
+    }
+
+    #[tokio::test]
+    async fn purge_old_records() {
+        let now = Utc::now();
+        let publications = Arc::new(Deleted {
+            dates: Mutex::new(vec![
+                now - chrono::Duration::days(40),
+                now - chrono::Duration::days(10),
+            ]),
+        });
+        let users = Arc::new(Deleted {
+            dates: Mutex::new(vec![now - chrono::Duration::days(31), now]),
+        });
+
+        let retention = Retention::new(chrono::Duration::days(30))
+            .repository(publications.clone())
+            .repository(users.clone());
+
+        assert_eq!(retention.purge().await.unwrap(), 2);
+        assert_eq!(retention.purge().await.unwrap(), 0);
+        assert_eq!(publications.dates.lock().await.len(), 1);
+        assert_eq!(users.dates.lock().await.len(), 1);
+    }
+}
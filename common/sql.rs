@@ -1 +1,16 @@
+mod compiler;
+mod criteria;
+mod cursor;
+mod query;
+mod record;
+mod value;
 pub mod where_builder;
+pub use compiler::*;
+pub use criteria::*;
+pub use cursor::*;
+pub use query::*;
+pub use record::*;
+pub use value::*;
+
+#[cfg(test)]
+mod semantics;
@@ -0,0 +1,264 @@
+use std::fmt::Debug;
+
+use tokio_postgres::types::ToSql;
+
+use crate::sql::{words, Criteria, Direction, Query, Value};
+
+/// Column is how a field is read in SQL.
+#[derive(Debug, Clone, Copy)]
+pub enum Column {
+    /// Column or expression, like `name` or `status_history->-1->>'status'`.
+    Expr(&'static str),
+    /// Path of the objects of a JSONB array, like `["slug"]` for the slug of each tag. A criteria
+    /// matches if any of the objects matches, and values are compared as text. It can't be used
+    /// to sort.
+    JsonArray(&'static str, &'static [&'static str]),
+}
+
+/// Field is implemented by the fields of a record stored in Postgres.
+pub trait Field {
+    fn column(&self) -> Column;
+}
+
+/// SqlQuery is a compiled query. Clauses are empty when there is nothing to filter or sort.
+#[derive(Debug)]
+pub struct SqlQuery {
+    where_clause: String,
+    order_by: String,
+    params: Vec<Value>,
+}
+
+impl SqlQuery {
+    pub fn where_clause(&self) -> &str {
+        &self.where_clause
+    }
+
+    pub fn order_by(&self) -> &str {
+        &self.order_by
+    }
+
+    pub fn params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        self.params
+            .iter()
+            .map(|param| param as &(dyn ToSql + Sync))
+            .collect()
+    }
+}
+
+struct Compiler {
+    params: Vec<Value>,
+}
+
+impl Compiler {
+    fn param(&mut self, value: Value) -> String {
+        self.params.push(value);
+        format!("${}", self.params.len())
+    }
+
+    fn compile<F: Field>(&mut self, criteria: &Criteria<F>) -> String {
+        match criteria {
+            Criteria::Not(criteria) => {
+                // Without COALESCE, NOT NULL would be NULL and records without value wouldn't
+                // match.
+                format!("NOT COALESCE(({}), FALSE)", self.compile(criteria))
+            }
+            Criteria::And(criteria) => self.join(criteria, "AND", "TRUE"),
+            Criteria::Or(criteria) => self.join(criteria, "OR", "FALSE"),
+            Criteria::Eq(field, _)
+            | Criteria::In(field, _)
+            | Criteria::Range(field, _, _)
+            | Criteria::Greater(field, _)
+            | Criteria::Less(field, _)
+            | Criteria::Contains(field, _)
+            | Criteria::Search(field, _)
+            | Criteria::IsNull(field) => match field.column() {
+                Column::Expr(expr) => self.predicate(expr, criteria),
+                Column::JsonArray(column, _) if matches!(criteria, Criteria::IsNull(_)) => {
+                    format!("jsonb_array_length(COALESCE({}, '[]'::jsonb)) = 0", column)
+                }
+                Column::JsonArray(column, path) => format!(
+                    "EXISTS (SELECT TRUE FROM jsonb_array_elements({}) elem WHERE {})",
+                    column,
+                    self.predicate(&json_path("elem", path), criteria),
+                ),
+            },
+        }
+    }
+
+    fn join<F: Field>(&mut self, criteria: &[Criteria<F>], op: &str, empty: &str) -> String {
+        match criteria.len() {
+            0 => empty.to_owned(),
+            1 => self.compile(&criteria[0]),
+            _ => criteria
+                .iter()
+                .map(|criteria| format!("({})", self.compile(criteria)))
+                .collect::<Vec<String>>()
+                .join(&format!(" {} ", op)),
+        }
+    }
+
+    fn predicate<F>(&mut self, expr: &str, criteria: &Criteria<F>) -> String {
+        match criteria {
+            Criteria::Eq(_, value) => format!("{} = {}", expr, self.param(value.clone())),
+            Criteria::In(_, values) => {
+                if values.is_empty() {
+                    return "FALSE".to_owned();
+                }
+
+                let params: Vec<String> = values
+                    .iter()
+                    .map(|value| self.param(value.clone()))
+                    .collect();
+                format!("{} IN ({})", expr, params.join(", "))
+            }
+            Criteria::Range(_, from, to) => {
+                let mut bounds = Vec::new();
+                if let Some(from) = from {
+                    bounds.push(format!("{} >= {}", expr, self.param(from.clone())));
+                }
+                if let Some(to) = to {
+                    bounds.push(format!("{} <= {}", expr, self.param(to.clone())));
+                }
+
+                if bounds.is_empty() {
+                    return "TRUE".to_owned();
+                }
+                bounds.join(" AND ")
+            }
+            Criteria::Greater(_, value) => format!("{} > {}", expr, self.param(value.clone())),
+            Criteria::Less(_, value) => format!("{} < {}", expr, self.param(value.clone())),
+            Criteria::Contains(_, text) => {
+                // The text is matched literally.
+                let text = text
+                    .replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_");
+                format!(
+                    "LOWER({}) LIKE '%' || LOWER({}) || '%'",
+                    expr,
+                    self.param(Value::Text(text)),
+                )
+            }
+            Criteria::Search(_, text) => {
+                let words = words(text);
+                if words.is_empty() {
+                    return "TRUE".to_owned();
+                }
+
+                format!(
+                    "to_tsvector('simple', {}) @@ plainto_tsquery('simple', {})",
+                    expr,
+                    self.param(Value::Text(words.join(" "))),
+                )
+            }
+            Criteria::IsNull(_) => format!("{} IS NULL", expr),
+            Criteria::Not(_) | Criteria::And(_) | Criteria::Or(_) => {
+                unreachable!("not a predicate")
+            }
+        }
+    }
+}
+
+/// Text at the path of a JSONB value, like `elem->'publication_id'->>'id'`.
+fn json_path(expr: &str, path: &[&str]) -> String {
+    let mut expr = expr.to_owned();
+    for (i, key) in path.iter().enumerate() {
+        let op = if i == path.len() - 1 { "->>" } else { "->" };
+        expr = format!("{}{}'{}'", expr, op, key);
+    }
+    expr
+}
+
+impl<F: Field + Debug + Clone> Query<F> {
+    /// Compiles the criteria, including the seek of the cursor, and the sort keys. Values are
+    /// bound as parameters, starting at `$1`.
+    pub fn to_sql(&self) -> SqlQuery {
+        let mut compiler = Compiler { params: Vec::new() };
+
+        let condition = compiler.compile(&self.full_criteria());
+        let where_clause = if condition == "TRUE" {
+            String::new()
+        } else {
+            format!("WHERE {}", condition)
+        };
+
+        let sort_keys: Vec<String> = self
+            .sort_keys()
+            .iter()
+            .filter_map(|sort| match sort.field.column() {
+                Column::Expr(expr) => Some(format!(
+                    "{} {}",
+                    expr,
+                    match sort.direction {
+                        Direction::Asc => "ASC",
+                        Direction::Desc => "DESC",
+                    }
+                )),
+                Column::JsonArray(..) => None,
+            })
+            .collect();
+        let order_by = if sort_keys.is_empty() {
+            String::new()
+        } else {
+            format!("ORDER BY {}", sort_keys.join(", "))
+        };
+
+        SqlQuery {
+            where_clause,
+            order_by,
+            params: compiler.params,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy)]
+    enum TestField {
+        Name,
+        Status,
+        Tag,
+    }
+
+    impl Field for TestField {
+        fn column(&self) -> Column {
+            match self {
+                TestField::Name => Column::Expr("name"),
+                TestField::Status => Column::Expr("status_history->-1->>'status'"),
+                TestField::Tag => Column::JsonArray("tags", &["slug"]),
+            }
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let sql = Query::<TestField>::new().to_sql();
+        assert_eq!(sql.where_clause(), "");
+        assert_eq!(sql.order_by(), "");
+        assert!(sql.params().is_empty());
+    }
+
+    #[test]
+    fn criteria_and_sort_keys() {
+        let sql = Query::new()
+            .filter(Criteria::contains(TestField::Name, "50%"))
+            .filter(Criteria::eq(TestField::Tag, "action").or(!Criteria::is_in(
+                TestField::Status,
+                vec!["draft", "rejected"],
+            )))
+            .sort(TestField::Name, Direction::Desc)
+            .sort(TestField::Tag, Direction::Asc)
+            .to_sql();
+
+        assert_eq!(
+            sql.where_clause(),
+            "WHERE (LOWER(name) LIKE '%' || LOWER($1) || '%') AND \
+            ((EXISTS (SELECT TRUE FROM jsonb_array_elements(tags) elem WHERE elem->>'slug' = $2)) OR \
+            (NOT COALESCE((status_history->-1->>'status' IN ($3, $4)), FALSE)))",
+        );
+        assert_eq!(sql.order_by(), "ORDER BY name DESC");
+        assert_eq!(sql.params().len(), 4);
+    }
+}
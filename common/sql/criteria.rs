@@ -0,0 +1,120 @@
+use std::ops;
+
+use crate::sql::Value;
+
+/// Criteria is a condition over the fields `F` of a record. The same criteria is compiled to SQL
+/// by the Postgres repositories (see `Column`) and evaluated in memory by the in-memory ones (see
+/// `Record`), so both of them return the same records.
+///
+/// A field without value (SQL `NULL`) doesn't match any comparison.
+#[derive(Debug, Clone)]
+pub enum Criteria<F> {
+    Eq(F, Value),
+    In(F, Vec<Value>),
+    /// Inclusive range. A missing bound is not checked.
+    Range(F, Option<Value>, Option<Value>),
+    Greater(F, Value),
+    Less(F, Value),
+    /// Case-insensitive substring.
+    Contains(F, String),
+    /// Full-text search: the field has every word of the text.
+    Search(F, String),
+    IsNull(F),
+    Not(Box<Criteria<F>>),
+    /// Matches everything when empty.
+    And(Vec<Criteria<F>>),
+    /// Matches nothing when empty.
+    Or(Vec<Criteria<F>>),
+}
+
+impl<F> Criteria<F> {
+    pub fn all() -> Self {
+        Criteria::And(Vec::new())
+    }
+
+    pub fn eq<V: Into<Value>>(field: F, value: V) -> Self {
+        Criteria::Eq(field, value.into())
+    }
+
+    pub fn is_in<V, I>(field: F, values: I) -> Self
+    where
+        V: Into<Value>,
+        I: IntoIterator<Item = V>,
+    {
+        Criteria::In(field, values.into_iter().map(Into::into).collect())
+    }
+
+    pub fn range<V: Into<Value>>(field: F, from: Option<V>, to: Option<V>) -> Self {
+        Criteria::Range(field, from.map(Into::into), to.map(Into::into))
+    }
+
+    pub fn gt<V: Into<Value>>(field: F, value: V) -> Self {
+        Criteria::Greater(field, value.into())
+    }
+
+    pub fn lt<V: Into<Value>>(field: F, value: V) -> Self {
+        Criteria::Less(field, value.into())
+    }
+
+    pub fn contains<S: Into<String>>(field: F, text: S) -> Self {
+        Criteria::Contains(field, text.into())
+    }
+
+    pub fn search<S: Into<String>>(field: F, text: S) -> Self {
+        Criteria::Search(field, text.into())
+    }
+
+    pub fn is_null(field: F) -> Self {
+        Criteria::IsNull(field)
+    }
+
+    pub fn and(self, other: Criteria<F>) -> Self {
+        match (self, other) {
+            (Criteria::And(mut a), Criteria::And(b)) => {
+                a.extend(b);
+                Criteria::And(a)
+            }
+            (Criteria::And(mut a), b) => {
+                a.push(b);
+                Criteria::And(a)
+            }
+            (a, b) => Criteria::And(vec![a, b]),
+        }
+    }
+
+    pub fn or(self, other: Criteria<F>) -> Self {
+        match (self, other) {
+            (Criteria::Or(mut a), Criteria::Or(b)) => {
+                a.extend(b);
+                Criteria::Or(a)
+            }
+            (Criteria::Or(mut a), b) => {
+                a.push(b);
+                Criteria::Or(a)
+            }
+            (a, b) => Criteria::Or(vec![a, b]),
+        }
+    }
+}
+
+impl<F> ops::Not for Criteria<F> {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Criteria::Not(Box::new(self))
+    }
+}
+
+impl<F> Default for Criteria<F> {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+/// Words of a text for full-text search, in lowercase.
+pub(crate) fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect()
+}
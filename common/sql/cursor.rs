@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+use crate::sql::Value;
+
+/// Cursor is the position of the last record of a page: the value of each sort key of the query,
+/// the last one being its unique key. The next page starts after it, so records created in the
+/// meantime don't shift the pages as with an offset.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cursor {
+    sort: String,
+    values: Vec<Option<Value>>,
+}
+
+impl Cursor {
+    /// `sort` describes the sort keys the values belong to, so the cursor can't be used with
+    /// another order.
+    pub fn new<S: Into<String>>(sort: S, values: Vec<Option<Value>>) -> Self {
+        Cursor {
+            sort: sort.into(),
+            values,
+        }
+    }
+
+    pub fn sort(&self) -> &str {
+        &self.sort
+    }
+
+    pub fn values(&self) -> &[Option<Value>] {
+        &self.values
+    }
+}
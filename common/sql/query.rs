@@ -0,0 +1,179 @@
+use std::fmt::Debug;
+
+use crate::error::Error;
+use crate::result::Result;
+use crate::sql::{Criteria, Cursor};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    Asc,
+    Desc,
+}
+
+/// Sort key. Records without value go last in ascending order and first in descending order, as
+/// in Postgres.
+#[derive(Debug, Clone)]
+pub struct Sort<F> {
+    pub field: F,
+    pub direction: Direction,
+}
+
+/// Query is a criteria with sort keys and pagination, accepted by the `search` method of
+/// repositories.
+#[derive(Debug, Clone)]
+pub struct Query<F> {
+    criteria: Criteria<F>,
+    sort: Vec<Sort<F>>,
+    has_key: bool,
+    after: Option<Cursor>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+}
+
+impl<F> Query<F> {
+    pub fn new() -> Self {
+        Query {
+            criteria: Criteria::all(),
+            sort: Vec::new(),
+            has_key: false,
+            after: None,
+            offset: None,
+            limit: None,
+        }
+    }
+
+    /// Adds a criteria that records must also match.
+    pub fn filter(mut self, criteria: Criteria<F>) -> Self {
+        self.criteria = self.criteria.and(criteria);
+        self
+    }
+
+    pub fn filter_opt(self, criteria: Option<Criteria<F>>) -> Self {
+        match criteria {
+            Some(criteria) => self.filter(criteria),
+            None => self,
+        }
+    }
+
+    pub fn sort(mut self, field: F, direction: Direction) -> Self {
+        self.sort.push(Sort { field, direction });
+        self
+    }
+
+    pub fn paginate(mut self, offset: Option<usize>, limit: Option<usize>) -> Self {
+        self.offset = offset;
+        self.limit = limit;
+        self
+    }
+
+    pub fn criteria(&self) -> &Criteria<F> {
+        &self.criteria
+    }
+
+    pub fn sort_keys(&self) -> &[Sort<F>] {
+        &self.sort
+    }
+
+    /// Whether the last sort key is unique, so pages can be read with cursors.
+    pub fn has_key(&self) -> bool {
+        self.has_key
+    }
+
+    /// Cursor the page starts after. Pages after a cursor are not counted.
+    pub fn after(&self) -> Option<&Cursor> {
+        self.after.as_ref()
+    }
+
+    /// Offset of the page, ignored if it starts after a cursor.
+    pub fn offset(&self) -> Option<usize> {
+        if self.after.is_some() {
+            return None;
+        }
+
+        self.offset
+    }
+
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+}
+
+impl<F: Debug + Clone> Query<F> {
+    /// Sorts by `key`, a field with a unique value such as the ID, after the other sort keys, so
+    /// every record has a fixed position and pages can be read with cursors. It has to be called
+    /// after `sort`. The page starts after the cursor `after` if there is one, which must have
+    /// been created for the same sort keys.
+    pub fn cursor(mut self, key: F, after: Option<Cursor>) -> Result<Self> {
+        self.sort.push(Sort {
+            field: key,
+            direction: Direction::Asc,
+        });
+        self.has_key = true;
+
+        if let Some(after) = after {
+            if after.sort() != self.sort_description() || after.values().len() != self.sort.len() {
+                return Err(Error::new("cursor", "invalid"));
+            }
+
+            self.after = Some(after);
+        }
+
+        Ok(self)
+    }
+
+    /// Sort keys as stored in cursors, like `CreatedAt Desc,Id Asc`.
+    pub fn sort_description(&self) -> String {
+        self.sort
+            .iter()
+            .map(|sort| format!("{:?} {:?}", sort.field, sort.direction))
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+
+    /// Criteria of the records sorted after the cursor: they have the same values as the cursor
+    /// for the first sort keys and are after it for the next one.
+    pub fn seek(&self) -> Option<Criteria<F>> {
+        let after = self.after.as_ref()?;
+
+        let mut seek = Criteria::Or(Vec::new());
+        let mut same = Criteria::all();
+        for (sort, value) in self.sort.iter().zip(after.values().iter()) {
+            let field = sort.field.clone();
+
+            // Records without value are the last ones in ascending order and the first ones in
+            // descending order.
+            let beyond = match (sort.direction, value) {
+                (Direction::Asc, Some(value)) => Some(
+                    Criteria::gt(field.clone(), value.clone()).or(Criteria::is_null(field.clone())),
+                ),
+                (Direction::Asc, None) => None,
+                (Direction::Desc, Some(value)) => Some(Criteria::lt(field.clone(), value.clone())),
+                (Direction::Desc, None) => Some(!Criteria::is_null(field.clone())),
+            };
+            if let Some(beyond) = beyond {
+                seek = seek.or(same.clone().and(beyond));
+            }
+
+            same = same.and(match value {
+                Some(value) => Criteria::eq(field, value.clone()),
+                None => Criteria::is_null(field),
+            });
+        }
+
+        Some(seek)
+    }
+
+    /// Criteria and seek of the cursor, if any.
+    pub(crate) fn full_criteria(&self) -> Criteria<F> {
+        match self.seek() {
+            Some(seek) => self.criteria.clone().and(seek),
+            None => self.criteria.clone(),
+        }
+    }
+}
+
+impl<F> Default for Query<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,126 @@
+use std::cmp::Ordering;
+use std::fmt::Debug;
+
+use crate::model::Pagination;
+use crate::sql::{words, Criteria, Cursor, Direction, Query, Value};
+
+/// Record is implemented by the entities stored in memory, to evaluate criteria over their
+/// fields `F`.
+pub trait Record<F> {
+    /// Values of the field: none if it's empty, or several for lists.
+    fn values(&self, field: &F) -> Vec<Value>;
+}
+
+impl<F> Criteria<F> {
+    pub fn matches<R: Record<F>>(&self, record: &R) -> bool {
+        match self {
+            Criteria::Eq(field, value) => record.values(field).iter().any(|v| v == value),
+            Criteria::In(field, values) => record
+                .values(field)
+                .iter()
+                .any(|v| values.iter().any(|value| v == value)),
+            Criteria::Range(field, from, to) => record.values(field).iter().any(|v| {
+                from.as_ref().map_or(true, |from| v >= from)
+                    && to.as_ref().map_or(true, |to| v <= to)
+            }),
+            Criteria::Greater(field, value) => record.values(field).iter().any(|v| v > value),
+            Criteria::Less(field, value) => record.values(field).iter().any(|v| v < value),
+            Criteria::Contains(field, text) => {
+                let text = text.to_lowercase();
+                record.values(field).iter().any(|v| match v {
+                    Value::Text(v) => v.to_lowercase().contains(&text),
+                    _ => false,
+                })
+            }
+            Criteria::Search(field, text) => {
+                let search = words(text);
+                record.values(field).iter().any(|v| match v {
+                    Value::Text(v) => {
+                        let words = words(v);
+                        search.iter().all(|word| words.contains(word))
+                    }
+                    _ => false,
+                })
+            }
+            Criteria::IsNull(field) => record.values(field).is_empty(),
+            Criteria::Not(criteria) => !criteria.matches(record),
+            Criteria::And(criteria) => criteria.iter().all(|criteria| criteria.matches(record)),
+            Criteria::Or(criteria) => criteria.iter().any(|criteria| criteria.matches(record)),
+        }
+    }
+}
+
+impl<F: Debug + Clone> Query<F> {
+    /// Filters, sorts and paginates the records like the compiled query would do in Postgres.
+    pub fn apply<R: Record<F>>(&self, records: Vec<R>) -> Pagination<R> {
+        let total = records.len();
+        let matching_criteria = records
+            .iter()
+            .filter(|record| self.criteria().matches(*record))
+            .count();
+
+        let criteria = self.full_criteria();
+        let mut records: Vec<R> = records
+            .into_iter()
+            .filter(|record| criteria.matches(record))
+            .collect();
+
+        records.sort_by(|a, b| {
+            for sort in self.sort_keys().iter() {
+                let ordering =
+                    compare(a.values(&sort.field).first(), b.values(&sort.field).first());
+                let ordering = match sort.direction {
+                    Direction::Asc => ordering,
+                    Direction::Desc => ordering.reverse(),
+                };
+
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+
+            Ordering::Equal
+        });
+
+        let offset = self.offset().unwrap_or(0);
+        let limit = self.limit().unwrap_or(total);
+        let records: Vec<R> = records.into_iter().skip(offset).take(limit).collect();
+        let next_cursor = self.next_cursor(&records);
+
+        let pagination = if self.after().is_none() {
+            Pagination::new(offset, limit, total, matching_criteria)
+        } else {
+            Pagination::uncounted(offset, limit)
+        };
+
+        pagination.add_items(records).set_next_cursor(next_cursor)
+    }
+
+    /// Cursor of the last record of a full page, as there may be more records after it. There is
+    /// none if the query has no key (see `Query::cursor`) or no limit.
+    pub fn next_cursor<R: Record<F>>(&self, records: &[R]) -> Option<Cursor> {
+        let limit = self.limit()?;
+        if !self.has_key() || limit == 0 || records.len() < limit {
+            return None;
+        }
+
+        let last = records.last()?;
+        let values = self
+            .sort_keys()
+            .iter()
+            .map(|sort| last.values(&sort.field).into_iter().next())
+            .collect();
+
+        Some(Cursor::new(self.sort_description(), values))
+    }
+}
+
+/// Empty values are greater than any other value, as `NULL` in Postgres.
+fn compare(a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
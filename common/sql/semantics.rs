@@ -0,0 +1,452 @@
+//! Cases run against both backends, so the SQL compiled from a query returns the same records, in
+//! the same order, as the query applied in memory.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+use crate::infrastructure::postgres::PostgresClient;
+use crate::sql::{Column, Criteria, Cursor, Direction, Field, Query, Record, Value};
+
+#[derive(Debug, Clone, Copy)]
+enum ItemField {
+    Id,
+    Name,
+    Price,
+    Tag,
+    Category,
+    CreatedAt,
+}
+
+impl Field for ItemField {
+    fn column(&self) -> Column {
+        match self {
+            ItemField::Id => Column::Expr("id"),
+            ItemField::Name => Column::Expr("name"),
+            ItemField::Price => Column::Expr("price"),
+            ItemField::Tag => Column::JsonArray("tags", &["slug"]),
+            ItemField::Category => Column::Expr("category"),
+            ItemField::CreatedAt => Column::Expr("created_at"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Item {
+    id: &'static str,
+    name: &'static str,
+    price: i64,
+    tags: Vec<&'static str>,
+    category: Option<&'static str>,
+    created_at: DateTime<Utc>,
+}
+
+impl Record<ItemField> for Item {
+    fn values(&self, field: &ItemField) -> Vec<Value> {
+        match field {
+            ItemField::Id => vec![self.id.into()],
+            ItemField::Name => vec![self.name.into()],
+            ItemField::Price => vec![self.price.into()],
+            ItemField::Tag => self.tags.iter().map(|tag| (*tag).into()).collect(),
+            ItemField::Category => self.category.into_iter().map(Into::into).collect(),
+            ItemField::CreatedAt => vec![self.created_at.into()],
+        }
+    }
+}
+
+fn date(date: &str) -> DateTime<Utc> {
+    DateTime::from_str(date).unwrap()
+}
+
+fn item(
+    id: &'static str,
+    name: &'static str,
+    price: i64,
+    tags: Vec<&'static str>,
+    category: Option<&'static str>,
+    created_at: &str,
+) -> Item {
+    Item {
+        id,
+        name,
+        price,
+        tags,
+        category,
+        created_at: date(created_at),
+    }
+}
+
+fn items() -> Vec<Item> {
+    vec![
+        item(
+            "1",
+            "The Amazing Spider-Man",
+            100,
+            vec!["marvel", "action"],
+            Some("comic"),
+            "2020-01-01T00:00:00Z",
+        ),
+        item(
+            "2",
+            "Batman: Year One",
+            250,
+            vec!["dc"],
+            Some("comic"),
+            "2020-02-01T00:00:00Z",
+        ),
+        item(
+            "3",
+            "Watchmen",
+            300,
+            vec!["dc", "classic"],
+            None,
+            "2020-03-01T00:00:00Z",
+        ),
+        item(
+            "4",
+            "Sandman 100% Edition",
+            80,
+            vec![],
+            Some("novel"),
+            "2020-04-01T00:00:00Z",
+        ),
+        item(
+            "5",
+            "spider-woman",
+            120,
+            vec!["marvel"],
+            Some("comic"),
+            "2020-05-01T00:00:00Z",
+        ),
+    ]
+}
+
+/// Queries without sort keys are sorted by id, so the expected ids have a single order.
+fn by_id(criteria: Criteria<ItemField>) -> Query<ItemField> {
+    Query::new()
+        .filter(criteria)
+        .sort(ItemField::Id, Direction::Asc)
+}
+
+fn cases() -> Vec<(&'static str, Query<ItemField>, Vec<&'static str>)> {
+    use ItemField::*;
+
+    vec![
+        ("all", by_id(Criteria::all()), vec!["1", "2", "3", "4", "5"]),
+        (
+            "eq",
+            by_id(Criteria::eq(Category, "comic")),
+            vec!["1", "2", "5"],
+        ),
+        (
+            "eq json array",
+            by_id(Criteria::eq(Tag, "dc")),
+            vec!["2", "3"],
+        ),
+        (
+            "in",
+            by_id(Criteria::is_in(Price, vec![100, 300, 999])),
+            vec!["1", "3"],
+        ),
+        (
+            "empty in",
+            by_id(Criteria::is_in(Price, Vec::<i64>::new())),
+            vec![],
+        ),
+        (
+            "range",
+            by_id(Criteria::range(Price, Some(100), Some(250))),
+            vec!["1", "2", "5"],
+        ),
+        (
+            "open range",
+            by_id(Criteria::range(
+                CreatedAt,
+                Some(date("2020-03-01T00:00:00Z")),
+                None,
+            )),
+            vec!["3", "4", "5"],
+        ),
+        (
+            "range without bounds",
+            by_id(Criteria::range::<i64>(Price, None, None)),
+            vec!["1", "2", "3", "4", "5"],
+        ),
+        (
+            "contains ignores case",
+            by_id(Criteria::contains(Name, "SPIDER")),
+            vec!["1", "5"],
+        ),
+        (
+            "contains is literal",
+            by_id(Criteria::contains(Name, "100%")),
+            vec!["4"],
+        ),
+        (
+            "contains wildcard",
+            by_id(Criteria::contains(Name, "_")),
+            vec![],
+        ),
+        (
+            "search every word",
+            by_id(Criteria::search(Name, "spider man")),
+            vec!["1"],
+        ),
+        (
+            "empty search",
+            by_id(Criteria::search(Name, "  ")),
+            vec!["1", "2", "3", "4", "5"],
+        ),
+        ("is null", by_id(Criteria::is_null(Category)), vec!["3"]),
+        ("empty json array", by_id(Criteria::is_null(Tag)), vec!["4"]),
+        (
+            "not is null",
+            by_id(!Criteria::is_null(Category)),
+            vec!["1", "2", "4", "5"],
+        ),
+        (
+            "not matches null",
+            by_id(!Criteria::eq(Category, "comic")),
+            vec!["3", "4"],
+        ),
+        (
+            "or",
+            by_id(Criteria::eq(Tag, "classic").or(Criteria::range(Price, None, Some(90)))),
+            vec!["3", "4"],
+        ),
+        (
+            "and",
+            by_id(Criteria::eq(Tag, "marvel").and(!Criteria::contains(Name, "woman"))),
+            vec!["1"],
+        ),
+        (
+            "sort desc",
+            Query::new().sort(Price, Direction::Desc),
+            vec!["3", "2", "5", "1", "4"],
+        ),
+        (
+            "null last in asc",
+            Query::new()
+                .sort(Category, Direction::Asc)
+                .sort(Id, Direction::Desc),
+            vec!["5", "2", "1", "4", "3"],
+        ),
+        (
+            "null first in desc",
+            Query::new()
+                .sort(Category, Direction::Desc)
+                .sort(Id, Direction::Asc),
+            vec!["3", "4", "1", "2", "5"],
+        ),
+        (
+            "paginate",
+            by_id(Criteria::all()).paginate(Some(1), Some(2)),
+            vec!["2", "3"],
+        ),
+    ]
+}
+
+/// Sort keys of queries read in pages of two records with cursors, and the ids of every page.
+fn cursor_cases() -> Vec<(&'static str, Vec<(ItemField, Direction)>, Vec<&'static str>)> {
+    use ItemField::*;
+
+    vec![
+        ("cursor by key", vec![], vec!["1", "2", "3", "4", "5"]),
+        (
+            "cursor with repeated values",
+            vec![(Category, Direction::Asc), (Price, Direction::Desc)],
+            vec!["2", "5", "1", "4", "3"],
+        ),
+        (
+            "cursor desc",
+            vec![(Price, Direction::Desc)],
+            vec!["3", "2", "5", "1", "4"],
+        ),
+        (
+            "cursor with null last",
+            vec![(Category, Direction::Asc)],
+            vec!["1", "2", "5", "4", "3"],
+        ),
+        (
+            "cursor with null first",
+            vec![(Category, Direction::Desc), (CreatedAt, Direction::Desc)],
+            vec!["3", "4", "5", "2", "1"],
+        ),
+    ]
+}
+
+fn cursor_query(sort: &[(ItemField, Direction)], after: Option<Cursor>) -> Query<ItemField> {
+    let mut query = Query::new();
+    for (field, direction) in sort.iter() {
+        query = query.sort(*field, *direction);
+    }
+
+    query
+        .cursor(ItemField::Id, after)
+        .unwrap()
+        .paginate(None, Some(2))
+}
+
+fn item_by_id(id: &str) -> Item {
+    items().into_iter().find(|item| item.id == id).unwrap()
+}
+
+#[test]
+fn inmem() {
+    for (name, query, expected) in cases().into_iter() {
+        let pagination = query.apply(items());
+        let ids: Vec<&str> = pagination.items().iter().map(|item| item.id).collect();
+        assert_eq!(ids, expected, "{}", name);
+        assert_eq!(pagination.total(), Some(5), "{}", name);
+    }
+}
+
+#[test]
+fn inmem_cursors() {
+    for (name, sort, expected) in cursor_cases().into_iter() {
+        let mut ids = Vec::new();
+        let mut after = None;
+        loop {
+            let first_page = after.is_none();
+            let pagination = cursor_query(&sort, after).apply(items());
+            assert_eq!(pagination.total().is_some(), first_page, "{}", name);
+
+            ids.extend(pagination.items().iter().map(|item| item.id));
+            after = pagination.next_cursor().cloned();
+            if after.is_none() {
+                break;
+            }
+        }
+        assert_eq!(ids, expected, "{}", name);
+    }
+
+    // Records created after the first page don't shift the next ones.
+    let sort = [(ItemField::Price, Direction::Desc)];
+    let after = cursor_query(&sort, None)
+        .apply(items())
+        .next_cursor()
+        .cloned();
+    let mut records = items();
+    records.push(item("6", "New", 500, vec![], None, "2020-06-01T00:00:00Z"));
+    let pagination = cursor_query(&sort, after).apply(records);
+    let ids: Vec<&str> = pagination.items().iter().map(|item| item.id).collect();
+    assert_eq!(ids, vec!["5", "1"]);
+
+    // Cursors can't be used with other sort keys.
+    let after = cursor_query(&sort, None)
+        .apply(items())
+        .next_cursor()
+        .cloned();
+    let err = Query::new()
+        .sort(ItemField::Price, Direction::Asc)
+        .cursor(ItemField::Id, after)
+        .err()
+        .unwrap();
+    assert_eq!(err.code(), "invalid");
+}
+
+async fn fetch(client: &PostgresClient, query: &Query<ItemField>) -> Vec<String> {
+    let sql = query.to_sql();
+    let rows = client
+        .query(
+            &format!(
+                "SELECT id FROM sql_items
+                {}
+                {}
+                OFFSET {}
+                LIMIT {}",
+                sql.where_clause(),
+                sql.order_by(),
+                query.offset().unwrap_or(0),
+                query.limit().map_or("ALL".to_owned(), |l| l.to_string()),
+            ) as &str,
+            &sql.params(),
+        )
+        .await
+        .unwrap();
+
+    rows.iter().map(|row| row.get("id")).collect()
+}
+
+// Requires Postgres, configured with the same variables as the server. Run it with
+// `cargo test -- --ignored`.
+#[tokio::test]
+#[ignore]
+async fn postgres() {
+    use crate::config::Config;
+    use crate::infrastructure::postgres::PostgresPool;
+    use crate::infrastructure::transaction::PostgresUnitOfWork;
+    use crate::transaction;
+
+    let pool = PostgresPool::connect(&Config::load().unwrap())
+        .await
+        .unwrap();
+    let client = PostgresClient::new(pool.clone());
+    let uow = PostgresUnitOfWork::new(pool);
+
+    // The temporary table only exists in the connection of the transaction.
+    transaction::run(&uow, async {
+        client
+            .execute(
+                "CREATE TEMPORARY TABLE sql_items (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    price INTEGER NOT NULL,
+                    tags JSONB NOT NULL,
+                    category TEXT,
+                    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+                ) ON COMMIT DROP",
+                &[],
+            )
+            .await
+            .unwrap();
+
+        for item in items().into_iter() {
+            let tags: Vec<_> = item.tags.iter().map(|tag| json!({ "slug": tag })).collect();
+            client
+                .execute(
+                    "INSERT INTO sql_items VALUES ($1, $2, $3, $4, $5, $6)",
+                    &[
+                        &item.id,
+                        &item.name,
+                        &(item.price as i32),
+                        &json!(tags),
+                        &item.category,
+                        &item.created_at,
+                    ],
+                )
+                .await
+                .unwrap();
+        }
+
+        for (name, query, expected) in cases().into_iter() {
+            let ids = fetch(&client, &query).await;
+            assert_eq!(ids, expected, "{}", name);
+        }
+
+        for (name, sort, expected) in cursor_cases().into_iter() {
+            let mut ids = Vec::new();
+            let mut after = None;
+            loop {
+                let query = cursor_query(&sort, after);
+                let page: Vec<Item> = fetch(&client, &query)
+                    .await
+                    .iter()
+                    .map(|id| item_by_id(id))
+                    .collect();
+
+                ids.extend(page.iter().map(|item| item.id));
+                after = query.next_cursor(&page);
+                if after.is_none() {
+                    break;
+                }
+            }
+            assert_eq!(ids, expected, "{}", name);
+        }
+
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
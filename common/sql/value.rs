@@ -0,0 +1,158 @@
+use std::cmp::Ordering;
+use std::error::Error;
+
+use bytes::BytesMut;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio_postgres::types::{IsNull, ToSql, Type};
+use uuid::Uuid;
+
+use crate::model::StringId;
+
+/// Value compared with a field in a criteria. It's bound as a parameter in SQL and compared
+/// directly with the values of the records in memory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Uuid(Uuid),
+    DateTime(DateTime<Utc>),
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Value {
+    /// Values of different kinds can't be compared, except numbers.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Value::Bool(a), Value::Bool(b)) => a.partial_cmp(b),
+            (Value::Int(a), Value::Int(b)) => a.partial_cmp(b),
+            (Value::Float(a), Value::Float(b)) => a.partial_cmp(b),
+            (Value::Int(a), Value::Float(b)) => (*a as f64).partial_cmp(b),
+            (Value::Float(a), Value::Int(b)) => a.partial_cmp(&(*b as f64)),
+            (Value::Text(a), Value::Text(b)) => a.partial_cmp(b),
+            (Value::Uuid(a), Value::Uuid(b)) => a.partial_cmp(b),
+            (Value::DateTime(a), Value::DateTime(b)) => a.partial_cmp(b),
+            _ => None,
+        }
+    }
+}
+
+impl From<bool> for Value {
+    fn from(v: bool) -> Self {
+        Value::Bool(v)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(v: i32) -> Self {
+        Value::Int(v as i64)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(v: i64) -> Self {
+        Value::Int(v)
+    }
+}
+
+impl From<u32> for Value {
+    fn from(v: u32) -> Self {
+        Value::Int(v as i64)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(v: f64) -> Self {
+        Value::Float(v)
+    }
+}
+
+impl From<String> for Value {
+    fn from(v: String) -> Self {
+        Value::Text(v)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(v: &str) -> Self {
+        Value::Text(v.to_owned())
+    }
+}
+
+impl From<&StringId> for Value {
+    fn from(v: &StringId) -> Self {
+        Value::Text(v.value().to_owned())
+    }
+}
+
+impl From<Uuid> for Value {
+    fn from(v: Uuid) -> Self {
+        Value::Uuid(v)
+    }
+}
+
+impl From<DateTime<Utc>> for Value {
+    fn from(v: DateTime<Utc>) -> Self {
+        Value::DateTime(v)
+    }
+}
+
+impl From<&DateTime<Utc>> for Value {
+    fn from(v: &DateTime<Utc>) -> Self {
+        Value::DateTime(*v)
+    }
+}
+
+impl ToSql for Value {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        // Numbers and ids are converted to the type of the column, which is inferred by Postgres.
+        match self {
+            Value::Bool(v) => v.to_sql(ty, out),
+            Value::Int(v) if *ty == Type::INT2 => (*v as i16).to_sql(ty, out),
+            Value::Int(v) if *ty == Type::INT4 => (*v as i32).to_sql(ty, out),
+            Value::Int(v) if *ty == Type::FLOAT8 => (*v as f64).to_sql(ty, out),
+            Value::Int(v) => v.to_sql(ty, out),
+            Value::Float(v) if *ty == Type::FLOAT4 => (*v as f32).to_sql(ty, out),
+            Value::Float(v) => v.to_sql(ty, out),
+            Value::Text(v) if *ty == Type::UUID => Uuid::parse_str(v)?.to_sql(ty, out),
+            Value::Text(v) => v.to_sql(ty, out),
+            Value::Uuid(v) => v.to_sql(ty, out),
+            Value::DateTime(v) => v.to_sql(ty, out),
+        }
+    }
+
+    fn accepts(_ty: &Type) -> bool {
+        true
+    }
+
+    fn to_sql_checked(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        match self {
+            Value::Bool(v) => v.to_sql_checked(ty, out),
+            Value::Int(v) if *ty == Type::INT2 => (*v as i16).to_sql_checked(ty, out),
+            Value::Int(v) if *ty == Type::INT4 => (*v as i32).to_sql_checked(ty, out),
+            Value::Int(v) if *ty == Type::FLOAT8 => (*v as f64).to_sql_checked(ty, out),
+            Value::Int(v) => v.to_sql_checked(ty, out),
+            Value::Float(v) if *ty == Type::FLOAT4 => (*v as f32).to_sql_checked(ty, out),
+            Value::Float(v) => v.to_sql_checked(ty, out),
+            Value::Text(v) if *ty == Type::UUID => Uuid::parse_str(v)?.to_sql_checked(ty, out),
+            Value::Text(v) => v.to_sql_checked(ty, out),
+            Value::Uuid(v) => v.to_sql_checked(ty, out),
+            Value::DateTime(v) => v.to_sql_checked(ty, out),
+        }
+    }
+}
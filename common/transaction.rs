@@ -1,7 +1,62 @@
-use crate::error::Error;
+use std::any::Any;
+use std::future::Future;
+use std::sync::Arc;
 
-pub trait Transaction {
-    fn begin(&mut self) -> Option<Error>;
-    fn commit(&mut self) -> Option<Error>;
-    fn rollback(&mut self) -> Option<Error>;
+use async_trait::async_trait;
+
+use crate::result::Result;
+
+tokio::task_local! {
+    static CURRENT: Arc<dyn Transaction>;
+}
+
+/// Transaction is a unit of work shared by every repository used inside of it. Repositories
+/// join the current transaction (see `current`) instead of receiving it as a parameter, so use
+/// cases don't depend on the storage.
+#[async_trait]
+pub trait Transaction: Sync + Send {
+    async fn begin(&self) -> Result<()>;
+    async fn commit(&self) -> Result<()>;
+    async fn rollback(&self) -> Result<()>;
+
+    /// Lets repositories access the implementation they know how to join.
+    fn as_any(&self) -> &(dyn Any + Send + Sync);
+}
+
+/// UnitOfWork creates transactions for a specific storage.
+pub trait UnitOfWork: Sync + Send {
+    fn transaction(&self) -> Arc<dyn Transaction>;
+}
+
+/// Transaction of the current scope, if any.
+pub fn current() -> Option<Arc<dyn Transaction>> {
+    CURRENT.try_with(|tx| Arc::clone(tx)).ok()
+}
+
+/// Runs `f` inside a transaction. It's committed if `f` succeeds and rolled back otherwise. If
+/// there is already a transaction in the current scope `f` joins it, and the outermost `run`
+/// decides the outcome.
+pub async fn run<T, F>(unit_of_work: &dyn UnitOfWork, f: F) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    if current().is_some() {
+        return f.await;
+    }
+
+    let tx = unit_of_work.transaction();
+    tx.begin().await?;
+
+    match CURRENT.scope(Arc::clone(&tx), f).await {
+        Ok(res) => {
+            tx.commit().await?;
+            Ok(res)
+        }
+        Err(err) => {
+            if let Err(rollback_err) = tx.rollback().await {
+                println!("{:?}", rollback_err);
+            }
+            Err(err)
+        }
+    }
 }
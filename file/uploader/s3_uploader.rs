@@ -19,8 +19,7 @@ pub struct S3FileUploader {
 }
 
 impl S3FileUploader {
-    pub fn new() -> Self {
-        let config = Config::get();
+    pub fn new(config: &Config) -> Self {
         let region = Region::default();
 
         S3FileUploader {
@@ -41,12 +40,6 @@ impl S3FileUploader {
     }
 }
 
-impl Default for S3FileUploader {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 #[async_trait]
 impl FileUploader for S3FileUploader {
     async fn upload(&self, temp_file: TempFile) -> Result<UploadedFile> {
@@ -1,13 +1,19 @@
 use serde::Serialize;
 
+use common::request::Schema;
+
+use crate::domain::oauth::ExternalIdentity;
 use crate::domain::role::{Permission, Role};
-use crate::domain::user::User;
+use crate::domain::token::{Session, SessionTokens};
+use crate::domain::user::{LoginChallenge, TwoFactor, User};
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct UserDto {
     pub id: String,
     pub username: String,
     pub email: String,
+    /// `local` for users with a password, or the identity provider they registered with.
+    pub provider: String,
     pub name: Option<String>,
     pub lastname: Option<String>,
     pub birthdate: Option<String>,
@@ -15,15 +21,42 @@ pub struct UserDto {
     pub biography: Option<String>,
     pub profile_image: Option<String>,
     pub validated: bool,
+    pub two_factor: bool,
     pub role_id: Option<String>,
     pub role: Option<RoleDto>,
     pub payment_email: Option<String>,
     pub flag: i64,
     pub created_at: String,
     pub updated_at: Option<String>,
+    pub deleted_at: Option<String>,
 }
 
 impl UserDto {
+    pub const SCHEMA: Schema = Schema::new(
+        &[
+            "id",
+            "username",
+            "email",
+            "provider",
+            "name",
+            "lastname",
+            "birthdate",
+            "gender",
+            "biography",
+            "profile_image",
+            "validated",
+            "two_factor",
+            "role_id",
+            "role",
+            "payment_email",
+            "flag",
+            "created_at",
+            "updated_at",
+            "deleted_at",
+        ],
+        &[("role", &RoleDto::SCHEMA)],
+    );
+
     pub fn role(mut self, role: RoleDto) -> Self {
         self.role_id = None;
         self.role = Some(role);
@@ -37,6 +70,7 @@ impl From<&User> for UserDto {
             id: user.base().id().to_string(),
             username: user.identity().username().to_string(),
             email: user.identity().email().to_string(),
+            provider: user.identity().provider().to_string(),
             name: user.person().map(|p| p.fullname().name().to_string()),
             lastname: user.person().map(|p| p.fullname().lastname().to_string()),
             birthdate: user
@@ -56,17 +90,19 @@ impl From<&User> for UserDto {
                 .map(|p| p.profile_image().map(|i| i.to_string()))
                 .flatten(),
             validated: user.is_validated(),
+            two_factor: user.has_two_factor(),
             role_id: Some(user.role_id().to_string()),
             role: None,
             payment_email: user.payment_email().map(|p| p.to_string()),
             flag: user.flag(),
             created_at: user.base().created_at().to_rfc3339(),
             updated_at: user.base().updated_at().map(|d| d.to_rfc3339()),
+            deleted_at: user.base().deleted_at().map(|d| d.to_rfc3339()),
         }
     }
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct PermissionDto {
     pub id: String,
     pub name: String,
@@ -81,14 +117,32 @@ impl PermissionDto {
     }
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct RoleDto {
     pub id: String,
     pub name: String,
     pub permissions: Vec<PermissionDto>,
     pub default: bool,
+    pub two_factor_required: bool,
     pub created_at: String,
     pub updated_at: Option<String>,
+    pub deleted_at: Option<String>,
+}
+
+impl RoleDto {
+    pub const SCHEMA: Schema = Schema::new(
+        &[
+            "id",
+            "name",
+            "permissions",
+            "default",
+            "two_factor_required",
+            "created_at",
+            "updated_at",
+            "deleted_at",
+        ],
+        &[],
+    );
 }
 
 impl From<&Role> for RoleDto {
@@ -98,8 +152,106 @@ impl From<&Role> for RoleDto {
             name: role.name().to_string(),
             permissions: role.permissions().iter().map(PermissionDto::from).collect(),
             default: role.is_default(),
+            two_factor_required: role.requires_two_factor(),
             created_at: role.base().created_at().to_rfc3339(),
             updated_at: role.base().updated_at().map(|d| d.to_rfc3339()),
+            deleted_at: role.base().deleted_at().map(|d| d.to_rfc3339()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct SessionDto {
+    pub id: String,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+    pub created_at: String,
+    pub refreshed_at: String,
+    pub expires_at: String,
+}
+
+impl From<&Session> for SessionDto {
+    fn from(session: &Session) -> Self {
+        SessionDto {
+            id: session.id().to_string(),
+            user_agent: session.device().user_agent().cloned(),
+            ip: session.device().ip().cloned(),
+            created_at: session.created_at().to_rfc3339(),
+            refreshed_at: session.refreshed_at().to_rfc3339(),
+            expires_at: session.expires_at().to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ExternalIdentityDto {
+    pub provider: String,
+    pub email: Option<String>,
+    pub linked_at: String,
+}
+
+impl From<&ExternalIdentity> for ExternalIdentityDto {
+    fn from(external_identity: &ExternalIdentity) -> Self {
+        ExternalIdentityDto {
+            provider: external_identity.provider().to_string(),
+            email: external_identity.email().map(|email| email.to_string()),
+            linked_at: external_identity.linked_at().to_rfc3339(),
+        }
+    }
+}
+
+/// Tokens of a session. The access token expires at `expires_at`, and then a new one is obtained
+/// with the refresh token.
+#[derive(Serialize)]
+pub struct SessionTokensDto {
+    pub session_id: String,
+    pub auth_token: String,
+    pub refresh_token: String,
+    pub expires_at: String,
+}
+
+impl From<&SessionTokens> for SessionTokensDto {
+    fn from(tokens: &SessionTokens) -> Self {
+        SessionTokensDto {
+            session_id: tokens.session_id.to_string(),
+            auth_token: tokens.access_token.to_string(),
+            refresh_token: tokens.refresh_token.to_string(),
+            expires_at: tokens.expires_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Second step of a login with two-factor authentication, completed with a code of the
+/// authenticator app.
+#[derive(Serialize)]
+pub struct TwoFactorChallengeDto {
+    pub challenge: String,
+    /// Whether the user has to enroll before entering a code, because their role requires
+    /// two-factor authentication.
+    pub enrollment_required: bool,
+}
+
+impl TwoFactorChallengeDto {
+    pub fn new(user: &User, challenge: &LoginChallenge) -> Self {
+        TwoFactorChallengeDto {
+            challenge: challenge.id().to_owned(),
+            enrollment_required: !user.has_two_factor(),
+        }
+    }
+}
+
+/// Secret added to the authenticator app, directly or scanning `otpauth_uri` as a QR code.
+#[derive(Serialize)]
+pub struct TwoFactorEnrollmentDto {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+impl TwoFactorEnrollmentDto {
+    pub fn new(user: &User, two_factor: &TwoFactor) -> Self {
+        TwoFactorEnrollmentDto {
+            secret: two_factor.secret().to_owned(),
+            otpauth_uri: two_factor.otpauth_uri(user.identity().username().value()),
         }
     }
 }
@@ -0,0 +1,68 @@
+use std::collections::{HashMap, HashSet};
+
+use common::error::Error;
+use common::request::Include;
+use common::result::Result;
+
+use crate::application::dtos::{RoleDto, UserDto};
+use crate::domain::role::{RoleId, RoleRepository};
+use crate::domain::user::User;
+
+/// Expand builds DTOs with the relations requested in `Include`. Each relation is loaded for all
+/// the DTOs with a single repository call.
+pub struct Expand<'a> {
+    role_repo: &'a dyn RoleRepository,
+}
+
+impl<'a> Expand<'a> {
+    pub fn new(role_repo: &'a dyn RoleRepository) -> Self {
+        Expand { role_repo }
+    }
+
+    pub async fn user(&self, user: &User, include: &Include) -> Result<UserDto> {
+        let mut user_dtos = self.users(std::slice::from_ref(user), include).await?;
+        Ok(user_dtos.remove(0))
+    }
+
+    pub async fn users(&self, users: &[User], include: &Include) -> Result<Vec<UserDto>> {
+        let roles = if include.has("role") {
+            Some(self.roles(users.iter().map(|u| u.role_id())).await?)
+        } else {
+            None
+        };
+
+        users
+            .iter()
+            .map(|user| {
+                let mut user_dto = UserDto::from(user);
+
+                if let Some(roles) = &roles {
+                    let role = roles
+                        .get(user.role_id())
+                        .cloned()
+                        .ok_or_else(|| Error::not_found("role"))?;
+                    user_dto = user_dto.role(role);
+                }
+
+                Ok(user_dto)
+            })
+            .collect()
+    }
+
+    async fn roles<'b, I>(&self, ids: I) -> Result<HashMap<RoleId, RoleDto>>
+    where
+        I: Iterator<Item = &'b RoleId>,
+    {
+        let ids: Vec<RoleId> = ids
+            .cloned()
+            .collect::<HashSet<RoleId>>()
+            .into_iter()
+            .collect();
+        let roles = self.role_repo.find_by_ids(&ids).await?;
+
+        Ok(roles
+            .iter()
+            .map(|role| (role.base().id().clone(), RoleDto::from(role)))
+            .collect())
+    }
+}
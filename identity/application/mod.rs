@@ -1,3 +1,4 @@
 pub mod dtos;
+pub mod expand;
 pub mod role;
 pub mod user;
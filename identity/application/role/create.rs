@@ -11,6 +11,8 @@ use crate::UserIdAndRole;
 pub struct CreateCommand {
     pub name: String,
     pub permissions: Vec<String>,
+    #[serde(default)]
+    pub two_factor_required: bool,
 }
 
 #[derive(Serialize)]
@@ -66,6 +68,7 @@ impl<'a> Create<'a> {
             .collect();
 
         role.set_permissions(permissions_to_set)?;
+        role.set_two_factor_required(cmd.two_factor_required)?;
 
         self.role_repo.save(&mut role).await?;
 
@@ -1,9 +1,10 @@
 use common::error::Error;
 use common::request::CommandResponse;
 use common::result::Result;
+use common::sql::{Criteria, Query};
 
 use crate::domain::role::{RoleId, RoleRepository};
-use crate::domain::user::UserRepository;
+use crate::domain::user::{UserField, UserRepository};
 use crate::UserIdAndRole;
 
 pub struct Delete<'a> {
@@ -32,7 +33,11 @@ impl<'a> Delete<'a> {
 
         let p_users = self
             .user_repo
-            .search(None, Some(&role_id), None, None, None, Some(5), None)
+            .search(
+                &Query::new()
+                    .filter(Criteria::eq(UserField::RoleId, &role_id))
+                    .paginate(None, Some(5)),
+            )
             .await?;
 
         if p_users.count() > 0 {
@@ -47,7 +52,7 @@ impl<'a> Delete<'a> {
 
         role.delete()?;
 
-        self.role_repo.delete(&role_id).await?;
+        self.role_repo.save(&mut role).await?;
 
         Ok(CommandResponse::default())
     }
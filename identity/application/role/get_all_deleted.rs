@@ -0,0 +1,29 @@
+use common::error::Error;
+use common::result::Result;
+
+use crate::application::dtos::RoleDto;
+use crate::application::role::GetAllResponse;
+use crate::domain::role::RoleRepository;
+use crate::UserIdAndRole;
+
+pub struct GetAllDeleted<'a> {
+    role_repo: &'a dyn RoleRepository,
+}
+
+impl<'a> GetAllDeleted<'a> {
+    pub fn new(role_repo: &'a dyn RoleRepository) -> Self {
+        GetAllDeleted { role_repo }
+    }
+
+    pub async fn exec(&self, (_auth_id, auth_role): UserIdAndRole) -> Result<GetAllResponse> {
+        if !auth_role.can("restore_deleted") {
+            return Err(Error::unauthorized());
+        }
+
+        let roles = self.role_repo.find_all_deleted().await?;
+
+        Ok(GetAllResponse {
+            roles: roles.iter().map(RoleDto::from).collect(),
+        })
+    }
+}
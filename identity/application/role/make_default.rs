@@ -1,17 +1,23 @@
 use common::error::Error;
 use common::request::CommandResponse;
 use common::result::Result;
+use common::transaction::{self, UnitOfWork};
 
 use crate::domain::role::{RoleId, RoleRepository};
 use crate::UserIdAndRole;
 
 pub struct MakeDefault<'a> {
     role_repo: &'a dyn RoleRepository,
+
+    unit_of_work: &'a dyn UnitOfWork,
 }
 
 impl<'a> MakeDefault<'a> {
-    pub fn new(role_repo: &'a dyn RoleRepository) -> Self {
-        MakeDefault { role_repo }
+    pub fn new(role_repo: &'a dyn RoleRepository, unit_of_work: &'a dyn UnitOfWork) -> Self {
+        MakeDefault {
+            role_repo,
+            unit_of_work,
+        }
     }
 
     pub async fn exec(
@@ -33,9 +39,92 @@ impl<'a> MakeDefault<'a> {
         role.set_default(true)?;
         default_role.set_default(false)?;
 
-        self.role_repo.save(&mut role).await?;
-        self.role_repo.save(&mut default_role).await?;
+        // There must always be exactly one default role.
+        transaction::run(self.unit_of_work, async {
+            self.role_repo.save(&mut role).await?;
+            self.role_repo.save(&mut default_role).await
+        })
+        .await?;
 
         Ok(CommandResponse::default())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_trait::async_trait;
+
+    use crate::domain::role::Role;
+    use crate::domain::user::UserId;
+    use crate::infrastructure::persistence::inmem::InMemRoleRepository;
+    use crate::mocks;
+
+    /// Fails to save roles that are not the default one.
+    struct FailingRoleRepository {
+        inner: InMemRoleRepository,
+    }
+
+    #[async_trait]
+    impl RoleRepository for FailingRoleRepository {
+        async fn find_all(&self) -> Result<Vec<Role>> {
+            self.inner.find_all().await
+        }
+
+        async fn find_by_id(&self, id: &RoleId) -> Result<Role> {
+            self.inner.find_by_id(id).await
+        }
+
+        async fn find_by_ids(&self, ids: &[RoleId]) -> Result<Vec<Role>> {
+            self.inner.find_by_ids(ids).await
+        }
+
+        async fn find_all_deleted(&self) -> Result<Vec<Role>> {
+            self.inner.find_all_deleted().await
+        }
+
+        async fn find_deleted_by_id(&self, id: &RoleId) -> Result<Role> {
+            self.inner.find_deleted_by_id(id).await
+        }
+
+        async fn find_by_user_id(&self, user_id: &UserId) -> Result<Role> {
+            self.inner.find_by_user_id(user_id).await
+        }
+
+        async fn find_default(&self) -> Result<Role> {
+            self.inner.find_default().await
+        }
+
+        async fn save(&self, role: &mut Role) -> Result<()> {
+            if !role.is_default() {
+                return Err(Error::internal("role_repository", "save"));
+            }
+
+            self.inner.save(role).await
+        }
+
+        async fn delete(&self, id: &RoleId) -> Result<()> {
+            self.inner.delete(id).await
+        }
+    }
+
+    #[tokio::test]
+    async fn roll_back_when_a_role_fails_to_be_saved() {
+        let c = mocks::container();
+        let role_repo = FailingRoleRepository {
+            inner: InMemRoleRepository::new(),
+        };
+        let uc = MakeDefault::new(&role_repo, c.unit_of_work());
+
+        let mut role = mocks::role("Editor");
+        role_repo.inner.save(&mut role).await.unwrap();
+        let admin = (UserId::new("admin-1").unwrap(), mocks::role("Admin"));
+
+        // The new default role is saved, but the old one fails.
+        assert!(uc.exec(admin, "editor".to_owned()).await.is_err());
+
+        let role = role_repo.find_by_id(role.base().id()).await.unwrap();
+        assert!(!role.is_default());
+    }
+}
@@ -1,14 +1,18 @@
 mod create;
 mod delete;
 mod get_all;
+mod get_all_deleted;
 mod get_by_id;
 mod get_permissions;
 mod make_default;
+mod restore;
 mod update;
 pub use create::*;
 pub use delete::*;
 pub use get_all::*;
+pub use get_all_deleted::*;
 pub use get_by_id::*;
 pub use get_permissions::*;
 pub use make_default::*;
+pub use restore::*;
 pub use update::*;
@@ -0,0 +1,71 @@
+use common::error::Error;
+use common::request::CommandResponse;
+use common::result::Result;
+
+use crate::domain::role::{RoleId, RoleRepository};
+use crate::UserIdAndRole;
+
+pub struct Restore<'a> {
+    role_repo: &'a dyn RoleRepository,
+}
+
+impl<'a> Restore<'a> {
+    pub fn new(role_repo: &'a dyn RoleRepository) -> Self {
+        Restore { role_repo }
+    }
+
+    pub async fn exec(
+        &self,
+        (_auth_id, auth_role): UserIdAndRole,
+        role_id: String,
+    ) -> Result<CommandResponse> {
+        if !auth_role.can("restore_deleted") {
+            return Err(Error::unauthorized());
+        }
+
+        let role_id = RoleId::new(role_id)?;
+        let mut role = self.role_repo.find_deleted_by_id(&role_id).await?;
+
+        role.restore()?;
+
+        self.role_repo.save(&mut role).await?;
+
+        Ok(CommandResponse::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::application::role::{Delete, GetAllDeleted};
+    use crate::domain::user::UserId;
+    use crate::mocks;
+
+    #[tokio::test]
+    async fn restore_deleted_role() {
+        let c = mocks::container();
+        let uc = Restore::new(c.role_repo());
+
+        let mut role = mocks::role("Editor");
+        c.role_repo().save(&mut role).await.unwrap();
+        let admin = (UserId::new("admin-1").unwrap(), mocks::role("Admin"));
+
+        Delete::new(c.role_repo(), c.user_repo())
+            .exec(admin.clone(), "editor".to_owned())
+            .await
+            .unwrap();
+        assert!(c.role_repo().find_by_id(role.base().id()).await.is_err());
+
+        let res = GetAllDeleted::new(c.role_repo())
+            .exec(admin.clone())
+            .await
+            .unwrap();
+        assert_eq!(res.roles.len(), 1);
+        assert_eq!(res.roles[0].id, "editor");
+
+        uc.exec(admin.clone(), "editor".to_owned()).await.unwrap();
+        assert!(c.role_repo().find_by_id(role.base().id()).await.is_ok());
+        assert!(uc.exec(admin, "editor".to_owned()).await.is_err());
+    }
+}
@@ -12,6 +12,7 @@ use crate::UserIdAndRole;
 pub struct UpdateCommand {
     pub name: Option<String>,
     pub permissions: Option<Vec<String>>,
+    pub two_factor_required: Option<bool>,
 }
 
 pub struct Update<'a> {
@@ -65,6 +66,10 @@ impl<'a> Update<'a> {
             role.set_permissions(permissions_to_set)?;
         }
 
+        if let Some(two_factor_required) = cmd.two_factor_required {
+            role.set_two_factor_required(two_factor_required)?;
+        }
+
         self.role_repo.save(&mut role).await?;
 
         Ok(CommandResponse::default())
@@ -0,0 +1,39 @@
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use common::result::Result;
+
+use crate::domain::oauth::OAuthService;
+use crate::domain::user::Provider;
+
+#[derive(Serialize)]
+pub struct AuthorizeProviderResponse {
+    pub authorization_url: String,
+    /// Key the browser has to send to complete the authorization. It's stored in a cookie
+    /// instead of being returned in the body.
+    #[serde(skip)]
+    pub browser_key: String,
+}
+
+pub struct AuthorizeProvider<'a> {
+    oauth_serv: &'a OAuthService,
+}
+
+impl<'a> AuthorizeProvider<'a> {
+    pub fn new(oauth_serv: &'a OAuthService) -> Self {
+        AuthorizeProvider { oauth_serv }
+    }
+
+    /// Starts logging in with an identity provider. The user has to be redirected to the
+    /// returned URL.
+    pub async fn exec(&self, provider: String) -> Result<AuthorizeProviderResponse> {
+        let provider = Provider::from_str(&provider)?;
+        let (authorization_url, browser_key) = self.oauth_serv.authorize(&provider, None).await?;
+
+        Ok(AuthorizeProviderResponse {
+            authorization_url,
+            browser_key,
+        })
+    }
+}
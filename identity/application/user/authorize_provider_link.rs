@@ -0,0 +1,42 @@
+use std::str::FromStr;
+
+use common::error::Error;
+use common::result::Result;
+
+use crate::application::user::AuthorizeProviderResponse;
+use crate::domain::oauth::OAuthService;
+use crate::domain::user::{Provider, UserId};
+use crate::UserIdAndRole;
+
+pub struct AuthorizeProviderLink<'a> {
+    oauth_serv: &'a OAuthService,
+}
+
+impl<'a> AuthorizeProviderLink<'a> {
+    pub fn new(oauth_serv: &'a OAuthService) -> Self {
+        AuthorizeProviderLink { oauth_serv }
+    }
+
+    /// Starts linking an account of an identity provider to the user. Only the user can link
+    /// their accounts.
+    pub async fn exec(
+        &self,
+        (auth_id, _auth_role): UserIdAndRole,
+        user_id: String,
+        provider: String,
+    ) -> Result<AuthorizeProviderResponse> {
+        let user_id = UserId::new(user_id)?;
+        if auth_id != user_id {
+            return Err(Error::unauthorized());
+        }
+
+        let provider = Provider::from_str(&provider)?;
+        let (authorization_url, browser_key) =
+            self.oauth_serv.authorize(&provider, Some(user_id)).await?;
+
+        Ok(AuthorizeProviderResponse {
+            authorization_url,
+            browser_key,
+        })
+    }
+}
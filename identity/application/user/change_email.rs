@@ -0,0 +1,65 @@
+use serde::Deserialize;
+
+use common::error::Error;
+use common::event::EventPublisher;
+use common::request::CommandResponse;
+use common::result::Result;
+
+use crate::domain::user::{EmailChangeSender, UserId, UserRepository, UserService};
+use crate::UserIdAndRole;
+
+#[derive(Deserialize)]
+pub struct ChangeEmailCommand {
+    pub email: String,
+}
+
+pub struct ChangeEmail<'a> {
+    event_pub: &'a dyn EventPublisher,
+
+    user_repo: &'a dyn UserRepository,
+
+    email_change_sender: &'a dyn EmailChangeSender,
+    user_serv: &'a UserService,
+}
+
+impl<'a> ChangeEmail<'a> {
+    pub fn new(
+        event_pub: &'a dyn EventPublisher,
+        user_repo: &'a dyn UserRepository,
+        email_change_sender: &'a dyn EmailChangeSender,
+        user_serv: &'a UserService,
+    ) -> Self {
+        ChangeEmail {
+            event_pub,
+            user_repo,
+            email_change_sender,
+            user_serv,
+        }
+    }
+
+    /// Sends a code to the new email, which is confirmed with `ConfirmEmailChange`. Only the
+    /// user can change it, because they have to receive the code.
+    pub async fn exec(
+        &self,
+        (auth_id, auth_role): UserIdAndRole,
+        user_id: String,
+        cmd: ChangeEmailCommand,
+    ) -> Result<CommandResponse> {
+        let user_id = UserId::new(user_id)?;
+        if auth_id != user_id || !auth_role.can("update_own_user") {
+            return Err(Error::unauthorized());
+        }
+
+        let mut user = self.user_repo.find_by_id(&user_id).await?;
+
+        let code = self.user_serv.change_email(&mut user, &cmd.email).await?;
+
+        self.user_repo.save(&mut user).await?;
+
+        self.email_change_sender.send(&user, &code).await?;
+
+        self.event_pub.publish_all(user.events().to_vec()?).await?;
+
+        Ok(CommandResponse::default())
+    }
+}
@@ -0,0 +1,119 @@
+use serde::Deserialize;
+
+use common::error::Error;
+use common::event::EventPublisher;
+use common::request::CommandResponse;
+use common::result::Result;
+
+use crate::domain::user::{UserId, UserRepository, UserService};
+use crate::UserIdAndRole;
+
+#[derive(Deserialize)]
+pub struct ChangeUsernameCommand {
+    pub username: String,
+}
+
+pub struct ChangeUsername<'a> {
+    event_pub: &'a dyn EventPublisher,
+
+    user_repo: &'a dyn UserRepository,
+
+    user_serv: &'a UserService,
+}
+
+impl<'a> ChangeUsername<'a> {
+    pub fn new(
+        event_pub: &'a dyn EventPublisher,
+        user_repo: &'a dyn UserRepository,
+        user_serv: &'a UserService,
+    ) -> Self {
+        ChangeUsername {
+            event_pub,
+            user_repo,
+            user_serv,
+        }
+    }
+
+    pub async fn exec(
+        &self,
+        (auth_id, auth_role): UserIdAndRole,
+        user_id: String,
+        cmd: ChangeUsernameCommand,
+    ) -> Result<CommandResponse> {
+        let user_id = UserId::new(user_id)?;
+        if !auth_role.can("update_any_user") {
+            if auth_id != user_id || !auth_role.can("update_own_user") {
+                return Err(Error::unauthorized());
+            }
+        }
+
+        let mut user = self.user_repo.find_by_id(&user_id).await?;
+
+        self.user_serv
+            .change_username(&mut user, &cmd.username)
+            .await?;
+
+        self.user_repo.save(&mut user).await?;
+
+        self.event_pub.publish_all(user.events().to_vec()?).await?;
+
+        Ok(CommandResponse::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::mocks;
+
+    #[tokio::test]
+    async fn change_own_username() {
+        let c = mocks::container();
+        let uc = ChangeUsername::new(c.event_pub(), c.user_repo(), c.user_serv());
+
+        let mut user = mocks::user(
+            "user-1",
+            "username",
+            "user@omics.com",
+            "P@asswd!",
+            true,
+            None,
+            None,
+            "user",
+        );
+        c.user_repo().save(&mut user).await.unwrap();
+        let role = mocks::role("User");
+
+        uc.exec(
+            (user.base().id().clone(), role.clone()),
+            user.base().id().to_string(),
+            ChangeUsernameCommand {
+                username: "new-username".to_owned(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let user = c.user_repo().find_by_id(user.base().id()).await.unwrap();
+        assert_eq!(user.identity().username().value(), "new-username");
+        assert_eq!(
+            c.event_pub().events().await.last().unwrap().code(),
+            "username-changed"
+        );
+
+        // The cooldown has to end before changing it again.
+        let err = uc
+            .exec(
+                (user.base().id().clone(), role),
+                user.base().id().to_string(),
+                ChangeUsernameCommand {
+                    username: "other-username".to_owned(),
+                },
+            )
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "cooldown");
+    }
+}
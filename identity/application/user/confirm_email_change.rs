@@ -0,0 +1,157 @@
+use serde::Deserialize;
+
+use common::error::Error;
+use common::event::EventPublisher;
+use common::request::CommandResponse;
+use common::result::Result;
+
+use crate::domain::user::{UserId, UserRepository, UserService};
+use crate::UserIdAndRole;
+
+#[derive(Deserialize)]
+pub struct ConfirmEmailChangeCommand {
+    pub code: String,
+}
+
+pub struct ConfirmEmailChange<'a> {
+    event_pub: &'a dyn EventPublisher,
+
+    user_repo: &'a dyn UserRepository,
+
+    user_serv: &'a UserService,
+}
+
+impl<'a> ConfirmEmailChange<'a> {
+    pub fn new(
+        event_pub: &'a dyn EventPublisher,
+        user_repo: &'a dyn UserRepository,
+        user_serv: &'a UserService,
+    ) -> Self {
+        ConfirmEmailChange {
+            event_pub,
+            user_repo,
+            user_serv,
+        }
+    }
+
+    pub async fn exec(
+        &self,
+        (auth_id, auth_role): UserIdAndRole,
+        user_id: String,
+        cmd: ConfirmEmailChangeCommand,
+    ) -> Result<CommandResponse> {
+        let user_id = UserId::new(user_id)?;
+        if auth_id != user_id || !auth_role.can("update_own_user") {
+            return Err(Error::unauthorized());
+        }
+
+        let mut user = self.user_repo.find_by_id(&user_id).await?;
+
+        self.user_serv
+            .confirm_email_change(&mut user, &cmd.code)
+            .await?;
+
+        self.user_repo.save(&mut user).await?;
+
+        self.event_pub.publish_all(user.events().to_vec()?).await?;
+
+        Ok(CommandResponse::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::application::user::{ChangeEmail, ChangeEmailCommand};
+    use crate::mocks;
+
+    #[tokio::test]
+    async fn change_email_after_confirming_it() {
+        let c = mocks::container();
+        let change = ChangeEmail::new(
+            c.event_pub(),
+            c.user_repo(),
+            c.email_change_sender(),
+            c.user_serv(),
+        );
+        let confirm = ConfirmEmailChange::new(c.event_pub(), c.user_repo(), c.user_serv());
+
+        let mut user = mocks::user(
+            "user-1",
+            "username",
+            "user@omics.com",
+            "P@asswd!",
+            true,
+            None,
+            None,
+            "user",
+        );
+        c.user_repo().save(&mut user).await.unwrap();
+        let auth = (user.base().id().clone(), mocks::role("User"));
+        let user_id = user.base().id().to_string();
+
+        // Other users can't change it.
+        assert!(change
+            .exec(
+                (UserId::new("user-2").unwrap(), mocks::role("Admin")),
+                user_id.clone(),
+                ChangeEmailCommand {
+                    email: "new@omics.com".to_owned(),
+                },
+            )
+            .await
+            .is_err());
+
+        change
+            .exec(
+                auth.clone(),
+                user_id.clone(),
+                ChangeEmailCommand {
+                    email: "new@omics.com".to_owned(),
+                },
+            )
+            .await
+            .unwrap();
+
+        let mut user = c.user_repo().find_by_id(user.base().id()).await.unwrap();
+        assert_eq!(user.identity().email().value(), "user@omics.com");
+        let event = c.event_pub().events().await.pop().unwrap();
+        assert_eq!(event.code(), "email-change-requested");
+        assert!(event.payload()["EmailChangeRequested"]
+            .get("code")
+            .is_none());
+
+        // The code is only sent to the new email, so the change is requested again to know it.
+        let code = c
+            .user_serv()
+            .change_email(&mut user, "new@omics.com")
+            .await
+            .unwrap();
+        c.user_repo().save(&mut user).await.unwrap();
+
+        assert!(confirm
+            .exec(
+                auth.clone(),
+                user_id.clone(),
+                ConfirmEmailChangeCommand {
+                    code: "invalid".to_owned(),
+                },
+            )
+            .await
+            .is_err());
+
+        confirm
+            .exec(auth, user_id, ConfirmEmailChangeCommand { code })
+            .await
+            .unwrap();
+
+        let user = c.user_repo().find_by_id(user.base().id()).await.unwrap();
+        assert_eq!(user.identity().email().value(), "new@omics.com");
+        assert!(user.email_change().is_none());
+        assert_eq!(
+            c.event_pub().events().await.last().unwrap().code(),
+            "email-changed"
+        );
+    }
+}
@@ -36,7 +36,7 @@ impl<'a> Delete<'a> {
 
         user.delete()?;
 
-        self.user_repo.delete(user.base().id()).await?;
+        self.user_repo.save(&mut user).await?;
 
         self.event_pub.publish_all(user.events().to_vec()?).await?;
 
@@ -0,0 +1,58 @@
+use common::error::Error;
+use common::event::EventPublisher;
+use common::request::CommandResponse;
+use common::result::Result;
+
+use crate::application::user::TwoFactorCodeCommand;
+use crate::domain::role::RoleRepository;
+use crate::domain::user::{UserId, UserRepository};
+use crate::UserIdAndRole;
+
+pub struct DisableTwoFactor<'a> {
+    event_pub: &'a dyn EventPublisher,
+
+    role_repo: &'a dyn RoleRepository,
+    user_repo: &'a dyn UserRepository,
+}
+
+impl<'a> DisableTwoFactor<'a> {
+    pub fn new(
+        event_pub: &'a dyn EventPublisher,
+        role_repo: &'a dyn RoleRepository,
+        user_repo: &'a dyn UserRepository,
+    ) -> Self {
+        DisableTwoFactor {
+            event_pub,
+            role_repo,
+            user_repo,
+        }
+    }
+
+    /// Disables two-factor authentication with a code of the authenticator app or a recovery
+    /// code. Users whose role requires it can't disable it.
+    pub async fn exec(
+        &self,
+        (auth_id, _auth_role): UserIdAndRole,
+        user_id: String,
+        cmd: TwoFactorCodeCommand,
+    ) -> Result<CommandResponse> {
+        let user_id = UserId::new(user_id)?;
+        if auth_id != user_id {
+            return Err(Error::unauthorized());
+        }
+
+        let role = self.role_repo.find_by_user_id(&user_id).await?;
+        if role.requires_two_factor() {
+            return Err(Error::new("two_factor", "required"));
+        }
+
+        let mut user = self.user_repo.find_by_id(&user_id).await?;
+        user.disable_two_factor(&cmd.code)?;
+
+        self.user_repo.save(&mut user).await?;
+
+        self.event_pub.publish_all(user.events().to_vec()?).await?;
+
+        Ok(CommandResponse::default())
+    }
+}
@@ -0,0 +1,192 @@
+use serde::{Deserialize, Serialize};
+
+use common::error::Error;
+use common::event::EventPublisher;
+use common::result::Result;
+
+use crate::domain::user::{UserId, UserRepository};
+use crate::UserIdAndRole;
+
+/// Code of the authenticator app, or a recovery code.
+#[derive(Deserialize)]
+pub struct TwoFactorCodeCommand {
+    pub code: String,
+}
+
+/// Recovery codes are only shown once.
+#[derive(Serialize)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+pub struct EnableTwoFactor<'a> {
+    event_pub: &'a dyn EventPublisher,
+
+    user_repo: &'a dyn UserRepository,
+}
+
+impl<'a> EnableTwoFactor<'a> {
+    pub fn new(event_pub: &'a dyn EventPublisher, user_repo: &'a dyn UserRepository) -> Self {
+        EnableTwoFactor {
+            event_pub,
+            user_repo,
+        }
+    }
+
+    /// Enables two-factor authentication with the first code of the authenticator app the user
+    /// enrolled with.
+    pub async fn exec(
+        &self,
+        (auth_id, _auth_role): UserIdAndRole,
+        user_id: String,
+        cmd: TwoFactorCodeCommand,
+    ) -> Result<RecoveryCodesResponse> {
+        let user_id = UserId::new(user_id)?;
+        if auth_id != user_id {
+            return Err(Error::unauthorized());
+        }
+
+        let mut user = self.user_repo.find_by_id(&user_id).await?;
+        let recovery_codes = user.enable_two_factor(&cmd.code)?;
+
+        self.user_repo.save(&mut user).await?;
+
+        self.event_pub.publish_all(user.events().to_vec()?).await?;
+
+        Ok(RecoveryCodesResponse { recovery_codes })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Utc;
+
+    use crate::application::user::{DisableTwoFactor, EnrollTwoFactor, RegenerateRecoveryCodes};
+    use crate::mocks;
+
+    #[tokio::test]
+    async fn enroll_enable_and_disable() {
+        let c = mocks::container();
+        let uc = EnableTwoFactor::new(c.event_pub(), c.user_repo());
+
+        let mut user = mocks::user(
+            "user-1",
+            "username",
+            "user@omics.com",
+            "P@asswd!",
+            true,
+            None,
+            None,
+            "user",
+        );
+        c.user_repo().save(&mut user).await.unwrap();
+        let user_id = user.base().id().clone();
+        let auth = (user_id.clone(), mocks::role("User"));
+        let other_auth = (UserId::new("user-2").unwrap(), mocks::role("Admin"));
+
+        assert!(EnrollTwoFactor::new(c.user_repo())
+            .exec(other_auth.clone(), user_id.to_string())
+            .await
+            .is_err());
+        let enrollment = EnrollTwoFactor::new(c.user_repo())
+            .exec(auth.clone(), user_id.to_string())
+            .await
+            .unwrap();
+        assert!(enrollment.otpauth_uri.contains(&enrollment.secret));
+
+        let user = c.user_repo().find_by_id(&user_id).await.unwrap();
+        let now = Utc::now();
+        let code = |offset| {
+            user.two_factor()
+                .unwrap()
+                .code_at(&(now + chrono::Duration::seconds(offset)))
+                .unwrap()
+        };
+
+        assert!(uc
+            .exec(
+                auth.clone(),
+                user_id.to_string(),
+                TwoFactorCodeCommand {
+                    code: "000000".to_owned()
+                },
+            )
+            .await
+            .is_err());
+        let res = uc
+            .exec(
+                auth.clone(),
+                user_id.to_string(),
+                TwoFactorCodeCommand { code: code(0) },
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.recovery_codes.len(), 10);
+        assert!(c
+            .user_repo()
+            .find_by_id(&user_id)
+            .await
+            .unwrap()
+            .has_two_factor());
+        assert_eq!(c.event_pub().events().await.len(), 1);
+
+        let res = RegenerateRecoveryCodes::new(c.event_pub(), c.user_repo())
+            .exec(
+                auth.clone(),
+                user_id.to_string(),
+                TwoFactorCodeCommand { code: code(30) },
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.recovery_codes.len(), 10);
+
+        let uc = DisableTwoFactor::new(c.event_pub(), c.role_repo(), c.user_repo());
+        assert!(uc
+            .exec(
+                other_auth,
+                user_id.to_string(),
+                TwoFactorCodeCommand {
+                    code: res.recovery_codes[0].clone(),
+                },
+            )
+            .await
+            .is_err());
+
+        // Roles can require two-factor authentication.
+        let mut role = mocks::role("User");
+        role.set_two_factor_required(true).unwrap();
+        c.role_repo().save(&mut role).await.unwrap();
+        let err = uc
+            .exec(
+                auth.clone(),
+                user_id.to_string(),
+                TwoFactorCodeCommand {
+                    code: res.recovery_codes[0].clone(),
+                },
+            )
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "required");
+
+        role.set_two_factor_required(false).unwrap();
+        c.role_repo().save(&mut role).await.unwrap();
+        uc.exec(
+            auth,
+            user_id.to_string(),
+            TwoFactorCodeCommand {
+                code: res.recovery_codes[0].clone(),
+            },
+        )
+        .await
+        .unwrap();
+        assert!(!c
+            .user_repo()
+            .find_by_id(&user_id)
+            .await
+            .unwrap()
+            .has_two_factor());
+    }
+}
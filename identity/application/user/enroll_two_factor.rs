@@ -0,0 +1,39 @@
+use common::error::Error;
+use common::result::Result;
+
+use crate::application::dtos::TwoFactorEnrollmentDto;
+use crate::domain::user::{UserId, UserRepository};
+use crate::UserIdAndRole;
+
+pub struct EnrollTwoFactor<'a> {
+    user_repo: &'a dyn UserRepository,
+}
+
+impl<'a> EnrollTwoFactor<'a> {
+    pub fn new(user_repo: &'a dyn UserRepository) -> Self {
+        EnrollTwoFactor { user_repo }
+    }
+
+    /// Generates the secret the user adds to an authenticator app. Enrolling again replaces the
+    /// secret until a code is verified with `EnableTwoFactor`.
+    pub async fn exec(
+        &self,
+        (auth_id, _auth_role): UserIdAndRole,
+        user_id: String,
+    ) -> Result<TwoFactorEnrollmentDto> {
+        let user_id = UserId::new(user_id)?;
+        if auth_id != user_id {
+            return Err(Error::unauthorized());
+        }
+
+        let mut user = self.user_repo.find_by_id(&user_id).await?;
+        user.enroll_two_factor()?;
+
+        self.user_repo.save(&mut user).await?;
+
+        Ok(TwoFactorEnrollmentDto::new(
+            &user,
+            user.two_factor().unwrap(),
+        ))
+    }
+}
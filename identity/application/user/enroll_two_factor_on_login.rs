@@ -0,0 +1,37 @@
+use serde::Deserialize;
+
+use common::result::Result;
+
+use crate::application::dtos::TwoFactorEnrollmentDto;
+use crate::domain::user::AuthenticationService;
+
+#[derive(Deserialize)]
+pub struct EnrollTwoFactorOnLoginCommand {
+    pub challenge: String,
+}
+
+pub struct EnrollTwoFactorOnLogin<'a> {
+    authentication_serv: &'a AuthenticationService,
+}
+
+impl<'a> EnrollTwoFactorOnLogin<'a> {
+    pub fn new(authentication_serv: &'a AuthenticationService) -> Self {
+        EnrollTwoFactorOnLogin {
+            authentication_serv,
+        }
+    }
+
+    /// Enrolls a user whose role requires two-factor authentication during the login. The login
+    /// is completed with `LoginWithTwoFactor` and the first code of the authenticator app.
+    pub async fn exec(&self, cmd: EnrollTwoFactorOnLoginCommand) -> Result<TwoFactorEnrollmentDto> {
+        let user = self
+            .authentication_serv
+            .enroll_two_factor(&cmd.challenge)
+            .await?;
+
+        Ok(TwoFactorEnrollmentDto::new(
+            &user,
+            user.two_factor().unwrap(),
+        ))
+    }
+}
@@ -2,7 +2,8 @@ use common::error::Error;
 use common::request::Include;
 use common::result::Result;
 
-use crate::application::dtos::{RoleDto, UserDto};
+use crate::application::dtos::UserDto;
+use crate::application::expand::Expand;
 use crate::domain::role::RoleRepository;
 use crate::domain::user::{UserId, UserRepository};
 use crate::UserIdAndRole;
@@ -26,6 +27,8 @@ impl<'a> GetById<'a> {
         user_id: String,
         include: Include,
     ) -> Result<UserDto> {
+        include.validate(&UserDto::SCHEMA)?;
+
         let user_id = UserId::new(user_id)?;
 
         if !auth_role.can("get_any_user") {
@@ -35,14 +38,8 @@ impl<'a> GetById<'a> {
         }
 
         let user = self.user_repo.find_by_id(&user_id).await?;
-        let mut user_dto = UserDto::from(&user);
-
-        if include.has("role") {
-            let role = self.role_repo.find_by_id(user.role_id()).await?;
-            user_dto = user_dto.role(RoleDto::from(&role));
-        }
 
-        Ok(user_dto)
+        Expand::new(self.role_repo).user(&user, &include).await
     }
 }
 
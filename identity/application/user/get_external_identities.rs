@@ -0,0 +1,45 @@
+use serde::Serialize;
+
+use common::error::Error;
+use common::result::Result;
+
+use crate::application::dtos::ExternalIdentityDto;
+use crate::domain::oauth::OAuthService;
+use crate::domain::user::UserId;
+use crate::UserIdAndRole;
+
+#[derive(Serialize)]
+pub struct GetExternalIdentitiesResponse {
+    pub external_identities: Vec<ExternalIdentityDto>,
+}
+
+pub struct GetExternalIdentities<'a> {
+    oauth_serv: &'a OAuthService,
+}
+
+impl<'a> GetExternalIdentities<'a> {
+    pub fn new(oauth_serv: &'a OAuthService) -> Self {
+        GetExternalIdentities { oauth_serv }
+    }
+
+    /// Accounts of identity providers linked to the user. Only the user can see them.
+    pub async fn exec(
+        &self,
+        (auth_id, _auth_role): UserIdAndRole,
+        user_id: String,
+    ) -> Result<GetExternalIdentitiesResponse> {
+        let user_id = UserId::new(user_id)?;
+        if auth_id != user_id {
+            return Err(Error::unauthorized());
+        }
+
+        let external_identities = self.oauth_serv.identities(&user_id).await?;
+
+        Ok(GetExternalIdentitiesResponse {
+            external_identities: external_identities
+                .iter()
+                .map(ExternalIdentityDto::from)
+                .collect(),
+        })
+    }
+}
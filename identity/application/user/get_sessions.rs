@@ -0,0 +1,42 @@
+use serde::Serialize;
+
+use common::error::Error;
+use common::result::Result;
+
+use crate::application::dtos::SessionDto;
+use crate::domain::token::TokenService;
+use crate::domain::user::UserId;
+use crate::UserIdAndRole;
+
+#[derive(Serialize)]
+pub struct GetSessionsResponse {
+    pub sessions: Vec<SessionDto>,
+}
+
+pub struct GetSessions<'a> {
+    token_serv: &'a TokenService,
+}
+
+impl<'a> GetSessions<'a> {
+    pub fn new(token_serv: &'a TokenService) -> Self {
+        GetSessions { token_serv }
+    }
+
+    /// Active sessions of the user. Only the user can see them.
+    pub async fn exec(
+        &self,
+        (auth_id, _auth_role): UserIdAndRole,
+        user_id: String,
+    ) -> Result<GetSessionsResponse> {
+        let user_id = UserId::new(user_id)?;
+        if auth_id != user_id {
+            return Err(Error::unauthorized());
+        }
+
+        let sessions = self.token_serv.sessions(&user_id).await?;
+
+        Ok(GetSessionsResponse {
+            sessions: sessions.iter().map(SessionDto::from).collect(),
+        })
+    }
+}
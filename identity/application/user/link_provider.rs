@@ -0,0 +1,155 @@
+use std::str::FromStr;
+
+use common::error::Error;
+use common::result::Result;
+
+use crate::application::dtos::ExternalIdentityDto;
+use crate::application::user::ProviderCallbackCommand;
+use crate::domain::oauth::OAuthService;
+use crate::domain::user::{Provider, UserId};
+use crate::UserIdAndRole;
+
+pub struct LinkProvider<'a> {
+    oauth_serv: &'a OAuthService,
+}
+
+impl<'a> LinkProvider<'a> {
+    pub fn new(oauth_serv: &'a OAuthService) -> Self {
+        LinkProvider { oauth_serv }
+    }
+
+    /// Completes linking an account of an identity provider, so the user can log in with it.
+    /// `browser_key` is the one returned when the authorization started.
+    pub async fn exec(
+        &self,
+        (auth_id, _auth_role): UserIdAndRole,
+        user_id: String,
+        provider: String,
+        cmd: ProviderCallbackCommand,
+        browser_key: String,
+    ) -> Result<ExternalIdentityDto> {
+        let user_id = UserId::new(user_id)?;
+        if auth_id != user_id {
+            return Err(Error::unauthorized());
+        }
+
+        let provider = Provider::from_str(&provider)?;
+        let external_identity = self
+            .oauth_serv
+            .link(&user_id, &provider, &cmd.state, &browser_key, &cmd.code)
+            .await?;
+
+        Ok(ExternalIdentityDto::from(&external_identity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::application::user::{AuthorizeProviderLink, GetExternalIdentities, UnlinkProvider};
+    use crate::mocks::{self, FakeIdentityProvider};
+
+    #[tokio::test]
+    async fn link_and_unlink() {
+        let c = mocks::container();
+        let uc = LinkProvider::new(c.oauth_serv());
+        let authorize = AuthorizeProviderLink::new(c.oauth_serv());
+        let get_external_identities = GetExternalIdentities::new(c.oauth_serv());
+        let idp = FakeIdentityProvider::new(Provider::Google);
+
+        let mut user = mocks::user(
+            "user-1",
+            "username",
+            "user@omics.com",
+            "P@asswd!",
+            true,
+            None,
+            None,
+            "user",
+        );
+        c.user_repo().save(&mut user).await.unwrap();
+        let user_id = user.base().id().clone();
+        let other_user_id = UserId::new("user-2").unwrap();
+
+        assert!(authorize
+            .exec(
+                (other_user_id.clone(), mocks::role("User")),
+                user_id.to_string(),
+                "google".to_owned(),
+            )
+            .await
+            .is_err());
+
+        let res = authorize
+            .exec(
+                (user_id.clone(), mocks::role("User")),
+                user_id.to_string(),
+                "google".to_owned(),
+            )
+            .await
+            .unwrap();
+        let (state, code) = idp
+            .login(&res.authorization_url, "google-1", "user@gmail.com")
+            .unwrap();
+
+        // The authorization was started by another user.
+        assert!(uc
+            .exec(
+                (other_user_id.clone(), mocks::role("User")),
+                other_user_id.to_string(),
+                "google".to_owned(),
+                ProviderCallbackCommand {
+                    state: state.clone(),
+                    code: code.clone(),
+                },
+                res.browser_key.clone(),
+            )
+            .await
+            .is_err());
+
+        let res = authorize
+            .exec(
+                (user_id.clone(), mocks::role("User")),
+                user_id.to_string(),
+                "google".to_owned(),
+            )
+            .await
+            .unwrap();
+        let (state, code) = idp
+            .login(&res.authorization_url, "google-1", "user@gmail.com")
+            .unwrap();
+        let external_identity = uc
+            .exec(
+                (user_id.clone(), mocks::role("User")),
+                user_id.to_string(),
+                "google".to_owned(),
+                ProviderCallbackCommand { state, code },
+                res.browser_key,
+            )
+            .await
+            .unwrap();
+        assert_eq!(external_identity.provider, "google");
+        assert_eq!(external_identity.email, Some("user@gmail.com".to_owned()));
+
+        let res = get_external_identities
+            .exec((user_id.clone(), mocks::role("User")), user_id.to_string())
+            .await
+            .unwrap();
+        assert_eq!(res.external_identities.len(), 1);
+
+        UnlinkProvider::new(c.user_repo(), c.oauth_serv())
+            .exec(
+                (user_id.clone(), mocks::role("User")),
+                user_id.to_string(),
+                "google".to_owned(),
+            )
+            .await
+            .unwrap();
+        let res = get_external_identities
+            .exec((user_id.clone(), mocks::role("User")), user_id.to_string())
+            .await
+            .unwrap();
+        assert!(res.external_identities.is_empty());
+    }
+}
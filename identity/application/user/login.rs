@@ -4,8 +4,10 @@ use common::error::Error;
 use common::event::EventPublisher;
 use common::result::Result;
 
+use crate::application::dtos::{SessionTokensDto, TwoFactorChallengeDto};
 use crate::domain::role::RoleRepository;
-use crate::domain::user::AuthenticationService;
+use crate::domain::token::{Device, TokenService};
+use crate::domain::user::{Authentication, AuthenticationService, RateLimit, ThrottlingService};
 
 #[derive(Deserialize)]
 pub struct LoginCommand {
@@ -13,10 +15,15 @@ pub struct LoginCommand {
     pub password: String,
 }
 
+/// The session tokens, or the challenge of the second step for users with two-factor
+/// authentication.
 #[derive(Serialize)]
 pub struct LoginResponse {
-    user_id: String,
-    auth_token: String,
+    pub user_id: String,
+    #[serde(flatten)]
+    pub tokens: Option<SessionTokensDto>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub two_factor: Option<TwoFactorChallengeDto>,
 }
 
 pub struct Login<'a> {
@@ -25,6 +32,8 @@ pub struct Login<'a> {
     role_repo: &'a dyn RoleRepository,
 
     authentication_serv: &'a AuthenticationService,
+    throttling_serv: &'a ThrottlingService,
+    token_serv: &'a TokenService,
 }
 
 impl<'a> Login<'a> {
@@ -32,31 +41,54 @@ impl<'a> Login<'a> {
         event_pub: &'a dyn EventPublisher,
         role_repo: &'a dyn RoleRepository,
         authentication_serv: &'a AuthenticationService,
+        throttling_serv: &'a ThrottlingService,
+        token_serv: &'a TokenService,
     ) -> Self {
         Login {
             event_pub,
             role_repo,
             authentication_serv,
+            throttling_serv,
+            token_serv,
         }
     }
 
-    pub async fn exec(&self, cmd: LoginCommand) -> Result<LoginResponse> {
+    /// Starts a session in the device the user logs in from.
+    pub async fn exec(&self, cmd: LoginCommand, device: Device) -> Result<LoginResponse> {
+        self.throttling_serv
+            .limit(RateLimit::Login, device.client())
+            .await?;
+
         match self
             .authentication_serv
-            .authenticate(&cmd.username, &cmd.password)
+            .authenticate(&cmd.username, &cmd.password, device)
             .await
         {
-            Ok((user, token)) => {
+            Ok(Authentication::Session(user, tokens)) => {
                 self.event_pub.publish_all(user.events().to_vec()?).await?;
 
                 let role = self.role_repo.find_by_user_id(user.base().id()).await?;
                 if !role.can("login") {
+                    self.token_serv.invalidate(&tokens.access_token).await?;
                     return Err(Error::unauthorized());
                 }
 
                 Ok(LoginResponse {
                     user_id: user.base().id().to_string(),
-                    auth_token: token.to_string(),
+                    tokens: Some(SessionTokensDto::from(&tokens)),
+                    two_factor: None,
+                })
+            }
+            Ok(Authentication::TwoFactorRequired(user, challenge)) => {
+                let role = self.role_repo.find_by_user_id(user.base().id()).await?;
+                if !role.can("login") {
+                    return Err(Error::unauthorized());
+                }
+
+                Ok(LoginResponse {
+                    user_id: user.base().id().to_string(),
+                    tokens: None,
+                    two_factor: Some(TwoFactorChallengeDto::new(&user, &challenge)),
                 })
             }
             Err(e) => Err(e),
@@ -73,7 +105,13 @@ mod tests {
     #[tokio::test]
     async fn not_validated_user() {
         let c = mocks::container();
-        let uc = Login::new(c.event_pub(), c.role_repo(), c.authentication_serv());
+        let uc = Login::new(
+            c.event_pub(),
+            c.role_repo(),
+            c.authentication_serv(),
+            c.throttling_serv(),
+            c.token_serv(),
+        );
 
         let mut user = mocks::user(
             "user-1",
@@ -88,18 +126,33 @@ mod tests {
         c.user_repo().save(&mut user).await.unwrap();
 
         assert!(uc
-            .exec(LoginCommand {
-                username: user.identity().username().to_string(),
-                password: "P@asswd!".to_owned(),
-            })
+            .exec(
+                LoginCommand {
+                    username: user.identity().username().to_string(),
+                    password: "P@asswd!".to_owned(),
+                },
+                Device::default()
+            )
             .await
             .is_err());
+        assert!(c
+            .token_serv()
+            .sessions(user.base().id())
+            .await
+            .unwrap()
+            .is_empty());
     }
 
     #[tokio::test]
     async fn validated_user() {
         let c = mocks::container();
-        let uc = Login::new(c.event_pub(), c.role_repo(), c.authentication_serv());
+        let uc = Login::new(
+            c.event_pub(),
+            c.role_repo(),
+            c.authentication_serv(),
+            c.throttling_serv(),
+            c.token_serv(),
+        );
 
         let mut user = mocks::user(
             "user-1",
@@ -114,28 +167,39 @@ mod tests {
         c.user_repo().save(&mut user).await.unwrap();
 
         let res = uc
-            .exec(LoginCommand {
-                username: user.identity().username().to_string(),
-                password: "P@asswd!".to_owned(),
-            })
+            .exec(
+                LoginCommand {
+                    username: user.identity().username().to_string(),
+                    password: "P@asswd!".to_owned(),
+                },
+                Device::default(),
+            )
             .await
             .unwrap();
-        assert!(!res.auth_token.is_empty());
+        let tokens = res.tokens.unwrap();
+        assert!(!tokens.auth_token.is_empty());
+        assert!(!tokens.refresh_token.is_empty());
         assert_eq!(c.event_pub().events().await.len(), 1);
 
         assert!(uc
-            .exec(LoginCommand {
-                username: "non-existing".to_owned(),
-                password: "P@asswd!".to_owned(),
-            })
+            .exec(
+                LoginCommand {
+                    username: "non-existing".to_owned(),
+                    password: "P@asswd!".to_owned(),
+                },
+                Device::default()
+            )
             .await
             .is_err());
 
         assert!(uc
-            .exec(LoginCommand {
-                username: user.identity().username().to_string(),
-                password: "invalid".to_owned(),
-            })
+            .exec(
+                LoginCommand {
+                    username: user.identity().username().to_string(),
+                    password: "invalid".to_owned(),
+                },
+                Device::default()
+            )
             .await
             .is_err());
     }
@@ -0,0 +1,206 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use common::error::Error;
+use common::event::EventPublisher;
+use common::result::Result;
+
+use crate::application::dtos::{SessionTokensDto, TwoFactorChallengeDto};
+use crate::domain::oauth::OAuthService;
+use crate::domain::role::RoleRepository;
+use crate::domain::token::{Device, TokenService};
+use crate::domain::user::{Authentication, AuthenticationService, Provider};
+
+/// Parameters the identity provider redirects back with.
+#[derive(Deserialize)]
+pub struct ProviderCallbackCommand {
+    pub state: String,
+    pub code: String,
+}
+
+#[derive(Serialize)]
+pub struct LoginWithProviderResponse {
+    user_id: String,
+    /// Whether the user was registered by this login.
+    registered: bool,
+    #[serde(flatten)]
+    tokens: Option<SessionTokensDto>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    two_factor: Option<TwoFactorChallengeDto>,
+}
+
+pub struct LoginWithProvider<'a> {
+    event_pub: &'a dyn EventPublisher,
+
+    role_repo: &'a dyn RoleRepository,
+
+    authentication_serv: &'a AuthenticationService,
+    oauth_serv: &'a OAuthService,
+    token_serv: &'a TokenService,
+}
+
+impl<'a> LoginWithProvider<'a> {
+    pub fn new(
+        event_pub: &'a dyn EventPublisher,
+        role_repo: &'a dyn RoleRepository,
+        authentication_serv: &'a AuthenticationService,
+        oauth_serv: &'a OAuthService,
+        token_serv: &'a TokenService,
+    ) -> Self {
+        LoginWithProvider {
+            event_pub,
+            role_repo,
+            authentication_serv,
+            oauth_serv,
+            token_serv,
+        }
+    }
+
+    /// Completes logging in with an identity provider, registering the user the first time.
+    /// `browser_key` is the one returned when the authorization started.
+    pub async fn exec(
+        &self,
+        provider: String,
+        cmd: ProviderCallbackCommand,
+        browser_key: String,
+        device: Device,
+    ) -> Result<LoginWithProviderResponse> {
+        let provider = Provider::from_str(&provider)?;
+        let (mut user, registered) = self
+            .oauth_serv
+            .authenticate(&provider, &cmd.state, &browser_key, &cmd.code)
+            .await?;
+
+        // The user is registered even if they can't log in yet.
+        if registered {
+            self.event_pub.publish_all(user.events().to_vec()?).await?;
+            user.events_mut().clear();
+        }
+
+        match self.authentication_serv.login(user, device).await? {
+            Authentication::Session(user, tokens) => {
+                self.event_pub.publish_all(user.events().to_vec()?).await?;
+
+                let role = self.role_repo.find_by_user_id(user.base().id()).await?;
+                if !role.can("login") {
+                    self.token_serv.invalidate(&tokens.access_token).await?;
+                    return Err(Error::unauthorized());
+                }
+
+                Ok(LoginWithProviderResponse {
+                    user_id: user.base().id().to_string(),
+                    registered,
+                    tokens: Some(SessionTokensDto::from(&tokens)),
+                    two_factor: None,
+                })
+            }
+            // The provider doesn't replace the second factor.
+            Authentication::TwoFactorRequired(user, challenge) => {
+                let role = self.role_repo.find_by_user_id(user.base().id()).await?;
+                if !role.can("login") {
+                    return Err(Error::unauthorized());
+                }
+
+                Ok(LoginWithProviderResponse {
+                    user_id: user.base().id().to_string(),
+                    registered,
+                    tokens: None,
+                    two_factor: Some(TwoFactorChallengeDto::new(&user, &challenge)),
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::user::UserId;
+    use crate::mocks::{self, FakeIdentityProvider};
+
+    #[tokio::test]
+    async fn register_and_login() {
+        let c = mocks::container();
+        let uc = LoginWithProvider::new(
+            c.event_pub(),
+            c.role_repo(),
+            c.authentication_serv(),
+            c.oauth_serv(),
+            c.token_serv(),
+        );
+        let idp = FakeIdentityProvider::new(Provider::Google);
+
+        let (url, key) = c
+            .oauth_serv()
+            .authorize(&Provider::Google, None)
+            .await
+            .unwrap();
+        let (state, code) = idp.login(&url, "google-1", "user@gmail.com").unwrap();
+        let res = uc
+            .exec(
+                "google".to_owned(),
+                ProviderCallbackCommand { state, code },
+                key,
+                Device::default(),
+            )
+            .await
+            .unwrap();
+        assert!(res.registered);
+        assert!(!res.tokens.unwrap().auth_token.is_empty());
+        // Registered, Validated and LoggedIn.
+        assert_eq!(c.event_pub().events().await.len(), 3);
+
+        let user = c
+            .user_repo()
+            .find_by_id(&UserId::new(res.user_id.clone()).unwrap())
+            .await
+            .unwrap();
+        assert!(user.identity().password().is_none());
+        assert!(c
+            .authentication_serv()
+            .authenticate(user.identity().username().value(), "", Device::default())
+            .await
+            .is_err());
+
+        let (url, key) = c
+            .oauth_serv()
+            .authorize(&Provider::Google, None)
+            .await
+            .unwrap();
+        let (state, code) = idp.login(&url, "google-1", "user@gmail.com").unwrap();
+        let logged_in = uc
+            .exec(
+                "google".to_owned(),
+                ProviderCallbackCommand { state, code },
+                key,
+                Device::default(),
+            )
+            .await
+            .unwrap();
+        assert!(!logged_in.registered);
+        assert_eq!(logged_in.user_id, res.user_id);
+        assert_eq!(
+            c.token_serv()
+                .sessions(user.base().id())
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        assert!(uc
+            .exec(
+                "local".to_owned(),
+                ProviderCallbackCommand {
+                    state: "state".to_owned(),
+                    code: "code".to_owned(),
+                },
+                "key".to_owned(),
+                Device::default(),
+            )
+            .await
+            .is_err());
+    }
+}
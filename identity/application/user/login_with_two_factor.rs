@@ -0,0 +1,186 @@
+use serde::{Deserialize, Serialize};
+
+use common::error::Error;
+use common::event::EventPublisher;
+use common::result::Result;
+
+use crate::application::dtos::SessionTokensDto;
+use crate::domain::role::RoleRepository;
+use crate::domain::token::TokenService;
+use crate::domain::user::AuthenticationService;
+
+#[derive(Deserialize)]
+pub struct LoginWithTwoFactorCommand {
+    pub challenge: String,
+    /// Code of the authenticator app, or a recovery code.
+    pub code: String,
+}
+
+#[derive(Serialize)]
+pub struct LoginWithTwoFactorResponse {
+    user_id: String,
+    #[serde(flatten)]
+    tokens: SessionTokensDto,
+    /// Recovery codes of users that enabled two-factor authentication with this login.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recovery_codes: Option<Vec<String>>,
+}
+
+pub struct LoginWithTwoFactor<'a> {
+    event_pub: &'a dyn EventPublisher,
+
+    role_repo: &'a dyn RoleRepository,
+
+    authentication_serv: &'a AuthenticationService,
+    token_serv: &'a TokenService,
+}
+
+impl<'a> LoginWithTwoFactor<'a> {
+    pub fn new(
+        event_pub: &'a dyn EventPublisher,
+        role_repo: &'a dyn RoleRepository,
+        authentication_serv: &'a AuthenticationService,
+        token_serv: &'a TokenService,
+    ) -> Self {
+        LoginWithTwoFactor {
+            event_pub,
+            role_repo,
+            authentication_serv,
+            token_serv,
+        }
+    }
+
+    /// Completes the challenge returned by `Login` for users with two-factor authentication.
+    pub async fn exec(&self, cmd: LoginWithTwoFactorCommand) -> Result<LoginWithTwoFactorResponse> {
+        let (user, tokens, recovery_codes) = self
+            .authentication_serv
+            .complete_two_factor(&cmd.challenge, &cmd.code)
+            .await?;
+
+        self.event_pub.publish_all(user.events().to_vec()?).await?;
+
+        let role = self.role_repo.find_by_user_id(user.base().id()).await?;
+        if !role.can("login") {
+            self.token_serv.invalidate(&tokens.access_token).await?;
+            return Err(Error::unauthorized());
+        }
+
+        Ok(LoginWithTwoFactorResponse {
+            user_id: user.base().id().to_string(),
+            tokens: SessionTokensDto::from(&tokens),
+            recovery_codes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Utc;
+
+    use crate::application::user::{
+        EnrollTwoFactorOnLogin, EnrollTwoFactorOnLoginCommand, Login, LoginCommand,
+    };
+    use crate::domain::token::Device;
+    use crate::mocks;
+
+    #[tokio::test]
+    async fn enroll_and_login() {
+        let c = mocks::container();
+        let uc = LoginWithTwoFactor::new(
+            c.event_pub(),
+            c.role_repo(),
+            c.authentication_serv(),
+            c.token_serv(),
+        );
+        let login = Login::new(
+            c.event_pub(),
+            c.role_repo(),
+            c.authentication_serv(),
+            c.throttling_serv(),
+            c.token_serv(),
+        );
+
+        let mut role = mocks::role("User");
+        role.set_two_factor_required(true).unwrap();
+        c.role_repo().save(&mut role).await.unwrap();
+
+        let mut user = mocks::user(
+            "user-1",
+            "username",
+            "user@omics.com",
+            "P@asswd!",
+            true,
+            None,
+            None,
+            "user",
+        );
+        c.user_repo().save(&mut user).await.unwrap();
+
+        let res = login
+            .exec(
+                LoginCommand {
+                    username: "username".to_owned(),
+                    password: "P@asswd!".to_owned(),
+                },
+                Device::default(),
+            )
+            .await
+            .unwrap();
+        assert!(res.tokens.is_none());
+        let challenge = res.two_factor.unwrap();
+        assert!(challenge.enrollment_required);
+
+        let enrollment = EnrollTwoFactorOnLogin::new(c.authentication_serv())
+            .exec(EnrollTwoFactorOnLoginCommand {
+                challenge: challenge.challenge.clone(),
+            })
+            .await
+            .unwrap();
+        assert!(!enrollment.secret.is_empty());
+
+        let user = c.user_repo().find_by_id(user.base().id()).await.unwrap();
+        let code = user.two_factor().unwrap().code_at(&Utc::now()).unwrap();
+        let res = uc
+            .exec(LoginWithTwoFactorCommand {
+                challenge: challenge.challenge,
+                code,
+            })
+            .await
+            .unwrap();
+        assert!(!res.tokens.auth_token.is_empty());
+        let recovery_codes = res.recovery_codes.unwrap();
+        // TwoFactorEnabled and LoggedIn.
+        assert_eq!(c.event_pub().events().await.len(), 2);
+
+        let res = login
+            .exec(
+                LoginCommand {
+                    username: "username".to_owned(),
+                    password: "P@asswd!".to_owned(),
+                },
+                Device::default(),
+            )
+            .await
+            .unwrap();
+        let challenge = res.two_factor.unwrap();
+        assert!(!challenge.enrollment_required);
+        let res = uc
+            .exec(LoginWithTwoFactorCommand {
+                challenge: challenge.challenge,
+                code: recovery_codes[0].clone(),
+            })
+            .await
+            .unwrap();
+        assert!(res.recovery_codes.is_none());
+        assert_eq!(
+            c.token_serv()
+                .sessions(user.base().id())
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+}
@@ -0,0 +1,21 @@
+use common::request::CommandResponse;
+use common::result::Result;
+
+use crate::domain::token::{Token, TokenService};
+
+pub struct Logout<'a> {
+    token_serv: &'a TokenService,
+}
+
+impl<'a> Logout<'a> {
+    pub fn new(token_serv: &'a TokenService) -> Self {
+        Logout { token_serv }
+    }
+
+    /// Ends the session of the access token.
+    pub async fn exec(&self, token: Token) -> Result<CommandResponse> {
+        self.token_serv.invalidate(&token).await?;
+
+        Ok(CommandResponse::default())
+    }
+}
@@ -1,24 +1,70 @@
+mod authorize_provider;
+mod authorize_provider_link;
+mod change_email;
 mod change_password;
 mod change_payment_email;
 mod change_role;
+mod change_username;
+mod confirm_email_change;
 mod delete;
+mod disable_two_factor;
+mod enable_two_factor;
+mod enroll_two_factor;
+mod enroll_two_factor_on_login;
 mod get_by_id;
+mod get_external_identities;
+mod get_sessions;
+mod link_provider;
 mod login;
+mod login_with_provider;
+mod login_with_two_factor;
+mod logout;
 mod recover_password;
+mod refresh;
+mod regenerate_recovery_codes;
 mod register;
+mod reset_password;
+mod restore;
+mod revoke_session;
 mod search;
+mod search_deleted;
 mod set_flag;
+mod unlink_provider;
+mod unlock;
 mod update;
 mod validate;
+pub use authorize_provider::*;
+pub use authorize_provider_link::*;
+pub use change_email::*;
 pub use change_password::*;
 pub use change_payment_email::*;
 pub use change_role::*;
+pub use change_username::*;
+pub use confirm_email_change::*;
 pub use delete::*;
+pub use disable_two_factor::*;
+pub use enable_two_factor::*;
+pub use enroll_two_factor::*;
+pub use enroll_two_factor_on_login::*;
 pub use get_by_id::*;
+pub use get_external_identities::*;
+pub use get_sessions::*;
+pub use link_provider::*;
 pub use login::*;
+pub use login_with_provider::*;
+pub use login_with_two_factor::*;
+pub use logout::*;
 pub use recover_password::*;
+pub use refresh::*;
+pub use regenerate_recovery_codes::*;
 pub use register::*;
+pub use reset_password::*;
+pub use restore::*;
+pub use revoke_session::*;
 pub use search::*;
+pub use search_deleted::*;
 pub use set_flag::*;
+pub use unlink_provider::*;
+pub use unlock::*;
 pub use update::*;
 pub use validate::*;
@@ -1,5 +1,4 @@
 use serde::Deserialize;
-use uuid::Uuid;
 
 use common::error::Error;
 use common::event::EventPublisher;
@@ -7,7 +6,10 @@ use common::request::CommandResponse;
 use common::result::Result;
 
 use crate::domain::role::RoleRepository;
-use crate::domain::user::{Email, Password, UserRepository, UserService};
+use crate::domain::token::Device;
+use crate::domain::user::{
+    Email, PasswordResetSender, RateLimit, ThrottlingService, UserRepository,
+};
 
 #[derive(Deserialize)]
 pub struct RecoverPasswordCommand {
@@ -20,7 +22,8 @@ pub struct RecoverPassword<'a> {
     role_repo: &'a dyn RoleRepository,
     user_repo: &'a dyn UserRepository,
 
-    user_serv: &'a UserService,
+    password_reset_sender: &'a dyn PasswordResetSender,
+    throttling_serv: &'a ThrottlingService,
 }
 
 impl<'a> RecoverPassword<'a> {
@@ -28,18 +31,34 @@ impl<'a> RecoverPassword<'a> {
         event_pub: &'a dyn EventPublisher,
         role_repo: &'a dyn RoleRepository,
         user_repo: &'a dyn UserRepository,
-        user_serv: &'a UserService,
+        password_reset_sender: &'a dyn PasswordResetSender,
+        throttling_serv: &'a ThrottlingService,
     ) -> Self {
         RecoverPassword {
             event_pub,
             role_repo,
             user_repo,
-            user_serv,
+            password_reset_sender,
+            throttling_serv,
         }
     }
 
-    pub async fn exec(&self, cmd: RecoverPasswordCommand) -> Result<CommandResponse> {
+    /// Sends the user a token to reset the password with `ResetPassword`. Limited by IP and by
+    /// email, so the emails of a user can't be flooded.
+    pub async fn exec(
+        &self,
+        cmd: RecoverPasswordCommand,
+        device: Device,
+    ) -> Result<CommandResponse> {
+        self.throttling_serv
+            .limit(RateLimit::RecoverPassword, device.client())
+            .await?;
+
         let email = Email::new(cmd.email)?;
+        self.throttling_serv
+            .limit(RateLimit::RecoverPassword, email.value())
+            .await?;
+
         let mut user = self.user_repo.find_by_email(&email).await?;
 
         let role = self.role_repo.find_by_user_id(user.base().id()).await?;
@@ -47,14 +66,12 @@ impl<'a> RecoverPassword<'a> {
             return Err(Error::unauthorized());
         }
 
-        let tmp_password = Uuid::new_v4().to_string();
-        let hashed_password = self.user_serv.generate_password(&tmp_password)?;
-        let password = Password::new(hashed_password)?;
-
-        user.recover_password(password, tmp_password)?;
+        let token = user.request_password_reset()?;
 
         self.user_repo.save(&mut user).await?;
 
+        self.password_reset_sender.send(&user, &token).await?;
+
         self.event_pub.publish_all(user.events().to_vec()?).await?;
 
         Ok(CommandResponse::default())
@@ -70,20 +87,35 @@ mod tests {
     #[tokio::test]
     async fn non_existing_user() {
         let c = mocks::container();
-        let uc = RecoverPassword::new(c.event_pub(), c.role_repo(), c.user_repo(), c.user_serv());
+        let uc = RecoverPassword::new(
+            c.event_pub(),
+            c.role_repo(),
+            c.user_repo(),
+            c.password_reset_sender(),
+            c.throttling_serv(),
+        );
 
         assert!(uc
-            .exec(RecoverPasswordCommand {
-                email: "non-existing@omics.com".to_owned(),
-            })
+            .exec(
+                RecoverPasswordCommand {
+                    email: "non-existing@omics.com".to_owned(),
+                },
+                Device::default()
+            )
             .await
             .is_err())
     }
 
     #[tokio::test]
-    async fn password_recovery_code_generated() {
+    async fn password_reset_requested() {
         let c = mocks::container();
-        let uc = RecoverPassword::new(c.event_pub(), c.role_repo(), c.user_repo(), c.user_serv());
+        let uc = RecoverPassword::new(
+            c.event_pub(),
+            c.role_repo(),
+            c.user_repo(),
+            c.password_reset_sender(),
+            c.throttling_serv(),
+        );
 
         let mut user = mocks::user(
             "user-1",
@@ -95,19 +127,77 @@ mod tests {
             None,
             "user",
         );
-        let old_password = user.identity().password().unwrap().to_string();
+        let old_password = user.identity().password().unwrap().value().to_owned();
         c.user_repo().save(&mut user).await.unwrap();
 
         assert!(uc
-            .exec(RecoverPasswordCommand {
-                email: user.identity().email().to_string(),
-            })
+            .exec(
+                RecoverPasswordCommand {
+                    email: user.identity().email().to_string(),
+                },
+                Device::default()
+            )
             .await
             .is_ok());
 
+        // The password is kept until the user resets it.
         let user = c.user_repo().find_by_id(&user.base().id()).await.unwrap();
-        assert_ne!(user.identity().password().unwrap().value(), old_password);
+        assert_eq!(user.identity().password().unwrap().value(), old_password);
+        assert!(user.password_reset().is_some());
+
+        // The token is only sent to the user.
+        let events = c.event_pub().events().await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].code(), "password-recovery-requested");
+        assert!(events[0].payload()["PasswordRecoveryRequested"]
+            .get("temp_password")
+            .is_none());
+    }
 
-        assert!(!c.event_pub().events().await.is_empty());
+    #[tokio::test]
+    async fn rate_limited_by_email() {
+        let c = mocks::container();
+        let uc = RecoverPassword::new(
+            c.event_pub(),
+            c.role_repo(),
+            c.user_repo(),
+            c.password_reset_sender(),
+            c.throttling_serv(),
+        );
+
+        let mut user = mocks::user(
+            "user-1",
+            "username",
+            "user@omics.com",
+            "P@asswd!",
+            true,
+            None,
+            None,
+            "user",
+        );
+        c.user_repo().save(&mut user).await.unwrap();
+
+        for i in 0..5 {
+            uc.exec(
+                RecoverPasswordCommand {
+                    email: "user@omics.com".to_owned(),
+                },
+                Device::new(None, Some(format!("10.0.0.{}", i))),
+            )
+            .await
+            .unwrap();
+        }
+
+        let err = uc
+            .exec(
+                RecoverPasswordCommand {
+                    email: "user@omics.com".to_owned(),
+                },
+                Device::new(None, Some("10.0.0.9".to_owned())),
+            )
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "rate_limited");
     }
 }
@@ -0,0 +1,76 @@
+use serde::Deserialize;
+
+use common::result::Result;
+
+use crate::application::dtos::SessionTokensDto;
+use crate::domain::token::{Token, TokenService};
+
+#[derive(Deserialize)]
+pub struct RefreshCommand {
+    pub refresh_token: String,
+}
+
+pub struct Refresh<'a> {
+    token_serv: &'a TokenService,
+}
+
+impl<'a> Refresh<'a> {
+    pub fn new(token_serv: &'a TokenService) -> Self {
+        Refresh { token_serv }
+    }
+
+    pub async fn exec(&self, cmd: RefreshCommand) -> Result<SessionTokensDto> {
+        let tokens = self
+            .token_serv
+            .refresh(&Token::new(cmd.refresh_token))
+            .await?;
+
+        Ok(SessionTokensDto::from(&tokens))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::token::{Data, Device};
+    use crate::domain::user::UserId;
+    use crate::mocks;
+
+    #[tokio::test]
+    async fn rotate_tokens() {
+        let c = mocks::container();
+        let uc = Refresh::new(c.token_serv());
+
+        let tokens = c
+            .token_serv()
+            .create(
+                &UserId::new("#user01").unwrap(),
+                Data::new(),
+                Device::default(),
+            )
+            .await
+            .unwrap();
+
+        let res = uc
+            .exec(RefreshCommand {
+                refresh_token: tokens.refresh_token.to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(res.session_id, tokens.session_id.to_string());
+        assert_ne!(res.refresh_token, tokens.refresh_token.to_string());
+        assert!(c
+            .token_serv()
+            .validate(&Token::new(res.auth_token))
+            .await
+            .is_ok());
+
+        assert!(uc
+            .exec(RefreshCommand {
+                refresh_token: "invalid".to_owned(),
+            })
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,44 @@
+use common::error::Error;
+use common::event::EventPublisher;
+use common::result::Result;
+
+use crate::application::user::{RecoveryCodesResponse, TwoFactorCodeCommand};
+use crate::domain::user::{UserId, UserRepository};
+use crate::UserIdAndRole;
+
+pub struct RegenerateRecoveryCodes<'a> {
+    event_pub: &'a dyn EventPublisher,
+
+    user_repo: &'a dyn UserRepository,
+}
+
+impl<'a> RegenerateRecoveryCodes<'a> {
+    pub fn new(event_pub: &'a dyn EventPublisher, user_repo: &'a dyn UserRepository) -> Self {
+        RegenerateRecoveryCodes {
+            event_pub,
+            user_repo,
+        }
+    }
+
+    /// Replaces the recovery codes of the user, invalidating the previous ones.
+    pub async fn exec(
+        &self,
+        (auth_id, _auth_role): UserIdAndRole,
+        user_id: String,
+        cmd: TwoFactorCodeCommand,
+    ) -> Result<RecoveryCodesResponse> {
+        let user_id = UserId::new(user_id)?;
+        if auth_id != user_id {
+            return Err(Error::unauthorized());
+        }
+
+        let mut user = self.user_repo.find_by_id(&user_id).await?;
+        let recovery_codes = user.regenerate_recovery_codes(&cmd.code)?;
+
+        self.user_repo.save(&mut user).await?;
+
+        self.event_pub.publish_all(user.events().to_vec()?).await?;
+
+        Ok(RecoveryCodesResponse { recovery_codes })
+    }
+}
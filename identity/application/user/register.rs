@@ -4,8 +4,10 @@ use common::event::EventPublisher;
 use common::result::Result;
 
 use crate::domain::role::RoleRepository;
+use crate::domain::token::Device;
 use crate::domain::user::{
-    Email, Identity, Password, Provider, User, UserRepository, UserService, Username,
+    Email, Identity, Password, Provider, RateLimit, ThrottlingService, User, UserRepository,
+    UserService, Username,
 };
 
 #[derive(Deserialize)]
@@ -26,6 +28,7 @@ pub struct Register<'a> {
     role_repo: &'a dyn RoleRepository,
     user_repo: &'a dyn UserRepository,
 
+    throttling_serv: &'a ThrottlingService,
     user_serv: &'a UserService,
 }
 
@@ -34,17 +37,23 @@ impl<'a> Register<'a> {
         event_pub: &'a dyn EventPublisher,
         role_repo: &'a dyn RoleRepository,
         user_repo: &'a dyn UserRepository,
+        throttling_serv: &'a ThrottlingService,
         user_serv: &'a UserService,
     ) -> Self {
         Register {
             event_pub,
             role_repo,
             user_repo,
+            throttling_serv,
             user_serv,
         }
     }
 
-    pub async fn exec(&self, cmd: RegisterCommand) -> Result<RegisterResponse> {
+    pub async fn exec(&self, cmd: RegisterCommand, device: Device) -> Result<RegisterResponse> {
+        self.throttling_serv
+            .limit(RateLimit::Register, device.client())
+            .await?;
+
         self.user_serv.available(&cmd.username, &cmd.email).await?;
 
         let hashed_password = self.user_serv.generate_password(&cmd.password)?;
@@ -82,7 +91,13 @@ mod tests {
     #[tokio::test]
     async fn new_user() {
         let c = mocks::container();
-        let uc = Register::new(c.event_pub(), c.role_repo(), c.user_repo(), c.user_serv());
+        let uc = Register::new(
+            c.event_pub(),
+            c.role_repo(),
+            c.user_repo(),
+            c.throttling_serv(),
+            c.user_serv(),
+        );
 
         let cmd = RegisterCommand {
             username: "new-user".to_owned(),
@@ -90,7 +105,7 @@ mod tests {
             password: "P@asswd!".to_owned(),
         };
 
-        let res = uc.exec(cmd).await.unwrap();
+        let res = uc.exec(cmd, Device::default()).await.unwrap();
         let saved_user = c
             .user_repo()
             .find_by_id(&UserId::new(&res.id).unwrap())
@@ -110,32 +125,47 @@ mod tests {
     #[tokio::test]
     async fn invalid_data() {
         let c = mocks::container();
-        let uc = Register::new(c.event_pub(), c.role_repo(), c.user_repo(), c.user_serv());
+        let uc = Register::new(
+            c.event_pub(),
+            c.role_repo(),
+            c.user_repo(),
+            c.throttling_serv(),
+            c.user_serv(),
+        );
 
         assert!(uc
-            .exec(RegisterCommand {
-                username: "us".to_owned(),
-                email: "new@user.com".to_owned(),
-                password: "P@asswd!".to_owned(),
-            })
+            .exec(
+                RegisterCommand {
+                    username: "us".to_owned(),
+                    email: "new@user.com".to_owned(),
+                    password: "P@asswd!".to_owned(),
+                },
+                Device::default()
+            )
             .await
             .is_err());
 
         assert!(uc
-            .exec(RegisterCommand {
-                username: "new-user".to_owned(),
-                email: "invalid-email".to_owned(),
-                password: "P@asswd!".to_owned(),
-            })
+            .exec(
+                RegisterCommand {
+                    username: "new-user".to_owned(),
+                    email: "invalid-email".to_owned(),
+                    password: "P@asswd!".to_owned(),
+                },
+                Device::default()
+            )
             .await
             .is_err());
 
         assert!(uc
-            .exec(RegisterCommand {
-                username: "new-user".to_owned(),
-                email: "new@user.com".to_owned(),
-                password: "1234".to_owned(),
-            })
+            .exec(
+                RegisterCommand {
+                    username: "new-user".to_owned(),
+                    email: "new@user.com".to_owned(),
+                    password: "1234".to_owned(),
+                },
+                Device::default()
+            )
             .await
             .is_err());
     }
@@ -143,7 +173,13 @@ mod tests {
     #[tokio::test]
     async fn existing_user() {
         let c = mocks::container();
-        let uc = Register::new(c.event_pub(), c.role_repo(), c.user_repo(), c.user_serv());
+        let uc = Register::new(
+            c.event_pub(),
+            c.role_repo(),
+            c.user_repo(),
+            c.throttling_serv(),
+            c.user_serv(),
+        );
 
         let mut user = mocks::user(
             "user-1",
@@ -158,29 +194,38 @@ mod tests {
         c.user_repo().save(&mut user).await.unwrap();
 
         assert!(uc
-            .exec(RegisterCommand {
-                username: user.identity().username().to_string(),
-                email: user.identity().email().to_string(),
-                password: "P@asswd!".to_owned(),
-            })
+            .exec(
+                RegisterCommand {
+                    username: user.identity().username().to_string(),
+                    email: user.identity().email().to_string(),
+                    password: "P@asswd!".to_owned(),
+                },
+                Device::default()
+            )
             .await
             .is_err());
 
         assert!(uc
-            .exec(RegisterCommand {
-                username: "other".to_owned(),
-                email: user.identity().email().to_string(),
-                password: "P@asswd!".to_owned(),
-            })
+            .exec(
+                RegisterCommand {
+                    username: "other".to_owned(),
+                    email: user.identity().email().to_string(),
+                    password: "P@asswd!".to_owned(),
+                },
+                Device::default()
+            )
             .await
             .is_err());
 
         assert!(uc
-            .exec(RegisterCommand {
-                username: user.identity().username().to_string(),
-                email: "other@other.com".to_owned(),
-                password: "P@asswd!".to_owned(),
-            })
+            .exec(
+                RegisterCommand {
+                    username: user.identity().username().to_string(),
+                    email: "other@other.com".to_owned(),
+                    password: "P@asswd!".to_owned(),
+                },
+                Device::default()
+            )
             .await
             .is_err());
     }
@@ -0,0 +1,177 @@
+use serde::Deserialize;
+
+use common::error::Error;
+use common::event::EventPublisher;
+use common::request::CommandResponse;
+use common::result::Result;
+use common::transaction::{self, UnitOfWork};
+
+use crate::domain::role::RoleRepository;
+use crate::domain::token::{Device, TokenService};
+use crate::domain::user::{
+    Password, PasswordReset, RateLimit, ThrottlingService, UserRepository, UserService,
+};
+
+#[derive(Deserialize)]
+pub struct ResetPasswordCommand {
+    pub token: String,
+    pub password: String,
+}
+
+pub struct ResetPassword<'a> {
+    event_pub: &'a dyn EventPublisher,
+
+    role_repo: &'a dyn RoleRepository,
+    user_repo: &'a dyn UserRepository,
+
+    throttling_serv: &'a ThrottlingService,
+    token_serv: &'a TokenService,
+    user_serv: &'a UserService,
+
+    unit_of_work: &'a dyn UnitOfWork,
+}
+
+impl<'a> ResetPassword<'a> {
+    pub fn new(
+        event_pub: &'a dyn EventPublisher,
+        role_repo: &'a dyn RoleRepository,
+        user_repo: &'a dyn UserRepository,
+        throttling_serv: &'a ThrottlingService,
+        token_serv: &'a TokenService,
+        user_serv: &'a UserService,
+        unit_of_work: &'a dyn UnitOfWork,
+    ) -> Self {
+        ResetPassword {
+            event_pub,
+            role_repo,
+            user_repo,
+            throttling_serv,
+            token_serv,
+            user_serv,
+            unit_of_work,
+        }
+    }
+
+    /// Sets the password with the token sent by `RecoverPassword`. Every session of the user is
+    /// ended, in case the old password was stolen.
+    pub async fn exec(&self, cmd: ResetPasswordCommand, device: Device) -> Result<CommandResponse> {
+        self.throttling_serv
+            .limit(RateLimit::ResetPassword, device.client())
+            .await?;
+
+        let mut user = self
+            .user_repo
+            .find_by_password_reset(&PasswordReset::hash(&cmd.token))
+            .await
+            .map_err(|err| Error::new("password_reset", "invalid").wrap(err))?;
+
+        let role = self.role_repo.find_by_user_id(user.base().id()).await?;
+        if !role.can("recover_user_password") {
+            return Err(Error::unauthorized());
+        }
+
+        let hashed_password = self.user_serv.generate_password(&cmd.password)?;
+        let password = Password::new(hashed_password)?;
+
+        user.reset_password(&cmd.token, password)?;
+
+        transaction::run(self.unit_of_work, async {
+            self.user_repo.save(&mut user).await?;
+            self.token_serv.revoke_all(user.base().id()).await
+        })
+        .await?;
+
+        self.event_pub.publish_all(user.events().to_vec()?).await?;
+
+        Ok(CommandResponse::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::token::Data;
+    use crate::mocks;
+
+    #[tokio::test]
+    async fn reset_password_and_revoke_sessions() {
+        let c = mocks::container();
+        let uc = ResetPassword::new(
+            c.event_pub(),
+            c.role_repo(),
+            c.user_repo(),
+            c.throttling_serv(),
+            c.token_serv(),
+            c.user_serv(),
+            c.unit_of_work(),
+        );
+
+        let mut user = mocks::user(
+            "user-1",
+            "username",
+            "user@omics.com",
+            "P@asswd!",
+            true,
+            None,
+            None,
+            "user",
+        );
+        let token = user.request_password_reset().unwrap();
+        c.user_repo().save(&mut user).await.unwrap();
+
+        let tokens = c
+            .token_serv()
+            .create(user.base().id(), Data::new(), Device::default())
+            .await
+            .unwrap();
+
+        let err = uc
+            .exec(
+                ResetPasswordCommand {
+                    token: "invalid".to_owned(),
+                    password: "New_P@asswd!".to_owned(),
+                },
+                Device::default(),
+            )
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "invalid");
+
+        uc.exec(
+            ResetPasswordCommand {
+                token: token.clone(),
+                password: "New_P@asswd!".to_owned(),
+            },
+            Device::default(),
+        )
+        .await
+        .unwrap();
+
+        let user = c.user_repo().find_by_id(user.base().id()).await.unwrap();
+        assert!(c
+            .password_hasher()
+            .compare(user.identity().password().unwrap().value(), "New_P@asswd!"));
+        assert!(user.password_reset().is_none());
+        assert!(c.token_serv().validate(&tokens.access_token).await.is_err());
+        assert!(c
+            .token_serv()
+            .sessions(user.base().id())
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Tokens are used once.
+        assert!(uc
+            .exec(
+                ResetPasswordCommand {
+                    token,
+                    password: "Other_P@asswd!".to_owned(),
+                },
+                Device::default(),
+            )
+            .await
+            .is_err());
+    }
+}
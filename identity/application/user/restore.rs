@@ -0,0 +1,96 @@
+use common::error::Error;
+use common::event::EventPublisher;
+use common::request::CommandResponse;
+use common::result::Result;
+
+use crate::domain::user::{UserId, UserRepository};
+use crate::UserIdAndRole;
+
+pub struct Restore<'a> {
+    event_pub: &'a dyn EventPublisher,
+
+    user_repo: &'a dyn UserRepository,
+}
+
+impl<'a> Restore<'a> {
+    pub fn new(event_pub: &'a dyn EventPublisher, user_repo: &'a dyn UserRepository) -> Self {
+        Restore {
+            event_pub,
+            user_repo,
+        }
+    }
+
+    pub async fn exec(
+        &self,
+        (_auth_id, auth_role): UserIdAndRole,
+        user_id: String,
+    ) -> Result<CommandResponse> {
+        if !auth_role.can("restore_deleted") {
+            return Err(Error::unauthorized());
+        }
+
+        let user_id = UserId::new(user_id)?;
+        let mut user = self.user_repo.find_deleted_by_id(&user_id).await?;
+
+        user.restore()?;
+
+        self.user_repo.save(&mut user).await?;
+
+        self.event_pub.publish_all(user.events().to_vec()?).await?;
+
+        Ok(CommandResponse::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::application::user::Delete;
+    use crate::mocks;
+
+    #[tokio::test]
+    async fn restore_deleted_user() {
+        let c = mocks::container();
+        let uc = Restore::new(c.event_pub(), c.user_repo());
+
+        let mut user = mocks::user(
+            "user-1",
+            "username",
+            "user@omics.com",
+            "P@asswd!",
+            true,
+            None,
+            None,
+            "user",
+        );
+        c.user_repo().save(&mut user).await.unwrap();
+        let role = mocks::role("Admin");
+
+        let user_id = user.base().id().to_string();
+        Delete::new(c.event_pub(), c.user_repo())
+            .exec((user.base().id().clone(), role.clone()), user_id.clone())
+            .await
+            .unwrap();
+        assert!(c.user_repo().find_by_id(user.base().id()).await.is_err());
+        assert!(c
+            .user_repo()
+            .find_by_username(user.identity().username())
+            .await
+            .is_ok());
+
+        uc.exec((user.base().id().clone(), role.clone()), user_id.clone())
+            .await
+            .unwrap();
+        assert!(c.user_repo().find_by_id(user.base().id()).await.is_ok());
+
+        let events = c.event_pub().events().await;
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].code(), "restored");
+
+        assert!(uc
+            .exec((user.base().id().clone(), role), user_id)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,102 @@
+use common::error::Error;
+use common::request::CommandResponse;
+use common::result::Result;
+
+use crate::domain::token::{SessionId, TokenService};
+use crate::domain::user::UserId;
+use crate::UserIdAndRole;
+
+pub struct RevokeSession<'a> {
+    token_serv: &'a TokenService,
+}
+
+impl<'a> RevokeSession<'a> {
+    pub fn new(token_serv: &'a TokenService) -> Self {
+        RevokeSession { token_serv }
+    }
+
+    /// Logs the user out of one of their devices.
+    pub async fn exec(
+        &self,
+        (auth_id, _auth_role): UserIdAndRole,
+        user_id: String,
+        session_id: String,
+    ) -> Result<CommandResponse> {
+        let user_id = UserId::new(user_id)?;
+        if auth_id != user_id {
+            return Err(Error::unauthorized());
+        }
+
+        self.token_serv
+            .revoke(&user_id, &SessionId::build(session_id))
+            .await?;
+
+        Ok(CommandResponse::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::application::user::GetSessions;
+    use crate::domain::token::{Data, Device};
+    use crate::mocks;
+
+    #[tokio::test]
+    async fn revoke_own_session() {
+        let c = mocks::container();
+        let uc = RevokeSession::new(c.token_serv());
+
+        let user_id = UserId::new("#user01").unwrap();
+        let other_user_id = UserId::new("#user02").unwrap();
+        let mut tokens = Vec::new();
+        for ip in ["10.0.0.1", "10.0.0.2"].iter() {
+            let device = Device::new(Some("Firefox".to_owned()), Some(ip.to_string()));
+            tokens.push(
+                c.token_serv()
+                    .create(&user_id, Data::new(), device)
+                    .await
+                    .unwrap(),
+            );
+        }
+
+        let get_sessions = GetSessions::new(c.token_serv());
+        let res = get_sessions
+            .exec((user_id.clone(), mocks::role("User")), user_id.to_string())
+            .await
+            .unwrap();
+        assert_eq!(res.sessions.len(), 2);
+
+        assert!(uc
+            .exec(
+                (other_user_id, mocks::role("Admin")),
+                user_id.to_string(),
+                tokens[0].session_id.to_string(),
+            )
+            .await
+            .is_err());
+
+        uc.exec(
+            (user_id.clone(), mocks::role("User")),
+            user_id.to_string(),
+            tokens[0].session_id.to_string(),
+        )
+        .await
+        .unwrap();
+
+        let sessions = get_sessions
+            .exec((user_id.clone(), mocks::role("User")), user_id.to_string())
+            .await
+            .unwrap()
+            .sessions;
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].id, tokens[1].session_id.to_string());
+        assert_eq!(sessions[0].ip, Some("10.0.0.2".to_owned()));
+        assert!(c
+            .token_serv()
+            .validate(&tokens[0].access_token)
+            .await
+            .is_err());
+    }
+}
@@ -1,16 +1,18 @@
 use std::str::FromStr;
 
-use chrono::DateTime;
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
 use common::error::Error;
 use common::request::Include;
-use common::request::{PaginationParams, PaginationResponse};
+use common::request::{CursorSigner, PaginationParams, PaginationResponse};
 use common::result::Result;
+use common::sql::{Criteria, Query};
 
-use crate::application::dtos::{RoleDto, UserDto};
+use crate::application::dtos::UserDto;
+use crate::application::expand::Expand;
 use crate::domain::role::{RoleId, RoleRepository};
-use crate::domain::user::{UserOrderBy, UserRepository};
+use crate::domain::user::{UserField, UserOrderBy, UserRepository};
 use crate::UserIdAndRole;
 
 #[derive(Deserialize)]
@@ -24,13 +26,20 @@ pub struct SearchCommand {
 pub struct Search<'a> {
     role_repo: &'a dyn RoleRepository,
     user_repo: &'a dyn UserRepository,
+
+    cursor_signer: &'a CursorSigner,
 }
 
 impl<'a> Search<'a> {
-    pub fn new(role_repo: &'a dyn RoleRepository, user_repo: &'a dyn UserRepository) -> Self {
+    pub fn new(
+        role_repo: &'a dyn RoleRepository,
+        user_repo: &'a dyn UserRepository,
+        cursor_signer: &'a CursorSigner,
+    ) -> Self {
         Search {
             role_repo,
             user_repo,
+            cursor_signer,
         }
     }
 
@@ -45,48 +54,51 @@ impl<'a> Search<'a> {
             return Err(Error::unauthorized());
         }
 
-        let pagination_users = self
-            .user_repo
-            .search(
-                cmd.name.as_ref(),
-                cmd.role_id.map(RoleId::new).transpose()?.as_ref(),
+        include.validate(&UserDto::SCHEMA)?;
+
+        let order_by = pagination
+            .order_by()
+            .map(|o| UserOrderBy::from_str(&o))
+            .transpose()?
+            .unwrap_or(UserOrderBy::Oldest);
+        let (field, direction) = order_by.sort_key();
+
+        let query = Query::new()
+            .filter_opt(cmd.name.map(|name| {
+                Criteria::contains(UserField::Username, name.clone())
+                    .or(Criteria::contains(UserField::Name, name.clone()))
+                    .or(Criteria::contains(UserField::Lastname, name.clone()))
+                    .or(Criteria::contains(UserField::Fullname, name))
+            }))
+            .filter_opt(
+                cmd.role_id
+                    .map(RoleId::new)
+                    .transpose()?
+                    .map(|id| Criteria::eq(UserField::RoleId, &id)),
+            )
+            .filter(Criteria::range(
+                UserField::CreatedAt,
                 cmd.date_from
-                    .map(|d| DateTime::from_str(&d))
+                    .map(|d| DateTime::<Utc>::from_str(&d))
                     .transpose()
-                    .map_err(|err| Error::bad_format("date_from").wrap_raw(err))?
-                    .as_ref(),
+                    .map_err(|err| Error::bad_format("date_from").wrap_raw(err))?,
                 cmd.date_to
-                    .map(|d| DateTime::from_str(&d))
+                    .map(|d| DateTime::<Utc>::from_str(&d))
                     .transpose()
-                    .map_err(|err| Error::bad_format("date_to").wrap_raw(err))?
-                    .as_ref(),
-                pagination.offset(),
-                pagination.limit(),
-                pagination
-                    .order_by()
-                    .map(|o| UserOrderBy::from_str(&o))
-                    .transpose()?
-                    .as_ref(),
-            )
-            .await?;
-
-        let mut res = PaginationResponse::new(
-            pagination_users.offset(),
-            pagination_users.limit(),
-            pagination_users.total(),
-            pagination_users.matching_criteria(),
-        );
+                    .map_err(|err| Error::bad_format("date_to").wrap_raw(err))?,
+            ))
+            .sort(field, direction)
+            .cursor(UserField::Id, pagination.cursor(self.cursor_signer)?)?
+            .paginate(pagination.offset(), pagination.limit());
 
-        for user in pagination_users.into_items().into_iter() {
-            let mut user_dto = UserDto::from(&user);
+        let pagination_users = self.user_repo.search(&query).await?;
 
-            if include.has("role") {
-                let role = self.role_repo.find_by_id(user.role_id()).await?;
-                user_dto = user_dto.role(RoleDto::from(&role));
-            }
-
-            res.add_item(user_dto);
-        }
+        let mut res = PaginationResponse::from_pagination(&pagination_users, self.cursor_signer);
+        res.add_items(
+            Expand::new(self.role_repo)
+                .users(pagination_users.items(), &include)
+                .await?,
+        );
 
         Ok(res)
     }
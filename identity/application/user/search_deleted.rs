@@ -0,0 +1,61 @@
+use common::error::Error;
+use common::request::{CursorSigner, Include, PaginationParams, PaginationResponse};
+use common::result::Result;
+use common::sql::{Direction, Query};
+
+use crate::application::dtos::UserDto;
+use crate::application::expand::Expand;
+use crate::domain::role::RoleRepository;
+use crate::domain::user::{UserField, UserRepository};
+use crate::UserIdAndRole;
+
+pub struct SearchDeleted<'a> {
+    role_repo: &'a dyn RoleRepository,
+    user_repo: &'a dyn UserRepository,
+
+    cursor_signer: &'a CursorSigner,
+}
+
+impl<'a> SearchDeleted<'a> {
+    pub fn new(
+        role_repo: &'a dyn RoleRepository,
+        user_repo: &'a dyn UserRepository,
+        cursor_signer: &'a CursorSigner,
+    ) -> Self {
+        SearchDeleted {
+            role_repo,
+            user_repo,
+            cursor_signer,
+        }
+    }
+
+    pub async fn exec(
+        &self,
+        (_auth_id, auth_role): UserIdAndRole,
+        include: Include,
+        pagination: PaginationParams,
+    ) -> Result<PaginationResponse<UserDto>> {
+        if !auth_role.can("restore_deleted") {
+            return Err(Error::unauthorized());
+        }
+
+        include.validate(&UserDto::SCHEMA)?;
+
+        // The last deleted users first.
+        let query = Query::new()
+            .sort(UserField::DeletedAt, Direction::Desc)
+            .cursor(UserField::Id, pagination.cursor(self.cursor_signer)?)?
+            .paginate(pagination.offset(), pagination.limit());
+
+        let pagination_users = self.user_repo.search_deleted(&query).await?;
+
+        let mut res = PaginationResponse::from_pagination(&pagination_users, self.cursor_signer);
+        res.add_items(
+            Expand::new(self.role_repo)
+                .users(pagination_users.items(), &include)
+                .await?,
+        );
+
+        Ok(res)
+    }
+}
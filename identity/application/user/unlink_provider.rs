@@ -0,0 +1,44 @@
+use std::str::FromStr;
+
+use common::error::Error;
+use common::request::CommandResponse;
+use common::result::Result;
+
+use crate::domain::oauth::OAuthService;
+use crate::domain::user::{Provider, UserId, UserRepository};
+use crate::UserIdAndRole;
+
+pub struct UnlinkProvider<'a> {
+    user_repo: &'a dyn UserRepository,
+
+    oauth_serv: &'a OAuthService,
+}
+
+impl<'a> UnlinkProvider<'a> {
+    pub fn new(user_repo: &'a dyn UserRepository, oauth_serv: &'a OAuthService) -> Self {
+        UnlinkProvider {
+            user_repo,
+            oauth_serv,
+        }
+    }
+
+    /// Stops logging in with the account of the provider. Only the user can unlink their
+    /// accounts.
+    pub async fn exec(
+        &self,
+        (auth_id, _auth_role): UserIdAndRole,
+        user_id: String,
+        provider: String,
+    ) -> Result<CommandResponse> {
+        let user_id = UserId::new(user_id)?;
+        if auth_id != user_id {
+            return Err(Error::unauthorized());
+        }
+
+        let user = self.user_repo.find_by_id(&user_id).await?;
+        let provider = Provider::from_str(&provider)?;
+        self.oauth_serv.unlink(&user, &provider).await?;
+
+        Ok(CommandResponse::default())
+    }
+}
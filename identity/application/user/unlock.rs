@@ -0,0 +1,94 @@
+use common::error::Error;
+use common::event::EventPublisher;
+use common::request::CommandResponse;
+use common::result::Result;
+
+use crate::domain::user::{UserId, UserRepository};
+use crate::UserIdAndRole;
+
+/// Unlocks a user locked out after failing to log in, before the lock ends.
+pub struct Unlock<'a> {
+    event_pub: &'a dyn EventPublisher,
+
+    user_repo: &'a dyn UserRepository,
+}
+
+impl<'a> Unlock<'a> {
+    pub fn new(event_pub: &'a dyn EventPublisher, user_repo: &'a dyn UserRepository) -> Self {
+        Unlock {
+            event_pub,
+            user_repo,
+        }
+    }
+
+    pub async fn exec(
+        &self,
+        (_auth_id, auth_role): UserIdAndRole,
+        user_id: String,
+    ) -> Result<CommandResponse> {
+        if !auth_role.can("unlock_user") {
+            return Err(Error::unauthorized());
+        }
+
+        let mut user = self.user_repo.find_by_id(&UserId::new(user_id)?).await?;
+
+        user.unlock()?;
+
+        self.user_repo.save(&mut user).await?;
+
+        self.event_pub.publish_all(user.events().to_vec()?).await?;
+
+        Ok(CommandResponse::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::{Duration, Utc};
+
+    use crate::mocks;
+
+    #[tokio::test]
+    async fn unlock_locked_out_user() {
+        let c = mocks::container();
+        let uc = Unlock::new(c.event_pub(), c.user_repo());
+
+        let mut user = mocks::user(
+            "user-1",
+            "username",
+            "user@omics.com",
+            "P@asswd!",
+            true,
+            None,
+            None,
+            "user",
+        );
+        user.lock_out(Utc::now() + Duration::minutes(15)).unwrap();
+        c.user_repo().save(&mut user).await.unwrap();
+        let role = mocks::role("Admin");
+
+        let user_id = user.base().id().to_string();
+        uc.exec((user.base().id().clone(), role.clone()), user_id.clone())
+            .await
+            .unwrap();
+        assert!(!c
+            .user_repo()
+            .find_by_id(user.base().id())
+            .await
+            .unwrap()
+            .is_locked_out());
+
+        let events = c.event_pub().events().await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].code(), "unlocked");
+
+        let err = uc
+            .exec((user.base().id().clone(), role), user_id)
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "not_locked_out");
+    }
+}
@@ -1,72 +1,130 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 
+use common::cache::Cache;
 use common::container::Container;
 use common::event::EventPublisher;
+use common::transaction::UnitOfWork;
 
+use crate::domain::oauth::{
+    AuthorizationRequest, ExternalIdentityRepository, IdentityProvider, OAuthService,
+};
 use crate::domain::role::{PermissionRepository, RoleRepository};
 use crate::domain::token::{TokenEncoder, TokenRepository, TokenService};
 use crate::domain::user::{
-    AuthenticationService, AuthorizationService, PasswordHasher, UserRepository, UserService,
+    AttemptRepository, AuthenticationService, AuthorizationService, EmailChangeSender,
+    LoginChallenge, PasswordHasher, PasswordResetSender, ThrottlingService, UserRepository,
+    UserService,
 };
 
 pub struct IdentityContainer<EPub> {
     event_pub: Arc<EPub>,
 
+    attempt_repo: Arc<dyn AttemptRepository>,
+    authorization_cache: Arc<dyn Cache<String, AuthorizationRequest>>,
+    external_identity_repo: Arc<dyn ExternalIdentityRepository>,
+    login_challenge_cache: Arc<dyn Cache<String, LoginChallenge>>,
     permission_repo: Arc<dyn PermissionRepository>,
     role_repo: Arc<dyn RoleRepository>,
     token_repo: Arc<dyn TokenRepository>,
     user_repo: Arc<dyn UserRepository>,
 
+    email_change_sender: Arc<dyn EmailChangeSender>,
     password_hasher: Arc<dyn PasswordHasher>,
+    password_reset_sender: Arc<dyn PasswordResetSender>,
     token_enc: Arc<dyn TokenEncoder>,
 
     token_serv: Arc<TokenService>,
     user_serv: Arc<UserService>,
+    throttling_serv: Arc<ThrottlingService>,
     authentication_serv: Arc<AuthenticationService>,
     authorization_serv: Arc<AuthorizationService>,
+    oauth_serv: Arc<OAuthService>,
+
+    unit_of_work: Arc<dyn UnitOfWork>,
 }
 
 impl<EPub> IdentityContainer<EPub>
 where
     EPub: EventPublisher,
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         event_pub: Arc<EPub>,
 
+        attempt_repo: Arc<dyn AttemptRepository>,
+        authorization_cache: Arc<dyn Cache<String, AuthorizationRequest>>,
+        external_identity_repo: Arc<dyn ExternalIdentityRepository>,
+        login_challenge_cache: Arc<dyn Cache<String, LoginChallenge>>,
         permission_repo: Arc<dyn PermissionRepository>,
         role_repo: Arc<dyn RoleRepository>,
         token_repo: Arc<dyn TokenRepository>,
         user_repo: Arc<dyn UserRepository>,
 
+        email_change_sender: Arc<dyn EmailChangeSender>,
         password_hasher: Arc<dyn PasswordHasher>,
+        password_reset_sender: Arc<dyn PasswordResetSender>,
         token_enc: Arc<dyn TokenEncoder>,
+        identity_providers: Vec<Arc<dyn IdentityProvider>>,
+        unit_of_work: Arc<dyn UnitOfWork>,
+
+        access_token_ttl: Duration,
+        session_ttl: Duration,
     ) -> Self {
-        let token_serv = Arc::new(TokenService::new(token_repo.clone(), token_enc.clone()));
+        let token_serv = Arc::new(
+            TokenService::new(token_repo.clone(), token_enc.clone())
+                .access_ttl(access_token_ttl)
+                .session_ttl(session_ttl),
+        );
         let user_serv = Arc::new(UserService::new(user_repo.clone(), password_hasher.clone()));
+        let throttling_serv = Arc::new(ThrottlingService::new(attempt_repo.clone()));
         let authentication_serv = Arc::new(AuthenticationService::new(
+            login_challenge_cache.clone(),
+            role_repo.clone(),
             user_repo.clone(),
             password_hasher.clone(),
+            throttling_serv.clone(),
             token_serv.clone(),
         ));
         let authorization_serv = Arc::new(AuthorizationService::new(token_serv.clone()));
+        let oauth_serv = Arc::new(identity_providers.into_iter().fold(
+            OAuthService::new(
+                authorization_cache.clone(),
+                external_identity_repo.clone(),
+                role_repo.clone(),
+                user_repo.clone(),
+                user_serv.clone(),
+            ),
+            |oauth_serv, identity_provider| oauth_serv.provider(identity_provider),
+        ));
 
         IdentityContainer {
             event_pub,
 
+            attempt_repo,
+            authorization_cache,
+            external_identity_repo,
+            login_challenge_cache,
             permission_repo,
             role_repo,
             token_repo,
             user_repo,
 
+            email_change_sender,
             password_hasher,
+            password_reset_sender,
             token_enc,
 
             token_serv,
             user_serv,
+            throttling_serv,
             authentication_serv,
             authorization_serv,
+            oauth_serv,
+
+            unit_of_work,
         }
     }
 
@@ -74,6 +132,22 @@ where
         &self.event_pub
     }
 
+    pub fn attempt_repo(&self) -> &dyn AttemptRepository {
+        self.attempt_repo.as_ref()
+    }
+
+    pub fn authorization_cache(&self) -> &dyn Cache<String, AuthorizationRequest> {
+        self.authorization_cache.as_ref()
+    }
+
+    pub fn external_identity_repo(&self) -> &dyn ExternalIdentityRepository {
+        self.external_identity_repo.as_ref()
+    }
+
+    pub fn login_challenge_cache(&self) -> &dyn Cache<String, LoginChallenge> {
+        self.login_challenge_cache.as_ref()
+    }
+
     pub fn permission_repo(&self) -> &dyn PermissionRepository {
         self.permission_repo.as_ref()
     }
@@ -91,10 +165,18 @@ where
     }
 
     // Services
+    pub fn email_change_sender(&self) -> &dyn EmailChangeSender {
+        self.email_change_sender.as_ref()
+    }
+
     pub fn password_hasher(&self) -> &dyn PasswordHasher {
         self.password_hasher.as_ref()
     }
 
+    pub fn password_reset_sender(&self) -> &dyn PasswordResetSender {
+        self.password_reset_sender.as_ref()
+    }
+
     pub fn token_enc(&self) -> &dyn TokenEncoder {
         self.token_enc.as_ref()
     }
@@ -107,6 +189,10 @@ where
         &self.user_serv
     }
 
+    pub fn throttling_serv(&self) -> &ThrottlingService {
+        &self.throttling_serv
+    }
+
     pub fn authentication_serv(&self) -> &AuthenticationService {
         &self.authentication_serv
     }
@@ -114,6 +200,14 @@ where
     pub fn authorization_serv(&self) -> &AuthorizationService {
         &self.authorization_serv
     }
+
+    pub fn oauth_serv(&self) -> &OAuthService {
+        &self.oauth_serv
+    }
+
+    pub fn unit_of_work(&self) -> &dyn UnitOfWork {
+        self.unit_of_work.as_ref()
+    }
 }
 
 #[async_trait]
@@ -1,3 +1,4 @@
+pub mod oauth;
 pub mod role;
 pub mod token;
 pub mod user;
@@ -0,0 +1,12 @@
+mod authorization_request;
+mod external_identity;
+mod identity_provider;
+mod pkce;
+mod repository;
+mod service;
+pub use authorization_request::*;
+pub use external_identity::*;
+pub use identity_provider::*;
+pub use pkce::*;
+pub use repository::*;
+pub use service::*;
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use common::cache::CacheNamespace;
+
+use crate::domain::oauth::Pkce;
+use crate::domain::user::{Provider, UserId};
+
+/// Authorization started by redirecting a user to an identity provider. It's kept until the
+/// provider redirects back with the code, using the `state` sent to the provider as its key, and
+/// it can only be completed once.
+///
+/// It's bound to the browser that started it with a key stored in a cookie, so a code obtained
+/// by someone else can't be completed in the browser of the user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorizationRequest {
+    state: String,
+    /// Sent to OpenID Connect providers, which include it in the ID token.
+    nonce: String,
+    provider: Provider,
+    code_verifier: String,
+    browser_key_hash: String,
+    /// User the external identity is linked to. Without it the external identity is used to log
+    /// in.
+    user_id: Option<UserId>,
+}
+
+impl AuthorizationRequest {
+    /// Creates the request and returns the key of the browser, which is only stored hashed.
+    pub fn new(provider: Provider, pkce: &Pkce, user_id: Option<UserId>) -> (Self, String) {
+        let browser_key = generate_secret();
+
+        let request = AuthorizationRequest {
+            state: Uuid::new_v4().to_simple().to_string(),
+            nonce: generate_secret(),
+            provider,
+            code_verifier: pkce.verifier().to_owned(),
+            browser_key_hash: hash(&browser_key),
+            user_id,
+        };
+
+        (request, browser_key)
+    }
+
+    pub fn state(&self) -> &str {
+        &self.state
+    }
+
+    pub fn nonce(&self) -> &str {
+        &self.nonce
+    }
+
+    pub fn provider(&self) -> &Provider {
+        &self.provider
+    }
+
+    pub fn pkce(&self) -> Pkce {
+        Pkce::build(&self.code_verifier)
+    }
+
+    pub fn user_id(&self) -> Option<&UserId> {
+        self.user_id.as_ref()
+    }
+
+    /// Whether the request was started by the browser with `browser_key`.
+    pub fn is_bound_to(&self, browser_key: &str) -> bool {
+        hash(browser_key) == self.browser_key_hash
+    }
+}
+
+impl CacheNamespace for AuthorizationRequest {
+    const NAMESPACE: &'static str = "authorization_requests";
+}
+
+fn generate_secret() -> String {
+    format!(
+        "{}{}",
+        Uuid::new_v4().to_simple(),
+        Uuid::new_v4().to_simple()
+    )
+}
+
+fn hash(secret: &str) -> String {
+    format!("{:x}", Sha256::digest(secret.as_bytes()))
+}
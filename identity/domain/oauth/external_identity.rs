@@ -0,0 +1,114 @@
+use chrono::{DateTime, Utc};
+
+use common::error::Error;
+use common::result::Result;
+
+use crate::domain::user::{Email, Fullname, Provider, UserId};
+
+/// User as authenticated by an identity provider.
+#[derive(Debug, Clone)]
+pub struct ExternalUser {
+    subject: String,
+    email: Option<Email>,
+    email_verified: bool,
+    fullname: Option<Fullname>,
+}
+
+impl ExternalUser {
+    /// `subject` is the ID of the user in the provider, the only claim that can't change.
+    pub fn new<S: Into<String>>(
+        subject: S,
+        email: Option<Email>,
+        email_verified: bool,
+        fullname: Option<Fullname>,
+    ) -> Result<Self> {
+        let subject = subject.into();
+        if subject.is_empty() {
+            return Err(Error::new("external_user", "invalid_subject"));
+        }
+
+        Ok(ExternalUser {
+            subject,
+            email,
+            email_verified,
+            fullname,
+        })
+    }
+
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    pub fn email(&self) -> Option<&Email> {
+        self.email.as_ref()
+    }
+
+    /// Whether the provider checked the user owns the email.
+    pub fn is_email_verified(&self) -> bool {
+        self.email.is_some() && self.email_verified
+    }
+
+    pub fn fullname(&self) -> Option<&Fullname> {
+        self.fullname.as_ref()
+    }
+}
+
+/// Account of a user in an identity provider, linked to a user so they can log in with it.
+/// A user can link one account of each provider.
+#[derive(Debug, Clone)]
+pub struct ExternalIdentity {
+    provider: Provider,
+    subject: String,
+    user_id: UserId,
+    email: Option<Email>,
+    linked_at: DateTime<Utc>,
+}
+
+impl ExternalIdentity {
+    pub fn new(provider: Provider, external_user: &ExternalUser, user_id: UserId) -> Self {
+        ExternalIdentity {
+            provider,
+            subject: external_user.subject().to_owned(),
+            user_id,
+            email: external_user.email().cloned(),
+            linked_at: Utc::now(),
+        }
+    }
+
+    pub fn build(
+        provider: Provider,
+        subject: String,
+        user_id: UserId,
+        email: Option<Email>,
+        linked_at: DateTime<Utc>,
+    ) -> Self {
+        ExternalIdentity {
+            provider,
+            subject,
+            user_id,
+            email,
+            linked_at,
+        }
+    }
+
+    pub fn provider(&self) -> &Provider {
+        &self.provider
+    }
+
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    pub fn user_id(&self) -> &UserId {
+        &self.user_id
+    }
+
+    /// Email of the account when it was linked.
+    pub fn email(&self) -> Option<&Email> {
+        self.email.as_ref()
+    }
+
+    pub fn linked_at(&self) -> &DateTime<Utc> {
+        &self.linked_at
+    }
+}
@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+
+use common::result::Result;
+
+use crate::domain::oauth::{ExternalUser, Pkce};
+use crate::domain::user::Provider;
+
+/// IdentityProvider authenticates users with the OAuth 2.0 authorization code flow. OpenID
+/// Connect providers also return an ID token, which has to contain the nonce of the
+/// authorization.
+#[async_trait]
+pub trait IdentityProvider: Sync + Send {
+    fn provider(&self) -> Provider;
+
+    /// URL the user is redirected to in order to authorize the application. The provider
+    /// redirects back with the given `state` and a code.
+    fn authorization_url(&self, state: &str, nonce: &str, pkce: &Pkce) -> Result<String>;
+
+    /// Exchanges the code the provider redirected back with for the user that authorized the
+    /// application. The PKCE verifier has to match the challenge of the authorization URL, and
+    /// the ID token, if the provider returns one, has to contain the same nonce.
+    async fn exchange(&self, code: &str, nonce: &str, pkce: &Pkce) -> Result<ExternalUser>;
+}
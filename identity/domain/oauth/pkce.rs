@@ -0,0 +1,69 @@
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Proof Key for Code Exchange (RFC 7636) of an authorization request. Only the challenge is
+/// sent to the provider when the user is redirected; the verifier is kept by the server and sent
+/// when the code is exchanged, so an intercepted code can't be used by anyone else.
+#[derive(Debug, Clone)]
+pub struct Pkce {
+    verifier: String,
+}
+
+impl Pkce {
+    pub fn new() -> Self {
+        Pkce {
+            verifier: format!(
+                "{}{}",
+                Uuid::new_v4().to_simple(),
+                Uuid::new_v4().to_simple()
+            ),
+        }
+    }
+
+    pub fn build<S: Into<String>>(verifier: S) -> Self {
+        Pkce {
+            verifier: verifier.into(),
+        }
+    }
+
+    pub fn verifier(&self) -> &str {
+        &self.verifier
+    }
+
+    /// S256 challenge: the base64url encoded SHA-256 of the verifier.
+    pub fn challenge(&self) -> String {
+        base64::encode_config(
+            Sha256::digest(self.verifier.as_bytes()),
+            base64::URL_SAFE_NO_PAD,
+        )
+    }
+
+    pub fn method(&self) -> &str {
+        "S256"
+    }
+}
+
+impl Default for Pkce {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn challenge() {
+        // Example of the RFC.
+        let pkce = Pkce::build("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk");
+        assert_eq!(
+            pkce.challenge(),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+
+        let pkce = Pkce::new();
+        assert!(pkce.verifier().len() >= 43 && pkce.verifier().len() <= 128);
+        assert_ne!(pkce.verifier(), Pkce::new().verifier());
+    }
+}
@@ -0,0 +1,16 @@
+use async_trait::async_trait;
+
+use common::result::Result;
+
+use crate::domain::oauth::ExternalIdentity;
+use crate::domain::user::{Provider, UserId};
+
+#[async_trait]
+pub trait ExternalIdentityRepository: Sync + Send {
+    async fn find(&self, provider: &Provider, subject: &str) -> Result<ExternalIdentity>;
+    async fn find_by_user_id(&self, user_id: &UserId) -> Result<Vec<ExternalIdentity>>;
+
+    async fn save(&self, external_identity: &ExternalIdentity) -> Result<()>;
+
+    async fn delete(&self, provider: &Provider, subject: &str) -> Result<()>;
+}
@@ -0,0 +1,413 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use common::cache::Cache;
+use common::error::Error;
+use common::result::Result;
+
+use crate::domain::oauth::{
+    AuthorizationRequest, ExternalIdentity, ExternalIdentityRepository, ExternalUser,
+    IdentityProvider, Pkce,
+};
+use crate::domain::role::RoleRepository;
+use crate::domain::user::{Identity, Person, Provider, User, UserId, UserRepository, UserService};
+
+/// Time a user has to authorize the application in the provider.
+const AUTHORIZATION_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// OAuthService logs in and registers users with their accounts in identity providers, and
+/// links those accounts to existing users.
+pub struct OAuthService {
+    authorization_cache: Arc<dyn Cache<String, AuthorizationRequest>>,
+    external_identity_repo: Arc<dyn ExternalIdentityRepository>,
+    role_repo: Arc<dyn RoleRepository>,
+    user_repo: Arc<dyn UserRepository>,
+
+    user_serv: Arc<UserService>,
+
+    providers: HashMap<Provider, Arc<dyn IdentityProvider>>,
+}
+
+impl OAuthService {
+    pub fn new(
+        authorization_cache: Arc<dyn Cache<String, AuthorizationRequest>>,
+        external_identity_repo: Arc<dyn ExternalIdentityRepository>,
+        role_repo: Arc<dyn RoleRepository>,
+        user_repo: Arc<dyn UserRepository>,
+        user_serv: Arc<UserService>,
+    ) -> Self {
+        OAuthService {
+            authorization_cache,
+            external_identity_repo,
+            role_repo,
+            user_repo,
+            user_serv,
+            providers: HashMap::new(),
+        }
+    }
+
+    /// Enables logging in with the provider.
+    pub fn provider(mut self, identity_provider: Arc<dyn IdentityProvider>) -> Self {
+        self.providers
+            .insert(identity_provider.provider(), identity_provider);
+        self
+    }
+
+    fn identity_provider(&self, provider: &Provider) -> Result<&dyn IdentityProvider> {
+        self.providers
+            .get(provider)
+            .map(AsRef::as_ref)
+            .ok_or_else(|| {
+                Error::new("provider", "not_configured")
+                    .add_context("provider", provider.to_string().as_str())
+            })
+    }
+
+    /// Starts an authorization and returns the URL of the provider the user has to be
+    /// redirected to, and the key the browser has to send to complete it. The external identity
+    /// is linked to `user_id` when it's given.
+    pub async fn authorize(
+        &self,
+        provider: &Provider,
+        user_id: Option<UserId>,
+    ) -> Result<(String, String)> {
+        let identity_provider = self.identity_provider(provider)?;
+
+        let pkce = Pkce::new();
+        let (request, browser_key) = AuthorizationRequest::new(provider.clone(), &pkce, user_id);
+        let url = identity_provider.authorization_url(request.state(), request.nonce(), &pkce)?;
+
+        self.authorization_cache
+            .set_with_ttl(request.state().to_owned(), request, AUTHORIZATION_TTL)
+            .await?;
+
+        Ok((url, browser_key))
+    }
+
+    /// Finds the user of the external identity that authorized the application, registering a
+    /// new one the first time. Returns whether the user was registered.
+    pub async fn authenticate(
+        &self,
+        provider: &Provider,
+        state: &str,
+        browser_key: &str,
+        code: &str,
+    ) -> Result<(User, bool)> {
+        let (request, external_user) = self.complete(provider, state, browser_key, code).await?;
+        if request.user_id().is_some() {
+            return Err(Error::new("authorization", "invalid_state"));
+        }
+
+        if let Ok(external_identity) = self
+            .external_identity_repo
+            .find(provider, external_user.subject())
+            .await
+        {
+            let user = self
+                .user_repo
+                .find_by_id(external_identity.user_id())
+                .await?;
+            return Ok((user, false));
+        }
+
+        let user = self.register(provider, &external_user).await?;
+        Ok((user, true))
+    }
+
+    /// Links the external identity that authorized the application to the user that started the
+    /// authorization.
+    pub async fn link(
+        &self,
+        user_id: &UserId,
+        provider: &Provider,
+        state: &str,
+        browser_key: &str,
+        code: &str,
+    ) -> Result<ExternalIdentity> {
+        let (request, external_user) = self.complete(provider, state, browser_key, code).await?;
+        if request.user_id() != Some(user_id) {
+            return Err(Error::new("authorization", "invalid_state"));
+        }
+
+        let already_linked = self
+            .external_identity_repo
+            .find(provider, external_user.subject())
+            .await
+            .is_ok()
+            || self
+                .external_identity_repo
+                .find_by_user_id(user_id)
+                .await?
+                .iter()
+                .any(|external_identity| external_identity.provider() == provider);
+        if already_linked {
+            return Err(Error::new("external_identity", "already_linked"));
+        }
+
+        let external_identity =
+            ExternalIdentity::new(provider.clone(), &external_user, user_id.clone());
+        self.external_identity_repo.save(&external_identity).await?;
+
+        Ok(external_identity)
+    }
+
+    pub async fn identities(&self, user_id: &UserId) -> Result<Vec<ExternalIdentity>> {
+        self.external_identity_repo.find_by_user_id(user_id).await
+    }
+
+    /// Removes the external identity of the provider from the user. Users without a password
+    /// can't remove the last one, because they couldn't log in anymore.
+    pub async fn unlink(&self, user: &User, provider: &Provider) -> Result<()> {
+        let external_identities = self
+            .external_identity_repo
+            .find_by_user_id(user.base().id())
+            .await?;
+
+        let external_identity = external_identities
+            .iter()
+            .find(|external_identity| external_identity.provider() == provider)
+            .ok_or_else(|| Error::not_found("external_identity"))?;
+
+        if user.identity().password().is_none() && external_identities.len() == 1 {
+            return Err(Error::new("external_identity", "required"));
+        }
+
+        self.external_identity_repo
+            .delete(external_identity.provider(), external_identity.subject())
+            .await
+    }
+
+    /// Exchanges the code of the authorization identified by `state`, which has to be completed
+    /// by the browser that started it. Each authorization can be completed once.
+    async fn complete(
+        &self,
+        provider: &Provider,
+        state: &str,
+        browser_key: &str,
+        code: &str,
+    ) -> Result<(AuthorizationRequest, ExternalUser)> {
+        let err = || Error::new("authorization", "invalid_state");
+
+        let request = self
+            .authorization_cache
+            .take(&state.to_owned())
+            .await
+            .ok_or_else(err)?;
+
+        if request.provider() != provider || !request.is_bound_to(browser_key) {
+            return Err(err());
+        }
+
+        let external_user = self
+            .identity_provider(provider)?
+            .exchange(code, request.nonce(), &request.pkce())
+            .await?;
+
+        Ok((request, external_user))
+    }
+
+    async fn register(&self, provider: &Provider, external_user: &ExternalUser) -> Result<User> {
+        let email = external_user
+            .email()
+            .ok_or_else(|| Error::new("external_user", "email_required"))?;
+
+        // The existing user has to link the external identity, otherwise anyone with an account
+        // in a provider that doesn't verify emails could take it over.
+        if self.user_repo.find_by_email(email).await.is_ok() {
+            return Err(Error::new("external_identity", "not_linked"));
+        }
+
+        let default_role = self.role_repo.find_default().await?;
+
+        let mut user = User::new(
+            self.user_repo.next_id().await?,
+            Identity::new(
+                provider.clone(),
+                self.user_serv.generate_username(email).await?,
+                email.clone(),
+                None,
+            )?,
+            default_role.base().id().clone(),
+        )?;
+
+        if external_user.is_email_verified() {
+            if let Some(validation) = user.validation().cloned() {
+                user.validate(&validation)?;
+            }
+        }
+
+        if let Some(fullname) = external_user.fullname() {
+            user.set_person(Person::new(fullname.clone(), None, None, None, None)?)?;
+        }
+
+        self.user_repo.save(&mut user).await?;
+        self.external_identity_repo
+            .save(&ExternalIdentity::new(
+                provider.clone(),
+                external_user,
+                user.base().id().clone(),
+            ))
+            .await?;
+
+        Ok(user)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::mocks::{self, FakeIdentityProvider};
+
+    #[tokio::test]
+    async fn register_and_login() {
+        let c = mocks::container();
+        let serv = c.oauth_serv();
+        let idp = FakeIdentityProvider::new(Provider::Google);
+
+        let (url, key) = serv.authorize(&Provider::Google, None).await.unwrap();
+        let (state, code) = idp.login(&url, "google-1", "user@gmail.com").unwrap();
+        let (user, registered) = serv
+            .authenticate(&Provider::Google, &state, &key, &code)
+            .await
+            .unwrap();
+        assert!(registered);
+        assert!(user.is_validated());
+        assert!(user.identity().password().is_none());
+        assert_eq!(user.identity().provider(), &Provider::Google);
+        assert_eq!(user.identity().email().value(), "user@gmail.com");
+
+        // Each authorization is completed once.
+        assert!(serv
+            .authenticate(&Provider::Google, &state, &key, &code)
+            .await
+            .is_err());
+
+        let (url, key) = serv.authorize(&Provider::Google, None).await.unwrap();
+        let (state, code) = idp.login(&url, "google-1", "user@gmail.com").unwrap();
+        let (logged_in_user, registered) = serv
+            .authenticate(&Provider::Google, &state, &key, &code)
+            .await
+            .unwrap();
+        assert!(!registered);
+        assert_eq!(logged_in_user.base().id(), user.base().id());
+
+        assert!(serv.authorize(&Provider::Facebook, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn invalid_code_verifier() {
+        let c = mocks::container();
+        let serv = c.oauth_serv();
+        let idp = FakeIdentityProvider::new(Provider::Google);
+
+        let (url, key) = serv.authorize(&Provider::Google, None).await.unwrap();
+        let (state, _) = idp.login(&url, "google-1", "user@gmail.com").unwrap();
+
+        // A code obtained by someone else, for a different challenge.
+        let (_, code) = idp
+            .login(
+                &idp.authorization_url("other", "nonce", &Pkce::new())
+                    .unwrap(),
+                "google-2",
+                "other@gmail.com",
+            )
+            .unwrap();
+
+        let err = serv
+            .authenticate(&Provider::Google, &state, &key, &code)
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "invalid_grant");
+    }
+
+    #[tokio::test]
+    async fn link_and_unlink() {
+        let c = mocks::container();
+        let serv = c.oauth_serv();
+        let idp = FakeIdentityProvider::new(Provider::Google);
+
+        let mut user = mocks::user(
+            "user-1",
+            "username",
+            "user@omics.com",
+            "P@asswd!",
+            true,
+            None,
+            None,
+            "user",
+        );
+        c.user_repo().save(&mut user).await.unwrap();
+
+        // An account with the email of an existing user has to be linked by that user.
+        let (url, key) = serv.authorize(&Provider::Google, None).await.unwrap();
+        let (state, code) = idp.login(&url, "google-1", "user@omics.com").unwrap();
+        let err = serv
+            .authenticate(&Provider::Google, &state, &key, &code)
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "not_linked");
+
+        let (url, key) = serv
+            .authorize(&Provider::Google, Some(user.base().id().clone()))
+            .await
+            .unwrap();
+        let (state, code) = idp.login(&url, "google-1", "user@omics.com").unwrap();
+        serv.link(user.base().id(), &Provider::Google, &state, &key, &code)
+            .await
+            .unwrap();
+        assert_eq!(serv.identities(user.base().id()).await.unwrap().len(), 1);
+
+        let (url, key) = serv.authorize(&Provider::Google, None).await.unwrap();
+        let (state, code) = idp.login(&url, "google-1", "user@omics.com").unwrap();
+        let (logged_in_user, registered) = serv
+            .authenticate(&Provider::Google, &state, &key, &code)
+            .await
+            .unwrap();
+        assert!(!registered);
+        assert_eq!(logged_in_user.base().id(), user.base().id());
+
+        assert!(serv.unlink(&user, &Provider::Google).await.is_ok());
+        assert!(serv.identities(user.base().id()).await.unwrap().is_empty());
+        assert!(serv.unlink(&user, &Provider::Google).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn keep_last_identity_without_password() {
+        let c = mocks::container();
+        let serv = c.oauth_serv();
+        let idp = FakeIdentityProvider::new(Provider::Google);
+
+        let (url, key) = serv.authorize(&Provider::Google, None).await.unwrap();
+        let (state, code) = idp.login(&url, "google-1", "user@gmail.com").unwrap();
+        let (user, _) = serv
+            .authenticate(&Provider::Google, &state, &key, &code)
+            .await
+            .unwrap();
+
+        let err = serv.unlink(&user, &Provider::Google).await.err().unwrap();
+        assert_eq!(err.code(), "required");
+    }
+
+    #[tokio::test]
+    async fn bound_to_browser() {
+        let c = mocks::container();
+        let serv = c.oauth_serv();
+        let idp = FakeIdentityProvider::new(Provider::Google);
+
+        // The code is obtained by someone else and sent to the browser of the user.
+        let (url, _) = serv.authorize(&Provider::Google, None).await.unwrap();
+        let (state, code) = idp.login(&url, "google-1", "other@gmail.com").unwrap();
+        let (_, key) = serv.authorize(&Provider::Google, None).await.unwrap();
+
+        let err = serv
+            .authenticate(&Provider::Google, &state, &key, &code)
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "invalid_state");
+    }
+}
@@ -9,6 +9,7 @@ pub use repository::*;
 
 use slug::slugify;
 
+use common::error::Error;
 use common::model::{AggregateRoot, StringId};
 use common::result::Result;
 
@@ -20,6 +21,7 @@ pub struct Role {
     name: Name,
     permissions: Vec<Permission>,
     default: bool,
+    two_factor_required: bool,
 }
 
 impl Role {
@@ -32,6 +34,7 @@ impl Role {
             name,
             permissions: Vec::new(),
             default: false,
+            two_factor_required: false,
         })
     }
 
@@ -40,12 +43,14 @@ impl Role {
         name: Name,
         permissions: Vec<Permission>,
         default: bool,
+        two_factor_required: bool,
     ) -> Self {
         Role {
             base,
             name,
             permissions,
             default,
+            two_factor_required,
         }
     }
 
@@ -77,6 +82,11 @@ impl Role {
         self.default
     }
 
+    /// Whether users of the role have to log in with two-factor authentication.
+    pub fn requires_two_factor(&self) -> bool {
+        self.two_factor_required
+    }
+
     pub fn set_name(&mut self, name: Name) -> Result<()> {
         self.name = name;
         self.base.update();
@@ -95,10 +105,25 @@ impl Role {
         Ok(())
     }
 
+    pub fn set_two_factor_required(&mut self, two_factor_required: bool) -> Result<()> {
+        self.two_factor_required = two_factor_required;
+        self.base.update();
+        Ok(())
+    }
+
     pub fn delete(&mut self) -> Result<()> {
         self.base.delete();
         Ok(())
     }
+
+    pub fn restore(&mut self) -> Result<()> {
+        if !self.base.is_deleted() {
+            return Err(Error::new("role", "not_deleted"));
+        }
+
+        self.base.restore();
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -7,8 +7,14 @@ use crate::domain::user::UserId;
 
 #[async_trait]
 pub trait RoleRepository: Sync + Send {
+    /// Deleted roles are skipped, except by the `*_deleted` methods.
     async fn find_all(&self) -> Result<Vec<Role>>;
     async fn find_by_id(&self, id: &RoleId) -> Result<Role>;
+    async fn find_all_deleted(&self) -> Result<Vec<Role>>;
+    async fn find_deleted_by_id(&self, id: &RoleId) -> Result<Role>;
+
+    /// Finds the roles with the given IDs. IDs not found are skipped.
+    async fn find_by_ids(&self, ids: &[RoleId]) -> Result<Vec<Role>>;
     async fn find_by_user_id(&self, user_id: &UserId) -> Result<Role>;
     async fn find_default(&self) -> Result<Role>;
 
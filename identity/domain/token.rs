@@ -1,9 +1,11 @@
 mod encoder;
 mod repository;
 mod service;
+mod session;
 pub use encoder::*;
 pub use repository::*;
 pub use service::*;
+pub use session::*;
 
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
@@ -11,6 +13,8 @@ use std::hash::{Hash, Hasher};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use common::cache::CacheNamespace;
+
 // TokenId
 #[derive(Default, Debug, Clone, Eq)]
 pub struct TokenId {
@@ -107,3 +111,7 @@ impl Data {
         self.data.get(&k.into())
     }
 }
+
+impl CacheNamespace for Data {
+    const NAMESPACE: &'static str = "tokens";
+}
@@ -1,8 +1,11 @@
+use chrono::{DateTime, Utc};
+
 use common::result::Result;
 
 use crate::domain::token::{Token, TokenId};
 
 pub trait TokenEncoder: Sync + Send {
-    fn encode(&self, token_id: &TokenId) -> Result<Token>;
+    /// Encodes a token that can't be decoded after `expires_at`.
+    fn encode(&self, token_id: &TokenId, expires_at: &DateTime<Utc>) -> Result<Token>;
     fn decode(&self, token: &Token) -> Result<TokenId>;
 }
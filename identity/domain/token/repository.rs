@@ -1,8 +1,25 @@
 use async_trait::async_trait;
 
 use common::cache::Cache;
+use common::result::Result;
 
-use crate::domain::token::{Data, TokenId};
+use crate::domain::token::{Data, Session, SessionId, TokenId};
+use crate::domain::user::UserId;
 
+/// TokenRepository caches the data of the access tokens, which expire soon, and stores the
+/// sessions they belong to.
 #[async_trait]
-pub trait TokenRepository: Cache<TokenId, Data> + Sync + Send {}
+pub trait TokenRepository: Cache<TokenId, Data> + Sync + Send {
+    async fn find_session_by_id(&self, id: &SessionId) -> Result<Session>;
+
+    /// Sessions of the user that have not expired, the last refreshed first.
+    async fn find_sessions_by_user_id(&self, user_id: &UserId) -> Result<Vec<Session>>;
+
+    /// Stores a new session.
+    async fn save_session(&self, session: &Session) -> Result<()>;
+
+    /// Replaces a refreshed session only if its refresh token hash is still `refresh_token_hash`,
+    /// so only one of two concurrent refreshes with the same token succeeds. The other fails.
+    async fn update_session(&self, session: &Session, refresh_token_hash: &str) -> Result<()>;
+    async fn delete_session(&self, id: &SessionId) -> Result<()>;
+}
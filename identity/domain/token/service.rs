@@ -1,14 +1,32 @@
 use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
 
 use common::error::Error;
 use common::result::Result;
 
-use crate::domain::token::{Data, Token, TokenEncoder, TokenId, TokenRepository};
+use crate::domain::token::{
+    Data, Device, Session, SessionId, Token, TokenEncoder, TokenRepository,
+};
+use crate::domain::user::UserId;
+
+/// Tokens given to the client when a session starts or is refreshed.
+#[derive(Debug, Clone)]
+pub struct SessionTokens {
+    pub session_id: SessionId,
+    pub access_token: Token,
+    pub refresh_token: Token,
+    pub expires_at: DateTime<Utc>,
+}
 
 pub struct TokenService {
     token_repo: Arc<dyn TokenRepository>,
 
     token_enc: Arc<dyn TokenEncoder>,
+
+    access_ttl: Duration,
+    session_ttl: Duration,
 }
 
 impl TokenService {
@@ -16,15 +34,36 @@ impl TokenService {
         TokenService {
             token_enc,
             token_repo,
+            access_ttl: Duration::from_secs(15 * 60),
+            session_ttl: Duration::from_secs(30 * 24 * 60 * 60),
         }
     }
 
-    pub async fn create(&self, data: Data) -> Result<Token> {
-        let token_id = TokenId::new();
-        let token = self.token_enc.encode(&token_id)?;
-        self.token_repo.set(token_id, data).await?;
+    /// Time an access token is valid after it's created. Expired tokens are removed from the
+    /// repository.
+    pub fn access_ttl(mut self, ttl: Duration) -> Self {
+        self.access_ttl = ttl;
+        self
+    }
 
-        Ok(token)
+    /// Time a session is kept after its tokens are refreshed.
+    pub fn session_ttl(mut self, ttl: Duration) -> Self {
+        self.session_ttl = ttl;
+        self
+    }
+
+    /// Starts a session with `data` in its access tokens.
+    pub async fn create(
+        &self,
+        user_id: &UserId,
+        data: Data,
+        device: Device,
+    ) -> Result<SessionTokens> {
+        let (session, refresh_token) =
+            Session::new(user_id.clone(), data, device, chrono_ttl(self.session_ttl)?);
+        self.token_repo.save_session(&session).await?;
+
+        self.issue(&session, refresh_token).await
     }
 
     pub async fn validate(&self, token: &Token) -> Result<Data> {
@@ -35,18 +74,118 @@ impl TokenService {
         Err(Error::new("token", "not_found"))
     }
 
+    /// Rotates the tokens of the session the refresh token belongs to. The previous access token
+    /// stops being valid. A refresh token that was already used revokes the session.
+    pub async fn refresh(&self, refresh_token: &Token) -> Result<SessionTokens> {
+        let session_id = Session::id_from_refresh_token(refresh_token)?;
+        let mut session = self
+            .token_repo
+            .find_session_by_id(&session_id)
+            .await
+            .map_err(|err| Error::new("refresh_token", "invalid").wrap(err))?;
+
+        let previous_access_token_id = session.access_token_id().clone();
+        let previous_refresh_token_hash = session.refresh_token_hash().to_owned();
+        let refresh_token = match session.refresh(refresh_token, chrono_ttl(self.session_ttl)?) {
+            Ok(refresh_token) => refresh_token,
+            Err(err) => {
+                if err.code() == "reused" {
+                    self.delete_session(&session).await?;
+                }
+                return Err(err);
+            }
+        };
+
+        self.token_repo
+            .update_session(&session, &previous_refresh_token_hash)
+            .await?;
+        self.token_repo.delete(&previous_access_token_id).await?;
+
+        self.issue(&session, refresh_token).await
+    }
+
+    /// Ends the session of the access token.
     pub async fn invalidate(&self, token: &Token) -> Result<()> {
         let token_id = self.token_enc.decode(token)?;
+
+        if let Some(session_id) = self
+            .token_repo
+            .get(&token_id)
+            .await
+            .and_then(|data| data.get("session_id").cloned())
+        {
+            self.token_repo
+                .delete_session(&SessionId::build(session_id))
+                .await?;
+        }
+
         self.token_repo.delete(&token_id).await?;
         Ok(())
     }
+
+    pub async fn sessions(&self, user_id: &UserId) -> Result<Vec<Session>> {
+        self.token_repo.find_sessions_by_user_id(user_id).await
+    }
+
+    /// Ends a session of the user from another device.
+    pub async fn revoke(&self, user_id: &UserId, session_id: &SessionId) -> Result<()> {
+        let session = self.token_repo.find_session_by_id(session_id).await?;
+        if session.user_id() != user_id {
+            return Err(Error::not_found("session"));
+        }
+
+        self.delete_session(&session).await
+    }
+
+    /// Ends every session of the user, like when its password is reset.
+    pub async fn revoke_all(&self, user_id: &UserId) -> Result<()> {
+        for session in self.token_repo.find_sessions_by_user_id(user_id).await? {
+            self.delete_session(&session).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn issue(&self, session: &Session, refresh_token: Token) -> Result<SessionTokens> {
+        let expires_at = Utc::now() + chrono_ttl(self.access_ttl)?;
+        let access_token = self
+            .token_enc
+            .encode(session.access_token_id(), &expires_at)?;
+
+        let mut data = session.data().clone();
+        data.add("session_id", session.id().id());
+        self.token_repo
+            .set_with_ttl(session.access_token_id().clone(), data, self.access_ttl)
+            .await?;
+
+        Ok(SessionTokens {
+            session_id: session.id().clone(),
+            access_token,
+            refresh_token,
+            expires_at,
+        })
+    }
+
+    async fn delete_session(&self, session: &Session) -> Result<()> {
+        self.token_repo.delete(session.access_token_id()).await?;
+        self.token_repo.delete_session(session.id()).await
+    }
+}
+
+fn chrono_ttl(ttl: Duration) -> Result<chrono::Duration> {
+    chrono::Duration::from_std(ttl).map_err(|err| Error::internal("token", "ttl").wrap_raw(err))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use crate::mocks;
+    use crate::infrastructure::persistence::inmem::InMemTokenRepository;
+    use crate::mocks::{self, FakeTokenEncoder};
+
+    fn user_id() -> UserId {
+        UserId::new("#user01").unwrap()
+    }
 
     #[tokio::test]
     async fn create_validate_invalidate() {
@@ -57,15 +196,88 @@ mod tests {
         data.add("user_id", "u123");
         data.add("user_username", "admin");
 
-        let token = serv.create(data).await.unwrap();
-        assert!(!token.value().is_empty());
+        let tokens = serv
+            .create(&user_id(), data, Device::default())
+            .await
+            .unwrap();
+        assert!(!tokens.access_token.value().is_empty());
+        assert!(!tokens.refresh_token.value().is_empty());
 
-        let data = serv.validate(&token).await.unwrap();
+        let data = serv.validate(&tokens.access_token).await.unwrap();
         assert_eq!(data.get("user_id"), Some(&"u123".to_owned()));
         assert_eq!(data.get("user_username"), Some(&"admin".to_owned()));
+        assert_eq!(data.get("session_id"), Some(&tokens.session_id.to_string()));
+        assert_eq!(serv.sessions(&user_id()).await.unwrap().len(), 1);
+
+        assert!(serv.invalidate(&tokens.access_token).await.is_ok());
+
+        assert!(serv.validate(&tokens.access_token).await.is_err());
+        assert!(serv.refresh(&tokens.refresh_token).await.is_err());
+        assert!(serv.sessions(&user_id()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn refresh_and_revoke() {
+        let c = mocks::container();
+        let serv = c.token_serv();
+
+        let tokens = serv
+            .create(&user_id(), Data::new(), Device::default())
+            .await
+            .unwrap();
+
+        let refreshed = serv.refresh(&tokens.refresh_token).await.unwrap();
+        assert_eq!(refreshed.session_id, tokens.session_id);
+        assert!(serv.validate(&tokens.access_token).await.is_err());
+        assert!(serv.validate(&refreshed.access_token).await.is_ok());
+
+        // The first refresh token was stolen and used again.
+        let err = serv.refresh(&tokens.refresh_token).await.err().unwrap();
+        assert_eq!(err.code(), "reused");
+        assert!(serv.validate(&refreshed.access_token).await.is_err());
+        assert!(serv.refresh(&refreshed.refresh_token).await.is_err());
+
+        let tokens = serv
+            .create(&user_id(), Data::new(), Device::default())
+            .await
+            .unwrap();
+        let other_user_id = UserId::new("#user02").unwrap();
+        assert!(serv
+            .revoke(&other_user_id, &tokens.session_id)
+            .await
+            .is_err());
+        assert!(serv.revoke(&user_id(), &tokens.session_id).await.is_ok());
+        assert!(serv.validate(&tokens.access_token).await.is_err());
+
+        let tokens = serv
+            .create(&user_id(), Data::new(), Device::default())
+            .await
+            .unwrap();
+        serv.create(&user_id(), Data::new(), Device::default())
+            .await
+            .unwrap();
+        assert!(serv.revoke_all(&user_id()).await.is_ok());
+        assert!(serv.validate(&tokens.access_token).await.is_err());
+        assert!(serv.refresh(&tokens.refresh_token).await.is_err());
+        assert!(serv.sessions(&user_id()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn expired_token() {
+        let serv = TokenService::new(
+            Arc::new(InMemTokenRepository::new()),
+            Arc::new(FakeTokenEncoder::new()),
+        )
+        .access_ttl(Duration::from_millis(50));
 
-        assert!(serv.invalidate(&token).await.is_ok());
+        let tokens = serv
+            .create(&user_id(), Data::new(), Device::default())
+            .await
+            .unwrap();
+        assert!(serv.validate(&tokens.access_token).await.is_ok());
 
-        assert!(serv.validate(&token).await.is_err());
+        tokio::time::delay_for(Duration::from_millis(100)).await;
+        assert!(serv.validate(&tokens.access_token).await.is_err());
+        assert!(serv.refresh(&tokens.refresh_token).await.is_ok());
     }
 }
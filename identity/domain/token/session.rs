@@ -0,0 +1,260 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use common::error::Error;
+use common::result::Result;
+
+use crate::domain::token::{Data, Token, TokenId};
+use crate::domain::user::UserId;
+
+pub type SessionId = TokenId;
+
+/// Device a session was started from, as reported by the client.
+#[derive(Default, Debug, Clone)]
+pub struct Device {
+    user_agent: Option<String>,
+    ip: Option<String>,
+}
+
+impl Device {
+    pub fn new(user_agent: Option<String>, ip: Option<String>) -> Self {
+        Device { user_agent, ip }
+    }
+
+    pub fn user_agent(&self) -> Option<&String> {
+        self.user_agent.as_ref()
+    }
+
+    pub fn ip(&self) -> Option<&String> {
+        self.ip.as_ref()
+    }
+
+    /// Client the requests of the device are limited for: its IP, or the same one for every
+    /// device without it, so they are not left unlimited.
+    pub fn client(&self) -> &str {
+        self.ip.as_deref().unwrap_or("unknown")
+    }
+}
+
+/// Session of a user in a device, started when they log in. Its access token is replaced each
+/// time the refresh token is used, and each refresh token can be used once: using a rotated one
+/// means it was stolen, so the session is revoked.
+#[derive(Debug, Clone)]
+pub struct Session {
+    id: SessionId,
+    user_id: UserId,
+    data: Data,
+    access_token_id: TokenId,
+    refresh_token_hash: String,
+    previous_refresh_token_hash: Option<String>,
+    device: Device,
+    created_at: DateTime<Utc>,
+    refreshed_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+}
+
+impl Session {
+    /// Creates the session and returns its refresh token, which is only stored hashed.
+    pub fn new(
+        user_id: UserId,
+        data: Data,
+        device: Device,
+        ttl: chrono::Duration,
+    ) -> (Self, Token) {
+        let id = SessionId::new();
+        let secret = generate_secret();
+        let now = Utc::now();
+
+        let session = Session {
+            id: id.clone(),
+            user_id,
+            data,
+            access_token_id: TokenId::new(),
+            refresh_token_hash: hash(&secret),
+            previous_refresh_token_hash: None,
+            device,
+            created_at: now,
+            refreshed_at: now,
+            expires_at: now + ttl,
+        };
+
+        (session, refresh_token(&id, &secret))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn build(
+        id: SessionId,
+        user_id: UserId,
+        data: Data,
+        access_token_id: TokenId,
+        refresh_token_hash: String,
+        previous_refresh_token_hash: Option<String>,
+        device: Device,
+        created_at: DateTime<Utc>,
+        refreshed_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Self {
+        Session {
+            id,
+            user_id,
+            data,
+            access_token_id,
+            refresh_token_hash,
+            previous_refresh_token_hash,
+            device,
+            created_at,
+            refreshed_at,
+            expires_at,
+        }
+    }
+
+    /// ID of the session a refresh token belongs to.
+    pub fn id_from_refresh_token(refresh_token: &Token) -> Result<SessionId> {
+        refresh_token
+            .value()
+            .split('.')
+            .next()
+            .filter(|id| !id.is_empty())
+            .map(SessionId::build)
+            .ok_or_else(|| Error::new("refresh_token", "invalid"))
+    }
+
+    pub fn id(&self) -> &SessionId {
+        &self.id
+    }
+
+    pub fn user_id(&self) -> &UserId {
+        &self.user_id
+    }
+
+    /// Data of the access tokens of the session.
+    pub fn data(&self) -> &Data {
+        &self.data
+    }
+
+    pub fn access_token_id(&self) -> &TokenId {
+        &self.access_token_id
+    }
+
+    pub fn refresh_token_hash(&self) -> &str {
+        &self.refresh_token_hash
+    }
+
+    pub fn previous_refresh_token_hash(&self) -> Option<&String> {
+        self.previous_refresh_token_hash.as_ref()
+    }
+
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn refreshed_at(&self) -> &DateTime<Utc> {
+        &self.refreshed_at
+    }
+
+    pub fn expires_at(&self) -> &DateTime<Utc> {
+        &self.expires_at
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+
+    /// Rotates the refresh token, extending the session for `ttl`. The access token has to be
+    /// replaced too, so the session gets a new access token ID. Returns the new refresh token.
+    pub fn refresh(&mut self, refresh_token: &Token, ttl: chrono::Duration) -> Result<Token> {
+        let err = || Error::new("refresh_token", "invalid");
+
+        let mut parts = refresh_token.value().splitn(2, '.');
+        if parts.next() != Some(self.id.id()) {
+            return Err(err());
+        }
+        let hashed = hash(parts.next().ok_or_else(err)?);
+
+        if self.previous_refresh_token_hash.as_ref() == Some(&hashed) {
+            return Err(Error::new("refresh_token", "reused"));
+        }
+
+        if hashed != self.refresh_token_hash || self.is_expired() {
+            return Err(err());
+        }
+
+        let secret = generate_secret();
+        let now = Utc::now();
+
+        self.previous_refresh_token_hash = Some(hashed);
+        self.refresh_token_hash = hash(&secret);
+        self.access_token_id = TokenId::new();
+        self.refreshed_at = now;
+        self.expires_at = now + ttl;
+
+        Ok(refresh_token(&self.id, &secret))
+    }
+}
+
+fn generate_secret() -> String {
+    format!(
+        "{}{}",
+        Uuid::new_v4().to_simple(),
+        Uuid::new_v4().to_simple()
+    )
+}
+
+fn hash(secret: &str) -> String {
+    format!("{:x}", Sha256::digest(secret.as_bytes()))
+}
+
+fn refresh_token(id: &SessionId, secret: &str) -> Token {
+    Token::new(format!("{}.{}", id.id(), secret))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotate_refresh_token() {
+        let (mut session, refresh_token) = Session::new(
+            UserId::new("#user01").unwrap(),
+            Data::new(),
+            Device::new(Some("Firefox".to_owned()), Some("10.0.0.1".to_owned())),
+            chrono::Duration::days(30),
+        );
+        assert!(!refresh_token.value().contains(session.refresh_token_hash()));
+        assert_eq!(
+            &Session::id_from_refresh_token(&refresh_token).unwrap(),
+            session.id()
+        );
+
+        let access_token_id = session.access_token_id().clone();
+        let new_refresh_token = session
+            .refresh(&refresh_token, chrono::Duration::days(30))
+            .unwrap();
+        assert_ne!(new_refresh_token, refresh_token);
+        assert_ne!(session.access_token_id(), &access_token_id);
+
+        let err = session
+            .refresh(&refresh_token, chrono::Duration::days(30))
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "reused");
+
+        let forged = Token::new(format!("{}.forged", session.id().id()));
+        assert!(session
+            .refresh(&forged, chrono::Duration::days(30))
+            .is_err());
+
+        let new_refresh_token = session
+            .refresh(&new_refresh_token, chrono::Duration::seconds(-1))
+            .unwrap();
+        assert!(session.is_expired());
+        assert!(session
+            .refresh(&new_refresh_token, chrono::Duration::days(30))
+            .is_err());
+    }
+}
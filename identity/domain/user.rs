@@ -1,48 +1,74 @@
+mod attempt_repository;
+mod attempts;
 mod authentication_service;
 mod authorization_service;
 mod biography;
 mod birthdate;
 mod email;
+mod email_change;
+mod email_change_sender;
 mod fullname;
 mod gender;
 mod identity;
 mod image;
+mod login_challenge;
 mod password;
 mod password_hasher;
+mod password_reset;
+mod password_reset_sender;
 mod person;
 mod provider;
 mod repository;
 mod service;
+mod throttling_service;
+mod two_factor;
 mod username;
 mod validation;
 pub use self::identity::*;
+pub use attempt_repository::*;
+pub use attempts::*;
 pub use authentication_service::*;
 pub use authorization_service::*;
 pub use biography::*;
 pub use birthdate::*;
 pub use email::*;
+pub use email_change::*;
+pub use email_change_sender::*;
 pub use fullname::*;
 pub use gender::*;
 pub use image::*;
+pub use login_challenge::*;
 pub use password::*;
 pub use password_hasher::*;
+pub use password_reset::*;
+pub use password_reset_sender::*;
 pub use person::*;
 pub use provider::*;
 pub use repository::*;
 pub use service::*;
+pub use throttling_service::*;
+pub use two_factor::*;
 pub use username::*;
 pub use validation::*;
 
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
 use common::error::Error;
 use common::model::{AggregateRoot, Events, StringId};
 use common::result::Result;
 use shared::event::UserEvent;
 
 use crate::domain::role::RoleId;
-use crate::domain::token::Token;
 
 pub type UserId = StringId;
 
+/// Domain of the emails given to anonymized users.
+pub const ANONYMIZED_EMAIL_DOMAIN: &str = "deleted.omics";
+
+/// Time a user has to wait to change the username again.
+pub const USERNAME_CHANGE_COOLDOWN_DAYS: i64 = 30;
+
 #[derive(Debug, Clone)]
 pub struct User {
     base: AggregateRoot<UserId>,
@@ -53,6 +79,11 @@ pub struct User {
     validation: Option<Validation>,
     payment_email: Option<Email>,
     flag: i64,
+    two_factor: Option<TwoFactor>,
+    locked_until: Option<DateTime<Utc>>,
+    password_reset: Option<PasswordReset>,
+    email_change: Option<EmailChange>,
+    username_changed_at: Option<DateTime<Utc>>,
 }
 
 impl User {
@@ -66,6 +97,11 @@ impl User {
             validation: Some(Validation::new()),
             payment_email: None,
             flag: 0,
+            two_factor: None,
+            locked_until: None,
+            password_reset: None,
+            email_change: None,
+            username_changed_at: None,
         };
 
         user.events.record_event(UserEvent::Registered {
@@ -79,6 +115,7 @@ impl User {
         Ok(user)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn build(
         base: AggregateRoot<UserId>,
         identity: Identity,
@@ -87,6 +124,11 @@ impl User {
         validation: Option<Validation>,
         payment_email: Option<Email>,
         flag: i64,
+        two_factor: Option<TwoFactor>,
+        locked_until: Option<DateTime<Utc>>,
+        password_reset: Option<PasswordReset>,
+        email_change: Option<EmailChange>,
+        username_changed_at: Option<DateTime<Utc>>,
     ) -> Self {
         User {
             base,
@@ -97,6 +139,11 @@ impl User {
             validation,
             payment_email,
             flag,
+            two_factor,
+            locked_until,
+            password_reset,
+            email_change,
+            username_changed_at,
         }
     }
 
@@ -108,6 +155,10 @@ impl User {
         &self.events
     }
 
+    pub fn events_mut(&mut self) -> &mut Events<UserEvent> {
+        &mut self.events
+    }
+
     pub fn identity(&self) -> &Identity {
         &self.identity
     }
@@ -140,6 +191,51 @@ impl User {
         self.flag
     }
 
+    /// Two-factor authentication of the user, which can be an enrollment not verified yet.
+    pub fn two_factor(&self) -> Option<&TwoFactor> {
+        self.two_factor.as_ref()
+    }
+
+    pub fn has_two_factor(&self) -> bool {
+        self.two_factor
+            .as_ref()
+            .map_or(false, |two_factor| two_factor.is_enabled())
+    }
+
+    /// Date until the user can't log in after failing too many times. It's kept after it passes,
+    /// until the user logs in again.
+    pub fn locked_until(&self) -> Option<&DateTime<Utc>> {
+        self.locked_until.as_ref()
+    }
+
+    pub fn password_reset(&self) -> Option<&PasswordReset> {
+        self.password_reset.as_ref()
+    }
+
+    /// Email the user asked to change to, until it's confirmed.
+    pub fn email_change(&self) -> Option<&EmailChange> {
+        self.email_change.as_ref()
+    }
+
+    pub fn username_changed_at(&self) -> Option<&DateTime<Utc>> {
+        self.username_changed_at.as_ref()
+    }
+
+    pub fn is_locked_out(&self) -> bool {
+        self.locked_until
+            .map_or(false, |locked_until| locked_until > Utc::now())
+    }
+
+    pub fn check_locked_out(&self) -> Result<()> {
+        match self.locked_until {
+            Some(locked_until) if locked_until > Utc::now() => {
+                Err(Error::new("user", "locked_out")
+                    .add_context("locked_until", locked_until.to_rfc3339().as_str()))
+            }
+            _ => Ok(()),
+        }
+    }
+
     pub fn set_password(&mut self, password: Password) -> Result<()> {
         self.identity.set_password(password)?;
         self.base.update();
@@ -220,7 +316,9 @@ impl User {
         Ok(())
     }
 
-    pub fn login(&mut self, token: &Token) -> Result<()> {
+    /// Records the login. The tokens of the session are not part of the event, which is stored
+    /// and streamed.
+    pub fn login(&mut self) -> Result<()> {
         if !self.is_validated() {
             return Err(Error::new("user", "not_validated"));
         }
@@ -229,23 +327,136 @@ impl User {
             return Err(Error::new("user", "not_active"));
         }
 
+        self.check_locked_out()?;
+
         self.events.record_event(UserEvent::LoggedIn {
             id: self.base().id().to_string(),
-            auth_token: token.to_string(),
         });
 
         Ok(())
     }
 
-    pub fn recover_password(&mut self, password: Password, temp_password: String) -> Result<()> {
-        self.identity.set_password(password)?;
+    /// Starts a password reset, replacing any previous one. Returns the token the user resets the
+    /// password with. Users that log in with an identity provider have no password to recover.
+    pub fn request_password_reset(&mut self) -> Result<String> {
+        if self.identity.password().is_none() {
+            return Err(Error::new("password", "unavailable"));
+        }
+
+        let (password_reset, token) = PasswordReset::new();
+        self.password_reset = Some(password_reset);
+        self.base.update();
+
         self.events
             .record_event(UserEvent::PasswordRecoveryRequested {
                 id: self.base().id().to_string(),
-                temp_password,
                 email: self.identity().email().to_string(),
             });
 
+        Ok(token)
+    }
+
+    /// Sets the password with the token of the password reset, which can't be used again. The
+    /// user is unlocked, because it proved to own the email.
+    pub fn reset_password(&mut self, token: &str, password: Password) -> Result<()> {
+        match &self.password_reset {
+            Some(password_reset) if password_reset.matches(token) => {
+                if password_reset.is_expired() {
+                    return Err(Error::new("password_reset", "expired"));
+                }
+            }
+            _ => return Err(Error::new("password_reset", "invalid")),
+        }
+
+        self.identity.set_password(password)?;
+        self.password_reset = None;
+        self.base.update();
+
+        if self.locked_until.is_some() {
+            self.unlock()?;
+        }
+
+        self.events.record_event(UserEvent::PasswordReset {
+            id: self.base().id().to_string(),
+            email: self.identity().email().to_string(),
+        });
+
+        Ok(())
+    }
+
+    /// Changes the username, which can be done once every `USERNAME_CHANGE_COOLDOWN_DAYS`. Its
+    /// availability is checked by `UserService`.
+    pub fn change_username(&mut self, username: Username) -> Result<()> {
+        if username.value() == self.identity.username().value() {
+            return Err(Error::new("username", "unchanged"));
+        }
+
+        if let Some(username_changed_at) = self.username_changed_at {
+            let available_at =
+                username_changed_at + chrono::Duration::days(USERNAME_CHANGE_COOLDOWN_DAYS);
+            if available_at > Utc::now() {
+                return Err(Error::new("username", "cooldown")
+                    .add_context("available_at", available_at.to_rfc3339().as_str()));
+            }
+        }
+
+        self.identity.set_username(username)?;
+        self.username_changed_at = Some(Utc::now());
+        self.base.update();
+
+        self.events.record_event(UserEvent::UsernameChanged {
+            id: self.base().id().to_string(),
+            username: self.identity().username().to_string(),
+        });
+
+        Ok(())
+    }
+
+    /// Starts the change of the email, replacing any previous one. Returns the code to send to
+    /// the new email, which the user confirms the change with. Its availability is checked by
+    /// `UserService`.
+    pub fn change_email(&mut self, email: Email) -> Result<String> {
+        if email.value() == self.identity.email().value() {
+            return Err(Error::new("email", "unchanged"));
+        }
+
+        let (email_change, code) = EmailChange::new(email);
+        self.email_change = Some(email_change);
+        self.base.update();
+
+        self.events.record_event(UserEvent::EmailChangeRequested {
+            id: self.base().id().to_string(),
+            email: self.email_change().unwrap().email().to_string(),
+        });
+
+        Ok(code)
+    }
+
+    pub fn confirm_email_change(&mut self, code: &str) -> Result<()> {
+        let email_change = match self.email_change.take() {
+            Some(email_change) => email_change,
+            None => return Err(Error::new("email_change", "not_requested")),
+        };
+
+        if !email_change.matches(code) {
+            self.email_change = Some(email_change);
+            return Err(Error::new("email_change", "invalid_code"));
+        }
+
+        if email_change.is_expired() {
+            return Err(Error::new("email_change", "expired"));
+        }
+
+        let old_email = self.identity.email().to_string();
+        self.identity.set_email(email_change.email().clone())?;
+        self.base.update();
+
+        self.events.record_event(UserEvent::EmailChanged {
+            id: self.base().id().to_string(),
+            email: self.identity().email().to_string(),
+            old_email,
+        });
+
         Ok(())
     }
 
@@ -266,6 +477,129 @@ impl User {
         self.base.update();
     }
 
+    /// Generates the secret the user adds to an authenticator app. Two-factor authentication is
+    /// enabled once a code is verified with `enable_two_factor`.
+    pub fn enroll_two_factor(&mut self) -> Result<&TwoFactor> {
+        if self.has_two_factor() {
+            return Err(Error::new("two_factor", "already_enabled"));
+        }
+
+        self.two_factor = Some(TwoFactor::new());
+        self.base.update();
+
+        Ok(self.two_factor.as_ref().unwrap())
+    }
+
+    /// Enables two-factor authentication with the first code of the authenticator app. Returns
+    /// the recovery codes.
+    pub fn enable_two_factor(&mut self, code: &str) -> Result<Vec<String>> {
+        let two_factor = match self.two_factor.as_mut() {
+            Some(two_factor) if two_factor.is_enabled() => {
+                return Err(Error::new("two_factor", "already_enabled"))
+            }
+            Some(two_factor) => two_factor,
+            None => return Err(Error::new("two_factor", "not_enrolled")),
+        };
+
+        if !two_factor.verify(code)? {
+            return Err(Error::new("two_factor", "invalid_code"));
+        }
+
+        two_factor.enable();
+        let recovery_codes = two_factor.generate_recovery_codes();
+        self.base.update();
+
+        self.events.record_event(UserEvent::TwoFactorEnabled {
+            id: self.base().id().to_string(),
+        });
+
+        Ok(recovery_codes)
+    }
+
+    /// Verifies a code of the authenticator app or a recovery code, which can't be used again.
+    pub fn verify_two_factor(&mut self, code: &str) -> Result<()> {
+        let two_factor = match self.two_factor.as_mut() {
+            Some(two_factor) if two_factor.is_enabled() => two_factor,
+            _ => return Err(Error::new("two_factor", "not_enabled")),
+        };
+
+        if two_factor.verify(code)? {
+            self.base.update();
+            return Ok(());
+        }
+
+        if two_factor.use_recovery_code(code) {
+            let remaining_recovery_codes = two_factor.recovery_codes().len();
+            self.base.update();
+
+            self.events.record_event(UserEvent::RecoveryCodeUsed {
+                id: self.base().id().to_string(),
+                remaining_recovery_codes,
+            });
+
+            return Ok(());
+        }
+
+        Err(Error::new("two_factor", "invalid_code"))
+    }
+
+    pub fn disable_two_factor(&mut self, code: &str) -> Result<()> {
+        self.verify_two_factor(code)?;
+
+        self.two_factor = None;
+        self.base.update();
+
+        self.events.record_event(UserEvent::TwoFactorDisabled {
+            id: self.base().id().to_string(),
+        });
+
+        Ok(())
+    }
+
+    /// Replaces the recovery codes, for users that used or lost them.
+    pub fn regenerate_recovery_codes(&mut self, code: &str) -> Result<Vec<String>> {
+        self.verify_two_factor(code)?;
+
+        let recovery_codes = self.two_factor.as_mut().unwrap().generate_recovery_codes();
+        self.base.update();
+
+        self.events.record_event(UserEvent::RecoveryCodesGenerated {
+            id: self.base().id().to_string(),
+        });
+
+        Ok(recovery_codes)
+    }
+
+    /// Locks the user out after failing to log in too many times.
+    pub fn lock_out(&mut self, until: DateTime<Utc>) -> Result<()> {
+        self.locked_until = Some(until);
+        self.base.update();
+
+        self.events.record_event(UserEvent::LockedOut {
+            id: self.base().id().to_string(),
+            email: self.identity().email().to_string(),
+            locked_until: until.to_rfc3339(),
+        });
+
+        Ok(())
+    }
+
+    /// Removes the lock, either because it ended or before it does.
+    pub fn unlock(&mut self) -> Result<()> {
+        if self.locked_until.take().is_none() {
+            return Err(Error::new("user", "not_locked_out"));
+        }
+
+        self.base.update();
+
+        self.events.record_event(UserEvent::Unlocked {
+            id: self.base().id().to_string(),
+            email: self.identity().email().to_string(),
+        });
+
+        Ok(())
+    }
+
     pub fn delete(&mut self) -> Result<()> {
         if !self.is_active() {
             return Err(Error::new("user", "not_active"));
@@ -279,6 +613,58 @@ impl User {
 
         Ok(())
     }
+
+    pub fn restore(&mut self) -> Result<()> {
+        if !self.base.is_deleted() {
+            return Err(Error::new("user", "not_deleted"));
+        }
+
+        if self.is_anonymized() {
+            return Err(Error::new("user", "anonymized"));
+        }
+
+        self.base.restore();
+
+        self.events.record_event(UserEvent::Restored {
+            id: self.base().id().to_string(),
+        });
+
+        Ok(())
+    }
+
+    pub fn is_anonymized(&self) -> bool {
+        self.identity
+            .email()
+            .value()
+            .ends_with(&format!("@{}", ANONYMIZED_EMAIL_DOMAIN))
+    }
+
+    /// Removes the personal data of a deleted user. The user is kept because other records, like
+    /// donations, reference it.
+    pub fn anonymize(&mut self) -> Result<()> {
+        if !self.base.is_deleted() {
+            return Err(Error::new("user", "not_deleted"));
+        }
+
+        let key = Uuid::new_v4().to_simple().to_string();
+        self.identity = Identity::new(
+            self.identity.provider().clone(),
+            Username::new(format!("deleted-{}", &key[..16]))?,
+            Email::new(format!("{}@{}", key, ANONYMIZED_EMAIL_DOMAIN))?,
+            // Not a hash of any password, so it can't be used to log in.
+            Some(Password::new(format!("{:X>64}", key))?),
+        )?;
+        self.person = None;
+        self.validation = None;
+        self.payment_email = None;
+        self.two_factor = None;
+        self.locked_until = None;
+        self.password_reset = None;
+        self.email_change = None;
+        self.base.update();
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -360,4 +746,310 @@ mod tests {
         assert!(user.delete().is_ok());
         assert!(user.delete().is_err());
     }
+
+    #[test]
+    fn restore_and_anonymize() {
+        let mut user = User::new(
+            UserId::new("user123").unwrap(),
+            Identity::new(
+                Provider::Local,
+                Username::new("user1").unwrap(),
+                Email::new("email@user.com").unwrap(),
+                Some(Password::new(&format!("{:X>50}", "2")).unwrap()),
+            )
+            .unwrap(),
+            RoleId::new("user").unwrap(),
+        )
+        .unwrap();
+        let code = user.validation().unwrap().clone();
+        user.validate(&code).unwrap();
+
+        assert!(user.restore().is_err());
+        assert!(user.anonymize().is_err());
+
+        user.delete().unwrap();
+        assert!(user.restore().is_ok());
+        assert!(user.is_active());
+        assert_eq!(
+            user.events().to_vec().unwrap().last().unwrap().code(),
+            "restored"
+        );
+
+        user.delete().unwrap();
+        user.anonymize().unwrap();
+        assert!(user.is_anonymized());
+        assert_ne!(user.identity().username().value(), "user1");
+        assert_ne!(user.identity().email().value(), "email@user.com");
+        assert!(user.person().is_none());
+        assert_eq!(user.restore().err().unwrap().code(), "anonymized");
+    }
+
+    #[test]
+    fn two_factor() {
+        let mut user = User::new(
+            UserId::new("user123").unwrap(),
+            Identity::new(
+                Provider::Local,
+                Username::new("user1").unwrap(),
+                Email::new("email@user.com").unwrap(),
+                Some(Password::new(&format!("{:X>50}", "2")).unwrap()),
+            )
+            .unwrap(),
+            RoleId::new("user").unwrap(),
+        )
+        .unwrap();
+        assert!(user.verify_two_factor("123456").is_err());
+        assert!(user.enable_two_factor("123456").is_err());
+
+        user.enroll_two_factor().unwrap();
+        assert!(!user.has_two_factor());
+        assert!(user.verify_two_factor("123456").is_err());
+
+        let now = Utc::now();
+        let code = user.two_factor().unwrap().code_at(&now).unwrap();
+        let recovery_codes = user.enable_two_factor(&code).unwrap();
+        assert!(user.has_two_factor());
+        assert!(user.enroll_two_factor().is_err());
+
+        // Codes are accepted once.
+        let err = user.verify_two_factor(&code).err().unwrap();
+        assert_eq!(err.code(), "invalid_code");
+
+        let next_code = user
+            .two_factor()
+            .unwrap()
+            .code_at(&(now + chrono::Duration::seconds(30)))
+            .unwrap();
+        assert!(user.verify_two_factor(&next_code).is_ok());
+
+        assert!(user.verify_two_factor(&recovery_codes[0]).is_ok());
+        assert!(user.verify_two_factor(&recovery_codes[0]).is_err());
+        assert_eq!(
+            user.events().to_vec().unwrap().last().unwrap().code(),
+            "recovery-code-used"
+        );
+
+        let new_recovery_codes = user.regenerate_recovery_codes(&recovery_codes[1]).unwrap();
+        assert!(user.verify_two_factor(&recovery_codes[2]).is_err());
+
+        user.disable_two_factor(&new_recovery_codes[0]).unwrap();
+        assert!(!user.has_two_factor());
+        assert!(user.two_factor().is_none());
+    }
+
+    #[test]
+    fn lock_out() {
+        let mut user = User::new(
+            UserId::new("user123").unwrap(),
+            Identity::new(
+                Provider::Local,
+                Username::new("user1").unwrap(),
+                Email::new("email@user.com").unwrap(),
+                Some(Password::new(&format!("{:X>50}", "2")).unwrap()),
+            )
+            .unwrap(),
+            RoleId::new("user").unwrap(),
+        )
+        .unwrap();
+        let code = user.validation().unwrap().clone();
+        user.validate(&code).unwrap();
+        assert!(user.unlock().is_err());
+
+        user.lock_out(Utc::now() + chrono::Duration::minutes(15))
+            .unwrap();
+        assert!(user.is_locked_out());
+        let err = user.login().err().unwrap();
+        assert_eq!(err.code(), "locked_out");
+        assert!(err.context().contains_key("locked_until"));
+
+        user.unlock().unwrap();
+        assert!(!user.is_locked_out());
+        assert!(user.login().is_ok());
+        assert!(user.unlock().is_err());
+
+        // Expired locks don't prevent the login.
+        user.lock_out(Utc::now() - chrono::Duration::seconds(1))
+            .unwrap();
+        assert!(!user.is_locked_out());
+        assert!(user.locked_until().is_some());
+        assert!(user.login().is_ok());
+    }
+
+    #[test]
+    fn reset_password() {
+        let mut user = User::new(
+            UserId::new("user123").unwrap(),
+            Identity::new(
+                Provider::Local,
+                Username::new("user1").unwrap(),
+                Email::new("email@user.com").unwrap(),
+                Some(Password::new(&format!("{:X>50}", "2")).unwrap()),
+            )
+            .unwrap(),
+            RoleId::new("user").unwrap(),
+        )
+        .unwrap();
+        let password = Password::new(&format!("{:X>50}", "3")).unwrap();
+
+        let err = user
+            .reset_password("token", password.clone())
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "invalid");
+
+        let old_token = user.request_password_reset().unwrap();
+        let token = user.request_password_reset().unwrap();
+        assert!(user.reset_password(&old_token, password.clone()).is_err());
+
+        user.lock_out(Utc::now() + chrono::Duration::minutes(15))
+            .unwrap();
+        user.reset_password(&token, password.clone()).unwrap();
+        assert_eq!(
+            user.identity().password().unwrap().value(),
+            password.value()
+        );
+        assert!(user.password_reset().is_none());
+        assert!(!user.is_locked_out());
+        assert_eq!(
+            user.events().to_vec().unwrap().last().unwrap().code(),
+            "password-reset"
+        );
+
+        // Tokens are used once.
+        assert!(user.reset_password(&token, password.clone()).is_err());
+
+        let token = user.request_password_reset().unwrap();
+        user.password_reset = Some(PasswordReset::build(
+            PasswordReset::hash(&token),
+            Utc::now() - chrono::Duration::seconds(1),
+        ));
+        let err = user.reset_password(&token, password).err().unwrap();
+        assert_eq!(err.code(), "expired");
+    }
+
+    #[test]
+    fn change_username() {
+        let mut user = User::new(
+            UserId::new("user123").unwrap(),
+            Identity::new(
+                Provider::Local,
+                Username::new("user1").unwrap(),
+                Email::new("email@user.com").unwrap(),
+                Some(Password::new(&format!("{:X>50}", "2")).unwrap()),
+            )
+            .unwrap(),
+            RoleId::new("user").unwrap(),
+        )
+        .unwrap();
+
+        let err = user
+            .change_username(Username::new("user1").unwrap())
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "unchanged");
+
+        user.change_username(Username::new("user2").unwrap())
+            .unwrap();
+        assert_eq!(user.identity().username().value(), "user2");
+        assert!(user.username_changed_at().is_some());
+        assert_eq!(
+            user.events().to_vec().unwrap().last().unwrap().code(),
+            "username-changed"
+        );
+
+        let err = user
+            .change_username(Username::new("user3").unwrap())
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "cooldown");
+        assert!(err.context().contains_key("available_at"));
+
+        user.username_changed_at =
+            Some(Utc::now() - chrono::Duration::days(USERNAME_CHANGE_COOLDOWN_DAYS + 1));
+        assert!(user
+            .change_username(Username::new("user3").unwrap())
+            .is_ok());
+    }
+
+    #[test]
+    fn change_email() {
+        let mut user = User::new(
+            UserId::new("user123").unwrap(),
+            Identity::new(
+                Provider::Local,
+                Username::new("user1").unwrap(),
+                Email::new("email@user.com").unwrap(),
+                Some(Password::new(&format!("{:X>50}", "2")).unwrap()),
+            )
+            .unwrap(),
+            RoleId::new("user").unwrap(),
+        )
+        .unwrap();
+
+        assert!(user.confirm_email_change("code").is_err());
+        assert!(user
+            .change_email(Email::new("email@user.com").unwrap())
+            .is_err());
+
+        let code = user
+            .change_email(Email::new("new@user.com").unwrap())
+            .unwrap();
+        assert_eq!(user.identity().email().value(), "email@user.com");
+        assert_ne!(user.email_change().unwrap().code_hash(), code);
+
+        // The code is only sent to the new email.
+        let event = user.events().to_vec().unwrap().pop().unwrap();
+        assert_eq!(event.code(), "email-change-requested");
+        assert!(!event.payload().to_string().contains(&code));
+        assert!(event.payload()["EmailChangeRequested"]
+            .get("code")
+            .is_none());
+
+        let err = user.confirm_email_change("invalid").err().unwrap();
+        assert_eq!(err.code(), "invalid_code");
+
+        user.confirm_email_change(&code).unwrap();
+        assert_eq!(user.identity().email().value(), "new@user.com");
+        assert!(user.email_change().is_none());
+        assert_eq!(
+            user.events().to_vec().unwrap().last().unwrap().code(),
+            "email-changed"
+        );
+
+        let code = user
+            .change_email(Email::new("other@user.com").unwrap())
+            .unwrap();
+        user.email_change = Some(EmailChange::build(
+            Email::new("other@user.com").unwrap(),
+            EmailChange::hash(&code),
+            Utc::now() - chrono::Duration::seconds(1),
+        ));
+        let err = user.confirm_email_change(&code).err().unwrap();
+        assert_eq!(err.code(), "expired");
+        assert!(user.email_change().is_none());
+        assert_eq!(user.identity().email().value(), "new@user.com");
+    }
+
+    #[test]
+    fn without_password() {
+        let mut user = User::new(
+            UserId::new("user123").unwrap(),
+            Identity::new(
+                Provider::Google,
+                Username::new("user1").unwrap(),
+                Email::new("email@user.com").unwrap(),
+                None,
+            )
+            .unwrap(),
+            RoleId::new("user").unwrap(),
+        )
+        .unwrap();
+        assert!(user.identity().password().is_none());
+
+        let password = Password::new(&format!("{:X>50}", "2")).unwrap();
+        let err = user.request_password_reset().err().unwrap();
+        assert_eq!(err.code(), "unavailable");
+        assert!(user.set_password(password).is_err());
+        assert!(user.identity().password().is_none());
+    }
 }
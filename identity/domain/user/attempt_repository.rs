@@ -0,0 +1,19 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use common::result::Result;
+
+use crate::domain::user::Attempts;
+
+#[async_trait]
+pub trait AttemptRepository: Sync + Send {
+    /// Attempts of the key, unless their window ended.
+    async fn find(&self, key: &str) -> Result<Option<Attempts>>;
+
+    /// Counts an attempt. The first attempt, or the first one after the window ended, starts a
+    /// new window.
+    async fn record(&self, key: &str, window: Duration) -> Result<Attempts>;
+
+    async fn delete(&self, key: &str) -> Result<()>;
+}
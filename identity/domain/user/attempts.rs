@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+/// Attempts counted for a key, like the failed logins of an account or the requests of an IP, in
+/// a window that starts with the first attempt.
+#[derive(Debug, Clone)]
+pub struct Attempts {
+    key: String,
+    count: u32,
+    last_attempt_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+}
+
+impl Attempts {
+    pub fn new<S: Into<String>>(key: S, window: Duration) -> Self {
+        let now = Utc::now();
+
+        Attempts {
+            key: key.into(),
+            count: 1,
+            last_attempt_at: now,
+            expires_at: now + chrono::Duration::seconds(window.as_secs() as i64),
+        }
+    }
+
+    pub fn build(
+        key: String,
+        count: u32,
+        last_attempt_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Self {
+        Attempts {
+            key,
+            count,
+            last_attempt_at,
+            expires_at,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    pub fn last_attempt_at(&self) -> &DateTime<Utc> {
+        &self.last_attempt_at
+    }
+
+    pub fn expires_at(&self) -> &DateTime<Utc> {
+        &self.expires_at
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+
+    /// Time left until the window ends.
+    pub fn ttl(&self) -> Duration {
+        (self.expires_at - Utc::now())
+            .to_std()
+            .unwrap_or_else(|_| Duration::from_secs(0))
+    }
+
+    /// Counts another attempt in the same window.
+    pub fn record(&mut self) {
+        self.count += 1;
+        self.last_attempt_at = Utc::now();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window() {
+        let mut attempts = Attempts::new("login:127.0.0.1", Duration::from_secs(60));
+        assert_eq!(attempts.count(), 1);
+        assert!(!attempts.is_expired());
+        assert!(attempts.ttl() <= Duration::from_secs(60));
+        assert!(attempts.ttl() > Duration::from_secs(55));
+
+        attempts.record();
+        assert_eq!(attempts.count(), 2);
+
+        let attempts = Attempts::new("login:127.0.0.1", Duration::from_secs(0));
+        assert!(attempts.is_expired());
+        assert_eq!(attempts.ttl(), Duration::from_secs(0));
+    }
+}
@@ -1,75 +1,265 @@
 use std::sync::Arc;
+use std::time::Duration;
 
+use common::cache::Cache;
 use common::error::Error;
 use common::result::Result;
 
-use crate::domain::token::{Data, Token, TokenService};
-use crate::domain::user::{Email, PasswordHasher, User, UserRepository, Username};
+use crate::domain::role::RoleRepository;
+use crate::domain::token::{Data, Device, SessionTokens, TokenService};
+use crate::domain::user::{
+    Email, LoginChallenge, PasswordHasher, ThrottlingService, User, UserRepository, Username,
+};
+
+/// Time a user has to enter the code of the authenticator app.
+const CHALLENGE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Result of logging in a user whose identity was verified.
+pub enum Authentication {
+    Session(User, SessionTokens),
+    /// The session starts when the challenge is completed with a code of the authenticator app.
+    /// Users whose role requires two-factor authentication, and don't have it enabled, have to
+    /// enroll first.
+    TwoFactorRequired(User, LoginChallenge),
+}
 
 pub struct AuthenticationService {
+    challenge_cache: Arc<dyn Cache<String, LoginChallenge>>,
+    role_repo: Arc<dyn RoleRepository>,
     user_repo: Arc<dyn UserRepository>,
 
     password_hasher: Arc<dyn PasswordHasher>,
 
+    throttling_serv: Arc<ThrottlingService>,
     token_serv: Arc<TokenService>,
 }
 
 /// AutenticationService authenticate any user, validated or not.
 impl AuthenticationService {
     pub fn new(
+        challenge_cache: Arc<dyn Cache<String, LoginChallenge>>,
+        role_repo: Arc<dyn RoleRepository>,
         user_repo: Arc<dyn UserRepository>,
         password_hasher: Arc<dyn PasswordHasher>,
+        throttling_serv: Arc<ThrottlingService>,
         token_serv: Arc<TokenService>,
     ) -> Self {
         AuthenticationService {
+            challenge_cache,
+            role_repo,
             user_repo,
             password_hasher,
+            throttling_serv,
             token_serv,
         }
     }
 
+    /// Failed logins are counted for the account and the IP, making the next ones wait and
+    /// locking the account after too many of them.
     pub async fn authenticate(
         &self,
         username_or_email: &str,
         password: &str,
-    ) -> Result<(User, Token)> {
+        device: Device,
+    ) -> Result<Authentication> {
         let err = Error::new("credentials", "invalid");
 
-        let mut user = match (
+        if let Some(ip) = device.ip() {
+            self.throttling_serv.check_ip(ip).await?;
+        }
+
+        let user = match (
             Username::new(username_or_email),
             Email::new(username_or_email),
         ) {
             (Ok(username), Err(_)) => self.user_repo.find_by_username(&username).await,
             (Err(_), Ok(email)) => self.user_repo.find_by_email(&email).await,
             _ => return Err(err),
-        }?;
+        };
+
+        let mut user = match user {
+            Ok(user) => user,
+            Err(e) => {
+                self.throttling_serv.login_failed(None, device.ip()).await?;
+                return Err(e);
+            }
+        };
+
+        user.check_locked_out()?;
+        self.throttling_serv.check_account(user.base().id()).await?;
+
+        let valid = match user.identity().password() {
+            Some(user_password) => self
+                .password_hasher
+                .compare(user_password.value(), password),
+            None => false,
+        };
+
+        if !valid {
+            self.count_failure(&mut user, &device).await?;
+            return Err(err);
+        }
+
+        self.throttling_serv
+            .login_succeeded(user.base().id())
+            .await?;
+
+        self.login(user, device).await
+    }
+
+    /// Logs in a user authenticated by a password or an identity provider. The session is started
+    /// after the second factor is verified when the user has two-factor authentication or their
+    /// role requires it.
+    pub async fn login(&self, mut user: User, device: Device) -> Result<Authentication> {
+        user.check_locked_out()?;
+
+        if user.locked_until().is_some() {
+            // The lock ended.
+            user.unlock()?;
+            self.user_repo.save(&mut user).await?;
+        }
+
+        let role = self.role_repo.find_by_user_id(user.base().id()).await?;
+
+        if !user.has_two_factor() && !role.requires_two_factor() {
+            let (user, tokens) = self.start_session(user, device).await?;
+            return Ok(Authentication::Session(user, tokens));
+        }
+
+        let challenge = LoginChallenge::new(user.base().id().clone(), &device, CHALLENGE_TTL);
+        self.challenge_cache
+            .set_with_ttl(challenge.id().to_owned(), challenge.clone(), CHALLENGE_TTL)
+            .await?;
+
+        Ok(Authentication::TwoFactorRequired(user, challenge))
+    }
+
+    /// Generates the two-factor secret of a user that has to enroll to complete the login.
+    pub async fn enroll_two_factor(&self, challenge_id: &str) -> Result<User> {
+        let challenge = self.challenge(challenge_id).await?;
+
+        let mut user = self.user_repo.find_by_id(challenge.user_id()).await?;
+        user.enroll_two_factor()?;
+
+        self.user_repo.save(&mut user).await?;
+
+        Ok(user)
+    }
+
+    /// Completes the login with a code of the authenticator app or a recovery code, starting the
+    /// session in the device the user logged in from. Users that enrolled during the login enable
+    /// two-factor authentication with the code, getting their recovery codes.
+    pub async fn complete_two_factor(
+        &self,
+        challenge_id: &str,
+        code: &str,
+    ) -> Result<(User, SessionTokens, Option<Vec<String>>)> {
+        let mut challenge = self.challenge(challenge_id).await?;
+        let mut user = self.user_repo.find_by_id(challenge.user_id()).await?;
+        user.check_locked_out()?;
 
-        let user_password = match user.identity().password() {
-            Some(password) => password.value(),
-            None => return Err(err),
+        let res = if user.has_two_factor() {
+            user.verify_two_factor(code).map(|_| None)
+        } else {
+            user.enable_two_factor(code).map(Some)
         };
 
-        if self.password_hasher.compare(user_password, password) {
-            let mut data = Data::new();
-            data.add("user_id", user.base().id().value());
+        let recovery_codes = match res {
+            Ok(recovery_codes) => recovery_codes,
+            Err(err) => {
+                // Codes are short, so each challenge can only be tried a few times, and they count
+                // as failed logins of the account.
+                let locked_out = self.count_failure(&mut user, &challenge.device()).await;
 
-            let token = match self.token_serv.create(data).await {
-                Ok(token) => token,
-                Err(e) => return Err(err.wrap(e)),
-            };
+                if locked_out.is_ok() && challenge.fail() {
+                    let ttl = challenge.ttl();
+                    self.challenge_cache
+                        .set_with_ttl(challenge.id().to_owned(), challenge, ttl)
+                        .await?;
+                } else {
+                    self.challenge_cache
+                        .delete(&challenge.id().to_owned())
+                        .await?;
+                }
 
-            user.login(&token)?;
+                locked_out?;
+                return Err(err);
+            }
+        };
+
+        self.challenge_cache
+            .delete(&challenge.id().to_owned())
+            .await?;
+        self.user_repo.save(&mut user).await?;
+
+        let (user, tokens) = self.start_session(user, challenge.device()).await?;
+
+        Ok((user, tokens, recovery_codes))
+    }
 
-            return Ok((user, token));
+    /// Counts a failed login, locking the user out when it failed too many times.
+    async fn count_failure(&self, user: &mut User, device: &Device) -> Result<()> {
+        if let Some(locked_until) = self
+            .throttling_serv
+            .login_failed(Some(user.base().id()), device.ip())
+            .await?
+        {
+            user.lock_out(locked_until)?;
+            self.user_repo.save(user).await?;
+
+            return user.check_locked_out();
         }
-        Err(err)
+
+        Ok(())
+    }
+
+    async fn start_session(&self, mut user: User, device: Device) -> Result<(User, SessionTokens)> {
+        let mut data = Data::new();
+        data.add("user_id", user.base().id().value());
+
+        let tokens = self
+            .token_serv
+            .create(user.base().id(), data, device)
+            .await?;
+
+        if let Err(e) = user.login() {
+            self.token_serv.invalidate(&tokens.access_token).await?;
+            return Err(e);
+        }
+
+        Ok((user, tokens))
+    }
+
+    async fn challenge(&self, challenge_id: &str) -> Result<LoginChallenge> {
+        self.challenge_cache
+            .get(&challenge_id.to_owned())
+            .await
+            .ok_or_else(|| Error::new("login_challenge", "invalid"))
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    use chrono::Utc;
+
     use crate::mocks;
 
+    fn session(authentication: Authentication) -> SessionTokens {
+        match authentication {
+            Authentication::Session(_, tokens) => tokens,
+            Authentication::TwoFactorRequired(..) => panic!("two-factor authentication required"),
+        }
+    }
+
+    fn challenge(authentication: Authentication) -> LoginChallenge {
+        match authentication {
+            Authentication::Session(..) => panic!("session started"),
+            Authentication::TwoFactorRequired(_, challenge) => challenge,
+        }
+    }
+
     #[tokio::test]
     async fn authenticate() {
         let c = mocks::container();
@@ -87,35 +277,259 @@ mod tests {
         );
         c.user_repo().save(&mut user).await.unwrap();
 
-        let (_, token) = serv
-            .authenticate(user.identity().username().value(), "P@asswd!")
+        let tokens = serv
+            .authenticate(
+                user.identity().username().value(),
+                "P@asswd!",
+                Device::default(),
+            )
             .await
+            .map(session)
             .unwrap();
-        assert!(!token.value().is_empty());
+        assert!(!tokens.access_token.value().is_empty());
+        assert_eq!(
+            c.token_serv()
+                .sessions(user.base().id())
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
 
-        let (_, token) = serv
-            .authenticate(user.identity().email().value(), "P@asswd!")
+        let tokens = serv
+            .authenticate(
+                user.identity().email().value(),
+                "P@asswd!",
+                Device::default(),
+            )
             .await
+            .map(session)
             .unwrap();
-        assert!(!token.value().is_empty());
+        assert!(!tokens.access_token.value().is_empty());
 
-        assert!(serv.authenticate("user2", "user123").await.is_err());
-        assert!(serv.authenticate("user1", "user124").await.is_err());
         assert!(serv
-            .authenticate("user@email.com.ar", "user123")
+            .authenticate("user2", "user123", Device::default())
+            .await
+            .is_err());
+        assert!(serv
+            .authenticate("user1", "user124", Device::default())
+            .await
+            .is_err());
+        assert!(serv
+            .authenticate("user@email.com.ar", "user123", Device::default())
             .await
             .is_err());
         assert!(serv
-            .authenticate("user@email.com", "user124")
+            .authenticate("user@email.com", "user124", Device::default())
             .await
             .is_err());
         assert!(serv
-            .authenticate(user.identity().username().value(), "invalid")
+            .authenticate(
+                user.identity().username().value(),
+                "invalid",
+                Device::default()
+            )
             .await
             .is_err());
         assert!(serv
-            .authenticate(user.identity().email().value(), "invalid")
+            .authenticate(
+                user.identity().email().value(),
+                "invalid",
+                Device::default()
+            )
             .await
             .is_err());
     }
+
+    #[tokio::test]
+    async fn lockout() {
+        let c = mocks::container();
+        let serv = c.authentication_serv();
+
+        let mut user = mocks::user(
+            "user-1",
+            "username",
+            "user@omics.com",
+            "P@asswd!",
+            true,
+            None,
+            None,
+            "user",
+        );
+        c.user_repo().save(&mut user).await.unwrap();
+
+        for _ in 0..3 {
+            let err = serv
+                .authenticate("username", "invalid", Device::default())
+                .await
+                .err()
+                .unwrap();
+            assert_eq!(err.code(), "invalid");
+        }
+
+        // Even the right password has to wait.
+        let err = serv
+            .authenticate("username", "P@asswd!", Device::default())
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "throttled");
+
+        for _ in 3..9 {
+            c.throttling_serv()
+                .login_failed(Some(user.base().id()), None)
+                .await
+                .unwrap();
+        }
+        let err = serv
+            .count_failure(&mut user, &Device::default())
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "locked_out");
+
+        let err = serv
+            .authenticate("username", "P@asswd!", Device::default())
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "locked_out");
+
+        // Users are unlocked when they log in after the lock ends.
+        let mut user = c.user_repo().find_by_id(user.base().id()).await.unwrap();
+        assert!(user.is_locked_out());
+        user.lock_out(Utc::now() - chrono::Duration::seconds(1))
+            .unwrap();
+        c.user_repo().save(&mut user).await.unwrap();
+
+        assert!(serv
+            .authenticate("username", "P@asswd!", Device::default())
+            .await
+            .is_ok());
+        assert!(c
+            .user_repo()
+            .find_by_id(user.base().id())
+            .await
+            .unwrap()
+            .locked_until()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn two_factor() {
+        let c = mocks::container();
+        let serv = c.authentication_serv();
+
+        let mut user = mocks::user(
+            "user-1",
+            "username",
+            "user@omics.com",
+            "P@asswd!",
+            true,
+            None,
+            None,
+            "user",
+        );
+        user.enroll_two_factor().unwrap();
+        let code = user.two_factor().unwrap().code_at(&Utc::now()).unwrap();
+        let recovery_codes = user.enable_two_factor(&code).unwrap();
+        c.user_repo().save(&mut user).await.unwrap();
+
+        let challenge = serv
+            .authenticate("username", "P@asswd!", Device::default())
+            .await
+            .map(challenge)
+            .unwrap();
+        assert!(c
+            .token_serv()
+            .sessions(user.base().id())
+            .await
+            .unwrap()
+            .is_empty());
+
+        // The code was already used to enable two-factor authentication.
+        assert!(serv
+            .complete_two_factor(challenge.id(), &code)
+            .await
+            .is_err());
+
+        let (_, tokens, new_recovery_codes) = serv
+            .complete_two_factor(challenge.id(), &recovery_codes[0])
+            .await
+            .unwrap();
+        assert!(!tokens.access_token.value().is_empty());
+        assert!(new_recovery_codes.is_none());
+
+        // Each challenge is completed once.
+        assert!(serv
+            .complete_two_factor(challenge.id(), &recovery_codes[1])
+            .await
+            .is_err());
+
+        // Challenges are discarded after a few invalid codes.
+        let challenge = serv
+            .authenticate("username", "P@asswd!", Device::default())
+            .await
+            .map(challenge)
+            .unwrap();
+        for _ in 0..5 {
+            assert!(serv
+                .complete_two_factor(challenge.id(), "000000")
+                .await
+                .is_err());
+        }
+        let err = serv
+            .complete_two_factor(challenge.id(), &recovery_codes[1])
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "invalid");
+    }
+
+    #[tokio::test]
+    async fn two_factor_required_by_role() {
+        let c = mocks::container();
+        let serv = c.authentication_serv();
+
+        let mut role = mocks::role("User");
+        role.set_two_factor_required(true).unwrap();
+        c.role_repo().save(&mut role).await.unwrap();
+
+        let mut user = mocks::user(
+            "user-1",
+            "username",
+            "user@omics.com",
+            "P@asswd!",
+            true,
+            None,
+            None,
+            "user",
+        );
+        c.user_repo().save(&mut user).await.unwrap();
+
+        let challenge = serv
+            .authenticate("username", "P@asswd!", Device::default())
+            .await
+            .map(challenge)
+            .unwrap();
+        assert!(serv
+            .complete_two_factor(challenge.id(), "123456")
+            .await
+            .is_err());
+
+        let user = serv.enroll_two_factor(challenge.id()).await.unwrap();
+        let code = user.two_factor().unwrap().code_at(&Utc::now()).unwrap();
+        let (user, _, recovery_codes) = serv
+            .complete_two_factor(challenge.id(), &code)
+            .await
+            .unwrap();
+        assert!(user.has_two_factor());
+        assert_eq!(recovery_codes.unwrap().len(), 10);
+        assert!(c
+            .user_repo()
+            .find_by_id(user.base().id())
+            .await
+            .unwrap()
+            .has_two_factor());
+    }
 }
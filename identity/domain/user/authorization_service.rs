@@ -28,7 +28,7 @@ impl AuthorizationService {
 mod tests {
     use super::*;
 
-    use crate::domain::token::Data;
+    use crate::domain::token::{Data, Device};
     use crate::mocks;
 
     #[tokio::test]
@@ -49,11 +49,15 @@ mod tests {
 
         let mut data = Data::new();
         data.add("user_id", user.base().id().value());
-        let token = c.token_serv().create(data).await.unwrap();
+        let tokens = c
+            .token_serv()
+            .create(user.base().id(), data, Device::default())
+            .await
+            .unwrap();
 
         let serv = c.authorization_serv();
 
-        let user_id = serv.authorize(&token).await.unwrap();
+        let user_id = serv.authorize(&tokens.access_token).await.unwrap();
         assert_eq!(user_id, user.base().id().to_string());
 
         assert!(serv.authorize(&Token::new("invalid")).await.is_err());
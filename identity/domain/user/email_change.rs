@@ -0,0 +1,87 @@
+use chrono::{DateTime, Duration, Utc};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::domain::user::Email;
+
+/// Time the user has to confirm the new email.
+const TTL_HOURS: i64 = 24;
+
+/// Email a user asked to change to. It's not used until the user confirms it with the code sent
+/// to it, so nobody can take an email they don't own. The code is only sent to the new email, so
+/// just its hash is stored.
+#[derive(Debug, Clone)]
+pub struct EmailChange {
+    email: Email,
+    code_hash: String,
+    expires_at: DateTime<Utc>,
+}
+
+impl EmailChange {
+    /// Creates the change and returns the code the user confirms it with.
+    pub fn new(email: Email) -> (Self, String) {
+        let code = Uuid::new_v4().to_simple().to_string();
+
+        let email_change = EmailChange {
+            email,
+            code_hash: Self::hash(&code),
+            expires_at: Utc::now() + Duration::hours(TTL_HOURS),
+        };
+
+        (email_change, code)
+    }
+
+    pub fn build<S: Into<String>>(email: Email, code_hash: S, expires_at: DateTime<Utc>) -> Self {
+        EmailChange {
+            email,
+            code_hash: code_hash.into(),
+            expires_at,
+        }
+    }
+
+    pub fn hash(code: &str) -> String {
+        format!("{:x}", Sha256::digest(code.as_bytes()))
+    }
+
+    pub fn email(&self) -> &Email {
+        &self.email
+    }
+
+    pub fn code_hash(&self) -> &str {
+        &self.code_hash
+    }
+
+    pub fn expires_at(&self) -> &DateTime<Utc> {
+        &self.expires_at
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+
+    pub fn matches(&self, code: &str) -> bool {
+        self.code_hash == Self::hash(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashed_code() {
+        let (email_change, code) = EmailChange::new(Email::new("new@user.com").unwrap());
+        assert_ne!(email_change.code_hash(), code);
+        assert_eq!(email_change.code_hash(), EmailChange::hash(&code));
+        assert!(email_change.matches(&code));
+        assert!(!email_change.matches("invalid"));
+        assert!(!email_change.is_expired());
+
+        let email_change = EmailChange::build(
+            email_change.email().clone(),
+            email_change.code_hash(),
+            Utc::now() - Duration::seconds(1),
+        );
+        assert!(email_change.is_expired());
+    }
+}
@@ -0,0 +1,12 @@
+use async_trait::async_trait;
+
+use common::result::Result;
+
+use crate::domain::user::User;
+
+/// EmailChangeSender sends the code of an email change to the new email, the only place where the
+/// code can be read.
+#[async_trait]
+pub trait EmailChangeSender: Sync + Send {
+    async fn send(&self, user: &User, code: &str) -> Result<()>;
+}
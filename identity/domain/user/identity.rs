@@ -50,6 +50,16 @@ impl Identity {
         self.password.as_ref()
     }
 
+    pub fn set_username(&mut self, username: Username) -> Result<()> {
+        self.username = username;
+        Ok(())
+    }
+
+    pub fn set_email(&mut self, email: Email) -> Result<()> {
+        self.email = email;
+        Ok(())
+    }
+
     pub fn set_password(&mut self, password: Password) -> Result<()> {
         self.password = match self.provider {
             Provider::Local => Some(password),
@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::cache::CacheNamespace;
+
+use crate::domain::token::Device;
+use crate::domain::user::UserId;
+
+/// Codes that can be tried for a challenge before it's discarded.
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Second step of the login of a user with two-factor authentication. It's created when the
+/// password is verified and completed with a code of the authenticator app, starting the session
+/// in the device the user logged in from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginChallenge {
+    id: String,
+    user_id: UserId,
+    user_agent: Option<String>,
+    ip: Option<String>,
+    attempts: u32,
+    expires_at: i64,
+}
+
+impl LoginChallenge {
+    pub fn new(user_id: UserId, device: &Device, ttl: Duration) -> Self {
+        LoginChallenge {
+            id: Uuid::new_v4().to_simple().to_string(),
+            user_id,
+            user_agent: device.user_agent().cloned(),
+            ip: device.ip().cloned(),
+            attempts: 0,
+            expires_at: Utc::now().timestamp() + ttl.as_secs() as i64,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn user_id(&self) -> &UserId {
+        &self.user_id
+    }
+
+    pub fn device(&self) -> Device {
+        Device::new(self.user_agent.clone(), self.ip.clone())
+    }
+
+    /// Time left to complete the challenge.
+    pub fn ttl(&self) -> Duration {
+        Duration::from_secs((self.expires_at - Utc::now().timestamp()).max(0) as u64)
+    }
+
+    /// Records an invalid code. Returns whether more codes can be tried.
+    pub fn fail(&mut self) -> bool {
+        self.attempts += 1;
+        self.attempts < MAX_ATTEMPTS && self.ttl().as_secs() > 0
+    }
+}
+
+impl CacheNamespace for LoginChallenge {
+    const NAMESPACE: &'static str = "login_challenges";
+}
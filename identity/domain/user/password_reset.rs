@@ -0,0 +1,82 @@
+use chrono::{DateTime, Duration, Utc};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Time the user has to reset the password after requesting it.
+const TTL_HOURS: i64 = 1;
+
+/// Request of a user to reset the password. The token is only sent to the user, so just its hash
+/// is stored, and it can be used once.
+#[derive(Debug, Clone)]
+pub struct PasswordReset {
+    token_hash: String,
+    expires_at: DateTime<Utc>,
+}
+
+impl PasswordReset {
+    /// Creates the request and returns the token the user resets the password with.
+    pub fn new() -> (Self, String) {
+        let token = format!(
+            "{}{}",
+            Uuid::new_v4().to_simple(),
+            Uuid::new_v4().to_simple()
+        );
+
+        let password_reset = PasswordReset {
+            token_hash: Self::hash(&token),
+            expires_at: Utc::now() + Duration::hours(TTL_HOURS),
+        };
+
+        (password_reset, token)
+    }
+
+    pub fn build<S: Into<String>>(token_hash: S, expires_at: DateTime<Utc>) -> Self {
+        PasswordReset {
+            token_hash: token_hash.into(),
+            expires_at,
+        }
+    }
+
+    /// Hash of a token, to find the request it belongs to.
+    pub fn hash(token: &str) -> String {
+        format!("{:x}", Sha256::digest(token.as_bytes()))
+    }
+
+    pub fn token_hash(&self) -> &str {
+        &self.token_hash
+    }
+
+    pub fn expires_at(&self) -> &DateTime<Utc> {
+        &self.expires_at
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+
+    pub fn matches(&self, token: &str) -> bool {
+        self.token_hash == Self::hash(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashed_token() {
+        let (password_reset, token) = PasswordReset::new();
+        assert_eq!(token.len(), 64);
+        assert_ne!(password_reset.token_hash(), token);
+        assert_eq!(password_reset.token_hash(), PasswordReset::hash(&token));
+        assert!(password_reset.matches(&token));
+        assert!(!password_reset.matches("invalid"));
+        assert!(!password_reset.is_expired());
+
+        let password_reset = PasswordReset::build(
+            password_reset.token_hash(),
+            Utc::now() - Duration::seconds(1),
+        );
+        assert!(password_reset.is_expired());
+    }
+}
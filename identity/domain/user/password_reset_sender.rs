@@ -0,0 +1,12 @@
+use async_trait::async_trait;
+
+use common::result::Result;
+
+use crate::domain::user::User;
+
+/// PasswordResetSender sends the token of a password reset to the user. The token is a secret, so
+/// it's sent directly instead of through an event, which would store it.
+#[async_trait]
+pub trait PasswordResetSender: Sync + Send {
+    async fn send(&self, user: &User, token: &str) -> Result<()>;
+}
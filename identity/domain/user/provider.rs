@@ -1,9 +1,12 @@
 use std::str::FromStr;
 
+use serde::{Deserialize, Serialize};
+
 use common::error::Error;
 use common::result::Result;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Provider {
     Local,
     Google,
@@ -1,37 +1,93 @@
 use std::str::FromStr;
 
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use common::error::Error;
 use common::model::Pagination;
 use common::result::Result;
+use common::sql::{Criteria, Direction, Query, Record, Value};
 
 use crate::domain::role::RoleId;
 use crate::domain::user::{Email, User, UserId, Username};
 
+/// Fields of a user that can be used in a search `Query`.
+#[derive(Debug, Clone, Copy)]
+pub enum UserField {
+    Id,
+    Username,
+    Name,
+    Lastname,
+    /// Name and lastname separated by a space.
+    Fullname,
+    RoleId,
+    CreatedAt,
+    /// Only set in deleted users.
+    DeletedAt,
+}
+
+impl Record<UserField> for User {
+    fn values(&self, field: &UserField) -> Vec<Value> {
+        let fullname = self.person().map(|person| person.fullname());
+
+        match field {
+            UserField::Id => vec![self.base().id().into()],
+            UserField::Username => vec![self.identity().username().value().into()],
+            UserField::Name => fullname
+                .map(|fullname| fullname.name().into())
+                .into_iter()
+                .collect(),
+            UserField::Lastname => fullname
+                .map(|fullname| fullname.lastname().into())
+                .into_iter()
+                .collect(),
+            UserField::Fullname => vec![format!(
+                "{} {}",
+                fullname.map_or("", |fullname| fullname.name()),
+                fullname.map_or("", |fullname| fullname.lastname()),
+            )
+            .into()],
+            UserField::RoleId => vec![self.role_id().into()],
+            UserField::CreatedAt => vec![self.base().created_at().into()],
+            UserField::DeletedAt => self
+                .base()
+                .deleted_at()
+                .map(Value::from)
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
 #[async_trait]
 pub trait UserRepository: Sync + Send {
     async fn next_id(&self) -> Result<UserId> {
         UserId::new(Uuid::new_v4().to_string())
     }
 
+    /// Deleted users are skipped, except by `find_by_username`, `find_by_email` and the
+    /// `*_deleted` methods, because their username and email are still taken.
     async fn find_all(&self) -> Result<Vec<User>>;
     async fn find_by_id(&self, id: &UserId) -> Result<User>;
+    async fn find_deleted_by_id(&self, id: &UserId) -> Result<User>;
+
+    /// Finds the users with the given IDs in a single search. IDs not found are skipped.
+    async fn find_by_ids(&self, ids: &[UserId]) -> Result<Vec<User>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let query = Query::new().filter(Criteria::is_in(UserField::Id, ids));
+        Ok(self.search(&query).await?.into_items())
+    }
     async fn find_by_username(&self, username: &Username) -> Result<User>;
     async fn find_by_email(&self, email: &Email) -> Result<User>;
+
+    /// Finds the active user with a password reset with the given token hash.
+    async fn find_by_password_reset(&self, token_hash: &str) -> Result<User>;
     async fn find_by_role_id(&self, role_id: &RoleId) -> Result<Vec<User>>;
-    async fn search(
-        &self,
-        name: Option<&String>,
-        role_id: Option<&RoleId>,
-        from: Option<&DateTime<Utc>>,
-        to: Option<&DateTime<Utc>>,
-        offset: Option<usize>,
-        limit: Option<usize>,
-        order_by: Option<&UserOrderBy>,
-    ) -> Result<Pagination<User>>;
+    async fn search(&self, query: &Query<UserField>) -> Result<Pagination<User>>;
+    async fn search_deleted(&self, query: &Query<UserField>) -> Result<Pagination<User>>;
 
     async fn save(&self, user: &mut User) -> Result<()>;
 
@@ -43,6 +99,15 @@ pub enum UserOrderBy {
     Newest,
 }
 
+impl UserOrderBy {
+    pub fn sort_key(&self) -> (UserField, Direction) {
+        match self {
+            UserOrderBy::Oldest => (UserField::CreatedAt, Direction::Asc),
+            UserOrderBy::Newest => (UserField::CreatedAt, Direction::Desc),
+        }
+    }
+}
+
 impl FromStr for UserOrderBy {
     type Err = Error;
 
@@ -1,5 +1,7 @@
 use std::sync::Arc;
 
+use uuid::Uuid;
+
 use common::error::Error;
 use common::result::Result;
 
@@ -48,6 +50,79 @@ impl UserService {
         Ok(true)
     }
 
+    /// Generates an available username from the email of a user that registered without
+    /// choosing one. A random suffix is added if it's taken.
+    pub async fn generate_username(&self, email: &Email) -> Result<Username> {
+        let name: String = email
+            .value()
+            .split('@')
+            .next()
+            .unwrap_or("")
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_' || *c == '.')
+            .take(16)
+            .collect();
+        let name = name.trim_matches(|c: char| !c.is_ascii_alphanumeric());
+        let name = if name.len() < 4 {
+            format!("user{}", name)
+        } else {
+            name.to_owned()
+        };
+
+        let mut username = Username::new(&name)?;
+        for _ in 0..5 {
+            if self.user_repo.find_by_username(&username).await.is_err() {
+                return Ok(username);
+            }
+
+            let suffix = Uuid::new_v4().to_simple().to_string();
+            username = Username::new(format!("{}-{}", name, &suffix[..6]))?;
+        }
+
+        Err(Error::new("username", "not_available"))
+    }
+
+    /// Changes the username, unless another user, even a deleted one, has it.
+    pub async fn change_username(&self, user: &mut User, username: &str) -> Result<()> {
+        let username = Username::new(username)?;
+        self.check_username_taken(user, &username).await?;
+
+        user.change_username(username)
+    }
+
+    /// Sends a code to the new email to confirm it, unless another user has it.
+    pub async fn change_email(&self, user: &mut User, email: &str) -> Result<String> {
+        let email = Email::new(email)?;
+        self.check_email_taken(user, &email).await?;
+
+        user.change_email(email)
+    }
+
+    /// Changes the email, which could be taken by another user since the change was requested.
+    pub async fn confirm_email_change(&self, user: &mut User, code: &str) -> Result<()> {
+        if let Some(email_change) = user.email_change() {
+            self.check_email_taken(user, email_change.email()).await?;
+        }
+
+        user.confirm_email_change(code)
+    }
+
+    async fn check_username_taken(&self, user: &User, username: &Username) -> Result<()> {
+        match self.user_repo.find_by_username(username).await {
+            Ok(other) if other.base().id() != user.base().id() => {
+                Err(Error::new("username", "taken"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    async fn check_email_taken(&self, user: &User, email: &Email) -> Result<()> {
+        match self.user_repo.find_by_email(email).await {
+            Ok(other) if other.base().id() != user.base().id() => Err(Error::new("email", "taken")),
+            _ => Ok(()),
+        }
+    }
+
     pub async fn change_password(
         &self,
         user: &mut User,
@@ -87,6 +162,8 @@ impl UserService {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     use crate::mocks;
 
     #[tokio::test]
@@ -159,6 +236,111 @@ mod tests {
             .is_ok());
     }
 
+    #[tokio::test]
+    async fn change_username_and_email() {
+        let c = mocks::container();
+        let serv = c.user_serv();
+
+        let mut user = mocks::user(
+            "user-1",
+            "username",
+            "user@omics.com",
+            "P@asswd!",
+            true,
+            None,
+            None,
+            "user",
+        );
+        c.user_repo().save(&mut user).await.unwrap();
+        let mut other = mocks::user(
+            "user-2",
+            "other",
+            "other@omics.com",
+            "P@asswd!",
+            true,
+            None,
+            None,
+            "user",
+        );
+        c.user_repo().save(&mut other).await.unwrap();
+
+        let err = serv
+            .change_username(&mut user, "other")
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "taken");
+        let err = serv
+            .change_username(&mut user, "username")
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "unchanged");
+        assert!(serv
+            .change_username(&mut user, "new-username")
+            .await
+            .is_ok());
+
+        let err = serv
+            .change_email(&mut user, "other@omics.com")
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "taken");
+        let code = serv.change_email(&mut user, "new@omics.com").await.unwrap();
+
+        // The email was taken before the change was confirmed.
+        let other_code = other
+            .change_email(Email::new("new@omics.com").unwrap())
+            .unwrap();
+        other.confirm_email_change(&other_code).unwrap();
+        c.user_repo().save(&mut other).await.unwrap();
+
+        let err = serv
+            .confirm_email_change(&mut user, &code)
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "taken");
+        assert_eq!(user.identity().email().value(), "user@omics.com");
+    }
+
+    #[tokio::test]
+    async fn generate_username() {
+        let c = mocks::container();
+        let serv = c.user_serv();
+
+        let mut user = mocks::user(
+            "user-1",
+            "john.doe",
+            "user@omics.com",
+            "P@asswd!",
+            true,
+            None,
+            None,
+            "user",
+        );
+        c.user_repo().save(&mut user).await.unwrap();
+
+        let username = serv
+            .generate_username(&Email::new("jane+omics@gmail.com").unwrap())
+            .await
+            .unwrap();
+        assert_eq!(username.value(), "janeomics");
+
+        let username = serv
+            .generate_username(&Email::new("john.doe@gmail.com").unwrap())
+            .await
+            .unwrap();
+        assert!(username.value().starts_with("john.doe-"));
+
+        let username = serv
+            .generate_username(&Email::new("a_b@gmail.com").unwrap())
+            .await
+            .unwrap();
+        assert_eq!(username.value(), "usera_b");
+    }
+
     #[test]
     fn generate_password() {
         let c = mocks::container();
@@ -0,0 +1,282 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use common::error::Error;
+use common::result::Result;
+
+use crate::domain::user::{AttemptRepository, UserId};
+
+/// Failed logins are counted for this long after the first one.
+const FAILURE_WINDOW: Duration = Duration::from_secs(15 * 60);
+/// Failed logins of an account that don't make the next one wait.
+const FREE_FAILURES: u32 = 3;
+/// Longest wait between failed logins of an account.
+const MAX_DELAY: Duration = Duration::from_secs(60);
+/// Failed logins that lock the account.
+const LOCKOUT_FAILURES: u32 = 10;
+const LOCKOUT_DURATION: Duration = Duration::from_secs(15 * 60);
+/// Failed logins of an IP, to any account, after which it has to wait for the window to end.
+const IP_FAILURES: u32 = 50;
+
+/// Requests limited for each client, an IP or an email, because they can be made without being
+/// authenticated.
+#[derive(Debug, Clone, Copy)]
+pub enum RateLimit {
+    Login,
+    RecoverPassword,
+    Register,
+    ResetPassword,
+}
+
+impl RateLimit {
+    fn name(&self) -> &str {
+        match self {
+            RateLimit::Login => "login",
+            RateLimit::RecoverPassword => "recover-password",
+            RateLimit::Register => "register",
+            RateLimit::ResetPassword => "reset-password",
+        }
+    }
+
+    /// Requests allowed in each window.
+    fn requests(&self) -> u32 {
+        match self {
+            RateLimit::Login => 20,
+            RateLimit::RecoverPassword => 5,
+            RateLimit::Register => 5,
+            RateLimit::ResetPassword => 10,
+        }
+    }
+
+    fn window(&self) -> Duration {
+        match self {
+            RateLimit::Login => Duration::from_secs(60),
+            RateLimit::RecoverPassword => Duration::from_secs(60 * 60),
+            RateLimit::Register => Duration::from_secs(60 * 60),
+            RateLimit::ResetPassword => Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+/// ThrottlingService protects the login from brute-force attacks. Each failed login of an account
+/// makes the next one wait longer, until the account is locked, and an IP failing too many logins
+/// has to wait for the window to end. It also limits the requests that create emails or users, or
+/// that could guess tokens.
+pub struct ThrottlingService {
+    attempt_repo: Arc<dyn AttemptRepository>,
+}
+
+impl ThrottlingService {
+    pub fn new(attempt_repo: Arc<dyn AttemptRepository>) -> Self {
+        ThrottlingService { attempt_repo }
+    }
+
+    /// Counts a request of the client, failing when it exceeds the limit.
+    pub async fn limit(&self, rate_limit: RateLimit, client: &str) -> Result<()> {
+        let attempts = self
+            .attempt_repo
+            .record(
+                &format!("{}:{}", rate_limit.name(), client),
+                rate_limit.window(),
+            )
+            .await?;
+
+        if attempts.count() > rate_limit.requests() {
+            return Err(Error::new("request", "rate_limited")
+                .add_context("retry_after", seconds(attempts.ttl()).as_str()));
+        }
+
+        Ok(())
+    }
+
+    /// Fails while the IP has to wait after failing too many logins.
+    pub async fn check_ip(&self, ip: &str) -> Result<()> {
+        if let Some(attempts) = self.attempt_repo.find(&ip_key(ip)).await? {
+            if attempts.count() >= IP_FAILURES {
+                return Err(throttled(attempts.ttl()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fails while the account has to wait after its last failed login.
+    pub async fn check_account(&self, user_id: &UserId) -> Result<()> {
+        if let Some(attempts) = self.attempt_repo.find(&account_key(user_id)).await? {
+            let elapsed = (Utc::now() - *attempts.last_attempt_at())
+                .to_std()
+                .unwrap_or_default();
+            let delay = delay(attempts.count());
+
+            if elapsed < delay {
+                return Err(throttled(delay - elapsed));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Counts a failed login of the IP and of the account, if it exists. Returns the date until
+    /// the account has to be locked when it failed too many times.
+    pub async fn login_failed(
+        &self,
+        user_id: Option<&UserId>,
+        ip: Option<&String>,
+    ) -> Result<Option<DateTime<Utc>>> {
+        if let Some(ip) = ip {
+            self.attempt_repo
+                .record(&ip_key(ip), FAILURE_WINDOW)
+                .await?;
+        }
+
+        if let Some(user_id) = user_id {
+            let key = account_key(user_id);
+            let attempts = self.attempt_repo.record(&key, FAILURE_WINDOW).await?;
+
+            if attempts.count() >= LOCKOUT_FAILURES {
+                // The lock replaces the delays, which start again when it ends.
+                self.attempt_repo.delete(&key).await?;
+
+                return Ok(Some(
+                    Utc::now() + chrono::Duration::seconds(LOCKOUT_DURATION.as_secs() as i64),
+                ));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Forgets the failed logins of the account. The ones of the IP are kept, so an attacker
+    /// can't reset them by logging in to their own account.
+    pub async fn login_succeeded(&self, user_id: &UserId) -> Result<()> {
+        self.attempt_repo.delete(&account_key(user_id)).await
+    }
+}
+
+fn ip_key(ip: &str) -> String {
+    format!("login-failures:ip:{}", ip)
+}
+
+fn account_key(user_id: &UserId) -> String {
+    format!("login-failures:user:{}", user_id.value())
+}
+
+/// Wait before the next login of an account, doubled with each failure.
+fn delay(failures: u32) -> Duration {
+    if failures < FREE_FAILURES {
+        return Duration::from_secs(0);
+    }
+
+    Duration::from_secs(1 << (failures - FREE_FAILURES).min(6)).min(MAX_DELAY)
+}
+
+/// Whole seconds, rounded up, as clients expect them in `Retry-After`.
+fn seconds(duration: Duration) -> String {
+    let mut seconds = duration.as_secs();
+    if duration.subsec_nanos() > 0 {
+        seconds += 1;
+    }
+    seconds.to_string()
+}
+
+fn throttled(wait: Duration) -> Error {
+    Error::new("credentials", "throttled").add_context("retry_after", seconds(wait).as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::mocks;
+
+    #[test]
+    fn progressive_delay() {
+        assert_eq!(delay(0), Duration::from_secs(0));
+        assert_eq!(delay(2), Duration::from_secs(0));
+        assert_eq!(delay(3), Duration::from_secs(1));
+        assert_eq!(delay(4), Duration::from_secs(2));
+        assert_eq!(delay(7), Duration::from_secs(16));
+        assert_eq!(delay(9), MAX_DELAY);
+        assert_eq!(delay(40), MAX_DELAY);
+    }
+
+    #[tokio::test]
+    async fn rate_limit() {
+        let c = mocks::container();
+        let serv = c.throttling_serv();
+
+        for _ in 0..RateLimit::Register.requests() {
+            serv.limit(RateLimit::Register, "127.0.0.1").await.unwrap();
+        }
+
+        let err = serv
+            .limit(RateLimit::Register, "127.0.0.1")
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "rate_limited");
+        assert!(err.context().contains_key("retry_after"));
+
+        // Limits are counted by action and client.
+        assert!(serv.limit(RateLimit::Register, "127.0.0.2").await.is_ok());
+        assert!(serv.limit(RateLimit::Login, "127.0.0.1").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn lockout() {
+        let c = mocks::container();
+        let serv = c.throttling_serv();
+        let user_id = UserId::new("user-1").unwrap();
+        let ip = "127.0.0.1".to_owned();
+
+        for _ in 0..FREE_FAILURES {
+            assert!(serv.check_account(&user_id).await.is_ok());
+            let locked_until = serv.login_failed(Some(&user_id), Some(&ip)).await.unwrap();
+            assert!(locked_until.is_none());
+        }
+
+        let err = serv.check_account(&user_id).await.err().unwrap();
+        assert_eq!(err.code(), "throttled");
+        assert_eq!(err.context().get("retry_after").unwrap(), "1");
+        assert!(serv.check_ip(&ip).await.is_ok());
+
+        for _ in FREE_FAILURES..(LOCKOUT_FAILURES - 1) {
+            assert!(serv
+                .login_failed(Some(&user_id), Some(&ip))
+                .await
+                .unwrap()
+                .is_none());
+        }
+        let locked_until = serv
+            .login_failed(Some(&user_id), Some(&ip))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(locked_until > Utc::now());
+
+        // The lock replaces the delays.
+        assert!(serv.check_account(&user_id).await.is_ok());
+
+        serv.login_failed(Some(&user_id), None).await.unwrap();
+        serv.login_succeeded(&user_id).await.unwrap();
+        assert!(serv.check_account(&user_id).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn ip_failures() {
+        let c = mocks::container();
+        let serv = c.throttling_serv();
+        let ip = "127.0.0.1".to_owned();
+
+        for _ in 0..IP_FAILURES {
+            assert!(serv.check_ip(&ip).await.is_ok());
+            serv.login_failed(None, Some(&ip)).await.unwrap();
+        }
+
+        let err = serv.check_ip(&ip).await.err().unwrap();
+        assert_eq!(err.code(), "throttled");
+        assert!(serv.check_ip("127.0.0.2").await.is_ok());
+    }
+}
@@ -0,0 +1,280 @@
+use base32::Alphabet;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac, NewMac};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+use common::error::Error;
+use common::result::Result;
+
+type HmacSha1 = Hmac<Sha1>;
+
+const ALPHABET: Alphabet = Alphabet::RFC4648 { padding: false };
+/// Characters encoded in the label and the issuer of the URI: everything but the unreserved ones.
+const URI_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+/// Name authenticator apps show next to the account.
+const ISSUER: &str = "Omics";
+/// Length of the secret in bytes, the size of the HMAC-SHA1 output recommended by RFC 4226.
+const SECRET_LENGTH: usize = 20;
+const DIGITS: u32 = 6;
+const PERIOD: i64 = 30;
+/// Steps accepted before and after the current one, for devices with their clock out of sync.
+const SKEW: i64 = 1;
+const RECOVERY_CODES: usize = 10;
+
+/// Time-based one-time passwords (RFC 6238) generated by an authenticator app from a shared
+/// secret, and recovery codes for users that lose the device. Recovery codes are only stored
+/// hashed and each one can be used once.
+#[derive(Debug, Clone)]
+pub struct TwoFactor {
+    secret: String,
+    recovery_codes: Vec<String>,
+    last_step: Option<i64>,
+    enabled_at: Option<DateTime<Utc>>,
+}
+
+impl TwoFactor {
+    /// Starts an enrollment with a new secret. It's enabled when the user verifies a code.
+    pub fn new() -> Self {
+        let mut key = [0u8; SECRET_LENGTH];
+        OsRng.fill_bytes(&mut key);
+
+        TwoFactor {
+            secret: base32::encode(ALPHABET, &key),
+            recovery_codes: Vec::new(),
+            last_step: None,
+            enabled_at: None,
+        }
+    }
+
+    pub fn build(
+        secret: String,
+        recovery_codes: Vec<String>,
+        last_step: Option<i64>,
+        enabled_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        TwoFactor {
+            secret,
+            recovery_codes,
+            last_step,
+            enabled_at,
+        }
+    }
+
+    /// Secret encoded in base32, as authenticator apps expect it.
+    pub fn secret(&self) -> &str {
+        &self.secret
+    }
+
+    /// Hashes of the recovery codes that weren't used.
+    pub fn recovery_codes(&self) -> &[String] {
+        &self.recovery_codes
+    }
+
+    /// Last step a code was accepted for. Codes of that step and previous ones are rejected, so
+    /// an intercepted code can't be used again.
+    pub fn last_step(&self) -> Option<i64> {
+        self.last_step
+    }
+
+    pub fn enabled_at(&self) -> Option<&DateTime<Utc>> {
+        self.enabled_at.as_ref()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled_at.is_some()
+    }
+
+    /// URI encoded in the QR code scanned by authenticator apps. The account is percent-encoded,
+    /// so emails or usernames with reserved characters can't break it or add parameters.
+    pub fn otpauth_uri(&self, account: &str) -> String {
+        format!(
+            "otpauth://totp/{issuer}:{account}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={digits}&period={period}",
+            issuer = utf8_percent_encode(ISSUER, URI_COMPONENT),
+            account = utf8_percent_encode(account, URI_COMPONENT),
+            secret = self.secret,
+            digits = DIGITS,
+            period = PERIOD,
+        )
+    }
+
+    /// Code an authenticator app shows at `time`.
+    pub fn code_at(&self, time: &DateTime<Utc>) -> Result<String> {
+        self.code(time.timestamp().div_euclid(PERIOD))
+    }
+
+    /// Accepts a code of the current step, or of the adjacent ones, that is newer than the last
+    /// accepted code.
+    pub fn verify(&mut self, code: &str) -> Result<bool> {
+        let current = Utc::now().timestamp().div_euclid(PERIOD);
+        let code = code.trim();
+
+        for step in (current - SKEW)..=(current + SKEW) {
+            if self.last_step.map_or(false, |last_step| step <= last_step) {
+                continue;
+            }
+
+            // Compared in constant time, so the time it takes doesn't reveal matching digits.
+            if bool::from(self.code(step)?.as_bytes().ct_eq(code.as_bytes())) {
+                self.last_step = Some(step);
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Replaces the recovery codes, returning them. They can't be obtained again.
+    pub fn generate_recovery_codes(&mut self) -> Vec<String> {
+        let codes: Vec<String> = (0..RECOVERY_CODES)
+            .map(|_| {
+                let code = Uuid::new_v4().to_simple().to_string();
+                format!("{}-{}", &code[..5], &code[5..10])
+            })
+            .collect();
+
+        self.recovery_codes = codes.iter().map(|code| hash(code)).collect();
+
+        codes
+    }
+
+    /// Removes the recovery code if it's valid.
+    pub fn use_recovery_code(&mut self, code: &str) -> bool {
+        let hashed = hash(&code.trim().to_lowercase());
+
+        match self
+            .recovery_codes
+            .iter()
+            .position(|code| bool::from(code.as_bytes().ct_eq(hashed.as_bytes())))
+        {
+            Some(index) => {
+                self.recovery_codes.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn enable(&mut self) {
+        self.enabled_at = Some(Utc::now());
+    }
+
+    fn code(&self, step: i64) -> Result<String> {
+        let key = base32::decode(ALPHABET, &self.secret)
+            .ok_or_else(|| Error::internal("two_factor", "invalid_secret"))?;
+
+        let mut mac = HmacSha1::new_varkey(&key)
+            .map_err(|_| Error::internal("two_factor", "invalid_secret"))?;
+        mac.update(&step.to_be_bytes());
+        let hash = mac.finalize().into_bytes();
+
+        // Dynamic truncation
+        let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+        let binary = u32::from_be_bytes([
+            hash[offset] & 0x7f,
+            hash[offset + 1],
+            hash[offset + 2],
+            hash[offset + 3],
+        ]);
+
+        Ok(format!(
+            "{:0digits$}",
+            binary % 10u32.pow(DIGITS),
+            digits = DIGITS as usize
+        ))
+    }
+}
+
+impl Default for TwoFactor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn hash(code: &str) -> String {
+    format!("{:x}", Sha256::digest(code.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone;
+
+    #[test]
+    fn rfc6238_codes() {
+        let two_factor = TwoFactor::build(
+            base32::encode(ALPHABET, b"12345678901234567890"),
+            Vec::new(),
+            None,
+            None,
+        );
+
+        let code = |timestamp| two_factor.code_at(&Utc.timestamp(timestamp, 0)).unwrap();
+        assert_eq!(code(59), "287082");
+        assert_eq!(code(1111111109), "081804");
+        assert_eq!(code(1234567890), "005924");
+        assert_eq!(code(2000000000), "279037");
+    }
+
+    #[test]
+    fn random_secret() {
+        let two_factor = TwoFactor::new();
+        let key = base32::decode(ALPHABET, two_factor.secret()).unwrap();
+        assert_eq!(key.len(), SECRET_LENGTH);
+        assert_ne!(two_factor.secret(), TwoFactor::new().secret());
+    }
+
+    #[test]
+    fn verify_once() {
+        let mut two_factor = TwoFactor::new();
+        assert!(!two_factor.is_enabled());
+
+        let code = two_factor.code_at(&Utc::now()).unwrap();
+        assert!(two_factor.verify(&code).unwrap());
+        assert!(!two_factor.verify(&code).unwrap());
+
+        let old_code = two_factor
+            .code_at(&(Utc::now() - chrono::Duration::minutes(5)))
+            .unwrap();
+        assert!(!two_factor.verify(&old_code).unwrap());
+    }
+
+    #[test]
+    fn recovery_codes() {
+        let mut two_factor = TwoFactor::new();
+        let codes = two_factor.generate_recovery_codes();
+        assert_eq!(codes.len(), RECOVERY_CODES);
+        assert!(!two_factor.recovery_codes().contains(&codes[0]));
+
+        assert!(two_factor.use_recovery_code(&codes[0].to_uppercase()));
+        assert!(!two_factor.use_recovery_code(&codes[0]));
+        assert_eq!(two_factor.recovery_codes().len(), RECOVERY_CODES - 1);
+
+        let uri = two_factor.otpauth_uri("user1");
+        assert!(uri.starts_with("otpauth://totp/Omics:user1?secret="));
+        assert!(uri.contains(two_factor.secret()));
+    }
+
+    #[test]
+    fn encoded_otpauth_uri() {
+        let two_factor = TwoFactor::new();
+
+        let uri = two_factor.otpauth_uri("a&b@x.com");
+        assert!(uri.starts_with("otpauth://totp/Omics:a%26b%40x.com?secret="));
+        assert_eq!(uri.matches('&').count(), 4);
+
+        let uri = two_factor.otpauth_uri("user 1:x?issuer=Evil#");
+        assert!(uri.starts_with("otpauth://totp/Omics:user%201%3Ax%3Fissuer%3DEvil%23?secret="));
+        assert_eq!(uri.matches("issuer=").count(), 1);
+    }
+}
@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+
+use common::cache::{Cache, Sweep};
+use common::infrastructure::cache::InMemCache;
+use common::result::Result;
+
+use crate::domain::user::{AttemptRepository, Attempts};
+
+pub struct InMemAttemptRepository {
+    cache: InMemCache<String, Attempts>,
+    // Attempts are read and written back, so concurrent ones have to wait.
+    lock: Mutex<()>,
+}
+
+impl InMemAttemptRepository {
+    pub fn new() -> Self {
+        InMemAttemptRepository {
+            cache: InMemCache::new(),
+            lock: Mutex::new(()),
+        }
+    }
+}
+
+impl Default for InMemAttemptRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl AttemptRepository for InMemAttemptRepository {
+    async fn find(&self, key: &str) -> Result<Option<Attempts>> {
+        Ok(self
+            .cache
+            .get(&key.to_owned())
+            .await
+            .filter(|attempts| !attempts.is_expired()))
+    }
+
+    async fn record(&self, key: &str, window: Duration) -> Result<Attempts> {
+        let _lock = self.lock.lock().await;
+
+        let attempts = match self.find(key).await? {
+            Some(mut attempts) => {
+                attempts.record();
+                attempts
+            }
+            None => Attempts::new(key, window),
+        };
+
+        self.cache
+            .set_with_ttl(key.to_owned(), attempts.clone(), attempts.ttl())
+            .await?;
+
+        Ok(attempts)
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.cache.delete(&key.to_owned()).await
+    }
+}
+
+#[async_trait]
+impl Sweep for InMemAttemptRepository {
+    async fn sweep(&self) -> Result<usize> {
+        self.cache.sweep().await
+    }
+}
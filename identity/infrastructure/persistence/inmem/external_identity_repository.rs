@@ -0,0 +1,57 @@
+use async_trait::async_trait;
+
+use common::cache::Cache;
+use common::error::Error;
+use common::infrastructure::cache::InMemCache;
+use common::result::Result;
+
+use crate::domain::oauth::{ExternalIdentity, ExternalIdentityRepository};
+use crate::domain::user::{Provider, UserId};
+
+#[derive(Default)]
+pub struct InMemExternalIdentityRepository {
+    cache: InMemCache<(Provider, String), ExternalIdentity>,
+}
+
+impl InMemExternalIdentityRepository {
+    pub fn new() -> Self {
+        InMemExternalIdentityRepository {
+            cache: InMemCache::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl ExternalIdentityRepository for InMemExternalIdentityRepository {
+    async fn find(&self, provider: &Provider, subject: &str) -> Result<ExternalIdentity> {
+        self.cache
+            .get(&(provider.clone(), subject.to_owned()))
+            .await
+            .ok_or_else(|| Error::not_found("external_identity"))
+    }
+
+    async fn find_by_user_id(&self, user_id: &UserId) -> Result<Vec<ExternalIdentity>> {
+        Ok(self
+            .cache
+            .filter(|(_, external_identity)| external_identity.user_id() == user_id)
+            .await)
+    }
+
+    async fn save(&self, external_identity: &ExternalIdentity) -> Result<()> {
+        self.cache
+            .set(
+                (
+                    external_identity.provider().clone(),
+                    external_identity.subject().to_owned(),
+                ),
+                external_identity.clone(),
+            )
+            .await
+    }
+
+    async fn delete(&self, provider: &Provider, subject: &str) -> Result<()> {
+        self.cache
+            .delete(&(provider.clone(), subject.to_owned()))
+            .await
+    }
+}
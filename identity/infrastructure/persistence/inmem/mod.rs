@@ -1,7 +1,11 @@
+mod attempt_repository;
+mod external_identity_repository;
 mod permission_repository;
 mod role_repository;
 mod token_repository;
 mod user_repository;
+pub use attempt_repository::*;
+pub use external_identity_repository::*;
 pub use permission_repository::*;
 pub use role_repository::*;
 pub use token_repository::*;
@@ -30,18 +30,53 @@ impl Default for InMemRoleRepository {
 #[async_trait]
 impl RoleRepository for InMemRoleRepository {
     async fn find_all(&self) -> Result<Vec<Role>> {
-        Ok(self.cache.all().await)
+        Ok(self
+            .cache
+            .filter(|(_, role)| !role.base().is_deleted())
+            .await)
     }
 
     async fn find_by_id(&self, id: &RoleId) -> Result<Role> {
         self.cache
             .get(id)
             .await
+            .filter(|role| !role.base().is_deleted())
             .ok_or_else(|| Error::not_found("role"))
     }
 
+    async fn find_by_ids(&self, ids: &[RoleId]) -> Result<Vec<Role>> {
+        let mut roles = Vec::new();
+
+        for id in ids.iter() {
+            if let Some(role) = self.cache.get(id).await {
+                if !role.base().is_deleted() {
+                    roles.push(role);
+                }
+            }
+        }
+
+        Ok(roles)
+    }
+
+    async fn find_all_deleted(&self) -> Result<Vec<Role>> {
+        Ok(self
+            .cache
+            .filter(|(_, role)| role.base().is_deleted())
+            .await)
+    }
+
+    async fn find_deleted_by_id(&self, id: &RoleId) -> Result<Role> {
+        self.cache
+            .get(id)
+            .await
+            .filter(|role| role.base().is_deleted())
+            .ok_or_else(|| Error::not_found("role"))
+    }
+
+    /// Every user has the mock role, unless a role with its ID was saved.
     async fn find_by_user_id(&self, _user_id: &UserId) -> Result<Role> {
-        Ok(mocks::role("User"))
+        let role = mocks::role("User");
+        Ok(self.cache.get(role.base().id()).await.unwrap_or(role))
     }
 
     async fn find_default(&self) -> Result<Role> {
@@ -49,11 +84,7 @@ impl RoleRepository for InMemRoleRepository {
     }
 
     async fn save(&self, role: &mut Role) -> Result<()> {
-        if role.base().deleted_at().is_none() {
-            self.cache.set(role.base().id().clone(), role.clone()).await
-        } else {
-            self.cache.delete(role.base().id()).await
-        }
+        self.cache.set(role.base().id().clone(), role.clone()).await
     }
 
     async fn delete(&self, id: &RoleId) -> Result<()> {
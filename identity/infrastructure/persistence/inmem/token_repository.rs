@@ -1,20 +1,29 @@
+use std::time::Duration;
+
 use async_trait::async_trait;
+use tokio::sync::Mutex;
 
-use common::cache::Cache;
+use common::cache::{Cache, Sweep};
+use common::error::Error;
 use common::infrastructure::cache::InMemCache;
 use common::result::Result;
 
-use crate::domain::token::{Data, TokenId, TokenRepository};
+use crate::domain::token::{Data, Session, SessionId, TokenId, TokenRepository};
+use crate::domain::user::UserId;
 
 #[derive(Default)]
 pub struct InMemTokenRepository {
     cache: InMemCache<TokenId, Data>,
+    sessions: InMemCache<SessionId, Session>,
+    update_lock: Mutex<()>,
 }
 
 impl InMemTokenRepository {
     pub fn new() -> Self {
         InMemTokenRepository {
             cache: InMemCache::new(),
+            sessions: InMemCache::new(),
+            update_lock: Mutex::new(()),
         }
     }
 
@@ -36,14 +45,84 @@ impl Cache<TokenId, Data> for InMemTokenRepository {
     async fn delete(&self, token_id: &TokenId) -> Result<()> {
         self.cache.delete(token_id).await
     }
+
+    async fn take(&self, token_id: &TokenId) -> Option<Data> {
+        self.cache.take(token_id).await
+    }
+
+    async fn set_with_ttl(&self, token_id: TokenId, data: Data, ttl: Duration) -> Result<()> {
+        self.cache.set_with_ttl(token_id, data, ttl).await
+    }
+}
+
+#[async_trait]
+impl Sweep for InMemTokenRepository {
+    async fn sweep(&self) -> Result<usize> {
+        let mut swept = self.cache.sweep().await?;
+
+        for session in self
+            .sessions
+            .filter(|(_, session)| session.is_expired())
+            .await
+        {
+            self.sessions.delete(session.id()).await?;
+            swept += 1;
+        }
+
+        Ok(swept)
+    }
 }
 
-impl TokenRepository for InMemTokenRepository {}
+#[async_trait]
+impl TokenRepository for InMemTokenRepository {
+    async fn find_session_by_id(&self, id: &SessionId) -> Result<Session> {
+        self.sessions
+            .get(id)
+            .await
+            .filter(|session| !session.is_expired())
+            .ok_or_else(|| Error::not_found("session"))
+    }
+
+    async fn find_sessions_by_user_id(&self, user_id: &UserId) -> Result<Vec<Session>> {
+        let mut sessions = self
+            .sessions
+            .filter(|(_, session)| session.user_id() == user_id && !session.is_expired())
+            .await;
+        sessions.sort_by(|a, b| b.refreshed_at().cmp(a.refreshed_at()));
+
+        Ok(sessions)
+    }
+
+    async fn save_session(&self, session: &Session) -> Result<()> {
+        self.sessions
+            .set(session.id().clone(), session.clone())
+            .await
+    }
+
+    async fn update_session(&self, session: &Session, refresh_token_hash: &str) -> Result<()> {
+        let _lock = self.update_lock.lock().await;
+
+        match self.sessions.get(session.id()).await {
+            Some(saved) if saved.refresh_token_hash() == refresh_token_hash => {
+                self.sessions
+                    .set(session.id().clone(), session.clone())
+                    .await
+            }
+            _ => Err(Error::new("refresh_token", "invalid")),
+        }
+    }
+
+    async fn delete_session(&self, id: &SessionId) -> Result<()> {
+        self.sessions.delete(id).await
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use crate::domain::token::Device;
+
     #[tokio::test]
     async fn test() {
         fn check_trait_impl<T: TokenRepository>(_repo: &T) {}
@@ -67,4 +146,40 @@ mod tests {
         assert!(repo.delete(&TokenId::from("T123")).await.is_ok());
         assert!(repo.get(&TokenId::from("T124")).await.is_some());
     }
+
+    #[tokio::test]
+    async fn concurrent_refresh() {
+        let repo = InMemTokenRepository::new();
+
+        let (session, refresh_token) = Session::new(
+            UserId::new("#user01").unwrap(),
+            Data::new(),
+            Device::default(),
+            chrono::Duration::days(1),
+        );
+        repo.save_session(&session).await.unwrap();
+
+        // Both requests read the session before any of them saves it.
+        let mut first = repo.find_session_by_id(session.id()).await.unwrap();
+        let mut second = first.clone();
+        first
+            .refresh(&refresh_token, chrono::Duration::days(1))
+            .unwrap();
+        second
+            .refresh(&refresh_token, chrono::Duration::days(1))
+            .unwrap();
+
+        repo.update_session(&first, session.refresh_token_hash())
+            .await
+            .unwrap();
+        let err = repo
+            .update_session(&second, session.refresh_token_hash())
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "invalid");
+
+        let saved = repo.find_session_by_id(session.id()).await.unwrap();
+        assert_eq!(saved.refresh_token_hash(), first.refresh_token_hash());
+    }
 }
@@ -1,14 +1,14 @@
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
 
 use common::cache::Cache;
 use common::error::Error;
 use common::infrastructure::cache::InMemCache;
 use common::model::Pagination;
 use common::result::Result;
+use common::sql::Query;
 
 use crate::domain::role::RoleId;
-use crate::domain::user::{Email, User, UserId, UserOrderBy, UserRepository, Username};
+use crate::domain::user::{Email, User, UserField, UserId, UserRepository, Username};
 
 pub struct InMemUserRepository {
     cache: InMemCache<UserId, User>,
@@ -31,13 +31,25 @@ impl Default for InMemUserRepository {
 #[async_trait]
 impl UserRepository for InMemUserRepository {
     async fn find_all(&self) -> Result<Vec<User>> {
-        Ok(self.cache.all().await)
+        Ok(self
+            .cache
+            .filter(|(_, user)| !user.base().is_deleted())
+            .await)
     }
 
     async fn find_by_id(&self, id: &UserId) -> Result<User> {
         self.cache
             .get(id)
             .await
+            .filter(|user| !user.base().is_deleted())
+            .ok_or_else(|| Error::not_found("user"))
+    }
+
+    async fn find_deleted_by_id(&self, id: &UserId) -> Result<User> {
+        self.cache
+            .get(id)
+            .await
+            .filter(|user| user.base().is_deleted())
             .ok_or_else(|| Error::not_found("user"))
     }
 
@@ -55,33 +67,43 @@ impl UserRepository for InMemUserRepository {
             .ok_or_else(|| Error::new("user", "not_found"))
     }
 
+    async fn find_by_password_reset(&self, token_hash: &str) -> Result<User> {
+        self.cache
+            .find(|(_, user)| {
+                !user.base().is_deleted()
+                    && user
+                        .password_reset()
+                        .map_or(false, |pr| pr.token_hash() == token_hash)
+            })
+            .await
+            .ok_or_else(|| Error::not_found("user"))
+    }
+
     async fn find_by_role_id(&self, role_id: &RoleId) -> Result<Vec<User>> {
         Ok(self
             .cache
-            .filter(|(_, user)| user.role_id() == role_id)
+            .filter(|(_, user)| user.role_id() == role_id && !user.base().is_deleted())
             .await)
     }
 
-    async fn search(
-        &self,
-        _name: Option<&String>,
-        _role_id: Option<&RoleId>,
-        _from: Option<&DateTime<Utc>>,
-        _to: Option<&DateTime<Utc>>,
-        _offset: Option<usize>,
-        _limit: Option<usize>,
-        _order_by: Option<&UserOrderBy>,
-    ) -> Result<Pagination<User>> {
-        let users = self.cache.all().await;
-        Ok(Pagination::new(0, users.len(), users.len(), users.len()).add_items(users))
+    async fn search(&self, query: &Query<UserField>) -> Result<Pagination<User>> {
+        Ok(query.apply(
+            self.cache
+                .filter(|(_, user)| !user.base().is_deleted())
+                .await,
+        ))
+    }
+
+    async fn search_deleted(&self, query: &Query<UserField>) -> Result<Pagination<User>> {
+        Ok(query.apply(
+            self.cache
+                .filter(|(_, user)| user.base().is_deleted())
+                .await,
+        ))
     }
 
     async fn save(&self, user: &mut User) -> Result<()> {
-        if user.base().deleted_at().is_none() {
-            self.cache.set(user.base().id().clone(), user.clone()).await
-        } else {
-            self.cache.delete(user.base().id()).await
-        }
+        self.cache.set(user.base().id().clone(), user.clone()).await
     }
 
     async fn delete(&self, id: &UserId) -> Result<()> {
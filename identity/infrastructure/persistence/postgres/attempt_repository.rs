@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio_postgres::row::Row;
+
+use common::cache::Sweep;
+use common::error::Error;
+use common::infrastructure::postgres::PostgresClient;
+use common::result::Result;
+
+use crate::domain::user::{AttemptRepository, Attempts};
+
+impl Attempts {
+    fn from_row(row: Row) -> Result<Self> {
+        let key: String = row.get("key");
+        let count: i32 = row.get("count");
+        let last_attempt_at: DateTime<Utc> = row.get("last_attempt_at");
+        let expires_at: DateTime<Utc> = row.get("expires_at");
+
+        Ok(Attempts::build(
+            key,
+            count as u32,
+            last_attempt_at,
+            expires_at,
+        ))
+    }
+}
+
+/// PostgresAttemptRepository keeps the attempts in the `attempts` table, so every instance counts
+/// the same ones.
+pub struct PostgresAttemptRepository {
+    client: PostgresClient,
+}
+
+impl PostgresAttemptRepository {
+    pub fn new(client: PostgresClient) -> Self {
+        PostgresAttemptRepository { client }
+    }
+}
+
+#[async_trait]
+impl AttemptRepository for PostgresAttemptRepository {
+    async fn find(&self, key: &str) -> Result<Option<Attempts>> {
+        let rows = self
+            .client
+            .query(
+                "SELECT * FROM attempts WHERE key = $1 AND expires_at > $2",
+                &[&key, &Utc::now()],
+            )
+            .await
+            .map_err(|err| Error::not_found("attempts").wrap_raw(err))?;
+
+        rows.into_iter().next().map(Attempts::from_row).transpose()
+    }
+
+    async fn record(&self, key: &str, window: Duration) -> Result<Attempts> {
+        let attempts = Attempts::new(key, window);
+
+        // Counted in a single statement, so concurrent attempts are not lost.
+        let row = self
+            .client
+            .query_one(
+                "INSERT INTO attempts(key, count, last_attempt_at, expires_at)
+                VALUES ($1, 1, $2, $3)
+                ON CONFLICT (key) DO UPDATE SET
+                    count = CASE
+                        WHEN attempts.expires_at <= $2 THEN 1
+                        ELSE attempts.count + 1
+                    END,
+                    last_attempt_at = $2,
+                    expires_at = CASE
+                        WHEN attempts.expires_at <= $2 THEN $3
+                        ELSE attempts.expires_at
+                    END
+                RETURNING *",
+                &[
+                    &attempts.key(),
+                    attempts.last_attempt_at(),
+                    attempts.expires_at(),
+                ],
+            )
+            .await
+            .map_err(|err| Error::new("attempts", "record").wrap_raw(err))?;
+
+        Attempts::from_row(row)
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.client
+            .execute("DELETE FROM attempts WHERE key = $1", &[&key])
+            .await
+            .map_err(|err| Error::new("attempts", "delete").wrap_raw(err))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Sweep for PostgresAttemptRepository {
+    async fn sweep(&self) -> Result<usize> {
+        let deleted = self
+            .client
+            .execute(
+                "DELETE FROM attempts WHERE expires_at <= $1",
+                &[&Utc::now()],
+            )
+            .await
+            .map_err(|err| Error::new("attempts", "delete").wrap_raw(err))?;
+
+        Ok(deleted as usize)
+    }
+}
@@ -0,0 +1,108 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio_postgres::row::Row;
+use uuid::Uuid;
+
+use common::error::Error;
+use common::infrastructure::postgres::PostgresClient;
+use common::result::Result;
+
+use crate::domain::oauth::{ExternalIdentity, ExternalIdentityRepository};
+use crate::domain::user::{Email, Provider, UserId};
+
+impl ExternalIdentity {
+    fn from_row(row: Row) -> Result<Self> {
+        let provider: String = row.get("provider");
+        let subject: String = row.get("subject");
+        let user_id: Uuid = row.get("user_id");
+        let email: Option<String> = row.get("email");
+        let linked_at: DateTime<Utc> = row.get("linked_at");
+
+        Ok(ExternalIdentity::build(
+            Provider::from_str(&provider)?,
+            subject,
+            UserId::new(user_id.to_string())?,
+            email.map(Email::new).transpose()?,
+            linked_at,
+        ))
+    }
+}
+
+pub struct PostgresExternalIdentityRepository {
+    client: PostgresClient,
+}
+
+impl PostgresExternalIdentityRepository {
+    pub fn new(client: PostgresClient) -> Self {
+        PostgresExternalIdentityRepository { client }
+    }
+}
+
+#[async_trait]
+impl ExternalIdentityRepository for PostgresExternalIdentityRepository {
+    async fn find(&self, provider: &Provider, subject: &str) -> Result<ExternalIdentity> {
+        let row = self
+            .client
+            .query_one(
+                "SELECT * FROM external_identities WHERE provider = $1 AND subject = $2",
+                &[&provider.to_string(), &subject],
+            )
+            .await
+            .map_err(|err| Error::not_found("external_identity").wrap_raw(err))?;
+
+        ExternalIdentity::from_row(row)
+    }
+
+    async fn find_by_user_id(&self, user_id: &UserId) -> Result<Vec<ExternalIdentity>> {
+        let rows = self
+            .client
+            .query(
+                "SELECT * FROM external_identities WHERE user_id = $1 ORDER BY linked_at",
+                &[&user_id.to_uuid()?],
+            )
+            .await
+            .map_err(|err| Error::not_found("external_identity").wrap_raw(err))?;
+
+        rows.into_iter().map(ExternalIdentity::from_row).collect()
+    }
+
+    async fn save(&self, external_identity: &ExternalIdentity) -> Result<()> {
+        self.client
+            .execute(
+                "INSERT INTO external_identities(
+                    provider,
+                    subject,
+                    user_id,
+                    email,
+                    linked_at
+                ) VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (provider, subject) DO UPDATE
+                SET email = EXCLUDED.email",
+                &[
+                    &external_identity.provider().to_string(),
+                    &external_identity.subject(),
+                    &external_identity.user_id().to_uuid()?,
+                    &external_identity.email().map(|email| email.to_string()),
+                    &external_identity.linked_at(),
+                ],
+            )
+            .await
+            .map_err(|err| Error::new("external_identity", "create").wrap_raw(err))?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, provider: &Provider, subject: &str) -> Result<()> {
+        self.client
+            .execute(
+                "DELETE FROM external_identities WHERE provider = $1 AND subject = $2",
+                &[&provider.to_string(), &subject],
+            )
+            .await
+            .map_err(|err| Error::new("external_identity", "delete").wrap_raw(err))?;
+
+        Ok(())
+    }
+}
@@ -1,10 +1,8 @@
-use std::sync::Arc;
-
 use async_trait::async_trait;
 use tokio_postgres::row::Row;
-use tokio_postgres::Client;
 
 use common::error::Error;
+use common::infrastructure::postgres::PostgresClient;
 use common::result::Result;
 
 use crate::domain::role::{Permission, PermissionRepository};
@@ -19,11 +17,11 @@ impl Permission {
 }
 
 pub struct PostgresPermissionRepository {
-    client: Arc<Client>,
+    client: PostgresClient,
 }
 
 impl PostgresPermissionRepository {
-    pub fn new(client: Arc<Client>) -> Self {
+    pub fn new(client: PostgresClient) -> Self {
         PostgresPermissionRepository { client }
     }
 }
@@ -1,13 +1,12 @@
-use std::sync::Arc;
-
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use tokio_postgres::row::Row;
-use tokio_postgres::Client;
 
 use common::error::Error;
+use common::infrastructure::postgres::PostgresClient;
 use common::model::AggregateRoot;
 use common::result::Result;
+use common::retention::Purge;
 
 use crate::domain::role::{Name, Permission, Role, RoleId, RoleRepository};
 use crate::domain::user::UserId;
@@ -20,6 +19,7 @@ impl Role {
         let permissions: Vec<Permission> = serde_json::from_value(row.get("permissions"))?;
 
         let default: bool = row.get("default");
+        let two_factor_required: bool = row.get("two_factor_required");
 
         let created_at: DateTime<Utc> = row.get("created_at");
         let updated_at: Option<DateTime<Utc>> = row.get("updated_at");
@@ -30,16 +30,17 @@ impl Role {
             Name::new(name)?,
             permissions,
             default,
+            two_factor_required,
         ))
     }
 }
 
 pub struct PostgresRoleRepository {
-    client: Arc<Client>,
+    client: PostgresClient,
 }
 
 impl PostgresRoleRepository {
-    pub fn new(client: Arc<Client>) -> Self {
+    pub fn new(client: PostgresClient) -> Self {
         PostgresRoleRepository { client }
     }
 }
@@ -49,7 +50,7 @@ impl RoleRepository for PostgresRoleRepository {
     async fn find_all(&self) -> Result<Vec<Role>> {
         let rows = self
             .client
-            .query("SELECT * FROM roles", &[])
+            .query("SELECT * FROM roles WHERE deleted_at IS NULL", &[])
             .await
             .map_err(|err| Error::not_found("role").wrap_raw(err))?;
 
@@ -65,7 +66,63 @@ impl RoleRepository for PostgresRoleRepository {
     async fn find_by_id(&self, id: &RoleId) -> Result<Role> {
         let row = self
             .client
-            .query_one("SELECT * FROM roles WHERE id = $1", &[&id.value()])
+            .query_one(
+                "SELECT * FROM roles WHERE id = $1 AND deleted_at IS NULL",
+                &[&id.value()],
+            )
+            .await
+            .map_err(|err| Error::not_found("role").wrap_raw(err))?;
+
+        Role::from_row(row)
+    }
+
+    async fn find_by_ids(&self, ids: &[RoleId]) -> Result<Vec<Role>> {
+        let ids: Vec<&str> = ids.iter().map(|id| id.value()).collect();
+
+        let rows = self
+            .client
+            .query(
+                "SELECT * FROM roles WHERE id = ANY($1) AND deleted_at IS NULL",
+                &[&ids],
+            )
+            .await
+            .map_err(|err| Error::not_found("role").wrap_raw(err))?;
+
+        let mut roles = Vec::new();
+
+        for row in rows.into_iter() {
+            roles.push(Role::from_row(row)?);
+        }
+
+        Ok(roles)
+    }
+
+    async fn find_all_deleted(&self) -> Result<Vec<Role>> {
+        let rows = self
+            .client
+            .query(
+                "SELECT * FROM roles WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC",
+                &[],
+            )
+            .await
+            .map_err(|err| Error::not_found("role").wrap_raw(err))?;
+
+        let mut roles = Vec::new();
+
+        for row in rows.into_iter() {
+            roles.push(Role::from_row(row)?);
+        }
+
+        Ok(roles)
+    }
+
+    async fn find_deleted_by_id(&self, id: &RoleId) -> Result<Role> {
+        let row = self
+            .client
+            .query_one(
+                "SELECT * FROM roles WHERE id = $1 AND deleted_at IS NOT NULL",
+                &[&id.value()],
+            )
             .await
             .map_err(|err| Error::not_found("role").wrap_raw(err))?;
 
@@ -112,13 +169,20 @@ impl RoleRepository for PostgresRoleRepository {
         if create {
             self.client
                 .execute(
-                    r#"INSERT INTO roles(id, name, permissions, "default", created_at)
-                    VALUES ($1, $2, $3, $4, $5)"#,
+                    r#"INSERT INTO roles(
+                        id,
+                        name,
+                        permissions,
+                        "default",
+                        two_factor_required,
+                        created_at
+                    ) VALUES ($1, $2, $3, $4, $5, $6)"#,
                     &[
                         &role.base().id().value(),
                         &role.name().value(),
                         &permissions,
                         &role.is_default(),
+                        &role.requires_two_factor(),
                         &role.base().created_at(),
                     ],
                 )
@@ -132,7 +196,9 @@ impl RoleRepository for PostgresRoleRepository {
                         name = $2,
                         permissions = $3,
                         "default" = $4,
-                        updated_at = $5
+                        updated_at = $5,
+                        deleted_at = $6,
+                        two_factor_required = $7
                     WHERE
                         id = $1"#,
                     &[
@@ -141,6 +207,8 @@ impl RoleRepository for PostgresRoleRepository {
                         &permissions,
                         &role.is_default(),
                         &role.base().updated_at(),
+                        &role.base().deleted_at(),
+                        &role.requires_two_factor(),
                     ],
                 )
                 .await
@@ -163,3 +231,22 @@ impl RoleRepository for PostgresRoleRepository {
         Ok(())
     }
 }
+
+#[async_trait]
+impl Purge for PostgresRoleRepository {
+    // Users reference their role, even if they are deleted, so those roles are kept.
+    async fn purge(&self, deleted_before: &DateTime<Utc>) -> Result<usize> {
+        let purged = self
+            .client
+            .execute(
+                "DELETE FROM roles
+                WHERE deleted_at < $1
+                AND NOT EXISTS (SELECT 1 FROM users WHERE users.role_id = roles.id)",
+                &[deleted_before],
+            )
+            .await
+            .map_err(|err| Error::new("role", "purge").wrap_raw(err))?;
+
+        Ok(purged as usize)
+    }
+}
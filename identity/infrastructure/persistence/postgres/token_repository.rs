@@ -0,0 +1,224 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio_postgres::row::Row;
+use uuid::Uuid;
+
+use common::cache::{Cache, Sweep};
+use common::error::Error;
+use common::infrastructure::cache::PostgresCache;
+use common::infrastructure::postgres::PostgresClient;
+use common::result::Result;
+
+use crate::domain::token::{Data, Device, Session, SessionId, TokenId, TokenRepository};
+use crate::domain::user::UserId;
+
+fn to_uuid(id: &TokenId) -> Result<Uuid> {
+    Uuid::parse_str(id.id()).map_err(|err| Error::bad_format("id").wrap_raw(err))
+}
+
+impl Session {
+    fn from_row(row: Row) -> Result<Self> {
+        let id: Uuid = row.get("id");
+        let user_id: Uuid = row.get("user_id");
+        let data: Data = serde_json::from_value(row.get("data"))?;
+        let access_token_id: Uuid = row.get("access_token_id");
+        let refresh_token_hash: String = row.get("refresh_token_hash");
+        let previous_refresh_token_hash: Option<String> = row.get("previous_refresh_token_hash");
+
+        let user_agent: Option<String> = row.get("user_agent");
+        let ip: Option<String> = row.get("ip");
+
+        let created_at: DateTime<Utc> = row.get("created_at");
+        let refreshed_at: DateTime<Utc> = row.get("refreshed_at");
+        let expires_at: DateTime<Utc> = row.get("expires_at");
+
+        Ok(Session::build(
+            SessionId::build(id.to_string()),
+            UserId::new(user_id.to_string())?,
+            data,
+            TokenId::build(access_token_id.to_string()),
+            refresh_token_hash,
+            previous_refresh_token_hash,
+            Device::new(user_agent, ip),
+            created_at,
+            refreshed_at,
+            expires_at,
+        ))
+    }
+}
+
+/// PostgresTokenRepository keeps the data of the access tokens in the shared cache and the
+/// sessions in the `sessions` table, so users stay logged in after a restart.
+pub struct PostgresTokenRepository {
+    client: PostgresClient,
+    cache: PostgresCache,
+}
+
+impl PostgresTokenRepository {
+    pub fn new(client: PostgresClient) -> Self {
+        PostgresTokenRepository {
+            cache: PostgresCache::new(client.clone()),
+            client,
+        }
+    }
+}
+
+#[async_trait]
+impl Cache<TokenId, Data> for PostgresTokenRepository {
+    async fn get(&self, token_id: &TokenId) -> Option<Data> {
+        self.cache.get(&token_id.to_string()).await
+    }
+
+    async fn set(&self, token_id: TokenId, data: Data) -> Result<()> {
+        self.cache.set(token_id.to_string(), data).await
+    }
+
+    async fn delete(&self, token_id: &TokenId) -> Result<()> {
+        Cache::<String, Data>::delete(&self.cache, &token_id.to_string()).await
+    }
+
+    async fn take(&self, token_id: &TokenId) -> Option<Data> {
+        self.cache.take(&token_id.to_string()).await
+    }
+
+    async fn set_with_ttl(&self, token_id: TokenId, data: Data, ttl: Duration) -> Result<()> {
+        self.cache
+            .set_with_ttl(token_id.to_string(), data, ttl)
+            .await
+    }
+}
+
+#[async_trait]
+impl Sweep for PostgresTokenRepository {
+    /// Removes the expired sessions. The access tokens are removed when the shared cache is
+    /// swept.
+    async fn sweep(&self) -> Result<usize> {
+        let deleted = self
+            .client
+            .execute(
+                "DELETE FROM sessions WHERE expires_at <= $1",
+                &[&Utc::now()],
+            )
+            .await
+            .map_err(|err| Error::new("session", "delete").wrap_raw(err))?;
+
+        Ok(deleted as usize)
+    }
+}
+
+#[async_trait]
+impl TokenRepository for PostgresTokenRepository {
+    async fn find_session_by_id(&self, id: &SessionId) -> Result<Session> {
+        let row = self
+            .client
+            .query_one(
+                "SELECT * FROM sessions WHERE id = $1 AND expires_at > $2",
+                &[&to_uuid(id)?, &Utc::now()],
+            )
+            .await
+            .map_err(|err| Error::not_found("session").wrap_raw(err))?;
+
+        Session::from_row(row)
+    }
+
+    async fn find_sessions_by_user_id(&self, user_id: &UserId) -> Result<Vec<Session>> {
+        let rows = self
+            .client
+            .query(
+                "SELECT * FROM sessions
+                WHERE
+                    user_id = $1
+                    AND expires_at > $2
+                ORDER BY refreshed_at DESC",
+                &[&user_id.to_uuid()?, &Utc::now()],
+            )
+            .await
+            .map_err(|err| Error::not_found("session").wrap_raw(err))?;
+
+        rows.into_iter().map(Session::from_row).collect()
+    }
+
+    async fn save_session(&self, session: &Session) -> Result<()> {
+        let data = serde_json::to_value(session.data())?;
+
+        self.client
+            .execute(
+                "INSERT INTO sessions(
+                    id,
+                    user_id,
+                    data,
+                    access_token_id,
+                    refresh_token_hash,
+                    previous_refresh_token_hash,
+                    user_agent,
+                    ip,
+                    created_at,
+                    refreshed_at,
+                    expires_at
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+                &[
+                    &to_uuid(session.id())?,
+                    &session.user_id().to_uuid()?,
+                    &data,
+                    &to_uuid(session.access_token_id())?,
+                    &session.refresh_token_hash(),
+                    &session.previous_refresh_token_hash(),
+                    &session.device().user_agent(),
+                    &session.device().ip(),
+                    &session.created_at(),
+                    &session.refreshed_at(),
+                    &session.expires_at(),
+                ],
+            )
+            .await
+            .map_err(|err| Error::new("session", "create").wrap_raw(err))?;
+
+        Ok(())
+    }
+
+    async fn update_session(&self, session: &Session, refresh_token_hash: &str) -> Result<()> {
+        let updated = self
+            .client
+            .execute(
+                "UPDATE sessions
+                SET
+                    access_token_id = $3,
+                    refresh_token_hash = $4,
+                    previous_refresh_token_hash = $5,
+                    refreshed_at = $6,
+                    expires_at = $7
+                WHERE
+                    id = $1
+                    AND refresh_token_hash = $2",
+                &[
+                    &to_uuid(session.id())?,
+                    &refresh_token_hash,
+                    &to_uuid(session.access_token_id())?,
+                    &session.refresh_token_hash(),
+                    &session.previous_refresh_token_hash(),
+                    &session.refreshed_at(),
+                    &session.expires_at(),
+                ],
+            )
+            .await
+            .map_err(|err| Error::new("session", "update").wrap_raw(err))?;
+
+        // Another refresh with the same token was saved first.
+        if updated == 0 {
+            return Err(Error::new("refresh_token", "invalid"));
+        }
+
+        Ok(())
+    }
+
+    async fn delete_session(&self, id: &SessionId) -> Result<()> {
+        self.client
+            .execute("DELETE FROM sessions WHERE id = $1", &[&to_uuid(id)?])
+            .await
+            .map_err(|err| Error::new("session", "delete").wrap_raw(err))?;
+
+        Ok(())
+    }
+}
@@ -1,23 +1,41 @@
 use std::str::FromStr;
-use std::sync::Arc;
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use tokio_postgres::row::Row;
-use tokio_postgres::Client;
 use uuid::Uuid;
 
 use common::error::Error;
+use common::infrastructure::event::PostgresOutbox;
+use common::infrastructure::postgres::PostgresClient;
 use common::model::{AggregateRoot, Pagination};
+use common::request::PAGINATION_LIMIT;
 use common::result::Result;
-use common::sql::where_builder::WhereBuilder;
+use common::retention::Purge;
+use common::sql::{Column, Criteria, Field, Query};
 
 use crate::domain::role::RoleId;
 use crate::domain::user::{
-    Biography, Birthdate, Email, Fullname, Gender, Identity, Image, Password, Person, Provider,
-    User, UserId, UserOrderBy, UserRepository, Username, Validation,
+    Biography, Birthdate, Email, EmailChange, Fullname, Gender, Identity, Image, Password,
+    PasswordReset, Person, Provider, TwoFactor, User, UserField, UserId, UserRepository, Username,
+    Validation, ANONYMIZED_EMAIL_DOMAIN,
 };
 
+impl Field for UserField {
+    fn column(&self) -> Column {
+        match self {
+            UserField::Id => Column::Expr("id"),
+            UserField::Username => Column::Expr("username"),
+            UserField::Name => Column::Expr("name"),
+            UserField::Lastname => Column::Expr("lastname"),
+            UserField::Fullname => Column::Expr("CONCAT(name, ' ', lastname)"),
+            UserField::RoleId => Column::Expr("role_id"),
+            UserField::CreatedAt => Column::Expr("created_at"),
+            UserField::DeletedAt => Column::Expr("deleted_at"),
+        }
+    }
+}
+
 impl User {
     fn from_row(row: Row) -> Result<Self> {
         let id: Uuid = row.get("id");
@@ -42,6 +60,22 @@ impl User {
 
         let flag: i64 = row.get("flag");
 
+        let two_factor_secret: Option<String> = row.get("two_factor_secret");
+        let two_factor_recovery_codes: Option<serde_json::Value> =
+            row.get("two_factor_recovery_codes");
+        let two_factor_last_step: Option<i64> = row.get("two_factor_last_step");
+        let two_factor_enabled_at: Option<DateTime<Utc>> = row.get("two_factor_enabled_at");
+
+        let locked_until: Option<DateTime<Utc>> = row.get("locked_until");
+
+        let password_reset_token_hash: Option<String> = row.get("password_reset_token_hash");
+        let password_reset_expires_at: Option<DateTime<Utc>> = row.get("password_reset_expires_at");
+
+        let email_change_email: Option<String> = row.get("email_change_email");
+        let email_change_code_hash: Option<String> = row.get("email_change_code_hash");
+        let email_change_expires_at: Option<DateTime<Utc>> = row.get("email_change_expires_at");
+        let username_changed_at: Option<DateTime<Utc>> = row.get("username_changed_at");
+
         let created_at: DateTime<Utc> = row.get("created_at");
         let updated_at: Option<DateTime<Utc>> = row.get("updated_at");
         let deleted_at: Option<DateTime<Utc>> = row.get("deleted_at");
@@ -74,6 +108,36 @@ impl User {
 
         let role_id = RoleId::new(role_id)?;
         let validation = validation_code.map(Validation::build);
+        let two_factor = match two_factor_secret {
+            Some(secret) => Some(TwoFactor::build(
+                secret,
+                two_factor_recovery_codes
+                    .map(serde_json::from_value)
+                    .transpose()?
+                    .unwrap_or_default(),
+                two_factor_last_step,
+                two_factor_enabled_at,
+            )),
+            None => None,
+        };
+        let password_reset = match (password_reset_token_hash, password_reset_expires_at) {
+            (Some(token_hash), Some(expires_at)) => {
+                Some(PasswordReset::build(token_hash, expires_at))
+            }
+            _ => None,
+        };
+        let email_change = match (
+            email_change_email,
+            email_change_code_hash,
+            email_change_expires_at,
+        ) {
+            (Some(email), Some(code_hash), Some(expires_at)) => Some(EmailChange::build(
+                Email::new(email)?,
+                code_hash,
+                expires_at,
+            )),
+            _ => None,
+        };
 
         Ok(User::build(
             agg_root,
@@ -83,18 +147,116 @@ impl User {
             validation,
             payment_email.map(Email::new).transpose()?,
             flag,
+            two_factor,
+            locked_until,
+            password_reset,
+            email_change,
+            username_changed_at,
         ))
     }
 }
 
 pub struct PostgresUserRepository {
-    client: Arc<Client>,
+    client: PostgresClient,
 }
 
 impl PostgresUserRepository {
-    pub fn new(client: Arc<Client>) -> Self {
+    pub fn new(client: PostgresClient) -> Self {
         PostgresUserRepository { client }
     }
+
+    /// Searches the active or the deleted users.
+    async fn search_by_state(
+        &self,
+        query: &Query<UserField>,
+        deleted: bool,
+    ) -> Result<Pagination<User>> {
+        let deleted_at = Criteria::is_null(UserField::DeletedAt);
+        let query = query
+            .clone()
+            .filter(if deleted { !deleted_at } else { deleted_at });
+        let state = if deleted { "NOT NULL" } else { "NULL" };
+
+        let sql = query.to_sql();
+        let params = sql.params();
+
+        // Pages after a cursor are not counted
+        let mut counts = None;
+        if query.after().is_none() {
+            // Total
+            let row = self
+                .client
+                .query_one(
+                    &format!(
+                        "SELECT COUNT(*) FROM users
+                        WHERE deleted_at IS {}",
+                        state,
+                    ) as &str,
+                    &[],
+                )
+                .await
+                .map_err(|err| Error::new("user", "total").wrap_raw(err))?;
+            let total: i64 = row.get(0);
+
+            // Matching criteria
+            let row = self
+                .client
+                .query_one(
+                    &format!(
+                        "SELECT COUNT(*) FROM users
+                        {}",
+                        sql.where_clause(),
+                    ) as &str,
+                    &params,
+                )
+                .await
+                .map_err(|err| Error::new("user", "matching_criteria").wrap_raw(err))?;
+            let matching_criteria: i64 = row.get(0);
+
+            counts = Some((total as usize, matching_criteria as usize));
+        }
+
+        // Query
+        let offset = query.offset().unwrap_or(0);
+        let limit = query
+            .limit()
+            .or_else(|| counts.map(|(total, _)| total))
+            .unwrap_or(PAGINATION_LIMIT);
+
+        let rows = self
+            .client
+            .query(
+                &format!(
+                    "SELECT * FROM users
+                    {}
+                    {}
+                    OFFSET {}
+                    LIMIT {}",
+                    sql.where_clause(),
+                    sql.order_by(),
+                    offset,
+                    limit,
+                ) as &str,
+                &params,
+            )
+            .await
+            .map_err(|err| Error::not_found("user").wrap_raw(err))?;
+
+        let mut users = Vec::new();
+        for row in rows.into_iter() {
+            users.push(User::from_row(row)?);
+        }
+
+        let next_cursor = query.next_cursor(&users);
+        let pagination = match counts {
+            Some((total, matching_criteria)) => {
+                Pagination::new(offset, limit, total, matching_criteria)
+            }
+            None => Pagination::uncounted(offset, limit),
+        };
+
+        Ok(pagination.add_items(users).set_next_cursor(next_cursor))
+    }
 }
 
 #[async_trait]
@@ -102,7 +264,7 @@ impl UserRepository for PostgresUserRepository {
     async fn find_all(&self) -> Result<Vec<User>> {
         let rows = self
             .client
-            .query("SELECT * FROM users", &[])
+            .query("SELECT * FROM users WHERE deleted_at IS NULL", &[])
             .await
             .map_err(|err| Error::not_found("user").wrap_raw(err))?;
 
@@ -119,7 +281,8 @@ impl UserRepository for PostgresUserRepository {
             .client
             .query_one(
                 "SELECT * FROM users
-                WHERE id = $1",
+                WHERE id = $1
+                AND deleted_at IS NULL",
                 &[&id.to_uuid()?],
             )
             .await
@@ -128,13 +291,14 @@ impl UserRepository for PostgresUserRepository {
         User::from_row(row)
     }
 
-    async fn find_by_username(&self, username: &Username) -> Result<User> {
+    async fn find_deleted_by_id(&self, id: &UserId) -> Result<User> {
         let row = self
             .client
             .query_one(
                 "SELECT * FROM users
-                WHERE username = $1",
-                &[&username.value()],
+                WHERE id = $1
+                AND deleted_at IS NOT NULL",
+                &[&id.to_uuid()?],
             )
             .await
             .map_err(|err| Error::not_found("user").wrap_raw(err))?;
@@ -142,13 +306,13 @@ impl UserRepository for PostgresUserRepository {
         User::from_row(row)
     }
 
-    async fn find_by_email(&self, email: &Email) -> Result<User> {
+    async fn find_by_username(&self, username: &Username) -> Result<User> {
         let row = self
             .client
             .query_one(
                 "SELECT * FROM users
-                WHERE email = $1",
-                &[&email.value()],
+                WHERE username = $1",
+                &[&username.value()],
             )
             .await
             .map_err(|err| Error::not_found("user").wrap_raw(err))?;
@@ -156,113 +320,71 @@ impl UserRepository for PostgresUserRepository {
         User::from_row(row)
     }
 
-    async fn find_by_role_id(&self, role_id: &RoleId) -> Result<Vec<User>> {
-        let rows = self
+    async fn find_by_email(&self, email: &Email) -> Result<User> {
+        let row = self
             .client
-            .query(
+            .query_one(
                 "SELECT * FROM users
-                WHERE role_id = $1",
-                &[&role_id.value()],
+                WHERE email = $1",
+                &[&email.value()],
             )
             .await
             .map_err(|err| Error::not_found("user").wrap_raw(err))?;
 
-        let mut users = Vec::new();
-
-        for row in rows.into_iter() {
-            users.push(User::from_row(row)?);
-        }
-
-        Ok(users)
+        User::from_row(row)
     }
 
-    async fn search(
-        &self,
-        name: Option<&String>,
-        role_id: Option<&RoleId>,
-        from: Option<&DateTime<Utc>>,
-        to: Option<&DateTime<Utc>>,
-        offset: Option<usize>,
-        limit: Option<usize>,
-        order_by: Option<&UserOrderBy>,
-    ) -> Result<Pagination<User>> {
-        let role_id = role_id.map(|id| id.value());
-
-        let (sql, params) = WhereBuilder::new()
-            .add_param_opt(
-                "(
-                    LOWER(username) LIKE '%' || LOWER($$) || '%'
-                    OR LOWER(name) LIKE '%' || LOWER($$) || '%'
-                    OR LOWER(lastname) LIKE '%' || LOWER($$) || '%'
-                    OR LOWER(CONCAT(name, ' ', lastname)) LIKE '%' || LOWER($$) || '%'
-                )",
-                &name,
-                name.is_some(),
-            )
-            .add_param_opt("role_id = $$", &role_id, role_id.is_some())
-            .add_param_opt("created_at >= $$", &from, from.is_some())
-            .add_param_opt("created_at <= $$", &to, to.is_some())
-            .build();
-
-        // Total
-        let row = self
-            .client
-            .query_one("SELECT COUNT(*) FROM users", &[])
-            .await
-            .map_err(|err| Error::new("user", "total").wrap_raw(err))?;
-        let total: i64 = row.get(0);
-
-        // Matching criteria
+    async fn find_by_password_reset(&self, token_hash: &str) -> Result<User> {
         let row = self
             .client
             .query_one(
-                &format!(
-                    "SELECT COUNT(*) FROM users
-                    {}",
-                    sql,
-                ) as &str,
-                &params,
+                "SELECT * FROM users
+                WHERE password_reset_token_hash = $1
+                AND deleted_at IS NULL",
+                &[&token_hash],
             )
             .await
-            .map_err(|err| Error::new("user", "matching_criteria").wrap_raw(err))?;
-        let matching_criteria: i64 = row.get(0);
+            .map_err(|err| Error::not_found("user").wrap_raw(err))?;
 
-        // Query
-        let offset = offset.unwrap_or_else(|| 0);
-        let limit = limit.unwrap_or_else(|| total as usize);
-        let order_by = match order_by {
-            Some(UserOrderBy::Newest) => "created_at DESC",
-            _ => "created_at ASC",
-        };
+        User::from_row(row)
+    }
 
+    async fn find_by_role_id(&self, role_id: &RoleId) -> Result<Vec<User>> {
         let rows = self
             .client
             .query(
-                &format!(
-                    "SELECT * FROM users
-                    {}
-                    ORDER BY {}
-                    OFFSET {}
-                    LIMIT {}",
-                    sql, order_by, offset, limit,
-                ) as &str,
-                &params,
+                "SELECT * FROM users
+                WHERE role_id = $1
+                AND deleted_at IS NULL",
+                &[&role_id.value()],
             )
             .await
             .map_err(|err| Error::not_found("user").wrap_raw(err))?;
 
         let mut users = Vec::new();
+
         for row in rows.into_iter() {
             users.push(User::from_row(row)?);
         }
 
-        Ok(
-            Pagination::new(offset, limit, total as usize, matching_criteria as usize)
-                .add_items(users),
-        )
+        Ok(users)
+    }
+
+    async fn search(&self, query: &Query<UserField>) -> Result<Pagination<User>> {
+        self.search_by_state(query, false).await
+    }
+
+    async fn search_deleted(&self, query: &Query<UserField>) -> Result<Pagination<User>> {
+        self.search_by_state(query, true).await
     }
 
     async fn save(&self, user: &mut User) -> Result<()> {
+        let events = PostgresOutbox::records(&user.events().to_vec()?)?;
+        let two_factor_recovery_codes = user
+            .two_factor()
+            .map(|tf| serde_json::to_value(tf.recovery_codes()))
+            .transpose()?;
+
         let create = self
             .client
             .query_one(
@@ -275,43 +397,46 @@ impl UserRepository for PostgresUserRepository {
         if create {
             self.client
                 .execute(
-                    "INSERT INTO users(
-                        id,
-                        provider,
-                        username,
-                        email,
-                        password,
-                        name,
-                        lastname,
-                        birthdate,
-                        gender,
-                        biography,
-                        profile_image,
-                        role_id,
-                        validation_code,
-                        flag,
-                        created_at,
-                        updated_at,
-                        deleted_at
-                    ) VALUES (
-                        $1,
-                        $2,
-                        $3,
-                        $4,
-                        $5,
-                        $6,
-                        $7,
-                        $8,
-                        $9,
-                        $10,
-                        $11,
-                        $12,
-                        $13,
-                        $14,
-                        $15,
-                        $16,
-                        $17
-                    )",
+                    &PostgresOutbox::enlist(
+                        "INSERT INTO users(
+                            id,
+                            provider,
+                            username,
+                            email,
+                            password,
+                            name,
+                            lastname,
+                            birthdate,
+                            gender,
+                            biography,
+                            profile_image,
+                            role_id,
+                            validation_code,
+                            flag,
+                            created_at,
+                            updated_at,
+                            deleted_at
+                        ) VALUES (
+                            $1,
+                            $2,
+                            $3,
+                            $4,
+                            $5,
+                            $6,
+                            $7,
+                            $8,
+                            $9,
+                            $10,
+                            $11,
+                            $12,
+                            $13,
+                            $14,
+                            $15,
+                            $16,
+                            $17
+                        )",
+                        18,
+                    ) as &str,
                     &[
                         &user.base().id().to_uuid()?,
                         &user.identity().provider().to_string(),
@@ -342,6 +467,7 @@ impl UserRepository for PostgresUserRepository {
                         &user.base().created_at(),
                         &user.base().updated_at(),
                         &user.base().deleted_at(),
+                        &events,
                     ],
                 )
                 .await
@@ -349,23 +475,39 @@ impl UserRepository for PostgresUserRepository {
         } else {
             self.client
                 .execute(
-                    "UPDATE users
-                    SET
-                        password = $2,
-                        name = $3,
-                        lastname = $4,
-                        birthdate = $5,
-                        gender = $6,
-                        biography = $7,
-                        profile_image = $8,
-                        role_id = $9,
-                        validation_code = $10,
-                        payment_email = $11,
-                        flag = $12,
-                        updated_at = $13,
-                        deleted_at = $14
-                    WHERE
-                        id = $1",
+                    &PostgresOutbox::enlist(
+                        "UPDATE users
+                        SET
+                            password = $2,
+                            name = $3,
+                            lastname = $4,
+                            birthdate = $5,
+                            gender = $6,
+                            biography = $7,
+                            profile_image = $8,
+                            role_id = $9,
+                            validation_code = $10,
+                            payment_email = $11,
+                            flag = $12,
+                            updated_at = $13,
+                            deleted_at = $14,
+                            username = $15,
+                            email = $16,
+                            two_factor_secret = $17,
+                            two_factor_recovery_codes = $18,
+                            two_factor_last_step = $19,
+                            two_factor_enabled_at = $20,
+                            locked_until = $21,
+                            password_reset_token_hash = $22,
+                            password_reset_expires_at = $23,
+                            email_change_email = $24,
+                            email_change_code_hash = $25,
+                            email_change_expires_at = $26,
+                            username_changed_at = $27
+                        WHERE
+                            id = $1",
+                        28,
+                    ) as &str,
                     &[
                         &user.base().id().to_uuid()?,
                         &user.identity().password().map(|p| p.value()),
@@ -393,12 +535,28 @@ impl UserRepository for PostgresUserRepository {
                         &user.flag(),
                         &user.base().updated_at(),
                         &user.base().deleted_at(),
+                        &user.identity().username().value(),
+                        &user.identity().email().value(),
+                        &user.two_factor().map(|tf| tf.secret()),
+                        &two_factor_recovery_codes,
+                        &user.two_factor().map(|tf| tf.last_step()).flatten(),
+                        &user.two_factor().map(|tf| tf.enabled_at()).flatten(),
+                        &user.locked_until(),
+                        &user.password_reset().map(|pr| pr.token_hash()),
+                        &user.password_reset().map(|pr| pr.expires_at()),
+                        &user.email_change().map(|ec| ec.email().value()),
+                        &user.email_change().map(|ec| ec.code_hash()),
+                        &user.email_change().map(|ec| ec.expires_at()),
+                        &user.username_changed_at(),
+                        &events,
                     ],
                 )
                 .await
                 .map_err(|err| Error::new("user", "update").wrap_raw(err))?;
         }
 
+        user.events_mut().clear();
+
         Ok(())
     }
 
@@ -415,3 +573,38 @@ impl UserRepository for PostgresUserRepository {
         Ok(())
     }
 }
+
+#[async_trait]
+impl Purge for PostgresUserRepository {
+    /// Users are anonymized instead of deleted, so their donations, subscriptions and contracts
+    /// are kept. Their external identities are unlinked.
+    async fn purge(&self, deleted_before: &DateTime<Utc>) -> Result<usize> {
+        let rows = self
+            .client
+            .query(
+                "SELECT * FROM users
+                WHERE deleted_at < $1
+                AND email NOT LIKE $2",
+                &[deleted_before, &format!("%@{}", ANONYMIZED_EMAIL_DOMAIN)],
+            )
+            .await
+            .map_err(|err| Error::new("user", "purge").wrap_raw(err))?;
+
+        let mut purged = 0;
+        for row in rows.into_iter() {
+            let mut user = User::from_row(row)?;
+            user.anonymize()?;
+            self.save(&mut user).await?;
+            self.client
+                .execute(
+                    "DELETE FROM external_identities WHERE user_id = $1",
+                    &[&user.base().id().to_uuid()?],
+                )
+                .await
+                .map_err(|err| Error::new("user", "purge").wrap_raw(err))?;
+            purged += 1;
+        }
+
+        Ok(purged)
+    }
+}
@@ -1,4 +1,7 @@
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{decode, decode_header, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 
 use common::error::Error;
@@ -13,25 +16,52 @@ struct Claims {
     exp: usize,
 }
 
-#[derive(Default)]
-pub struct JWTEncoder;
+/// JWTEncoder signs tokens with the current key, identified by the `kid` header. To rotate it,
+/// the current key is added as a previous key, so tokens signed before the rotation can be
+/// decoded until they expire.
+pub struct JWTEncoder {
+    key_id: String,
+    secret: String,
+    previous_keys: HashMap<String, String>,
+}
 
 impl JWTEncoder {
-    pub fn new() -> Self {
-        JWTEncoder
+    pub fn new<S: Into<String>>(key_id: S, secret: S) -> Self {
+        JWTEncoder {
+            key_id: key_id.into(),
+            secret: secret.into(),
+            previous_keys: HashMap::new(),
+        }
+    }
+
+    pub fn previous_key<S: Into<String>>(mut self, key_id: S, secret: S) -> Self {
+        self.previous_keys.insert(key_id.into(), secret.into());
+        self
+    }
+
+    fn secret(&self, key_id: &str) -> Option<&str> {
+        if key_id == self.key_id {
+            return Some(&self.secret);
+        }
+
+        self.previous_keys.get(key_id).map(String::as_str)
     }
 }
 
 impl TokenEncoder for JWTEncoder {
-    fn encode(&self, token_id: &TokenId) -> Result<Token> {
+    fn encode(&self, token_id: &TokenId, expires_at: &DateTime<Utc>) -> Result<Token> {
         let claims = Claims {
             sub: token_id.to_string(),
             company: "Omics".to_owned(),
-            exp: 10000000000,
+            exp: expires_at.timestamp() as usize,
+        };
+        let header = Header {
+            kid: Some(self.key_id.clone()),
+            ..Header::default()
         };
-        let key = b"secret";
+        let key = EncodingKey::from_secret(self.secret.as_bytes());
 
-        let token = match encode(&Header::default(), &claims, &EncodingKey::from_secret(key)) {
+        let token = match encode(&header, &claims, &key) {
             Ok(token) => token,
             Err(err) => return Err(Error::new("token", "encode").wrap_raw(err)),
         };
@@ -40,13 +70,19 @@ impl TokenEncoder for JWTEncoder {
     }
 
     fn decode(&self, token: &Token) -> Result<TokenId> {
-        let key = b"secret";
+        let header = decode_header(token.value())
+            .map_err(|err| Error::new("token", "decode").wrap_raw(err))?;
 
-        let token_data = match decode::<Claims>(
-            token.value(),
-            &DecodingKey::from_secret(key),
-            &Validation::default(),
-        ) {
+        let secret = match header.kid.as_deref().and_then(|kid| self.secret(kid)) {
+            Some(secret) => secret,
+            None => {
+                return Err(Error::new("token", "decode")
+                    .add_context("kid", header.kid.as_deref().unwrap_or("")))
+            }
+        };
+        let key = DecodingKey::from_secret(secret.as_bytes());
+
+        let token_data = match decode::<Claims>(token.value(), &key, &Validation::default()) {
             Ok(data) => data,
             Err(err) => return Err(Error::new("token", "decode").wrap_raw(err)),
         };
@@ -62,10 +98,39 @@ mod tests {
     #[test]
     fn encode_decode() {
         let token_id = TokenId::build("#token01");
-        let enc = JWTEncoder::new();
+        let expires_at = Utc::now() + chrono::Duration::minutes(15);
+        let enc = JWTEncoder::new("key-1", "secret");
 
-        let token = enc.encode(&token_id).unwrap();
+        let token = enc.encode(&token_id, &expires_at).unwrap();
         assert!(token.value().len() > 10);
         assert_eq!(enc.decode(&token).unwrap(), token_id);
+
+        let other_enc = JWTEncoder::new("key-1", "other");
+        assert!(other_enc.decode(&token).is_err());
+
+        let expired = enc
+            .encode(&token_id, &(Utc::now() - chrono::Duration::minutes(5)))
+            .unwrap();
+        assert!(enc.decode(&expired).is_err());
+    }
+
+    #[test]
+    fn rotate_key() {
+        let token_id = TokenId::build("#token01");
+        let expires_at = Utc::now() + chrono::Duration::minutes(15);
+
+        let old_enc = JWTEncoder::new("key-1", "secret-1");
+        let old_token = old_enc.encode(&token_id, &expires_at).unwrap();
+
+        let enc = JWTEncoder::new("key-2", "secret-2").previous_key("key-1", "secret-1");
+        let token = enc.encode(&token_id, &expires_at).unwrap();
+        assert_eq!(enc.decode(&old_token).unwrap(), token_id);
+        assert_eq!(enc.decode(&token).unwrap(), token_id);
+
+        let err = old_enc.decode(&token).err().unwrap();
+        assert_eq!(err.context().get("kid"), Some(&"key-2".to_owned()));
+
+        let enc = JWTEncoder::new("key-2", "secret-2");
+        assert!(enc.decode(&old_token).is_err());
     }
 }
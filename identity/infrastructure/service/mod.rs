@@ -1,4 +1,6 @@
 mod bcrypt_hasher;
 mod jwt_encoder;
+mod oauth_provider;
 pub use bcrypt_hasher::*;
 pub use jwt_encoder::*;
+pub use oauth_provider::*;
@@ -0,0 +1,414 @@
+use async_trait::async_trait;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use reqwest::header::ACCEPT;
+use reqwest::Url;
+use serde::Deserialize;
+
+use common::error::Error;
+use common::result::Result;
+
+use crate::domain::oauth::{ExternalUser, IdentityProvider, Pkce};
+use crate::domain::user::{Email, Fullname, Provider};
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    id_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TokenError {
+    error: String,
+}
+
+/// Claims of the userinfo endpoint and of the ID token. Aliases are the fields returned by
+/// Facebook.
+#[derive(Deserialize)]
+struct UserInfo {
+    #[serde(alias = "id")]
+    sub: String,
+    email: Option<String>,
+    email_verified: Option<bool>,
+    #[serde(alias = "first_name")]
+    given_name: Option<String>,
+    #[serde(alias = "last_name")]
+    family_name: Option<String>,
+}
+
+impl UserInfo {
+    fn into_external_user(self, verified_emails: bool) -> Result<ExternalUser> {
+        let fullname = match (self.given_name, self.family_name) {
+            (Some(name), Some(lastname)) => Fullname::new(name, lastname).ok(),
+            _ => None,
+        };
+
+        ExternalUser::new(
+            self.sub,
+            self.email.map(Email::new).transpose()?,
+            self.email_verified.unwrap_or(verified_emails),
+            fullname,
+        )
+    }
+}
+
+/// Claims of the ID token. `exp` and `aud` are validated when it's decoded.
+#[derive(Deserialize)]
+struct IdTokenClaims {
+    iss: String,
+    nonce: Option<String>,
+    #[serde(flatten)]
+    user_info: UserInfo,
+}
+
+#[derive(Deserialize)]
+struct Jwks {
+    keys: Vec<Jwk>,
+}
+
+/// Public key of the provider. Only RSA keys are used.
+#[derive(Deserialize)]
+struct Jwk {
+    kty: String,
+    kid: Option<String>,
+    n: Option<String>,
+    e: Option<String>,
+}
+
+struct OpenIdConnect {
+    issuers: Vec<String>,
+    jwks_uri: String,
+}
+
+/// OAuthProvider follows the OAuth 2.0 authorization code flow with PKCE. The code is exchanged
+/// in the token endpoint for an access token, which is used to get the user from the userinfo
+/// endpoint. Endpoints can point to a local provider for testing.
+///
+/// With OpenID Connect enabled, the user is taken from the ID token returned with the access
+/// token instead, after checking its signature with the keys of the provider, its issuer,
+/// audience, expiration and nonce.
+pub struct OAuthProvider {
+    provider: Provider,
+    client_id: String,
+    client_secret: String,
+    redirect_uri: String,
+
+    authorization_endpoint: String,
+    token_endpoint: String,
+    userinfo_endpoint: String,
+    scopes: String,
+    verified_emails: bool,
+    openid_connect: Option<OpenIdConnect>,
+
+    client: reqwest::Client,
+}
+
+impl OAuthProvider {
+    pub fn new<S: Into<String>>(
+        provider: Provider,
+        client_id: S,
+        client_secret: S,
+        redirect_uri: S,
+    ) -> Self {
+        OAuthProvider {
+            provider,
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            redirect_uri: redirect_uri.into(),
+            authorization_endpoint: String::new(),
+            token_endpoint: String::new(),
+            userinfo_endpoint: String::new(),
+            scopes: "openid email profile".to_owned(),
+            verified_emails: false,
+            openid_connect: None,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn google<S: Into<String>>(client_id: S, client_secret: S, redirect_uri: S) -> Self {
+        OAuthProvider::new(Provider::Google, client_id, client_secret, redirect_uri)
+            .endpoints(
+                "https://accounts.google.com/o/oauth2/v2/auth",
+                "https://oauth2.googleapis.com/token",
+                "https://openidconnect.googleapis.com/v1/userinfo",
+            )
+            .openid_connect(
+                &["https://accounts.google.com", "accounts.google.com"],
+                "https://www.googleapis.com/oauth2/v3/certs",
+            )
+    }
+
+    /// Facebook only returns verified emails, without the `email_verified` claim. It doesn't
+    /// return an ID token in this flow.
+    pub fn facebook<S: Into<String>>(client_id: S, client_secret: S, redirect_uri: S) -> Self {
+        OAuthProvider::new(Provider::Facebook, client_id, client_secret, redirect_uri)
+            .endpoints(
+                "https://www.facebook.com/v12.0/dialog/oauth",
+                "https://graph.facebook.com/v12.0/oauth/access_token",
+                "https://graph.facebook.com/v12.0/me?fields=id,email,first_name,last_name",
+            )
+            .scopes("email public_profile")
+            .verified_emails()
+    }
+
+    pub fn endpoints<S: Into<String>>(
+        mut self,
+        authorization_endpoint: S,
+        token_endpoint: S,
+        userinfo_endpoint: S,
+    ) -> Self {
+        self.authorization_endpoint = authorization_endpoint.into();
+        self.token_endpoint = token_endpoint.into();
+        self.userinfo_endpoint = userinfo_endpoint.into();
+        self
+    }
+
+    /// Scopes requested to the user, separated by spaces.
+    pub fn scopes<S: Into<String>>(mut self, scopes: S) -> Self {
+        self.scopes = scopes.into();
+        self
+    }
+
+    /// Considers the emails without the `email_verified` claim as verified, for providers that
+    /// don't return unverified emails.
+    pub fn verified_emails(mut self) -> Self {
+        self.verified_emails = true;
+        self
+    }
+
+    /// Requires an ID token issued by one of `issuers` and signed with a key of `jwks_uri`.
+    pub fn openid_connect<S: Into<String>>(mut self, issuers: &[&str], jwks_uri: S) -> Self {
+        self.openid_connect = Some(OpenIdConnect {
+            issuers: issuers.iter().map(|issuer| issuer.to_string()).collect(),
+            jwks_uri: jwks_uri.into(),
+        });
+        self
+    }
+
+    async fn tokens(&self, code: &str, pkce: &Pkce) -> Result<TokenResponse> {
+        let res = self
+            .client
+            .post(&self.token_endpoint)
+            .header(ACCEPT, "application/json")
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", self.redirect_uri.as_str()),
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+                ("code_verifier", pkce.verifier()),
+            ])
+            .send()
+            .await
+            .map_err(|err| Error::new("authorization", "exchange").wrap_raw(err))?;
+
+        // Invalid or expired codes, and verifiers that don't match the challenge.
+        if res.status().is_client_error() {
+            let err = Error::new("authorization", "invalid_grant");
+            return Err(match res.json::<TokenError>().await {
+                Ok(token_err) => err.add_context("error", token_err.error.as_str()),
+                Err(_) => err,
+            });
+        }
+
+        res.error_for_status()
+            .map_err(|err| Error::new("authorization", "exchange").wrap_raw(err))?
+            .json()
+            .await
+            .map_err(|err| Error::new("response", "deserialize").wrap_raw(err))
+    }
+
+    async fn user_info(&self, access_token: &str) -> Result<UserInfo> {
+        self.client
+            .get(&self.userinfo_endpoint)
+            .header(ACCEPT, "application/json")
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|err| Error::new("authorization", "exchange").wrap_raw(err))?
+            .json()
+            .await
+            .map_err(|err| Error::new("response", "deserialize").wrap_raw(err))
+    }
+
+    async fn validate_id_token(
+        &self,
+        openid_connect: &OpenIdConnect,
+        id_token: &str,
+        nonce: &str,
+    ) -> Result<UserInfo> {
+        let err = || Error::new("authorization", "invalid_id_token");
+
+        // The algorithm is fixed, so a token can't choose to be verified as unsigned or with a
+        // shared secret.
+        let header = jsonwebtoken::decode_header(id_token).map_err(|raw| err().wrap_raw(raw))?;
+        if header.alg != Algorithm::RS256 {
+            return Err(err());
+        }
+
+        let jwks: Jwks = self
+            .client
+            .get(&openid_connect.jwks_uri)
+            .header(ACCEPT, "application/json")
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|err| Error::new("authorization", "exchange").wrap_raw(err))?
+            .json()
+            .await
+            .map_err(|err| Error::new("response", "deserialize").wrap_raw(err))?;
+
+        let (n, e) = jwks
+            .keys
+            .iter()
+            .filter(|jwk| jwk.kty == "RSA" && jwk.kid == header.kid)
+            .find_map(|jwk| match (&jwk.n, &jwk.e) {
+                (Some(n), Some(e)) => Some((n, e)),
+                _ => None,
+            })
+            .ok_or_else(err)?;
+
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_audience(&[self.client_id.as_str()]);
+
+        let claims = jsonwebtoken::decode::<IdTokenClaims>(
+            id_token,
+            &DecodingKey::from_rsa_components(n, e),
+            &validation,
+        )
+        .map_err(|raw| err().wrap_raw(raw))?
+        .claims;
+
+        if !openid_connect.issuers.contains(&claims.iss) || claims.nonce.as_deref() != Some(nonce) {
+            return Err(err());
+        }
+
+        Ok(claims.user_info)
+    }
+}
+
+#[async_trait]
+impl IdentityProvider for OAuthProvider {
+    fn provider(&self) -> Provider {
+        self.provider.clone()
+    }
+
+    fn authorization_url(&self, state: &str, nonce: &str, pkce: &Pkce) -> Result<String> {
+        let challenge = pkce.challenge();
+        let mut params = vec![
+            ("response_type", "code"),
+            ("client_id", self.client_id.as_str()),
+            ("redirect_uri", self.redirect_uri.as_str()),
+            ("scope", self.scopes.as_str()),
+            ("state", state),
+            ("code_challenge", challenge.as_str()),
+            ("code_challenge_method", pkce.method()),
+        ];
+        if self.openid_connect.is_some() {
+            params.push(("nonce", nonce));
+        }
+
+        let url = Url::parse_with_params(&self.authorization_endpoint, &params).map_err(|err| {
+            Error::internal("provider", "invalid_url")
+                .add_context("provider", self.provider.to_string().as_str())
+                .wrap_raw(err)
+        })?;
+
+        Ok(url.to_string())
+    }
+
+    async fn exchange(&self, code: &str, nonce: &str, pkce: &Pkce) -> Result<ExternalUser> {
+        let tokens = self.tokens(code, pkce).await?;
+
+        let user_info = match &self.openid_connect {
+            Some(openid_connect) => {
+                let id_token = tokens
+                    .id_token
+                    .ok_or_else(|| Error::new("authorization", "invalid_id_token"))?;
+                self.validate_id_token(openid_connect, &id_token, nonce)
+                    .await?
+            }
+            None => self.user_info(&tokens.access_token).await?,
+        };
+
+        user_info.into_external_user(self.verified_emails)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn authorization_url() {
+        let idp = OAuthProvider::new(
+            Provider::Google,
+            "omics-app",
+            "s3cr3t",
+            "http://localhost:4000/auth/google",
+        )
+        .endpoints(
+            "http://localhost:8080/authorize",
+            "http://localhost:8080/token",
+            "http://localhost:8080/userinfo",
+        );
+        let pkce = Pkce::new();
+
+        let url = Url::parse(&idp.authorization_url("state01", "nonce01", &pkce).unwrap()).unwrap();
+        assert_eq!(url.path(), "/authorize");
+
+        let params: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+        let param = |name: &str| {
+            params
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(param("client_id"), Some("omics-app"));
+        assert_eq!(
+            param("redirect_uri"),
+            Some("http://localhost:4000/auth/google")
+        );
+        assert_eq!(param("scope"), Some("openid email profile"));
+        assert_eq!(param("state"), Some("state01"));
+        assert_eq!(param("code_challenge"), Some(pkce.challenge().as_str()));
+        assert_eq!(param("code_challenge_method"), Some("S256"));
+        assert!(param("client_secret").is_none());
+        assert!(param("nonce").is_none());
+
+        let idp = idp.openid_connect(&["http://localhost:8080"], "http://localhost:8080/jwks");
+        let url = Url::parse(&idp.authorization_url("state01", "nonce01", &pkce).unwrap()).unwrap();
+        let params: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+        assert!(params.contains(&("nonce".to_owned(), "nonce01".to_owned())));
+
+        let idp = OAuthProvider::new(Provider::Google, "omics-app", "s3cr3t", "");
+        assert!(idp.authorization_url("state01", "nonce01", &pkce).is_err());
+    }
+
+    #[tokio::test]
+    async fn reject_id_tokens_not_signed_with_rsa() {
+        let idp = OAuthProvider::new(Provider::Google, "omics-app", "s3cr3t", "")
+            .openid_connect(&["http://localhost:8080"], "http://localhost:8080/jwks");
+        let openid_connect = idp.openid_connect.as_ref().unwrap();
+
+        let id_token = jsonwebtoken::encode(
+            &jsonwebtoken::Header::default(),
+            &serde_json::json!({
+                "iss": "http://localhost:8080",
+                "aud": "omics-app",
+                "sub": "google-1",
+                "nonce": "nonce01",
+                "exp": 4102444800u64,
+            }),
+            &jsonwebtoken::EncodingKey::from_secret(b"s3cr3t"),
+        )
+        .unwrap();
+
+        let err = idp
+            .validate_id_token(openid_connect, &id_token, "nonce01")
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "invalid_id_token");
+    }
+}
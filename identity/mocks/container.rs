@@ -1,22 +1,40 @@
 use std::sync::Arc;
+use std::time::Duration;
 
+use common::infrastructure::cache::InMemCache;
+use common::infrastructure::transaction::InMemUnitOfWork;
 use common::mocks::FakeEventPublisher;
 
 use crate::container::IdentityContainer;
+use crate::domain::user::Provider;
 use crate::infrastructure::persistence::inmem::{
-    InMemPermissionRepository, InMemRoleRepository, InMemTokenRepository, InMemUserRepository,
+    InMemAttemptRepository, InMemExternalIdentityRepository, InMemPermissionRepository,
+    InMemRoleRepository, InMemTokenRepository, InMemUserRepository,
 };
 
-use crate::mocks::{FakePasswordHasher, FakeTokenEncoder};
+use crate::mocks::{
+    FakeEmailChangeSender, FakeIdentityProvider, FakePasswordHasher, FakePasswordResetSender,
+    FakeTokenEncoder,
+};
 
 pub fn container() -> IdentityContainer<FakeEventPublisher> {
     IdentityContainer::new(
         Arc::new(FakeEventPublisher::new()),
+        Arc::new(InMemAttemptRepository::new()),
+        Arc::new(InMemCache::new()),
+        Arc::new(InMemExternalIdentityRepository::new()),
+        Arc::new(InMemCache::new()),
         Arc::new(InMemPermissionRepository::new()),
         Arc::new(InMemRoleRepository::new()),
         Arc::new(InMemTokenRepository::new()),
         Arc::new(InMemUserRepository::new()),
+        Arc::new(FakeEmailChangeSender::new()),
         Arc::new(FakePasswordHasher::new()),
+        Arc::new(FakePasswordResetSender::new()),
         Arc::new(FakeTokenEncoder::new()),
+        vec![Arc::new(FakeIdentityProvider::new(Provider::Google))],
+        Arc::new(InMemUnitOfWork::new()),
+        Duration::from_secs(15 * 60),
+        Duration::from_secs(30 * 24 * 60 * 60),
     )
 }
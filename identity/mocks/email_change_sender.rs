@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+
+use common::result::Result;
+
+use crate::domain::user::{EmailChangeSender, User};
+
+/// FakeEmailChangeSender discards the codes, which tests get from `UserService` directly.
+#[derive(Default)]
+pub struct FakeEmailChangeSender;
+
+impl FakeEmailChangeSender {
+    pub fn new() -> Self {
+        FakeEmailChangeSender
+    }
+}
+
+#[async_trait]
+impl EmailChangeSender for FakeEmailChangeSender {
+    async fn send(&self, _user: &User, _code: &str) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+use async_trait::async_trait;
+
+use common::error::Error;
+use common::result::Result;
+
+use crate::domain::oauth::{ExternalUser, IdentityProvider, Pkce};
+use crate::domain::user::{Email, Provider};
+
+/// FakeIdentityProvider is a local identity provider where users authorize the application
+/// with any account. Its codes contain the account, and the PKCE challenge and the nonce of the
+/// authorization, so they can be exchanged by any instance.
+pub struct FakeIdentityProvider {
+    provider: Provider,
+}
+
+impl FakeIdentityProvider {
+    pub fn new(provider: Provider) -> Self {
+        FakeIdentityProvider { provider }
+    }
+
+    /// Authorizes the application as the given account from the authorization URL the user was
+    /// redirected to. Returns the state and the code the provider redirects back with.
+    pub fn login(
+        &self,
+        authorization_url: &str,
+        subject: &str,
+        email: &str,
+    ) -> Result<(String, String)> {
+        let param = |name: &str| {
+            authorization_url
+                .splitn(2, '?')
+                .nth(1)
+                .unwrap_or("")
+                .split('&')
+                .find_map(|param| {
+                    let mut param = param.splitn(2, '=');
+                    match (param.next(), param.next()) {
+                        (Some(k), Some(v)) if k == name => Some(v.to_owned()),
+                        _ => None,
+                    }
+                })
+                .ok_or_else(|| Error::internal("fake_identity_provider", "invalid_url"))
+        };
+
+        let code = format!(
+            "{}|{}|{}|{}",
+            param("code_challenge")?,
+            param("nonce")?,
+            subject,
+            email
+        );
+        Ok((param("state")?, code))
+    }
+}
+
+#[async_trait]
+impl IdentityProvider for FakeIdentityProvider {
+    fn provider(&self) -> Provider {
+        self.provider.clone()
+    }
+
+    fn authorization_url(&self, state: &str, nonce: &str, pkce: &Pkce) -> Result<String> {
+        Ok(format!(
+            "http://localhost/{}/authorize?state={}&nonce={}&code_challenge={}\
+            &code_challenge_method={}",
+            self.provider.to_string(),
+            state,
+            nonce,
+            pkce.challenge(),
+            pkce.method(),
+        ))
+    }
+
+    async fn exchange(&self, code: &str, nonce: &str, pkce: &Pkce) -> Result<ExternalUser> {
+        let mut parts = code.splitn(4, '|');
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(challenge), Some(code_nonce), Some(subject), Some(email))
+                if challenge == pkce.challenge() && code_nonce == nonce =>
+            {
+                ExternalUser::new(subject, Some(Email::new(email)?), true, None)
+            }
+            _ => Err(Error::new("authorization", "invalid_grant")),
+        }
+    }
+}
@@ -1,10 +1,16 @@
 mod container;
 mod domain;
+mod email_change_sender;
+mod identity_provider;
 mod password_hasher;
+mod password_reset_sender;
 mod populate;
 mod token_encoder;
 pub use self::domain::*;
 pub use container::*;
+pub use email_change_sender::*;
+pub use identity_provider::*;
 pub use password_hasher::*;
+pub use password_reset_sender::*;
 pub use populate::*;
 pub use token_encoder::*;
@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+
+use common::result::Result;
+
+use crate::domain::user::{PasswordResetSender, User};
+
+/// FakePasswordResetSender discards the tokens, which tests get from `User` directly.
+#[derive(Default)]
+pub struct FakePasswordResetSender;
+
+impl FakePasswordResetSender {
+    pub fn new() -> Self {
+        FakePasswordResetSender
+    }
+}
+
+#[async_trait]
+impl PasswordResetSender for FakePasswordResetSender {
+    async fn send(&self, _user: &User, _token: &str) -> Result<()> {
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+use chrono::{DateTime, Utc};
+
 use common::error::Error;
 use common::result::Result;
 
@@ -13,7 +15,7 @@ impl FakeTokenEncoder {
 }
 
 impl TokenEncoder for FakeTokenEncoder {
-    fn encode(&self, token_id: &TokenId) -> Result<Token> {
+    fn encode(&self, token_id: &TokenId, _expires_at: &DateTime<Utc>) -> Result<Token> {
         Ok(Token::new(&format!("<<token::{}", token_id.id())))
     }
 
@@ -35,7 +37,7 @@ mod tests {
         let enc = FakeTokenEncoder::new();
 
         assert_eq!(
-            enc.encode(&TokenId::from("t007"))?,
+            enc.encode(&TokenId::from("t007"), &Utc::now())?,
             Token::new("<<token::t007")
         );
         assert_eq!(
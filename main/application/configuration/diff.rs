@@ -0,0 +1,36 @@
+use serde::Deserialize;
+
+use common::config::{BusinessRulesChange, ConfigService};
+use common::error::Error;
+use common::result::Result;
+
+use identity::UserIdAndRole;
+
+#[derive(Deserialize)]
+pub struct DiffCommand {
+    pub from: u32,
+    pub to: Option<u32>,
+}
+
+pub struct Diff<'a> {
+    config_serv: &'a ConfigService,
+}
+
+impl<'a> Diff<'a> {
+    pub fn new(config_serv: &'a ConfigService) -> Self {
+        Diff { config_serv }
+    }
+
+    /// Without `to`, compares against the rules in force.
+    pub async fn exec(
+        &self,
+        (_auth_id, auth_role): UserIdAndRole,
+        cmd: DiffCommand,
+    ) -> Result<Vec<BusinessRulesChange>> {
+        if !auth_role.can("change_business_rules") {
+            return Err(Error::unauthorized());
+        }
+
+        self.config_serv.diff(cmd.from, cmd.to).await
+    }
+}
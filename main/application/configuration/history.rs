@@ -0,0 +1,26 @@
+use common::config::{BusinessRulesVersion, ConfigService};
+use common::error::Error;
+use common::result::Result;
+
+use identity::UserIdAndRole;
+
+pub struct History<'a> {
+    config_serv: &'a ConfigService,
+}
+
+impl<'a> History<'a> {
+    pub fn new(config_serv: &'a ConfigService) -> Self {
+        History { config_serv }
+    }
+
+    pub async fn exec(
+        &self,
+        (_auth_id, auth_role): UserIdAndRole,
+    ) -> Result<Vec<BusinessRulesVersion>> {
+        if !auth_role.can("change_business_rules") {
+            return Err(Error::unauthorized());
+        }
+
+        self.config_serv.history().await
+    }
+}
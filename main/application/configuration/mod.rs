@@ -1,4 +1,8 @@
+mod diff;
 mod get;
+mod history;
 mod update;
+pub use diff::*;
 pub use get::*;
+pub use history::*;
 pub use update::*;
@@ -1,11 +1,18 @@
-use common::config::ConfigService;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use common::config::{BusinessRules, BusinessRulesVersion, ConfigService};
 use common::error::Error;
-use common::request::CommandResponse;
 use common::result::Result;
 
 use identity::UserIdAndRole;
 
-use common::config::BusinessRules;
+#[derive(Deserialize)]
+pub struct UpdateCommand {
+    #[serde(flatten)]
+    pub rules: BusinessRules,
+    pub effective_from: Option<DateTime<Utc>>,
+}
 
 pub struct Update<'a> {
     config_serv: &'a ConfigService,
@@ -18,15 +25,15 @@ impl<'a> Update<'a> {
 
     pub async fn exec(
         &self,
-        (_auth_id, auth_role): UserIdAndRole,
-        cmd: BusinessRules,
-    ) -> Result<CommandResponse> {
+        (auth_id, auth_role): UserIdAndRole,
+        cmd: UpdateCommand,
+    ) -> Result<BusinessRulesVersion> {
         if !auth_role.can("change_business_rules") {
             return Err(Error::unauthorized());
         }
 
-        self.config_serv.save_business_rules(cmd).await?;
-
-        Ok(CommandResponse::default())
+        self.config_serv
+            .save_business_rules(cmd.rules, Some(auth_id.to_string()), cmd.effective_from)
+            .await
     }
 }
@@ -1,4 +1,7 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use common::event::DeadLetter;
 
 #[derive(Serialize, Deserialize)]
 pub struct ConfigurationDto {
@@ -9,3 +12,44 @@ pub struct ConfigurationDto {
     pub minimum_views_percentage_to_require_contract: f64,
     pub subscription_percentage_retention: f64,
 }
+
+#[derive(Serialize)]
+pub struct DeadLetterDto {
+    pub id: String,
+    pub event_id: String,
+    pub topic: String,
+    pub code: String,
+    pub version: u32,
+    pub timestamp: String,
+    pub payload: Value,
+    pub correlation_id: Option<String>,
+    pub causation_id: Option<String>,
+    pub actor_id: Option<String>,
+    pub handler_topic: String,
+    pub errors: Vec<String>,
+    pub attempts: u32,
+    pub failed_at: String,
+}
+
+impl From<&DeadLetter> for DeadLetterDto {
+    fn from(dead_letter: &DeadLetter) -> Self {
+        let event = dead_letter.event();
+
+        DeadLetterDto {
+            id: dead_letter.id().to_string(),
+            event_id: event.id().to_string(),
+            topic: event.topic().to_string(),
+            code: event.code().to_string(),
+            version: event.version(),
+            timestamp: event.timestamp().to_rfc3339(),
+            payload: event.payload(),
+            correlation_id: event.correlation_id().map(ToOwned::to_owned),
+            causation_id: event.causation_id().map(ToOwned::to_owned),
+            actor_id: event.actor_id().map(ToOwned::to_owned),
+            handler_topic: dead_letter.handler_topic().to_string(),
+            errors: dead_letter.errors().to_vec(),
+            attempts: dead_letter.attempts(),
+            failed_at: dead_letter.failed_at().to_rfc3339(),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use common::error::Error;
+use common::event::{DeadLetterId, DeadLetterRepository};
+use common::request::CommandResponse;
+use common::result::Result;
+use identity::UserIdAndRole;
+
+pub struct DiscardDeadLetter<'a> {
+    dead_letter_repo: &'a dyn DeadLetterRepository,
+}
+
+impl<'a> DiscardDeadLetter<'a> {
+    pub fn new(dead_letter_repo: &'a dyn DeadLetterRepository) -> Self {
+        DiscardDeadLetter { dead_letter_repo }
+    }
+
+    pub async fn exec(
+        &self,
+        (_auth_id, auth_role): UserIdAndRole,
+        dead_letter_id: String,
+    ) -> Result<CommandResponse> {
+        if !auth_role.can("manage_events") {
+            return Err(Error::unauthorized());
+        }
+
+        let dead_letter_id = DeadLetterId::new(dead_letter_id)?;
+        let dead_letter = self.dead_letter_repo.find_by_id(&dead_letter_id).await?;
+
+        self.dead_letter_repo.delete(dead_letter.id()).await?;
+
+        Ok(CommandResponse::default())
+    }
+}
@@ -0,0 +1,26 @@
+use common::error::Error;
+use common::event::DeadLetterRepository;
+use common::result::Result;
+use identity::UserIdAndRole;
+
+use crate::application::dtos::DeadLetterDto;
+
+pub struct ListDeadLetters<'a> {
+    dead_letter_repo: &'a dyn DeadLetterRepository,
+}
+
+impl<'a> ListDeadLetters<'a> {
+    pub fn new(dead_letter_repo: &'a dyn DeadLetterRepository) -> Self {
+        ListDeadLetters { dead_letter_repo }
+    }
+
+    pub async fn exec(&self, (_auth_id, auth_role): UserIdAndRole) -> Result<Vec<DeadLetterDto>> {
+        if !auth_role.can("manage_events") {
+            return Err(Error::unauthorized());
+        }
+
+        let dead_letters = self.dead_letter_repo.find_all().await?;
+
+        Ok(dead_letters.iter().map(DeadLetterDto::from).collect())
+    }
+}
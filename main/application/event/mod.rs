@@ -0,0 +1,8 @@
+mod discard_dead_letter;
+mod list_dead_letters;
+mod replay;
+mod replay_dead_letter;
+pub use discard_dead_letter::*;
+pub use list_dead_letters::*;
+pub use replay::*;
+pub use replay_dead_letter::*;
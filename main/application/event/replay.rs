@@ -0,0 +1,93 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use common::error::Error;
+use common::event::{EventHandler, EventReplayer, ReplayOptions, ReplayReport};
+use common::result::Result;
+use identity::UserIdAndRole;
+
+#[derive(Deserialize)]
+pub struct ReplayCommand {
+    pub handlers: Vec<String>,
+    pub topic: Option<String>,
+    pub code: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub dry_run: Option<bool>,
+}
+
+impl ReplayCommand {
+    pub fn options(&self) -> ReplayOptions {
+        ReplayOptions {
+            topic: self.topic.clone(),
+            code: self.code.clone(),
+            from: self.from,
+            to: self.to,
+            dry_run: self.dry_run.unwrap_or(false),
+        }
+    }
+}
+
+/// Picks the named handlers from the ones available for replay.
+pub fn select_handlers(
+    available: Vec<(&'static str, Box<dyn EventHandler>)>,
+    names: &[String],
+) -> Result<Vec<Box<dyn EventHandler>>> {
+    if names.is_empty() {
+        return Err(Error::new("replay", "missing_handlers"));
+    }
+
+    for name in names.iter() {
+        if !available
+            .iter()
+            .any(|(available_name, _)| *available_name == name.as_str())
+        {
+            return Err(Error::new("replay", "unknown_handler")
+                .set_message(format!("Unknown handler: {}", name)));
+        }
+    }
+
+    Ok(available
+        .into_iter()
+        .filter(|(name, _)| names.iter().any(|n| n.as_str() == *name))
+        .map(|(_, handler)| handler)
+        .collect())
+}
+
+pub struct Replay<'a> {
+    event_replayer: &'a EventReplayer,
+    available_handlers: Vec<(&'static str, Box<dyn EventHandler>)>,
+}
+
+impl<'a> Replay<'a> {
+    pub fn new(
+        event_replayer: &'a EventReplayer,
+        available_handlers: Vec<(&'static str, Box<dyn EventHandler>)>,
+    ) -> Self {
+        Replay {
+            event_replayer,
+            available_handlers,
+        }
+    }
+
+    pub async fn exec(
+        self,
+        (_auth_id, auth_role): UserIdAndRole,
+        cmd: ReplayCommand,
+    ) -> Result<ReplayReport> {
+        if !auth_role.can("manage_events") {
+            return Err(Error::unauthorized());
+        }
+
+        let mut handlers = select_handlers(self.available_handlers, &cmd.handlers)?;
+
+        self.event_replayer
+            .replay(&mut handlers, &cmd.options(), |report| {
+                println!(
+                    "[REPLAY] {} events, {} ok, {} errors",
+                    report.events, report.ok_handlers, report.err_handlers
+                )
+            })
+            .await
+    }
+}
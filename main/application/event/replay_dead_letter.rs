@@ -0,0 +1,57 @@
+use serde::Serialize;
+
+use common::error::Error;
+use common::event::{DeadLetterId, DeadLetterRepository};
+use common::result::Result;
+use identity::UserIdAndRole;
+
+use crate::infrastructure::EventBus;
+
+#[derive(Serialize)]
+pub struct ReplayDeadLetterResponse {
+    pub ok_handlers: u32,
+    pub err_handlers: u32,
+}
+
+pub struct ReplayDeadLetter<'a> {
+    event_bus: &'a EventBus,
+    dead_letter_repo: &'a dyn DeadLetterRepository,
+}
+
+impl<'a> ReplayDeadLetter<'a> {
+    pub fn new(event_bus: &'a EventBus, dead_letter_repo: &'a dyn DeadLetterRepository) -> Self {
+        ReplayDeadLetter {
+            event_bus,
+            dead_letter_repo,
+        }
+    }
+
+    pub async fn exec(
+        &self,
+        (_auth_id, auth_role): UserIdAndRole,
+        dead_letter_id: String,
+    ) -> Result<ReplayDeadLetterResponse> {
+        if !auth_role.can("manage_events") {
+            return Err(Error::unauthorized());
+        }
+
+        let dead_letter_id = DeadLetterId::new(dead_letter_id)?;
+        let dead_letter = self.dead_letter_repo.find_by_id(&dead_letter_id).await?;
+
+        // The dead letter is removed before replaying: if the event fails again the bus stores
+        // a new one with the updated errors.
+        self.dead_letter_repo.delete(dead_letter.id()).await?;
+
+        let res = self
+            .event_bus
+            .replay(&dead_letter)
+            .await?
+            .await
+            .map_err(|err| Error::internal("dead_letter", "replay").wrap_raw(err))?;
+
+        Ok(ReplayDeadLetterResponse {
+            ok_handlers: res.ok_handlers(),
+            err_handlers: res.err_handlers(),
+        })
+    }
+}
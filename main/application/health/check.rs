@@ -0,0 +1,32 @@
+use serde::Serialize;
+
+use common::infrastructure::postgres::{PoolStatus, PostgresPool};
+use common::result::Result;
+
+#[derive(Serialize)]
+pub struct CheckResponse {
+    pub healthy: bool,
+    pub postgres: PoolStatus,
+}
+
+pub struct Check<'a> {
+    postgres_pool: &'a PostgresPool,
+}
+
+impl<'a> Check<'a> {
+    pub fn new(postgres_pool: &'a PostgresPool) -> Self {
+        Check { postgres_pool }
+    }
+
+    pub async fn exec(&self) -> Result<CheckResponse> {
+        let healthy = match self.postgres_pool.get().await {
+            Ok(client) => client.execute("SELECT 1", &[]).await.is_ok(),
+            Err(_) => false,
+        };
+
+        Ok(CheckResponse {
+            healthy,
+            postgres: self.postgres_pool.status(),
+        })
+    }
+}
@@ -0,0 +1,2 @@
+mod check;
+pub use check::*;
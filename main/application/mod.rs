@@ -1,3 +1,5 @@
 pub mod backup;
 pub mod configuration;
 pub mod dtos;
+pub mod event;
+pub mod health;
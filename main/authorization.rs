@@ -1,14 +1,20 @@
-use actix_web::HttpRequest;
+use std::net::IpAddr;
 
+use actix_web::cookie::{Cookie, SameSite};
+use actix_web::{http, HttpMessage, HttpRequest};
+
+use common::config::Config;
 use common::error::Error;
-use identity::domain::token::Token;
+use common::event::EventMetadata;
+use identity::domain::token::{Device, Token};
 use identity::domain::user::UserId;
 use identity::UserIdAndRole;
 
 use crate::container::MainContainer;
 use crate::error::PublicError;
 
-pub async fn auth(req: &HttpRequest, c: &MainContainer) -> Result<UserIdAndRole, PublicError> {
+/// Token of the `Authorization` header.
+pub fn token(req: &HttpRequest) -> Result<Token, PublicError> {
     let auth_header = match req.headers().get("authorization") {
         Some(header) => {
             if let Ok(header) = header.to_str() {
@@ -21,7 +27,11 @@ pub async fn auth(req: &HttpRequest, c: &MainContainer) -> Result<UserIdAndRole,
     }
     .map_err(PublicError::from)?;
 
-    let token = extract_token(auth_header).map_err(PublicError::from)?;
+    extract_token(auth_header).map_err(PublicError::from)
+}
+
+pub async fn auth(req: &HttpRequest, c: &MainContainer) -> Result<UserIdAndRole, PublicError> {
+    let token = token(req)?;
 
     let user_id = c
         .identity
@@ -32,6 +42,9 @@ pub async fn auth(req: &HttpRequest, c: &MainContainer) -> Result<UserIdAndRole,
 
     let user_id = UserId::new(user_id)?;
 
+    // Events published while handling the request are performed by the authenticated user.
+    EventMetadata::set_current_actor(user_id.value());
+
     let role = c
         .identity
         .role_repo()
@@ -42,6 +55,73 @@ pub async fn auth(req: &HttpRequest, c: &MainContainer) -> Result<UserIdAndRole,
     Ok((user_id, role))
 }
 
+/// Device the request comes from, shown in the sessions of the user. Its IP is the address of
+/// the peer, unless the peer is a trusted proxy (see `client_ip`).
+pub fn device(req: &HttpRequest, config: &Config) -> Device {
+    let user_agent = req
+        .headers()
+        .get(http::header::USER_AGENT)
+        .and_then(|header| header.to_str().ok())
+        .map(str::to_owned);
+
+    let ip = client_ip(
+        req.peer_addr().map(|addr| addr.ip()),
+        req.headers()
+            .get("x-forwarded-for")
+            .and_then(|header| header.to_str().ok()),
+        &config.trusted_proxies(),
+    )
+    .map(|ip| ip.to_string());
+
+    Device::new(user_agent, ip)
+}
+
+/// IP of the client. `X-Forwarded-For` can be sent by any client, so it's only read while the
+/// address is a trusted proxy: each proxy appends the address it received the request from, and
+/// the last one that is not a trusted proxy is the client.
+fn client_ip(
+    peer: Option<IpAddr>,
+    forwarded_for: Option<&str>,
+    trusted_proxies: &[IpAddr],
+) -> Option<IpAddr> {
+    let mut ip = peer?;
+
+    if let Some(forwarded_for) = forwarded_for {
+        for addr in forwarded_for.rsplit(',') {
+            if !trusted_proxies.contains(&ip) {
+                break;
+            }
+
+            match addr.trim().parse() {
+                Ok(addr) => ip = addr,
+                Err(_) => break,
+            }
+        }
+    }
+
+    Some(ip)
+}
+
+const BROWSER_KEY_COOKIE: &str = "oauth_browser_key";
+
+/// Cookie with the key that binds an authorization with a provider to the browser that started
+/// it. The callback is only accepted from the same browser.
+pub fn browser_key_cookie(browser_key: String) -> Cookie<'static> {
+    Cookie::build(BROWSER_KEY_COOKIE, browser_key)
+        .path("/")
+        .http_only(true)
+        .secure(true)
+        .same_site(SameSite::None)
+        .finish()
+}
+
+/// Key of the authorization with a provider, empty if the cookie was not sent.
+pub fn browser_key(req: &HttpRequest) -> String {
+    req.cookie(BROWSER_KEY_COOKIE)
+        .map(|cookie| cookie.value().to_owned())
+        .unwrap_or_default()
+}
+
 fn extract_token<S: Into<String>>(authorization: S) -> Result<Token, Error> {
     let authorization = authorization.into();
 
@@ -60,6 +140,10 @@ fn extract_token<S: Into<String>>(authorization: S) -> Result<Token, Error> {
 mod tests {
     use super::*;
 
+    use std::net::SocketAddr;
+
+    use actix_web::test::TestRequest;
+
     #[test]
     fn valid_token() {
         let token = extract_token("Bearer token#123").unwrap();
@@ -70,4 +154,57 @@ mod tests {
     fn invalid_token() {
         assert!(extract_token("token#123").is_err());
     }
+
+    #[test]
+    fn browser_key_from_cookie() {
+        let req = TestRequest::default()
+            .cookie(browser_key_cookie("key01".to_owned()))
+            .to_http_request();
+        assert_eq!(browser_key(&req), "key01");
+
+        assert_eq!(browser_key(&TestRequest::default().to_http_request()), "");
+    }
+
+    #[test]
+    fn spoofed_forwarded_for() {
+        let peer: IpAddr = "203.0.113.7".parse().unwrap();
+
+        // Without trusted proxies the header is ignored, so every spoofed value is limited as the
+        // same client.
+        for spoofed in ["1.1.1.1", "2.2.2.2", "198.51.100.1, 10.0.0.1"].iter() {
+            let req = TestRequest::default()
+                .peer_addr(SocketAddr::new(peer, 40000))
+                .header("x-forwarded-for", *spoofed)
+                .to_http_request();
+            let device = device(&req, &Config::default());
+            assert_eq!(device.client(), "203.0.113.7");
+        }
+
+        assert_eq!(client_ip(Some(peer), Some("1.1.1.1"), &[]), Some(peer));
+        assert_eq!(client_ip(None, Some("1.1.1.1"), &[]), None);
+    }
+
+    #[test]
+    fn forwarded_by_trusted_proxies() {
+        let proxies: Vec<IpAddr> = vec!["10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap()];
+        let client: IpAddr = "198.51.100.1".parse().unwrap();
+
+        assert_eq!(
+            client_ip(Some(proxies[0]), Some("198.51.100.1"), &proxies),
+            Some(client)
+        );
+        // Addresses added by the client before the first untrusted one are ignored.
+        assert_eq!(
+            client_ip(
+                Some(proxies[0]),
+                Some("1.1.1.1, 198.51.100.1, 10.0.0.2"),
+                &proxies
+            ),
+            Some(client)
+        );
+        assert_eq!(
+            client_ip(Some(proxies[0]), Some("invalid"), &proxies),
+            Some(proxies[0])
+        );
+    }
 }
@@ -0,0 +1,121 @@
+use chrono::{DateTime, Utc};
+
+use common::error::Error;
+use common::result::Result;
+
+use crate::application::event::{select_handlers, ReplayCommand};
+use crate::container::MainContainer;
+
+const REPLAY_USAGE: &str = "Usage: omics replay --handlers <name,...> [--topic <topic>] \
+    [--code <code>] [--from <rfc3339>] [--to <rfc3339>] [--dry-run] [--list]";
+
+/// Runs the subcommand given in `args` (without the binary name). Returns false if there is
+/// no subcommand and the server has to be started.
+pub async fn run(c: &MainContainer, args: &[String]) -> Result<bool> {
+    match args.first().map(String::as_str) {
+        None => Ok(false),
+        Some("replay") => {
+            replay(c, &args[1..]).await?;
+            Ok(true)
+        }
+        Some("purge") => {
+            purge(c).await?;
+            Ok(true)
+        }
+        Some(subcommand) => Err(Error::new("cli", "unknown_subcommand")
+            .set_message(format!("Unknown subcommand: {}", subcommand))),
+    }
+}
+
+async fn replay(c: &MainContainer, args: &[String]) -> Result<()> {
+    let mut cmd = ReplayCommand {
+        handlers: Vec::new(),
+        topic: None,
+        code: None,
+        from: None,
+        to: None,
+        dry_run: None,
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--list" => {
+                for (name, handler) in c.replay_handlers().iter() {
+                    println!("{} ({})", name, handler.topic());
+                }
+                return Ok(());
+            }
+            "--dry-run" => cmd.dry_run = Some(true),
+            "--handlers" => {
+                cmd.handlers = value(&mut args, arg)?
+                    .split(',')
+                    .map(|name| name.trim().to_owned())
+                    .filter(|name| !name.is_empty())
+                    .collect()
+            }
+            "--topic" => cmd.topic = Some(value(&mut args, arg)?.to_owned()),
+            "--code" => cmd.code = Some(value(&mut args, arg)?.to_owned()),
+            "--from" => cmd.from = Some(date(value(&mut args, arg)?)?),
+            "--to" => cmd.to = Some(date(value(&mut args, arg)?)?),
+            _ => {
+                return Err(Error::new("cli", "invalid_argument")
+                    .set_message(format!("Unknown argument: {}\n{}", arg, REPLAY_USAGE)))
+            }
+        }
+    }
+
+    if cmd.handlers.is_empty() {
+        println!("{}", REPLAY_USAGE);
+    }
+
+    let mut handlers = select_handlers(c.replay_handlers(), &cmd.handlers)?;
+
+    let report = c
+        .event_replayer()
+        .replay(&mut handlers, &cmd.options(), |report| {
+            println!(
+                "{} events, {} handlers activated, {} ok, {} errors",
+                report.events, report.activated_handlers, report.ok_handlers, report.err_handlers
+            )
+        })
+        .await?;
+
+    for err in report.errors.iter() {
+        println!("Error: {}", err);
+    }
+
+    if report.dry_run {
+        println!("Dry run: no handler was executed");
+    }
+
+    Ok(())
+}
+
+/// Purges the records deleted before the retention period without waiting for the server to do
+/// it.
+async fn purge(c: &MainContainer) -> Result<()> {
+    let purged = c.retention().purge().await?;
+    println!(
+        "{} records deleted more than {} days ago purged",
+        purged,
+        c.retention().period().num_days()
+    );
+
+    Ok(())
+}
+
+fn value<'a, I>(args: &mut I, arg: &str) -> Result<&'a str>
+where
+    I: Iterator<Item = &'a String>,
+{
+    args.next().map(String::as_str).ok_or_else(|| {
+        Error::new("cli", "missing_value").set_message(format!("Missing value for {}", arg))
+    })
+}
+
+fn date(value: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|date| date.with_timezone(&Utc))
+        .map_err(|err| Error::new("cli", "invalid_date").wrap_raw(err))
+}
@@ -1,23 +1,33 @@
 use std::sync::Arc;
-
-use tokio_postgres::NoTls;
+use std::time::Duration;
 
 use common::config::Config;
 use common::config::ConfigService;
 use common::container::Container;
-use common::event::EventSubscriber;
-use common::infrastructure::cache::PostgresCache;
-use common::infrastructure::event::{InMemEventBus, PostgresEventRepository};
+use common::event::{EventHandler, EventReplayer, EventSubscriber, RetryPolicy};
+use common::infrastructure::cache::{CacheSweeper, PostgresCache};
+use common::infrastructure::config::PostgresBusinessRulesRepository;
+use common::infrastructure::event::{
+    InMemEventBus, OutboxRelay, PostgresDeadLetterRepository, PostgresEventBus,
+    PostgresEventRepository, PostgresOutbox,
+};
+use common::infrastructure::postgres::{PostgresClient, PostgresPool};
+use common::infrastructure::transaction::PostgresUnitOfWork;
+use common::request::CursorSigner;
 use common::result::Result;
+use common::retention::Retention;
 use identity::container::IdentityContainer;
-use identity::infrastructure::persistence::inmem::InMemTokenRepository;
+use identity::domain::oauth::IdentityProvider;
 use identity::infrastructure::persistence::postgres::{
-    PostgresPermissionRepository, PostgresRoleRepository, PostgresUserRepository,
+    PostgresAttemptRepository, PostgresExternalIdentityRepository, PostgresPermissionRepository,
+    PostgresRoleRepository, PostgresTokenRepository, PostgresUserRepository,
 };
-use identity::infrastructure::service::{BcryptHasher, JWTEncoder};
+use identity::infrastructure::service::{BcryptHasher, JWTEncoder, OAuthProvider};
 use notification::container::NotificationContainer;
 use notification::infrastructure::persistence::postgres::PostgresNotificationRepository;
-use notification::infrastructure::service::GmailService;
+use notification::infrastructure::service::{
+    EmailEmailChangeSender, EmailPasswordResetSender, GmailService,
+};
 use payment::container::PaymentContainer;
 use payment::domain::payment::PaymentService;
 use payment::infrastructure::persistence::postgres::{
@@ -32,55 +42,92 @@ use publishing::infrastructure::persistence::postgres::{
 };
 
 use crate::development::EventLogger;
+use crate::infrastructure::{EventBus, EventStream, EventStreamHandler};
 
 pub struct MainContainer {
-    pub event_bus: Arc<InMemEventBus>,
+    pub config: Config,
+    pub postgres_pool: PostgresPool,
+    pub event_bus: Arc<EventBus>,
     pub event_repo: Arc<PostgresEventRepository>,
+    pub dead_letter_repo: Arc<PostgresDeadLetterRepository>,
+    pub outbox_relay: Arc<OutboxRelay>,
+    pub event_replayer: Arc<EventReplayer>,
+    pub event_stream: Arc<EventStream>,
+    pub cache_sweeper: Arc<CacheSweeper>,
+    pub retention: Arc<Retention>,
     pub config_serv: Arc<ConfigService>,
+    pub cursor_signer: CursorSigner,
 
-    pub identity: IdentityContainer<InMemEventBus>,
-    pub publishing: PublishingContainer<InMemEventBus>,
-    pub payment: PaymentContainer<InMemEventBus>,
-    pub notification: NotificationContainer<InMemEventBus>,
+    pub identity: IdentityContainer<EventBus>,
+    pub publishing: PublishingContainer<EventBus>,
+    pub payment: PaymentContainer<EventBus>,
+    pub notification: NotificationContainer<EventBus>,
 }
 
 impl MainContainer {
-    pub async fn new() -> Self {
-        let config = Config::get();
-        let (client, connection) = tokio_postgres::connect(
-            &format!(
-                "host={} user={} password={} dbname={}",
-                config.postgres_host(),
-                config.postgres_username(),
-                config.postgres_password(),
-                config.postgres_database()
-            ),
-            NoTls,
-        )
-        .await
-        .unwrap();
-
-        tokio::spawn(async move {
-            if let Err(err) = connection.await {
-                eprintln!("error: {}", err);
-            }
-        });
-
-        let client = Arc::new(client);
+    /// Fails if Postgres is not reachable, instead of starting a server that can't handle any
+    /// request.
+    pub async fn new(config: Config) -> Result<Self> {
+        let postgres_pool = PostgresPool::connect(&config).await?;
+        let client = PostgresClient::new(postgres_pool.clone());
 
         // Common
-        let event_bus = Arc::new(InMemEventBus::new());
-        let event_repo = Arc::new(PostgresEventRepository::new(client.clone()));
+        let dead_letter_repo = Arc::new(PostgresDeadLetterRepository::new(client.clone()));
+        let upcasters = Arc::new(shared::event::upcasters()?);
+        let local_bus = InMemEventBus::new()
+            .retry_policy(RetryPolicy::exponential(3, Duration::from_millis(200)))
+            .dead_letter_repo(dead_letter_repo.clone());
+        let event_bus = Arc::new(match config.event_bus() {
+            "postgres" => EventBus::Postgres(Arc::new(
+                PostgresEventBus::connect(client.clone(), config.event_bus_consumer_group())
+                    .await?
+                    .local_bus(local_bus)
+                    .upcasters(upcasters.clone()),
+            )),
+            _ => EventBus::InMem(local_bus),
+        });
+        let event_repo =
+            Arc::new(PostgresEventRepository::new(client.clone()).upcasters(upcasters.clone()));
+        let outbox = Arc::new(PostgresOutbox::new(client.clone()));
+        let outbox_relay =
+            Arc::new(OutboxRelay::new(outbox, event_bus.clone()).upcasters(upcasters));
+        let event_replayer = Arc::new(EventReplayer::new(event_repo.clone()));
+        let event_stream = Arc::new(EventStream::new());
         let cache = Arc::new(PostgresCache::new(client.clone()));
-        let config_serv = Arc::new(ConfigService::new(cache));
+        let config_serv = Arc::new(ConfigService::new(Arc::new(
+            PostgresBusinessRulesRepository::new(client.clone()),
+        )));
+        let unit_of_work = Arc::new(PostgresUnitOfWork::new(postgres_pool.clone()));
+        let cursor_signer = CursorSigner::new(config.cursor_secret());
 
         // Identity
+        let id_attempt_repo = Arc::new(PostgresAttemptRepository::new(client.clone()));
+        let id_external_identity_repo =
+            Arc::new(PostgresExternalIdentityRepository::new(client.clone()));
         let id_permission_repo = Arc::new(PostgresPermissionRepository::new(client.clone()));
         let id_role_repo = Arc::new(PostgresRoleRepository::new(client.clone()));
-        let id_tokenot_repo = Arc::new(InMemTokenRepository::new());
+        let id_tokenot_repo = Arc::new(PostgresTokenRepository::new(client.clone()));
         let id_user_repo = Arc::new(PostgresUserRepository::new(client.clone()));
         let id_password_hasher = Arc::new(BcryptHasher::new());
-        let id_tokenot_enc = Arc::new(JWTEncoder::new());
+        let id_tokenot_enc = Arc::new(config.jwt_previous_keys().into_iter().fold(
+            JWTEncoder::new(config.jwt_key_id(), config.jwt_secret()),
+            |enc, (key_id, secret)| enc.previous_key(key_id, secret),
+        ));
+        let mut id_identity_providers: Vec<Arc<dyn IdentityProvider>> = Vec::new();
+        if let Some((client_id, client_secret)) = config.google_client() {
+            id_identity_providers.push(Arc::new(OAuthProvider::google(
+                client_id,
+                client_secret,
+                config.oauth_redirect_url("google").as_str(),
+            )));
+        }
+        if let Some((client_id, client_secret)) = config.facebook_client() {
+            id_identity_providers.push(Arc::new(OAuthProvider::facebook(
+                client_id,
+                client_secret,
+                config.oauth_redirect_url("facebook").as_str(),
+            )));
+        }
 
         // Publishing
         let pub_author_repo = Arc::new(PostgresAuthorRepository::new(client.clone()));
@@ -96,24 +143,50 @@ impl MainContainer {
         let pay_plan_repo = Arc::new(PostgresPlanRepository::new(client.clone()));
         let pay_subscription_repo = Arc::new(PostgresSubscriptionRepository::new(client.clone()));
         let pay_payment_serv = if config.env() == "production" {
-            Arc::new(MercadoPagoService::new()) as Arc<dyn PaymentService>
+            Arc::new(MercadoPagoService::new(&config)) as Arc<dyn PaymentService>
         } else {
-            Arc::new(DevelopmentPaymentService::new()) as Arc<dyn PaymentService>
+            Arc::new(DevelopmentPaymentService::new(&config)) as Arc<dyn PaymentService>
         };
 
         // Notification
         let not_notificationot_repo = Arc::new(PostgresNotificationRepository::new(client));
-        let not_email_serv = Arc::new(GmailService::new());
+        let not_email_serv = Arc::new(GmailService::new(&config));
+
+        let cache_sweeper = Arc::new(
+            CacheSweeper::new()
+                .cache(cache.clone())
+                .cache(id_attempt_repo.clone())
+                .cache(id_tokenot_repo.clone()),
+        );
+
+        // Soft-deleted records can be restored until the retention period ends.
+        let retention = Arc::new(
+            Retention::new(config.retention_period())
+                .repository(pub_publicationot_repo.clone())
+                .repository(pub_collectionot_repo.clone())
+                .repository(id_user_repo.clone())
+                .repository(id_role_repo.clone()),
+        );
 
         // Containers
         let identity = IdentityContainer::new(
             event_bus.clone(),
+            id_attempt_repo,
+            cache.clone(),
+            id_external_identity_repo,
+            cache,
             id_permission_repo,
             id_role_repo,
             id_tokenot_repo,
             id_user_repo.clone(),
+            Arc::new(EmailEmailChangeSender::new(not_email_serv.clone())),
             id_password_hasher,
+            Arc::new(EmailPasswordResetSender::new(not_email_serv.clone())),
             id_tokenot_enc,
+            id_identity_providers,
+            unit_of_work.clone(),
+            config.access_token_ttl(),
+            config.session_ttl(),
         );
 
         let publishing = PublishingContainer::new(
@@ -125,6 +198,7 @@ impl MainContainer {
             pub_publicationot_repo.clone(),
             pub_reader_repo.clone(),
             id_user_repo.clone(),
+            unit_of_work.clone(),
         );
 
         let payment = PaymentContainer::new(
@@ -139,6 +213,7 @@ impl MainContainer {
             config_serv.clone(),
             pay_payment_serv,
             publishing.statistics_serv_clone(),
+            unit_of_work,
         );
 
         let notification = NotificationContainer::new(
@@ -152,21 +227,39 @@ impl MainContainer {
             not_email_serv,
         );
 
-        MainContainer {
+        Ok(MainContainer {
+            config,
+            postgres_pool,
             event_bus,
             event_repo,
+            dead_letter_repo,
+            outbox_relay,
+            event_replayer,
+            event_stream,
+            cache_sweeper,
+            retention,
             config_serv,
+            cursor_signer,
 
             identity,
             publishing,
             payment,
             notification,
-        }
+        })
     }
 
     pub async fn subscribe(&self) -> Result<()> {
-        let event_logger = EventLogger::new(self.event_repo.clone());
-        self.event_bus.subscribe(Box::new(event_logger)).await?;
+        // A durable bus already stores the events it publishes.
+        if !self.event_bus.is_durable() {
+            let event_logger = EventLogger::new(self.event_repo.clone());
+            self.event_bus.subscribe(Box::new(event_logger)).await?;
+        }
+
+        // With a durable bus each instance only streams the events processed by its handlers.
+        let event_stream_handler = EventStreamHandler::new(self.event_stream.clone());
+        self.event_bus
+            .subscribe(Box::new(event_stream_handler))
+            .await?;
 
         self.identity.subscribe(self.event_bus.as_ref()).await?;
         self.publishing.subscribe(self.event_bus.as_ref()).await?;
@@ -176,8 +269,38 @@ impl MainContainer {
         Ok(())
     }
 
-    #[allow(dead_code)]
-    pub fn event_bus(&self) -> &InMemEventBus {
+    pub async fn start(&self) -> Result<()> {
+        // Events written to the outbox by repositories are relayed to the event bus.
+        self.outbox_relay.relay_all().await?;
+        self.outbox_relay.clone().start(Duration::from_millis(500));
+        self.event_bus.start();
+        self.cache_sweeper.clone().start(Duration::from_secs(60));
+        self.retention
+            .clone()
+            .start(self.config.retention_interval());
+
+        Ok(())
+    }
+
+    /// Handlers of every context that can be used to rebuild state from stored events.
+    pub fn replay_handlers(&self) -> Vec<(&'static str, Box<dyn EventHandler>)> {
+        let mut handlers = Vec::new();
+        handlers.extend(self.identity.replay_handlers());
+        handlers.extend(self.publishing.replay_handlers());
+        handlers.extend(self.payment.replay_handlers());
+        handlers.extend(self.notification.replay_handlers());
+        handlers
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn postgres_pool(&self) -> &PostgresPool {
+        &self.postgres_pool
+    }
+
+    pub fn event_bus(&self) -> &EventBus {
         &self.event_bus
     }
 
@@ -185,7 +308,27 @@ impl MainContainer {
         &self.event_repo
     }
 
+    pub fn dead_letter_repo(&self) -> &PostgresDeadLetterRepository {
+        &self.dead_letter_repo
+    }
+
+    pub fn event_replayer(&self) -> &EventReplayer {
+        &self.event_replayer
+    }
+
+    pub fn event_stream(&self) -> &EventStream {
+        &self.event_stream
+    }
+
+    pub fn retention(&self) -> &Retention {
+        &self.retention
+    }
+
     pub fn config_serv(&self) -> &ConfigService {
         &self.config_serv
     }
+
+    pub fn cursor_signer(&self) -> &CursorSigner {
+        &self.cursor_signer
+    }
 }
@@ -1,10 +1,18 @@
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use actix_web::{dev::HttpResponseBuilder, error, http::header, http::StatusCode, HttpResponse};
 use serde::Serialize;
 
-use common::config::Config;
-use common::error::{Error, ErrorKind};
+use common::error::{find_definition, Error, ErrorKind, Language};
+
+/// Whether internal errors are included in responses. It is set at startup from the
+/// configuration, and internal errors are hidden until then.
+static SHOW_INTERNAL_ERRORS: AtomicBool = AtomicBool::new(false);
+
+pub fn show_internal_errors(show: bool) {
+    SHOW_INTERNAL_ERRORS.store(show, Ordering::Relaxed);
+}
 
 #[derive(Debug, Clone, Serialize)]
 pub struct PublicError {
@@ -27,16 +35,18 @@ impl std::error::Error for PublicError {}
 
 impl From<Error> for PublicError {
     fn from(err: Error) -> Self {
-        let config = Config::get();
+        let show_internal_errors = SHOW_INTERNAL_ERRORS.load(Ordering::Relaxed);
+        let language = Language::current();
 
-        if config.env() != "development" {
+        if !show_internal_errors {
             if let ErrorKind::Internal = err.kind() {
                 return PublicError {
                     kind: ErrorKind::Application.to_string(),
                     code: "internal_server".to_owned(),
                     path: "error".to_owned(),
                     status: Some(500),
-                    message: None,
+                    message: find_definition("error", "internal_server")
+                        .map(|def| def.message(language).to_owned()),
                     context: HashMap::new(),
                     cause: None,
                 };
@@ -45,7 +55,7 @@ impl From<Error> for PublicError {
 
         let cause = match err.cause() {
             Some(err) => {
-                if config.env() == "development" {
+                if show_internal_errors {
                     Some(Box::new(Self::from(err.clone())))
                 } else if let ErrorKind::Application = err.kind() {
                     Some(Box::new(Self::from(err.clone())))
@@ -60,8 +70,8 @@ impl From<Error> for PublicError {
             kind: err.kind().to_string(),
             code: err.code().to_string(),
             path: err.path().to_string(),
-            status: err.status(),
-            message: err.message().cloned(),
+            status: err.public_status(),
+            message: err.localized_message(language),
             context: err.context().clone(),
             cause,
         }
@@ -70,9 +80,15 @@ impl From<Error> for PublicError {
 
 impl error::ResponseError for PublicError {
     fn error_response(&self) -> HttpResponse {
-        HttpResponseBuilder::new(self.status_code())
-            .set_header(header::CONTENT_TYPE, "application/json")
-            .body(serde_json::to_string(self).unwrap())
+        let mut res = HttpResponseBuilder::new(self.status_code());
+        res.set_header(header::CONTENT_TYPE, "application/json");
+
+        // Throttled requests tell clients how long to wait.
+        if let Some(retry_after) = self.context.get("retry_after") {
+            res.set_header(header::RETRY_AFTER, retry_after.as_str());
+        }
+
+        res.body(serde_json::to_string(self).unwrap())
     }
     fn status_code(&self) -> StatusCode {
         match self.status {
@@ -130,6 +146,7 @@ mod tests {
 
     #[test]
     fn with_internal_error() {
+        show_internal_errors(true);
         let err = err();
 
         let public_err = PublicError::from(err);
@@ -143,4 +160,22 @@ mod tests {
         assert_eq!(two.code, "two");
         assert!(two.cause.is_some());
     }
+
+    #[tokio::test]
+    async fn localized_message() {
+        let err = || Error::new("publication", "not_published");
+
+        let public_err = Language::En.scope(async { PublicError::from(err()) }).await;
+        assert_eq!(public_err.status, Some(400));
+        assert_eq!(
+            public_err.message.unwrap(),
+            "The publication is not published"
+        );
+
+        let public_err = PublicError::from(err());
+        assert_eq!(
+            public_err.message.unwrap(),
+            "La publicación no está publicada"
+        );
+    }
 }
@@ -1,6 +1,6 @@
 use actix_web::{get, post, web, HttpRequest, HttpResponse, Responder};
 
-use common::request::{IncludeParams, PaginationParams};
+use common::request::{Include, IncludeParams, PaginationParams};
 use payment::application::donation::{
     Donate, DonateCommand, Search as SearchDonation, SearchCommand as SearchDonationCommand,
 };
@@ -25,7 +25,7 @@ async fn search(
 ) -> impl Responder {
     let user_id_and_role = auth(&req, &c).await.ok();
 
-    Search::new(c.publishing.author_repo())
+    Search::new(c.publishing.author_repo(), c.cursor_signer())
         .exec(user_id_and_role, cmd.into_inner(), pagination.into_inner())
         .await
         .map(|res| HttpResponse::Ok().json(res))
@@ -63,6 +63,7 @@ async fn get_publications(
     c: web::Data<MainContainer>,
 ) -> impl Responder {
     let user_id_and_role = auth(&req, &c).await.ok();
+    let include = Include::from(include.into_inner());
 
     let mut user_id = path.into_inner();
     if user_id == "me" {
@@ -78,15 +79,16 @@ async fn get_publications(
         c.publishing.author_repo(),
         c.publishing.category_repo(),
         c.publishing.publication_repo(),
+        c.cursor_signer(),
     )
     .exec(
         user_id_and_role,
         cmd,
-        include.into_inner().into(),
+        include.clone(),
         PaginationParams::default(),
     )
     .await
-    .map(|res| HttpResponse::Ok().json(res))
+    .map(|res| HttpResponse::Ok().json(include.select_in(&res, &["items"])))
     .map_err(PublicError::from)
 }
 
@@ -99,6 +101,7 @@ async fn get_collections(
     c: web::Data<MainContainer>,
 ) -> impl Responder {
     let user_id_and_role = auth(&req, &c).await.ok();
+    let include = Include::from(include.into_inner());
 
     let mut user_id = path.into_inner();
     if user_id == "me" {
@@ -114,15 +117,16 @@ async fn get_collections(
         c.publishing.author_repo(),
         c.publishing.category_repo(),
         c.publishing.collection_repo(),
+        c.cursor_signer(),
     )
     .exec(
         user_id_and_role,
         cmd,
-        include.into_inner().into(),
+        include.clone(),
         PaginationParams::default(),
     )
     .await
-    .map(|res| HttpResponse::Ok().json(res))
+    .map(|res| HttpResponse::Ok().json(include.select_in(&res, &["items"])))
     .map_err(PublicError::from)
 }
 
@@ -139,6 +143,7 @@ async fn follow(
         c.publishing.author_repo(),
         c.publishing.interaction_repo(),
         c.publishing.reader_repo(),
+        c.publishing.unit_of_work(),
     )
     .exec(user_id_and_role, path.into_inner())
     .await
@@ -159,6 +164,7 @@ async fn unfollow(
         c.publishing.author_repo(),
         c.publishing.interaction_repo(),
         c.publishing.reader_repo(),
+        c.publishing.unit_of_work(),
     )
     .exec(user_id_and_role, path.into_inner())
     .await
@@ -175,6 +181,7 @@ async fn get_donations(
     c: web::Data<MainContainer>,
 ) -> impl Responder {
     let user_id_and_role = auth(&req, &c).await?;
+    let include = Include::from(include.into_inner());
 
     let mut user_id = path.into_inner();
     if user_id == "me" {
@@ -188,15 +195,16 @@ async fn get_donations(
         c.publishing.author_repo(),
         c.payment.donation_repo(),
         c.publishing.reader_repo(),
+        c.cursor_signer(),
     )
     .exec(
         user_id_and_role,
         cmd,
-        include.into_inner().into(),
+        include.clone(),
         PaginationParams::default(),
     )
     .await
-    .map(|res| HttpResponse::Ok().json(res))
+    .map(|res| HttpResponse::Ok().json(include.select_in(&res, &["items"])))
     .map_err(PublicError::from)
 }
 
@@ -217,6 +225,7 @@ async fn donate(
         c.identity.user_repo(),
         c.config_serv(),
         c.payment.payment_serv(),
+        c.payment.unit_of_work(),
     )
     .exec(user_id_and_role, path.into_inner(), cmd.into_inner())
     .await
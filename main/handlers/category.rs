@@ -1,6 +1,6 @@
 use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder};
 
-use common::request::{IncludeParams, PaginationParams};
+use common::request::{Include, IncludeParams, PaginationParams};
 use publishing::application::category::{
     Create, CreateCommand, Delete, GetAll, GetById, Update, UpdateCommand,
 };
@@ -50,6 +50,7 @@ async fn get_publications(
     c: web::Data<MainContainer>,
 ) -> impl Responder {
     let user_id_and_role = auth(&req, &c).await.ok();
+    let include = Include::from(include.into_inner());
 
     let mut cmd = cmd.into_inner();
     cmd.category_id = Some(path.into_inner());
@@ -58,15 +59,16 @@ async fn get_publications(
         c.publishing.author_repo(),
         c.publishing.category_repo(),
         c.publishing.publication_repo(),
+        c.cursor_signer(),
     )
     .exec(
         user_id_and_role,
         cmd,
-        include.into_inner().into(),
+        include.clone(),
         PaginationParams::default(),
     )
     .await
-    .map(|res| HttpResponse::Ok().json(res))
+    .map(|res| HttpResponse::Ok().json(include.select_in(&res, &["items"])))
     .map_err(PublicError::from)
 }
 
@@ -79,6 +81,7 @@ async fn get_collections(
     c: web::Data<MainContainer>,
 ) -> impl Responder {
     let user_id_and_role = auth(&req, &c).await.ok();
+    let include = Include::from(include.into_inner());
 
     let mut cmd = cmd.into_inner();
     cmd.category_id = Some(path.into_inner());
@@ -87,15 +90,16 @@ async fn get_collections(
         c.publishing.author_repo(),
         c.publishing.category_repo(),
         c.publishing.collection_repo(),
+        c.cursor_signer(),
     )
     .exec(
         user_id_and_role,
         cmd,
-        include.into_inner().into(),
+        include.clone(),
         PaginationParams::default(),
     )
     .await
-    .map(|res| HttpResponse::Ok().json(res))
+    .map(|res| HttpResponse::Ok().json(include.select_in(&res, &["items"])))
     .map_err(PublicError::from)
 }
 
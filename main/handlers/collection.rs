@@ -1,9 +1,10 @@
 use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder};
 
-use common::request::{IncludeParams, PaginationParams};
+use common::request::{Include, IncludeParams, PaginationParams};
 use publishing::application::collection::{
     AddPublication, AddToFavorites, Create, CreateCommand, Delete, GetById, GetPublications,
-    RemoveFromFavorites, RemovePublication, Search, SearchCommand, Update, UpdateCommand,
+    RemoveFromFavorites, RemovePublication, Restore, Search, SearchCommand, SearchDeleted, Update,
+    UpdateCommand,
 };
 
 use crate::authorization::auth;
@@ -39,20 +40,44 @@ async fn search(
     c: web::Data<MainContainer>,
 ) -> impl Responder {
     let user_id_and_role = auth(&req, &c).await.ok();
+    let include = Include::from(include.into_inner());
 
     Search::new(
         c.publishing.author_repo(),
         c.publishing.category_repo(),
         c.publishing.collection_repo(),
+        c.cursor_signer(),
     )
     .exec(
         user_id_and_role,
         cmd.into_inner(),
-        include.into_inner().into(),
+        include.clone(),
         pagination.into_inner(),
     )
     .await
-    .map(|res| HttpResponse::Ok().json(res))
+    .map(|res| HttpResponse::Ok().json(include.select_in(&res, &["items"])))
+    .map_err(PublicError::from)
+}
+
+#[get("/deleted")]
+async fn search_deleted(
+    req: HttpRequest,
+    include: web::Query<IncludeParams>,
+    pagination: web::Query<PaginationParams>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+    let include = Include::from(include.into_inner());
+
+    SearchDeleted::new(
+        c.publishing.author_repo(),
+        c.publishing.category_repo(),
+        c.publishing.collection_repo(),
+        c.cursor_signer(),
+    )
+    .exec(user_id_and_role, include.clone(), pagination.into_inner())
+    .await
+    .map(|res| HttpResponse::Ok().json(include.select_in(&res, &["items"])))
     .map_err(PublicError::from)
 }
 
@@ -64,19 +89,16 @@ async fn get_by_id(
     c: web::Data<MainContainer>,
 ) -> impl Responder {
     let user_id_and_role = auth(&req, &c).await.ok();
+    let include = Include::from(include.into_inner());
 
     GetById::new(
         c.publishing.author_repo(),
         c.publishing.category_repo(),
         c.publishing.collection_repo(),
     )
-    .exec(
-        user_id_and_role,
-        path.into_inner(),
-        include.into_inner().into(),
-    )
+    .exec(user_id_and_role, path.into_inner(), include.clone())
     .await
-    .map(|res| HttpResponse::Ok().json(res))
+    .map(|res| HttpResponse::Ok().json(include.select(&res)))
     .map_err(PublicError::from)
 }
 
@@ -88,6 +110,7 @@ async fn get_publications(
     c: web::Data<MainContainer>,
 ) -> impl Responder {
     let user_id_and_role = auth(&req, &c).await.ok();
+    let include = Include::from(include.into_inner());
 
     GetPublications::new(
         c.publishing.author_repo(),
@@ -95,13 +118,9 @@ async fn get_publications(
         c.publishing.collection_repo(),
         c.publishing.publication_repo(),
     )
-    .exec(
-        user_id_and_role,
-        path.into_inner(),
-        include.into_inner().into(),
-    )
+    .exec(user_id_and_role, path.into_inner(), include.clone())
     .await
-    .map(|res| HttpResponse::Ok().json(res))
+    .map(|res| HttpResponse::Ok().json(include.select_in(&res, &["publications"])))
     .map_err(PublicError::from)
 }
 
@@ -140,6 +159,21 @@ async fn delete(
         .map_err(PublicError::from)
 }
 
+#[post("/{collection_id}/restore")]
+async fn restore(
+    req: HttpRequest,
+    path: web::Path<String>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    Restore::new(c.publishing.event_pub(), c.publishing.collection_repo())
+        .exec(user_id_and_role, path.into_inner())
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
 #[post("/{collection_id}/publication/{publication_id}")]
 async fn add_publication(
     req: HttpRequest,
@@ -221,10 +255,12 @@ pub fn routes(cfg: &mut web::ServiceConfig) {
         web::scope("/collections")
             .service(create)
             .service(search)
+            .service(search_deleted)
             .service(get_by_id)
             .service(get_publications)
             .service(update)
             .service(delete)
+            .service(restore)
             .service(add_publication)
             .service(remove_publication)
             .service(add_to_favorites)
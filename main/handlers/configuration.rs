@@ -1,8 +1,6 @@
 use actix_web::{get, put, web, HttpRequest, HttpResponse, Responder};
 
-use common::config::BusinessRules;
-
-use crate::application::configuration::{Get, Update};
+use crate::application::configuration::{Diff, DiffCommand, Get, History, Update, UpdateCommand};
 use crate::authorization::auth;
 use crate::container::MainContainer;
 use crate::error::PublicError;
@@ -19,7 +17,7 @@ async fn get_business_rules(c: web::Data<MainContainer>) -> impl Responder {
 #[put("")]
 async fn update_business_rules(
     req: HttpRequest,
-    cmd: web::Json<BusinessRules>,
+    cmd: web::Json<UpdateCommand>,
     c: web::Data<MainContainer>,
 ) -> impl Responder {
     let user_id_and_role = auth(&req, &c).await?;
@@ -31,10 +29,38 @@ async fn update_business_rules(
         .map_err(PublicError::from)
 }
 
+#[get("/history")]
+async fn history(req: HttpRequest, c: web::Data<MainContainer>) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    History::new(c.config_serv())
+        .exec(user_id_and_role)
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
+#[get("/diff")]
+async fn diff(
+    req: HttpRequest,
+    cmd: web::Query<DiffCommand>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    Diff::new(c.config_serv())
+        .exec(user_id_and_role, cmd.into_inner())
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
 pub fn routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/configuration")
             .service(get_business_rules)
-            .service(update_business_rules),
+            .service(update_business_rules)
+            .service(history)
+            .service(diff),
     );
 }
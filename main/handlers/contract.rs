@@ -1,6 +1,6 @@
 use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Responder};
 
-use common::request::{IncludeParams, PaginationParams};
+use common::request::{Include, IncludeParams, PaginationParams};
 use payment::application::contract::{
     Approve, Cancel, ChargeForContract, GenerateSummaries, GenerateSummariesCommand, Reject,
     Search, SearchCommand,
@@ -19,17 +19,24 @@ async fn search(
     c: web::Data<MainContainer>,
 ) -> impl Responder {
     let user_id_and_role = auth(&req, &c).await?;
+    let include = Include::from(include.into_inner());
 
-    Search::new(c.payment.contract_repo(), c.payment.publication_repo())
-        .exec(
-            user_id_and_role,
-            cmd.into_inner(),
-            include.into_inner().into(),
-            pagination.into_inner(),
-        )
-        .await
-        .map(|res| HttpResponse::Ok().json(res))
-        .map_err(PublicError::from)
+    Search::new(
+        c.publishing.author_repo(),
+        c.publishing.category_repo(),
+        c.payment.contract_repo(),
+        c.payment.publication_repo(),
+        c.cursor_signer(),
+    )
+    .exec(
+        user_id_and_role,
+        cmd.into_inner(),
+        include.clone(),
+        pagination.into_inner(),
+    )
+    .await
+    .map(|res| HttpResponse::Ok().json(include.select_in(&res, &["items"])))
+    .map_err(PublicError::from)
 }
 
 #[post("/{contract_id}/approve")]
@@ -123,6 +130,7 @@ async fn charge(
         c.identity.user_repo(),
         c.config_serv(),
         c.payment.payment_serv(),
+        c.payment.unit_of_work(),
     )
     .exec(user_id_and_role, path.into_inner())
     .await
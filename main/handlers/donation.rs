@@ -1,6 +1,6 @@
 use actix_web::{get, post, web, HttpRequest, HttpResponse, Responder};
 
-use common::request::{IncludeParams, PaginationParams};
+use common::request::{Include, IncludeParams, PaginationParams};
 use payment::application::donation::{Charge, GetById, Search, SearchCommand};
 
 use crate::authorization::auth;
@@ -16,20 +16,22 @@ async fn search(
     c: web::Data<MainContainer>,
 ) -> impl Responder {
     let user_id_and_role = auth(&req, &c).await?;
+    let include = Include::from(include.into_inner());
 
     Search::new(
         c.publishing.author_repo(),
         c.payment.donation_repo(),
         c.publishing.reader_repo(),
+        c.cursor_signer(),
     )
     .exec(
         user_id_and_role,
         cmd.into_inner(),
-        include.into_inner().into(),
+        include.clone(),
         pagination.into_inner(),
     )
     .await
-    .map(|res| HttpResponse::Ok().json(res))
+    .map(|res| HttpResponse::Ok().json(include.select_in(&res, &["items"])))
     .map_err(PublicError::from)
 }
 
@@ -41,19 +43,16 @@ async fn get_by_id(
     c: web::Data<MainContainer>,
 ) -> impl Responder {
     let user_id_and_role = auth(&req, &c).await?;
+    let include = Include::from(include.into_inner());
 
     GetById::new(
         c.publishing.author_repo(),
         c.payment.donation_repo(),
         c.publishing.reader_repo(),
     )
-    .exec(
-        user_id_and_role,
-        path.into_inner(),
-        include.into_inner().into(),
-    )
+    .exec(user_id_and_role, path.into_inner(), include.clone())
     .await
-    .map(|res| HttpResponse::Ok().json(res))
+    .map(|res| HttpResponse::Ok().json(include.select(&res)))
     .map_err(PublicError::from)
 }
 
@@ -66,6 +65,7 @@ async fn charge(req: HttpRequest, c: web::Data<MainContainer>) -> impl Responder
         c.payment.donation_repo(),
         c.identity.user_repo(),
         c.payment.payment_serv(),
+        c.payment.unit_of_work(),
     )
     .exec(user_id_and_role)
     .await
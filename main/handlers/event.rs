@@ -1,72 +1,294 @@
-use actix_web::{get, web, HttpResponse, Responder};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Responder};
+use bytes::Bytes;
 use chrono::{DateTime, Utc};
+use futures::future;
+use futures::stream::{self, StreamExt};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use common::event::EventRepository;
+use common::error::Error;
+use common::event::{Event, EventId, EventOrder, EventRepository};
+use common::request::PAGINATION_LIMIT;
 
+use crate::application::event::{
+    DiscardDeadLetter, ListDeadLetters, Replay, ReplayCommand, ReplayDeadLetter,
+};
+use crate::authorization::auth;
 use crate::container::MainContainer;
 use crate::error::PublicError;
+use crate::infrastructure::{StreamFilter, StreamScope};
 
 #[derive(Serialize)]
 pub struct PublicEvent {
     pub id: String,
     pub topic: String,
     pub code: String,
+    pub version: u32,
     pub timestamp: String,
     pub payload: Value,
+    pub correlation_id: Option<String>,
+    pub causation_id: Option<String>,
+    pub actor_id: Option<String>,
+}
+
+impl From<&Event> for PublicEvent {
+    fn from(event: &Event) -> Self {
+        PublicEvent {
+            id: event.id().to_string(),
+            topic: event.topic().to_string(),
+            code: event.code().to_string(),
+            version: event.version(),
+            timestamp: event.timestamp().to_rfc3339(),
+            payload: event.payload(),
+            correlation_id: event.correlation_id().map(ToOwned::to_owned),
+            causation_id: event.causation_id().map(ToOwned::to_owned),
+            actor_id: event.actor_id().map(ToOwned::to_owned),
+        }
+    }
 }
 
 #[derive(Deserialize)]
 pub struct SearchCommand {
+    pub after_id: Option<String>,
     pub topic: Option<String>,
     pub code: Option<String>,
+    pub correlation_id: Option<String>,
     pub from: Option<DateTime<Utc>>,
     pub to: Option<DateTime<Utc>>,
+    pub limit: Option<usize>,
     pub order_by: Option<String>,
 }
 
 #[derive(Serialize)]
 pub struct GetAllResponse {
     pub events: Vec<PublicEvent>,
+    pub next_cursor: Option<String>,
 }
 
 // GET /events
 #[get("")]
-async fn get(cmd: web::Query<SearchCommand>, c: web::Data<MainContainer>) -> impl Responder {
+async fn get(
+    req: HttpRequest,
+    cmd: web::Query<SearchCommand>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    // Events expose who performed every action and how they are related.
+    let (_, auth_role) = auth(&req, &c).await?;
+    if !auth_role.can("manage_events") {
+        return Err(PublicError::from(Error::unauthorized()));
+    }
+
+    let after_id = cmd
+        .after_id
+        .as_ref()
+        .map(EventId::new)
+        .transpose()
+        .map_err(PublicError::from)?;
+
+    let limit = cmd
+        .limit
+        .map_or(PAGINATION_LIMIT, |limit| limit.min(PAGINATION_LIMIT));
+
+    let order = match cmd.order_by.as_deref() {
+        Some("newest") => EventOrder::Desc,
+        _ => EventOrder::Asc,
+    };
+
     c.event_repo()
         .search(
-            None,
+            after_id.as_ref(),
             cmd.topic.as_ref(),
             cmd.code.as_ref(),
+            cmd.correlation_id.as_ref(),
             cmd.from.as_ref(),
             cmd.to.as_ref(),
+            Some(limit),
+            order,
         )
         .await
-        .map(|events| {
-            let mut events: Vec<PublicEvent> = events
-                .into_iter()
-                .map(|event| PublicEvent {
-                    id: event.id().to_string(),
-                    topic: event.topic().to_string(),
-                    code: event.code().to_string(),
-                    timestamp: event.timestamp().to_rfc3339(),
-                    payload: serde_json::from_value(event.payload()).unwrap(),
-                })
-                .collect();
-
-            if let Some(order_by) = &cmd.order_by {
-                if order_by == "newest" {
-                    events.reverse();
-                }
-            }
-
-            events
+        .map(|page| GetAllResponse {
+            events: page.events().iter().map(PublicEvent::from).collect(),
+            next_cursor: page.next_cursor().map(|id| id.to_string()),
         })
-        .map(|events| HttpResponse::Ok().json(GetAllResponse { events }))
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
+#[derive(Deserialize)]
+pub struct StreamCommand {
+    pub topic: Option<String>,
+}
+
+/// Maximum number of stored events sent to a client resuming from `Last-Event-ID`.
+const MAX_RESUMED_EVENTS: usize = 1000;
+
+fn sse_message(event: &Event) -> Result<Bytes, actix_web::Error> {
+    let data = serde_json::to_string(&PublicEvent::from(event))?;
+    Ok(Bytes::from(format!(
+        "id: {}\ndata: {}\n\n",
+        event.id().value(),
+        data
+    )))
+}
+
+// GET /events/stream
+#[get("/stream")]
+async fn stream(
+    req: HttpRequest,
+    cmd: web::Query<StreamCommand>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let (auth_id, auth_role) = auth(&req, &c).await?;
+
+    let topic = cmd
+        .topic
+        .as_ref()
+        .map(|topic| Regex::new(topic))
+        .transpose()
+        .map_err(|err| PublicError::from(Error::bad_format("topic").wrap_raw(err)))?;
+
+    // Users with permission to manage events receive every domain event, the rest only their
+    // notifications.
+    let (scope, stored_topic) = if auth_role.can("manage_events") {
+        (StreamScope::Events, None)
+    } else {
+        (
+            StreamScope::Notifications(auth_id),
+            Some("notification".to_owned()),
+        )
+    };
+    let filter = Arc::new(StreamFilter::new(scope, topic));
+
+    // The client starts listening before reading stored events, so no event is lost while
+    // resuming. Events sent from the repository are not sent again.
+    let live = c.event_stream().listen(filter.clone());
+
+    let last_event_id = req
+        .headers()
+        .get("Last-Event-ID")
+        .and_then(|header| header.to_str().ok())
+        .map(EventId::new)
+        .transpose()
+        .map_err(PublicError::from)?;
+
+    let mut resumed = Vec::new();
+    let mut after_id = last_event_id;
+    while let Some(id) = after_id {
+        let page = c
+            .event_repo()
+            .search(
+                Some(&id),
+                stored_topic.as_ref(),
+                None,
+                None,
+                None,
+                None,
+                Some(100),
+                EventOrder::Asc,
+            )
+            .await
+            .map_err(PublicError::from)?;
+
+        resumed.extend(
+            page.events()
+                .iter()
+                .filter(|event| filter.matches(event))
+                .cloned(),
+        );
+
+        after_id = match page.next_cursor() {
+            Some(next_cursor) if resumed.len() < MAX_RESUMED_EVENTS => Some(next_cursor.clone()),
+            _ => None,
+        };
+    }
+
+    let resumed_ids: HashSet<EventId> = resumed.iter().map(|event| event.id().clone()).collect();
+    let live = live.filter(move |event| future::ready(!resumed_ids.contains(event.id())));
+
+    let body = stream::iter(resumed)
+        .chain(live)
+        .map(|event| sse_message(&event));
+
+    Ok::<_, PublicError>(
+        HttpResponse::Ok()
+            .content_type("text/event-stream")
+            .header("Cache-Control", "no-cache")
+            .streaming(Box::pin(body)),
+    )
+}
+
+// POST /events/replay
+#[post("/replay")]
+async fn replay(
+    req: HttpRequest,
+    cmd: web::Json<ReplayCommand>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    Replay::new(c.event_replayer(), c.replay_handlers())
+        .exec(user_id_and_role, cmd.into_inner())
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
+// GET /events/dead-letters
+#[get("/dead-letters")]
+async fn get_dead_letters(req: HttpRequest, c: web::Data<MainContainer>) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    ListDeadLetters::new(c.dead_letter_repo())
+        .exec(user_id_and_role)
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
+// POST /events/dead-letters/:id/replay
+#[post("/dead-letters/{dead_letter_id}/replay")]
+async fn replay_dead_letter(
+    req: HttpRequest,
+    path: web::Path<String>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    ReplayDeadLetter::new(c.event_bus(), c.dead_letter_repo())
+        .exec(user_id_and_role, path.into_inner())
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
+// DELETE /events/dead-letters/:id
+#[delete("/dead-letters/{dead_letter_id}")]
+async fn discard_dead_letter(
+    req: HttpRequest,
+    path: web::Path<String>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    DiscardDeadLetter::new(c.dead_letter_repo())
+        .exec(user_id_and_role, path.into_inner())
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
         .map_err(PublicError::from)
 }
 
 pub fn routes(cfg: &mut web::ServiceConfig) {
-    cfg.service(web::scope("/events").service(get));
+    cfg.service(
+        web::scope("/events")
+            .service(get)
+            .service(stream)
+            .service(replay)
+            .service(get_dead_letters)
+            .service(replay_dead_letter)
+            .service(discard_dead_letter),
+    );
 }
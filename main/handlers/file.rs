@@ -5,6 +5,7 @@ use serde::Serialize;
 use file::file::UploadedFile;
 use file::uploader::{FileUploader, S3FileUploader};
 
+use crate::container::MainContainer;
 use crate::error::PublicError;
 
 #[derive(Serialize)]
@@ -13,7 +14,10 @@ pub struct UploadResponse {
 }
 
 #[post("")]
-async fn upload(mut payload: Multipart) -> Result<HttpResponse, Error> {
+async fn upload(
+    mut payload: Multipart,
+    c: web::Data<MainContainer>,
+) -> Result<HttpResponse, Error> {
     let (data, files) = file::extract_payload(&mut payload)
         .await
         .map_err(PublicError::from)?;
@@ -21,7 +25,7 @@ async fn upload(mut payload: Multipart) -> Result<HttpResponse, Error> {
     println!("bytes = {:#?}", data);
     println!("files = {:#?}", files);
 
-    let uploader = S3FileUploader::new();
+    let uploader = S3FileUploader::new(c.config());
 
     let mut uploaded_files = Vec::new();
     for file in files.into_iter() {
@@ -0,0 +1,24 @@
+use actix_web::{get, web, HttpResponse, Responder};
+
+use crate::application::health::Check;
+use crate::container::MainContainer;
+use crate::error::PublicError;
+
+#[get("")]
+async fn check(c: web::Data<MainContainer>) -> impl Responder {
+    Check::new(c.postgres_pool())
+        .exec()
+        .await
+        .map(|res| {
+            if res.healthy {
+                HttpResponse::Ok().json(res)
+            } else {
+                HttpResponse::ServiceUnavailable().json(res)
+            }
+        })
+        .map_err(PublicError::from)
+}
+
+pub fn routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::scope("/health").service(check));
+}
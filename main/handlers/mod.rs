@@ -7,6 +7,7 @@ pub mod contract;
 pub mod donation;
 pub mod event;
 pub mod file;
+pub mod health;
 pub mod notification;
 pub mod payment;
 pub mod plan;
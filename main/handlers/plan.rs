@@ -81,6 +81,7 @@ async fn subscribe(
         c.payment.subscription_repo(),
         c.payment.user_repo(),
         c.payment.payment_serv(),
+        c.payment.unit_of_work(),
     )
     .exec(user_id_and_role, path.into_inner())
     .await
@@ -1,6 +1,6 @@
 use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder};
 
-use common::request::{IncludeParams, PaginationParams};
+use common::request::{Include, IncludeParams, PaginationParams};
 use payment::application::contract::{
     CanRequest as CanRequestContract, GenerateSummariesForPublication,
     GetByPublication as GetContractByPublication, Request as RequestContract,
@@ -11,8 +11,8 @@ use publishing::application::collection::{
 use publishing::application::publication::{
     AddReview, AddReviewCommand, AddToFavorites, Approve, ApproveCommand, Create, CreateCommand,
     Delete, DeleteReview, GetById, GetReviews, GetStatistics, GetStatisticsCommand, Like, Publish,
-    Read, Reject, RejectCommand, RemoveFromFavorites, Search, SearchCommand, Unlike, Update,
-    UpdateCommand, UpdatePages, UpdatePagesCommand,
+    Read, Reject, RejectCommand, RemoveFromFavorites, Restore, Search, SearchCommand,
+    SearchDeleted, Unlike, Update, UpdateCommand, UpdatePages, UpdatePagesCommand,
 };
 
 use crate::authorization::auth;
@@ -48,20 +48,44 @@ async fn search(
     c: web::Data<MainContainer>,
 ) -> impl Responder {
     let user_id_and_role = auth(&req, &c).await.ok();
+    let include = Include::from(include.into_inner());
 
     Search::new(
         c.publishing.author_repo(),
         c.publishing.category_repo(),
         c.publishing.publication_repo(),
+        c.cursor_signer(),
     )
     .exec(
         user_id_and_role,
         cmd.into_inner(),
-        include.into_inner().into(),
+        include.clone(),
         pagination.into_inner(),
     )
     .await
-    .map(|res| HttpResponse::Ok().json(res))
+    .map(|res| HttpResponse::Ok().json(include.select_in(&res, &["items"])))
+    .map_err(PublicError::from)
+}
+
+#[get("/deleted")]
+async fn search_deleted(
+    req: HttpRequest,
+    include: web::Query<IncludeParams>,
+    pagination: web::Query<PaginationParams>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+    let include = Include::from(include.into_inner());
+
+    SearchDeleted::new(
+        c.publishing.author_repo(),
+        c.publishing.category_repo(),
+        c.publishing.publication_repo(),
+        c.cursor_signer(),
+    )
+    .exec(user_id_and_role, include.clone(), pagination.into_inner())
+    .await
+    .map(|res| HttpResponse::Ok().json(include.select_in(&res, &["items"])))
     .map_err(PublicError::from)
 }
 
@@ -73,6 +97,7 @@ async fn get_by_id(
     c: web::Data<MainContainer>,
 ) -> impl Responder {
     let user_id_and_role = auth(&req, &c).await.ok();
+    let include = Include::from(include.into_inner());
 
     GetById::new(
         c.publishing.event_pub(),
@@ -82,14 +107,11 @@ async fn get_by_id(
         c.publishing.publication_repo(),
         c.publishing.reader_repo(),
         c.publishing.statistics_serv(),
+        c.publishing.unit_of_work(),
     )
-    .exec(
-        user_id_and_role,
-        path.into_inner(),
-        include.into_inner().into(),
-    )
+    .exec(user_id_and_role, path.into_inner(), include.clone())
     .await
-    .map(|res| HttpResponse::Ok().json(res))
+    .map(|res| HttpResponse::Ok().json(include.select_in(&res, &["publication"])))
     .map_err(PublicError::from)
 }
 
@@ -144,6 +166,21 @@ async fn delete(
         .map_err(PublicError::from)
 }
 
+#[post("/{publication_id}/restore")]
+async fn restore(
+    req: HttpRequest,
+    path: web::Path<String>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    Restore::new(c.publishing.event_pub(), c.publishing.publication_repo())
+        .exec(user_id_and_role, path.into_inner())
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
 #[post("/{publication_id}/publish")]
 async fn publish(
     req: HttpRequest,
@@ -208,6 +245,7 @@ async fn read(
         c.publishing.interaction_repo(),
         c.publishing.publication_repo(),
         c.publishing.reader_repo(),
+        c.publishing.unit_of_work(),
     )
     .exec(user_id_and_role, path.into_inner())
     .await
@@ -228,6 +266,7 @@ async fn like(
         c.publishing.interaction_repo(),
         c.publishing.publication_repo(),
         c.publishing.reader_repo(),
+        c.publishing.unit_of_work(),
     )
     .exec(user_id_and_role, path.into_inner())
     .await
@@ -248,6 +287,7 @@ async fn unlike(
         c.publishing.interaction_repo(),
         c.publishing.publication_repo(),
         c.publishing.reader_repo(),
+        c.publishing.unit_of_work(),
     )
     .exec(user_id_and_role, path.into_inner())
     .await
@@ -269,6 +309,7 @@ async fn review(
         c.publishing.interaction_repo(),
         c.publishing.publication_repo(),
         c.publishing.reader_repo(),
+        c.publishing.unit_of_work(),
     )
     .exec(user_id_and_role, path.into_inner(), cmd.into_inner())
     .await
@@ -289,6 +330,7 @@ async fn delete_review(
         c.publishing.interaction_repo(),
         c.publishing.publication_repo(),
         c.publishing.reader_repo(),
+        c.publishing.unit_of_work(),
     )
     .exec(user_id_and_role, path.into_inner())
     .await
@@ -320,6 +362,7 @@ async fn get_collections(
     c: web::Data<MainContainer>,
 ) -> impl Responder {
     let user_id_and_role = auth(&req, &c).await.ok();
+    let include = Include::from(include.into_inner());
 
     let mut cmd = cmd.into_inner();
     cmd.publication_id = Some(path.into_inner());
@@ -328,15 +371,16 @@ async fn get_collections(
         c.publishing.author_repo(),
         c.publishing.category_repo(),
         c.publishing.collection_repo(),
+        c.cursor_signer(),
     )
     .exec(
         user_id_and_role,
         cmd,
-        include.into_inner().into(),
+        include.clone(),
         PaginationParams::default(), // TODO: use real PaginationParams
     )
     .await
-    .map(|res| HttpResponse::Ok().json(res))
+    .map(|res| HttpResponse::Ok().json(include.select_in(&res, &["items"])))
     .map_err(PublicError::from)
 }
 
@@ -462,6 +506,7 @@ async fn request_contract(
         c.payment.contract_repo(),
         c.payment.publication_repo(),
         c.payment.contract_serv(),
+        c.payment.unit_of_work(),
     )
     .exec(user_id_and_role, path.into_inner())
     .await
@@ -474,10 +519,12 @@ pub fn routes(cfg: &mut web::ServiceConfig) {
         web::scope("/publications")
             .service(create)
             .service(search)
+            .service(search_deleted)
             .service(get_by_id)
             .service(update)
             .service(update_pages)
             .service(delete)
+            .service(restore)
             .service(publish)
             .service(approve)
             .service(reject)
@@ -1,6 +1,6 @@
 use actix_web::{get, web, HttpRequest, HttpResponse, Responder};
 
-use common::request::IncludeParams;
+use common::request::{Include, IncludeParams};
 use payment::application::subscription::GetByReader as GetSubscriptionByReader;
 use publishing::application::reader::{GetById, GetFavorites, GetFollowing};
 
@@ -56,6 +56,7 @@ async fn get_favorites(
     c: web::Data<MainContainer>,
 ) -> impl Responder {
     let user_id_and_role = auth(&req, &c).await?;
+    let include = Include::from(include.into_inner());
 
     let mut user_id = path.into_inner();
     if user_id == "me" {
@@ -69,9 +70,9 @@ async fn get_favorites(
         c.publishing.interaction_repo(),
         c.publishing.publication_repo(),
     )
-    .exec(user_id_and_role, user_id, include.into_inner().into())
+    .exec(user_id_and_role, user_id, include.clone())
     .await
-    .map(|res| HttpResponse::Ok().json(res))
+    .map(|res| HttpResponse::Ok().json(include.select_in(&res, &["publications", "collections"])))
     .map_err(PublicError::from)
 }
 
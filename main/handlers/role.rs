@@ -1,9 +1,9 @@
 use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder};
 
-use common::request::{IncludeParams, PaginationParams};
+use common::request::{Include, IncludeParams, PaginationParams};
 use identity::application::role::{
-    Create, CreateCommand, Delete, GetAll, GetById, GetPermissions, MakeDefault, Update,
-    UpdateCommand,
+    Create, CreateCommand, Delete, GetAll, GetAllDeleted, GetById, GetPermissions, MakeDefault,
+    Restore, Update, UpdateCommand,
 };
 use identity::application::user::{Search as SearchUser, SearchCommand as SearchUserCommand};
 
@@ -22,6 +22,17 @@ async fn get_all(req: HttpRequest, c: web::Data<MainContainer>) -> impl Responde
         .map_err(PublicError::from)
 }
 
+#[get("/deleted")]
+async fn get_all_deleted(req: HttpRequest, c: web::Data<MainContainer>) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    GetAllDeleted::new(c.identity.role_repo())
+        .exec(user_id_and_role)
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
 #[get("/{role_id}")]
 async fn get_by_id(
     req: HttpRequest,
@@ -47,20 +58,25 @@ async fn get_users(
     c: web::Data<MainContainer>,
 ) -> impl Responder {
     let user_id_and_role = auth(&req, &c).await?;
+    let include = Include::from(include.into_inner());
 
     let mut cmd = cmd.into_inner();
     cmd.role_id = Some(path.into_inner());
 
-    SearchUser::new(c.identity.role_repo(), c.identity.user_repo())
-        .exec(
-            user_id_and_role,
-            cmd,
-            include.into_inner().into(),
-            pagination.into_inner(),
-        )
-        .await
-        .map(|res| HttpResponse::Ok().json(res))
-        .map_err(PublicError::from)
+    SearchUser::new(
+        c.identity.role_repo(),
+        c.identity.user_repo(),
+        c.cursor_signer(),
+    )
+    .exec(
+        user_id_and_role,
+        cmd,
+        include.clone(),
+        pagination.into_inner(),
+    )
+    .await
+    .map(|res| HttpResponse::Ok().json(include.select_in(&res, &["items"])))
+    .map_err(PublicError::from)
 }
 
 #[post("")]
@@ -109,6 +125,21 @@ async fn delete(
         .map_err(PublicError::from)
 }
 
+#[post("/{role_id}/restore")]
+async fn restore(
+    req: HttpRequest,
+    path: web::Path<String>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    Restore::new(c.identity.role_repo())
+        .exec(user_id_and_role, path.into_inner())
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
 #[put("/{role_id}/default")]
 async fn make_default(
     req: HttpRequest,
@@ -117,7 +148,7 @@ async fn make_default(
 ) -> impl Responder {
     let user_id_and_role = auth(&req, &c).await?;
 
-    MakeDefault::new(c.identity.role_repo())
+    MakeDefault::new(c.identity.role_repo(), c.identity.unit_of_work())
         .exec(user_id_and_role, path.into_inner())
         .await
         .map(|res| HttpResponse::Ok().json(res))
@@ -140,11 +171,13 @@ pub fn routes(cfg: &mut web::ServiceConfig) {
         web::scope("/roles")
             .service(get_permissions)
             .service(get_all)
+            .service(get_all_deleted)
             .service(get_by_id)
             .service(get_users)
             .service(create)
             .service(update)
             .service(delete)
+            .service(restore)
             .service(make_default),
     );
 }
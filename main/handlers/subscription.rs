@@ -1,6 +1,6 @@
 use actix_web::{delete, get, web, HttpRequest, HttpResponse, Responder};
 
-use common::request::{IncludeParams, PaginationParams};
+use common::request::{Include, IncludeParams, PaginationParams};
 use payment::application::subscription::{Search, SearchCommand, Unsubscribe};
 
 use crate::authorization::auth;
@@ -16,17 +16,23 @@ async fn search(
     c: web::Data<MainContainer>,
 ) -> impl Responder {
     let user_id_and_role = auth(&req, &c).await?;
+    let include = Include::from(include.into_inner());
 
-    Search::new(c.payment.subscription_repo(), c.payment.user_repo())
-        .exec(
-            user_id_and_role,
-            cmd.into_inner(),
-            include.into_inner().into(),
-            pagination.into_inner(),
-        )
-        .await
-        .map(|res| HttpResponse::Ok().json(res))
-        .map_err(PublicError::from)
+    Search::new(
+        c.identity.role_repo(),
+        c.payment.subscription_repo(),
+        c.payment.user_repo(),
+        c.cursor_signer(),
+    )
+    .exec(
+        user_id_and_role,
+        cmd.into_inner(),
+        include.clone(),
+        pagination.into_inner(),
+    )
+    .await
+    .map(|res| HttpResponse::Ok().json(include.select_in(&res, &["items"])))
+    .map_err(PublicError::from)
 }
 
 #[delete("")]
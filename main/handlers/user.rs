@@ -1,37 +1,72 @@
 use actix_web::{delete, get, http, post, put, web, HttpRequest, HttpResponse, Responder};
 
-use common::request::{IncludeParams, PaginationParams};
+use common::request::{Include, IncludeParams, PaginationParams};
 use identity::application::user::{
-    ChangePassword, ChangePasswordCommand, ChangePaymentEmail, ChangePaymentEmailCommand,
-    ChangeRole, ChangeRoleCommand, Delete, GetById, Login, LoginCommand, RecoverPassword,
-    RecoverPasswordCommand, Register, RegisterCommand, Search, SearchCommand, SetFlag,
-    SetFlagCommand, Update, UpdateCommand, Validate,
+    AuthorizeProvider, AuthorizeProviderLink, ChangeEmail, ChangeEmailCommand, ChangePassword,
+    ChangePasswordCommand, ChangePaymentEmail, ChangePaymentEmailCommand, ChangeRole,
+    ChangeRoleCommand, ChangeUsername, ChangeUsernameCommand, ConfirmEmailChange,
+    ConfirmEmailChangeCommand, Delete, DisableTwoFactor, EnableTwoFactor, EnrollTwoFactor,
+    EnrollTwoFactorOnLogin, EnrollTwoFactorOnLoginCommand, GetById, GetExternalIdentities,
+    GetSessions, LinkProvider, Login, LoginCommand, LoginWithProvider, LoginWithTwoFactor,
+    LoginWithTwoFactorCommand, Logout, ProviderCallbackCommand, RecoverPassword,
+    RecoverPasswordCommand, Refresh, RefreshCommand, RegenerateRecoveryCodes, Register,
+    RegisterCommand, ResetPassword, ResetPasswordCommand, Restore, RevokeSession, Search,
+    SearchCommand, SearchDeleted, SetFlag, SetFlagCommand, TwoFactorCodeCommand, UnlinkProvider,
+    Unlock, Update, UpdateCommand, Validate,
 };
 
-use crate::authorization::auth;
+use crate::authorization::{auth, browser_key, browser_key_cookie, device, token};
 use crate::container::MainContainer;
 use crate::error::PublicError;
 
 #[post("/register")]
-async fn register(cmd: web::Json<RegisterCommand>, c: web::Data<MainContainer>) -> impl Responder {
+async fn register(
+    req: HttpRequest,
+    cmd: web::Json<RegisterCommand>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
     Register::new(
         c.identity.event_pub(),
         c.identity.role_repo(),
         c.identity.user_repo(),
+        c.identity.throttling_serv(),
         c.identity.user_serv(),
     )
-    .exec(cmd.into_inner())
+    .exec(cmd.into_inner(), device(&req, c.config()))
     .await
     .map(|res| HttpResponse::Ok().json(res))
     .map_err(PublicError::from)
 }
 
 #[post("/login")]
-async fn login(cmd: web::Json<LoginCommand>, c: web::Data<MainContainer>) -> impl Responder {
+async fn login(
+    req: HttpRequest,
+    cmd: web::Json<LoginCommand>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
     Login::new(
         c.identity.event_pub(),
         c.identity.role_repo(),
         c.identity.authentication_serv(),
+        c.identity.throttling_serv(),
+        c.identity.token_serv(),
+    )
+    .exec(cmd.into_inner(), device(&req, c.config()))
+    .await
+    .map(|res| HttpResponse::Ok().json(res))
+    .map_err(PublicError::from)
+}
+
+#[post("/login/two-factor")]
+async fn login_with_two_factor(
+    cmd: web::Json<LoginWithTwoFactorCommand>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    LoginWithTwoFactor::new(
+        c.identity.event_pub(),
+        c.identity.role_repo(),
+        c.identity.authentication_serv(),
+        c.identity.token_serv(),
     )
     .exec(cmd.into_inner())
     .await
@@ -39,8 +74,82 @@ async fn login(cmd: web::Json<LoginCommand>, c: web::Data<MainContainer>) -> imp
     .map_err(PublicError::from)
 }
 
+#[post("/login/two-factor/enroll")]
+async fn enroll_two_factor_on_login(
+    cmd: web::Json<EnrollTwoFactorOnLoginCommand>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    EnrollTwoFactorOnLogin::new(c.identity.authentication_serv())
+        .exec(cmd.into_inner())
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
+#[get("/auth/{provider}/authorize")]
+async fn authorize_provider(
+    path: web::Path<String>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    AuthorizeProvider::new(c.identity.oauth_serv())
+        .exec(path.into_inner())
+        .await
+        .map(|res| {
+            HttpResponse::Ok()
+                .cookie(browser_key_cookie(res.browser_key.clone()))
+                .json(res)
+        })
+        .map_err(PublicError::from)
+}
+
+#[post("/auth/{provider}/callback")]
+async fn login_with_provider(
+    req: HttpRequest,
+    path: web::Path<String>,
+    cmd: web::Json<ProviderCallbackCommand>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    LoginWithProvider::new(
+        c.identity.event_pub(),
+        c.identity.role_repo(),
+        c.identity.authentication_serv(),
+        c.identity.oauth_serv(),
+        c.identity.token_serv(),
+    )
+    .exec(
+        path.into_inner(),
+        cmd.into_inner(),
+        browser_key(&req),
+        device(&req, c.config()),
+    )
+    .await
+    .map(|res| HttpResponse::Ok().json(res))
+    .map_err(PublicError::from)
+}
+
+#[post("/refresh")]
+async fn refresh(cmd: web::Json<RefreshCommand>, c: web::Data<MainContainer>) -> impl Responder {
+    Refresh::new(c.identity.token_serv())
+        .exec(cmd.into_inner())
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
+#[post("/logout")]
+async fn logout(req: HttpRequest, c: web::Data<MainContainer>) -> impl Responder {
+    let token = token(&req)?;
+
+    Logout::new(c.identity.token_serv())
+        .exec(token)
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
 #[post("/recover-password")]
 async fn recover_password(
+    req: HttpRequest,
     cmd: web::Json<RecoverPasswordCommand>,
     c: web::Data<MainContainer>,
 ) -> impl Responder {
@@ -48,9 +157,31 @@ async fn recover_password(
         c.identity.event_pub(),
         c.identity.role_repo(),
         c.identity.user_repo(),
+        c.identity.password_reset_sender(),
+        c.identity.throttling_serv(),
+    )
+    .exec(cmd.into_inner(), device(&req, c.config()))
+    .await
+    .map(|res| HttpResponse::Ok().json(res))
+    .map_err(PublicError::from)
+}
+
+#[post("/reset-password")]
+async fn reset_password(
+    req: HttpRequest,
+    cmd: web::Json<ResetPasswordCommand>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    ResetPassword::new(
+        c.identity.event_pub(),
+        c.identity.role_repo(),
+        c.identity.user_repo(),
+        c.identity.throttling_serv(),
+        c.identity.token_serv(),
         c.identity.user_serv(),
+        c.identity.unit_of_work(),
     )
-    .exec(cmd.into_inner())
+    .exec(cmd.into_inner(), device(&req, c.config()))
     .await
     .map(|res| HttpResponse::Ok().json(res))
     .map_err(PublicError::from)
@@ -65,17 +196,43 @@ async fn search(
     c: web::Data<MainContainer>,
 ) -> impl Responder {
     let user_id_and_role = auth(&req, &c).await?;
+    let include = Include::from(include.into_inner());
 
-    Search::new(c.identity.role_repo(), c.identity.user_repo())
-        .exec(
-            user_id_and_role,
-            cmd.into_inner(),
-            include.into_inner().into(),
-            pagination.into_inner(),
-        )
-        .await
-        .map(|res| HttpResponse::Ok().json(res))
-        .map_err(PublicError::from)
+    Search::new(
+        c.identity.role_repo(),
+        c.identity.user_repo(),
+        c.cursor_signer(),
+    )
+    .exec(
+        user_id_and_role,
+        cmd.into_inner(),
+        include.clone(),
+        pagination.into_inner(),
+    )
+    .await
+    .map(|res| HttpResponse::Ok().json(include.select_in(&res, &["items"])))
+    .map_err(PublicError::from)
+}
+
+#[get("/deleted")]
+async fn search_deleted(
+    req: HttpRequest,
+    include: web::Query<IncludeParams>,
+    pagination: web::Query<PaginationParams>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+    let include = Include::from(include.into_inner());
+
+    SearchDeleted::new(
+        c.identity.role_repo(),
+        c.identity.user_repo(),
+        c.cursor_signer(),
+    )
+    .exec(user_id_and_role, include.clone(), pagination.into_inner())
+    .await
+    .map(|res| HttpResponse::Ok().json(include.select_in(&res, &["items"])))
+    .map_err(PublicError::from)
 }
 
 #[get("/{user_id}")]
@@ -86,6 +243,7 @@ async fn get_by_id(
     c: web::Data<MainContainer>,
 ) -> impl Responder {
     let user_id_and_role = auth(&req, &c).await?;
+    let include = Include::from(include.into_inner());
 
     let mut user_id = path.into_inner();
     if user_id == "me" {
@@ -93,9 +251,9 @@ async fn get_by_id(
     }
 
     GetById::new(c.identity.role_repo(), c.identity.user_repo())
-        .exec(user_id_and_role, user_id, include.into_inner().into())
+        .exec(user_id_and_role, user_id, include.clone())
         .await
-        .map(|res| HttpResponse::Ok().json(res))
+        .map(|res| HttpResponse::Ok().json(include.select(&res)))
         .map_err(PublicError::from)
 }
 
@@ -120,6 +278,82 @@ async fn update(
         .map_err(PublicError::from)
 }
 
+#[put("/{user_id}/username")]
+async fn change_username(
+    req: HttpRequest,
+    path: web::Path<String>,
+    cmd: web::Json<ChangeUsernameCommand>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    let mut user_id = path.into_inner();
+    if user_id == "me" {
+        user_id = user_id_and_role.0.to_string();
+    }
+
+    ChangeUsername::new(
+        c.identity.event_pub(),
+        c.identity.user_repo(),
+        c.identity.user_serv(),
+    )
+    .exec(user_id_and_role, user_id, cmd.into_inner())
+    .await
+    .map(|res| HttpResponse::Ok().json(res))
+    .map_err(PublicError::from)
+}
+
+#[put("/{user_id}/email")]
+async fn change_email(
+    req: HttpRequest,
+    path: web::Path<String>,
+    cmd: web::Json<ChangeEmailCommand>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    let mut user_id = path.into_inner();
+    if user_id == "me" {
+        user_id = user_id_and_role.0.to_string();
+    }
+
+    ChangeEmail::new(
+        c.identity.event_pub(),
+        c.identity.user_repo(),
+        c.identity.email_change_sender(),
+        c.identity.user_serv(),
+    )
+    .exec(user_id_and_role, user_id, cmd.into_inner())
+    .await
+    .map(|res| HttpResponse::Ok().json(res))
+    .map_err(PublicError::from)
+}
+
+#[post("/{user_id}/email/confirm")]
+async fn confirm_email_change(
+    req: HttpRequest,
+    path: web::Path<String>,
+    cmd: web::Json<ConfirmEmailChangeCommand>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    let mut user_id = path.into_inner();
+    if user_id == "me" {
+        user_id = user_id_and_role.0.to_string();
+    }
+
+    ConfirmEmailChange::new(
+        c.identity.event_pub(),
+        c.identity.user_repo(),
+        c.identity.user_serv(),
+    )
+    .exec(user_id_and_role, user_id, cmd.into_inner())
+    .await
+    .map(|res| HttpResponse::Ok().json(res))
+    .map_err(PublicError::from)
+}
+
 #[delete("/{user_id}")]
 async fn delete(
     req: HttpRequest,
@@ -140,6 +374,254 @@ async fn delete(
         .map_err(PublicError::from)
 }
 
+#[post("/{user_id}/restore")]
+async fn restore(
+    req: HttpRequest,
+    path: web::Path<String>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    Restore::new(c.identity.event_pub(), c.identity.user_repo())
+        .exec(user_id_and_role, path.into_inner())
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
+#[post("/{user_id}/unlock")]
+async fn unlock(
+    req: HttpRequest,
+    path: web::Path<String>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    Unlock::new(c.identity.event_pub(), c.identity.user_repo())
+        .exec(user_id_and_role, path.into_inner())
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
+#[get("/{user_id}/sessions")]
+async fn get_sessions(
+    req: HttpRequest,
+    path: web::Path<String>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    let mut user_id = path.into_inner();
+    if user_id == "me" {
+        user_id = user_id_and_role.0.to_string();
+    }
+
+    GetSessions::new(c.identity.token_serv())
+        .exec(user_id_and_role, user_id)
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
+#[delete("/{user_id}/sessions/{session_id}")]
+async fn revoke_session(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    let (mut user_id, session_id) = path.into_inner();
+    if user_id == "me" {
+        user_id = user_id_and_role.0.to_string();
+    }
+
+    RevokeSession::new(c.identity.token_serv())
+        .exec(user_id_and_role, user_id, session_id)
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
+#[get("/{user_id}/providers")]
+async fn get_external_identities(
+    req: HttpRequest,
+    path: web::Path<String>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    let mut user_id = path.into_inner();
+    if user_id == "me" {
+        user_id = user_id_and_role.0.to_string();
+    }
+
+    GetExternalIdentities::new(c.identity.oauth_serv())
+        .exec(user_id_and_role, user_id)
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
+#[post("/{user_id}/providers/{provider}/authorize")]
+async fn authorize_provider_link(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    let (mut user_id, provider) = path.into_inner();
+    if user_id == "me" {
+        user_id = user_id_and_role.0.to_string();
+    }
+
+    AuthorizeProviderLink::new(c.identity.oauth_serv())
+        .exec(user_id_and_role, user_id, provider)
+        .await
+        .map(|res| {
+            HttpResponse::Ok()
+                .cookie(browser_key_cookie(res.browser_key.clone()))
+                .json(res)
+        })
+        .map_err(PublicError::from)
+}
+
+#[post("/{user_id}/providers/{provider}")]
+async fn link_provider(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    cmd: web::Json<ProviderCallbackCommand>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    let (mut user_id, provider) = path.into_inner();
+    if user_id == "me" {
+        user_id = user_id_and_role.0.to_string();
+    }
+
+    LinkProvider::new(c.identity.oauth_serv())
+        .exec(
+            user_id_and_role,
+            user_id,
+            provider,
+            cmd.into_inner(),
+            browser_key(&req),
+        )
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
+#[delete("/{user_id}/providers/{provider}")]
+async fn unlink_provider(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    let (mut user_id, provider) = path.into_inner();
+    if user_id == "me" {
+        user_id = user_id_and_role.0.to_string();
+    }
+
+    UnlinkProvider::new(c.identity.user_repo(), c.identity.oauth_serv())
+        .exec(user_id_and_role, user_id, provider)
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
+#[post("/{user_id}/two-factor")]
+async fn enroll_two_factor(
+    req: HttpRequest,
+    path: web::Path<String>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    let mut user_id = path.into_inner();
+    if user_id == "me" {
+        user_id = user_id_and_role.0.to_string();
+    }
+
+    EnrollTwoFactor::new(c.identity.user_repo())
+        .exec(user_id_and_role, user_id)
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
+#[put("/{user_id}/two-factor")]
+async fn enable_two_factor(
+    req: HttpRequest,
+    path: web::Path<String>,
+    cmd: web::Json<TwoFactorCodeCommand>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    let mut user_id = path.into_inner();
+    if user_id == "me" {
+        user_id = user_id_and_role.0.to_string();
+    }
+
+    EnableTwoFactor::new(c.identity.event_pub(), c.identity.user_repo())
+        .exec(user_id_and_role, user_id, cmd.into_inner())
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
+#[delete("/{user_id}/two-factor")]
+async fn disable_two_factor(
+    req: HttpRequest,
+    path: web::Path<String>,
+    cmd: web::Json<TwoFactorCodeCommand>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    let mut user_id = path.into_inner();
+    if user_id == "me" {
+        user_id = user_id_and_role.0.to_string();
+    }
+
+    DisableTwoFactor::new(
+        c.identity.event_pub(),
+        c.identity.role_repo(),
+        c.identity.user_repo(),
+    )
+    .exec(user_id_and_role, user_id, cmd.into_inner())
+    .await
+    .map(|res| HttpResponse::Ok().json(res))
+    .map_err(PublicError::from)
+}
+
+#[post("/{user_id}/two-factor/recovery-codes")]
+async fn regenerate_recovery_codes(
+    req: HttpRequest,
+    path: web::Path<String>,
+    cmd: web::Json<TwoFactorCodeCommand>,
+    c: web::Data<MainContainer>,
+) -> impl Responder {
+    let user_id_and_role = auth(&req, &c).await?;
+
+    let mut user_id = path.into_inner();
+    if user_id == "me" {
+        user_id = user_id_and_role.0.to_string();
+    }
+
+    RegenerateRecoveryCodes::new(c.identity.event_pub(), c.identity.user_repo())
+        .exec(user_id_and_role, user_id, cmd.into_inner())
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(PublicError::from)
+}
+
 #[put("/{user_id}/password")]
 async fn change_password(
     req: HttpRequest,
@@ -286,13 +768,36 @@ async fn set_flag(
 pub fn routes(cfg: &mut web::ServiceConfig) {
     cfg.service(register)
         .service(login)
+        .service(login_with_two_factor)
+        .service(enroll_two_factor_on_login)
+        .service(authorize_provider)
+        .service(login_with_provider)
+        .service(refresh)
+        .service(logout)
         .service(recover_password)
         .service(
             web::scope("/users")
+                .service(reset_password)
                 .service(search)
+                .service(search_deleted)
                 .service(get_by_id)
                 .service(update)
+                .service(change_username)
+                .service(change_email)
+                .service(confirm_email_change)
                 .service(delete)
+                .service(restore)
+                .service(unlock)
+                .service(get_sessions)
+                .service(revoke_session)
+                .service(get_external_identities)
+                .service(authorize_provider_link)
+                .service(link_provider)
+                .service(unlink_provider)
+                .service(enroll_two_factor)
+                .service(enable_two_factor)
+                .service(disable_two_factor)
+                .service(regenerate_recovery_codes)
                 .service(change_password)
                 .service(validate)
                 .service(change_role)
@@ -0,0 +1,61 @@
+use std::task::{Context, Poll};
+
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header;
+use actix_web::Error;
+use futures::future::{self, LocalBoxFuture, Ready};
+
+use common::error::Language;
+
+/// AcceptLanguage middleware handles every request in the language selected by its
+/// `Accept-Language` header, so errors are returned with messages users can read.
+pub struct AcceptLanguage;
+
+impl<S, B> Transform<S> for AcceptLanguage
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = AcceptLanguageMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        future::ok(AcceptLanguageMiddleware { service })
+    }
+}
+
+pub struct AcceptLanguageMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service for AcceptLanguageMiddleware<S>
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: ServiceRequest) -> Self::Future {
+        let language = req
+            .headers()
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|header| header.to_str().ok())
+            .map(Language::from_accept_language)
+            .unwrap_or_default();
+
+        Box::pin(language.scope(self.service.call(req)))
+    }
+}
@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::oneshot::Receiver;
+
+use common::event::{
+    DeadLetter, Event, EventHandler, EventPublisher, EventSubscriber, PublicationResult,
+};
+use common::infrastructure::event::{InMemEventBus, PostgresEventBus};
+use common::result::Result;
+
+/// EventBus is the implementation selected through `Config::event_bus`, so contexts don't depend
+/// on a specific one.
+pub enum EventBus {
+    InMem(InMemEventBus),
+    Postgres(Arc<PostgresEventBus>),
+}
+
+impl EventBus {
+    pub async fn replay(&self, dead_letter: &DeadLetter) -> Result<Receiver<PublicationResult>> {
+        match self {
+            EventBus::InMem(bus) => bus.replay(dead_letter).await,
+            EventBus::Postgres(bus) => bus.replay(dead_letter).await,
+        }
+    }
+
+    /// Starts consuming stored events. Only needed by buses shared among instances.
+    pub fn start(&self) {
+        if let EventBus::Postgres(bus) = self {
+            Arc::clone(bus).start();
+        }
+    }
+
+    /// Whether published events are stored by the bus itself.
+    pub fn is_durable(&self) -> bool {
+        matches!(self, EventBus::Postgres(_))
+    }
+}
+
+#[async_trait]
+impl EventPublisher for EventBus {
+    async fn publish(&self, event: Event) -> Result<Receiver<PublicationResult>> {
+        match self {
+            EventBus::InMem(bus) => bus.publish(event).await,
+            EventBus::Postgres(bus) => bus.publish(event).await,
+        }
+    }
+
+    async fn publish_all(&self, events: Vec<Event>) -> Result<Receiver<PublicationResult>> {
+        match self {
+            EventBus::InMem(bus) => bus.publish_all(events).await,
+            EventBus::Postgres(bus) => bus.publish_all(events).await,
+        }
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for EventBus {
+    async fn subscribe(&self, handler: Box<dyn EventHandler>) -> Result<bool> {
+        match self {
+            EventBus::InMem(bus) => bus.subscribe(handler).await,
+            EventBus::Postgres(bus) => bus.subscribe(handler).await,
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use regex::Regex;
+use tokio::sync::mpsc;
+
+use common::event::{Event, EventHandler};
+use common::result::Result;
+use identity::domain::user::UserId;
+use shared::event::NotificationEvent;
+
+pub enum StreamScope {
+    /// Raw domain events, for users with permission to manage events.
+    Events,
+    /// Only the notifications of the given user.
+    Notifications(UserId),
+}
+
+pub struct StreamFilter {
+    scope: StreamScope,
+    topic: Option<Regex>,
+}
+
+impl StreamFilter {
+    pub fn new(scope: StreamScope, topic: Option<Regex>) -> Self {
+        StreamFilter { scope, topic }
+    }
+
+    pub fn matches(&self, event: &Event) -> bool {
+        if let Some(topic) = &self.topic {
+            if !topic.is_match(event.topic()) {
+                return false;
+            }
+        }
+
+        match &self.scope {
+            StreamScope::Events => true,
+            StreamScope::Notifications(user_id) => {
+                if event.topic() != "notification" {
+                    return false;
+                }
+
+                match serde_json::from_value::<NotificationEvent>(event.payload()) {
+                    Ok(notification) => notification.user_id() == user_id.value(),
+                    Err(_) => false,
+                }
+            }
+        }
+    }
+}
+
+struct Listener {
+    filter: Arc<StreamFilter>,
+    sender: mpsc::Sender<Event>,
+}
+
+/// EventStream fans out the events published in the event bus to the connected clients. Each
+/// client has a bounded buffer: clients that don't keep up are disconnected and have to resume
+/// from the last event they received.
+pub struct EventStream {
+    listeners: Mutex<Vec<Listener>>,
+    buffer: usize,
+}
+
+impl EventStream {
+    pub fn new() -> Self {
+        EventStream {
+            listeners: Mutex::new(Vec::new()),
+            buffer: 256,
+        }
+    }
+
+    pub fn listen(&self, filter: Arc<StreamFilter>) -> mpsc::Receiver<Event> {
+        let (sender, receiver) = mpsc::channel(self.buffer);
+        self.listeners
+            .lock()
+            .unwrap()
+            .push(Listener { filter, sender });
+        receiver
+    }
+
+    pub fn broadcast(&self, event: &Event) {
+        let mut listeners = self.listeners.lock().unwrap();
+        let current = std::mem::take(&mut *listeners);
+
+        *listeners = current
+            .into_iter()
+            .filter_map(|mut listener| {
+                if !listener.filter.matches(event) {
+                    return Some(listener);
+                }
+
+                // Closed or full buffers remove the listener.
+                listener
+                    .sender
+                    .try_send(event.clone())
+                    .ok()
+                    .map(|_| listener)
+            })
+            .collect();
+    }
+
+    pub fn listeners(&self) -> usize {
+        self.listeners.lock().unwrap().len()
+    }
+}
+
+impl Default for EventStream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct EventStreamHandler {
+    event_stream: Arc<EventStream>,
+}
+
+impl EventStreamHandler {
+    pub fn new(event_stream: Arc<EventStream>) -> Self {
+        EventStreamHandler { event_stream }
+    }
+}
+
+#[async_trait]
+impl EventHandler for EventStreamHandler {
+    fn topic(&self) -> &str {
+        ".*"
+    }
+
+    async fn handle(&mut self, event: &Event) -> Result<bool> {
+        self.event_stream.broadcast(event);
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use common::event::ToEvent;
+    use serde_json::json;
+
+    fn notification(user_id: &str) -> Event {
+        NotificationEvent::Created {
+            id: "#notification01".to_owned(),
+            user_id: user_id.to_owned(),
+            code: "welcome".to_owned(),
+            body: json!({}),
+        }
+        .to_event()
+        .unwrap()
+    }
+
+    #[test]
+    fn filter_by_scope_and_topic() {
+        let event = Event::new("publication", "published", json!({}));
+
+        let filter = StreamFilter::new(StreamScope::Events, None);
+        assert!(filter.matches(&event));
+        assert!(filter.matches(&notification("#user01")));
+
+        let filter = StreamFilter::new(StreamScope::Events, Some(Regex::new("^user").unwrap()));
+        assert!(!filter.matches(&event));
+
+        let filter = StreamFilter::new(
+            StreamScope::Notifications(UserId::new("#user01").unwrap()),
+            None,
+        );
+        assert!(!filter.matches(&event));
+        assert!(filter.matches(&notification("#user01")));
+        assert!(!filter.matches(&notification("#user02")));
+    }
+
+    #[tokio::test]
+    async fn broadcast() {
+        let event_stream = EventStream::new();
+        let mut user01 = event_stream.listen(Arc::new(StreamFilter::new(
+            StreamScope::Notifications(UserId::new("#user01").unwrap()),
+            None,
+        )));
+        let mut admin = event_stream.listen(Arc::new(StreamFilter::new(StreamScope::Events, None)));
+        let closed = event_stream.listen(Arc::new(StreamFilter::new(StreamScope::Events, None)));
+        drop(closed);
+
+        event_stream.broadcast(&notification("#user01"));
+        event_stream.broadcast(&notification("#user02"));
+
+        assert_eq!(event_stream.listeners(), 2);
+        assert_eq!(user01.recv().await.unwrap().code(), "created");
+        assert!(admin.recv().await.is_some());
+        assert!(admin.recv().await.is_some());
+    }
+}
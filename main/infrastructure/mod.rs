@@ -1 +1,8 @@
-
+mod accept_language;
+mod event_bus;
+mod event_stream;
+mod request_id;
+pub use accept_language::*;
+pub use event_bus::*;
+pub use event_stream::*;
+pub use request_id::*;
@@ -0,0 +1,116 @@
+use std::task::{Context, Poll};
+
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::{HeaderName, HeaderValue};
+use actix_web::Error;
+use futures::future::{self, LocalBoxFuture, Ready};
+use uuid::Uuid;
+
+use common::event::EventMetadata;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Length of a hyphenated UUID. Request IDs received from clients longer than this are replaced
+/// by a new one without parsing them.
+const MAX_REQUEST_ID_LENGTH: usize = 36;
+
+/// Returns the request ID sent by the client if it's a UUID, or a new one if it's missing or
+/// invalid, so clients can't put arbitrary data in the metadata of events and in logs.
+pub fn request_id(header: Option<&HeaderValue>) -> String {
+    header
+        .and_then(|header| header.to_str().ok())
+        .map(str::trim)
+        .filter(|id| id.len() <= MAX_REQUEST_ID_LENGTH)
+        .and_then(|id| Uuid::parse_str(id).ok())
+        .unwrap_or_else(Uuid::new_v4)
+        .to_string()
+}
+
+/// RequestId middleware assigns an ID to every request and returns it in the `X-Request-ID`
+/// header. The request is handled inside an `EventMetadata` scope, so every event published by
+/// use cases and handlers while processing it has the request ID as correlation ID.
+pub struct RequestId;
+
+impl<S, B> Transform<S> for RequestId
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequestIdMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        future::ok(RequestIdMiddleware { service })
+    }
+}
+
+pub struct RequestIdMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service for RequestIdMiddleware<S>
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: ServiceRequest) -> Self::Future {
+        let request_id = request_id(req.headers().get(REQUEST_ID_HEADER));
+        let res = EventMetadata::from_request(request_id.as_str()).scope(self.service.call(req));
+
+        Box::pin(async move {
+            let mut res = res.await?;
+
+            if let Ok(value) = HeaderValue::from_str(&request_id) {
+                res.headers_mut()
+                    .insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+            }
+
+            Ok(res)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keep_valid_request_id() {
+        let header = HeaderValue::from_static("8b4e8c6e-2b0a-4d9f-9a8e-0f6f1a3b2c1d");
+        assert_eq!(
+            request_id(Some(&header)),
+            "8b4e8c6e-2b0a-4d9f-9a8e-0f6f1a3b2c1d"
+        );
+    }
+
+    #[test]
+    fn generate_missing_or_invalid_request_id() {
+        assert!(Uuid::parse_str(&request_id(None)).is_ok());
+
+        let header = HeaderValue::from_static("  ");
+        assert!(Uuid::parse_str(&request_id(Some(&header))).is_ok());
+
+        let header = HeaderValue::from_static("request-123");
+        let id = request_id(Some(&header));
+        assert_ne!(id, "request-123");
+        assert!(Uuid::parse_str(&id).is_ok());
+
+        let header = HeaderValue::from_str(&"a".repeat(MAX_REQUEST_ID_LENGTH + 1)).unwrap();
+        assert!(Uuid::parse_str(&request_id(Some(&header))).is_ok());
+    }
+}
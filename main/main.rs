@@ -1,5 +1,6 @@
 mod application;
 mod authorization;
+mod cli;
 mod container;
 mod development;
 mod error;
@@ -14,9 +15,10 @@ use common::config::Config;
 
 use container::MainContainer;
 use handlers::{
-    author, backup, category, collection, configuration, contract, donation, event, file,
+    author, backup, category, collection, configuration, contract, donation, event, file, health,
     notification, payment, plan, publication, reader, report, role, subscription, user,
 };
+use infrastructure::{AcceptLanguage, RequestId};
 
 async fn index() -> impl Responder {
     HttpResponse::Ok().body("Omics")
@@ -26,14 +28,50 @@ async fn index() -> impl Responder {
 async fn main() -> std::io::Result<()> {
     env_logger::init();
 
-    let config = Config::get();
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(err) => {
+            println!("Config: {}", err);
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "invalid configuration",
+            ));
+        }
+    };
+    error::show_internal_errors(config.env() == "development");
 
     // Dependencies
-    let container = web::Data::new(MainContainer::new().await);
+    let container = match MainContainer::new(config.clone()).await {
+        Ok(container) => web::Data::new(container),
+        Err(err) => {
+            // Exits with an error so the process is restarted, instead of serving requests that
+            // would fail.
+            println!("Container: {}", err);
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "dependencies not available",
+            ));
+        }
+    };
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match cli::run(&container, &args).await {
+        Ok(true) => return Ok(()),
+        Ok(false) => {}
+        Err(err) => {
+            println!("{}", err);
+            return Ok(());
+        }
+    }
+
     if let Err(err) = container.subscribe().await {
         println!("Subscriptions: {}", err);
         return Ok(());
     }
+    if let Err(err) = container.start().await {
+        println!("Start: {}", err);
+        return Ok(());
+    }
 
     // if config.env() == "development" {
     //     if let Err(err) = development::populate(&container).await {
@@ -46,11 +84,15 @@ async fn main() -> std::io::Result<()> {
 
     HttpServer::new(move || {
         App::new()
-            .wrap(Cors::new().finish())
+            // The browser key of the authorizations with providers is sent in a cookie.
+            .wrap(Cors::new().supports_credentials().finish())
+            .wrap(RequestId)
+            .wrap(AcceptLanguage)
             .app_data(container.clone())
             .service(
                 web::scope("/api")
                     .route("/dev", web::get().to(index))
+                    .configure(health::routes)
                     .configure(file::routes)
                     .configure(author::routes)
                     .configure(category::routes)
@@ -1,8 +1,9 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 
-use common::event::{Event, EventHandler};
+use common::event::{Event, EventHandler, RetryPolicy};
 use common::result::Result;
 use identity::domain::user::UserRepository;
 use publishing::domain::publication::{PublicationId, PublicationRepository};
@@ -37,6 +38,11 @@ impl EventHandler for ApprovedRejectedPublicationHandler {
         "publication"
     }
 
+    // Emails can fail because of the provider, so they are retried for a longer time.
+    fn retry_policy(&self) -> Option<RetryPolicy> {
+        Some(RetryPolicy::exponential(5, Duration::from_secs(1)))
+    }
+
     async fn handle(&mut self, event: &Event) -> Result<bool> {
         let event: PublicationEvent = serde_json::from_value(event.payload())?;
 
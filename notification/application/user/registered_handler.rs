@@ -1,8 +1,9 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 
-use common::event::{Event, EventHandler};
+use common::event::{Event, EventHandler, RetryPolicy};
 use common::result::Result;
 use shared::event::UserEvent;
 
@@ -24,6 +25,11 @@ impl EventHandler for RegisteredHandler {
         "user"
     }
 
+    // Emails can fail because of the provider, so they are retried for a longer time.
+    fn retry_policy(&self) -> Option<RetryPolicy> {
+        Some(RetryPolicy::exponential(5, Duration::from_secs(1)))
+    }
+
     async fn handle(&mut self, event: &Event) -> Result<bool> {
         let event: UserEvent = serde_json::from_value(event.payload())?;
 
@@ -59,25 +65,89 @@ impl EventHandler for RegisteredHandler {
 
                 self.email_serv.send(&email).await?;
             }
-            UserEvent::PasswordRecoveryRequested {
-                id,
-                temp_password,
+            UserEvent::PasswordReset { email, .. } => {
+                let email = Email::new(
+                    email,
+                    "Tu contraseña fue cambiada".to_owned(),
+                    r#"
+                    <p>
+                        <b>Hola</b>.
+                    </p>
+                    <p>
+                        Tu contraseña fue cambiada y se cerraron todas tus sesiones.
+                    </p>
+                    <p>
+                        Si no fuiste vos, recuperá tu contraseña y contactanos.
+                    </p>
+                    "#
+                    .to_owned(),
+                )?;
+
+                self.email_serv.send(&email).await?;
+            }
+            UserEvent::LockedOut {
                 email,
+                locked_until,
+                ..
             } => {
                 let email = Email::new(
                     email,
-                    "Recuperar contraseña".to_owned(),
+                    "Tu cuenta fue bloqueada".to_owned(),
                     format!(
                         r#"
                         <p>
                             <b>Hola</b>.
                         </p>
                         <p>
-                            Recupera tu contraseña desde el siguiente
-                            <a href="http://localhost:4200/home/{}/recover-password/{}">enlace</a>.
+                            Bloqueamos tu cuenta hasta {} porque hubo demasiados intentos
+                            fallidos de ingresar.
+                        </p>
+                        <p>
+                            Si no fuiste vos, te recomendamos cambiar tu contraseña.
+                        </p>
+                        "#,
+                        locked_until,
+                    ),
+                )?;
+
+                self.email_serv.send(&email).await?;
+            }
+            UserEvent::Unlocked { email, .. } => {
+                let email = Email::new(
+                    email,
+                    "Tu cuenta fue desbloqueada".to_owned(),
+                    r#"
+                    <p>
+                        <b>Hola</b>.
+                    </p>
+                    <p>
+                        Tu cuenta fue desbloqueada y ya podés ingresar.
+                    </p>
+                    "#
+                    .to_owned(),
+                )?;
+
+                self.email_serv.send(&email).await?;
+            }
+            UserEvent::EmailChanged {
+                email, old_email, ..
+            } => {
+                let email = Email::new(
+                    old_email,
+                    "Tu email fue cambiado".to_owned(),
+                    format!(
+                        r#"
+                        <p>
+                            <b>Hola</b>.
+                        </p>
+                        <p>
+                            El email de tu cuenta fue cambiado a {}.
+                        </p>
+                        <p>
+                            Si no fuiste vos, contactanos.
                         </p>
                         "#,
-                        id, temp_password,
+                        email,
                     ),
                 )?;
 
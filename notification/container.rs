@@ -2,9 +2,8 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 
-use common::config::Config;
 use common::container::Container;
-use common::event::{EventPublisher, EventSubscriber};
+use common::event::{EventHandler, EventPublisher, EventSubscriber};
 use common::result::Result;
 use identity::domain::user::UserRepository;
 use publishing::domain::author::AuthorRepository;
@@ -101,8 +100,6 @@ where
     where
         ES: EventSubscriber + Sync + Send,
     {
-        let _config = Config::get();
-
         // if config.env() == "production" {
         let registered_handler = RegisteredHandler::new(self.email_serv.clone());
         event_sub.subscribe(Box::new(registered_handler)).await?;
@@ -128,4 +125,9 @@ where
 
         Ok(())
     }
+
+    // No handler is replayed: replaying them would send the emails and the notifications again.
+    fn replay_handlers(&self) -> Vec<(&'static str, Box<dyn EventHandler>)> {
+        Vec::new()
+    }
 }
@@ -3,15 +3,17 @@ mod repository;
 pub use body::*;
 pub use repository::*;
 
-use common::model::{AggregateRoot, StringId};
+use common::model::{AggregateRoot, Events, StringId};
 use common::result::Result;
 use identity::domain::user::UserId;
+use shared::event::NotificationEvent;
 
 pub type NotificationId = StringId;
 
 #[derive(Debug, Clone)]
 pub struct Notification {
     base: AggregateRoot<NotificationId>,
+    events: Events<NotificationEvent>,
     user_id: UserId,
     code: String,
     body: Body,
@@ -25,13 +27,25 @@ impl Notification {
         code: S,
         body: Body,
     ) -> Result<Self> {
-        Ok(Notification {
+        let mut notification = Notification {
             base: AggregateRoot::new(id),
+            events: Events::new(),
             user_id,
             code: code.into(),
             body,
             read: false,
-        })
+        };
+
+        notification
+            .events
+            .record_event(NotificationEvent::Created {
+                id: notification.base().id().to_string(),
+                user_id: notification.user_id().to_string(),
+                code: notification.code().to_owned(),
+                body: serde_json::to_value(notification.body())?,
+            });
+
+        Ok(notification)
     }
 
     pub fn build(
@@ -43,6 +57,7 @@ impl Notification {
     ) -> Self {
         Notification {
             base,
+            events: Events::new(),
             user_id,
             code,
             body,
@@ -54,6 +69,14 @@ impl Notification {
         &self.base
     }
 
+    pub fn events(&self) -> &Events<NotificationEvent> {
+        &self.events
+    }
+
+    pub fn events_mut(&mut self) -> &mut Events<NotificationEvent> {
+        &mut self.events
+    }
+
     pub fn user_id(&self) -> &UserId {
         &self.user_id
     }
@@ -71,6 +94,15 @@ impl Notification {
     }
 
     pub fn mark_as_read(&mut self) {
+        if self.read {
+            return;
+        }
+
         self.read = true;
+
+        self.events.record_event(NotificationEvent::Read {
+            id: self.base().id().to_string(),
+            user_id: self.user_id().to_string(),
+        });
     }
 }
@@ -1,12 +1,11 @@
-use std::sync::Arc;
-
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use tokio_postgres::row::Row;
-use tokio_postgres::Client;
 use uuid::Uuid;
 
 use common::error::Error;
+use common::infrastructure::event::PostgresOutbox;
+use common::infrastructure::postgres::PostgresClient;
 use common::model::AggregateRoot;
 use common::result::Result;
 use common::sql::where_builder::WhereBuilder;
@@ -34,11 +33,11 @@ impl Notification {
 }
 
 pub struct PostgresNotificationRepository {
-    client: Arc<Client>,
+    client: PostgresClient,
 }
 
 impl PostgresNotificationRepository {
-    pub fn new(client: Arc<Client>) -> Self {
+    pub fn new(client: PostgresClient) -> Self {
         PostgresNotificationRepository { client }
     }
 }
@@ -76,6 +75,8 @@ impl NotificationRepository for PostgresNotificationRepository {
     }
 
     async fn save(&self, notification: &mut Notification) -> Result<()> {
+        let events = PostgresOutbox::records(&notification.events().to_vec()?)?;
+
         let create = self
             .client
             .query_one(
@@ -91,21 +92,24 @@ impl NotificationRepository for PostgresNotificationRepository {
         if create {
             self.client
                 .execute(
-                    "INSERT INTO notifications(
-                        id,
-                        user_id,
-                        code,
-                        body,
-                        read,
-                        datetime
-                    ) VALUES (
-                        $1,
-                        $2,
-                        $3,
-                        $4,
-                        $5,
-                        $6
-                    )",
+                    &PostgresOutbox::enlist(
+                        "INSERT INTO notifications(
+                            id,
+                            user_id,
+                            code,
+                            body,
+                            read,
+                            datetime
+                        ) VALUES (
+                            $1,
+                            $2,
+                            $3,
+                            $4,
+                            $5,
+                            $6
+                        )",
+                        7,
+                    ) as &str,
                     &[
                         &notification.base().id().to_uuid()?,
                         &notification.user_id().to_uuid()?,
@@ -113,6 +117,7 @@ impl NotificationRepository for PostgresNotificationRepository {
                         &body,
                         &notification.is_read(),
                         &notification.base().created_at(),
+                        &events,
                     ],
                 )
                 .await
@@ -120,20 +125,26 @@ impl NotificationRepository for PostgresNotificationRepository {
         } else {
             self.client
                 .execute(
-                    "UPDATE notifications
-                    SET
-                        read = $2
-                    WHERE
-                        id = $1",
+                    &PostgresOutbox::enlist(
+                        "UPDATE notifications
+                        SET
+                            read = $2
+                        WHERE
+                            id = $1",
+                        3,
+                    ) as &str,
                     &[
                         &notification.base().id().to_uuid()?,
                         &notification.is_read(),
+                        &events,
                     ],
                 )
                 .await
                 .map_err(|err| Error::new("notification", "update").wrap_raw(err))?;
         }
 
+        notification.events_mut().clear();
+
         Ok(())
     }
 }
@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use common::result::Result;
+use identity::domain::user::{EmailChangeSender, User};
+
+use crate::domain::email::{Email, EmailService};
+
+/// EmailEmailChangeSender emails the code of the change to the new email.
+pub struct EmailEmailChangeSender {
+    email_serv: Arc<dyn EmailService>,
+}
+
+impl EmailEmailChangeSender {
+    pub fn new(email_serv: Arc<dyn EmailService>) -> Self {
+        EmailEmailChangeSender { email_serv }
+    }
+}
+
+#[async_trait]
+impl EmailChangeSender for EmailEmailChangeSender {
+    async fn send(&self, user: &User, code: &str) -> Result<()> {
+        let email_change = match user.email_change() {
+            Some(email_change) => email_change,
+            None => return Ok(()),
+        };
+
+        let email = Email::new(
+            email_change.email().to_string(),
+            "Confirmá tu nuevo email".to_owned(),
+            format!(
+                r#"
+                <p>
+                    <b>Hola</b>.
+                </p>
+                <p>
+                    Para usar este email en tu cuenta, ingresá el siguiente código:
+                    <b>{}</b>. Vence en 24 horas.
+                </p>
+                <p>
+                    Si no lo pediste, podés ignorar este email.
+                </p>
+                "#,
+                code,
+            ),
+        )?;
+
+        self.email_serv.send(&email).await
+    }
+}
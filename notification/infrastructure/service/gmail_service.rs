@@ -9,26 +9,27 @@ use common::result::Result;
 
 use crate::domain::email::{Email, EmailService};
 
-#[derive(Default)]
-pub struct GmailService;
+pub struct GmailService {
+    server: String,
+    email: String,
+    password: String,
+}
 
 impl GmailService {
-    pub fn new() -> Self {
-        GmailService
+    pub fn new(config: &Config) -> Self {
+        GmailService {
+            server: config.smtp_server().to_owned(),
+            email: config.smtp_email().to_owned(),
+            password: config.smtp_password().to_owned(),
+        }
     }
 }
 
 #[async_trait]
 impl EmailService for GmailService {
     async fn send(&self, email: &Email) -> Result<()> {
-        let config = Config::get();
-
         let email = Message::builder()
-            .from(
-                format!("Equipo de Omics <{}>", config.smtp_email())
-                    .parse()
-                    .unwrap(),
-            )
+            .from(format!("Equipo de Omics <{}>", self.email).parse().unwrap())
             .to(format!("{} <{}>", email.to(), email.to()).parse().unwrap())
             .subject(email.title())
             .singlepart(
@@ -38,13 +39,10 @@ impl EmailService for GmailService {
             )
             .unwrap();
 
-        let creds = Credentials::new(
-            config.smtp_email().to_string(),
-            config.smtp_password().to_string(),
-        );
+        let creds = Credentials::new(self.email.clone(), self.password.clone());
 
         // Open a remote connection to gmail
-        let mailer = SmtpTransport::relay(config.smtp_server())
+        let mailer = SmtpTransport::relay(&self.server)
             .unwrap()
             .credentials(creds)
             .build();
@@ -1,2 +1,6 @@
+mod email_change_sender;
 mod gmail_service;
+mod password_reset_sender;
+pub use email_change_sender::*;
 pub use gmail_service::*;
+pub use password_reset_sender::*;
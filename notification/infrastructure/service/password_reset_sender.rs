@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use common::result::Result;
+use identity::domain::user::{PasswordResetSender, User};
+
+use crate::domain::email::{Email, EmailService};
+
+/// EmailPasswordResetSender emails the link to reset the password, which contains the token.
+pub struct EmailPasswordResetSender {
+    email_serv: Arc<dyn EmailService>,
+}
+
+impl EmailPasswordResetSender {
+    pub fn new(email_serv: Arc<dyn EmailService>) -> Self {
+        EmailPasswordResetSender { email_serv }
+    }
+}
+
+#[async_trait]
+impl PasswordResetSender for EmailPasswordResetSender {
+    async fn send(&self, user: &User, token: &str) -> Result<()> {
+        let email = Email::new(
+            user.identity().email().to_string(),
+            "Recuperar contraseña".to_owned(),
+            format!(
+                r#"
+                <p>
+                    <b>Hola</b>.
+                </p>
+                <p>
+                    Cambiá tu contraseña desde el siguiente
+                    <a href="http://localhost:4200/home/reset-password/{}">enlace</a>.
+                    Vence en una hora y solo se puede usar una vez.
+                </p>
+                <p>
+                    Si no lo pediste, podés ignorar este email.
+                </p>
+                "#,
+                token,
+            ),
+        )?;
+
+        self.email_serv.send(&email).await
+    }
+}
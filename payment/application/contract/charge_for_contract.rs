@@ -2,6 +2,7 @@ use common::config::ConfigService;
 use common::error::Error;
 use common::event::EventPublisher;
 use common::result::Result;
+use common::transaction::{self, UnitOfWork};
 use identity::domain::user::UserRepository;
 use identity::UserIdAndRole;
 use publishing::domain::publication::PublicationRepository;
@@ -18,6 +19,8 @@ pub struct ChargeForContract<'a> {
 
     config_serv: &'a ConfigService,
     payment_serv: &'a dyn PaymentService,
+
+    unit_of_work: &'a dyn UnitOfWork,
 }
 
 impl<'a> ChargeForContract<'a> {
@@ -28,6 +31,7 @@ impl<'a> ChargeForContract<'a> {
         user_repo: &'a dyn UserRepository,
         config_serv: &'a ConfigService,
         payment_serv: &'a dyn PaymentService,
+        unit_of_work: &'a dyn UnitOfWork,
     ) -> Self {
         ChargeForContract {
             event_pub,
@@ -36,6 +40,7 @@ impl<'a> ChargeForContract<'a> {
             user_repo,
             config_serv,
             payment_serv,
+            unit_of_work,
         }
     }
 
@@ -69,6 +74,15 @@ impl<'a> ChargeForContract<'a> {
             return Err(Error::new("contract", "minimum_charge"));
         }
 
+        // The paid summaries are committed before sending the payment, so the transaction isn't
+        // kept open during the request and a retry can't charge them again. If sending fails, the
+        // payment stays recorded in the contract and the error is returned to be resolved
+        // manually, instead of paying twice.
+        transaction::run(self.unit_of_work, async {
+            self.contract_repo.save(&mut contract).await
+        })
+        .await?;
+
         self.payment_serv
             .send_payment(
                 user.payment_email().unwrap().to_string(),
@@ -80,8 +94,6 @@ impl<'a> ChargeForContract<'a> {
             )
             .await?;
 
-        self.contract_repo.save(&mut contract).await?;
-
         self.event_pub
             .publish_all(contract.events().to_vec()?)
             .await?;
@@ -5,6 +5,7 @@ use common::event::EventPublisher;
 use identity::UserIdAndRole;
 
 use common::result::Result;
+use common::transaction::{self, UnitOfWork};
 use publishing::domain::publication::{PublicationId, PublicationRepository};
 
 use crate::domain::contract::{Contract, ContractRepository, ContractService};
@@ -21,6 +22,8 @@ pub struct Request<'a> {
     publication_repo: &'a dyn PublicationRepository,
 
     contract_serv: &'a ContractService,
+
+    unit_of_work: &'a dyn UnitOfWork,
 }
 
 impl<'a> Request<'a> {
@@ -29,12 +32,14 @@ impl<'a> Request<'a> {
         contract_repo: &'a dyn ContractRepository,
         publication_repo: &'a dyn PublicationRepository,
         contract_serv: &'a ContractService,
+        unit_of_work: &'a dyn UnitOfWork,
     ) -> Self {
         Request {
             event_pub,
             contract_repo,
             publication_repo,
             contract_serv,
+            unit_of_work,
         }
     }
 
@@ -52,18 +57,21 @@ impl<'a> Request<'a> {
 
         self.contract_serv.can_request(&publication_id).await?;
 
-        // TODO: should be done by a domain service
-        if let Ok(last) = self
-            .contract_repo
-            .find_by_publication_id(publication.base().id())
-            .await
-        {
-            self.contract_repo.delete(last.base().id()).await?;
-        }
-
         let mut contract = Contract::new(self.contract_repo.next_id().await?, &publication)?;
 
-        self.contract_repo.save(&mut contract).await?;
+        transaction::run(self.unit_of_work, async {
+            // TODO: should be done by a domain service
+            if let Ok(last) = self
+                .contract_repo
+                .find_by_publication_id(publication.base().id())
+                .await
+            {
+                self.contract_repo.delete(last.base().id()).await?;
+            }
+
+            self.contract_repo.save(&mut contract).await
+        })
+        .await?;
 
         self.event_pub
             .publish_all(contract.events().to_vec()?)
@@ -1,13 +1,16 @@
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 
 use chrono::DateTime;
 use serde::Deserialize;
 
 use common::error::Error;
-use common::request::{Include, PaginationParams, PaginationResponse};
+use common::request::{CursorSigner, Include, PaginationParams, PaginationResponse};
 use common::result::Result;
 use identity::UserIdAndRole;
-use publishing::application::dtos::PublicationDto;
+use publishing::application::expand::Expand;
+use publishing::domain::author::AuthorRepository;
+use publishing::domain::category::CategoryRepository;
 use publishing::domain::publication::{PublicationId, PublicationRepository};
 
 use crate::application::dtos::ContractDto;
@@ -22,18 +25,28 @@ pub struct SearchCommand {
 }
 
 pub struct Search<'a> {
+    author_repo: &'a dyn AuthorRepository,
+    category_repo: &'a dyn CategoryRepository,
     contract_repo: &'a dyn ContractRepository,
     publication_repo: &'a dyn PublicationRepository,
+
+    cursor_signer: &'a CursorSigner,
 }
 
 impl<'a> Search<'a> {
     pub fn new(
+        author_repo: &'a dyn AuthorRepository,
+        category_repo: &'a dyn CategoryRepository,
         contract_repo: &'a dyn ContractRepository,
         publication_repo: &'a dyn PublicationRepository,
+        cursor_signer: &'a CursorSigner,
     ) -> Self {
         Search {
+            author_repo,
+            category_repo,
             contract_repo,
             publication_repo,
+            cursor_signer,
         }
     }
 
@@ -48,6 +61,8 @@ impl<'a> Search<'a> {
             return Err(Error::unauthorized());
         }
 
+        include.validate(&ContractDto::SCHEMA)?;
+
         let pagination_contracts = self
             .contract_repo
             .search(
@@ -79,7 +94,8 @@ impl<'a> Search<'a> {
             )
             .await?;
 
-        let mut res = PaginationResponse::from(&pagination_contracts);
+        let mut res =
+            PaginationResponse::from_pagination(&pagination_contracts, self.cursor_signer);
         // let mut res = PaginationResponse::new(
         //     pagination_contracts.offset(),
         //     pagination_contracts.limit(),
@@ -87,15 +103,41 @@ impl<'a> Search<'a> {
         //     pagination_contracts.matching_criteria(),
         // );
 
-        for contract in pagination_contracts.into_items().into_iter() {
-            let mut contract_dto = ContractDto::from(&contract);
+        let contracts = pagination_contracts.items();
+
+        // Publications are loaded for the whole page with a single call, with their own
+        // relations.
+        let publications = if include.has("publication") {
+            let ids: HashSet<PublicationId> = contracts
+                .iter()
+                .map(|c| c.publication_id().clone())
+                .collect();
+            let ids: Vec<PublicationId> = ids.into_iter().collect();
+            let publications = self.publication_repo.find_by_ids(&ids).await?;
+            let publication_dtos = Expand::new(self.author_repo, self.category_repo)
+                .publications(&publications, &include.nested("publication"))
+                .await?;
+
+            Some(
+                publications
+                    .iter()
+                    .map(|publication| publication.base().id().clone())
+                    .zip(publication_dtos.into_iter())
+                    .collect::<HashMap<_, _>>(),
+            )
+        } else {
+            None
+        };
+
+        for contract in contracts.iter() {
+            let mut contract_dto = ContractDto::from(contract);
 
-            if include.has("publication") {
-                let publication = self
-                    .publication_repo
-                    .find_by_id(contract.publication_id())
-                    .await?;
-                contract_dto = contract_dto.publication(PublicationDto::from(&publication));
+            if let Some(publications) = &publications {
+                let publication = publications
+                    .get(contract.publication_id())
+                    .cloned()
+                    .ok_or_else(|| Error::not_found("publication"))?;
+                contract_dto = contract_dto.publication(publication);
             }
 
             res.add_item(contract_dto);
@@ -2,10 +2,12 @@ use common::error::Error;
 use common::event::EventPublisher;
 use common::request::CommandResponse;
 use common::result::Result;
+use common::sql::{Criteria, Query};
+use common::transaction::{self, UnitOfWork};
 use identity::domain::user::UserRepository;
 use identity::UserIdAndRole;
 
-use crate::domain::donation::{DonationRepository, Status};
+use crate::domain::donation::{DonationField, DonationRepository, Status};
 use crate::domain::payment::PaymentService;
 
 pub struct Charge<'a> {
@@ -15,6 +17,8 @@ pub struct Charge<'a> {
     user_repo: &'a dyn UserRepository,
 
     payment_serv: &'a dyn PaymentService,
+
+    unit_of_work: &'a dyn UnitOfWork,
 }
 
 impl<'a> Charge<'a> {
@@ -23,12 +27,14 @@ impl<'a> Charge<'a> {
         donation_repo: &'a dyn DonationRepository,
         user_repo: &'a dyn UserRepository,
         payment_serv: &'a dyn PaymentService,
+        unit_of_work: &'a dyn UnitOfWork,
     ) -> Self {
         Charge {
             event_pub,
             donation_repo,
             user_repo,
             payment_serv,
+            unit_of_work,
         }
     }
 
@@ -45,14 +51,12 @@ impl<'a> Charge<'a> {
         let pagination_donations = self
             .donation_repo
             .search(
-                Some(&auth_id),
-                None,
-                Some(&Status::Paid),
-                None,
-                None,
-                None,
-                None,
-                None,
+                &Query::new()
+                    .filter(Criteria::eq(DonationField::AuthorId, &auth_id))
+                    .filter(Criteria::eq(
+                        DonationField::Status,
+                        Status::Paid.to_string(),
+                    )),
             )
             .await?;
 
@@ -62,9 +66,20 @@ impl<'a> Charge<'a> {
         for donation in donations.iter_mut() {
             let payment = donation.charge()?;
             total += payment.amount().value();
+        }
+
+        // All the donations are charged together or none of them is, so the payment sent below
+        // always matches the donations marked as charged.
+        transaction::run(self.unit_of_work, async {
+            for donation in donations.iter_mut() {
+                self.donation_repo.save(donation).await?;
+            }
 
-            self.donation_repo.save(donation).await?;
+            Ok(())
+        })
+        .await?;
 
+        for donation in donations.iter() {
             self.event_pub
                 .publish_all(donation.events().to_vec()?)
                 .await?;
@@ -4,6 +4,7 @@ use common::config::ConfigService;
 use common::error::Error;
 use common::event::EventPublisher;
 use common::result::Result;
+use common::transaction::{self, UnitOfWork};
 use identity::domain::user::UserRepository;
 use identity::UserIdAndRole;
 use publishing::domain::author::{AuthorId, AuthorRepository};
@@ -34,6 +35,8 @@ pub struct Donate<'a> {
 
     config_serv: &'a ConfigService,
     payment_serv: &'a dyn PaymentService,
+
+    unit_of_work: &'a dyn UnitOfWork,
 }
 
 impl<'a> Donate<'a> {
@@ -45,6 +48,7 @@ impl<'a> Donate<'a> {
         user_repo: &'a dyn UserRepository,
         config_serv: &'a ConfigService,
         payment_serv: &'a dyn PaymentService,
+        unit_of_work: &'a dyn UnitOfWork,
     ) -> Self {
         Donate {
             event_pub,
@@ -54,6 +58,7 @@ impl<'a> Donate<'a> {
             user_repo,
             config_serv,
             payment_serv,
+            unit_of_work,
         }
     }
 
@@ -89,6 +94,13 @@ impl<'a> Donate<'a> {
             author_percentage,
         )?;
 
+        // The donation is committed before requesting the link, so the transaction isn't kept
+        // open during the request. If it fails, the donation stays pending and is never paid.
+        transaction::run(self.unit_of_work, async {
+            self.donation_repo.save(&mut donation).await
+        })
+        .await?;
+
         let payment_link = self
             .payment_serv
             .get_payment_link(
@@ -100,8 +112,6 @@ impl<'a> Donate<'a> {
             )
             .await?;
 
-        self.donation_repo.save(&mut donation).await?;
-
         self.event_pub
             .publish_all(donation.events().to_vec()?)
             .await?;
@@ -46,6 +46,8 @@ impl<'a> GetById<'a> {
             }
         }
 
+        include.validate(&DonationDto::SCHEMA)?;
+
         let mut donation_dto = DonationDto::from(&donation);
 
         if include.has("author") {
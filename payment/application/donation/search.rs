@@ -1,11 +1,13 @@
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 
-use chrono::DateTime;
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
 use common::error::Error;
-use common::request::{Include, PaginationParams, PaginationResponse};
+use common::request::{CursorSigner, Include, PaginationParams, PaginationResponse};
 use common::result::Result;
+use common::sql::{Criteria, Query};
 use identity::domain::user::UserId;
 use identity::UserIdAndRole;
 use publishing::application::dtos::{AuthorDto, ReaderDto};
@@ -13,7 +15,7 @@ use publishing::domain::author::AuthorRepository;
 use publishing::domain::reader::ReaderRepository;
 
 use crate::application::dtos::DonationDto;
-use crate::domain::donation::{DonationOrderBy, DonationRepository, Status};
+use crate::domain::donation::{DonationField, DonationOrderBy, DonationRepository, Status};
 
 #[derive(Deserialize)]
 pub struct SearchCommand {
@@ -28,6 +30,8 @@ pub struct Search<'a> {
     author_repo: &'a dyn AuthorRepository,
     donation_repo: &'a dyn DonationRepository,
     reader_repo: &'a dyn ReaderRepository,
+
+    cursor_signer: &'a CursorSigner,
 }
 
 impl<'a> Search<'a> {
@@ -35,11 +39,13 @@ impl<'a> Search<'a> {
         author_repo: &'a dyn AuthorRepository,
         donation_repo: &'a dyn DonationRepository,
         reader_repo: &'a dyn ReaderRepository,
+        cursor_signer: &'a CursorSigner,
     ) -> Self {
         Search {
             author_repo,
             donation_repo,
             reader_repo,
+            cursor_signer,
         }
     }
 
@@ -64,53 +70,103 @@ impl<'a> Search<'a> {
             }
         }
 
-        let pagination_donations = self
-            .donation_repo
-            .search(
-                cmd.author_id.map(UserId::new).transpose()?.as_ref(),
-                cmd.reader_id.map(UserId::new).transpose()?.as_ref(),
+        include.validate(&DonationDto::SCHEMA)?;
+
+        let order_by = pagination
+            .order_by()
+            .map(|o| DonationOrderBy::from_str(&o))
+            .transpose()?
+            .unwrap_or(DonationOrderBy::Oldest);
+        let (field, direction) = order_by.sort_key();
+
+        let query = Query::new()
+            .filter_opt(
+                cmd.author_id
+                    .map(UserId::new)
+                    .transpose()?
+                    .map(|id| Criteria::eq(DonationField::AuthorId, &id)),
+            )
+            .filter_opt(
+                cmd.reader_id
+                    .map(UserId::new)
+                    .transpose()?
+                    .map(|id| Criteria::eq(DonationField::ReaderId, &id)),
+            )
+            .filter_opt(
                 cmd.status
                     .map(|s| Status::from_str(&s))
                     .transpose()?
-                    .as_ref(),
+                    .map(|s| Criteria::eq(DonationField::Status, s.to_string())),
+            )
+            .filter(Criteria::range(
+                DonationField::CreatedAt,
                 cmd.date_from
-                    .map(|d| DateTime::from_str(&d))
+                    .map(|d| DateTime::<Utc>::from_str(&d))
                     .transpose()
-                    .map_err(|err| Error::bad_format("date_from").wrap_raw(err))?
-                    .as_ref(),
+                    .map_err(|err| Error::bad_format("date_from").wrap_raw(err))?,
                 cmd.date_to
-                    .map(|d| DateTime::from_str(&d))
+                    .map(|d| DateTime::<Utc>::from_str(&d))
                     .transpose()
-                    .map_err(|err| Error::bad_format("date_to").wrap_raw(err))?
-                    .as_ref(),
-                pagination.offset(),
-                pagination.limit(),
-                pagination
-                    .order_by()
-                    .map(|o| DonationOrderBy::from_str(&o))
-                    .transpose()?
-                    .as_ref(),
+                    .map_err(|err| Error::bad_format("date_to").wrap_raw(err))?,
+            ))
+            .sort(field, direction)
+            .cursor(DonationField::Id, pagination.cursor(self.cursor_signer)?)?
+            .paginate(pagination.offset(), pagination.limit());
+
+        let pagination_donations = self.donation_repo.search(&query).await?;
+
+        let mut res =
+            PaginationResponse::from_pagination(&pagination_donations, self.cursor_signer);
+        let donations = pagination_donations.items();
+
+        // Each relation is loaded for the whole page with a single call.
+        let authors: Option<HashMap<_, _>> = if include.has("author") {
+            let ids: HashSet<UserId> = donations.iter().map(|d| d.author_id().clone()).collect();
+            let ids: Vec<UserId> = ids.into_iter().collect();
+            let authors = self.author_repo.find_by_ids(&ids).await?;
+
+            Some(
+                authors
+                    .iter()
+                    .map(|author| (author.base().id().clone(), AuthorDto::from(author)))
+                    .collect(),
             )
-            .await?;
+        } else {
+            None
+        };
+
+        let readers: Option<HashMap<_, _>> = if include.has("reader") {
+            let ids: HashSet<UserId> = donations.iter().map(|d| d.reader_id().clone()).collect();
+            let ids: Vec<UserId> = ids.into_iter().collect();
+            let readers = self.reader_repo.find_by_ids(&ids).await?;
 
-        let mut res = PaginationResponse::new(
-            pagination_donations.offset(),
-            pagination_donations.limit(),
-            pagination_donations.total(),
-            pagination_donations.matching_criteria(),
-        );
+            Some(
+                readers
+                    .iter()
+                    .map(|reader| (reader.base().id().clone(), ReaderDto::from(reader)))
+                    .collect(),
+            )
+        } else {
+            None
+        };
 
-        for donation in pagination_donations.into_items().into_iter() {
-            let mut donation_dto = DonationDto::from(&donation);
+        for donation in donations.iter() {
+            let mut donation_dto = DonationDto::from(donation);
 
-            if include.has("author") {
-                let author = self.author_repo.find_by_id(donation.author_id()).await?;
-                donation_dto = donation_dto.author(AuthorDto::from(&author));
+            if let Some(authors) = &authors {
+                let author = authors
+                    .get(donation.author_id())
+                    .cloned()
+                    .ok_or_else(|| Error::not_found("author"))?;
+                donation_dto = donation_dto.author(author);
             }
 
-            if include.has("reader") {
-                let reader = self.reader_repo.find_by_id(donation.reader_id()).await?;
-                donation_dto = donation_dto.reader(ReaderDto::from(&reader));
+            if let Some(readers) = &readers {
+                let reader = readers
+                    .get(donation.reader_id())
+                    .cloned()
+                    .ok_or_else(|| Error::not_found("reader"))?;
+                donation_dto = donation_dto.reader(reader);
             }
 
             res.add_item(donation_dto);
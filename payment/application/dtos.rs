@@ -1,6 +1,7 @@
 use serde::Serialize;
 
 use common::model::StatusItem;
+use common::request::Schema;
 use identity::application::dtos::UserDto;
 use publishing::application::dtos::{AuthorDto, PublicationDto, ReaderDto, StatisticsDto};
 
@@ -91,6 +92,11 @@ pub struct SubscriptionDto {
 }
 
 impl SubscriptionDto {
+    pub const SCHEMA: Schema = Schema::new(
+        &["id", "user_id", "user", "plan", "payments", "status"],
+        &[("user", &UserDto::SCHEMA)],
+    );
+
     pub fn from(subscription: &Subscription) -> Self {
         SubscriptionDto {
             id: subscription.base().id().to_string(),
@@ -180,6 +186,18 @@ pub struct ContractDto {
 }
 
 impl ContractDto {
+    pub const SCHEMA: Schema = Schema::new(
+        &[
+            "id",
+            "publication_id",
+            "publication",
+            "summaries",
+            "payments",
+            "status",
+        ],
+        &[("publication", &PublicationDto::SCHEMA)],
+    );
+
     pub fn from(contract: &Contract) -> Self {
         ContractDto {
             id: contract.base().id().to_string(),
@@ -232,6 +250,27 @@ pub struct DonationDto {
 }
 
 impl DonationDto {
+    pub const SCHEMA: Schema = Schema::new(
+        &[
+            "id",
+            "author_id",
+            "author",
+            "reader_id",
+            "reader",
+            "total",
+            "subtotal",
+            "author_percentage",
+            "comment",
+            "reader_payment",
+            "author_charge",
+            "status",
+        ],
+        &[
+            ("author", &AuthorDto::SCHEMA),
+            ("reader", &ReaderDto::SCHEMA),
+        ],
+    );
+
     pub fn from(donation: &Donation) -> Self {
         DonationDto {
             id: donation.base().id().to_string(),
@@ -2,10 +2,11 @@ use common::error::Error;
 use common::event::EventPublisher;
 use common::request::CommandResponse;
 use common::result::Result;
+use common::sql::{Criteria, Query};
 use identity::UserIdAndRole;
 
 use crate::domain::plan::{PlanId, PlanRepository};
-use crate::domain::subscription::SubscriptionRepository;
+use crate::domain::subscription::{SubscriptionField, SubscriptionRepository};
 
 pub struct Delete<'a> {
     event_pub: &'a dyn EventPublisher,
@@ -40,9 +41,9 @@ impl<'a> Delete<'a> {
 
         let p_subscriptions = self
             .subscription_repo
-            .search(None, Some(&plan_id), None, None, None, None, None, None)
+            .search(&Query::new().filter(Criteria::eq(SubscriptionField::PlanId, &plan_id)))
             .await?;
-        if p_subscriptions.matching_criteria() > 0 {
+        if p_subscriptions.matching_criteria().unwrap_or(0) > 0 {
             return Err(Error::new("plan", "existing_subscriptions"));
         }
 
@@ -4,10 +4,11 @@ use async_trait::async_trait;
 
 use common::event::{Event, EventHandler, EventPublisher};
 use common::result::Result;
+use common::sql::{Criteria, Query};
 use shared::event::PlanEvent;
 
 use crate::domain::plan::{PlanId, PlanRepository};
-use crate::domain::subscription::SubscriptionRepository;
+use crate::domain::subscription::{SubscriptionField, SubscriptionRepository};
 
 pub struct PlanPriceChangedHandler {
     event_pub: Arc<dyn EventPublisher>,
@@ -44,7 +45,7 @@ impl EventHandler for PlanPriceChangedHandler {
                 let plan = self.plan_repo.find_by_id(&plan_id).await?;
                 let p_subscriptions = self
                     .subscription_repo
-                    .search(None, Some(&plan_id), None, None, None, None, None, None)
+                    .search(&Query::new().filter(Criteria::eq(SubscriptionField::PlanId, &plan_id)))
                     .await?;
 
                 for mut subscription in p_subscriptions.into_items() {
@@ -1,18 +1,23 @@
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 
-use chrono::DateTime;
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
 use common::error::Error;
-use common::request::{Include, PaginationParams, PaginationResponse};
+use common::request::{CursorSigner, Include, PaginationParams, PaginationResponse};
 use common::result::Result;
-use identity::application::dtos::UserDto;
+use common::sql::{Criteria, Query};
+use identity::application::expand::Expand;
+use identity::domain::role::RoleRepository;
 use identity::domain::user::{UserId, UserRepository};
 use identity::UserIdAndRole;
 
 use crate::application::dtos::SubscriptionDto;
 use crate::domain::plan::PlanId;
-use crate::domain::subscription::{Status, SubscriptionOrderBy, SubscriptionRepository};
+use crate::domain::subscription::{
+    Status, SubscriptionField, SubscriptionOrderBy, SubscriptionRepository,
+};
 
 #[derive(Deserialize)]
 pub struct SearchCommand {
@@ -24,18 +29,25 @@ pub struct SearchCommand {
 }
 
 pub struct Search<'a> {
+    role_repo: &'a dyn RoleRepository,
     subscription_repo: &'a dyn SubscriptionRepository,
     user_repo: &'a dyn UserRepository,
+
+    cursor_signer: &'a CursorSigner,
 }
 
 impl<'a> Search<'a> {
     pub fn new(
+        role_repo: &'a dyn RoleRepository,
         subscription_repo: &'a dyn SubscriptionRepository,
         user_repo: &'a dyn UserRepository,
+        cursor_signer: &'a CursorSigner,
     ) -> Self {
         Search {
+            role_repo,
             subscription_repo,
             user_repo,
+            cursor_signer,
         }
     }
 
@@ -50,43 +62,84 @@ impl<'a> Search<'a> {
             return Err(Error::unauthorized());
         }
 
-        let pagination_subscriptions = self
-            .subscription_repo
-            .search(
-                cmd.user_id.map(UserId::new).transpose()?.as_ref(),
-                cmd.plan_id.map(PlanId::new).transpose()?.as_ref(),
+        include.validate(&SubscriptionDto::SCHEMA)?;
+
+        let mut query = Query::new()
+            .filter_opt(
+                cmd.user_id
+                    .map(UserId::new)
+                    .transpose()?
+                    .map(|id| Criteria::eq(SubscriptionField::UserId, &id)),
+            )
+            .filter_opt(
+                cmd.plan_id
+                    .map(PlanId::new)
+                    .transpose()?
+                    .map(|id| Criteria::eq(SubscriptionField::PlanId, &id)),
+            )
+            .filter_opt(
                 cmd.status
                     .map(|s| Status::from_str(&s))
                     .transpose()?
-                    .as_ref(),
+                    .map(|s| Criteria::eq(SubscriptionField::Status, s.to_string())),
+            )
+            .filter(Criteria::range(
+                SubscriptionField::CreatedAt,
                 cmd.date_from
-                    .map(|d| DateTime::from_str(&d))
+                    .map(|d| DateTime::<Utc>::from_str(&d))
                     .transpose()
-                    .map_err(|err| Error::bad_format("date_from").wrap_raw(err))?
-                    .as_ref(),
+                    .map_err(|err| Error::bad_format("date_from").wrap_raw(err))?,
                 cmd.date_to
-                    .map(|d| DateTime::from_str(&d))
+                    .map(|d| DateTime::<Utc>::from_str(&d))
                     .transpose()
-                    .map_err(|err| Error::bad_format("date_to").wrap_raw(err))?
-                    .as_ref(),
-                pagination.offset(),
-                pagination.limit(),
-                pagination
-                    .order_by()
-                    .map(|o| SubscriptionOrderBy::from_str(&o))
-                    .transpose()?
-                    .as_ref(),
-            )
-            .await?;
+                    .map_err(|err| Error::bad_format("date_to").wrap_raw(err))?,
+            ))
+            .paginate(pagination.offset(), pagination.limit());
+
+        if let Some(order_by) = pagination
+            .order_by()
+            .map(|o| SubscriptionOrderBy::from_str(&o))
+            .transpose()?
+        {
+            let (field, direction) = order_by.sort_key();
+            query = query.sort(field, direction);
+        }
+
+        let pagination_subscriptions = self.subscription_repo.search(&query).await?;
 
-        let mut res = PaginationResponse::from(&pagination_subscriptions);
+        let mut res =
+            PaginationResponse::from_pagination(&pagination_subscriptions, self.cursor_signer);
+        let subscriptions = pagination_subscriptions.items();
+
+        // Users are loaded for the whole page with a single call, with their own relations.
+        let users = if include.has("user") {
+            let ids: HashSet<UserId> = subscriptions.iter().map(|s| s.user_id().clone()).collect();
+            let ids: Vec<UserId> = ids.into_iter().collect();
+            let users = self.user_repo.find_by_ids(&ids).await?;
+            let user_dtos = Expand::new(self.role_repo)
+                .users(&users, &include.nested("user"))
+                .await?;
+
+            Some(
+                users
+                    .iter()
+                    .map(|user| user.base().id().clone())
+                    .zip(user_dtos.into_iter())
+                    .collect::<HashMap<_, _>>(),
+            )
+        } else {
+            None
+        };
 
-        for subscription in pagination_subscriptions.into_items().into_iter() {
-            let mut subscription_dto = SubscriptionDto::from(&subscription);
+        for subscription in subscriptions.iter() {
+            let mut subscription_dto = SubscriptionDto::from(subscription);
 
-            if include.has("user") {
-                let user = self.user_repo.find_by_id(subscription.user_id()).await?;
-                subscription_dto = subscription_dto.user(UserDto::from(&user));
+            if let Some(users) = &users {
+                let user = users
+                    .get(subscription.user_id())
+                    .cloned()
+                    .ok_or_else(|| Error::not_found("user"))?;
+                subscription_dto = subscription_dto.user(user);
             }
 
             res.add_item(subscription_dto);
@@ -3,6 +3,7 @@ use serde::Serialize;
 use common::error::Error;
 use common::event::EventPublisher;
 use common::result::Result;
+use common::transaction::{self, UnitOfWork};
 use identity::domain::user::UserRepository;
 use identity::UserIdAndRole;
 use publishing::domain::reader::ReaderRepository;
@@ -26,6 +27,8 @@ pub struct Subscribe<'a> {
     user_repo: &'a dyn UserRepository,
 
     payment_serv: &'a dyn PaymentService,
+
+    unit_of_work: &'a dyn UnitOfWork,
 }
 
 impl<'a> Subscribe<'a> {
@@ -36,6 +39,7 @@ impl<'a> Subscribe<'a> {
         subscription_repo: &'a dyn SubscriptionRepository,
         user_repo: &'a dyn UserRepository,
         payment_serv: &'a dyn PaymentService,
+        unit_of_work: &'a dyn UnitOfWork,
     ) -> Self {
         Subscribe {
             event_pub,
@@ -44,6 +48,7 @@ impl<'a> Subscribe<'a> {
             subscription_repo,
             user_repo,
             payment_serv,
+            unit_of_work,
         }
     }
 
@@ -61,18 +66,15 @@ impl<'a> Subscribe<'a> {
         let plan = self.plan_repo.find_by_id(&PlanId::new(plan_id)?).await?;
 
         // TODO: should be done by a domain service
-        if let Ok(subscription) = self.subscription_repo.find_by_user_id(&auth_id).await {
-            match subscription.status_history().current() {
+        let previous = match self.subscription_repo.find_by_user_id(&auth_id).await {
+            Ok(subscription) => match subscription.status_history().current() {
                 Status::Active => {
                     return Err(Error::new("subscription", "already_exists"));
                 }
-                _ => {
-                    self.subscription_repo
-                        .delete(subscription.base().id())
-                        .await?;
-                }
-            }
-        }
+                _ => Some(subscription),
+            },
+            Err(_) => None,
+        };
 
         let mut subscription =
             Subscription::new(self.subscription_repo.next_id().await?, &reader, plan)?;
@@ -88,7 +90,14 @@ impl<'a> Subscribe<'a> {
             )
             .await?;
 
-        self.subscription_repo.save(&mut subscription).await?;
+        transaction::run(self.unit_of_work, async {
+            if let Some(previous) = &previous {
+                self.subscription_repo.delete(previous.base().id()).await?;
+            }
+
+            self.subscription_repo.save(&mut subscription).await
+        })
+        .await?;
 
         self.event_pub
             .publish_all(subscription.events().to_vec()?)
@@ -2,10 +2,11 @@ use common::error::Error;
 use common::event::EventPublisher;
 use common::request::CommandResponse;
 use common::result::Result;
+use common::sql::{Criteria, Query};
 
 use identity::UserIdAndRole;
 
-use crate::domain::subscription::{Status, SubscriptionRepository};
+use crate::domain::subscription::{Status, SubscriptionField, SubscriptionRepository};
 
 pub struct Unsubscribe<'a> {
     event_pub: &'a dyn EventPublisher,
@@ -31,7 +32,7 @@ impl<'a> Unsubscribe<'a> {
 
         let mut subscriptions = self
             .subscription_repo
-            .search(Some(&auth_id), None, None, None, None, None, None, None)
+            .search(&Query::new().filter(Criteria::eq(SubscriptionField::UserId, &auth_id)))
             .await?
             .into_items();
 
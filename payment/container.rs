@@ -6,6 +6,7 @@ use common::config::ConfigService;
 use common::container::Container;
 use common::event::{EventPublisher, EventSubscriber};
 use common::result::Result;
+use common::transaction::UnitOfWork;
 use identity::domain::user::UserRepository;
 use publishing::domain::publication::{PublicationRepository, StatisticsService};
 use publishing::domain::reader::ReaderRepository;
@@ -30,6 +31,8 @@ pub struct PaymentContainer<EPub> {
 
     contract_serv: Arc<ContractService>,
     payment_serv: Arc<dyn PaymentService>,
+
+    unit_of_work: Arc<dyn UnitOfWork>,
 }
 
 impl<EPub> PaymentContainer<EPub>
@@ -48,6 +51,7 @@ where
         config_serv: Arc<ConfigService>,
         payment_serv: Arc<dyn PaymentService>,
         statistics_serv: Arc<StatisticsService>,
+        unit_of_work: Arc<dyn UnitOfWork>,
     ) -> Self {
         let contract_serv = Arc::new(ContractService::new(
             contract_repo.clone(),
@@ -68,6 +72,7 @@ where
             user_repo,
             contract_serv,
             payment_serv,
+            unit_of_work,
         }
     }
 
@@ -110,6 +115,10 @@ where
     pub fn payment_serv(&self) -> &dyn PaymentService {
         self.payment_serv.as_ref()
     }
+
+    pub fn unit_of_work(&self) -> &dyn UnitOfWork {
+        self.unit_of_work.as_ref()
+    }
 }
 
 #[async_trait]
@@ -81,6 +81,10 @@ impl Contract {
         &self.events
     }
 
+    pub fn events_mut(&mut self) -> &mut Events<ContractEvent> {
+        &mut self.events
+    }
+
     pub fn publication_id(&self) -> &PublicationId {
         &self.publication_id
     }
@@ -5,6 +5,7 @@ use chrono::{DateTime, Duration, Utc};
 use common::config::ConfigService;
 use common::error::Error;
 use common::result::Result;
+use common::sql::Query;
 use publishing::domain::publication::{
     PublicationId, PublicationRepository, StatisticsService, Status as PublicationStatus,
 };
@@ -100,7 +101,7 @@ impl ContractService {
             .into_items();
         let subscriptions = self
             .subscription_repo
-            .search(None, None, None, None, None, None, None, None)
+            .search(&Query::new())
             .await?
             .into_items();
 
@@ -112,7 +113,9 @@ impl ContractService {
                 }
             }
         }
-        subscription_total = subscription_total * 0.3;
+        let business_rules = self.config_serv.get_business_rules_at(&from).await?;
+        let subscription_percentage = 1.0 - business_rules.subscription_percentage_retention;
+        subscription_total = subscription_total * subscription_percentage;
 
         if subscription_total == 0.0 {
             return Err(Error::new("subscription_total", "zero"));
@@ -180,7 +183,7 @@ impl ContractService {
 
         let subscriptions = self
             .subscription_repo
-            .search(None, None, None, None, None, None, None, None)
+            .search(&Query::new())
             .await?
             .into_items();
         let mut subscription_total: f64 = 0.0;
@@ -192,7 +195,9 @@ impl ContractService {
             }
         }
 
-        let subscription_percentage = 1.0 - business_rules.subscription_percentage_retention;
+        // The summary is computed with the rules that were in force when its period started.
+        let period_business_rules = self.config_serv.get_business_rules_at(&date_from).await?;
+        let subscription_percentage = 1.0 - period_business_rules.subscription_percentage_retention;
         subscription_total = subscription_total * subscription_percentage;
 
         if subscription_total == 0.0 {
@@ -258,9 +263,8 @@ mod tests {
 
     use async_trait::async_trait;
 
-    use common::cache::Cache;
-    use common::config::{BusinessRules, ConfigService};
-    use common::infrastructure::cache::InMemCache;
+    use common::config::{BusinessRules, BusinessRulesRepository, ConfigService};
+    use common::infrastructure::config::InMemBusinessRulesRepository;
     use common::model::{AggregateRoot, Pagination, StatusHistory, StatusItem};
     use identity::domain::user::UserId;
     use publishing::domain::author::AuthorId;
@@ -278,7 +282,7 @@ mod tests {
     use crate::domain::payment::{Amount, Kind, Payment};
     use crate::domain::plan::{Plan, PlanId, Price};
     use crate::domain::subscription::{
-        Status as SubscriptionStatus, Subscription, SubscriptionId, SubscriptionOrderBy,
+        Status as SubscriptionStatus, Subscription, SubscriptionField, SubscriptionId,
         SubscriptionPlan,
     };
 
@@ -437,14 +441,7 @@ mod tests {
         // Subscription 2: $225 (in date range)
         async fn search(
             &self,
-            _user_id: Option<&UserId>,
-            _plan_id: Option<&PlanId>,
-            _status: Option<&SubscriptionStatus>,
-            _from: Option<&DateTime<Utc>>,
-            _to: Option<&DateTime<Utc>>,
-            _offset: Option<usize>,
-            _limit: Option<usize>,
-            _order_by: Option<&SubscriptionOrderBy>,
+            _query: &Query<SubscriptionField>,
         ) -> Result<Pagination<Subscription>> {
             let plan = Plan::new(PlanId::new("basic")?, "Basic", "Basic", Price::new(75.0)?)?;
 
@@ -766,12 +763,16 @@ mod tests {
             subscription_percentage_retention: 0.7,
             minimum_charge_amount: 200.0,
         };
-        let cache = InMemCache::new();
-        cache
-            .set("business_rules".to_owned(), business_rules)
+        let business_rules_repo = InMemBusinessRulesRepository::new();
+        business_rules_repo
+            .append(
+                business_rules,
+                None,
+                DateTime::from_str("2020-01-01T00:00:00Z").unwrap(),
+            )
             .await
             .unwrap();
-        let config_serv = ConfigService::new(Arc::new(cache));
+        let config_serv = ConfigService::new(Arc::new(business_rules_repo));
 
         let contract_serv = ContractService::new(
             Arc::new(FakeContractRepository),
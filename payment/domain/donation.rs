@@ -107,6 +107,10 @@ impl Donation {
         &self.events
     }
 
+    pub fn events_mut(&mut self) -> &mut Events<DonationEvent> {
+        &mut self.events
+    }
+
     pub fn author_id(&self) -> &UserId {
         &self.author_id
     }
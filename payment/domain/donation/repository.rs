@@ -1,15 +1,39 @@
 use std::str::FromStr;
 
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use common::error::Error;
 use common::model::Pagination;
 use common::result::Result;
-use identity::domain::user::UserId;
+use common::sql::{Direction, Query, Record, Value};
 
-use crate::domain::donation::{Donation, DonationId, Status};
+use crate::domain::donation::{Donation, DonationId};
+
+/// Fields of a donation that can be used in a search `Query`.
+#[derive(Debug, Clone, Copy)]
+pub enum DonationField {
+    Id,
+    AuthorId,
+    ReaderId,
+    /// Current status.
+    Status,
+    Total,
+    CreatedAt,
+}
+
+impl Record<DonationField> for Donation {
+    fn values(&self, field: &DonationField) -> Vec<Value> {
+        match field {
+            DonationField::Id => vec![self.base().id().into()],
+            DonationField::AuthorId => vec![self.author_id().into()],
+            DonationField::ReaderId => vec![self.reader_id().into()],
+            DonationField::Status => vec![self.status_history().current().to_string().into()],
+            DonationField::Total => vec![self.total().value().into()],
+            DonationField::CreatedAt => vec![self.base().created_at().into()],
+        }
+    }
+}
 
 #[async_trait]
 pub trait DonationRepository: Sync + Send {
@@ -18,17 +42,7 @@ pub trait DonationRepository: Sync + Send {
     }
 
     async fn find_by_id(&self, id: &DonationId) -> Result<Donation>;
-    async fn search(
-        &self,
-        author_id: Option<&UserId>,
-        reader_id: Option<&UserId>,
-        status: Option<&Status>,
-        from: Option<&DateTime<Utc>>,
-        to: Option<&DateTime<Utc>>,
-        offset: Option<usize>,
-        limit: Option<usize>,
-        order_by: Option<&DonationOrderBy>,
-    ) -> Result<Pagination<Donation>>;
+    async fn search(&self, query: &Query<DonationField>) -> Result<Pagination<Donation>>;
 
     async fn save(&self, donation: &mut Donation) -> Result<()>;
 
@@ -41,6 +55,16 @@ pub enum DonationOrderBy {
     Amount,
 }
 
+impl DonationOrderBy {
+    pub fn sort_key(&self) -> (DonationField, Direction) {
+        match self {
+            DonationOrderBy::Oldest => (DonationField::CreatedAt, Direction::Asc),
+            DonationOrderBy::Newest => (DonationField::CreatedAt, Direction::Desc),
+            DonationOrderBy::Amount => (DonationField::Total, Direction::Desc),
+        }
+    }
+}
+
 impl FromStr for DonationOrderBy {
     type Err = Error;
 
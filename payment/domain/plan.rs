@@ -61,6 +61,10 @@ impl Plan {
         &self.events
     }
 
+    pub fn events_mut(&mut self) -> &mut Events<PlanEvent> {
+        &mut self.events
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -86,6 +86,10 @@ impl Subscription {
         &self.events
     }
 
+    pub fn events_mut(&mut self) -> &mut Events<SubscriptionEvent> {
+        &mut self.events
+    }
+
     pub fn user_id(&self) -> &UserId {
         &self.user_id
     }
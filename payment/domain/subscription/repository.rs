@@ -1,16 +1,25 @@
 use std::str::FromStr;
 
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use common::error::Error;
 use common::model::Pagination;
 use common::result::Result;
+use common::sql::{Direction, Query};
 use identity::domain::user::UserId;
 
-use crate::domain::plan::PlanId;
-use crate::domain::subscription::{Status, Subscription, SubscriptionId};
+use crate::domain::subscription::{Subscription, SubscriptionId};
+
+/// Fields of a subscription that can be used in a search `Query`.
+#[derive(Debug, Clone, Copy)]
+pub enum SubscriptionField {
+    UserId,
+    PlanId,
+    /// Current status.
+    Status,
+    CreatedAt,
+}
 
 #[async_trait]
 pub trait SubscriptionRepository: Sync + Send {
@@ -20,17 +29,7 @@ pub trait SubscriptionRepository: Sync + Send {
 
     async fn find_by_id(&self, id: &SubscriptionId) -> Result<Subscription>;
     async fn find_by_user_id(&self, id: &UserId) -> Result<Subscription>;
-    async fn search(
-        &self,
-        user_id: Option<&UserId>,
-        plan_id: Option<&PlanId>,
-        status: Option<&Status>,
-        from: Option<&DateTime<Utc>>,
-        to: Option<&DateTime<Utc>>,
-        offset: Option<usize>,
-        limit: Option<usize>,
-        order_by: Option<&SubscriptionOrderBy>,
-    ) -> Result<Pagination<Subscription>>;
+    async fn search(&self, query: &Query<SubscriptionField>) -> Result<Pagination<Subscription>>;
 
     async fn save(&self, subscription: &mut Subscription) -> Result<()>;
 
@@ -42,6 +41,15 @@ pub enum SubscriptionOrderBy {
     Newest,
 }
 
+impl SubscriptionOrderBy {
+    pub fn sort_key(&self) -> (SubscriptionField, Direction) {
+        match self {
+            SubscriptionOrderBy::Oldest => (SubscriptionField::CreatedAt, Direction::Asc),
+            SubscriptionOrderBy::Newest => (SubscriptionField::CreatedAt, Direction::Desc),
+        }
+    }
+}
+
 impl FromStr for SubscriptionOrderBy {
     type Err = Error;
 
@@ -1,12 +1,11 @@
-use std::sync::Arc;
-
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use tokio_postgres::row::Row;
-use tokio_postgres::Client;
 use uuid::Uuid;
 
 use common::error::Error;
+use common::infrastructure::event::PostgresOutbox;
+use common::infrastructure::postgres::PostgresClient;
 use common::model::{AggregateRoot, Pagination, StatusHistory, StatusItem};
 use common::result::Result;
 use common::sql::where_builder::WhereBuilder;
@@ -48,11 +47,11 @@ impl Contract {
 }
 
 pub struct PostgresContractRepository {
-    client: Arc<Client>,
+    client: PostgresClient,
 }
 
 impl PostgresContractRepository {
-    pub fn new(client: Arc<Client>) -> Self {
+    pub fn new(client: PostgresClient) -> Self {
         PostgresContractRepository { client }
     }
 }
@@ -176,6 +175,8 @@ impl ContractRepository for PostgresContractRepository {
     }
 
     async fn save(&self, contract: &mut Contract) -> Result<()> {
+        let events = PostgresOutbox::records(&contract.events().to_vec()?)?;
+
         let create = self
             .client
             .query_one(
@@ -192,14 +193,17 @@ impl ContractRepository for PostgresContractRepository {
         if create {
             self.client
                 .execute(
-                    "INSERT INTO contracts(
-                        id,
-                        publication_id,
-                        summaries,
-                        payments,
-                        status_history,
-                        created_at
-                    ) VALUES ($1, $2, $3, $4, $5, $6)",
+                    &PostgresOutbox::enlist(
+                        "INSERT INTO contracts(
+                            id,
+                            publication_id,
+                            summaries,
+                            payments,
+                            status_history,
+                            created_at
+                        ) VALUES ($1, $2, $3, $4, $5, $6)",
+                        7,
+                    ) as &str,
                     &[
                         &contract.base().id().to_uuid()?,
                         &contract.publication_id().to_uuid()?,
@@ -207,6 +211,7 @@ impl ContractRepository for PostgresContractRepository {
                         &payments,
                         &status_history,
                         &contract.base().created_at(),
+                        &events,
                     ],
                 )
                 .await
@@ -214,15 +219,18 @@ impl ContractRepository for PostgresContractRepository {
         } else {
             self.client
                 .execute(
-                    "UPDATE contracts
-                    SET
-                        summaries = $2,
-                        payments = $3,
-                        status_history = $4,
-                        updated_at = $5,
-                        deleted_at= $6
-                    WHERE
-                        id = $1",
+                    &PostgresOutbox::enlist(
+                        "UPDATE contracts
+                        SET
+                            summaries = $2,
+                            payments = $3,
+                            status_history = $4,
+                            updated_at = $5,
+                            deleted_at= $6
+                        WHERE
+                            id = $1",
+                        7,
+                    ) as &str,
                     &[
                         &contract.base().id().to_uuid()?,
                         &summaries,
@@ -230,12 +238,15 @@ impl ContractRepository for PostgresContractRepository {
                         &status_history,
                         &contract.base().updated_at(),
                         &contract.base().deleted_at(),
+                        &events,
                     ],
                 )
                 .await
                 .map_err(|err| Error::new("contract", "update").wrap_raw(err))?;
         }
 
+        contract.events_mut().clear();
+
         Ok(())
     }
 
@@ -1,20 +1,33 @@
-use std::sync::Arc;
-
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use tokio_postgres::row::Row;
-use tokio_postgres::Client;
 use uuid::Uuid;
 
 use common::error::Error;
+use common::infrastructure::event::PostgresOutbox;
+use common::infrastructure::postgres::PostgresClient;
 use common::model::{AggregateRoot, Pagination, StatusHistory, StatusItem};
+use common::request::PAGINATION_LIMIT;
 use common::result::Result;
-use common::sql::where_builder::WhereBuilder;
+use common::sql::{Column, Field, Query};
 use identity::domain::user::UserId;
 
-use crate::domain::donation::{Donation, DonationId, DonationOrderBy, DonationRepository, Status};
+use crate::domain::donation::{Donation, DonationField, DonationId, DonationRepository, Status};
 use crate::domain::payment::{Amount, Payment};
 
+impl Field for DonationField {
+    fn column(&self) -> Column {
+        match self {
+            DonationField::Id => Column::Expr("id"),
+            DonationField::AuthorId => Column::Expr("author_id"),
+            DonationField::ReaderId => Column::Expr("reader_id"),
+            DonationField::Status => Column::Expr("status_history->-1->>'status'"),
+            DonationField::Total => Column::Expr("total"),
+            DonationField::CreatedAt => Column::Expr("created_at"),
+        }
+    }
+}
+
 impl Donation {
     fn from_row(row: Row) -> Result<Self> {
         let id: Uuid = row.get("id");
@@ -57,11 +70,11 @@ impl Donation {
 }
 
 pub struct PostgresDonationRepository {
-    client: Arc<Client>,
+    client: PostgresClient,
 }
 
 impl PostgresDonationRepository {
-    pub fn new(client: Arc<Client>) -> Self {
+    pub fn new(client: PostgresClient) -> Self {
         PostgresDonationRepository { client }
     }
 }
@@ -82,64 +95,45 @@ impl DonationRepository for PostgresDonationRepository {
         Donation::from_row(row)
     }
 
-    async fn search(
-        &self,
-        author_id: Option<&UserId>,
-        reader_id: Option<&UserId>,
-        status: Option<&Status>,
-        from: Option<&DateTime<Utc>>,
-        to: Option<&DateTime<Utc>>,
-        offset: Option<usize>,
-        limit: Option<usize>,
-        order_by: Option<&DonationOrderBy>,
-    ) -> Result<Pagination<Donation>> {
-        let author_id = author_id.map(|id| id.to_uuid()).transpose()?;
-        let reader_id = reader_id.map(|id| id.to_uuid()).transpose()?;
-        let status = status.map(|s| s.to_string());
+    async fn search(&self, query: &Query<DonationField>) -> Result<Pagination<Donation>> {
+        let sql = query.to_sql();
+        let params = sql.params();
 
-        let (sql, params) = WhereBuilder::new()
-            .add_param_opt("author_id = $$", &author_id, author_id.is_some())
-            .add_param_opt("reader_id = $$", &reader_id, reader_id.is_some())
-            .add_param_opt(
-                "status_history->-1->>'status' = $$",
-                &status,
-                status.is_some(),
-            )
-            .add_param_opt("created_at >= $$", &from, from.is_some())
-            .add_param_opt("created_at <= $$", &to, to.is_some())
-            .build();
+        // Pages after a cursor are not counted
+        let mut counts = None;
+        if query.after().is_none() {
+            // Total
+            let row = self
+                .client
+                .query_one("SELECT COUNT(*) FROM donations", &[])
+                .await
+                .map_err(|err| Error::new("donation", "total").wrap_raw(err))?;
+            let total: i64 = row.get(0);
 
-        // Total
-        let row = self
-            .client
-            .query_one(&format!("SELECT COUNT(*) FROM donations") as &str, &[])
-            .await
-            .map_err(|err| Error::new("donation", "total").wrap_raw(err))?;
-        let total: i64 = row.get(0);
+            // Matching criteria
+            let row = self
+                .client
+                .query_one(
+                    &format!(
+                        "SELECT COUNT(*) FROM donations
+                        {}",
+                        sql.where_clause(),
+                    ) as &str,
+                    &params,
+                )
+                .await
+                .map_err(|err| Error::new("donation", "matching_criteria").wrap_raw(err))?;
+            let matching_criteria: i64 = row.get(0);
 
-        // Matching criteria
-        let row = self
-            .client
-            .query_one(
-                &format!(
-                    "SELECT COUNT(*) FROM donations
-                    {}",
-                    sql,
-                ) as &str,
-                &params,
-            )
-            .await
-            .map_err(|err| Error::new("donation", "matching_criteria").wrap_raw(err))?;
-        let matching_criteria: i64 = row.get(0);
+            counts = Some((total as usize, matching_criteria as usize));
+        }
 
         // Query
-        let offset = offset.unwrap_or_else(|| 0);
-        let limit = limit.unwrap_or_else(|| total as usize);
-        let order_by = match order_by {
-            Some(DonationOrderBy::Newest) => "created_at DESC",
-            Some(DonationOrderBy::Amount) => "total DESC",
-            _ => "created_at ASC",
-        };
+        let offset = query.offset().unwrap_or(0);
+        let limit = query
+            .limit()
+            .or_else(|| counts.map(|(total, _)| total))
+            .unwrap_or(PAGINATION_LIMIT);
 
         let rows = self
             .client
@@ -147,10 +141,13 @@ impl DonationRepository for PostgresDonationRepository {
                 &format!(
                     "SELECT * FROM donations
                     {}
-                    ORDER BY {}
+                    {}
                     OFFSET {}
                     LIMIT {}",
-                    sql, order_by, offset, limit,
+                    sql.where_clause(),
+                    sql.order_by(),
+                    offset,
+                    limit,
                 ) as &str,
                 &params,
             )
@@ -162,13 +159,20 @@ impl DonationRepository for PostgresDonationRepository {
             donations.push(Donation::from_row(row)?);
         }
 
-        Ok(
-            Pagination::new(offset, limit, total as usize, matching_criteria as usize)
-                .add_items(donations),
-        )
+        let next_cursor = query.next_cursor(&donations);
+        let pagination = match counts {
+            Some((total, matching_criteria)) => {
+                Pagination::new(offset, limit, total, matching_criteria)
+            }
+            None => Pagination::uncounted(offset, limit),
+        };
+
+        Ok(pagination.add_items(donations).set_next_cursor(next_cursor))
     }
 
     async fn save(&self, donation: &mut Donation) -> Result<()> {
+        let events = PostgresOutbox::records(&donation.events().to_vec()?)?;
+
         let create = self
             .client
             .query_one(
@@ -185,19 +189,22 @@ impl DonationRepository for PostgresDonationRepository {
         if create {
             self.client
                 .execute(
-                    "INSERT INTO donations(
-                        id,
-                        author_id,
-                        reader_id,
-                        total,
-                        subtotal,
-                        author_percentage,
-                        comment,
-                        reader_payment,
-                        author_charge,
-                        status_history,
-                        created_at
-                    ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+                    &PostgresOutbox::enlist(
+                        "INSERT INTO donations(
+                            id,
+                            author_id,
+                            reader_id,
+                            total,
+                            subtotal,
+                            author_percentage,
+                            comment,
+                            reader_payment,
+                            author_charge,
+                            status_history,
+                            created_at
+                        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+                        12,
+                    ) as &str,
                     &[
                         &donation.base().id().to_uuid()?,
                         &donation.author_id().to_uuid()?,
@@ -210,6 +217,7 @@ impl DonationRepository for PostgresDonationRepository {
                         &author_charge,
                         &status_history,
                         &donation.base().created_at(),
+                        &events,
                     ],
                 )
                 .await
@@ -217,15 +225,18 @@ impl DonationRepository for PostgresDonationRepository {
         } else {
             self.client
                 .execute(
-                    "UPDATE donations
-                    SET
-                        reader_payment = $2,
-                        author_charge = $3,
-                        status_history = $4,
-                        updated_at = $5,
-                        deleted_at= $6
-                    WHERE
-                        id = $1",
+                    &PostgresOutbox::enlist(
+                        "UPDATE donations
+                        SET
+                            reader_payment = $2,
+                            author_charge = $3,
+                            status_history = $4,
+                            updated_at = $5,
+                            deleted_at= $6
+                        WHERE
+                            id = $1",
+                        7,
+                    ) as &str,
                     &[
                         &donation.base().id().to_uuid()?,
                         &reader_payment,
@@ -233,12 +244,15 @@ impl DonationRepository for PostgresDonationRepository {
                         &status_history,
                         &donation.base().updated_at(),
                         &donation.base().deleted_at(),
+                        &events,
                     ],
                 )
                 .await
                 .map_err(|err| Error::new("donation", "update").wrap_raw(err))?;
         }
 
+        donation.events_mut().clear();
+
         Ok(())
     }
 
@@ -1,11 +1,10 @@
-use std::sync::Arc;
-
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use tokio_postgres::row::Row;
-use tokio_postgres::Client;
 
 use common::error::Error;
+use common::infrastructure::event::PostgresOutbox;
+use common::infrastructure::postgres::PostgresClient;
 use common::model::AggregateRoot;
 use common::result::Result;
 
@@ -32,11 +31,11 @@ impl Plan {
 }
 
 pub struct PostgresPlanRepository {
-    client: Arc<Client>,
+    client: PostgresClient,
 }
 
 impl PostgresPlanRepository {
-    pub fn new(client: Arc<Client>) -> Self {
+    pub fn new(client: PostgresClient) -> Self {
         PostgresPlanRepository { client }
     }
 }
@@ -69,6 +68,8 @@ impl PlanRepository for PostgresPlanRepository {
     }
 
     async fn save(&self, plan: &mut Plan) -> Result<()> {
+        let events = PostgresOutbox::records(&plan.events().to_vec()?)?;
+
         let create = self
             .client
             .query_one(
@@ -81,15 +82,19 @@ impl PlanRepository for PostgresPlanRepository {
         if create {
             self.client
                 .execute(
-                    "INSERT INTO plans(
-                        id,
-                        price,
-                        created_at
-                    ) VALUES ($1, $2, $3)",
+                    &PostgresOutbox::enlist(
+                        "INSERT INTO plans(
+                            id,
+                            price,
+                            created_at
+                        ) VALUES ($1, $2, $3)",
+                        4,
+                    ) as &str,
                     &[
                         &plan.base().id().value(),
                         &plan.price().value(),
                         &plan.base().created_at(),
+                        &events,
                     ],
                 )
                 .await
@@ -97,24 +102,30 @@ impl PlanRepository for PostgresPlanRepository {
         } else {
             self.client
                 .execute(
-                    "UPDATE plans
-                    SET
-                        price = $2,
-                        updated_at = $3,
-                        deleted_at= $4
-                    WHERE
-                        id = $1",
+                    &PostgresOutbox::enlist(
+                        "UPDATE plans
+                        SET
+                            price = $2,
+                            updated_at = $3,
+                            deleted_at= $4
+                        WHERE
+                            id = $1",
+                        5,
+                    ) as &str,
                     &[
                         &plan.base().id().value(),
                         &plan.price().value(),
                         &plan.base().updated_at(),
                         &plan.base().deleted_at(),
+                        &events,
                     ],
                 )
                 .await
                 .map_err(|err| Error::new("plan", "update").wrap_raw(err))?;
         }
 
+        plan.events_mut().clear();
+
         Ok(())
     }
 
@@ -1,24 +1,33 @@
-use std::sync::Arc;
-
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use tokio_postgres::row::Row;
-use tokio_postgres::Client;
 use uuid::Uuid;
 
 use common::error::Error;
+use common::infrastructure::event::PostgresOutbox;
+use common::infrastructure::postgres::PostgresClient;
 use common::model::{AggregateRoot, Pagination, StatusHistory, StatusItem};
 use common::result::Result;
-use common::sql::where_builder::WhereBuilder;
+use common::sql::{Column, Field, Query};
 use identity::domain::user::UserId;
 
 use crate::domain::payment::Payment;
-use crate::domain::plan::PlanId;
 use crate::domain::subscription::{
-    Status, Subscription, SubscriptionId, SubscriptionOrderBy, SubscriptionPlan,
+    Status, Subscription, SubscriptionField, SubscriptionId, SubscriptionPlan,
     SubscriptionRepository,
 };
 
+impl Field for SubscriptionField {
+    fn column(&self) -> Column {
+        match self {
+            SubscriptionField::UserId => Column::Expr("user_id"),
+            SubscriptionField::PlanId => Column::Expr("plan->'plan_id'->>'id'"),
+            SubscriptionField::Status => Column::Expr("status_history->-1->>'status'"),
+            SubscriptionField::CreatedAt => Column::Expr("created_at"),
+        }
+    }
+}
+
 impl Subscription {
     fn from_row(row: Row) -> Result<Self> {
         let id: Uuid = row.get("id");
@@ -48,11 +57,11 @@ impl Subscription {
 }
 
 pub struct PostgresSubscriptionRepository {
-    client: Arc<Client>,
+    client: PostgresClient,
 }
 
 impl PostgresSubscriptionRepository {
-    pub fn new(client: Arc<Client>) -> Self {
+    pub fn new(client: PostgresClient) -> Self {
         PostgresSubscriptionRepository { client }
     }
 }
@@ -87,37 +96,14 @@ impl SubscriptionRepository for PostgresSubscriptionRepository {
         Subscription::from_row(row)
     }
 
-    async fn search(
-        &self,
-        user_id: Option<&UserId>,
-        plan_id: Option<&PlanId>,
-        status: Option<&Status>,
-        from: Option<&DateTime<Utc>>,
-        to: Option<&DateTime<Utc>>,
-        offset: Option<usize>,
-        limit: Option<usize>,
-        order_by: Option<&SubscriptionOrderBy>,
-    ) -> Result<Pagination<Subscription>> {
-        let user_id = user_id.map(|id| id.to_uuid()).transpose()?;
-        let plan_id = plan_id.map(|id| id.value()); // TODO: use
-        let status = status.map(|s| s.to_string());
-
-        let (sql, params) = WhereBuilder::new()
-            .add_param_opt("user_id = $$", &user_id, user_id.is_some())
-            .add_param_opt("plan->'plan_id'->>'id' = $$", &plan_id, plan_id.is_some())
-            .add_param_opt(
-                "status_history->-1->>'status' = $$",
-                &status,
-                status.is_some(),
-            )
-            .add_param_opt("created_at >= $$", &from, from.is_some())
-            .add_param_opt("created_at <= $$", &to, to.is_some())
-            .build();
+    async fn search(&self, query: &Query<SubscriptionField>) -> Result<Pagination<Subscription>> {
+        let sql = query.to_sql();
+        let params = sql.params();
 
         // Total
         let row = self
             .client
-            .query_one(&format!("SELECT COUNT(*) FROM subscriptions") as &str, &[])
+            .query_one("SELECT COUNT(*) FROM subscriptions", &[])
             .await
             .map_err(|err| Error::new("subscription", "total").wrap_raw(err))?;
         let total: i64 = row.get(0);
@@ -129,21 +115,17 @@ impl SubscriptionRepository for PostgresSubscriptionRepository {
                 &format!(
                     "SELECT COUNT(*) FROM subscriptions
                     {}",
-                    sql,
+                    sql.where_clause(),
                 ) as &str,
                 &params,
             )
             .await
             .map_err(|err| Error::new("subscription", "matching_criteria").wrap_raw(err))?;
         let matching_criteria: i64 = row.get(0);
-        //
+
         // Query
-        let offset = offset.unwrap_or_else(|| 0);
-        let limit = limit.unwrap_or_else(|| total as usize);
-        let order_by = match order_by {
-            Some(SubscriptionOrderBy::Newest) => "created_at DESC",
-            _ => "created_at ASC",
-        };
+        let offset = query.offset().unwrap_or(0);
+        let limit = query.limit().unwrap_or(total as usize);
 
         let rows = self
             .client
@@ -151,10 +133,13 @@ impl SubscriptionRepository for PostgresSubscriptionRepository {
                 &format!(
                     "SELECT * FROM subscriptions
                     {}
-                    ORDER BY {}
+                    {}
                     OFFSET {}
                     LIMIT {}",
-                    sql, order_by, offset, limit,
+                    sql.where_clause(),
+                    sql.order_by(),
+                    offset,
+                    limit,
                 ) as &str,
                 &params,
             )
@@ -173,6 +158,8 @@ impl SubscriptionRepository for PostgresSubscriptionRepository {
     }
 
     async fn save(&self, subscription: &mut Subscription) -> Result<()> {
+        let events = PostgresOutbox::records(&subscription.events().to_vec()?)?;
+
         let create = self
             .client
             .query_one(
@@ -189,14 +176,17 @@ impl SubscriptionRepository for PostgresSubscriptionRepository {
         if create {
             self.client
                 .execute(
-                    "INSERT INTO subscriptions(
-                        id,
-                        user_id,
-                        plan,
-                        payments,
-                        status_history,
-                        created_at
-                    ) VALUES ($1, $2, $3, $4, $5, $6)",
+                    &PostgresOutbox::enlist(
+                        "INSERT INTO subscriptions(
+                            id,
+                            user_id,
+                            plan,
+                            payments,
+                            status_history,
+                            created_at
+                        ) VALUES ($1, $2, $3, $4, $5, $6)",
+                        7,
+                    ) as &str,
                     &[
                         &subscription.base().id().to_uuid()?,
                         &subscription.user_id().to_uuid()?,
@@ -204,6 +194,7 @@ impl SubscriptionRepository for PostgresSubscriptionRepository {
                         &payments,
                         &status_history,
                         &subscription.base().created_at(),
+                        &events,
                     ],
                 )
                 .await
@@ -211,16 +202,19 @@ impl SubscriptionRepository for PostgresSubscriptionRepository {
         } else {
             self.client
                 .execute(
-                    "UPDATE subscriptions
-                    SET
-                        user_id = $2,
-                        plan = $3,
-                        payments = $4,
-                        status_history = $5,
-                        updated_at = $6,
-                        deleted_at = $7
-                    WHERE
-                        id = $1",
+                    &PostgresOutbox::enlist(
+                        "UPDATE subscriptions
+                        SET
+                            user_id = $2,
+                            plan = $3,
+                            payments = $4,
+                            status_history = $5,
+                            updated_at = $6,
+                            deleted_at = $7
+                        WHERE
+                            id = $1",
+                        8,
+                    ) as &str,
                     &[
                         &subscription.base().id().to_uuid()?,
                         &subscription.user_id().to_uuid()?,
@@ -229,12 +223,15 @@ impl SubscriptionRepository for PostgresSubscriptionRepository {
                         &status_history,
                         &subscription.base().updated_at(),
                         &subscription.base().deleted_at(),
+                        &events,
                     ],
                 )
                 .await
                 .map_err(|err| Error::new("subscription", "update").wrap_raw(err))?;
         }
 
+        subscription.events_mut().clear();
+
         Ok(())
     }
 
@@ -6,11 +6,15 @@ use identity::domain::user::User;
 
 use crate::domain::payment::PaymentService;
 
-pub struct DevelopmentPaymentService;
+pub struct DevelopmentPaymentService {
+    api_url: String,
+}
 
 impl DevelopmentPaymentService {
-    pub fn new() -> Self {
-        DevelopmentPaymentService
+    pub fn new(config: &Config) -> Self {
+        DevelopmentPaymentService {
+            api_url: config.api_url(),
+        }
     }
 }
 
@@ -24,12 +28,9 @@ impl PaymentService for DevelopmentPaymentService {
         external_reference: String,
         _payer: &User,
     ) -> Result<String> {
-        let config = Config::get();
-
         Ok(format!(
             "{}/webhook/development?reference={}",
-            config.api_url(),
-            external_reference,
+            self.api_url, external_reference,
         ))
     }
 
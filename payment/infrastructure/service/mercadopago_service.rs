@@ -26,9 +26,7 @@ pub struct MercadoPagoService {
 }
 
 impl MercadoPagoService {
-    pub fn new() -> Self {
-        let config = Config::get();
-
+    pub fn new(config: &Config) -> Self {
         MercadoPagoService {
             public_key: config.mp_public_key().to_string(),
             access_token: config.mp_access_token().to_string(),
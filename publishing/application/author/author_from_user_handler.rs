@@ -7,7 +7,7 @@ use common::event::{Event, EventHandler};
 use common::result::Result;
 use shared::event::UserEvent;
 
-use crate::domain::author::{Author, AuthorId, AuthorRepository};
+use crate::domain::author::{AuthorId, AuthorRepository};
 
 pub struct AuthorFromUserHandler {
     author_repo: Arc<dyn AuthorRepository>,
@@ -30,15 +30,21 @@ impl EventHandler for AuthorFromUserHandler {
             .map_err(|err| Error::new("author_from_user_handler", "deserialize").wrap_raw(err))?;
 
         match event {
-            UserEvent::Validated { id } => {
-                let mut author = Author::new(AuthorId::new(id)?)?;
-                self.author_repo.save(&mut author).await?;
-            }
             UserEvent::Deleted { id } => {
                 let mut author = self.author_repo.find_by_id(&AuthorId::new(id)?).await?;
                 author.delete()?;
                 self.author_repo.save(&mut author).await?;
             }
+            UserEvent::Restored { id } => {
+                let mut author = self.author_repo.find_by_id(&AuthorId::new(id)?).await?;
+                author.restore()?;
+                self.author_repo.save(&mut author).await?;
+            }
+            UserEvent::UsernameChanged { id, username } => {
+                let mut author = self.author_repo.find_by_id(&AuthorId::new(id)?).await?;
+                author.set_username(username)?;
+                self.author_repo.save(&mut author).await?;
+            }
             _ => return Ok(false),
         }
 
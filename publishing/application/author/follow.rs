@@ -2,6 +2,7 @@ use common::error::Error;
 use common::event::EventPublisher;
 use common::request::CommandResponse;
 use common::result::Result;
+use common::transaction::{self, UnitOfWork};
 use identity::UserIdAndRole;
 
 use crate::domain::author::{AuthorId, AuthorRepository};
@@ -14,6 +15,8 @@ pub struct Follow<'a> {
     author_repo: &'a dyn AuthorRepository,
     interaction_repo: &'a dyn InteractionRepository,
     reader_repo: &'a dyn ReaderRepository,
+
+    unit_of_work: &'a dyn UnitOfWork,
 }
 
 impl<'a> Follow<'a> {
@@ -22,12 +25,14 @@ impl<'a> Follow<'a> {
         author_repo: &'a dyn AuthorRepository,
         interaction_repo: &'a dyn InteractionRepository,
         reader_repo: &'a dyn ReaderRepository,
+        unit_of_work: &'a dyn UnitOfWork,
     ) -> Self {
         Follow {
             event_pub,
             author_repo,
             interaction_repo,
             reader_repo,
+            unit_of_work,
         }
     }
 
@@ -48,8 +53,11 @@ impl<'a> Follow<'a> {
 
         let mut follow = author.follow(&reader)?;
 
-        self.interaction_repo.save_follow(&mut follow).await?;
-        self.author_repo.save(&mut author).await?;
+        transaction::run(self.unit_of_work, async {
+            self.interaction_repo.save_follow(&mut follow).await?;
+            self.author_repo.save(&mut author).await
+        })
+        .await?;
 
         self.event_pub
             .publish_all(author.events().to_vec()?)
@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use common::event::{Event, EventHandler};
+use common::result::Result;
+use shared::event::AuthorEvent;
+
+use crate::domain::author::{AuthorId, AuthorRepository};
+use crate::domain::interaction::InteractionRepository;
+
+pub struct FollowerCounterHandler {
+    author_repo: Arc<dyn AuthorRepository>,
+    interaction_repo: Arc<dyn InteractionRepository>,
+}
+
+impl FollowerCounterHandler {
+    pub fn new(
+        author_repo: Arc<dyn AuthorRepository>,
+        interaction_repo: Arc<dyn InteractionRepository>,
+    ) -> Self {
+        FollowerCounterHandler {
+            author_repo,
+            interaction_repo,
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for FollowerCounterHandler {
+    fn topic(&self) -> &str {
+        "author"
+    }
+
+    /// Counts the follows of the author instead of incrementing the counter, so handling the same
+    /// event twice gives the same result.
+    async fn handle(&mut self, event: &Event) -> Result<bool> {
+        let event: AuthorEvent = serde_json::from_value(event.payload())?;
+
+        match event {
+            AuthorEvent::Followed { author_id, .. } | AuthorEvent::Unfollowed { author_id, .. } => {
+                let author_id = AuthorId::new(author_id)?;
+                let follows = self
+                    .interaction_repo
+                    .find_follows(None, Some(&author_id), None, None)
+                    .await?;
+
+                let mut author = self.author_repo.find_by_id(&author_id).await?;
+                author.set_followers(follows.len() as u32)?;
+                self.author_repo.save(&mut author).await?;
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use common::event::ToEvent;
+
+    use crate::infrastructure::persistence::inmem::{
+        InMemAuthorRepository, InMemInteractionRepository,
+    };
+    use crate::mocks;
+
+    #[tokio::test]
+    async fn replay_twice() {
+        let author_repo = Arc::new(InMemAuthorRepository::new());
+        let interaction_repo = Arc::new(InMemInteractionRepository::new());
+        let mut handler =
+            FollowerCounterHandler::new(author_repo.clone(), interaction_repo.clone());
+
+        let mut author = mocks::author("#user01", "user-1");
+        for (id, username) in [("#user02", "user-2"), ("#user03", "user-3")].iter() {
+            let reader = mocks::reader(id, username);
+            let mut follow = author.follow(&reader).unwrap();
+            interaction_repo.save_follow(&mut follow).await.unwrap();
+        }
+
+        // The counter is wrong, for example because a follow failed to be saved with it.
+        author.set_followers(5).unwrap();
+        author_repo.save(&mut author).await.unwrap();
+
+        let event = AuthorEvent::Followed {
+            author_id: author.base().id().to_string(),
+            reader_id: "#user02".to_owned(),
+        }
+        .to_event()
+        .unwrap();
+
+        for _ in 0..2 {
+            assert!(handler.handle(&event).await.unwrap());
+
+            let author = author_repo.find_by_id(author.base().id()).await.unwrap();
+            assert_eq!(author.followers(), 2);
+        }
+    }
+}
@@ -1,11 +1,13 @@
-mod publication_counter_handler;
-// mod author_from_user_handler;
+mod author_from_user_handler;
 mod follow;
+mod follower_counter_handler;
 mod get_by_id;
+mod publication_counter_handler;
 mod search;
 mod unfollow;
-// pub use author_from_user_handler::*;
+pub use author_from_user_handler::*;
 pub use follow::*;
+pub use follower_counter_handler::*;
 pub use get_by_id::*;
 pub use publication_counter_handler::*;
 pub use search::*;
@@ -4,11 +4,12 @@ use async_trait::async_trait;
 
 use common::event::{Event, EventHandler};
 use common::result::Result;
+use common::sql::{Criteria, Query};
 use shared::event::PublicationEvent;
 
 use crate::domain::author::AuthorRepository;
 use crate::domain::publication::{
-    PublicationId, PublicationRepository, Status as PublicationStatus,
+    PublicationField, PublicationId, PublicationRepository, Status as PublicationStatus,
 };
 
 pub struct PublicationCounterHandler {
@@ -40,33 +41,39 @@ impl EventHandler for PublicationCounterHandler {
         match event {
             PublicationEvent::Published { id, .. }
             | PublicationEvent::ChangedToDraft { id, .. }
-            | PublicationEvent::Deleted { id } => {
-                let publication = self
-                    .publication_repo
-                    .find_by_id(&PublicationId::new(id)?)
-                    .await?;
+            | PublicationEvent::Deleted { id }
+            | PublicationEvent::Restored { id } => {
+                let publication_id = PublicationId::new(id)?;
+                let publication = match self.publication_repo.find_by_id(&publication_id).await {
+                    Ok(publication) => publication,
+                    Err(_) => {
+                        self.publication_repo
+                            .find_deleted_by_id(&publication_id)
+                            .await?
+                    }
+                };
 
                 let p_publications = self
                     .publication_repo
                     .search(
-                        Some(publication.author_id()),
-                        None,
-                        None,
-                        Some(&PublicationStatus::Published {
-                            admin_id: None,
-                            comment: None,
-                        }),
-                        None,
-                        None,
-                        None,
-                        None,
-                        None,
-                        None,
+                        &Query::new()
+                            .filter(Criteria::eq(
+                                PublicationField::AuthorId,
+                                publication.author_id(),
+                            ))
+                            .filter(Criteria::eq(
+                                PublicationField::Status,
+                                PublicationStatus::Published {
+                                    admin_id: None,
+                                    comment: None,
+                                }
+                                .to_string(),
+                            )),
                     )
                     .await?;
 
                 let mut author = self.author_repo.find_by_id(publication.author_id()).await?;
-                author.set_publications(p_publications.matching_criteria() as u32)?;
+                author.set_publications(p_publications.matching_criteria().unwrap_or(0) as u32)?;
                 self.author_repo.save(&mut author).await?;
             }
             _ => return Ok(false),
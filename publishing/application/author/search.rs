@@ -1,15 +1,16 @@
 use std::str::FromStr;
 
-use chrono::DateTime;
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
 use common::error::Error;
-use common::request::{PaginationParams, PaginationResponse};
+use common::request::{CursorSigner, PaginationParams, PaginationResponse};
 use common::result::Result;
+use common::sql::{Criteria, Query};
 use identity::UserIdAndRole;
 
 use crate::application::dtos::AuthorDto;
-use crate::domain::author::{AuthorOrderBy, AuthorRepository};
+use crate::domain::author::{AuthorField, AuthorOrderBy, AuthorRepository};
 
 #[derive(Deserialize)]
 pub struct SearchCommand {
@@ -21,10 +22,15 @@ pub struct SearchCommand {
 
 pub struct Search<'a> {
     author_repo: &'a dyn AuthorRepository,
+
+    cursor_signer: &'a CursorSigner,
 }
 impl<'a> Search<'a> {
-    pub fn new(author_repo: &'a dyn AuthorRepository) -> Self {
-        Search { author_repo }
+    pub fn new(author_repo: &'a dyn AuthorRepository, cursor_signer: &'a CursorSigner) -> Self {
+        Search {
+            author_repo,
+            cursor_signer,
+        }
     }
 
     pub async fn exec(
@@ -33,37 +39,43 @@ impl<'a> Search<'a> {
         cmd: SearchCommand,
         pagination: PaginationParams,
     ) -> Result<PaginationResponse<AuthorDto>> {
-        let pagination_authors = self
-            .author_repo
-            .search(
-                cmd.name.as_ref(),
+        let order_by = pagination
+            .order_by()
+            .map(|o| AuthorOrderBy::from_str(&o))
+            .transpose()?
+            .unwrap_or(AuthorOrderBy::Oldest);
+        let (field, direction) = order_by.sort_key();
+
+        let query = Query::new()
+            .filter_opt(cmd.name.map(|name| {
+                Criteria::contains(AuthorField::Username, name.clone())
+                    .or(Criteria::contains(AuthorField::Name, name.clone()))
+                    .or(Criteria::contains(AuthorField::Lastname, name.clone()))
+                    .or(Criteria::contains(AuthorField::Fullname, name))
+            }))
+            .filter(Criteria::range(
+                AuthorField::Publications,
                 cmd.publications_gt,
+                None,
+            ))
+            .filter(Criteria::range(
+                AuthorField::CreatedAt,
                 cmd.date_from
-                    .map(|d| DateTime::from_str(&d))
+                    .map(|d| DateTime::<Utc>::from_str(&d))
                     .transpose()
-                    .map_err(|err| Error::bad_format("date_from").wrap_raw(err))?
-                    .as_ref(),
+                    .map_err(|err| Error::bad_format("date_from").wrap_raw(err))?,
                 cmd.date_to
-                    .map(|d| DateTime::from_str(&d))
+                    .map(|d| DateTime::<Utc>::from_str(&d))
                     .transpose()
-                    .map_err(|err| Error::bad_format("date_to").wrap_raw(err))?
-                    .as_ref(),
-                pagination.offset(),
-                pagination.limit(),
-                pagination
-                    .order_by()
-                    .map(|o| AuthorOrderBy::from_str(&o))
-                    .transpose()?
-                    .as_ref(),
-            )
-            .await?;
+                    .map_err(|err| Error::bad_format("date_to").wrap_raw(err))?,
+            ))
+            .sort(field, direction)
+            .cursor(AuthorField::Id, pagination.cursor(self.cursor_signer)?)?
+            .paginate(pagination.offset(), pagination.limit());
+
+        let pagination_authors = self.author_repo.search(&query).await?;
 
-        let mut res = PaginationResponse::new(
-            pagination_authors.offset(),
-            pagination_authors.limit(),
-            pagination_authors.total(),
-            pagination_authors.matching_criteria(),
-        );
+        let mut res = PaginationResponse::from_pagination(&pagination_authors, self.cursor_signer);
 
         for author in pagination_authors.into_items().into_iter() {
             // TODO: change this
@@ -2,6 +2,7 @@ use common::error::Error;
 use common::event::EventPublisher;
 use common::request::CommandResponse;
 use common::result::Result;
+use common::transaction::{self, UnitOfWork};
 use identity::UserIdAndRole;
 
 use crate::domain::author::{AuthorId, AuthorRepository};
@@ -14,6 +15,8 @@ pub struct Unfollow<'a> {
     author_repo: &'a dyn AuthorRepository,
     interaction_repo: &'a dyn InteractionRepository,
     reader_repo: &'a dyn ReaderRepository,
+
+    unit_of_work: &'a dyn UnitOfWork,
 }
 
 impl<'a> Unfollow<'a> {
@@ -22,12 +25,14 @@ impl<'a> Unfollow<'a> {
         author_repo: &'a dyn AuthorRepository,
         interaction_repo: &'a dyn InteractionRepository,
         reader_repo: &'a dyn ReaderRepository,
+        unit_of_work: &'a dyn UnitOfWork,
     ) -> Self {
         Unfollow {
             event_pub,
             author_repo,
             interaction_repo,
             reader_repo,
+            unit_of_work,
         }
     }
 
@@ -46,13 +51,16 @@ impl<'a> Unfollow<'a> {
             .find_by_id(&AuthorId::new(author_id)?)
             .await?;
 
-        self.interaction_repo
-            .delete_follow(reader.base().id(), author.base().id())
-            .await?;
+        transaction::run(self.unit_of_work, async {
+            self.interaction_repo
+                .delete_follow(reader.base().id(), author.base().id())
+                .await?;
 
-        author.unfollow(&reader)?;
+            author.unfollow(&reader)?;
 
-        self.author_repo.save(&mut author).await?;
+            self.author_repo.save(&mut author).await
+        })
+        .await?;
 
         self.event_pub
             .publish_all(author.events().to_vec()?)
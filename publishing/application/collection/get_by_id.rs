@@ -5,6 +5,7 @@ use common::result::Result;
 use identity::UserIdAndRole;
 
 use crate::application::dtos::{AuthorDto, CategoryDto, CollectionDto, PublicationDto};
+use crate::application::expand::Expand;
 use crate::domain::author::AuthorRepository;
 use crate::domain::category::CategoryRepository;
 use crate::domain::collection::{CollectionId, CollectionRepository};
@@ -45,26 +46,15 @@ impl<'a> GetById<'a> {
         collection_id: String,
         include: Include,
     ) -> Result<CollectionDto> {
+        include.validate(&CollectionDto::SCHEMA)?;
+
         let collection = self
             .collection_repo
             .find_by_id(&CollectionId::new(collection_id)?)
             .await?;
 
-        let mut collection_dto = CollectionDto::from(&collection);
-
-        if include.has("author") {
-            let author = self.author_repo.find_by_id(collection.author_id()).await?;
-            collection_dto = collection_dto.author(AuthorDto::from(&author));
-        }
-
-        if include.has("category") {
-            let category = self
-                .category_repo
-                .find_by_id(collection.header().category_id())
-                .await?;
-            collection_dto = collection_dto.category(CategoryDto::from(&category));
-        }
-
-        Ok(collection_dto)
+        Expand::new(self.author_repo, self.category_repo)
+            .collection(&collection, &include)
+            .await
     }
 }
@@ -5,7 +5,8 @@ use common::request::Include;
 use common::result::Result;
 use identity::UserIdAndRole;
 
-use crate::application::dtos::{AuthorDto, CategoryDto, PublicationDto};
+use crate::application::dtos::PublicationDto;
+use crate::application::expand::Expand;
 use crate::domain::author::AuthorRepository;
 use crate::domain::category::CategoryRepository;
 use crate::domain::collection::{CollectionId, CollectionRepository};
@@ -44,6 +45,8 @@ impl<'a> GetPublications<'a> {
         collection_id: String,
         include: Include,
     ) -> Result<GetPublicationsResponse> {
+        include.validate(&PublicationDto::SCHEMA)?;
+
         let collection = self
             .collection_repo
             .find_by_id(&CollectionId::new(collection_id)?)
@@ -60,7 +63,7 @@ impl<'a> GetPublications<'a> {
             false
         };
 
-        let mut publication_dtos = Vec::new();
+        let mut publications = Vec::new();
 
         for item in collection.items() {
             let publication = self
@@ -72,26 +75,13 @@ impl<'a> GetPublications<'a> {
                 continue;
             }
 
-            let mut publication_dto = PublicationDto::from(&publication);
-
-            if include.has("author") {
-                let author = self.author_repo.find_by_id(publication.author_id()).await?;
-                publication_dto = publication_dto.author(AuthorDto::from(&author));
-            }
-
-            if include.has("category") {
-                let category = self
-                    .category_repo
-                    .find_by_id(publication.header().category_id())
-                    .await?;
-                publication_dto = publication_dto.category(CategoryDto::from(&category));
-            }
-
-            publication_dtos.push(publication_dto)
+            publications.push(publication);
         }
 
         Ok(GetPublicationsResponse {
-            publications: publication_dtos,
+            publications: Expand::new(self.author_repo, self.category_repo)
+                .publications(&publications, &include)
+                .await?,
         })
     }
 }
@@ -6,7 +6,9 @@ mod get_by_id;
 mod get_publications;
 mod remove_from_favorites;
 mod remove_publication;
+mod restore;
 mod search;
+mod search_deleted;
 mod update;
 pub use add_publication::*;
 pub use add_to_favorites::*;
@@ -16,5 +18,7 @@ pub use get_by_id::*;
 pub use get_publications::*;
 pub use remove_from_favorites::*;
 pub use remove_publication::*;
+pub use restore::*;
 pub use search::*;
+pub use search_deleted::*;
 pub use update::*;
@@ -0,0 +1,51 @@
+use common::error::Error;
+use common::event::EventPublisher;
+use common::request::CommandResponse;
+use common::result::Result;
+use identity::UserIdAndRole;
+
+use crate::domain::collection::{CollectionId, CollectionRepository};
+
+pub struct Restore<'a> {
+    event_pub: &'a dyn EventPublisher,
+
+    collection_repo: &'a dyn CollectionRepository,
+}
+
+impl<'a> Restore<'a> {
+    pub fn new(
+        event_pub: &'a dyn EventPublisher,
+        collection_repo: &'a dyn CollectionRepository,
+    ) -> Self {
+        Restore {
+            event_pub,
+            collection_repo,
+        }
+    }
+
+    pub async fn exec(
+        &self,
+        (_auth_id, auth_role): UserIdAndRole,
+        collection_id: String,
+    ) -> Result<CommandResponse> {
+        if !auth_role.can("restore_deleted") {
+            return Err(Error::unauthorized());
+        }
+
+        let collection_id = CollectionId::new(collection_id)?;
+        let mut collection = self
+            .collection_repo
+            .find_deleted_by_id(&collection_id)
+            .await?;
+
+        collection.restore()?;
+
+        self.collection_repo.save(&mut collection).await?;
+
+        self.event_pub
+            .publish_all(collection.events().to_vec()?)
+            .await?;
+
+        Ok(CommandResponse::default())
+    }
+}
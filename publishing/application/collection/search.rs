@@ -1,17 +1,19 @@
 use std::str::FromStr;
 
-use chrono::DateTime;
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
 use common::error::Error;
-use common::request::{Include, PaginationParams, PaginationResponse};
+use common::request::{CursorSigner, Include, PaginationParams, PaginationResponse};
 use common::result::Result;
+use common::sql::{Criteria, Query};
 use identity::UserIdAndRole;
 
-use crate::application::dtos::{AuthorDto, CategoryDto, CollectionDto};
+use crate::application::dtos::CollectionDto;
+use crate::application::expand::Expand;
 use crate::domain::author::{AuthorId, AuthorRepository};
 use crate::domain::category::{CategoryId, CategoryRepository};
-use crate::domain::collection::{CollectionOrderBy, CollectionRepository};
+use crate::domain::collection::{CollectionField, CollectionOrderBy, CollectionRepository};
 use crate::domain::publication::{PublicationId, Tag};
 
 #[derive(Deserialize)]
@@ -29,6 +31,8 @@ pub struct Search<'a> {
     author_repo: &'a dyn AuthorRepository,
     category_repo: &'a dyn CategoryRepository,
     collection_repo: &'a dyn CollectionRepository,
+
+    cursor_signer: &'a CursorSigner,
 }
 
 impl<'a> Search<'a> {
@@ -36,11 +40,13 @@ impl<'a> Search<'a> {
         author_repo: &'a dyn AuthorRepository,
         category_repo: &'a dyn CategoryRepository,
         collection_repo: &'a dyn CollectionRepository,
+        cursor_signer: &'a CursorSigner,
     ) -> Self {
         Search {
             author_repo,
             category_repo,
             collection_repo,
+            cursor_signer,
         }
     }
 
@@ -51,62 +57,68 @@ impl<'a> Search<'a> {
         include: Include,
         pagination: PaginationParams,
     ) -> Result<PaginationResponse<CollectionDto>> {
-        let pagination_collections = self
-            .collection_repo
-            .search(
-                cmd.author_id.map(AuthorId::new).transpose()?.as_ref(),
-                cmd.category_id.map(CategoryId::new).transpose()?.as_ref(),
+        include.validate(&CollectionDto::SCHEMA)?;
+
+        let order_by = pagination
+            .order_by()
+            .map(|o| CollectionOrderBy::from_str(&o))
+            .transpose()?
+            .unwrap_or(CollectionOrderBy::Oldest);
+        let (field, direction) = order_by.sort_key();
+
+        let query = Query::new()
+            .filter_opt(
+                cmd.author_id
+                    .map(AuthorId::new)
+                    .transpose()?
+                    .map(|id| Criteria::eq(CollectionField::AuthorId, &id)),
+            )
+            .filter_opt(
+                cmd.category_id
+                    .map(CategoryId::new)
+                    .transpose()?
+                    .map(|id| Criteria::eq(CollectionField::CategoryId, &id)),
+            )
+            .filter_opt(
                 cmd.publication_id
                     .map(PublicationId::new)
                     .transpose()?
-                    .as_ref(),
-                cmd.tag.map(Tag::new).transpose()?.as_ref(),
-                cmd.name.as_ref(),
+                    .map(|id| Criteria::eq(CollectionField::PublicationId, &id)),
+            )
+            .filter_opt(
+                cmd.tag
+                    .map(Tag::new)
+                    .transpose()?
+                    .map(|tag| Criteria::eq(CollectionField::Tag, tag.slug())),
+            )
+            .filter_opt(
+                cmd.name
+                    .map(|name| Criteria::contains(CollectionField::Name, name)),
+            )
+            .filter(Criteria::range(
+                CollectionField::CreatedAt,
                 cmd.date_from
-                    .map(|d| DateTime::from_str(&d))
+                    .map(|d| DateTime::<Utc>::from_str(&d))
                     .transpose()
-                    .map_err(|err| Error::bad_format("date_from").wrap_raw(err))?
-                    .as_ref(),
+                    .map_err(|err| Error::bad_format("date_from").wrap_raw(err))?,
                 cmd.date_to
-                    .map(|d| DateTime::from_str(&d))
+                    .map(|d| DateTime::<Utc>::from_str(&d))
                     .transpose()
-                    .map_err(|err| Error::bad_format("date_to").wrap_raw(err))?
-                    .as_ref(),
-                pagination.offset(),
-                pagination.limit(),
-                pagination
-                    .order_by()
-                    .map(|o| CollectionOrderBy::from_str(&o))
-                    .transpose()?
-                    .as_ref(),
-            )
-            .await?;
-
-        let mut res = PaginationResponse::new(
-            pagination_collections.offset(),
-            pagination_collections.limit(),
-            pagination_collections.total(),
-            pagination_collections.matching_criteria(),
-        );
+                    .map_err(|err| Error::bad_format("date_to").wrap_raw(err))?,
+            ))
+            .sort(field, direction)
+            .cursor(CollectionField::Id, pagination.cursor(self.cursor_signer)?)?
+            .paginate(pagination.offset(), pagination.limit());
 
-        for collection in pagination_collections.into_items().into_iter() {
-            let mut collection_dto = CollectionDto::from(&collection);
+        let pagination_collections = self.collection_repo.search(&query).await?;
 
-            if include.has("author") {
-                let author = self.author_repo.find_by_id(collection.author_id()).await?;
-                collection_dto = collection_dto.author(AuthorDto::from(&author));
-            }
-
-            if include.has("category") {
-                let category = self
-                    .category_repo
-                    .find_by_id(collection.header().category_id())
-                    .await?;
-                collection_dto = collection_dto.category(CategoryDto::from(&category));
-            }
-
-            res.add_item(collection_dto);
-        }
+        let mut res =
+            PaginationResponse::from_pagination(&pagination_collections, self.cursor_signer);
+        res.add_items(
+            Expand::new(self.author_repo, self.category_repo)
+                .collections(pagination_collections.items(), &include)
+                .await?,
+        );
 
         Ok(res)
     }
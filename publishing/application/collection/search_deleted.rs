@@ -0,0 +1,66 @@
+use common::error::Error;
+use common::request::{CursorSigner, Include, PaginationParams, PaginationResponse};
+use common::result::Result;
+use common::sql::{Direction, Query};
+use identity::UserIdAndRole;
+
+use crate::application::dtos::CollectionDto;
+use crate::application::expand::Expand;
+use crate::domain::author::AuthorRepository;
+use crate::domain::category::CategoryRepository;
+use crate::domain::collection::{CollectionField, CollectionRepository};
+
+pub struct SearchDeleted<'a> {
+    author_repo: &'a dyn AuthorRepository,
+    category_repo: &'a dyn CategoryRepository,
+    collection_repo: &'a dyn CollectionRepository,
+
+    cursor_signer: &'a CursorSigner,
+}
+
+impl<'a> SearchDeleted<'a> {
+    pub fn new(
+        author_repo: &'a dyn AuthorRepository,
+        category_repo: &'a dyn CategoryRepository,
+        collection_repo: &'a dyn CollectionRepository,
+        cursor_signer: &'a CursorSigner,
+    ) -> Self {
+        SearchDeleted {
+            author_repo,
+            category_repo,
+            collection_repo,
+            cursor_signer,
+        }
+    }
+
+    pub async fn exec(
+        &self,
+        (_auth_id, auth_role): UserIdAndRole,
+        include: Include,
+        pagination: PaginationParams,
+    ) -> Result<PaginationResponse<CollectionDto>> {
+        if !auth_role.can("restore_deleted") {
+            return Err(Error::unauthorized());
+        }
+
+        include.validate(&CollectionDto::SCHEMA)?;
+
+        // The last deleted collections first.
+        let query = Query::new()
+            .sort(CollectionField::DeletedAt, Direction::Desc)
+            .cursor(CollectionField::Id, pagination.cursor(self.cursor_signer)?)?
+            .paginate(pagination.offset(), pagination.limit());
+
+        let pagination_collections = self.collection_repo.search_deleted(&query).await?;
+
+        let mut res =
+            PaginationResponse::from_pagination(&pagination_collections, self.cursor_signer);
+        res.add_items(
+            Expand::new(self.author_repo, self.category_repo)
+                .collections(pagination_collections.items(), &include)
+                .await?,
+        );
+
+        Ok(res)
+    }
+}
@@ -1,6 +1,7 @@
 use serde::Serialize;
 
 use common::model::StatusItem;
+use common::request::Schema;
 
 use crate::domain::author::Author;
 use crate::domain::category::Category;
@@ -9,7 +10,7 @@ use crate::domain::interaction::Review;
 use crate::domain::publication::{Image, Page, Publication, Statistics, Status};
 use crate::domain::reader::{Preferences, Reader};
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct StatisticsDto {
     pub views: u32,
     pub unique_views: u32,
@@ -32,7 +33,7 @@ impl From<&Statistics> for StatisticsDto {
     }
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct AuthorDto {
     pub id: String,
     pub username: String,
@@ -47,6 +48,22 @@ pub struct AuthorDto {
 }
 
 impl AuthorDto {
+    pub const SCHEMA: Schema = Schema::new(
+        &[
+            "id",
+            "username",
+            "name",
+            "lastname",
+            "biography",
+            "profile_image",
+            "followers",
+            "publications",
+            "created_at",
+            "updated_at",
+        ],
+        &[],
+    );
+
     pub fn from(author: &Author) -> Self {
         AuthorDto {
             id: author.base().id().to_string(),
@@ -63,7 +80,7 @@ impl AuthorDto {
     }
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct CategoryDto {
     pub id: String,
     pub name: String,
@@ -71,6 +88,10 @@ pub struct CategoryDto {
     pub updated_at: Option<String>,
 }
 
+impl CategoryDto {
+    pub const SCHEMA: Schema = Schema::new(&["id", "name", "created_at", "updated_at"], &[]);
+}
+
 impl From<&Category> for CategoryDto {
     fn from(category: &Category) -> Self {
         CategoryDto {
@@ -82,7 +103,7 @@ impl From<&Category> for CategoryDto {
     }
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct ImageDto {
     pub url: String,
 }
@@ -95,7 +116,7 @@ impl From<&Image> for ImageDto {
     }
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct PageDto {
     pub number: u32,
     pub images: Vec<ImageDto>,
@@ -110,7 +131,7 @@ impl From<&Page> for PageDto {
     }
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct PublicationStatusDto {
     pub status: String,
     pub changed_at: String,
@@ -148,7 +169,7 @@ impl From<&StatusItem<Status>> for PublicationStatusDto {
     }
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct PublicationDto {
     pub id: String,
     pub author_id: Option<String>,
@@ -165,6 +186,7 @@ pub struct PublicationDto {
     pub status: PublicationStatusDto,
     pub created_at: String,
     pub updated_at: Option<String>,
+    pub deleted_at: Option<String>,
 }
 
 impl From<&Publication> for PublicationDto {
@@ -190,11 +212,37 @@ impl From<&Publication> for PublicationDto {
             status: PublicationStatusDto::from(publication.status_history().current_item()),
             created_at: publication.base().created_at().to_rfc3339(),
             updated_at: publication.base().updated_at().map(|d| d.to_rfc3339()),
+            deleted_at: publication.base().deleted_at().map(|d| d.to_rfc3339()),
         }
     }
 }
 
 impl PublicationDto {
+    pub const SCHEMA: Schema = Schema::new(
+        &[
+            "id",
+            "author_id",
+            "author",
+            "name",
+            "synopsis",
+            "category_id",
+            "category",
+            "tags",
+            "cover",
+            "statistics",
+            "pages",
+            "contract",
+            "status",
+            "created_at",
+            "updated_at",
+            "deleted_at",
+        ],
+        &[
+            ("author", &AuthorDto::SCHEMA),
+            ("category", &CategoryDto::SCHEMA),
+        ],
+    );
+
     pub fn author(mut self, author: AuthorDto) -> Self {
         self.author_id = None;
         self.author = Some(author);
@@ -213,7 +261,7 @@ impl PublicationDto {
     }
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct CollectionDto {
     pub id: String,
     pub author_id: Option<String>,
@@ -227,6 +275,7 @@ pub struct CollectionDto {
     pub publications: u32,
     pub created_at: String,
     pub updated_at: Option<String>,
+    pub deleted_at: Option<String>,
 }
 
 impl From<&Collection> for CollectionDto {
@@ -249,11 +298,34 @@ impl From<&Collection> for CollectionDto {
             publications: collection.items().len() as u32,
             created_at: collection.base().created_at().to_rfc3339(),
             updated_at: collection.base().updated_at().map(|d| d.to_rfc3339()),
+            deleted_at: collection.base().deleted_at().map(|d| d.to_rfc3339()),
         }
     }
 }
 
 impl CollectionDto {
+    pub const SCHEMA: Schema = Schema::new(
+        &[
+            "id",
+            "author_id",
+            "author",
+            "name",
+            "synopsis",
+            "category_id",
+            "category",
+            "tags",
+            "cover",
+            "publications",
+            "created_at",
+            "updated_at",
+            "deleted_at",
+        ],
+        &[
+            ("author", &AuthorDto::SCHEMA),
+            ("category", &CategoryDto::SCHEMA),
+        ],
+    );
+
     pub fn author(mut self, author: AuthorDto) -> Self {
         self.author_id = None;
         self.author = Some(author);
@@ -298,7 +370,7 @@ impl ReviewDto {
     }
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct PreferencesDto {
     pub categories: Vec<String>,
     pub publications: Vec<String>,
@@ -321,7 +393,7 @@ impl From<&Preferences> for PreferencesDto {
     }
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct ReaderDto {
     pub id: String,
     pub username: String,
@@ -335,6 +407,21 @@ pub struct ReaderDto {
 }
 
 impl ReaderDto {
+    pub const SCHEMA: Schema = Schema::new(
+        &[
+            "id",
+            "username",
+            "name",
+            "lastname",
+            "profile_image",
+            "subscribed",
+            "preferences",
+            "created_at",
+            "updated_at",
+        ],
+        &[],
+    );
+
     pub fn from(reader: &Reader) -> Self {
         ReaderDto {
             id: reader.base().id().to_string(),
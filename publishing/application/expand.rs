@@ -0,0 +1,187 @@
+use std::collections::{HashMap, HashSet};
+
+use common::error::Error;
+use common::model::StringId;
+use common::request::Include;
+use common::result::Result;
+
+use crate::application::dtos::{AuthorDto, CategoryDto, CollectionDto, PublicationDto};
+use crate::domain::author::{AuthorId, AuthorRepository};
+use crate::domain::category::{CategoryId, CategoryRepository};
+use crate::domain::collection::Collection;
+use crate::domain::publication::Publication;
+
+/// Expand builds DTOs with the relations requested in `Include`. Each relation is loaded for all
+/// the DTOs with a single repository call.
+pub struct Expand<'a> {
+    author_repo: &'a dyn AuthorRepository,
+    category_repo: &'a dyn CategoryRepository,
+}
+
+impl<'a> Expand<'a> {
+    pub fn new(
+        author_repo: &'a dyn AuthorRepository,
+        category_repo: &'a dyn CategoryRepository,
+    ) -> Self {
+        Expand {
+            author_repo,
+            category_repo,
+        }
+    }
+
+    pub async fn publication(
+        &self,
+        publication: &Publication,
+        include: &Include,
+    ) -> Result<PublicationDto> {
+        let mut publication_dtos = self
+            .publications(std::slice::from_ref(publication), include)
+            .await?;
+        Ok(publication_dtos.remove(0))
+    }
+
+    pub async fn publications(
+        &self,
+        publications: &[Publication],
+        include: &Include,
+    ) -> Result<Vec<PublicationDto>> {
+        let authors = if include.has("author") {
+            Some(
+                self.authors(publications.iter().map(|p| p.author_id()))
+                    .await?,
+            )
+        } else {
+            None
+        };
+
+        let categories = if include.has("category") {
+            Some(
+                self.categories(publications.iter().map(|p| p.header().category_id()))
+                    .await?,
+            )
+        } else {
+            None
+        };
+
+        publications
+            .iter()
+            .map(|publication| {
+                let mut publication_dto = PublicationDto::from(publication);
+
+                if let Some(authors) = &authors {
+                    publication_dto = publication_dto.author(related(
+                        authors,
+                        publication.author_id(),
+                        "author",
+                    )?);
+                }
+
+                if let Some(categories) = &categories {
+                    publication_dto = publication_dto.category(related(
+                        categories,
+                        publication.header().category_id(),
+                        "category",
+                    )?);
+                }
+
+                Ok(publication_dto)
+            })
+            .collect()
+    }
+
+    pub async fn collection(
+        &self,
+        collection: &Collection,
+        include: &Include,
+    ) -> Result<CollectionDto> {
+        let mut collection_dtos = self
+            .collections(std::slice::from_ref(collection), include)
+            .await?;
+        Ok(collection_dtos.remove(0))
+    }
+
+    pub async fn collections(
+        &self,
+        collections: &[Collection],
+        include: &Include,
+    ) -> Result<Vec<CollectionDto>> {
+        let authors = if include.has("author") {
+            Some(
+                self.authors(collections.iter().map(|c| c.author_id()))
+                    .await?,
+            )
+        } else {
+            None
+        };
+
+        let categories = if include.has("category") {
+            Some(
+                self.categories(collections.iter().map(|c| c.header().category_id()))
+                    .await?,
+            )
+        } else {
+            None
+        };
+
+        collections
+            .iter()
+            .map(|collection| {
+                let mut collection_dto = CollectionDto::from(collection);
+
+                if let Some(authors) = &authors {
+                    collection_dto =
+                        collection_dto.author(related(authors, collection.author_id(), "author")?);
+                }
+
+                if let Some(categories) = &categories {
+                    collection_dto = collection_dto.category(related(
+                        categories,
+                        collection.header().category_id(),
+                        "category",
+                    )?);
+                }
+
+                Ok(collection_dto)
+            })
+            .collect()
+    }
+
+    async fn authors<'b, I>(&self, ids: I) -> Result<HashMap<AuthorId, AuthorDto>>
+    where
+        I: Iterator<Item = &'b AuthorId>,
+    {
+        let ids = unique(ids);
+        let authors = self.author_repo.find_by_ids(&ids).await?;
+
+        Ok(authors
+            .iter()
+            .map(|author| (author.base().id().clone(), AuthorDto::from(author)))
+            .collect())
+    }
+
+    async fn categories<'b, I>(&self, ids: I) -> Result<HashMap<CategoryId, CategoryDto>>
+    where
+        I: Iterator<Item = &'b CategoryId>,
+    {
+        let ids = unique(ids);
+        let categories = self.category_repo.find_by_ids(&ids).await?;
+
+        Ok(categories
+            .iter()
+            .map(|category| (category.base().id().clone(), CategoryDto::from(category)))
+            .collect())
+    }
+}
+
+fn unique<'b, I: Iterator<Item = &'b StringId>>(ids: I) -> Vec<StringId> {
+    ids.cloned()
+        .collect::<HashSet<StringId>>()
+        .into_iter()
+        .collect()
+}
+
+fn related<T: Clone>(dtos: &HashMap<StringId, T>, id: &StringId, entity: &str) -> Result<T> {
+    dtos.get(id)
+        .cloned()
+        .ok_or_else(|| Error::not_found(entity))
+}
@@ -2,5 +2,6 @@ pub mod author;
 pub mod category;
 pub mod collection;
 pub mod dtos;
+pub mod expand;
 pub mod publication;
 pub mod reader;
@@ -4,6 +4,7 @@ use common::error::Error;
 use common::event::EventPublisher;
 use common::request::CommandResponse;
 use common::result::Result;
+use common::transaction::{self, UnitOfWork};
 use identity::UserIdAndRole;
 
 use crate::domain::interaction::{Comment, InteractionRepository, Stars};
@@ -22,6 +23,8 @@ pub struct AddReview<'a> {
     interaction_repo: &'a dyn InteractionRepository,
     publication_repo: &'a dyn PublicationRepository,
     reader_repo: &'a dyn ReaderRepository,
+
+    unit_of_work: &'a dyn UnitOfWork,
 }
 
 impl<'a> AddReview<'a> {
@@ -30,12 +33,14 @@ impl<'a> AddReview<'a> {
         interaction_repo: &'a dyn InteractionRepository,
         publication_repo: &'a dyn PublicationRepository,
         reader_repo: &'a dyn ReaderRepository,
+        unit_of_work: &'a dyn UnitOfWork,
     ) -> Self {
         AddReview {
             event_pub,
             interaction_repo,
             publication_repo,
             reader_repo,
+            unit_of_work,
         }
     }
 
@@ -59,8 +64,11 @@ impl<'a> AddReview<'a> {
 
         let mut review = publication.review(&reader, stars, comment)?;
 
-        self.interaction_repo.save_review(&mut review).await?;
-        self.publication_repo.save(&mut publication).await?;
+        transaction::run(self.unit_of_work, async {
+            self.interaction_repo.save_review(&mut review).await?;
+            self.publication_repo.save(&mut publication).await
+        })
+        .await?;
 
         self.event_pub
             .publish_all(publication.events().to_vec()?)
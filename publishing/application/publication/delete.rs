@@ -41,9 +41,7 @@ impl<'a> Delete<'a> {
 
         publication.delete()?;
 
-        self.publication_repo
-            .delete(publication.base().id())
-            .await?;
+        self.publication_repo.save(&mut publication).await?;
 
         self.event_pub
             .publish_all(publication.events().to_vec()?)
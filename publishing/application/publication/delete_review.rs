@@ -2,6 +2,7 @@ use common::error::Error;
 use common::event::EventPublisher;
 use common::request::CommandResponse;
 use common::result::Result;
+use common::transaction::{self, UnitOfWork};
 use identity::UserIdAndRole;
 
 use crate::domain::interaction::InteractionRepository;
@@ -14,6 +15,8 @@ pub struct DeleteReview<'a> {
     interaction_repo: &'a dyn InteractionRepository,
     publication_repo: &'a dyn PublicationRepository,
     reader_repo: &'a dyn ReaderRepository,
+
+    unit_of_work: &'a dyn UnitOfWork,
 }
 
 impl<'a> DeleteReview<'a> {
@@ -22,12 +25,14 @@ impl<'a> DeleteReview<'a> {
         interaction_repo: &'a dyn InteractionRepository,
         publication_repo: &'a dyn PublicationRepository,
         reader_repo: &'a dyn ReaderRepository,
+        unit_of_work: &'a dyn UnitOfWork,
     ) -> Self {
         DeleteReview {
             event_pub,
             interaction_repo,
             publication_repo,
             reader_repo,
+            unit_of_work,
         }
     }
 
@@ -54,10 +59,13 @@ impl<'a> DeleteReview<'a> {
             publication.delete_review(&reader, review.stars())?;
         }
 
-        self.interaction_repo
-            .delete_review(&auth_id, &publication_id)
-            .await?;
-        self.publication_repo.save(&mut publication).await?;
+        transaction::run(self.unit_of_work, async {
+            self.interaction_repo
+                .delete_review(&auth_id, &publication_id)
+                .await?;
+            self.publication_repo.save(&mut publication).await
+        })
+        .await?;
 
         self.event_pub
             .publish_all(publication.events().to_vec()?)
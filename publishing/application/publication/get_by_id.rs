@@ -4,11 +4,11 @@ use common::error::Error;
 use common::event::EventPublisher;
 use common::request::Include;
 use common::result::Result;
+use common::transaction::{self, UnitOfWork};
 use identity::UserIdAndRole;
 
-use crate::application::dtos::{
-    AuthorDto, CategoryDto, PublicationDto, ReaderPublicationInteractionDto, ReviewDto,
-};
+use crate::application::dtos::{PublicationDto, ReaderPublicationInteractionDto, ReviewDto};
+use crate::application::expand::Expand;
 use crate::domain::author::AuthorRepository;
 use crate::domain::category::CategoryRepository;
 use crate::domain::interaction::InteractionRepository;
@@ -31,6 +31,8 @@ pub struct GetById<'a> {
     reader_repo: &'a dyn ReaderRepository,
 
     statistics_serv: &'a StatisticsService,
+
+    unit_of_work: &'a dyn UnitOfWork,
 }
 
 enum Viewer {
@@ -50,6 +52,7 @@ impl<'a> GetById<'a> {
         publication_repo: &'a dyn PublicationRepository,
         reader_repo: &'a dyn ReaderRepository,
         statistics_serv: &'a StatisticsService,
+        unit_of_work: &'a dyn UnitOfWork,
     ) -> Self {
         GetById {
             event_pub,
@@ -59,6 +62,7 @@ impl<'a> GetById<'a> {
             publication_repo,
             reader_repo,
             statistics_serv,
+            unit_of_work,
         }
     }
 
@@ -68,6 +72,8 @@ impl<'a> GetById<'a> {
         publication_id: String,
         include: Include,
     ) -> Result<GetByIdResponse> {
+        include.validate(&PublicationDto::SCHEMA)?;
+
         let publication_id = PublicationId::new(publication_id)?;
         let mut publication = self.publication_repo.find_by_id(&publication_id).await?;
 
@@ -87,10 +93,8 @@ impl<'a> GetById<'a> {
             Viewer::Public
         };
 
-        let (mut publication_dto, reader_interaction_dto) = match viewer {
-            Viewer::ContentManager | Viewer::Owner => {
-                (PublicationDto::from(&publication).pages(&publication), None)
-            }
+        let (with_pages, reader_interaction_dto) = match viewer {
+            Viewer::ContentManager | Viewer::Owner => (true, None),
             Viewer::Reader => {
                 let (auth_id, _auth_role) = user_id_and_role.unwrap();
                 let reader = self.reader_repo.find_by_id(&auth_id).await?;
@@ -103,8 +107,11 @@ impl<'a> GetById<'a> {
                         .is_empty(),
                 )?;
 
-                self.interaction_repo.save_view(&mut view).await?;
-                self.publication_repo.save(&mut publication).await?;
+                transaction::run(self.unit_of_work, async {
+                    self.interaction_repo.save_view(&mut view).await?;
+                    self.publication_repo.save(&mut publication).await
+                })
+                .await?;
 
                 self.event_pub
                     .publish_all(publication.events().to_vec()?)
@@ -139,25 +146,17 @@ impl<'a> GetById<'a> {
                         reader_interaction_dto.review(ReviewDto::from(&reviews[0]));
                 }
 
-                (
-                    PublicationDto::from(&publication),
-                    Some(reader_interaction_dto),
-                )
+                (false, Some(reader_interaction_dto))
             }
-            Viewer::Public => (PublicationDto::from(&publication), None),
+            Viewer::Public => (false, None),
         };
 
-        if include.has("author") {
-            let author = self.author_repo.find_by_id(publication.author_id()).await?;
-            publication_dto = publication_dto.author(AuthorDto::from(&author));
-        }
+        let mut publication_dto = Expand::new(self.author_repo, self.category_repo)
+            .publication(&publication, &include)
+            .await?;
 
-        if include.has("category") {
-            let category = self
-                .category_repo
-                .find_by_id(publication.header().category_id())
-                .await?;
-            publication_dto = publication_dto.category(CategoryDto::from(&category));
+        if with_pages {
+            publication_dto = publication_dto.pages(&publication);
         }
 
         Ok(GetByIdResponse {
@@ -206,6 +205,7 @@ mod tests {
             c.publication_repo(),
             c.reader_repo(),
             c.statistics_serv(),
+            c.unit_of_work(),
         );
 
         let (_user1, mut author1, mut reader1) = user(1);
@@ -271,6 +271,7 @@ mod tests {
             c.publication_repo(),
             c.reader_repo(),
             c.statistics_serv(),
+            c.unit_of_work(),
         );
 
         let (_user1, mut author1, mut reader1) = user(1);
@@ -320,6 +321,7 @@ mod tests {
             c.publication_repo(),
             c.reader_repo(),
             c.statistics_serv(),
+            c.unit_of_work(),
         );
 
         let (_user1, mut author1, mut reader1) = user(1);
@@ -379,6 +381,7 @@ mod tests {
             c.publication_repo(),
             c.reader_repo(),
             c.statistics_serv(),
+            c.unit_of_work(),
         );
 
         let (_user1, mut author1, mut reader1) = user(1);
@@ -428,6 +431,7 @@ mod tests {
             c.publication_repo(),
             c.reader_repo(),
             c.statistics_serv(),
+            c.unit_of_work(),
         );
 
         let (_user1, mut author1, mut reader1) = user(1);
@@ -474,4 +478,71 @@ mod tests {
         assert!(!res.read);
         assert!(!res.reviewed);
     }
+
+    #[tokio::test]
+    async fn unknown_include_or_fields() {
+        let c = mocks::container();
+        let uc = GetById::new(
+            c.event_pub(),
+            c.author_repo(),
+            c.category_repo(),
+            c.interaction_repo(),
+            c.publication_repo(),
+            c.reader_repo(),
+            c.statistics_serv(),
+            c.unit_of_work(),
+        );
+
+        let (_user1, mut author1, mut reader1) = user(1);
+        c.author_repo().save(&mut author1).await.unwrap();
+        c.reader_repo().save(&mut reader1).await.unwrap();
+
+        let mut publication = mocks::publication(
+            "#publication01",
+            "#user01",
+            "Publication 01",
+            "category-1",
+            vec!["Tag 1", "Tag 2"],
+            "domain.com/cover.jpg",
+            3,
+            true,
+            true,
+            false,
+        );
+        c.publication_repo().save(&mut publication).await.unwrap();
+
+        let err = uc
+            .exec(
+                None,
+                publication.base().id().to_string(),
+                Include::new("author.followers"),
+            )
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.path(), "include");
+        assert_eq!(err.code(), "unknown");
+
+        let err = uc
+            .exec(
+                None,
+                publication.base().id().to_string(),
+                Include::new("author").set_fields("id,author.email"),
+            )
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.path(), "fields");
+        assert_eq!(err.code(), "unknown");
+
+        let res = uc
+            .exec(
+                None,
+                publication.base().id().to_string(),
+                Include::new("author").set_fields("id,author.username"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.publication.author.unwrap().id, "#user01");
+    }
 }
@@ -2,6 +2,7 @@ use common::error::Error;
 use common::event::EventPublisher;
 use common::request::CommandResponse;
 use common::result::Result;
+use common::transaction::{self, UnitOfWork};
 use identity::UserIdAndRole;
 
 use crate::domain::interaction::InteractionRepository;
@@ -14,6 +15,8 @@ pub struct Like<'a> {
     interaction_repo: &'a dyn InteractionRepository,
     publication_repo: &'a dyn PublicationRepository,
     reader_repo: &'a dyn ReaderRepository,
+
+    unit_of_work: &'a dyn UnitOfWork,
 }
 
 impl<'a> Like<'a> {
@@ -22,12 +25,14 @@ impl<'a> Like<'a> {
         interaction_repo: &'a dyn InteractionRepository,
         publication_repo: &'a dyn PublicationRepository,
         reader_repo: &'a dyn ReaderRepository,
+        unit_of_work: &'a dyn UnitOfWork,
     ) -> Self {
         Like {
             event_pub,
             interaction_repo,
             publication_repo,
             reader_repo,
+            unit_of_work,
         }
     }
 
@@ -47,8 +52,11 @@ impl<'a> Like<'a> {
 
         let mut like = publication.like(&reader)?;
 
-        self.interaction_repo.save_like(&mut like).await?;
-        self.publication_repo.save(&mut publication).await?;
+        transaction::run(self.unit_of_work, async {
+            self.interaction_repo.save_like(&mut like).await?;
+            self.publication_repo.save(&mut publication).await
+        })
+        .await?;
 
         self.event_pub
             .publish_all(publication.events().to_vec()?)
@@ -75,6 +83,7 @@ mod tests {
             c.interaction_repo(),
             c.publication_repo(),
             c.reader_repo(),
+            c.unit_of_work(),
         );
 
         let mut reader = mocks::reader("#user02", "user-2");
@@ -118,6 +127,7 @@ mod tests {
             c.interaction_repo(),
             c.publication_repo(),
             c.reader_repo(),
+            c.unit_of_work(),
         );
 
         let mut reader = mocks::reader("#user02", "user-2");
@@ -154,6 +164,7 @@ mod tests {
             c.interaction_repo(),
             c.publication_repo(),
             c.reader_repo(),
+            c.unit_of_work(),
         );
 
         let mut reader = mocks::reader("#user02", "user-2");
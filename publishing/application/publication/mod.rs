@@ -14,7 +14,9 @@ mod publish;
 mod read;
 mod reject;
 mod remove_from_favorites;
+mod restore;
 mod search;
+mod search_deleted;
 mod unlike;
 mod update;
 mod update_pages;
@@ -34,7 +36,9 @@ pub use publish::*;
 pub use read::*;
 pub use reject::*;
 pub use remove_from_favorites::*;
+pub use restore::*;
 pub use search::*;
+pub use search_deleted::*;
 pub use unlike::*;
 pub use update::*;
 pub use update_pages::*;
@@ -3,6 +3,7 @@ use serde::Serialize;
 use common::error::Error;
 use common::event::EventPublisher;
 use common::result::Result;
+use common::transaction::{self, UnitOfWork};
 use identity::UserIdAndRole;
 
 use crate::application::dtos::PageDto;
@@ -22,6 +23,8 @@ pub struct Read<'a> {
     interaction_repo: &'a dyn InteractionRepository,
     publication_repo: &'a dyn PublicationRepository,
     reader_repo: &'a dyn ReaderRepository,
+
+    unit_of_work: &'a dyn UnitOfWork,
 }
 
 impl<'a> Read<'a> {
@@ -30,12 +33,14 @@ impl<'a> Read<'a> {
         interaction_repo: &'a dyn InteractionRepository,
         publication_repo: &'a dyn PublicationRepository,
         reader_repo: &'a dyn ReaderRepository,
+        unit_of_work: &'a dyn UnitOfWork,
     ) -> Self {
         Read {
             event_pub,
             interaction_repo,
             publication_repo,
             reader_repo,
+            unit_of_work,
         }
     }
 
@@ -56,8 +61,11 @@ impl<'a> Read<'a> {
         if publication.author_id() != &auth_id && !auth_role.can("approve_reject_publication") {
             let mut reading = publication.read(&reader)?;
 
-            self.interaction_repo.save_reading(&mut reading).await?;
-            self.publication_repo.save(&mut publication).await?;
+            transaction::run(self.unit_of_work, async {
+                self.interaction_repo.save_reading(&mut reading).await?;
+                self.publication_repo.save(&mut publication).await
+            })
+            .await?;
 
             self.event_pub
                 .publish_all(publication.events().to_vec()?)
@@ -88,6 +96,7 @@ mod tests {
             c.interaction_repo(),
             c.publication_repo(),
             c.reader_repo(),
+            c.unit_of_work(),
         );
 
         let mut reader = mocks::reader("#user02", "user-2");
@@ -131,6 +140,7 @@ mod tests {
             c.interaction_repo(),
             c.publication_repo(),
             c.reader_repo(),
+            c.unit_of_work(),
         );
 
         let mut reader = mocks::reader("#user02", "user-2");
@@ -167,6 +177,7 @@ mod tests {
             c.interaction_repo(),
             c.publication_repo(),
             c.reader_repo(),
+            c.unit_of_work(),
         );
 
         let mut reader = mocks::reader("#user01", "user-1");
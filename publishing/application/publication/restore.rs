@@ -0,0 +1,134 @@
+use common::error::Error;
+use common::event::EventPublisher;
+use common::request::CommandResponse;
+use common::result::Result;
+use identity::UserIdAndRole;
+
+use crate::domain::publication::{PublicationId, PublicationRepository};
+
+pub struct Restore<'a> {
+    event_pub: &'a dyn EventPublisher,
+
+    publication_repo: &'a dyn PublicationRepository,
+}
+
+impl<'a> Restore<'a> {
+    pub fn new(
+        event_pub: &'a dyn EventPublisher,
+        publication_repo: &'a dyn PublicationRepository,
+    ) -> Self {
+        Restore {
+            event_pub,
+            publication_repo,
+        }
+    }
+
+    pub async fn exec(
+        &self,
+        (_auth_id, auth_role): UserIdAndRole,
+        publication_id: String,
+    ) -> Result<CommandResponse> {
+        if !auth_role.can("restore_deleted") {
+            return Err(Error::unauthorized());
+        }
+
+        let publication_id = PublicationId::new(publication_id)?;
+        let mut publication = self
+            .publication_repo
+            .find_deleted_by_id(&publication_id)
+            .await?;
+
+        publication.restore()?;
+
+        self.publication_repo.save(&mut publication).await?;
+
+        self.event_pub
+            .publish_all(publication.events().to_vec()?)
+            .await?;
+
+        Ok(CommandResponse::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use common::request::{Include, PaginationParams};
+    use identity::domain::role::{Name, Role};
+    use identity::domain::user::UserId;
+    use identity::mocks as identity_mocks;
+
+    use crate::application::publication::SearchDeleted;
+    use crate::mocks;
+
+    #[tokio::test]
+    async fn restore_deleted_publication() {
+        let c = mocks::container();
+        let uc = Restore::new(c.event_pub(), c.publication_repo());
+        let search_deleted =
+            SearchDeleted::new(c.author_repo(), c.category_repo(), c.publication_repo());
+
+        let mut publication = mocks::publication(
+            "#publication01",
+            "#user01",
+            "Publication 01",
+            "category-1",
+            vec!["Tag 1", "Tag 2"],
+            "domain.com/cover.jpg",
+            3,
+            false,
+            false,
+            false,
+        );
+        publication.delete().unwrap();
+        c.publication_repo().save(&mut publication).await.unwrap();
+        assert!(c
+            .publication_repo()
+            .find_by_id(publication.base().id())
+            .await
+            .is_err());
+
+        let admin = (
+            UserId::new("#admin01").unwrap(),
+            identity_mocks::role("Admin"),
+        );
+        let res = search_deleted
+            .exec(
+                admin.clone(),
+                Include::default(),
+                PaginationParams::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.items.len(), 1);
+        assert!(res.items[0].deleted_at.is_some());
+
+        let user = (
+            UserId::new("#user01").unwrap(),
+            Role::new(Name::new("User").unwrap()).unwrap(),
+        );
+        assert!(uc
+            .exec(user, publication.base().id().to_string())
+            .await
+            .is_err());
+
+        uc.exec(admin.clone(), publication.base().id().to_string())
+            .await
+            .unwrap();
+
+        let publication = c
+            .publication_repo()
+            .find_by_id(publication.base().id())
+            .await
+            .unwrap();
+        assert!(publication.base().deleted_at().is_none());
+        assert_eq!(c.event_pub().events().await[0].code(), "restored");
+
+        // Only deleted publications can be restored.
+        assert!(uc
+            .exec(admin, publication.base().id().to_string())
+            .await
+            .is_err());
+    }
+}
@@ -1,17 +1,21 @@
 use std::str::FromStr;
 
-use chrono::DateTime;
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
 use common::error::Error;
-use common::request::{Include, PaginationParams, PaginationResponse};
+use common::request::{CursorSigner, Include, PaginationParams, PaginationResponse};
 use common::result::Result;
+use common::sql::{Criteria, Query};
 use identity::UserIdAndRole;
 
-use crate::application::dtos::{AuthorDto, CategoryDto, PublicationDto};
+use crate::application::dtos::PublicationDto;
+use crate::application::expand::Expand;
 use crate::domain::author::{AuthorId, AuthorRepository};
-use crate::domain::category::CategoryRepository;
-use crate::domain::publication::{PublicationOrderBy, PublicationRepository, Status, Tag};
+use crate::domain::category::{CategoryId, CategoryRepository};
+use crate::domain::publication::{
+    PublicationField, PublicationOrderBy, PublicationRepository, Status, Tag,
+};
 
 #[derive(Deserialize)]
 pub struct SearchCommand {
@@ -28,6 +32,8 @@ pub struct Search<'a> {
     author_repo: &'a dyn AuthorRepository,
     category_repo: &'a dyn CategoryRepository,
     publication_repo: &'a dyn PublicationRepository,
+
+    cursor_signer: &'a CursorSigner,
 }
 
 impl<'a> Search<'a> {
@@ -35,11 +41,13 @@ impl<'a> Search<'a> {
         author_repo: &'a dyn AuthorRepository,
         category_repo: &'a dyn CategoryRepository,
         publication_repo: &'a dyn PublicationRepository,
+        cursor_signer: &'a CursorSigner,
     ) -> Self {
         Search {
             author_repo,
             category_repo,
             publication_repo,
+            cursor_signer,
         }
     }
 
@@ -50,6 +58,8 @@ impl<'a> Search<'a> {
         include: Include,
         pagination: PaginationParams,
     ) -> Result<PaginationResponse<PublicationDto>> {
+        include.validate(&PublicationDto::SCHEMA)?;
+
         let is_reader_author =
             if let (Some((auth_id, _)), Some(author_id)) = (&user_id_and_role, &cmd.author_id) {
                 auth_id.value() == author_id
@@ -72,60 +82,69 @@ impl<'a> Search<'a> {
             })
         };
 
-        let pagination_publications = self
-            .publication_repo
-            .search(
-                cmd.author_id.map(AuthorId::new).transpose()?.as_ref(),
-                cmd.category_id.map(AuthorId::new).transpose()?.as_ref(),
-                cmd.tag.map(Tag::new).transpose()?.as_ref(),
-                status.as_ref(),
-                cmd.name.as_ref(),
+        let order_by = pagination
+            .order_by()
+            .map(|o| PublicationOrderBy::from_str(&o))
+            .transpose()?
+            .unwrap_or(PublicationOrderBy::Oldest);
+        let (field, direction) = order_by.sort_key();
+
+        let query = Query::new()
+            .filter_opt(
+                cmd.author_id
+                    .map(AuthorId::new)
+                    .transpose()?
+                    .map(|id| Criteria::eq(PublicationField::AuthorId, &id)),
+            )
+            .filter_opt(
+                cmd.category_id
+                    .map(CategoryId::new)
+                    .transpose()?
+                    .map(|id| Criteria::eq(PublicationField::CategoryId, &id)),
+            )
+            .filter_opt(
+                cmd.tag
+                    .map(Tag::new)
+                    .transpose()?
+                    .map(|tag| Criteria::eq(PublicationField::Tag, tag.slug())),
+            )
+            .filter_opt(status.map(|s| Criteria::eq(PublicationField::Status, s.to_string())))
+            .filter_opt(
+                cmd.name
+                    .map(|name| Criteria::contains(PublicationField::Name, name)),
+            )
+            .filter(Criteria::range(
+                PublicationField::CreatedAt,
                 cmd.date_from
-                    .map(|d| DateTime::from_str(&d))
+                    .map(|d| DateTime::<Utc>::from_str(&d))
                     .transpose()
-                    .map_err(|err| Error::bad_format("date_from").wrap_raw(err))?
-                    .as_ref(),
+                    .map_err(|err| Error::bad_format("date_from").wrap_raw(err))?,
                 cmd.date_to
-                    .map(|d| DateTime::from_str(&d))
+                    .map(|d| DateTime::<Utc>::from_str(&d))
                     .transpose()
-                    .map_err(|err| Error::bad_format("date_to").wrap_raw(err))?
-                    .as_ref(),
-                pagination.offset(),
-                pagination.limit(),
-                pagination
-                    .order_by()
-                    .map(|o| PublicationOrderBy::from_str(&o))
-                    .transpose()?
-                    .as_ref(),
-            )
-            .await?;
+                    .map_err(|err| Error::bad_format("date_to").wrap_raw(err))?,
+            ))
+            .sort(field, direction)
+            .cursor(PublicationField::Id, pagination.cursor(self.cursor_signer)?)?
+            .paginate(pagination.offset(), pagination.limit());
 
-        let mut res = PaginationResponse::new(
-            pagination_publications.offset(),
-            pagination_publications.limit(),
-            pagination_publications.total(),
-            pagination_publications.matching_criteria(),
-        );
+        let pagination_publications = self.publication_repo.search(&query).await?;
 
-        for publication in pagination_publications.into_items().into_iter() {
-            let mut publication_dto = PublicationDto::from(&publication);
+        let mut res =
+            PaginationResponse::from_pagination(&pagination_publications, self.cursor_signer);
 
-            if include.has("author") {
-                let author = self.author_repo.find_by_id(publication.author_id()).await?;
-                publication_dto = publication_dto.author(AuthorDto::from(&author));
-            }
-
-            if include.has("category") {
-                let category = self
-                    .category_repo
-                    .find_by_id(publication.header().category_id())
-                    .await?;
-                publication_dto = publication_dto.category(CategoryDto::from(&category));
-            }
+        let publication_dtos = Expand::new(self.author_repo, self.category_repo)
+            .publications(pagination_publications.items(), &include)
+            .await?;
 
+        for (publication, mut publication_dto) in pagination_publications
+            .items()
+            .iter()
+            .zip(publication_dtos.into_iter())
+        {
             if let Some((auth_id, _)) = &user_id_and_role {
                 if publication.author_id() == auth_id {
-                    publication_dto = publication_dto.pages(&publication)
+                    publication_dto = publication_dto.pages(publication)
                 }
             }
 
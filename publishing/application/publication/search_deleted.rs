@@ -0,0 +1,66 @@
+use common::error::Error;
+use common::request::{CursorSigner, Include, PaginationParams, PaginationResponse};
+use common::result::Result;
+use common::sql::{Direction, Query};
+use identity::UserIdAndRole;
+
+use crate::application::dtos::PublicationDto;
+use crate::application::expand::Expand;
+use crate::domain::author::AuthorRepository;
+use crate::domain::category::CategoryRepository;
+use crate::domain::publication::{PublicationField, PublicationRepository};
+
+pub struct SearchDeleted<'a> {
+    author_repo: &'a dyn AuthorRepository,
+    category_repo: &'a dyn CategoryRepository,
+    publication_repo: &'a dyn PublicationRepository,
+
+    cursor_signer: &'a CursorSigner,
+}
+
+impl<'a> SearchDeleted<'a> {
+    pub fn new(
+        author_repo: &'a dyn AuthorRepository,
+        category_repo: &'a dyn CategoryRepository,
+        publication_repo: &'a dyn PublicationRepository,
+        cursor_signer: &'a CursorSigner,
+    ) -> Self {
+        SearchDeleted {
+            author_repo,
+            category_repo,
+            publication_repo,
+            cursor_signer,
+        }
+    }
+
+    pub async fn exec(
+        &self,
+        (_auth_id, auth_role): UserIdAndRole,
+        include: Include,
+        pagination: PaginationParams,
+    ) -> Result<PaginationResponse<PublicationDto>> {
+        if !auth_role.can("restore_deleted") {
+            return Err(Error::unauthorized());
+        }
+
+        include.validate(&PublicationDto::SCHEMA)?;
+
+        // The last deleted publications first.
+        let query = Query::new()
+            .sort(PublicationField::DeletedAt, Direction::Desc)
+            .cursor(PublicationField::Id, pagination.cursor(self.cursor_signer)?)?
+            .paginate(pagination.offset(), pagination.limit());
+
+        let pagination_publications = self.publication_repo.search_deleted(&query).await?;
+
+        let mut res =
+            PaginationResponse::from_pagination(&pagination_publications, self.cursor_signer);
+        res.add_items(
+            Expand::new(self.author_repo, self.category_repo)
+                .publications(pagination_publications.items(), &include)
+                .await?,
+        );
+
+        Ok(res)
+    }
+}
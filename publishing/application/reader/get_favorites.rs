@@ -5,7 +5,8 @@ use common::request::Include;
 use common::result::Result;
 use identity::UserIdAndRole;
 
-use crate::application::dtos::{AuthorDto, CategoryDto, CollectionDto, PublicationDto};
+use crate::application::dtos::{CollectionDto, PublicationDto};
+use crate::application::expand::Expand;
 use crate::domain::author::AuthorRepository;
 use crate::domain::category::CategoryRepository;
 use crate::domain::collection::CollectionRepository;
@@ -54,6 +55,10 @@ impl<'a> GetFavorites<'a> {
             return Err(Error::unauthorized());
         }
 
+        // The same relations and fields are used for publications and collections.
+        include.validate(&PublicationDto::SCHEMA)?;
+        include.validate(&CollectionDto::SCHEMA)?;
+
         let publication_favorites = self
             .interaction_repo
             .find_publication_favorites(Some(&ReaderId::new(&reader_id)?), None, None, None)
@@ -64,7 +69,7 @@ impl<'a> GetFavorites<'a> {
             .find_collection_favorites(Some(&ReaderId::new(reader_id)?), None, None, None)
             .await?;
 
-        let mut publication_dtos = Vec::new();
+        let mut publications = Vec::new();
         for favorite in publication_favorites.iter() {
             let publication = self
                 .publication_repo
@@ -75,51 +80,23 @@ impl<'a> GetFavorites<'a> {
                 continue;
             }
 
-            let mut publication_dto = PublicationDto::from(&publication);
-
-            if include.has("author") {
-                let author = self.author_repo.find_by_id(publication.author_id()).await?;
-                publication_dto = publication_dto.author(AuthorDto::from(&author));
-            }
-
-            if include.has("category") {
-                let category = self
-                    .category_repo
-                    .find_by_id(publication.header().category_id())
-                    .await?;
-                publication_dto = publication_dto.category(CategoryDto::from(&category));
-            }
-
-            publication_dtos.push(publication_dto);
+            publications.push(publication);
         }
 
-        let mut collection_dtos = Vec::new();
+        let mut collections = Vec::new();
         for favorite in collection_favorites.iter() {
             let collection = self
                 .collection_repo
                 .find_by_id(favorite.base().id().collection_id())
                 .await?;
-            let mut collection_dto = CollectionDto::from(&collection);
-
-            if include.has("author") {
-                let author = self.author_repo.find_by_id(collection.author_id()).await?;
-                collection_dto = collection_dto.author(AuthorDto::from(&author));
-            }
-
-            if include.has("category") {
-                let category = self
-                    .category_repo
-                    .find_by_id(collection.header().category_id())
-                    .await?;
-                collection_dto = collection_dto.category(CategoryDto::from(&category));
-            }
-
-            collection_dtos.push(collection_dto);
+            collections.push(collection);
         }
 
+        let expand = Expand::new(self.author_repo, self.category_repo);
+
         Ok(GetFavoritesResponse {
-            publications: publication_dtos,
-            collections: collection_dtos,
+            publications: expand.publications(&publications, &include).await?,
+            collections: expand.collections(&collections, &include).await?,
         })
     }
 }
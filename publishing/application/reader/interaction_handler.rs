@@ -61,3 +61,60 @@ impl EventHandler for InteractionHandler {
         Ok(false)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use common::event::ToEvent;
+
+    use crate::infrastructure::persistence::inmem::{
+        InMemPublicationRepository, InMemReaderRepository,
+    };
+    use crate::mocks;
+
+    #[tokio::test]
+    async fn replay_twice() {
+        let reader_repo = Arc::new(InMemReaderRepository::new());
+        let publication_repo = Arc::new(InMemPublicationRepository::new());
+        let mut handler = InteractionHandler::new(reader_repo.clone(), publication_repo.clone());
+
+        let mut reader = mocks::reader("#user02", "user-2");
+        reader_repo.save(&mut reader).await.unwrap();
+        let mut publication = mocks::publication(
+            "#publication01",
+            "#user01",
+            "Publication 01",
+            "category-1",
+            vec!["Tag 1", "Tag 2"],
+            "domain.com/cover.jpg",
+            3,
+            true,
+            true,
+            false,
+        );
+        publication_repo.save(&mut publication).await.unwrap();
+
+        let event = PublicationEvent::Read {
+            reader_id: reader.base().id().to_string(),
+            publication_id: publication.base().id().to_string(),
+        }
+        .to_event()
+        .unwrap();
+
+        for _ in 0..2 {
+            assert!(handler.handle(&event).await.unwrap());
+            assert!(handler.handle(&event).await.unwrap());
+
+            let reader = reader_repo.find_by_id(reader.base().id()).await.unwrap();
+            assert_eq!(
+                reader.preferences().publication_ids(),
+                &[publication.base().id().clone()]
+            );
+            assert_eq!(
+                reader.preferences().category_ids(),
+                &[publication.header().category_id().clone()]
+            );
+        }
+    }
+}
@@ -1,8 +1,12 @@
 mod get_by_id;
 mod get_favorites;
 mod get_following;
+mod interaction_handler;
+mod reader_from_user_handler;
 mod subscription_handler;
 pub use get_by_id::*;
 pub use get_favorites::*;
 pub use get_following::*;
+pub use interaction_handler::*;
+pub use reader_from_user_handler::*;
 pub use subscription_handler::*;
@@ -7,7 +7,7 @@ use common::event::{Event, EventHandler};
 use common::result::Result;
 use shared::event::UserEvent;
 
-use crate::domain::reader::{Reader, ReaderId, ReaderRepository};
+use crate::domain::reader::{ReaderId, ReaderRepository};
 
 pub struct ReaderFromUserHandler {
     reader_repo: Arc<dyn ReaderRepository>,
@@ -30,15 +30,21 @@ impl EventHandler for ReaderFromUserHandler {
             .map_err(|err| Error::new("reader_from_user_handler", "deserialize").wrap_raw(err))?;
 
         match event {
-            UserEvent::Validated { id } => {
-                let mut reader = Reader::new(ReaderId::new(id)?)?;
-                self.reader_repo.save(&mut reader).await?;
-            }
             UserEvent::Deleted { id } => {
                 let mut reader = self.reader_repo.find_by_id(&ReaderId::new(id)?).await?;
                 reader.delete()?;
                 self.reader_repo.save(&mut reader).await?;
             }
+            UserEvent::Restored { id } => {
+                let mut reader = self.reader_repo.find_by_id(&ReaderId::new(id)?).await?;
+                reader.restore()?;
+                self.reader_repo.save(&mut reader).await?;
+            }
+            UserEvent::UsernameChanged { id, username } => {
+                let mut reader = self.reader_repo.find_by_id(&ReaderId::new(id)?).await?;
+                reader.set_username(username)?;
+                self.reader_repo.save(&mut reader).await?;
+            }
             _ => return Ok(false),
         }
 
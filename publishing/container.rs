@@ -3,13 +3,18 @@ use std::sync::Arc;
 use async_trait::async_trait;
 
 use common::container::Container;
-use common::event::{EventPublisher, EventSubscriber};
+use common::event::{EventHandler, EventPublisher, EventSubscriber};
 use common::result::Result;
+use common::transaction::UnitOfWork;
 use identity::domain::user::UserRepository;
 
-use crate::application::author::PublicationCounterHandler;
+use crate::application::author::{
+    AuthorFromUserHandler, FollowerCounterHandler, PublicationCounterHandler,
+};
 use crate::application::publication::ContractHandler;
-use crate::application::reader::SubscriptionHandler;
+use crate::application::reader::{
+    InteractionHandler as ReaderInteractionHandler, ReaderFromUserHandler, SubscriptionHandler,
+};
 use crate::domain::author::AuthorRepository;
 use crate::domain::category::CategoryRepository;
 use crate::domain::collection::CollectionRepository;
@@ -29,6 +34,8 @@ pub struct PublishingContainer<EPub> {
     user_repo: Arc<dyn UserRepository>,
 
     statistics_serv: Arc<StatisticsService>,
+
+    unit_of_work: Arc<dyn UnitOfWork>,
 }
 
 impl<EPub> PublishingContainer<EPub>
@@ -45,6 +52,7 @@ where
         publication_repo: Arc<dyn PublicationRepository>,
         reader_repo: Arc<dyn ReaderRepository>,
         user_repo: Arc<dyn UserRepository>,
+        unit_of_work: Arc<dyn UnitOfWork>,
     ) -> Self {
         let statistics_serv = Arc::new(StatisticsService::new(interaction_repo.clone()));
 
@@ -60,6 +68,8 @@ where
             user_repo,
 
             statistics_serv,
+
+            unit_of_work,
         }
     }
 
@@ -103,6 +113,10 @@ where
     pub fn statistics_serv_clone(&self) -> Arc<StatisticsService> {
         self.statistics_serv.clone()
     }
+
+    pub fn unit_of_work(&self) -> &dyn UnitOfWork {
+        self.unit_of_work.as_ref()
+    }
 }
 
 #[async_trait]
@@ -114,16 +128,16 @@ where
     where
         ES: EventSubscriber + Sync + Send,
     {
-        // let author_from_user_handler = AuthorFromUserHandler::new(self.author_repo.clone());
-        // event_sub
-        //     .subscribe(Box::new(author_from_user_handler))
-        //     .await?;
-        //
-        // let reader_from_user_handler = ReaderFromUserHandler::new(self.reader_repo.clone());
-        // event_sub
-        //     .subscribe(Box::new(reader_from_user_handler))
-        //     .await?;
-        //
+        let author_from_user_handler = AuthorFromUserHandler::new(self.author_repo.clone());
+        event_sub
+            .subscribe(Box::new(author_from_user_handler))
+            .await?;
+
+        let reader_from_user_handler = ReaderFromUserHandler::new(self.reader_repo.clone());
+        event_sub
+            .subscribe(Box::new(reader_from_user_handler))
+            .await?;
+
         // let reader_handler =
         //     InteractionHandler::new(self.reader_repo.clone(), self.publication_repo.clone());
         // event_sub.subscribe(Box::new(reader_handler)).await?;
@@ -142,4 +156,38 @@ where
 
         Ok(())
     }
+
+    fn replay_handlers(&self) -> Vec<(&'static str, Box<dyn EventHandler>)> {
+        vec![
+            (
+                "author-publications",
+                Box::new(PublicationCounterHandler::new(
+                    self.author_repo.clone(),
+                    self.publication_repo.clone(),
+                )),
+            ),
+            (
+                "author-followers",
+                Box::new(FollowerCounterHandler::new(
+                    self.author_repo.clone(),
+                    self.interaction_repo.clone(),
+                )),
+            ),
+            (
+                "reader-preferences",
+                Box::new(ReaderInteractionHandler::new(
+                    self.reader_repo.clone(),
+                    self.publication_repo.clone(),
+                )),
+            ),
+            (
+                "reader-subscriptions",
+                Box::new(SubscriptionHandler::new(self.reader_repo.clone())),
+            ),
+            (
+                "publication-contracts",
+                Box::new(ContractHandler::new(self.publication_repo.clone())),
+            ),
+        ]
+    }
 }
@@ -70,6 +70,10 @@ impl Author {
         &self.events
     }
 
+    pub fn events_mut(&mut self) -> &mut Events<AuthorEvent> {
+        &mut self.events
+    }
+
     pub fn username(&self) -> &str {
         &self.username
     }
@@ -98,6 +102,11 @@ impl Author {
         self.publications
     }
 
+    pub fn set_username<S: Into<String>>(&mut self, username: S) -> Result<()> {
+        self.username = username.into();
+        Ok(())
+    }
+
     pub fn set_name<S: Into<String>>(&mut self, name: S, lastname: S) -> Result<()> {
         self.name = Some(name.into());
         self.lastname = Some(lastname.into());
@@ -155,6 +164,11 @@ impl Author {
         Ok(())
     }
 
+    pub fn set_followers(&mut self, followers: u32) -> Result<()> {
+        self.followers = followers;
+        Ok(())
+    }
+
     pub fn set_publications(&mut self, publications: u32) -> Result<()> {
         self.publications = publications;
         Ok(())
@@ -164,4 +178,9 @@ impl Author {
         self.base.delete();
         Ok(())
     }
+
+    pub fn restore(&mut self) -> Result<()> {
+        self.base.restore();
+        Ok(())
+    }
 }
@@ -1,15 +1,57 @@
 use std::str::FromStr;
 
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use common::error::Error;
 use common::model::Pagination;
 use common::result::Result;
+use common::sql::{Criteria, Direction, Query, Record, Value};
 
 use crate::domain::author::{Author, AuthorId};
 
+/// Fields of an author that can be used in a search `Query`.
+#[derive(Debug, Clone, Copy)]
+pub enum AuthorField {
+    Id,
+    Username,
+    Name,
+    Lastname,
+    /// Name and lastname separated by a space.
+    Fullname,
+    Followers,
+    Publications,
+    CreatedAt,
+}
+
+impl Record<AuthorField> for Author {
+    fn values(&self, field: &AuthorField) -> Vec<Value> {
+        match field {
+            AuthorField::Id => vec![self.base().id().into()],
+            AuthorField::Username => vec![self.username().into()],
+            AuthorField::Name => self
+                .name()
+                .map(|name| name.as_str().into())
+                .into_iter()
+                .collect(),
+            AuthorField::Lastname => self
+                .lastname()
+                .map(|lastname| lastname.as_str().into())
+                .into_iter()
+                .collect(),
+            AuthorField::Fullname => vec![format!(
+                "{} {}",
+                self.name().map_or("", |name| name),
+                self.lastname().map_or("", |lastname| lastname),
+            )
+            .into()],
+            AuthorField::Followers => vec![self.followers().into()],
+            AuthorField::Publications => vec![self.publications().into()],
+            AuthorField::CreatedAt => vec![self.base().created_at().into()],
+        }
+    }
+}
+
 #[async_trait]
 pub trait AuthorRepository: Sync + Send {
     async fn next_id(&self) -> Result<AuthorId> {
@@ -17,16 +59,17 @@ pub trait AuthorRepository: Sync + Send {
     }
 
     async fn find_by_id(&self, id: &AuthorId) -> Result<Author>;
-    async fn search(
-        &self,
-        name: Option<&String>,
-        publications_gt: Option<u32>,
-        from: Option<&DateTime<Utc>>,
-        to: Option<&DateTime<Utc>>,
-        offset: Option<usize>,
-        limit: Option<usize>,
-        order_by: Option<&AuthorOrderBy>,
-    ) -> Result<Pagination<Author>>;
+
+    /// Finds the authors with the given IDs in a single search. IDs not found are skipped.
+    async fn find_by_ids(&self, ids: &[AuthorId]) -> Result<Vec<Author>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let query = Query::new().filter(Criteria::is_in(AuthorField::Id, ids));
+        Ok(self.search(&query).await?.into_items())
+    }
+    async fn search(&self, query: &Query<AuthorField>) -> Result<Pagination<Author>>;
 
     async fn save(&self, author: &mut Author) -> Result<()>;
 
@@ -40,6 +83,17 @@ pub enum AuthorOrderBy {
     Publications,
 }
 
+impl AuthorOrderBy {
+    pub fn sort_key(&self) -> (AuthorField, Direction) {
+        match self {
+            AuthorOrderBy::Oldest => (AuthorField::CreatedAt, Direction::Asc),
+            AuthorOrderBy::Newest => (AuthorField::CreatedAt, Direction::Desc),
+            AuthorOrderBy::Followers => (AuthorField::Followers, Direction::Desc),
+            AuthorOrderBy::Publications => (AuthorField::Publications, Direction::Desc),
+        }
+    }
+}
+
 impl FromStr for AuthorOrderBy {
     type Err = Error;
 
@@ -53,6 +53,10 @@ impl Category {
         &self.events
     }
 
+    pub fn events_mut(&mut self) -> &mut Events<CategoryEvent> {
+        &mut self.events
+    }
+
     pub fn name(&self) -> &Name {
         &self.name
     }
@@ -14,6 +14,9 @@ pub trait CategoryRepository: Sync + Send {
     async fn find_all(&self) -> Result<Vec<Category>>;
     async fn find_by_id(&self, id: &CategoryId) -> Result<Category>;
 
+    /// Finds the categories with the given IDs. IDs not found are skipped.
+    async fn find_by_ids(&self, ids: &[CategoryId]) -> Result<Vec<Category>>;
+
     async fn save(&self, category: &mut Category) -> Result<()>;
 
     async fn delete(&self, id: &CategoryId) -> Result<()>;
@@ -74,6 +74,10 @@ impl Collection {
         &self.events
     }
 
+    pub fn events_mut(&mut self) -> &mut Events<CollectionEvent> {
+        &mut self.events
+    }
+
     pub fn author_id(&self) -> &AuthorId {
         &self.author_id
     }
@@ -149,4 +153,18 @@ impl Collection {
 
         Ok(())
     }
+
+    pub fn restore(&mut self) -> Result<()> {
+        if !self.base.is_deleted() {
+            return Err(Error::new("collection", "not_deleted"));
+        }
+
+        self.base.restore();
+
+        self.events.record_event(CollectionEvent::Restored {
+            id: self.base().id().to_string(),
+        });
+
+        Ok(())
+    }
 }
@@ -1,17 +1,59 @@
 use std::str::FromStr;
 
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use common::error::Error;
 use common::model::Pagination;
 use common::result::Result;
+use common::sql::{Direction, Query, Record, Value};
 
-use crate::domain::author::AuthorId;
-use crate::domain::category::CategoryId;
 use crate::domain::collection::{Collection, CollectionId};
-use crate::domain::publication::{PublicationId, Tag};
+
+/// Fields of a collection that can be used in a search `Query`.
+#[derive(Debug, Clone, Copy)]
+pub enum CollectionField {
+    Id,
+    AuthorId,
+    CategoryId,
+    /// ID of any of the publications.
+    PublicationId,
+    /// Slug of any of the tags.
+    Tag,
+    Name,
+    CreatedAt,
+    /// Only set in deleted collections.
+    DeletedAt,
+}
+
+impl Record<CollectionField> for Collection {
+    fn values(&self, field: &CollectionField) -> Vec<Value> {
+        match field {
+            CollectionField::Id => vec![self.base().id().into()],
+            CollectionField::AuthorId => vec![self.author_id().into()],
+            CollectionField::CategoryId => vec![self.header().category_id().into()],
+            CollectionField::PublicationId => self
+                .items()
+                .iter()
+                .map(|item| item.publication_id().into())
+                .collect(),
+            CollectionField::Tag => self
+                .header()
+                .tags()
+                .iter()
+                .map(|tag| tag.slug().into())
+                .collect(),
+            CollectionField::Name => vec![self.header().name().value().into()],
+            CollectionField::CreatedAt => vec![self.base().created_at().into()],
+            CollectionField::DeletedAt => self
+                .base()
+                .deleted_at()
+                .map(Value::from)
+                .into_iter()
+                .collect(),
+        }
+    }
+}
 
 #[async_trait]
 pub trait CollectionRepository: Sync + Send {
@@ -19,19 +61,13 @@ pub trait CollectionRepository: Sync + Send {
         CollectionId::new(Uuid::new_v4().to_string())
     }
 
+    /// Deleted collections are skipped, except by the `*_deleted` methods.
     async fn find_by_id(&self, id: &CollectionId) -> Result<Collection>;
-    async fn search(
+    async fn find_deleted_by_id(&self, id: &CollectionId) -> Result<Collection>;
+    async fn search(&self, query: &Query<CollectionField>) -> Result<Pagination<Collection>>;
+    async fn search_deleted(
         &self,
-        author_id: Option<&AuthorId>,
-        category_id: Option<&CategoryId>,
-        publication_id: Option<&PublicationId>,
-        tag: Option<&Tag>,
-        name: Option<&String>,
-        from: Option<&DateTime<Utc>>,
-        to: Option<&DateTime<Utc>>,
-        offset: Option<usize>,
-        limit: Option<usize>,
-        order_by: Option<&CollectionOrderBy>,
+        query: &Query<CollectionField>,
     ) -> Result<Pagination<Collection>>;
 
     async fn save(&self, collection: &mut Collection) -> Result<()>;
@@ -44,6 +80,15 @@ pub enum CollectionOrderBy {
     Newest,
 }
 
+impl CollectionOrderBy {
+    pub fn sort_key(&self) -> (CollectionField, Direction) {
+        match self {
+            CollectionOrderBy::Oldest => (CollectionField::CreatedAt, Direction::Asc),
+            CollectionOrderBy::Newest => (CollectionField::CreatedAt, Direction::Desc),
+        }
+    }
+}
+
 impl FromStr for CollectionOrderBy {
     type Err = Error;
 
@@ -109,6 +109,10 @@ impl Publication {
         &self.events
     }
 
+    pub fn events_mut(&mut self) -> &mut Events<PublicationEvent> {
+        &mut self.events
+    }
+
     pub fn author_id(&self) -> &AuthorId {
         &self.author_id
     }
@@ -494,6 +498,20 @@ impl Publication {
 
         Ok(())
     }
+
+    pub fn restore(&mut self) -> Result<()> {
+        if !self.base.is_deleted() {
+            return Err(Error::new("publication", "not_deleted"));
+        }
+
+        self.base.restore();
+
+        self.events.record_event(PublicationEvent::Restored {
+            id: self.base().id().to_string(),
+        });
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
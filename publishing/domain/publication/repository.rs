@@ -1,16 +1,63 @@
 use std::str::FromStr;
 
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use common::error::Error;
 use common::model::Pagination;
 use common::result::Result;
+use common::sql::{Criteria, Direction, Query, Record, Value};
 
-use crate::domain::author::AuthorId;
-use crate::domain::category::CategoryId;
-use crate::domain::publication::{Publication, PublicationId, Status, Tag};
+use crate::domain::publication::{Publication, PublicationId};
+
+/// Fields of a publication that can be used in a search `Query`.
+#[derive(Debug, Clone, Copy)]
+pub enum PublicationField {
+    Id,
+    AuthorId,
+    CategoryId,
+    /// Slug of any of the tags.
+    Tag,
+    /// Current status.
+    Status,
+    Name,
+    CreatedAt,
+    Views,
+    Likes,
+    Stars,
+    /// Only set in deleted publications.
+    DeletedAt,
+}
+
+impl Record<PublicationField> for Publication {
+    fn values(&self, field: &PublicationField) -> Vec<Value> {
+        match field {
+            PublicationField::Id => vec![self.base().id().into()],
+            PublicationField::AuthorId => vec![self.author_id().into()],
+            PublicationField::CategoryId => vec![self.header().category_id().into()],
+            PublicationField::Tag => self
+                .header()
+                .tags()
+                .iter()
+                .map(|tag| tag.slug().into())
+                .collect(),
+            PublicationField::Status => {
+                vec![self.status_history().current().to_string().into()]
+            }
+            PublicationField::Name => vec![self.header().name().value().into()],
+            PublicationField::CreatedAt => vec![self.base().created_at().into()],
+            PublicationField::Views => vec![self.statistics().views().into()],
+            PublicationField::Likes => vec![self.statistics().likes().into()],
+            PublicationField::Stars => vec![(self.statistics().stars() as f64).into()],
+            PublicationField::DeletedAt => self
+                .base()
+                .deleted_at()
+                .map(Value::from)
+                .into_iter()
+                .collect(),
+        }
+    }
+}
 
 #[async_trait]
 pub trait PublicationRepository: Sync + Send {
@@ -18,19 +65,23 @@ pub trait PublicationRepository: Sync + Send {
         PublicationId::new(Uuid::new_v4().to_string())
     }
 
+    /// Deleted publications are skipped, except by the `*_deleted` methods.
     async fn find_by_id(&self, id: &PublicationId) -> Result<Publication>;
-    async fn search(
+    async fn find_deleted_by_id(&self, id: &PublicationId) -> Result<Publication>;
+
+    /// Finds the publications with the given IDs in a single search. IDs not found are skipped.
+    async fn find_by_ids(&self, ids: &[PublicationId]) -> Result<Vec<Publication>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let query = Query::new().filter(Criteria::is_in(PublicationField::Id, ids));
+        Ok(self.search(&query).await?.into_items())
+    }
+    async fn search(&self, query: &Query<PublicationField>) -> Result<Pagination<Publication>>;
+    async fn search_deleted(
         &self,
-        author_id: Option<&AuthorId>,
-        category_id: Option<&CategoryId>,
-        tag: Option<&Tag>,
-        status: Option<&Status>,
-        name: Option<&String>,
-        from: Option<&DateTime<Utc>>,
-        to: Option<&DateTime<Utc>>,
-        offset: Option<usize>,
-        limit: Option<usize>,
-        order_by: Option<&PublicationOrderBy>,
+        query: &Query<PublicationField>,
     ) -> Result<Pagination<Publication>>;
 
     async fn save(&self, publication: &mut Publication) -> Result<()>;
@@ -46,6 +97,18 @@ pub enum PublicationOrderBy {
     BestReviews,
 }
 
+impl PublicationOrderBy {
+    pub fn sort_key(&self) -> (PublicationField, Direction) {
+        match self {
+            PublicationOrderBy::Oldest => (PublicationField::CreatedAt, Direction::Asc),
+            PublicationOrderBy::Newest => (PublicationField::CreatedAt, Direction::Desc),
+            PublicationOrderBy::MostViewed => (PublicationField::Views, Direction::Desc),
+            PublicationOrderBy::MostLiked => (PublicationField::Likes, Direction::Desc),
+            PublicationOrderBy::BestReviews => (PublicationField::Stars, Direction::Desc),
+        }
+    }
+}
+
 impl FromStr for PublicationOrderBy {
     type Err = Error;
 
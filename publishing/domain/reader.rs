@@ -72,6 +72,10 @@ impl Reader {
         &self.events
     }
 
+    pub fn events_mut(&mut self) -> &mut Events<ReaderEvent> {
+        &mut self.events
+    }
+
     pub fn username(&self) -> &str {
         &self.username
     }
@@ -180,8 +184,18 @@ impl Reader {
         Ok(())
     }
 
+    pub fn set_username<S: Into<String>>(&mut self, username: S) -> Result<()> {
+        self.username = username.into();
+        Ok(())
+    }
+
     pub fn delete(&mut self) -> Result<()> {
         self.base.delete();
         Ok(())
     }
+
+    pub fn restore(&mut self) -> Result<()> {
+        self.base.restore();
+        Ok(())
+    }
 }
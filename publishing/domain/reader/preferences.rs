@@ -10,10 +10,18 @@ pub struct Preferences {
 }
 
 impl Preferences {
+    /// Adds the publication and its category once, so the same readings can be replayed.
     pub fn add_publication(&mut self, publication: &Publication) -> Result<()> {
-        self.publication_ids.push(publication.base().id().clone());
-        self.category_ids
-            .push(publication.header().category_id().clone());
+        let publication_id = publication.base().id();
+        if !self.publication_ids.contains(publication_id) {
+            self.publication_ids.push(publication_id.clone());
+        }
+
+        let category_id = publication.header().category_id();
+        if !self.category_ids.contains(category_id) {
+            self.category_ids.push(category_id.clone());
+        }
+
         Ok(())
     }
 
@@ -12,6 +12,7 @@ pub trait ReaderRepository: Sync + Send {
     }
 
     async fn find_by_id(&self, id: &ReaderId) -> Result<Reader>;
+    async fn find_by_ids(&self, ids: &[ReaderId]) -> Result<Vec<Reader>>;
 
     async fn save(&self, reader: &mut Reader) -> Result<()>;
 
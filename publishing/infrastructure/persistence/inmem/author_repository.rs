@@ -1,13 +1,13 @@
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
 
 use common::cache::Cache;
 use common::error::Error;
 use common::infrastructure::cache::InMemCache;
 use common::model::Pagination;
 use common::result::Result;
+use common::sql::Query;
 
-use crate::domain::author::{Author, AuthorId, AuthorOrderBy, AuthorRepository};
+use crate::domain::author::{Author, AuthorField, AuthorId, AuthorRepository};
 
 pub struct InMemAuthorRepository {
     cache: InMemCache<AuthorId, Author>,
@@ -36,18 +36,8 @@ impl AuthorRepository for InMemAuthorRepository {
             .ok_or_else(|| Error::not_found("author"))
     }
 
-    async fn search(
-        &self,
-        _name: Option<&String>,
-        _publications_gt: Option<u32>,
-        _from: Option<&DateTime<Utc>>,
-        _to: Option<&DateTime<Utc>>,
-        _offset: Option<usize>,
-        _limit: Option<usize>,
-        _order_by: Option<&AuthorOrderBy>,
-    ) -> Result<Pagination<Author>> {
-        let authors = self.cache.all().await;
-        Ok(Pagination::new(0, authors.len(), authors.len(), authors.len()).add_items(authors))
+    async fn search(&self, query: &Query<AuthorField>) -> Result<Pagination<Author>> {
+        Ok(query.apply(self.cache.all().await))
     }
 
     async fn save(&self, author: &mut Author) -> Result<()> {
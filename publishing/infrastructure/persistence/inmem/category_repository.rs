@@ -38,6 +38,18 @@ impl CategoryRepository for InMemCategoryRepository {
             .ok_or_else(|| Error::not_found("category"))
     }
 
+    async fn find_by_ids(&self, ids: &[CategoryId]) -> Result<Vec<Category>> {
+        let mut categories = Vec::new();
+
+        for id in ids.iter() {
+            if let Some(category) = self.cache.get(id).await {
+                categories.push(category);
+            }
+        }
+
+        Ok(categories)
+    }
+
     async fn save(&self, category: &mut Category) -> Result<()> {
         if category.base().deleted_at().is_none() {
             self.cache
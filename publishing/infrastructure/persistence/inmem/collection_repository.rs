@@ -1,18 +1,13 @@
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
 
 use common::cache::Cache;
 use common::error::Error;
 use common::infrastructure::cache::InMemCache;
 use common::model::Pagination;
 use common::result::Result;
+use common::sql::Query;
 
-use crate::domain::author::AuthorId;
-use crate::domain::category::CategoryId;
-use crate::domain::collection::{
-    Collection, CollectionId, CollectionOrderBy, CollectionRepository,
-};
-use crate::domain::publication::{PublicationId, Tag};
+use crate::domain::collection::{Collection, CollectionField, CollectionId, CollectionRepository};
 
 pub struct InMemCollectionRepository {
     cache: InMemCache<CollectionId, Collection>,
@@ -38,74 +33,41 @@ impl CollectionRepository for InMemCollectionRepository {
         self.cache
             .get(id)
             .await
+            .filter(|collection| !collection.base().is_deleted())
             .ok_or_else(|| Error::not_found("collection"))
     }
 
-    async fn search(
-        &self,
-        author_id: Option<&AuthorId>,
-        category_id: Option<&CategoryId>,
-        publication_id: Option<&PublicationId>,
-        _tag: Option<&Tag>,
-        name: Option<&String>,
-        _from: Option<&DateTime<Utc>>,
-        _to: Option<&DateTime<Utc>>,
-        _offset: Option<usize>,
-        _limit: Option<usize>,
-        _order_by: Option<&CollectionOrderBy>,
-    ) -> Result<Pagination<Collection>> {
-        let mut collections = self.cache.all().await;
-
-        if let Some(author_id) = author_id {
-            collections = collections
-                .into_iter()
-                .filter(|collection| collection.author_id() == author_id)
-                .collect();
-        }
-
-        if let Some(category_id) = category_id {
-            collections = collections
-                .into_iter()
-                .filter(|collection| collection.header().category_id() == category_id)
-                .collect();
-        }
-
-        if let Some(publication_id) = publication_id {
-            collections = collections
-                .into_iter()
-                .filter(|collection| {
-                    for item in collection.items() {
-                        if item.publication_id() == publication_id {
-                            return true;
-                        }
-                    }
-
-                    false
-                })
-                .collect();
-        }
+    async fn find_deleted_by_id(&self, id: &CollectionId) -> Result<Collection> {
+        self.cache
+            .get(id)
+            .await
+            .filter(|collection| collection.base().is_deleted())
+            .ok_or_else(|| Error::not_found("collection"))
+    }
 
-        if let Some(name) = name {
-            collections = collections
-                .into_iter()
-                .filter(|collection| collection.header().name().to_string().contains(name))
-                .collect();
-        }
+    async fn search(&self, query: &Query<CollectionField>) -> Result<Pagination<Collection>> {
+        Ok(query.apply(
+            self.cache
+                .filter(|(_, collection)| !collection.base().is_deleted())
+                .await,
+        ))
+    }
 
-        Ok(
-            Pagination::new(0, collections.len(), collections.len(), collections.len())
-                .add_items(collections),
-        )
+    async fn search_deleted(
+        &self,
+        query: &Query<CollectionField>,
+    ) -> Result<Pagination<Collection>> {
+        Ok(query.apply(
+            self.cache
+                .filter(|(_, collection)| collection.base().is_deleted())
+                .await,
+        ))
     }
 
     async fn save(&self, collection: &mut Collection) -> Result<()> {
-        if collection.base().deleted_at().is_none() {
-            self.cache
-                .set(collection.base().id().clone(), collection.clone())
-                .await
-        } else {
-            self.cache.delete(collection.base().id()).await
-        }
+        self.cache
+            .set(collection.base().id().clone(), collection.clone())
+            .await
     }
 
     async fn delete(&self, id: &CollectionId) -> Result<()> {
@@ -34,6 +34,18 @@ impl ReaderRepository for InMemReaderRepository {
             .ok_or_else(|| Error::not_found("reader"))
     }
 
+    async fn find_by_ids(&self, ids: &[ReaderId]) -> Result<Vec<Reader>> {
+        let mut readers = Vec::new();
+
+        for id in ids.iter() {
+            if let Some(reader) = self.cache.get(id).await {
+                readers.push(reader);
+            }
+        }
+
+        Ok(readers)
+    }
+
     async fn save(&self, reader: &mut Reader) -> Result<()> {
         if reader.base().deleted_at().is_none() {
             self.cache
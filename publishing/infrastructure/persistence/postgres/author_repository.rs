@@ -1,17 +1,32 @@
-use std::sync::Arc;
-
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use tokio_postgres::row::Row;
-use tokio_postgres::Client;
 use uuid::Uuid;
 
 use common::error::Error;
+use common::infrastructure::event::PostgresOutbox;
+use common::infrastructure::postgres::PostgresClient;
 use common::model::{AggregateRoot, Pagination};
+use common::request::PAGINATION_LIMIT;
 use common::result::Result;
-use common::sql::where_builder::WhereBuilder;
-
-use crate::domain::author::{Author, AuthorId, AuthorOrderBy, AuthorRepository};
+use common::sql::{Column, Field, Query};
+
+use crate::domain::author::{Author, AuthorField, AuthorId, AuthorRepository};
+
+impl Field for AuthorField {
+    fn column(&self) -> Column {
+        match self {
+            AuthorField::Id => Column::Expr("id"),
+            AuthorField::Username => Column::Expr("username"),
+            AuthorField::Name => Column::Expr("name"),
+            AuthorField::Lastname => Column::Expr("lastname"),
+            AuthorField::Fullname => Column::Expr("CONCAT(name, ' ', lastname)"),
+            AuthorField::Followers => Column::Expr("followers"),
+            AuthorField::Publications => Column::Expr("publications"),
+            AuthorField::CreatedAt => Column::Expr("created_at"),
+        }
+    }
+}
 
 impl Author {
     fn from_row(row: Row) -> Result<Self> {
@@ -50,11 +65,11 @@ impl Author {
 }
 
 pub struct PostgresAuthorRepository {
-    client: Arc<Client>,
+    client: PostgresClient,
 }
 
 impl PostgresAuthorRepository {
-    pub fn new(client: Arc<Client>) -> Self {
+    pub fn new(client: PostgresClient) -> Self {
         PostgresAuthorRepository { client }
     }
 }
@@ -71,70 +86,45 @@ impl AuthorRepository for PostgresAuthorRepository {
         Author::from_row(row)
     }
 
-    async fn search(
-        &self,
-        name: Option<&String>,
-        publications_gt: Option<u32>,
-        from: Option<&DateTime<Utc>>,
-        to: Option<&DateTime<Utc>>,
-        offset: Option<usize>,
-        limit: Option<usize>,
-        order_by: Option<&AuthorOrderBy>,
-    ) -> Result<Pagination<Author>> {
-        let publications_gt = publications_gt.map(|publications_gt| publications_gt as i32);
-
-        let (sql, params) = WhereBuilder::new()
-            .add_param_opt(
-                "(
-                    LOWER(username) LIKE '%' || LOWER($$) || '%'
-                    OR LOWER(name) LIKE '%' || LOWER($$) || '%'
-                    OR LOWER(lastname) LIKE '%' || LOWER($$) || '%'
-                    OR LOWER(CONCAT(name, ' ', lastname)) LIKE '%' || LOWER($$) || '%'
-                )",
-                &name,
-                name.is_some(),
-            )
-            .add_param_opt(
-                "publications >= $$",
-                &publications_gt,
-                publications_gt.is_some(),
-            )
-            .add_param_opt("created_at >= $$", &from, from.is_some())
-            .add_param_opt("created_at <= $$", &to, to.is_some())
-            .build();
-
-        // Total
-        let row = self
-            .client
-            .query_one(&format!("SELECT COUNT(*) FROM users") as &str, &[])
-            .await
-            .map_err(|err| Error::new("author", "total").wrap_raw(err))?;
-        let total: i64 = row.get(0);
-
-        // Matching criteria
-        let row = self
-            .client
-            .query_one(
-                &format!(
-                    "SELECT COUNT(*) FROM users
-                    {}",
-                    sql,
-                ) as &str,
-                &params,
-            )
-            .await
-            .map_err(|err| Error::new("author", "matching_criteria").wrap_raw(err))?;
-        let matching_criteria: i64 = row.get(0);
+    async fn search(&self, query: &Query<AuthorField>) -> Result<Pagination<Author>> {
+        let sql = query.to_sql();
+        let params = sql.params();
+
+        // Pages after a cursor are not counted
+        let mut counts = None;
+        if query.after().is_none() {
+            // Total
+            let row = self
+                .client
+                .query_one("SELECT COUNT(*) FROM users", &[])
+                .await
+                .map_err(|err| Error::new("author", "total").wrap_raw(err))?;
+            let total: i64 = row.get(0);
+
+            // Matching criteria
+            let row = self
+                .client
+                .query_one(
+                    &format!(
+                        "SELECT COUNT(*) FROM users
+                        {}",
+                        sql.where_clause(),
+                    ) as &str,
+                    &params,
+                )
+                .await
+                .map_err(|err| Error::new("author", "matching_criteria").wrap_raw(err))?;
+            let matching_criteria: i64 = row.get(0);
+
+            counts = Some((total as usize, matching_criteria as usize));
+        }
 
         // Query
-        let offset = offset.unwrap_or_else(|| 0);
-        let limit = limit.unwrap_or_else(|| total as usize);
-        let order_by = match order_by {
-            Some(AuthorOrderBy::Newest) => "created_at DESC",
-            Some(AuthorOrderBy::Followers) => "followers DESC",
-            Some(AuthorOrderBy::Publications) => "publications DESC",
-            _ => "created_at ASC",
-        };
+        let offset = query.offset().unwrap_or(0);
+        let limit = query
+            .limit()
+            .or_else(|| counts.map(|(total, _)| total))
+            .unwrap_or(PAGINATION_LIMIT);
 
         let rows = self
             .client
@@ -142,10 +132,13 @@ impl AuthorRepository for PostgresAuthorRepository {
                 &format!(
                     "SELECT * FROM users
                     {}
-                    ORDER BY {}
+                    {}
                     OFFSET {}
                     LIMIT {}",
-                    sql, order_by, offset, limit,
+                    sql.where_clause(),
+                    sql.order_by(),
+                    offset,
+                    limit,
                 ) as &str,
                 &params,
             )
@@ -157,13 +150,20 @@ impl AuthorRepository for PostgresAuthorRepository {
             authors.push(Author::from_row(row)?);
         }
 
-        Ok(
-            Pagination::new(offset, limit, total as usize, matching_criteria as usize)
-                .add_items(authors),
-        )
+        let next_cursor = query.next_cursor(&authors);
+        let pagination = match counts {
+            Some((total, matching_criteria)) => {
+                Pagination::new(offset, limit, total, matching_criteria)
+            }
+            None => Pagination::uncounted(offset, limit),
+        };
+
+        Ok(pagination.add_items(authors).set_next_cursor(next_cursor))
     }
 
     async fn save(&self, author: &mut Author) -> Result<()> {
+        let events = PostgresOutbox::records(&author.events().to_vec()?)?;
+
         self.client
             .query_one(
                 "SELECT * FROM users WHERE id = $1",
@@ -174,21 +174,27 @@ impl AuthorRepository for PostgresAuthorRepository {
 
         self.client
             .execute(
-                "UPDATE users
-                SET
-                    followers = $2,
-                    publications = $3
-                WHERE
-                    id = $1",
+                &PostgresOutbox::enlist(
+                    "UPDATE users
+                    SET
+                        followers = $2,
+                        publications = $3
+                    WHERE
+                        id = $1",
+                    4,
+                ) as &str,
                 &[
                     &author.base().id().to_uuid()?,
                     &(author.followers() as i32),
                     &(author.publications() as i32),
+                    &events,
                 ],
             )
             .await
             .map_err(|err| Error::new("author", "update").wrap_raw(err))?;
 
+        author.events_mut().clear();
+
         Ok(())
     }
 
@@ -1,11 +1,10 @@
-use std::sync::Arc;
-
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use tokio_postgres::row::Row;
-use tokio_postgres::Client;
 
 use common::error::Error;
+use common::infrastructure::event::PostgresOutbox;
+use common::infrastructure::postgres::PostgresClient;
 use common::model::AggregateRoot;
 use common::result::Result;
 
@@ -28,11 +27,11 @@ impl Category {
 }
 
 pub struct PostgresCategoryRepository {
-    client: Arc<Client>,
+    client: PostgresClient,
 }
 
 impl PostgresCategoryRepository {
-    pub fn new(client: Arc<Client>) -> Self {
+    pub fn new(client: PostgresClient) -> Self {
         PostgresCategoryRepository { client }
     }
 }
@@ -65,7 +64,27 @@ impl CategoryRepository for PostgresCategoryRepository {
         Category::from_row(row)
     }
 
+    async fn find_by_ids(&self, ids: &[CategoryId]) -> Result<Vec<Category>> {
+        let ids: Vec<&str> = ids.iter().map(|id| id.value()).collect();
+
+        let rows = self
+            .client
+            .query("SELECT * FROM categories WHERE id = ANY($1)", &[&ids])
+            .await
+            .map_err(|err| Error::not_found("category").wrap_raw(err))?;
+
+        let mut categories = Vec::new();
+
+        for row in rows.into_iter() {
+            categories.push(Category::from_row(row)?);
+        }
+
+        Ok(categories)
+    }
+
     async fn save(&self, category: &mut Category) -> Result<()> {
+        let events = PostgresOutbox::records(&category.events().to_vec()?)?;
+
         let create = self
             .client
             .query_one(
@@ -78,12 +97,16 @@ impl CategoryRepository for PostgresCategoryRepository {
         if create {
             self.client
                 .execute(
-                    "INSERT INTO categories(id, name, created_at)
-                    VALUES($1, $2, $3)",
+                    &PostgresOutbox::enlist(
+                        "INSERT INTO categories(id, name, created_at)
+                        VALUES($1, $2, $3)",
+                        4,
+                    ) as &str,
                     &[
                         &category.base().id().value(),
                         &category.name().value(),
                         &category.base().created_at(),
+                        &events,
                     ],
                 )
                 .await
@@ -91,24 +114,30 @@ impl CategoryRepository for PostgresCategoryRepository {
         } else {
             self.client
                 .execute(
-                    "UPDATE categories
-                    SET
-                        name = $2,
-                        updated_at = $3,
-                        deleted_at = $4
-                    WHERE
-                        id = $1",
+                    &PostgresOutbox::enlist(
+                        "UPDATE categories
+                        SET
+                            name = $2,
+                            updated_at = $3,
+                            deleted_at = $4
+                        WHERE
+                            id = $1",
+                        5,
+                    ) as &str,
                     &[
                         &category.base().id().value(),
                         &category.name().value(),
                         &category.base().updated_at(),
                         &category.base().deleted_at(),
+                        &events,
                     ],
                 )
                 .await
                 .map_err(|err| Error::new("category", "update").wrap_raw(err))?;
         }
 
+        category.events_mut().clear();
+
         Ok(())
     }
 
@@ -1,22 +1,38 @@
-use std::sync::Arc;
-
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use tokio_postgres::row::Row;
-use tokio_postgres::Client;
 use uuid::Uuid;
 
 use common::error::Error;
+use common::infrastructure::event::PostgresOutbox;
+use common::infrastructure::postgres::PostgresClient;
 use common::model::{AggregateRoot, Pagination};
+use common::request::PAGINATION_LIMIT;
 use common::result::Result;
-use common::sql::where_builder::WhereBuilder;
+use common::retention::Purge;
+use common::sql::{Column, Criteria, Field, Query};
 
 use crate::domain::author::AuthorId;
 use crate::domain::category::CategoryId;
 use crate::domain::collection::{
-    Collection, CollectionId, CollectionOrderBy, CollectionRepository, Item,
+    Collection, CollectionField, CollectionId, CollectionRepository, Item,
 };
-use crate::domain::publication::{Header, Image, Name, PublicationId, Synopsis, Tag};
+use crate::domain::publication::{Header, Image, Name, Synopsis, Tag};
+
+impl Field for CollectionField {
+    fn column(&self) -> Column {
+        match self {
+            CollectionField::Id => Column::Expr("id"),
+            CollectionField::AuthorId => Column::Expr("author_id"),
+            CollectionField::CategoryId => Column::Expr("category_id"),
+            CollectionField::PublicationId => Column::JsonArray("items", &["publication_id", "id"]),
+            CollectionField::Tag => Column::JsonArray("tags", &["slug"]),
+            CollectionField::Name => Column::Expr("name"),
+            CollectionField::CreatedAt => Column::Expr("created_at"),
+            CollectionField::DeletedAt => Column::Expr("deleted_at"),
+        }
+    }
+}
 
 impl Collection {
     fn from_row(row: Row) -> Result<Self> {
@@ -56,113 +72,71 @@ impl Collection {
 }
 
 pub struct PostgresCollectionRepository {
-    client: Arc<Client>,
+    client: PostgresClient,
 }
 
 impl PostgresCollectionRepository {
-    pub fn new(client: Arc<Client>) -> Self {
+    pub fn new(client: PostgresClient) -> Self {
         PostgresCollectionRepository { client }
     }
-}
 
-#[async_trait]
-impl CollectionRepository for PostgresCollectionRepository {
-    async fn find_by_id(&self, id: &CollectionId) -> Result<Collection> {
-        let row = self
-            .client
-            .query_one(
-                "SELECT * FROM collections
-                WHERE id = $1",
-                &[&id.to_uuid()?],
-            )
-            .await
-            .map_err(|err| Error::not_found("collection").wrap_raw(err))?;
-
-        Collection::from_row(row)
-    }
-
-    // SELECT *
-    // FROM mytable
-    // WHERE EXISTS (
-    //     SELECT TRUE
-    //     FROM jsonb_array_elements(data->'tags') x
-    //     WHERE x->>'name' IN ('tag2', 'tag3')
-    // )
-    async fn search(
+    /// Searches the active or the deleted collections.
+    async fn search_by_state(
         &self,
-        author_id: Option<&AuthorId>,
-        category_id: Option<&CategoryId>,
-        publication_id: Option<&PublicationId>,
-        tag: Option<&Tag>,
-        name: Option<&String>,
-        from: Option<&DateTime<Utc>>,
-        to: Option<&DateTime<Utc>>,
-        offset: Option<usize>,
-        limit: Option<usize>,
-        order_by: Option<&CollectionOrderBy>,
+        query: &Query<CollectionField>,
+        deleted: bool,
     ) -> Result<Pagination<Collection>> {
-        let author_id = author_id.map(|id| id.to_uuid()).transpose()?;
-        let category_id = category_id.map(|id| id.value());
-        let publication_id = publication_id.map(|id| id.value());
-        let tag = tag.map(|t| t.slug());
-
-        let (sql, params) = WhereBuilder::new()
-            .add_param_opt("author_id = $$", &author_id, author_id.is_some())
-            .add_param_opt("category_id = $$", &category_id, category_id.is_some())
-            .add_param_opt(
-                "to_json(array(select jsonb_array_elements(items)->'publication_id'->>'id'))::jsonb
-                    ?| array[$$]",
-                &publication_id,
-                publication_id.is_some(),
-            )
-            .add_param_opt(
-                "EXISTS (
-                    SELECT TRUE
-                    FROM jsonb_array_elements(tags) tag
-                    WHERE tag->>'slug' = $$
-                )",
-                &tag,
-                tag.is_some(),
-            )
-            .add_param_opt(
-                "LOWER(name) LIKE '%' || LOWER($$) || '%'",
-                &name,
-                name.is_some(),
-            )
-            .add_param_opt("created_at >= $$", &from, from.is_some())
-            .add_param_opt("created_at <= $$", &to, to.is_some())
-            .build();
+        let deleted_at = Criteria::is_null(CollectionField::DeletedAt);
+        let query = query
+            .clone()
+            .filter(if deleted { !deleted_at } else { deleted_at });
+        let state = if deleted { "NOT NULL" } else { "NULL" };
 
-        // Total
-        let row = self
-            .client
-            .query_one(&format!("SELECT COUNT(*) FROM collections") as &str, &[])
-            .await
-            .map_err(|err| Error::new("collection", "total").wrap_raw(err))?;
-        let total: i64 = row.get(0);
+        let sql = query.to_sql();
+        let params = sql.params();
 
-        // Matching criteria
-        let row = self
-            .client
-            .query_one(
-                &format!(
-                    "SELECT COUNT(*) FROM collections
-                    {}",
-                    sql,
-                ) as &str,
-                &params,
-            )
-            .await
-            .map_err(|err| Error::new("collection", "matching_criteria").wrap_raw(err))?;
-        let matching_criteria: i64 = row.get(0);
+        // Pages after a cursor are not counted
+        let mut counts = None;
+        if query.after().is_none() {
+            // Total
+            let row = self
+                .client
+                .query_one(
+                    &format!(
+                        "SELECT COUNT(*) FROM collections
+                        WHERE deleted_at IS {}",
+                        state,
+                    ) as &str,
+                    &[],
+                )
+                .await
+                .map_err(|err| Error::new("collection", "total").wrap_raw(err))?;
+            let total: i64 = row.get(0);
+
+            // Matching criteria
+            let row = self
+                .client
+                .query_one(
+                    &format!(
+                        "SELECT COUNT(*) FROM collections
+                        {}",
+                        sql.where_clause(),
+                    ) as &str,
+                    &params,
+                )
+                .await
+                .map_err(|err| Error::new("collection", "matching_criteria").wrap_raw(err))?;
+            let matching_criteria: i64 = row.get(0);
+
+            counts = Some((total as usize, matching_criteria as usize));
+        }
 
         // Query
-        let offset = offset.unwrap_or_else(|| 0);
-        let limit = limit.unwrap_or_else(|| total as usize);
-        let order_by = match order_by {
-            Some(CollectionOrderBy::Newest) => "created_at DESC",
-            _ => "created_at ASC",
-        };
+        let offset = query.offset().unwrap_or(0);
+        let limit = query
+            .limit()
+            .or_else(|| counts.map(|(total, _)| total))
+            .unwrap_or(PAGINATION_LIMIT);
 
         let rows = self
             .client
@@ -170,10 +144,13 @@ impl CollectionRepository for PostgresCollectionRepository {
                 &format!(
                     "SELECT * FROM collections
                     {}
-                    ORDER BY {}
+                    {}
                     OFFSET {}
                     LIMIT {}",
-                    sql, order_by, offset, limit,
+                    sql.where_clause(),
+                    sql.order_by(),
+                    offset,
+                    limit,
                 ) as &str,
                 &params,
             )
@@ -185,13 +162,73 @@ impl CollectionRepository for PostgresCollectionRepository {
             collections.push(Collection::from_row(row)?);
         }
 
-        Ok(
-            Pagination::new(offset, limit, total as usize, matching_criteria as usize)
-                .add_items(collections),
-        )
+        let next_cursor = query.next_cursor(&collections);
+        let pagination = match counts {
+            Some((total, matching_criteria)) => {
+                Pagination::new(offset, limit, total, matching_criteria)
+            }
+            None => Pagination::uncounted(offset, limit),
+        };
+
+        Ok(pagination
+            .add_items(collections)
+            .set_next_cursor(next_cursor))
+    }
+}
+
+#[async_trait]
+impl CollectionRepository for PostgresCollectionRepository {
+    async fn find_by_id(&self, id: &CollectionId) -> Result<Collection> {
+        let row = self
+            .client
+            .query_one(
+                "SELECT * FROM collections
+                WHERE id = $1
+                AND deleted_at IS NULL",
+                &[&id.to_uuid()?],
+            )
+            .await
+            .map_err(|err| Error::not_found("collection").wrap_raw(err))?;
+
+        Collection::from_row(row)
+    }
+
+    async fn find_deleted_by_id(&self, id: &CollectionId) -> Result<Collection> {
+        let row = self
+            .client
+            .query_one(
+                "SELECT * FROM collections
+                WHERE id = $1
+                AND deleted_at IS NOT NULL",
+                &[&id.to_uuid()?],
+            )
+            .await
+            .map_err(|err| Error::not_found("collection").wrap_raw(err))?;
+
+        Collection::from_row(row)
+    }
+
+    // SELECT *
+    // FROM mytable
+    // WHERE EXISTS (
+    //     SELECT TRUE
+    //     FROM jsonb_array_elements(data->'tags') x
+    //     WHERE x->>'name' IN ('tag2', 'tag3')
+    // )
+    async fn search(&self, query: &Query<CollectionField>) -> Result<Pagination<Collection>> {
+        self.search_by_state(query, false).await
+    }
+
+    async fn search_deleted(
+        &self,
+        query: &Query<CollectionField>,
+    ) -> Result<Pagination<Collection>> {
+        self.search_by_state(query, true).await
     }
 
     async fn save(&self, collection: &mut Collection) -> Result<()> {
+        let events = PostgresOutbox::records(&collection.events().to_vec()?)?;
+
         let create = self
             .client
             .query_one(
@@ -207,17 +244,20 @@ impl CollectionRepository for PostgresCollectionRepository {
         if create {
             self.client
                 .execute(
-                    "INSERT INTO collections(
-                        id,
-                        author_id,
-                        name,
-                        synopsis,
-                        category_id,
-                        tags,
-                        cover,
-                        items,
-                        created_at
-                    ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+                    &PostgresOutbox::enlist(
+                        "INSERT INTO collections(
+                            id,
+                            author_id,
+                            name,
+                            synopsis,
+                            category_id,
+                            tags,
+                            cover,
+                            items,
+                            created_at
+                        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+                        10,
+                    ) as &str,
                     &[
                         &collection.base().id().to_uuid()?,
                         &collection.author_id().to_uuid()?,
@@ -228,6 +268,7 @@ impl CollectionRepository for PostgresCollectionRepository {
                         &collection.header().cover().url(),
                         &items,
                         &collection.base().created_at(),
+                        &events,
                     ],
                 )
                 .await
@@ -235,18 +276,21 @@ impl CollectionRepository for PostgresCollectionRepository {
         } else {
             self.client
                 .execute(
-                    "UPDATE collections
-                    SET
-                        name = $2,
-                        synopsis = $3,
-                        category_id = $4,
-                        tags = $5,
-                        cover = $6,
-                        items = $7,
-                        updated_at = $8,
-                        deleted_at = $9
-                    WHERE
-                        id = $1",
+                    &PostgresOutbox::enlist(
+                        "UPDATE collections
+                        SET
+                            name = $2,
+                            synopsis = $3,
+                            category_id = $4,
+                            tags = $5,
+                            cover = $6,
+                            items = $7,
+                            updated_at = $8,
+                            deleted_at = $9
+                        WHERE
+                            id = $1",
+                        10,
+                    ) as &str,
                     &[
                         &collection.base().id().to_uuid()?,
                         &collection.header().name().value(),
@@ -257,12 +301,15 @@ impl CollectionRepository for PostgresCollectionRepository {
                         &items,
                         &collection.base().updated_at(),
                         &collection.base().deleted_at(),
+                        &events,
                     ],
                 )
                 .await
                 .map_err(|err| Error::new("collection", "update").wrap_raw(err))?;
         }
 
+        collection.events_mut().clear();
+
         Ok(())
     }
 
@@ -279,3 +326,20 @@ impl CollectionRepository for PostgresCollectionRepository {
         Ok(())
     }
 }
+
+#[async_trait]
+impl Purge for PostgresCollectionRepository {
+    async fn purge(&self, deleted_before: &DateTime<Utc>) -> Result<usize> {
+        let purged = self
+            .client
+            .execute(
+                "DELETE FROM collections
+                WHERE deleted_at < $1",
+                &[deleted_before],
+            )
+            .await
+            .map_err(|err| Error::new("collection", "purge").wrap_raw(err))?;
+
+        Ok(purged as usize)
+    }
+}
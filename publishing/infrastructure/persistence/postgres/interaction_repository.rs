@@ -1,12 +1,10 @@
-use std::sync::Arc;
-
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 
-use tokio_postgres::Client;
 use uuid::Uuid;
 
 use common::error::Error;
+use common::infrastructure::postgres::PostgresClient;
 use common::model::AggregateRoot;
 use common::result::Result;
 use common::sql::where_builder::WhereBuilder;
@@ -21,11 +19,11 @@ use crate::domain::publication::PublicationId;
 use crate::domain::reader::ReaderId;
 
 pub struct PostgresInteractionRepository {
-    client: Arc<Client>,
+    client: PostgresClient,
 }
 
 impl PostgresInteractionRepository {
-    pub fn new(client: Arc<Client>) -> Self {
+    pub fn new(client: PostgresClient) -> Self {
         PostgresInteractionRepository { client }
     }
 }
@@ -1,24 +1,43 @@
-use std::sync::Arc;
-
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use tokio_postgres::row::Row;
-use tokio_postgres::Client;
 use uuid::Uuid;
 
 use common::error::Error;
+use common::infrastructure::event::PostgresOutbox;
+use common::infrastructure::postgres::PostgresClient;
 use common::model::{AggregateRoot, Pagination, StatusHistory, StatusItem};
+use common::request::PAGINATION_LIMIT;
 use common::result::Result;
-use common::sql::where_builder::WhereBuilder;
+use common::retention::Purge;
+use common::sql::{Column, Criteria, Field, Query};
 
 use crate::domain::author::AuthorId;
 use crate::domain::category::CategoryId;
 
 use crate::domain::publication::{
-    Header, Image, Name, Page, Publication, PublicationId, PublicationOrderBy,
-    PublicationRepository, Statistics, Status, Synopsis, Tag,
+    Header, Image, Name, Page, Publication, PublicationField, PublicationId, PublicationRepository,
+    Statistics, Status, Synopsis, Tag,
 };
 
+impl Field for PublicationField {
+    fn column(&self) -> Column {
+        match self {
+            PublicationField::Id => Column::Expr("id"),
+            PublicationField::AuthorId => Column::Expr("author_id"),
+            PublicationField::CategoryId => Column::Expr("category_id"),
+            PublicationField::Tag => Column::JsonArray("tags", &["slug"]),
+            PublicationField::Status => Column::Expr("status_history->-1->>'status'"),
+            PublicationField::Name => Column::Expr("name"),
+            PublicationField::CreatedAt => Column::Expr("created_at"),
+            PublicationField::Views => Column::Expr("(statistics->>'views')::BIGINT"),
+            PublicationField::Likes => Column::Expr("(statistics->>'likes')::BIGINT"),
+            PublicationField::Stars => Column::Expr("(statistics->>'stars')::FLOAT8"),
+            PublicationField::DeletedAt => Column::Expr("deleted_at"),
+        }
+    }
+}
+
 impl Publication {
     fn from_row(row: Row) -> Result<Self> {
         let id: Uuid = row.get("id");
@@ -67,108 +86,71 @@ impl Publication {
 }
 
 pub struct PostgresPublicationRepository {
-    client: Arc<Client>,
+    client: PostgresClient,
 }
 
 impl PostgresPublicationRepository {
-    pub fn new(client: Arc<Client>) -> Self {
+    pub fn new(client: PostgresClient) -> Self {
         PostgresPublicationRepository { client }
     }
-}
-
-#[async_trait]
-impl PublicationRepository for PostgresPublicationRepository {
-    async fn find_by_id(&self, id: &PublicationId) -> Result<Publication> {
-        let row = self
-            .client
-            .query_one(
-                "SELECT * FROM publications
-                WHERE id = $1",
-                &[&id.to_uuid()?],
-            )
-            .await
-            .map_err(|err| Error::not_found("publication").wrap_raw(err))?;
-
-        Publication::from_row(row)
-    }
 
-    async fn search(
+    /// Searches the active or the deleted publications.
+    async fn search_by_state(
         &self,
-        author_id: Option<&AuthorId>,
-        category_id: Option<&CategoryId>,
-        tag: Option<&Tag>,
-        status: Option<&Status>,
-        name: Option<&String>,
-        from: Option<&DateTime<Utc>>,
-        to: Option<&DateTime<Utc>>,
-        offset: Option<usize>,
-        limit: Option<usize>,
-        order_by: Option<&PublicationOrderBy>,
+        query: &Query<PublicationField>,
+        deleted: bool,
     ) -> Result<Pagination<Publication>> {
-        let author_id = author_id.map(|id| id.to_uuid()).transpose()?;
-        let category_id = category_id.map(|id| id.value());
-        let tag = tag.map(|t| t.slug());
-        let status = status.map(|s| s.to_string());
-
-        let (sql, params) = WhereBuilder::new()
-            .add_param_opt("author_id = $$", &author_id, author_id.is_some())
-            .add_param_opt("category_id = $$", &category_id, category_id.is_some())
-            .add_param_opt(
-                "EXISTS (
-                    SELECT TRUE
-                    FROM jsonb_array_elements(tags) tag
-                    WHERE tag->>'slug' = $$
-                )",
-                &tag,
-                tag.is_some(),
-            )
-            .add_param_opt(
-                "status_history->-1->>'status' = $$",
-                &status,
-                status.is_some(),
-            )
-            .add_param_opt(
-                "LOWER(name) LIKE '%' || LOWER($$) || '%'",
-                &name,
-                name.is_some(),
-            )
-            .add_param_opt("created_at >= $$", &from, from.is_some())
-            .add_param_opt("created_at <= $$", &to, to.is_some())
-            .build();
+        let deleted_at = Criteria::is_null(PublicationField::DeletedAt);
+        let query = query
+            .clone()
+            .filter(if deleted { !deleted_at } else { deleted_at });
+        let state = if deleted { "NOT NULL" } else { "NULL" };
 
-        // Total
-        let row = self
-            .client
-            .query_one(&format!("SELECT COUNT(*) FROM publications") as &str, &[])
-            .await
-            .map_err(|err| Error::new("publication", "total").wrap_raw(err))?;
-        let total: i64 = row.get(0);
+        let sql = query.to_sql();
+        let params = sql.params();
 
-        // Matching criteria
-        let row = self
-            .client
-            .query_one(
-                &format!(
-                    "SELECT COUNT(*) FROM publications
-                    {}",
-                    sql,
-                ) as &str,
-                &params,
-            )
-            .await
-            .map_err(|err| Error::new("publication", "matching_criteria").wrap_raw(err))?;
-        let matching_criteria: i64 = row.get(0);
+        // Pages after a cursor are not counted
+        let mut counts = None;
+        if query.after().is_none() {
+            // Total
+            let row = self
+                .client
+                .query_one(
+                    &format!(
+                        "SELECT COUNT(*) FROM publications
+                        WHERE deleted_at IS {}",
+                        state,
+                    ) as &str,
+                    &[],
+                )
+                .await
+                .map_err(|err| Error::new("publication", "total").wrap_raw(err))?;
+            let total: i64 = row.get(0);
+
+            // Matching criteria
+            let row = self
+                .client
+                .query_one(
+                    &format!(
+                        "SELECT COUNT(*) FROM publications
+                        {}",
+                        sql.where_clause(),
+                    ) as &str,
+                    &params,
+                )
+                .await
+                .map_err(|err| Error::new("publication", "matching_criteria").wrap_raw(err))?;
+            let matching_criteria: i64 = row.get(0);
+
+            counts = Some((total as usize, matching_criteria as usize));
+        }
 
         // Query
-        let offset = offset.unwrap_or_else(|| 0);
-        let limit = limit.unwrap_or_else(|| total as usize);
-        let order_by = match order_by {
-            Some(PublicationOrderBy::Newest) => "created_at DESC",
-            Some(PublicationOrderBy::MostViewed) => "statistics->'views' DESC",
-            Some(PublicationOrderBy::MostLiked) => "statistics->'likes' DESC",
-            Some(PublicationOrderBy::BestReviews) => "statistics->'stars' DESC",
-            _ => "created_at ASC",
-        };
+        let offset = query.offset().unwrap_or(0);
+        let limit = query
+            .limit()
+            .or_else(|| counts.map(|(total, _)| total))
+            .unwrap_or(PAGINATION_LIMIT);
 
         let rows = self
             .client
@@ -176,10 +158,13 @@ impl PublicationRepository for PostgresPublicationRepository {
                 &format!(
                     "SELECT * FROM publications
                     {}
-                    ORDER BY {}
+                    {}
                     OFFSET {}
                     LIMIT {}",
-                    sql, order_by, offset, limit,
+                    sql.where_clause(),
+                    sql.order_by(),
+                    offset,
+                    limit,
                 ) as &str,
                 &params,
             )
@@ -191,13 +176,66 @@ impl PublicationRepository for PostgresPublicationRepository {
             publications.push(Publication::from_row(row)?);
         }
 
-        Ok(
-            Pagination::new(offset, limit, total as usize, matching_criteria as usize)
-                .add_items(publications),
-        )
+        let next_cursor = query.next_cursor(&publications);
+        let pagination = match counts {
+            Some((total, matching_criteria)) => {
+                Pagination::new(offset, limit, total, matching_criteria)
+            }
+            None => Pagination::uncounted(offset, limit),
+        };
+
+        Ok(pagination
+            .add_items(publications)
+            .set_next_cursor(next_cursor))
+    }
+}
+
+#[async_trait]
+impl PublicationRepository for PostgresPublicationRepository {
+    async fn find_by_id(&self, id: &PublicationId) -> Result<Publication> {
+        let row = self
+            .client
+            .query_one(
+                "SELECT * FROM publications
+                WHERE id = $1
+                AND deleted_at IS NULL",
+                &[&id.to_uuid()?],
+            )
+            .await
+            .map_err(|err| Error::not_found("publication").wrap_raw(err))?;
+
+        Publication::from_row(row)
+    }
+
+    async fn find_deleted_by_id(&self, id: &PublicationId) -> Result<Publication> {
+        let row = self
+            .client
+            .query_one(
+                "SELECT * FROM publications
+                WHERE id = $1
+                AND deleted_at IS NOT NULL",
+                &[&id.to_uuid()?],
+            )
+            .await
+            .map_err(|err| Error::not_found("publication").wrap_raw(err))?;
+
+        Publication::from_row(row)
+    }
+
+    async fn search(&self, query: &Query<PublicationField>) -> Result<Pagination<Publication>> {
+        self.search_by_state(query, false).await
+    }
+
+    async fn search_deleted(
+        &self,
+        query: &Query<PublicationField>,
+    ) -> Result<Pagination<Publication>> {
+        self.search_by_state(query, true).await
     }
 
     async fn save(&self, publication: &mut Publication) -> Result<()> {
+        let events = PostgresOutbox::records(&publication.events().to_vec()?)?;
+
         let create = self
             .client
             .query_one(
@@ -215,20 +253,23 @@ impl PublicationRepository for PostgresPublicationRepository {
         if create {
             self.client
                 .execute(
-                    "INSERT INTO publications(
-                        id,
-                        author_id,
-                        name,
-                        synopsis,
-                        category_id,
-                        tags,
-                        cover,
-                        contract,
-                        statistics,
-                        pages,
-                        status_history,
-                        created_at
-                    ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+                    &PostgresOutbox::enlist(
+                        "INSERT INTO publications(
+                            id,
+                            author_id,
+                            name,
+                            synopsis,
+                            category_id,
+                            tags,
+                            cover,
+                            contract,
+                            statistics,
+                            pages,
+                            status_history,
+                            created_at
+                        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+                        13,
+                    ) as &str,
                     &[
                         &publication.base().id().to_uuid()?,
                         &publication.author_id().to_uuid()?,
@@ -242,6 +283,7 @@ impl PublicationRepository for PostgresPublicationRepository {
                         &pages,
                         &status_history,
                         &publication.base().created_at(),
+                        &events,
                     ],
                 )
                 .await
@@ -249,21 +291,24 @@ impl PublicationRepository for PostgresPublicationRepository {
         } else {
             self.client
                 .execute(
-                    "UPDATE publications
-                    SET
-                        name = $2,
-                        synopsis = $3,
-                        category_id = $4,
-                        tags = $5,
-                        cover = $6,
-                        contract = $7,
-                        statistics = $8,
-                        pages = $9,
-                        status_history = $10,
-                        updated_at = $11,
-                        deleted_at = $12
-                    WHERE
-                        id = $1",
+                    &PostgresOutbox::enlist(
+                        "UPDATE publications
+                        SET
+                            name = $2,
+                            synopsis = $3,
+                            category_id = $4,
+                            tags = $5,
+                            cover = $6,
+                            contract = $7,
+                            statistics = $8,
+                            pages = $9,
+                            status_history = $10,
+                            updated_at = $11,
+                            deleted_at = $12
+                        WHERE
+                            id = $1",
+                        13,
+                    ) as &str,
                     &[
                         &publication.base().id().to_uuid()?,
                         &publication.header().name().value(),
@@ -277,12 +322,15 @@ impl PublicationRepository for PostgresPublicationRepository {
                         &status_history,
                         &publication.base().updated_at(),
                         &publication.base().deleted_at(),
+                        &events,
                     ],
                 )
                 .await
                 .map_err(|err| Error::new("publication", "update").wrap_raw(err))?;
         }
 
+        publication.events_mut().clear();
+
         Ok(())
     }
 
@@ -299,3 +347,20 @@ impl PublicationRepository for PostgresPublicationRepository {
         Ok(())
     }
 }
+
+#[async_trait]
+impl Purge for PostgresPublicationRepository {
+    async fn purge(&self, deleted_before: &DateTime<Utc>) -> Result<usize> {
+        let purged = self
+            .client
+            .execute(
+                "DELETE FROM publications
+                WHERE deleted_at < $1",
+                &[deleted_before],
+            )
+            .await
+            .map_err(|err| Error::new("publication", "purge").wrap_raw(err))?;
+
+        Ok(purged as usize)
+    }
+}
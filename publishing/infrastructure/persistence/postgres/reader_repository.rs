@@ -1,12 +1,11 @@
-use std::sync::Arc;
-
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use tokio_postgres::row::Row;
-use tokio_postgres::Client;
 use uuid::Uuid;
 
 use common::error::Error;
+use common::infrastructure::event::PostgresOutbox;
+use common::infrastructure::postgres::PostgresClient;
 use common::model::AggregateRoot;
 use common::result::Result;
 
@@ -45,11 +44,11 @@ impl Reader {
 }
 
 pub struct PostgresReaderRepository {
-    client: Arc<Client>,
+    client: PostgresClient,
 }
 
 impl PostgresReaderRepository {
-    pub fn new(client: Arc<Client>) -> Self {
+    pub fn new(client: PostgresClient) -> Self {
         PostgresReaderRepository { client }
     }
 }
@@ -66,7 +65,30 @@ impl ReaderRepository for PostgresReaderRepository {
         Reader::from_row(row)
     }
 
+    async fn find_by_ids(&self, ids: &[ReaderId]) -> Result<Vec<Reader>> {
+        let ids = ids
+            .iter()
+            .map(|id| id.to_uuid())
+            .collect::<Result<Vec<Uuid>>>()?;
+
+        let rows = self
+            .client
+            .query("SELECT * FROM users WHERE id = ANY($1)", &[&ids])
+            .await
+            .map_err(|err| Error::not_found("reader").wrap_raw(err))?;
+
+        let mut readers = Vec::new();
+
+        for row in rows.into_iter() {
+            readers.push(Reader::from_row(row)?);
+        }
+
+        Ok(readers)
+    }
+
     async fn save(&self, reader: &mut Reader) -> Result<()> {
+        let events = PostgresOutbox::records(&reader.events().to_vec()?)?;
+
         self.client
             .query_one(
                 "SELECT * FROM users WHERE id = $1",
@@ -77,16 +99,25 @@ impl ReaderRepository for PostgresReaderRepository {
 
         self.client
             .execute(
-                "UPDATE users
-                SET
-                    subscribed = $2
-                WHERE
-                    id = $1",
-                &[&reader.base().id().to_uuid()?, &reader.is_subscribed()],
+                &PostgresOutbox::enlist(
+                    "UPDATE users
+                    SET
+                        subscribed = $2
+                    WHERE
+                        id = $1",
+                    3,
+                ) as &str,
+                &[
+                    &reader.base().id().to_uuid()?,
+                    &reader.is_subscribed(),
+                    &events,
+                ],
             )
             .await
             .map_err(|err| Error::new("reader", "update").wrap_raw(err))?;
 
+        reader.events_mut().clear();
+
         Ok(())
     }
 
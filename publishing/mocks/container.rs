@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use common::infrastructure::transaction::InMemUnitOfWork;
 use common::mocks::FakeEventPublisher;
 
 use identity::infrastructure::persistence::inmem::InMemUserRepository;
@@ -21,5 +22,6 @@ pub fn container() -> PublishingContainer<FakeEventPublisher> {
         Arc::new(InMemPublicationRepository::new()),
         Arc::new(InMemReaderRepository::new()),
         Arc::new(InMemUserRepository::new()),
+        Arc::new(InMemUnitOfWork::new()),
     )
 }
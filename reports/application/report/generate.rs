@@ -6,14 +6,15 @@ use serde::Deserialize;
 
 use common::error::Error;
 use common::result::Result;
-use identity::domain::user::UserRepository;
+use common::sql::{Criteria, Query};
+use identity::domain::user::{UserField, UserRepository};
 use identity::UserIdAndRole;
 use payment::domain::contract::ContractRepository;
-use payment::domain::donation::DonationRepository;
-use payment::domain::subscription::SubscriptionRepository;
+use payment::domain::donation::{DonationField, DonationRepository};
+use payment::domain::subscription::{SubscriptionField, SubscriptionRepository};
 use publishing::domain::author::AuthorRepository;
 use publishing::domain::category::CategoryRepository;
-use publishing::domain::publication::PublicationRepository;
+use publishing::domain::publication::{PublicationField, PublicationRepository};
 
 use crate::domain::report::Report;
 
@@ -70,45 +71,29 @@ impl<'a> Generate<'a> {
 
         let p_users = self
             .user_repo
-            .search(
-                None,
-                None,
+            .search(&Query::new().filter(Criteria::range(
+                UserField::CreatedAt,
                 Some(&date_from),
                 Some(&date_to),
-                None,
-                None,
-                None,
-            )
+            )))
             .await?;
 
         let p_publications = self
             .publication_repo
-            .search(
-                None,
-                None,
-                None,
-                None,
-                None,
+            .search(&Query::new().filter(Criteria::range(
+                PublicationField::CreatedAt,
                 Some(&date_from),
                 Some(&date_to),
-                None,
-                None,
-                None,
-            )
+            )))
             .await?;
 
         let p_subscriptions = self
             .subscription_repo
-            .search(
-                None,
-                None,
-                None,
+            .search(&Query::new().filter(Criteria::range(
+                SubscriptionField::CreatedAt,
                 Some(&date_from),
                 Some(&date_to),
-                None,
-                None,
-                None,
-            )
+            )))
             .await?;
 
         let p_contracts = self
@@ -126,16 +111,11 @@ impl<'a> Generate<'a> {
 
         let p_donations = self
             .donation_repo
-            .search(
-                None,
-                None,
-                None,
+            .search(&Query::new().filter(Criteria::range(
+                DonationField::CreatedAt,
                 Some(&date_from),
                 Some(&date_to),
-                None,
-                None,
-                None,
-            )
+            )))
             .await?;
 
         let mut report = Report::new(date_from, date_to)?;
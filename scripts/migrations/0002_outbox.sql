@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS outbox (
+  sequence BIGSERIAL UNIQUE,
+  id UUID PRIMARY KEY,
+
+  topic VARCHAR(255) NOT NULL,
+  code VARCHAR(255) NOT NULL,
+  timestamp TIMESTAMP WITH TIME ZONE NOT NULL,
+
+  payload JSONB NOT NULL,
+
+  published_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS outbox_pending_idx ON outbox(sequence) WHERE published_at IS NULL;
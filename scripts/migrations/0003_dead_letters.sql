@@ -0,0 +1,23 @@
+CREATE TABLE IF NOT EXISTS dead_letters (
+  id UUID PRIMARY KEY,
+
+  event_id UUID NOT NULL,
+  topic VARCHAR(255) NOT NULL,
+  code VARCHAR(255) NOT NULL,
+  timestamp TIMESTAMP WITH TIME ZONE NOT NULL,
+  payload JSONB NOT NULL,
+
+  handler_topic VARCHAR(255) NOT NULL,
+  errors JSONB NOT NULL,
+  attempts INTEGER NOT NULL,
+
+  failed_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+INSERT INTO permissions(id, name)
+VALUES
+  ('manage_events', 'Gestionar eventos');
+
+UPDATE roles
+SET permissions = permissions || '[{ "id": "manage_events", "name": "Gestionar eventos" }]'::jsonb
+WHERE id = 'admin';
@@ -0,0 +1,16 @@
+ALTER TABLE events
+  ADD COLUMN IF NOT EXISTS correlation_id VARCHAR(255),
+  ADD COLUMN IF NOT EXISTS causation_id VARCHAR(255),
+  ADD COLUMN IF NOT EXISTS actor_id VARCHAR(255);
+
+ALTER TABLE outbox
+  ADD COLUMN IF NOT EXISTS correlation_id VARCHAR(255),
+  ADD COLUMN IF NOT EXISTS causation_id VARCHAR(255),
+  ADD COLUMN IF NOT EXISTS actor_id VARCHAR(255);
+
+ALTER TABLE dead_letters
+  ADD COLUMN IF NOT EXISTS correlation_id VARCHAR(255),
+  ADD COLUMN IF NOT EXISTS causation_id VARCHAR(255),
+  ADD COLUMN IF NOT EXISTS actor_id VARCHAR(255);
+
+CREATE INDEX IF NOT EXISTS events_correlation_id_idx ON events(correlation_id);
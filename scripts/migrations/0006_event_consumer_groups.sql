@@ -0,0 +1,10 @@
+ALTER TABLE events ADD COLUMN IF NOT EXISTS sequence BIGSERIAL;
+CREATE UNIQUE INDEX IF NOT EXISTS events_sequence_idx ON events(sequence);
+
+CREATE TABLE IF NOT EXISTS event_consumer_groups (
+  name VARCHAR(255) PRIMARY KEY,
+
+  last_sequence BIGINT NOT NULL,
+
+  updated_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
@@ -0,0 +1,9 @@
+ALTER TABLE cache ADD COLUMN IF NOT EXISTS namespace VARCHAR(255) NOT NULL DEFAULT '';
+ALTER TABLE cache ADD COLUMN IF NOT EXISTS expires_at TIMESTAMP WITH TIME ZONE;
+
+UPDATE cache SET namespace = 'business_rules' WHERE key = 'business_rules' AND namespace = '';
+
+ALTER TABLE cache DROP CONSTRAINT IF EXISTS cache_pkey;
+ALTER TABLE cache ADD PRIMARY KEY (namespace, key);
+
+CREATE INDEX IF NOT EXISTS cache_expires_at_idx ON cache(expires_at) WHERE expires_at IS NOT NULL;
@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS business_rules_versions (
+  version INTEGER PRIMARY KEY,
+  rules JSONB NOT NULL,
+  author_id VARCHAR(255),
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+  effective_from TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS business_rules_versions_effective_from_idx ON business_rules_versions(effective_from);
+
+-- The rules stored in the cache become the first version, in force since always.
+INSERT INTO business_rules_versions (version, rules, author_id, created_at, effective_from)
+SELECT 1, value, NULL, NOW(), TIMESTAMP WITH TIME ZONE 'epoch'
+FROM cache
+WHERE namespace = 'business_rules' AND key = 'business_rules'
+ON CONFLICT (version) DO NOTHING;
+
+DELETE FROM cache WHERE namespace = 'business_rules';
@@ -0,0 +1,12 @@
+CREATE INDEX IF NOT EXISTS publications_deleted_at_idx ON publications(deleted_at);
+CREATE INDEX IF NOT EXISTS collections_deleted_at_idx ON collections(deleted_at);
+CREATE INDEX IF NOT EXISTS users_deleted_at_idx ON users(deleted_at);
+CREATE INDEX IF NOT EXISTS roles_deleted_at_idx ON roles(deleted_at);
+
+INSERT INTO permissions(id, name)
+VALUES
+  ('restore_deleted', 'Restaurar eliminados');
+
+UPDATE roles
+SET permissions = permissions || '[{ "id": "restore_deleted", "name": "Restaurar eliminados" }]'::jsonb
+WHERE id = 'admin';
@@ -0,0 +1,19 @@
+CREATE TABLE IF NOT EXISTS sessions (
+  id UUID PRIMARY KEY,
+  user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+  data JSONB NOT NULL,
+  access_token_id UUID NOT NULL,
+  refresh_token_hash VARCHAR(64) NOT NULL,
+  previous_refresh_token_hash VARCHAR(64),
+
+  user_agent TEXT,
+  ip TEXT,
+
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+  refreshed_at TIMESTAMP WITH TIME ZONE NOT NULL,
+  expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS sessions_user_id_idx ON sessions(user_id, refreshed_at DESC);
+CREATE INDEX IF NOT EXISTS sessions_expires_at_idx ON sessions(expires_at);
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS external_identities (
+  provider VARCHAR(16) NOT NULL,
+  subject VARCHAR(255) NOT NULL,
+  user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+  email VARCHAR(64),
+
+  linked_at TIMESTAMP WITH TIME ZONE NOT NULL,
+
+  PRIMARY KEY (provider, subject),
+  UNIQUE (user_id, provider)
+);
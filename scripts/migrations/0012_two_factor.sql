@@ -0,0 +1,15 @@
+ALTER TABLE users
+  ADD COLUMN IF NOT EXISTS two_factor_secret VARCHAR(64),
+  ADD COLUMN IF NOT EXISTS two_factor_recovery_codes JSONB,
+  ADD COLUMN IF NOT EXISTS two_factor_last_step BIGINT,
+  ADD COLUMN IF NOT EXISTS two_factor_enabled_at TIMESTAMP WITH TIME ZONE;
+
+ALTER TABLE roles
+  ADD COLUMN IF NOT EXISTS two_factor_required BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Roles that control money and content.
+UPDATE roles
+SET two_factor_required = TRUE
+WHERE permissions @> '[{ "id": "approve_reject_publication" }]'::jsonb
+  OR permissions @> '[{ "id": "generate_backup" }]'::jsonb
+  OR permissions @> '[{ "id": "*" }]'::jsonb;
@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS attempts (
+  key TEXT PRIMARY KEY,
+  count INTEGER NOT NULL,
+  last_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL,
+  expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS attempts_expires_at_idx ON attempts(expires_at);
+
+ALTER TABLE users
+  ADD COLUMN IF NOT EXISTS locked_until TIMESTAMP WITH TIME ZONE;
+
+INSERT INTO permissions(id, name)
+VALUES
+  ('unlock_user', 'Desbloquear usuario')
+ON CONFLICT DO NOTHING;
+
+UPDATE roles
+SET permissions = permissions || '[{ "id": "unlock_user", "name": "Desbloquear usuario" }]'::jsonb
+WHERE id = 'admin'
+  AND NOT permissions @> '[{ "id": "unlock_user" }]'::jsonb;
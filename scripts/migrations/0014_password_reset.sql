@@ -0,0 +1,20 @@
+ALTER TABLE users
+  ADD COLUMN IF NOT EXISTS password_reset_token_hash VARCHAR(64),
+  ADD COLUMN IF NOT EXISTS password_reset_expires_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS users_password_reset_token_hash_idx
+  ON users(password_reset_token_hash)
+  WHERE password_reset_token_hash IS NOT NULL;
+
+-- Temporary passwords were sent in the events of the password recoveries.
+UPDATE events
+SET payload = payload #- '{PasswordRecoveryRequested,temp_password}'
+WHERE topic = 'user' AND code = 'password-recovery-requested';
+
+UPDATE outbox
+SET payload = payload #- '{PasswordRecoveryRequested,temp_password}'
+WHERE topic = 'user' AND code = 'password-recovery-requested';
+
+UPDATE dead_letters
+SET payload = payload #- '{PasswordRecoveryRequested,temp_password}'
+WHERE topic = 'user' AND code = 'password-recovery-requested';
@@ -0,0 +1,5 @@
+ALTER TABLE users
+  ADD COLUMN IF NOT EXISTS email_change_email VARCHAR(64),
+  ADD COLUMN IF NOT EXISTS email_change_code VARCHAR(64),
+  ADD COLUMN IF NOT EXISTS email_change_expires_at TIMESTAMP WITH TIME ZONE,
+  ADD COLUMN IF NOT EXISTS username_changed_at TIMESTAMP WITH TIME ZONE;
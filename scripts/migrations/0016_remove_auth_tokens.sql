@@ -0,0 +1,12 @@
+-- Access tokens were sent in the events of the logins.
+UPDATE events
+SET payload = payload #- '{LoggedIn,auth_token}'
+WHERE topic = 'user' AND code = 'logged-in';
+
+UPDATE outbox
+SET payload = payload #- '{LoggedIn,auth_token}'
+WHERE topic = 'user' AND code = 'logged-in';
+
+UPDATE dead_letters
+SET payload = payload #- '{LoggedIn,auth_token}'
+WHERE topic = 'user' AND code = 'logged-in';
@@ -0,0 +1,24 @@
+-- Email change codes are stored hashed. Pending changes with a plain code must be requested again.
+ALTER TABLE users
+  ADD COLUMN IF NOT EXISTS email_change_code_hash VARCHAR(64);
+
+UPDATE users
+SET email_change_email = NULL,
+    email_change_expires_at = NULL
+WHERE email_change_code_hash IS NULL;
+
+ALTER TABLE users
+  DROP COLUMN IF EXISTS email_change_code;
+
+-- Codes were sent in the events of the email changes.
+UPDATE events
+SET payload = payload #- '{EmailChangeRequested,code}'
+WHERE topic = 'user' AND code = 'email-change-requested';
+
+UPDATE outbox
+SET payload = payload #- '{EmailChangeRequested,code}'
+WHERE topic = 'user' AND code = 'email-change-requested';
+
+UPDATE dead_letters
+SET payload = payload #- '{EmailChangeRequested,code}'
+WHERE topic = 'user' AND code = 'email-change-requested';
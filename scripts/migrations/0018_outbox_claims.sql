@@ -0,0 +1,3 @@
+-- Relays claim pending events until the lease expires, so several instances can relay them.
+ALTER TABLE outbox
+  ADD COLUMN IF NOT EXISTS claimed_until TIMESTAMP WITH TIME ZONE;
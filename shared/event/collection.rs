@@ -33,6 +33,9 @@ pub enum CollectionEvent {
     Deleted {
         id: String,
     },
+    Restored {
+        id: String,
+    },
 }
 
 impl ToString for CollectionEvent {
@@ -43,6 +46,7 @@ impl ToString for CollectionEvent {
             CollectionEvent::PublicationAdded { .. } => "publication-added".to_owned(),
             CollectionEvent::PublicationRemoved { .. } => "publication-removed".to_owned(),
             CollectionEvent::Deleted { .. } => "deleted".to_owned(),
+            CollectionEvent::Restored { .. } => "restored".to_owned(),
         }
     }
 }
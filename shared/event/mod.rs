@@ -3,18 +3,22 @@ mod category;
 mod collection;
 mod contract;
 mod donation;
+mod notification;
 mod plan;
 mod publication;
 mod reader;
 mod subscription;
+mod upcasters;
 mod user;
 pub use author::*;
 pub use category::*;
 pub use collection::*;
 pub use contract::*;
 pub use donation::*;
+pub use notification::*;
 pub use plan::*;
 pub use publication::*;
 pub use reader::*;
 pub use subscription::*;
+pub use upcasters::*;
 pub use user::*;
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use common::event::{Event, ToEvent};
+use common::result::Result;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum NotificationEvent {
+    Created {
+        id: String,
+        user_id: String,
+        code: String,
+        body: Value,
+    },
+    Read {
+        id: String,
+        user_id: String,
+    },
+}
+
+impl NotificationEvent {
+    pub fn user_id(&self) -> &str {
+        match self {
+            NotificationEvent::Created { user_id, .. }
+            | NotificationEvent::Read { user_id, .. } => user_id,
+        }
+    }
+}
+
+impl ToString for NotificationEvent {
+    fn to_string(&self) -> String {
+        match self {
+            NotificationEvent::Created { .. } => "created".to_owned(),
+            NotificationEvent::Read { .. } => "read".to_owned(),
+        }
+    }
+}
+
+impl ToEvent for NotificationEvent {
+    fn to_event(&self) -> Result<Event> {
+        Ok(Event::new(
+            "notification".to_owned(),
+            self.to_string(),
+            serde_json::to_value(&self)?,
+        ))
+    }
+}
@@ -48,6 +48,9 @@ pub enum PublicationEvent {
     Deleted {
         id: String,
     },
+    Restored {
+        id: String,
+    },
     Viewed {
         reader_id: String,
         publication_id: String,
@@ -103,6 +106,7 @@ impl ToString for PublicationEvent {
             PublicationEvent::Published { .. } => "published".to_owned(),
             PublicationEvent::Rejected { .. } => "rejected".to_owned(),
             PublicationEvent::Deleted { .. } => "deleted".to_owned(),
+            PublicationEvent::Restored { .. } => "restored".to_owned(),
             PublicationEvent::Viewed { .. } => "viewed".to_owned(),
             PublicationEvent::Read { .. } => "read".to_owned(),
             PublicationEvent::Liked { .. } => "liked".to_owned(),
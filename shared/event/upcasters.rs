@@ -0,0 +1,290 @@
+use serde_json::Value;
+
+use common::event::{Upcaster, UpcasterRegistry};
+use common::result::Result;
+
+/// Upcasters of every event schema change. When the payload of a topic changes, its `ToEvent`
+/// implementation sets the new version and the upcaster from the previous one is registered
+/// here. Fixtures in `shared/event/fixtures` keep the stored shapes that must still be readable.
+pub fn upcasters() -> Result<UpcasterRegistry> {
+    let mut registry = UpcasterRegistry::new();
+    registry.register(Box::new(RemoveTempPassword))?;
+    registry.register(Box::new(RemoveAuthToken))?;
+    registry.register(Box::new(RemoveEmailChangeCode))?;
+    Ok(registry)
+}
+
+/// User v1 to v2: password recoveries are requested with a reset token sent to the user, so the
+/// temporary password is no longer part of the payload.
+struct RemoveTempPassword;
+
+impl Upcaster for RemoveTempPassword {
+    fn topic(&self) -> &str {
+        "user"
+    }
+
+    fn version(&self) -> u32 {
+        1
+    }
+
+    fn upcast(&self, code: &str, mut payload: Value) -> Result<Value> {
+        if code == "password-recovery-requested" {
+            if let Some(event) = payload
+                .get_mut("PasswordRecoveryRequested")
+                .and_then(Value::as_object_mut)
+            {
+                event.remove("temp_password");
+            }
+        }
+
+        Ok(payload)
+    }
+}
+
+/// User v2 to v3: sessions can be revoked, so the access token of a login is no longer part of
+/// the payload, where anyone reading the events could use it.
+struct RemoveAuthToken;
+
+impl Upcaster for RemoveAuthToken {
+    fn topic(&self) -> &str {
+        "user"
+    }
+
+    fn version(&self) -> u32 {
+        2
+    }
+
+    fn upcast(&self, code: &str, mut payload: Value) -> Result<Value> {
+        if code == "logged-in" {
+            if let Some(event) = payload.get_mut("LoggedIn").and_then(Value::as_object_mut) {
+                event.remove("auth_token");
+            }
+        }
+
+        Ok(payload)
+    }
+}
+
+/// User v3 to v4: email change codes are sent to the new email and only their hash is stored,
+/// so the code is no longer part of the payload.
+struct RemoveEmailChangeCode;
+
+impl Upcaster for RemoveEmailChangeCode {
+    fn topic(&self) -> &str {
+        "user"
+    }
+
+    fn version(&self) -> u32 {
+        3
+    }
+
+    fn upcast(&self, code: &str, mut payload: Value) -> Result<Value> {
+        if code == "email-change-requested" {
+            if let Some(event) = payload
+                .get_mut("EmailChangeRequested")
+                .and_then(Value::as_object_mut)
+            {
+                event.remove("code");
+            }
+        }
+
+        Ok(payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde::de::DeserializeOwned;
+    use serde::Deserialize;
+    use serde_json::{json, Value};
+
+    use chrono::Utc;
+    use common::event::{Event, EventId, ToEvent};
+    use uuid::Uuid;
+
+    use crate::event::*;
+
+    const FIXTURES: &[&str] = &[
+        include_str!("fixtures/author.json"),
+        include_str!("fixtures/category.json"),
+        include_str!("fixtures/collection.json"),
+        include_str!("fixtures/contract.json"),
+        include_str!("fixtures/donation.json"),
+        include_str!("fixtures/notification.json"),
+        include_str!("fixtures/plan.json"),
+        include_str!("fixtures/publication.json"),
+        include_str!("fixtures/reader.json"),
+        include_str!("fixtures/subscription.json"),
+        include_str!("fixtures/user.json"),
+    ];
+
+    #[derive(Deserialize)]
+    struct Fixture {
+        topic: String,
+        code: String,
+        version: u32,
+        payload: Value,
+    }
+
+    fn check<E>(registry: &UpcasterRegistry, event: Event)
+    where
+        E: DeserializeOwned + ToEvent,
+    {
+        let description = format!(
+            "{}.{} (version {})",
+            event.topic(),
+            event.code(),
+            event.version()
+        );
+
+        let event = registry
+            .upcast(event)
+            .unwrap_or_else(|err| panic!("{}: {}", description, err));
+        let payload: E = serde_json::from_value(event.payload())
+            .unwrap_or_else(|err| panic!("{}: {}", description, err));
+
+        // The current enum produces the same event with the current version.
+        let current = payload.to_event().unwrap();
+        assert_eq!(current.topic(), event.topic(), "{}", description);
+        assert_eq!(current.code(), event.code(), "{}", description);
+        assert_eq!(current.version(), event.version(), "{}", description);
+        assert_eq!(
+            current.version(),
+            registry.current_version(event.topic()),
+            "{}",
+            description
+        );
+    }
+
+    #[test]
+    fn stored_events_deserialize() {
+        let registry = upcasters().unwrap();
+
+        for fixtures in FIXTURES.iter() {
+            let fixtures: Vec<Fixture> = serde_json::from_str(fixtures).unwrap();
+
+            for fixture in fixtures.into_iter() {
+                let event = Event::build(
+                    EventId::new(Uuid::new_v4().to_string()).unwrap(),
+                    fixture.topic,
+                    fixture.code,
+                    Utc::now(),
+                    fixture.payload,
+                    fixture.version,
+                );
+
+                match event.topic() {
+                    "author" => check::<AuthorEvent>(&registry, event),
+                    "category" => check::<CategoryEvent>(&registry, event),
+                    "collection" => check::<CollectionEvent>(&registry, event),
+                    "contract" => check::<ContractEvent>(&registry, event),
+                    "donation" => check::<DonationEvent>(&registry, event),
+                    "notification" => check::<NotificationEvent>(&registry, event),
+                    "plan" => check::<PlanEvent>(&registry, event),
+                    "publication" => check::<PublicationEvent>(&registry, event),
+                    "reader" => check::<ReaderEvent>(&registry, event),
+                    "subscription" => check::<SubscriptionEvent>(&registry, event),
+                    "user" => check::<UserEvent>(&registry, event),
+                    topic => panic!("unknown topic: {}", topic),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn temp_password_removed() {
+        let registry = upcasters().unwrap();
+
+        let event = registry
+            .upcast(Event::new(
+                "user",
+                "password-recovery-requested",
+                json!({
+                    "PasswordRecoveryRequested": {
+                        "id": "#value01",
+                        "temp_password": "#value01",
+                        "email": "#value01",
+                    }
+                }),
+            ))
+            .unwrap();
+        assert_eq!(event.version(), 4);
+        assert_eq!(
+            event.payload(),
+            json!({
+                "PasswordRecoveryRequested": {
+                    "id": "#value01",
+                    "email": "#value01",
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn auth_token_removed() {
+        let registry = upcasters().unwrap();
+
+        for version in 1..=2 {
+            let event = Event::build(
+                EventId::new(Uuid::new_v4().to_string()).unwrap(),
+                "user".to_owned(),
+                "logged-in".to_owned(),
+                Utc::now(),
+                json!({
+                    "LoggedIn": {
+                        "id": "#value01",
+                        "auth_token": "#value01",
+                    }
+                }),
+                version,
+            );
+
+            let event = registry.upcast(event).unwrap();
+            assert_eq!(event.version(), 4);
+            assert_eq!(
+                event.payload(),
+                json!({
+                    "LoggedIn": {
+                        "id": "#value01",
+                    }
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn email_change_code_removed() {
+        let registry = upcasters().unwrap();
+
+        for version in 2..=3 {
+            let event = Event::build(
+                EventId::new(Uuid::new_v4().to_string()).unwrap(),
+                "user".to_owned(),
+                "email-change-requested".to_owned(),
+                Utc::now(),
+                json!({
+                    "EmailChangeRequested": {
+                        "id": "#value01",
+                        "email": "#value01",
+                        "code": "#value01",
+                    }
+                }),
+                version,
+            );
+
+            let event = registry.upcast(event).unwrap();
+            assert_eq!(event.version(), 4);
+            assert_eq!(
+                event.payload(),
+                json!({
+                    "EmailChangeRequested": {
+                        "id": "#value01",
+                        "email": "#value01",
+                    }
+                })
+            );
+        }
+    }
+}
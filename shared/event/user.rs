@@ -14,7 +14,6 @@ pub enum UserEvent {
     },
     LoggedIn {
         id: String,
-        auth_token: String,
     },
     Updated {
         id: String,
@@ -30,7 +29,10 @@ pub enum UserEvent {
     },
     PasswordRecoveryRequested {
         id: String,
-        temp_password: String,
+        email: String,
+    },
+    PasswordReset {
+        id: String,
         email: String,
     },
     RoleChanged {
@@ -44,6 +46,44 @@ pub enum UserEvent {
     Deleted {
         id: String,
     },
+    Restored {
+        id: String,
+    },
+    TwoFactorEnabled {
+        id: String,
+    },
+    TwoFactorDisabled {
+        id: String,
+    },
+    RecoveryCodesGenerated {
+        id: String,
+    },
+    RecoveryCodeUsed {
+        id: String,
+        remaining_recovery_codes: usize,
+    },
+    LockedOut {
+        id: String,
+        email: String,
+        locked_until: String,
+    },
+    Unlocked {
+        id: String,
+        email: String,
+    },
+    UsernameChanged {
+        id: String,
+        username: String,
+    },
+    EmailChangeRequested {
+        id: String,
+        email: String,
+    },
+    EmailChanged {
+        id: String,
+        email: String,
+        old_email: String,
+    },
 }
 
 impl ToString for UserEvent {
@@ -54,9 +94,20 @@ impl ToString for UserEvent {
             UserEvent::Updated { .. } => "updated".to_owned(),
             UserEvent::Validated { .. } => "validated".to_owned(),
             UserEvent::PasswordRecoveryRequested { .. } => "password-recovery-requested".to_owned(),
+            UserEvent::PasswordReset { .. } => "password-reset".to_owned(),
             UserEvent::RoleChanged { .. } => "role-changed".to_owned(),
             UserEvent::PaymentEmailChanged { .. } => "payment-email-changed".to_owned(),
             UserEvent::Deleted { .. } => "deleted".to_owned(),
+            UserEvent::Restored { .. } => "restored".to_owned(),
+            UserEvent::TwoFactorEnabled { .. } => "two-factor-enabled".to_owned(),
+            UserEvent::TwoFactorDisabled { .. } => "two-factor-disabled".to_owned(),
+            UserEvent::RecoveryCodesGenerated { .. } => "recovery-codes-generated".to_owned(),
+            UserEvent::RecoveryCodeUsed { .. } => "recovery-code-used".to_owned(),
+            UserEvent::LockedOut { .. } => "locked-out".to_owned(),
+            UserEvent::Unlocked { .. } => "unlocked".to_owned(),
+            UserEvent::UsernameChanged { .. } => "username-changed".to_owned(),
+            UserEvent::EmailChangeRequested { .. } => "email-change-requested".to_owned(),
+            UserEvent::EmailChanged { .. } => "email-changed".to_owned(),
         }
     }
 }
@@ -67,6 +118,7 @@ impl ToEvent for UserEvent {
             "user".to_owned(),
             self.to_string(),
             serde_json::to_value(&self)?,
-        ))
+        )
+        .with_version(4))
     }
 }
@@ -5,7 +5,7 @@ import { FormGroup, FormBuilder, Validators } from '@angular/forms';
 import { ValidadoresCustomService } from 'src/app/services/validadores-custom.service';
 import { NgxSpinnerService } from 'ngx-spinner';
 import { IdentityService } from 'src/app/domain/services/identity.service';
-import { IChangePasswordCommand } from '../../../../domain/services/identity.service';
+import { IChangePasswordCommand, IResetPasswordCommand } from '../../../../domain/services/identity.service';
 import { SwalComponent } from '@sweetalert2/ngx-sweetalert2';
 import { Router } from '@angular/router';
 
@@ -62,7 +62,7 @@ export class PasswordRewriteComponent implements OnInit {
 
     this.formRewritePassword = this.fb.group({
 
-      oldPassword: [this.data.temporalPass, [ Validators.required, Validators.minLength(8) ]],
+      oldPassword: [this.data.token, [ Validators.required, Validators.minLength(8) ]],
       password1  : ['', [ Validators.required, Validators.minLength(8) ] ],
       password2  : ['', [ Validators.required, Validators.minLength(8) ] ],
 
@@ -97,13 +97,19 @@ export class PasswordRewriteComponent implements OnInit {
 
       this.spinnerService.show();
 
-      const passwordChange: IChangePasswordCommand = {
-        old_password: this.formRewritePassword.get('oldPassword').value,
-        new_password: this.formRewritePassword.get('password1').value
-      };
+      // Al recuperar la contraseña se usa el token del email en lugar de la contraseña anterior
+      const request = ( this.isRecoveryPassword ) ?
+        this.identityService.resetPassword( {
+          token: this.data.token,
+          password: this.formRewritePassword.get('password1').value
+        } as IResetPasswordCommand ) :
+        this.identityService.changePassword( this.data.userId, {
+          old_password: this.formRewritePassword.get('oldPassword').value,
+          new_password: this.formRewritePassword.get('password1').value
+        } as IChangePasswordCommand );
 
 
-      this.identityService.changePassword( this.data.userId, passwordChange ).subscribe(
+      request.subscribe(
 
         (res: any) => {
 
@@ -58,6 +58,11 @@ export interface IRecoverPasswordCommand {
   email: string;
 }
 
+export interface IResetPasswordCommand {
+  token: string;
+  password: string;
+}
+
 export interface IChangePaymentEmailCommand {
   payment_email: string;
 }
@@ -150,6 +155,10 @@ export class IdentityService {
     return this.http.post(`${this.configServ.baseUrl()}/recover-password`, cmd);
   }
 
+  public resetPassword(cmd: IResetPasswordCommand): Observable<any> {
+    return this.http.post(`${this.baseUrl}/reset-password`, cmd);
+  }
+
   public changePaymentEmail(id: string, cmd: IChangePaymentEmailCommand): Observable<any> {
     return this.http.put(`${this.baseUrl}/${id}/payment-email`, cmd);
   }
@@ -124,7 +124,7 @@ export class HomeComponent implements OnInit {
 
     this.route.params.subscribe( (params: any) => {
 
-      isNeeded = (params.id) ? true : false;
+      isNeeded = (params.token) ? true : false;
       this.paramsToUse = params;
 
     });
@@ -137,8 +137,7 @@ export class HomeComponent implements OnInit {
 
     const dialogRef = this.dialog.open(PasswordRewriteComponent, {
       data: {
-              userId:  this.paramsToUse.id,
-              temporalPass: this.paramsToUse.temporal_password,
+              token: this.paramsToUse.token,
               isRecoveryPassword: true
             },
       panelClass: 'no-padding-dialog'